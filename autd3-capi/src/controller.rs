@@ -0,0 +1,384 @@
+use std::ffi::c_void;
+
+use autd3::{
+    driver::{
+        autd3_device::AUTD3,
+        geometry::{Point3, Quaternion, UnitQuaternion},
+    },
+    Controller,
+};
+use autd3_core::link::Link;
+
+use crate::{
+    result::{catch_unwind, into_status, null_pointer},
+    AUTDStatus, DatagramPtr, GainPtr, LinkPtr, ModulationPtr,
+};
+
+/// The handle of a controller.
+///
+/// The handle is released by [`autd_controller_close`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ControllerPtr(pub *mut c_void);
+
+impl ControllerPtr {
+    /// # Safety
+    ///
+    /// The handle must be created by [`autd_controller_open`] and not be released yet.
+    unsafe fn as_mut<'a>(self) -> &'a mut Controller<Box<dyn Link>> {
+        // SAFETY: This is guaranteed by the caller.
+        unsafe { &mut *(self.0 as *mut Controller<Box<dyn Link>>) }
+    }
+}
+
+/// Opens a controller with `num_devices` [`AUTD3`] devices and `link`.
+///
+/// `pos` is the positions of the devices as `[x0, y0, z0, x1, y1, z1, ...]`, and `rot` is the rotations of the devices as quaternions `[w0, x0, y0, z0, w1, ...]`.
+/// If `rot` is null, the devices are not rotated.
+/// On success, the handle of the controller is written to `out`.
+/// `link` is consumed even if opening fails.
+///
+/// # Safety
+///
+/// `pos` must be valid for reads of `3 * num_devices` floats, `rot` must be null or valid for reads of `4 * num_devices` floats, `link` must be a valid handle, and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn autd_controller_open(
+    pos: *const f32,
+    rot: *const f32,
+    num_devices: u16,
+    link: LinkPtr,
+    out: *mut ControllerPtr,
+) -> AUTDStatus {
+    catch_unwind(AUTDStatus::Panic, || {
+        if link.0.is_null() {
+            return null_pointer("link");
+        }
+        // SAFETY: `link` is a valid handle.
+        let link = unsafe { link.take() };
+        if pos.is_null() {
+            return null_pointer("pos");
+        }
+        if out.is_null() {
+            return null_pointer("out");
+        }
+
+        let n = num_devices as usize;
+        // SAFETY: `pos` is valid for reads of `3 * num_devices` floats.
+        let pos = unsafe { std::slice::from_raw_parts(pos, 3 * n) };
+        let rot = (!rot.is_null()).then(|| {
+            // SAFETY: `rot` is valid for reads of `4 * num_devices` floats.
+            unsafe { std::slice::from_raw_parts(rot, 4 * n) }
+        });
+        let devices = (0..n).map(|i| AUTD3 {
+            pos: Point3::new(pos[3 * i], pos[3 * i + 1], pos[3 * i + 2]),
+            rot: rot.map_or(UnitQuaternion::identity(), |rot| {
+                UnitQuaternion::from_quaternion(Quaternion::new(
+                    rot[4 * i],
+                    rot[4 * i + 1],
+                    rot[4 * i + 2],
+                    rot[4 * i + 3],
+                ))
+            }),
+        });
+
+        into_status(Controller::open(devices, link).map(|cnt| {
+            // SAFETY: `out` is valid for writes.
+            unsafe { *out = ControllerPtr(Box::into_raw(Box::new(cnt)) as _) };
+        }))
+    })
+}
+
+/// Returns the number of devices of the controller, or `0` if `cnt` is null.
+///
+/// # Safety
+///
+/// `cnt` must be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn autd_controller_num_devices(cnt: ControllerPtr) -> u32 {
+    if cnt.0.is_null() {
+        return 0;
+    }
+    // SAFETY: `cnt` is a valid handle.
+    unsafe { cnt.as_mut() }.geometry().num_devices() as u32
+}
+
+/// Sends the modulation `m` and the gain `g` to the devices.
+///
+/// Either `m` or `g` may be null to send only the other one. Non-null `m` and `g` are consumed even if sending fails.
+///
+/// # Safety
+///
+/// `cnt` must be a valid handle, and `m` and `g` must be null or valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn autd_controller_send(
+    cnt: ControllerPtr,
+    m: ModulationPtr,
+    g: GainPtr,
+) -> AUTDStatus {
+    catch_unwind(AUTDStatus::Panic, || {
+        // SAFETY: `m` and `g` are null or valid handles.
+        let m = (!m.0.is_null()).then(|| unsafe { m.take() });
+        let g = (!g.0.is_null()).then(|| unsafe { g.take() });
+        if cnt.0.is_null() {
+            return null_pointer("cnt");
+        }
+        // SAFETY: `cnt` is a valid handle.
+        let cnt = unsafe { cnt.as_mut() };
+        into_status(match (m, g) {
+            (Some(m), Some(g)) => cnt.send((m, g)),
+            (Some(m), None) => cnt.send(m),
+            (None, Some(g)) => cnt.send(g),
+            (None, None) => return null_pointer("m and g"),
+        })
+    })
+}
+
+/// Sends the datagram `d`, e.g., STM, to the devices.
+///
+/// `d` is consumed even if sending fails.
+///
+/// # Safety
+///
+/// `cnt` and `d` must be valid handles.
+#[no_mangle]
+pub unsafe extern "C" fn autd_controller_send_datagram(
+    cnt: ControllerPtr,
+    d: DatagramPtr,
+) -> AUTDStatus {
+    catch_unwind(AUTDStatus::Panic, || {
+        if d.0.is_null() {
+            return null_pointer("d");
+        }
+        // SAFETY: `d` is a valid handle.
+        let d = unsafe { d.take() };
+        if cnt.0.is_null() {
+            return null_pointer("cnt");
+        }
+        // SAFETY: `cnt` is a valid handle.
+        into_status(unsafe { cnt.as_mut() }.send(d))
+    })
+}
+
+/// Closes and releases the controller.
+///
+/// The handle is released even if closing fails.
+///
+/// # Safety
+///
+/// `cnt` must be a valid handle, and must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn autd_controller_close(cnt: ControllerPtr) -> AUTDStatus {
+    catch_unwind(AUTDStatus::Panic, || {
+        if cnt.0.is_null() {
+            return null_pointer("cnt");
+        }
+        // SAFETY: `cnt` is a valid handle.
+        let cnt = unsafe { Box::from_raw(cnt.0 as *mut Controller<Box<dyn Link>>) };
+        into_status(cnt.close())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3::{
+        driver::{datagram::GainSTMOption, firmware::fpga::EmitIntensity},
+        link::{Audit, AuditOption, Scenario},
+        modulation::SineOption,
+    };
+
+    use crate::{
+        autd_gain_focus, autd_gain_null, autd_get_last_error, autd_link_nop,
+        autd_modulation_sine_exact, autd_modulation_static, autd_stm_foci_exact,
+        autd_stm_foci_nearest, autd_stm_gain_exact, autd_stm_gain_nearest, FocusOption,
+    };
+
+    use super::*;
+
+    fn open(n: u16) -> ControllerPtr {
+        let pos = vec![0.; 3 * n as usize];
+        let mut cnt = ControllerPtr(std::ptr::null_mut());
+        assert_eq!(AUTDStatus::Ok, unsafe {
+            autd_controller_open(pos.as_ptr(), std::ptr::null(), n, autd_link_nop(), &mut cnt)
+        });
+        cnt
+    }
+
+    #[test]
+    fn open_send_close() {
+        let cnt = open(2);
+        assert_eq!(2, unsafe { autd_controller_num_devices(cnt) });
+
+        let null_m = ModulationPtr(std::ptr::null_mut());
+        let null_g = GainPtr(std::ptr::null_mut());
+        [
+            (autd_modulation_static(0xFF), null_g),
+            (
+                null_m,
+                autd_gain_focus(0., 0., 150., FocusOption::default()),
+            ),
+            (
+                autd_modulation_sine_exact(150, SineOption::default()),
+                autd_gain_focus(0., 0., 150., FocusOption::default()),
+            ),
+        ]
+        .into_iter()
+        .for_each(|(m, g)| {
+            assert_eq!(AUTDStatus::Ok, unsafe { autd_controller_send(cnt, m, g) });
+        });
+        assert_eq!(AUTDStatus::NullPointer, unsafe {
+            autd_controller_send(cnt, null_m, null_g)
+        });
+
+        assert_eq!(AUTDStatus::Ok, unsafe { autd_controller_close(cnt) });
+    }
+
+    #[test]
+    fn open_rot() {
+        let pos = [0.; 6];
+        let rot = [1., 0., 0., 0., 0., 0., 0., 1.];
+        let mut cnt = ControllerPtr(std::ptr::null_mut());
+        assert_eq!(AUTDStatus::Ok, unsafe {
+            autd_controller_open(pos.as_ptr(), rot.as_ptr(), 2, autd_link_nop(), &mut cnt)
+        });
+        let geometry = unsafe { cnt.as_mut() }.geometry();
+        assert_eq!(UnitQuaternion::identity(), *geometry[0].rotation());
+        assert_eq!(
+            UnitQuaternion::from_quaternion(Quaternion::new(0., 0., 0., 1.)),
+            *geometry[1].rotation()
+        );
+        assert_eq!(AUTDStatus::Ok, unsafe { autd_controller_close(cnt) });
+    }
+
+    #[test]
+    fn open_failed() {
+        let pos = [0.; 3];
+        let mut cnt = ControllerPtr(std::ptr::null_mut());
+        assert_eq!(AUTDStatus::ConfirmResponseFailed, unsafe {
+            autd_controller_open(
+                pos.as_ptr(),
+                std::ptr::null(),
+                1,
+                LinkPtr::new(Audit::new(AuditOption {
+                    scenario: Scenario::new().stale_response(0, usize::MAX),
+                    ..Default::default()
+                })),
+                &mut cnt,
+            )
+        });
+        assert!(cnt.0.is_null());
+        assert!(unsafe { autd_get_last_error(std::ptr::null_mut(), 0) } > 1);
+
+        assert_eq!(AUTDStatus::Other, unsafe {
+            autd_controller_open(pos.as_ptr(), std::ptr::null(), 0, autd_link_nop(), &mut cnt)
+        });
+    }
+
+    #[test]
+    fn open_panicked() {
+        let pos = [0.; 3];
+        let mut cnt = ControllerPtr(std::ptr::null_mut());
+        assert_eq!(AUTDStatus::Panic, unsafe {
+            autd_controller_open(
+                pos.as_ptr(),
+                std::ptr::null(),
+                1,
+                LinkPtr::new(Audit::new(AuditOption {
+                    // Panics only once, because the controller is closed while unwinding.
+                    scenario: Scenario::new().on_poll(0, {
+                        let mut panicked = false;
+                        move |_, _| {
+                            if !std::mem::replace(&mut panicked, true) {
+                                panic!("link");
+                            }
+                        }
+                    }),
+                    ..Default::default()
+                })),
+                &mut cnt,
+            )
+        });
+        assert!(cnt.0.is_null());
+
+        let mut buf = [0 as std::ffi::c_char; 16];
+        assert_eq!(15, unsafe { autd_get_last_error(buf.as_mut_ptr(), 16) });
+        assert_eq!(
+            "panicked: link",
+            unsafe { std::ffi::CStr::from_ptr(buf.as_ptr()) }
+                .to_str()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn send_failed() {
+        let cnt = open(1);
+        assert_eq!(AUTDStatus::Driver, unsafe {
+            autd_controller_send(
+                cnt,
+                autd_modulation_sine_exact(10000, SineOption::default()),
+                GainPtr(std::ptr::null_mut()),
+            )
+        });
+        assert_eq!(AUTDStatus::Ok, unsafe { autd_controller_close(cnt) });
+    }
+
+    #[rstest::rstest]
+    #[case(AUTDStatus::Ok, 1.)]
+    #[case(AUTDStatus::Driver, 0.3)]
+    #[test]
+    fn send_stm(#[case] expect: AUTDStatus, #[case] freq: f32) {
+        let cnt = open(1);
+        let points = [0., 0., 150., 10., 0., 150.];
+        let gains = [autd_gain_null(), autd_gain_null()];
+        [
+            unsafe { autd_stm_foci_exact(points.as_ptr(), 2, EmitIntensity::MAX, freq) },
+            unsafe { autd_stm_gain_exact(gains.as_ptr(), 2, freq, GainSTMOption::default()) },
+        ]
+        .into_iter()
+        .for_each(|d| {
+            assert_eq!(expect, unsafe { autd_controller_send_datagram(cnt, d) });
+        });
+
+        let gains = [autd_gain_null(), autd_gain_null()];
+        [
+            unsafe { autd_stm_foci_nearest(points.as_ptr(), 2, EmitIntensity::MAX, freq) },
+            unsafe { autd_stm_gain_nearest(gains.as_ptr(), 2, freq, GainSTMOption::default()) },
+        ]
+        .into_iter()
+        .for_each(|d| {
+            assert_eq!(AUTDStatus::Ok, unsafe {
+                autd_controller_send_datagram(cnt, d)
+            });
+        });
+
+        assert_eq!(AUTDStatus::Ok, unsafe { autd_controller_close(cnt) });
+    }
+
+    #[test]
+    fn null_pointer() {
+        assert_eq!(AUTDStatus::NullPointer, unsafe {
+            autd_controller_open(
+                std::ptr::null(),
+                std::ptr::null(),
+                1,
+                autd_link_nop(),
+                std::ptr::null_mut(),
+            )
+        });
+        let null = ControllerPtr(std::ptr::null_mut());
+        assert_eq!(0, unsafe { autd_controller_num_devices(null) });
+        assert_eq!(AUTDStatus::NullPointer, unsafe {
+            autd_controller_send(
+                null,
+                autd_modulation_static(0xFF),
+                GainPtr(std::ptr::null_mut()),
+            )
+        });
+        assert_eq!(AUTDStatus::NullPointer, unsafe {
+            autd_controller_send_datagram(null, DatagramPtr(std::ptr::null_mut()))
+        });
+        assert_eq!(AUTDStatus::NullPointer, unsafe {
+            autd_controller_close(null)
+        });
+    }
+}
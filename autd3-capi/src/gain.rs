@@ -0,0 +1,238 @@
+use std::ffi::c_void;
+
+use autd3::{
+    driver::{
+        datagram::{BoxedGain, IntoBoxedGain},
+        defined::Angle,
+        firmware::fpga::{EmitIntensity, Phase},
+        geometry::{Point3, UnitVector3, Vector3},
+    },
+    gain::{Bessel, BesselOption, Focus, NearFieldCompensation, Null, Plane, PlaneOption, Uniform},
+};
+
+use crate::result::set_last_error;
+
+/// The handle of a gain.
+///
+/// The handle is consumed by [`autd_controller_send`] or released by [`autd_gain_free`].
+/// The constructors return a null handle if the arguments are invalid.
+///
+/// [`autd_controller_send`]: crate::autd_controller_send
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct GainPtr(pub *mut c_void);
+
+impl GainPtr {
+    /// Creates a handle of `g`.
+    pub fn new(g: BoxedGain) -> Self {
+        Self(Box::into_raw(Box::new(g)) as _)
+    }
+
+    /// # Safety
+    ///
+    /// The handle must be created by [`GainPtr::new`] and not be released yet.
+    pub(crate) unsafe fn take(self) -> BoxedGain {
+        // SAFETY: This is guaranteed by the caller.
+        *unsafe { Box::from_raw(self.0 as *mut BoxedGain) }
+    }
+}
+
+fn dir(x: f32, y: f32, z: f32) -> Option<UnitVector3> {
+    let dir = UnitVector3::try_new(Vector3::new(x, y, z), 0.);
+    if dir.is_none() {
+        set_last_error("Direction must be a non-zero vector");
+    }
+    dir
+}
+
+/// Creates a [`Null`] gain.
+#[no_mangle]
+pub extern "C" fn autd_gain_null() -> GainPtr {
+    GainPtr::new(Null::new().into_boxed())
+}
+
+/// Creates a [`Uniform`] gain.
+#[no_mangle]
+pub extern "C" fn autd_gain_uniform(intensity: EmitIntensity, phase: Phase) -> GainPtr {
+    GainPtr::new(Uniform { intensity, phase }.into_boxed())
+}
+
+/// The option of [`Focus`]. See [`autd3::gain::FocusOption`] for details.
+///
+/// Unlike [`autd3::gain::FocusOption`], [`FocusOption::near_field_compensation`] is a raw value, so that an invalid value from C is rejected instead of causing undefined behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct FocusOption {
+    /// The intensity of the beam.
+    pub intensity: EmitIntensity,
+    /// The phase offset of the beam.
+    pub phase_offset: Phase,
+    /// The amplitude compensation: `0` for [`NearFieldCompensation::None`], `1` for [`NearFieldCompensation::InverseDistance`], and `2` for [`NearFieldCompensation::Directivity`].
+    pub near_field_compensation: u8,
+}
+
+impl Default for FocusOption {
+    fn default() -> Self {
+        let option = autd3::gain::FocusOption::default();
+        Self {
+            intensity: option.intensity,
+            phase_offset: option.phase_offset,
+            near_field_compensation: option.near_field_compensation as u8,
+        }
+    }
+}
+
+impl TryFrom<FocusOption> for autd3::gain::FocusOption {
+    type Error = String;
+
+    fn try_from(option: FocusOption) -> Result<Self, Self::Error> {
+        let near_field_compensation = match option.near_field_compensation {
+            0 => NearFieldCompensation::None,
+            1 => NearFieldCompensation::InverseDistance,
+            2 => NearFieldCompensation::Directivity,
+            v => return Err(format!("Invalid near field compensation: {}", v)),
+        };
+        Ok(Self {
+            intensity: option.intensity,
+            phase_offset: option.phase_offset,
+            near_field_compensation,
+        })
+    }
+}
+
+/// Creates a [`Focus`] gain at `(x, y, z)`.
+///
+/// Returns a null handle if [`FocusOption::near_field_compensation`] is invalid.
+#[no_mangle]
+pub extern "C" fn autd_gain_focus(x: f32, y: f32, z: f32, option: FocusOption) -> GainPtr {
+    option
+        .try_into()
+        .inspect_err(|e: &String| set_last_error(e))
+        .map_or(GainPtr(std::ptr::null_mut()), |option| {
+            GainPtr::new(
+                Focus {
+                    pos: Point3::new(x, y, z),
+                    option,
+                }
+                .into_boxed(),
+            )
+        })
+}
+
+/// Creates a [`Plane`] gain in the direction `(x, y, z)`, which is normalized.
+#[no_mangle]
+pub extern "C" fn autd_gain_plane(x: f32, y: f32, z: f32, option: PlaneOption) -> GainPtr {
+    dir(x, y, z).map_or(GainPtr(std::ptr::null_mut()), |dir| {
+        GainPtr::new(Plane { dir, option }.into_boxed())
+    })
+}
+
+/// Creates a [`Bessel`] gain with the vertex `(x, y, z)` and the direction `(nx, ny, nz)`, which is normalized.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub extern "C" fn autd_gain_bessel(
+    x: f32,
+    y: f32,
+    z: f32,
+    nx: f32,
+    ny: f32,
+    nz: f32,
+    theta: Angle,
+    option: BesselOption,
+) -> GainPtr {
+    dir(nx, ny, nz).map_or(GainPtr(std::ptr::null_mut()), |dir| {
+        GainPtr::new(
+            Bessel {
+                pos: Point3::new(x, y, z),
+                dir,
+                theta,
+                option,
+            }
+            .into_boxed(),
+        )
+    })
+}
+
+/// Releases the gain which is not passed to [`autd_controller_send`].
+///
+/// # Safety
+///
+/// `g` must be null or a valid handle, and must not be used after this call.
+///
+/// [`autd_controller_send`]: crate::autd_controller_send
+#[no_mangle]
+pub unsafe extern "C" fn autd_gain_free(g: GainPtr) {
+    if !g.0.is_null() {
+        // SAFETY: This is guaranteed by the caller.
+        drop(unsafe { g.take() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3::driver::defined::rad;
+
+    use super::*;
+
+    #[test]
+    fn gain() {
+        [
+            autd_gain_null(),
+            autd_gain_uniform(EmitIntensity::MAX, Phase::ZERO),
+            autd_gain_focus(0., 0., 150., FocusOption::default()),
+            autd_gain_plane(0., 0., 1., PlaneOption::default()),
+            autd_gain_bessel(0., 0., 0., 0., 0., 1., 0.1 * rad, BesselOption::default()),
+        ]
+        .into_iter()
+        .for_each(|g| {
+            assert!(!g.0.is_null());
+            unsafe { autd_gain_free(g) };
+        });
+    }
+
+    #[rstest::rstest]
+    #[case(Some(NearFieldCompensation::None), 0)]
+    #[case(Some(NearFieldCompensation::InverseDistance), 1)]
+    #[case(Some(NearFieldCompensation::Directivity), 2)]
+    #[case(None, 3)]
+    #[test]
+    fn focus_option(#[case] expect: Option<NearFieldCompensation>, #[case] v: u8) {
+        let option = FocusOption {
+            intensity: EmitIntensity(0x80),
+            phase_offset: Phase(0x40),
+            near_field_compensation: v,
+        };
+        assert_eq!(
+            expect.map(|near_field_compensation| autd3::gain::FocusOption {
+                intensity: EmitIntensity(0x80),
+                phase_offset: Phase(0x40),
+                near_field_compensation,
+            }),
+            autd3::gain::FocusOption::try_from(option).ok()
+        );
+
+        let g = autd_gain_focus(0., 0., 150., option);
+        assert_eq!(expect.is_some(), !g.0.is_null());
+        unsafe { autd_gain_free(g) };
+    }
+
+    #[test]
+    fn focus_option_default() {
+        assert_eq!(
+            Ok(autd3::gain::FocusOption::default()),
+            FocusOption::default().try_into()
+        );
+    }
+
+    #[test]
+    fn zero_dir() {
+        assert!(autd_gain_plane(0., 0., 0., PlaneOption::default())
+            .0
+            .is_null());
+        assert!(
+            autd_gain_bessel(0., 0., 0., 0., 0., 0., 0.1 * rad, BesselOption::default())
+                .0
+                .is_null()
+        );
+    }
+}
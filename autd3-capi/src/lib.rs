@@ -0,0 +1,24 @@
+#![cfg_attr(docsrs, feature(doc_cfg))]
+#![warn(missing_docs)]
+#![warn(rustdoc::missing_crate_level_docs)]
+#![warn(rustdoc::unescaped_backticks)]
+
+//! This crate provides a C-compatible interface of autd3.
+//!
+//! All objects are passed as opaque handles, and the functions return [`AUTDStatus`].
+//! If a function fails, the message of the error can be retrieved by [`autd_get_last_error`].
+//! Panics, e.g., in the links, are caught at the boundary and reported as [`AUTDStatus::Panic`] instead of unwinding into the caller.
+
+mod controller;
+mod gain;
+mod link;
+mod modulation;
+mod result;
+mod stm;
+
+pub use controller::*;
+pub use gain::*;
+pub use link::*;
+pub use modulation::*;
+pub use result::*;
+pub use stm::*;
@@ -0,0 +1,189 @@
+use std::ffi::c_void;
+
+use autd3::link::Nop;
+use autd3_core::link::Link;
+
+#[cfg(any(feature = "simulator", feature = "remote"))]
+use crate::result::catch_unwind;
+
+/// The handle of a link.
+///
+/// The handle is consumed by [`autd_controller_open`] or released by [`autd_link_free`].
+///
+/// [`autd_controller_open`]: crate::autd_controller_open
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct LinkPtr(pub *mut c_void);
+
+impl LinkPtr {
+    /// Creates a handle of `link`.
+    ///
+    /// This is used by the link crates to provide the constructors of their links.
+    pub fn new(link: impl Link + 'static) -> Self {
+        Self(Box::into_raw(Box::new(Box::new(link) as Box<dyn Link>)) as _)
+    }
+
+    /// # Safety
+    ///
+    /// The handle must be created by [`LinkPtr::new`] and not be released yet.
+    pub(crate) unsafe fn take(self) -> Box<dyn Link> {
+        // SAFETY: This is guaranteed by the caller.
+        *unsafe { Box::from_raw(self.0 as *mut Box<dyn Link>) }
+    }
+}
+
+/// Creates a [`Nop`] link.
+#[no_mangle]
+pub extern "C" fn autd_link_nop() -> LinkPtr {
+    LinkPtr::new(Nop::new())
+}
+
+/// # Safety
+///
+/// `s` must be null or a valid null-terminated string.
+#[cfg(any(feature = "simulator", feature = "remote"))]
+unsafe fn to_str<'a>(s: *const std::ffi::c_char, name: &str) -> Option<&'a str> {
+    if s.is_null() {
+        crate::result::set_last_error(format!("{} is null", name));
+        return None;
+    }
+    // SAFETY: This is guaranteed by the caller.
+    unsafe { std::ffi::CStr::from_ptr(s) }
+        .to_str()
+        .inspect_err(|e| crate::result::set_last_error(e))
+        .ok()
+}
+
+/// Creates a [`Simulator`] link to the server at `addr`, e.g., `"127.0.0.1:8080"`.
+///
+/// Returns a null handle if `addr` is invalid.
+///
+/// # Safety
+///
+/// `addr` must be null or a valid null-terminated string.
+///
+/// [`Simulator`]: autd3_link_simulator::Simulator
+#[cfg_attr(docsrs, doc(cfg(feature = "simulator")))]
+#[cfg(feature = "simulator")]
+#[no_mangle]
+pub unsafe extern "C" fn autd_link_simulator(addr: *const std::ffi::c_char) -> LinkPtr {
+    catch_unwind(LinkPtr(std::ptr::null_mut()), || {
+        // SAFETY: This is guaranteed by the caller.
+        unsafe { to_str(addr, "addr") }
+            .and_then(|addr| {
+                addr.parse()
+                    .inspect_err(|e| crate::result::set_last_error(e))
+                    .ok()
+            })
+            .map_or(LinkPtr(std::ptr::null_mut()), |addr| {
+                LinkPtr::new(autd3_link_simulator::Simulator::new(addr))
+            })
+    })
+}
+
+/// Creates a [`RemoteTwinCAT`] link to the server of `server_ams_net_id`.
+///
+/// `server_ip` and `client_ams_net_id` can be null, which means the default value of [`RemoteTwinCATOption`].
+/// Returns a null handle if any string is invalid.
+///
+/// # Safety
+///
+/// `server_ams_net_id` must be a valid null-terminated string, and `server_ip` and `client_ams_net_id` must be null or valid null-terminated strings.
+///
+/// [`RemoteTwinCAT`]: autd3_link_twincat::RemoteTwinCAT
+/// [`RemoteTwinCATOption`]: autd3_link_twincat::RemoteTwinCATOption
+#[cfg_attr(docsrs, doc(cfg(feature = "remote")))]
+#[cfg(feature = "remote")]
+#[no_mangle]
+pub unsafe extern "C" fn autd_link_remote_twincat(
+    server_ams_net_id: *const std::ffi::c_char,
+    server_ip: *const std::ffi::c_char,
+    client_ams_net_id: *const std::ffi::c_char,
+) -> LinkPtr {
+    catch_unwind(LinkPtr(std::ptr::null_mut()), || {
+        let opt = |s: *const std::ffi::c_char, name| {
+            if s.is_null() {
+                Some(String::new())
+            } else {
+                // SAFETY: This is guaranteed by the caller.
+                unsafe { to_str(s, name) }.map(str::to_owned)
+            }
+        };
+        // SAFETY: This is guaranteed by the caller.
+        let Some(server_ams_net_id) = (unsafe { to_str(server_ams_net_id, "server_ams_net_id") })
+        else {
+            return LinkPtr(std::ptr::null_mut());
+        };
+        let (Some(server_ip), Some(client_ams_net_id)) = (
+            opt(server_ip, "server_ip"),
+            opt(client_ams_net_id, "client_ams_net_id"),
+        ) else {
+            return LinkPtr(std::ptr::null_mut());
+        };
+        LinkPtr::new(autd3_link_twincat::RemoteTwinCAT::new(
+            server_ams_net_id,
+            autd3_link_twincat::RemoteTwinCATOption {
+                server_ip,
+                client_ams_net_id,
+            },
+        ))
+    })
+}
+
+/// Releases the link which is not passed to [`autd_controller_open`].
+///
+/// # Safety
+///
+/// `link` must be null or a valid handle, and must not be used after this call.
+///
+/// [`autd_controller_open`]: crate::autd_controller_open
+#[no_mangle]
+pub unsafe extern "C" fn autd_link_free(link: LinkPtr) {
+    if !link.0.is_null() {
+        // SAFETY: This is guaranteed by the caller.
+        drop(unsafe { link.take() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nop() {
+        let link = autd_link_nop();
+        assert!(!link.0.is_null());
+        unsafe { autd_link_free(link) };
+    }
+
+    #[cfg(feature = "simulator")]
+    #[rstest::rstest]
+    #[case(true, c"127.0.0.1:8080")]
+    #[case(false, c"127.0.0.1")]
+    #[test]
+    fn simulator(#[case] expect: bool, #[case] addr: &std::ffi::CStr) {
+        let link = unsafe { autd_link_simulator(addr.as_ptr()) };
+        assert_eq!(expect, !link.0.is_null());
+        unsafe { autd_link_free(link) };
+        assert!(unsafe { autd_link_simulator(std::ptr::null()) }.0.is_null());
+    }
+
+    #[cfg(feature = "remote")]
+    #[test]
+    fn remote_twincat() {
+        let link = unsafe {
+            autd_link_remote_twincat(
+                c"172.16.99.111.1.1".as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+            )
+        };
+        assert!(!link.0.is_null());
+        unsafe { autd_link_free(link) };
+        assert!(unsafe {
+            autd_link_remote_twincat(std::ptr::null(), std::ptr::null(), std::ptr::null())
+        }
+        .0
+        .is_null());
+    }
+}
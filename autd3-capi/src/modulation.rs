@@ -0,0 +1,129 @@
+use std::ffi::c_void;
+
+use autd3::{
+    driver::{
+        datagram::{BoxedModulation, IntoBoxedModulation},
+        defined::Hz,
+    },
+    modulation::{Sine, SineOption, Square, SquareOption, Static},
+};
+
+/// The handle of a modulation.
+///
+/// The handle is consumed by [`autd_controller_send`] or released by [`autd_modulation_free`].
+///
+/// [`autd_controller_send`]: crate::autd_controller_send
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ModulationPtr(pub *mut c_void);
+
+impl ModulationPtr {
+    /// Creates a handle of `m`.
+    pub fn new(m: BoxedModulation) -> Self {
+        Self(Box::into_raw(Box::new(m)) as _)
+    }
+
+    /// # Safety
+    ///
+    /// The handle must be created by [`ModulationPtr::new`] and not be released yet.
+    pub(crate) unsafe fn take(self) -> BoxedModulation {
+        // SAFETY: This is guaranteed by the caller.
+        *unsafe { Box::from_raw(self.0 as *mut BoxedModulation) }
+    }
+}
+
+/// Creates a [`Static`] modulation.
+#[no_mangle]
+pub extern "C" fn autd_modulation_static(intensity: u8) -> ModulationPtr {
+    ModulationPtr::new(Static { intensity }.into_boxed())
+}
+
+/// Creates a [`Sine`] modulation of the exact frequency `freq` Hz.
+///
+/// If the frequency cannot be output exactly, sending the modulation fails.
+#[no_mangle]
+pub extern "C" fn autd_modulation_sine_exact(freq: u32, option: SineOption) -> ModulationPtr {
+    ModulationPtr::new(
+        Sine {
+            freq: freq * Hz,
+            option,
+        }
+        .into_boxed(),
+    )
+}
+
+/// Creates a [`Sine`] modulation of the nearest frequency to `freq` Hz that can be output.
+#[no_mangle]
+pub extern "C" fn autd_modulation_sine_nearest(freq: f32, option: SineOption) -> ModulationPtr {
+    ModulationPtr::new(
+        Sine {
+            freq: freq * Hz,
+            option,
+        }
+        .into_nearest()
+        .into_boxed(),
+    )
+}
+
+/// Creates a [`Square`] modulation of the exact frequency `freq` Hz.
+///
+/// If the frequency cannot be output exactly, sending the modulation fails.
+#[no_mangle]
+pub extern "C" fn autd_modulation_square_exact(freq: u32, option: SquareOption) -> ModulationPtr {
+    ModulationPtr::new(
+        Square {
+            freq: freq * Hz,
+            option,
+        }
+        .into_boxed(),
+    )
+}
+
+/// Creates a [`Square`] modulation of the nearest frequency to `freq` Hz that can be output.
+#[no_mangle]
+pub extern "C" fn autd_modulation_square_nearest(freq: f32, option: SquareOption) -> ModulationPtr {
+    ModulationPtr::new(
+        Square {
+            freq: freq * Hz,
+            option,
+        }
+        .into_nearest()
+        .into_boxed(),
+    )
+}
+
+/// Releases the modulation which is not passed to [`autd_controller_send`].
+///
+/// # Safety
+///
+/// `m` must be null or a valid handle, and must not be used after this call.
+///
+/// [`autd_controller_send`]: crate::autd_controller_send
+#[no_mangle]
+pub unsafe extern "C" fn autd_modulation_free(m: ModulationPtr) {
+    if !m.0.is_null() {
+        // SAFETY: This is guaranteed by the caller.
+        drop(unsafe { m.take() });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modulation() {
+        [
+            autd_modulation_static(u8::MAX),
+            autd_modulation_sine_exact(150, SineOption::default()),
+            autd_modulation_sine_nearest(150., SineOption::default()),
+            autd_modulation_square_exact(150, SquareOption::default()),
+            autd_modulation_square_nearest(150., SquareOption::default()),
+        ]
+        .into_iter()
+        .for_each(|m| {
+            assert!(!m.0.is_null());
+            unsafe { autd_modulation_free(m) };
+        });
+    }
+}
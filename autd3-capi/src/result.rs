@@ -0,0 +1,169 @@
+use std::{cell::RefCell, ffi::c_char};
+
+use autd3::{driver::error::AUTDDriverError, error::AUTDError};
+
+/// The status code returned by the functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum AUTDStatus {
+    /// Succeeded.
+    Ok = 0,
+    /// A required handle or pointer is null.
+    NullPointer = 1,
+    /// The link is closed.
+    LinkClosed = 2,
+    /// The link failed.
+    Link = 3,
+    /// Failed to confirm that the devices have processed the data.
+    ConfirmResponseFailed = 4,
+    /// Other errors of the driver, e.g., invalid parameters of the gain or modulation.
+    Driver = 5,
+    /// The function panicked. The state of the handles passed to the function is unspecified.
+    Panic = 6,
+    /// Other errors.
+    Other = 255,
+}
+
+impl From<&AUTDDriverError> for AUTDStatus {
+    fn from(e: &AUTDDriverError) -> Self {
+        match e {
+            AUTDDriverError::LinkClosed => AUTDStatus::LinkClosed,
+            AUTDDriverError::Link(_) => AUTDStatus::Link,
+            AUTDDriverError::ConfirmResponseFailed => AUTDStatus::ConfirmResponseFailed,
+            _ => AUTDStatus::Driver,
+        }
+    }
+}
+
+impl From<&AUTDError> for AUTDStatus {
+    fn from(e: &AUTDError) -> Self {
+        match e {
+            AUTDError::Driver(e) => e.into(),
+            _ => AUTDStatus::Other,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+pub(crate) fn set_last_error(msg: impl ToString) {
+    LAST_ERROR.with(|e| *e.borrow_mut() = msg.to_string());
+}
+
+pub(crate) fn into_status<E: std::fmt::Display>(res: Result<(), E>) -> AUTDStatus
+where
+    for<'a> AUTDStatus: From<&'a E>,
+{
+    match res {
+        Ok(()) => AUTDStatus::Ok,
+        Err(e) => {
+            set_last_error(&e);
+            (&e).into()
+        }
+    }
+}
+
+/// Runs `f` and returns `default` if it panics, so that the panic does not unwind across the FFI boundary.
+///
+/// The panic message can be retrieved by [`autd_get_last_error`].
+pub(crate) fn catch_unwind<T>(default: T, f: impl FnOnce() -> T) -> T {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let msg = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        set_last_error(format!("panicked: {}", msg));
+        default
+    })
+}
+
+pub(crate) fn null_pointer(name: &str) -> AUTDStatus {
+    set_last_error(format!("{} is null", name));
+    AUTDStatus::NullPointer
+}
+
+/// Copies the message of the last error on the calling thread into `buf` as a null-terminated string.
+///
+/// Returns the length of the message including the null terminator. If `buf` is null or `len` is less than that, nothing is copied.
+///
+/// # Safety
+///
+/// `buf` must be null or valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn autd_get_last_error(buf: *mut c_char, len: u32) -> u32 {
+    LAST_ERROR.with(|e| {
+        let e = e.borrow();
+        let size = e.len() + 1;
+        if !buf.is_null() && size <= len as usize {
+            // SAFETY: `buf` is valid for writes of `len` bytes, and `size <= len`.
+            unsafe {
+                std::ptr::copy_nonoverlapping(e.as_ptr() as *const c_char, buf, e.len());
+                *buf.add(e.len()) = 0;
+            }
+        }
+        size as u32
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+
+    use autd3::core::link::LinkError;
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(AUTDStatus::LinkClosed, AUTDError::Driver(AUTDDriverError::LinkClosed))]
+    #[case(
+        AUTDStatus::Link,
+        AUTDError::Driver(AUTDDriverError::Link(LinkError::new("test".to_owned())))
+    )]
+    #[case(
+        AUTDStatus::ConfirmResponseFailed,
+        AUTDError::Driver(AUTDDriverError::ConfirmResponseFailed)
+    )]
+    #[case(
+        AUTDStatus::Driver,
+        AUTDError::Driver(AUTDDriverError::ModulationSizeOutOfRange(0))
+    )]
+    #[case(AUTDStatus::Other, AUTDError::EmptyGeometry)]
+    #[test]
+    fn status(#[case] expect: AUTDStatus, #[case] e: AUTDError) {
+        assert_eq!(expect, into_status(Err(e)));
+    }
+
+    #[rstest::rstest]
+    #[case("panicked: str", || panic!("str"))]
+    #[case("panicked: string 1", || panic!("string {}", 1))]
+    #[case("panicked: ", || std::panic::panic_any(1))]
+    #[test]
+    fn catch_panic(#[case] expect: &str, #[case] f: fn() -> AUTDStatus) {
+        assert_eq!(AUTDStatus::Panic, catch_unwind(AUTDStatus::Panic, f));
+        assert_eq!(expect, LAST_ERROR.with(|e| e.borrow().clone()));
+        assert_eq!(
+            AUTDStatus::Ok,
+            catch_unwind(AUTDStatus::Panic, || AUTDStatus::Ok)
+        );
+    }
+
+    #[test]
+    fn last_error() {
+        set_last_error("error");
+
+        assert_eq!(6, unsafe { autd_get_last_error(std::ptr::null_mut(), 0) });
+
+        let mut buf = [1 as c_char; 6];
+        assert_eq!(6, unsafe { autd_get_last_error(buf.as_mut_ptr(), 5) });
+        assert_eq!([1; 6], buf);
+
+        assert_eq!(6, unsafe { autd_get_last_error(buf.as_mut_ptr(), 6) });
+        assert_eq!(
+            "error",
+            unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().unwrap()
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::ffi::c_void;
+
+use autd3::driver::{
+    datagram::{BoxedDatagram, BoxedGain, FociSTM, GainSTM, GainSTMOption, IntoBoxedDatagram},
+    defined::Hz,
+    firmware::{
+        fpga::{EmitIntensity, Phase},
+        operation::{ControlPoint, ControlPoints},
+    },
+    geometry::Point3,
+};
+
+use crate::GainPtr;
+
+/// The handle of a datagram, e.g., STM.
+///
+/// The handle is consumed by [`autd_controller_send_datagram`] or released by [`autd_datagram_free`].
+///
+/// [`autd_controller_send_datagram`]: crate::autd_controller_send_datagram
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DatagramPtr(pub *mut c_void);
+
+impl DatagramPtr {
+    /// Creates a handle of `d`.
+    pub fn new(d: BoxedDatagram) -> Self {
+        Self(Box::into_raw(Box::new(d)) as _)
+    }
+
+    /// # Safety
+    ///
+    /// The handle must be created by [`DatagramPtr::new`] and not be released yet.
+    pub(crate) unsafe fn take(self) -> BoxedDatagram {
+        // SAFETY: This is guaranteed by the caller.
+        *unsafe { Box::from_raw(self.0 as *mut BoxedDatagram) }
+    }
+}
+
+/// # Safety
+///
+/// `points` must be valid for reads of `3 * size` floats.
+unsafe fn foci(points: *const f32, size: u16, intensity: EmitIntensity) -> Vec<ControlPoints<1>> {
+    // SAFETY: This is guaranteed by the caller.
+    unsafe { std::slice::from_raw_parts(points, 3 * size as usize) }
+        .chunks_exact(3)
+        .map(|p| ControlPoints {
+            points: [ControlPoint {
+                point: Point3::new(p[0], p[1], p[2]),
+                phase_offset: Phase::ZERO,
+            }],
+            intensity,
+        })
+        .collect()
+}
+
+/// # Safety
+///
+/// `gains` must be valid for reads of `size` valid handles.
+unsafe fn gains(gains: *const GainPtr, size: u16) -> Vec<BoxedGain> {
+    // SAFETY: This is guaranteed by the caller.
+    unsafe { std::slice::from_raw_parts(gains, size as usize) }
+        .iter()
+        .map(|g| unsafe { g.take() })
+        .collect()
+}
+
+/// Creates a [`FociSTM`] of the exact frequency `freq` Hz through the foci `points` as `[x0, y0, z0, x1, y1, z1, ...]`.
+///
+/// If the frequency cannot be output exactly, sending the STM fails.
+///
+/// # Safety
+///
+/// `points` must be valid for reads of `3 * size` floats.
+#[no_mangle]
+pub unsafe extern "C" fn autd_stm_foci_exact(
+    points: *const f32,
+    size: u16,
+    intensity: EmitIntensity,
+    freq: f32,
+) -> DatagramPtr {
+    DatagramPtr::new(
+        FociSTM {
+            // SAFETY: This is guaranteed by the caller.
+            foci: unsafe { foci(points, size, intensity) },
+            config: freq * Hz,
+        }
+        .into_boxed(),
+    )
+}
+
+/// Same as [`autd_stm_foci_exact`], but with the nearest frequency to `freq` Hz that can be output.
+///
+/// # Safety
+///
+/// `points` must be valid for reads of `3 * size` floats.
+#[no_mangle]
+pub unsafe extern "C" fn autd_stm_foci_nearest(
+    points: *const f32,
+    size: u16,
+    intensity: EmitIntensity,
+    freq: f32,
+) -> DatagramPtr {
+    DatagramPtr::new(
+        FociSTM {
+            // SAFETY: This is guaranteed by the caller.
+            foci: unsafe { foci(points, size, intensity) },
+            config: freq * Hz,
+        }
+        .into_nearest()
+        .into_boxed(),
+    )
+}
+
+/// Creates a [`GainSTM`] of the exact frequency `freq` Hz from the `gains`, which are consumed.
+///
+/// If the frequency cannot be output exactly, sending the STM fails.
+///
+/// # Safety
+///
+/// `gains` must be valid for reads of `size` valid handles, which must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn autd_stm_gain_exact(
+    gains: *const GainPtr,
+    size: u16,
+    freq: f32,
+    option: GainSTMOption,
+) -> DatagramPtr {
+    DatagramPtr::new(
+        GainSTM {
+            // SAFETY: This is guaranteed by the caller.
+            gains: unsafe { self::gains(gains, size) },
+            config: freq * Hz,
+            option,
+        }
+        .into_boxed(),
+    )
+}
+
+/// Same as [`autd_stm_gain_exact`], but with the nearest frequency to `freq` Hz that can be output.
+///
+/// # Safety
+///
+/// `gains` must be valid for reads of `size` valid handles, which must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn autd_stm_gain_nearest(
+    gains: *const GainPtr,
+    size: u16,
+    freq: f32,
+    option: GainSTMOption,
+) -> DatagramPtr {
+    DatagramPtr::new(
+        GainSTM {
+            // SAFETY: This is guaranteed by the caller.
+            gains: unsafe { self::gains(gains, size) },
+            config: freq * Hz,
+            option,
+        }
+        .into_nearest()
+        .into_boxed(),
+    )
+}
+
+/// Releases the datagram which is not passed to [`autd_controller_send_datagram`].
+///
+/// # Safety
+///
+/// `d` must be null or a valid handle, and must not be used after this call.
+///
+/// [`autd_controller_send_datagram`]: crate::autd_controller_send_datagram
+#[no_mangle]
+pub unsafe extern "C" fn autd_datagram_free(d: DatagramPtr) {
+    if !d.0.is_null() {
+        // SAFETY: This is guaranteed by the caller.
+        drop(unsafe { d.take() });
+    }
+}
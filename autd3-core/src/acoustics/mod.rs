@@ -10,6 +10,9 @@ use crate::{
 
 use directivity::Directivity;
 
+#[cfg(feature = "gain")]
+use crate::{gain::Drive, geometry::Device};
+
 /// Calculate the pressure at the target position.
 #[inline]
 pub fn propagate<D: Directivity>(
@@ -27,6 +30,23 @@ pub fn propagate<D: Directivity>(
     )
 }
 
+/// Calculate the complex pressure at the target position generated by the transducers of the device.
+///
+/// `drives` is indexed by the local index of the transducer. The amplitude of each transducer is assumed to be proportional to its intensity, and disabled transducers are ignored.
+#[cfg(feature = "gain")]
+pub fn pressure<D: Directivity>(device: &Device, drives: &[Drive], target_pos: &Point3) -> Complex {
+    let wavenumber = device.wavenumber();
+    device
+        .iter()
+        .zip(drives.iter())
+        .filter(|(tr, _)| tr.enable)
+        .map(|(tr, d)| {
+            propagate::<D>(tr, wavenumber, device.axial_direction(), target_pos)
+                * Complex::from_polar(d.intensity.0 as f32 / 255., d.phase.radian())
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,4 +129,156 @@ mod tests {
             super::propagate::<TestDirectivity>(&tr, wavenumber, device.axial_direction(), &target)
         );
     }
+
+    #[cfg(feature = "gain")]
+    mod pressure {
+        use super::*;
+
+        use crate::{
+            acoustics::directivity::Sphere,
+            defined::rad,
+            gain::{EmitIntensity, Phase},
+        };
+
+        use proptest::prelude::*;
+
+        fn point() -> impl Strategy<Value = Point3> {
+            (-100.0f32..100.0, -100.0f32..100.0, -100.0f32..100.0)
+                .prop_map(|(x, y, z)| Point3::new(x, y, z))
+        }
+
+        type DeviceParams = ((f32, f32, f32), Vec<Point3>, f32);
+
+        fn device_params() -> impl Strategy<Value = DeviceParams> {
+            (
+                (-180.0f32..180.0, -180.0f32..180.0, -180.0f32..180.0),
+                proptest::collection::vec(point(), 16),
+                300e3f32..400e3,
+            )
+        }
+
+        fn build_device(((rx, ry, rz), positions, sound_speed): DeviceParams) -> Device {
+            let rot = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), rx.to_radians())
+                * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), ry.to_radians())
+                * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), rz.to_radians());
+            let mut device = Device::new(
+                0,
+                rot,
+                positions
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, p)| Transducer::new(i as _, 0, p))
+                    .collect(),
+            );
+            device.sound_speed = sound_speed;
+            device
+        }
+
+        fn drives_strategy() -> impl Strategy<Value = Vec<Drive>> {
+            proptest::collection::vec(
+                (any::<u8>(), any::<u8>()).prop_map(|(phase, intensity)| Drive {
+                    phase: Phase(phase),
+                    intensity: EmitIntensity(intensity),
+                }),
+                16,
+            )
+        }
+
+        // Exclude the targets too close to any transducer, where the pressure diverges.
+        fn separated(device: &Device, target: &Point3) -> bool {
+            device.iter().all(|tr| (target - tr.position()).norm() > 1.)
+        }
+
+        #[rstest::fixture]
+        fn device(rot: UnitQuaternion, sound_speed: f32) -> Device {
+            let mut rng = rand::rng();
+            let mut device = Device::new(
+                0,
+                rot,
+                (0..16)
+                    .map(|i| {
+                        Transducer::new(
+                            i,
+                            0,
+                            Point3::new(
+                                rng.random_range(-100.0..100.0),
+                                rng.random_range(-100.0..100.0),
+                                rng.random_range(-100.0..100.0),
+                            ),
+                        )
+                    })
+                    .collect(),
+            );
+            device.sound_speed = sound_speed;
+            device
+        }
+
+        #[rstest::fixture]
+        fn drives() -> Vec<Drive> {
+            let mut rng = rand::rng();
+            (0..16)
+                .map(|_| Drive {
+                    phase: Phase(rng.random()),
+                    intensity: EmitIntensity(rng.random()),
+                })
+                .collect()
+        }
+
+        proptest::proptest! {
+            #[test]
+            fn test_pressure(device in device_params(), drives in drives_strategy(), target in point()) {
+                let device = build_device(device);
+                prop_assume!(separated(&device, &target));
+                let expect = device
+                    .iter()
+                    .zip(drives.iter())
+                    .map(|(tr, d)| {
+                        propagate::<TestDirectivity>(
+                            tr,
+                            device.wavenumber(),
+                            device.axial_direction(),
+                            &target,
+                        ) * Complex::from_polar(d.intensity.0 as f32 / 255., d.phase.radian())
+                    })
+                    .sum::<Complex>();
+                let p = super::super::pressure::<TestDirectivity>(&device, &drives, &target);
+                prop_assert!((expect - p).norm() <= 1e-3 / mm);
+            }
+
+            #[test]
+            fn test_pressure_focused_is_max(device in device_params(), drives in drives_strategy(), target in point()) {
+                let device = build_device(device);
+                prop_assume!(separated(&device, &target));
+                let focused = device
+                    .iter()
+                    .map(|tr| Drive {
+                        phase: Phase::from(
+                            -(target - tr.position()).norm() * device.wavenumber() * rad,
+                        ),
+                        intensity: EmitIntensity::MAX,
+                    })
+                    .collect::<Vec<_>>();
+                let max = super::super::pressure::<Sphere>(&device, &focused, &target).norm();
+                let upper = device
+                    .iter()
+                    .map(|tr| {
+                        propagate::<Sphere>(tr, device.wavenumber(), device.axial_direction(), &target)
+                            .norm()
+                    })
+                    .sum::<f32>();
+                prop_assert!((upper - max).abs() <= upper * 1e-2);
+                prop_assert!(super::super::pressure::<Sphere>(&device, &drives, &target).norm() <= max);
+            }
+        }
+
+        #[rstest::rstest]
+        #[test]
+        fn test_pressure_disabled(mut device: Device, drives: Vec<Drive>, target: Point3) {
+            device.iter_mut().for_each(|tr| tr.enable = false);
+            assert_eq!(
+                Complex::new(0., 0.),
+                super::super::pressure::<Sphere>(&device, &drives, &target)
+            );
+        }
+    }
 }
@@ -2,6 +2,7 @@ use std::num::NonZeroU16;
 
 /// The behavior of the loop.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub enum LoopBehavior {
     /// Infinite loop.
@@ -53,4 +54,15 @@ mod tests {
         assert_eq!(format!("{:?}", LoopBehavior::Infinite), "Infinite");
         assert_eq!(format!("{:?}", LoopBehavior::ONCE), "Finite(1)");
     }
+
+    #[cfg(feature = "serde")]
+    #[rstest::rstest]
+    #[test]
+    #[case(r#""Infinite""#, LoopBehavior::Infinite)]
+    #[case(r#"{"Finite":1}"#, LoopBehavior::ONCE)]
+    fn serde(#[case] expect: &str, #[case] target: LoopBehavior) -> anyhow::Result<()> {
+        assert_eq!(expect, serde_json::to_string(&target)?);
+        assert_eq!(target, serde_json::from_str(expect)?);
+        Ok(())
+    }
 }
@@ -1,5 +1,6 @@
 /// Segment of the FPGA memory
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Segment {
     /// Segment 0
@@ -53,13 +53,15 @@ mod inner {
 
 #[cfg(feature = "dynamic_freq")]
 mod inner {
-    use std::sync::LazyLock;
+    use std::sync::OnceLock;
 
     use super::Freq;
     use crate::defined::Hz;
 
-    static LAZY_FREQ: LazyLock<Freq<u32>> =
-        LazyLock::new(|| match std::env::var("AUTD3_ULTRASOUND_FREQ") {
+    static FREQ: OnceLock<Freq<u32>> = OnceLock::new();
+
+    fn from_env() -> Freq<u32> {
+        match std::env::var("AUTD3_ULTRASOUND_FREQ") {
             Ok(freq) => match freq.parse::<u32>() {
                 Ok(freq) => {
                     tracing::info!("Set ultrasound frequency to {} Hz.", freq);
@@ -79,16 +81,44 @@ mod inner {
                 );
                 Freq { freq: 40000 }
             }
-        });
+        }
+    }
 
     #[inline]
     /// The frequency of ultrasound
+    ///
+    /// The frequency is fixed when this function or [`set_ultrasound_freq`] is called for the first time. If [`set_ultrasound_freq`] has not been called, the frequency is read from the environment variable `AUTD3_ULTRASOUND_FREQ`.
     pub fn ultrasound_freq() -> Freq<u32> {
-        *LAZY_FREQ
+        *FREQ.get_or_init(from_env)
+    }
+
+    /// Sets the frequency of ultrasound.
+    ///
+    /// The frequency can be changed only before it is fixed, see [`ultrasound_freq`]. If the frequency has already been fixed to another value, the fixed frequency is returned as an error.
+    pub fn set_ultrasound_freq(freq: Freq<u32>) -> Result<(), Freq<u32>> {
+        let current = *FREQ.get_or_init(|| freq);
+        if current == freq {
+            Ok(())
+        } else {
+            Err(current)
+        }
     }
 
     #[doc(hidden)]
     pub const DRP_ROM_SIZE: usize = 32;
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn set_ultrasound_freq_after_fixed() {
+            let freq = ultrasound_freq();
+            assert_eq!(Ok(()), set_ultrasound_freq(freq));
+            assert_eq!(Err(freq), set_ultrasound_freq(freq + 1 * Hz));
+            assert_eq!(freq, ultrasound_freq());
+        }
+    }
 }
 
 pub use inner::*;
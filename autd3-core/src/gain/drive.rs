@@ -1,9 +1,10 @@
 use super::{emit_intensity::EmitIntensity, phase::Phase};
 
-use zerocopy::{Immutable, IntoBytes};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 /// A container for the phase and intensity of the ultrasound.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoBytes, Immutable)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoBytes, Immutable, FromBytes, KnownLayout)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Drive {
     /// The phase of the ultrasound.
@@ -34,4 +35,17 @@ mod tests {
             Drive::NULL
         );
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() -> anyhow::Result<()> {
+        let drive = Drive {
+            phase: Phase::PI,
+            intensity: EmitIntensity::MAX,
+        };
+        let json = serde_json::to_string(&drive)?;
+        assert_eq!(r#"{"phase":128,"intensity":255}"#, json);
+        assert_eq!(drive, serde_json::from_str(&json)?);
+        Ok(())
+    }
 }
@@ -1,8 +1,22 @@
 use derive_more::Debug;
-use zerocopy::{Immutable, IntoBytes};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 /// The intensity of the ultrasound.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, IntoBytes, Immutable)]
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Debug,
+    IntoBytes,
+    Immutable,
+    FromBytes,
+    KnownLayout,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[debug("{:#04X}", self.0)]
 #[repr(C)]
 pub struct EmitIntensity(pub u8);
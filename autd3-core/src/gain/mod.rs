@@ -24,12 +24,29 @@ use crate::{
 pub trait GainCalculator: Send + Sync {
     /// Calculates the phase and intensity for the transducer.
     fn calc(&self, tr: &Transducer) -> Drive;
+
+    /// Writes the phase and intensity of all transducers in the device to `dst`, which is the slice of the transmission frame.
+    ///
+    /// [`Drive::NULL`] is written for the disabled transducers. The default implementation calls [`GainCalculator::calc`] for each enabled transducer.
+    fn calc_into(&self, device: &Device, dst: &mut [Drive]) {
+        dst.iter_mut().zip(device.iter()).for_each(|(dst, tr)| {
+            *dst = if tr.enable {
+                self.calc(tr)
+            } else {
+                Drive::NULL
+            };
+        });
+    }
 }
 
 impl GainCalculator for Box<dyn GainCalculator> {
     fn calc(&self, tr: &Transducer) -> Drive {
         self.as_ref().calc(tr)
     }
+
+    fn calc_into(&self, device: &Device, dst: &mut [Drive]) {
+        self.as_ref().calc_into(device, dst)
+    }
 }
 
 /// A trait for generating a calculator for the gain operation.
@@ -7,10 +7,13 @@ use crate::{
 
 use derive_more::Debug;
 use nalgebra::ComplexField;
-use zerocopy::{Immutable, IntoBytes};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
 
 /// The phase of the ultrasound.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, IntoBytes, Immutable, Default)]
+#[derive(
+    Clone, Copy, PartialEq, Eq, Debug, IntoBytes, Immutable, FromBytes, KnownLayout, Default,
+)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 #[debug("{:#04X}", self.0)]
 pub struct Phase(pub u8);
@@ -0,0 +1,241 @@
+use nalgebra::{Matrix3, Rotation3};
+use thiserror::Error;
+
+use super::{Device, Isometry, Point3, Translation, UnitQuaternion, Vector3};
+
+/// An error occurred during the pose estimation.
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CalibrationError {
+    /// The number of points is less than 3.
+    #[error("At least 3 points are required, but {0} points are given")]
+    InsufficientPoints(usize),
+    /// The numbers of the model points and the measured points are different.
+    #[error("Number of model points ({0}) and measured points ({1}) must be the same")]
+    LengthMismatch(usize, usize),
+    /// The points are collinear or coincident, so the pose cannot be determined.
+    #[error("Points must not be collinear")]
+    Degenerate,
+    /// The transducer index is out of range.
+    #[error("Transducer index ({0}) is out of range")]
+    TransducerIndexOutOfRange(usize),
+}
+
+/// The pose estimated by [`fit_pose`] or [`fit_device_pose`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoseFit {
+    /// The rigid transformation from the model points to the measured points.
+    pub isometry: Isometry,
+    /// The distances between each transformed model point and the corresponding measured point.
+    pub residuals: Vec<f32>,
+}
+
+impl PoseFit {
+    /// The position, i.e., the translation of [`PoseFit::isometry`].
+    pub fn position(&self) -> Point3 {
+        Point3::from(self.isometry.translation.vector)
+    }
+
+    /// The rotation of [`PoseFit::isometry`].
+    pub fn rotation(&self) -> UnitQuaternion {
+        self.isometry.rotation
+    }
+
+    /// The root mean square of the residuals.
+    pub fn rms(&self) -> f32 {
+        (self.residuals.iter().map(|r| r * r).sum::<f32>() / self.residuals.len() as f32).sqrt()
+    }
+
+    /// The maximum of the residuals.
+    pub fn max_residual(&self) -> f32 {
+        self.residuals.iter().copied().fold(0., f32::max)
+    }
+
+    /// Moves the device to the estimated pose, where the model points are assumed to be in the device coordinate as in [`fit_device_pose`].
+    pub fn apply(&self, device: &mut Device) {
+        device.rotate_to(self.rotation());
+        device.translate_to(self.position());
+    }
+}
+
+/// Estimates the rigid transformation that maps `model` points to `measured` points in the least-squares sense by the Kabsch algorithm.
+///
+/// If `model` points are given in the device coordinate, i.e., the coordinate whose origin is the first transducer and whose axes are the x, y, and axial directions of the device,
+/// [`PoseFit::position`] and [`PoseFit::rotation`] are the position and rotation of the device, which can be used to construct the device, e.g., `AUTD3 { pos, rot }`.
+pub fn fit_pose(model: &[Point3], measured: &[Point3]) -> Result<PoseFit, CalibrationError> {
+    if model.len() != measured.len() {
+        return Err(CalibrationError::LengthMismatch(
+            model.len(),
+            measured.len(),
+        ));
+    }
+    if model.len() < 3 {
+        return Err(CalibrationError::InsufficientPoints(model.len()));
+    }
+
+    let centroid =
+        |points: &[Point3]| points.iter().map(|p| p.coords).sum::<Vector3>() / points.len() as f32;
+    let model_centroid = centroid(model);
+    let measured_centroid = centroid(measured);
+    let h = model
+        .iter()
+        .zip(measured)
+        .fold(Matrix3::zeros(), |acc, (m, p)| {
+            acc + (m.coords - model_centroid) * (p.coords - measured_centroid).transpose()
+        });
+
+    let svd = h.svd(true, true);
+    let mut singular_values = svd.singular_values;
+    singular_values
+        .as_mut_slice()
+        .sort_by(|a, b| b.total_cmp(a));
+    if singular_values[1] <= singular_values[0] * 1e-6 {
+        return Err(CalibrationError::Degenerate);
+    }
+    let (u, v) = (svd.u.unwrap(), svd.v_t.unwrap().transpose());
+    let mut d = Vector3::from_element(1.);
+    d[svd.singular_values.imin()] = (v * u.transpose()).determinant().signum();
+    let rotation = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(
+        v * Matrix3::from_diagonal(&d) * u.transpose(),
+    ));
+
+    let isometry = Isometry {
+        translation: Translation::from(measured_centroid - rotation * model_centroid),
+        rotation,
+    };
+    let residuals = model
+        .iter()
+        .zip(measured)
+        .map(|(m, p)| (isometry * m - p).norm())
+        .collect();
+    Ok(PoseFit {
+        isometry,
+        residuals,
+    })
+}
+
+/// Estimates the pose of the device from the measured positions of its transducers.
+///
+/// `measured` is the pairs of the transducer index and the measured position. The model points are the positions of the transducers in the device coordinate, so the result can be used as described in [`fit_pose`], or applied by [`PoseFit::apply`].
+pub fn fit_device_pose(
+    device: &Device,
+    measured: &[(usize, Point3)],
+) -> Result<PoseFit, CalibrationError> {
+    let (model, measured): (Vec<_>, Vec<_>) = measured
+        .iter()
+        .map(|&(idx, p)| {
+            device
+                .get(idx)
+                .map(|tr| (device.inv().transform_point(tr.position()), p))
+                .ok_or(CalibrationError::TransducerIndexOutOfRange(idx))
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .unzip();
+    fit_pose(&model, &measured)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        defined::{deg, mm},
+        geometry::{tests::TestDevice, EulerAngle, IntoDevice},
+    };
+
+    use super::*;
+
+    #[test]
+    fn fit() -> anyhow::Result<()> {
+        let model = [
+            Point3::origin(),
+            Point3::new(10., 0., 0.),
+            Point3::new(0., 20., 0.),
+            Point3::new(0., 0., 30.),
+        ];
+        let rotation = UnitQuaternion::from(EulerAngle::ZYZ(30. * deg, 45. * deg, 60. * deg));
+        let translation = Vector3::new(1., 2., 3.);
+        let measured = model
+            .iter()
+            .map(|p| rotation * p + translation)
+            .collect::<Vec<_>>();
+
+        let fit = fit_pose(&model, &measured)?;
+        approx::assert_relative_eq!(rotation, fit.rotation(), epsilon = 1e-5);
+        approx::assert_relative_eq!(Point3::from(translation), fit.position(), epsilon = 1e-4);
+        assert!(fit.rms() < 1e-4);
+        assert!(fit.max_residual() < 1e-4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fit_residual() -> anyhow::Result<()> {
+        let model = [
+            Point3::new(-1., -1., 0.),
+            Point3::new(1., -1., 0.),
+            Point3::new(1., 1., 0.),
+            Point3::new(-1., 1., 0.),
+        ];
+        let measured = [
+            Point3::new(-1., -1., 0.1),
+            Point3::new(1., -1., -0.1),
+            Point3::new(1., 1., 0.1),
+            Point3::new(-1., 1., -0.1),
+        ];
+
+        let fit = fit_pose(&model, &measured)?;
+        approx::assert_relative_eq!(UnitQuaternion::identity(), fit.rotation(), epsilon = 1e-5);
+        approx::assert_abs_diff_eq!(0.1, fit.rms(), epsilon = 1e-5);
+        approx::assert_abs_diff_eq!(0.1, fit.max_residual(), epsilon = 1e-5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fit_device() -> anyhow::Result<()> {
+        let mut device = TestDevice::new_autd3(Point3::origin()).into_device(0);
+        let pos = Point3::new(10. * mm, 20. * mm, 30. * mm);
+        let rot = UnitQuaternion::from(EulerAngle::ZYZ(90. * deg, 30. * deg, 0. * deg));
+        let expect = TestDevice::new_autd3_with_rot(pos, rot).into_device(0);
+
+        let fit = fit_device_pose(
+            &device,
+            &[0, 17, 234, 100].map(|i| (i, *expect[i].position())),
+        )?;
+        approx::assert_relative_eq!(rot, fit.rotation(), epsilon = 1e-5);
+        approx::assert_relative_eq!(pos, fit.position(), epsilon = 1e-3);
+
+        fit.apply(&mut device);
+        device.iter().zip(expect.iter()).for_each(|(a, b)| {
+            approx::assert_abs_diff_eq!(a.position(), b.position(), epsilon = 1e-3);
+        });
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(CalibrationError::LengthMismatch(3, 2), vec![Point3::origin(); 3], vec![Point3::origin(); 2])]
+    #[case(CalibrationError::InsufficientPoints(2), vec![Point3::origin(); 2], vec![Point3::origin(); 2])]
+    #[case(CalibrationError::Degenerate, vec![Point3::origin(); 3], vec![Point3::origin(); 3])]
+    #[case(
+        CalibrationError::Degenerate,
+        vec![Point3::origin(), Point3::new(1., 0., 0.), Point3::new(2., 0., 0.)],
+        vec![Point3::origin(), Point3::new(0., 1., 0.), Point3::new(0., 2., 0.)]
+    )]
+    #[test]
+    fn fit_err(
+        #[case] expect: CalibrationError,
+        #[case] model: Vec<Point3>,
+        #[case] measured: Vec<Point3>,
+    ) {
+        assert_eq!(Err(expect), fit_pose(&model, &measured));
+    }
+
+    #[test]
+    fn fit_device_err() {
+        let device = TestDevice::new_autd3(Point3::origin()).into_device(0);
+        assert_eq!(
+            Err(CalibrationError::TransducerIndexOutOfRange(252)),
+            fit_device_pose(&device, &[0, 1, 252].map(|i| (i, Point3::origin())))
+        );
+    }
+}
@@ -11,7 +11,14 @@ use super::{
 };
 
 /// An AUTD device unit.
+///
+/// With `serde` feature, [`Device`] is serialized as its index, enable flag, sound speed, rotation, and transducers, i.e., the pose of the device.
 #[derive(Getters, Deref, IntoIterator)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize),
+    serde(try_from = "DeviceEntry<Vec<Transducer>>")
+)]
 pub struct Device {
     idx: u16,
     #[deref]
@@ -20,6 +27,10 @@ pub struct Device {
     /// enable flag
     pub enable: bool,
     /// speed of sound
+    ///
+    /// This is used to calculate the wavelength and wavenumber of this device, e.g., by [`Gain`]s and FociSTM, so each device can have a different value and it can be updated at runtime.
+    ///
+    /// [`Gain`]: crate::gain::Gain
     pub sound_speed: f32,
     #[getset(get = "pub")]
     /// The rotation of the device.
@@ -98,6 +109,11 @@ impl Device {
         self.transducers.len()
     }
 
+    /// Gets the mutable iterator of the transducers.
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Transducer> {
+        self.transducers.iter_mut()
+    }
+
     /// Translates the device to the target position.
     pub fn translate_to(&mut self, t: Point3) {
         self.translate(t - self.transducers[0].position());
@@ -108,6 +124,14 @@ impl Device {
         self.rotate(r * self.rotation.conjugate());
     }
 
+    /// Moves the device to the target pose, i.e., the position of the first transducer and the rotation.
+    ///
+    /// This is equivalent to [`Self::rotate_to`] followed by [`Self::translate_to`], but the transducers are transformed only once.
+    pub fn set_pose(&mut self, t: Point3, r: UnitQuaternion) {
+        let r = r * self.rotation.conjugate();
+        self.affine(t - r * self.transducers[0].position(), r);
+    }
+
     /// Translates the device.
     pub fn translate(&mut self, t: Vector3) {
         self.affine(t, UnitQuaternion::identity());
@@ -172,6 +196,63 @@ impl IntoDevice for Device {
     }
 }
 
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DeviceEntry<T> {
+    idx: u16,
+    enable: bool,
+    sound_speed: f32,
+    rotation: UnitQuaternion,
+    transducers: T,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Device {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DeviceEntry {
+            idx: self.idx,
+            enable: self.enable,
+            sound_speed: self.sound_speed,
+            rotation: self.rotation,
+            transducers: &self.transducers,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DeviceEntry<Vec<Transducer>>> for Device {
+    type Error = String;
+
+    fn try_from(entry: DeviceEntry<Vec<Transducer>>) -> Result<Self, Self::Error> {
+        if entry.transducers.is_empty() {
+            return Err("Device must have at least one transducer".to_string());
+        }
+        if let Some((i, tr)) = entry
+            .transducers
+            .iter()
+            .enumerate()
+            .find(|(i, tr)| tr.idx() != *i || tr.dev_idx() != entry.idx as usize)
+        {
+            return Err(format!(
+                "Transducer {} of device {} has invalid index ({}, {})",
+                i,
+                entry.idx,
+                tr.dev_idx(),
+                tr.idx()
+            ));
+        }
+        let mut dev = Device::new(
+            entry.idx,
+            UnitQuaternion::new_normalize(entry.rotation.into_inner()),
+            entry.transducers,
+        );
+        dev.enable = entry.enable;
+        dev.sound_speed = entry.sound_speed;
+        Ok(dev)
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use rand::Rng;
@@ -215,6 +296,16 @@ pub(crate) mod tests {
         assert_eq!(n, create_device(0, n).num_transducers() as u8);
     }
 
+    #[test]
+    fn iter_mut() {
+        let mut device = create_device(0, 249);
+        device
+            .iter_mut()
+            .filter(|tr| tr.idx() % 2 == 0)
+            .for_each(|tr| tr.enable = false);
+        assert!(device.iter().all(|tr| tr.enable == (tr.idx() % 2 == 1)));
+    }
+
     #[test]
     fn center() {
         let device = TestDevice::new_autd3(Point3::origin()).into_device(0);
@@ -314,6 +405,33 @@ pub(crate) mod tests {
             });
     }
 
+    #[test]
+    fn set_pose() {
+        let mut rng = rand::rng();
+        let mut device = TestDevice::new_autd3_with_rot(
+            Point3::new(rng.random(), rng.random(), rng.random()),
+            UnitQuaternion::from_axis_angle(&Vector3::x_axis(), rng.random())
+                * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), rng.random())
+                * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), rng.random()),
+        )
+        .into_device(0);
+
+        let t = Point3::new(40., 50., 60.);
+        let rot = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 0.)
+            * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.)
+            * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), PI / 2.);
+        device.set_pose(t, rot);
+
+        assert_approx_eq_quat!(rot, device.rotation());
+        TestDevice::new_autd3_with_rot(t, rot)
+            .into_device(0)
+            .iter()
+            .zip(device.iter())
+            .for_each(|(expect, tr)| {
+                assert_approx_eq_vec3!(expect.position(), tr.position());
+            });
+    }
+
     #[test]
     fn translate() {
         let mut rng = rand::rng();
@@ -458,4 +576,37 @@ pub(crate) mod tests {
         assert_eq!(expect_aabb.min, dev.aabb.min);
         assert_eq!(expect_aabb.max, dev.aabb.max);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() -> anyhow::Result<()> {
+        let mut dev = create_device(1, 3);
+        dev.affine(
+            Vector3::new(10., 20., 30.),
+            UnitQuaternion::from_axis_angle(&Vector3::z_axis(), PI / 2.),
+        );
+        dev.enable = false;
+        dev.sound_speed = 350e3;
+
+        let json = serde_json::to_string(&dev)?;
+        let d: Device = serde_json::from_str(&json)?;
+        assert_eq!(dev.idx(), d.idx());
+        assert_eq!(dev.transducers, d.transducers);
+        assert_eq!(dev.enable, d.enable);
+        assert_eq!(dev.sound_speed, d.sound_speed);
+        assert_approx_eq_quat!(dev.rotation, d.rotation);
+        assert_approx_eq_vec3!(dev.center, d.center);
+        assert_approx_eq_vec3!(dev.axial_direction, d.axial_direction);
+
+        assert!(serde_json::from_str::<Device>(
+            r#"{"idx":0,"enable":true,"sound_speed":340000.0,"rotation":[0.0,0.0,0.0,1.0],"transducers":[]}"#
+        )
+        .is_err());
+        assert!(serde_json::from_str::<Device>(
+            r#"{"idx":0,"enable":true,"sound_speed":340000.0,"rotation":[0.0,0.0,0.0,1.0],"transducers":[{"idx":0,"dev_idx":1,"position":[0.0,0.0,0.0],"enable":true}]}"#
+        )
+        .is_err());
+
+        Ok(())
+    }
 }
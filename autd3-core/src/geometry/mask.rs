@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+
+use derive_more::{Deref, From, Into};
+use thiserror::Error;
+
+use super::Geometry;
+
+type BitVec = bit_vec::BitVec<u32>;
+
+/// An error occurred during constructing [`TransducerMask`].
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum MaskError {
+    /// The device index is out of range.
+    #[error("Device index ({0}) is out of range")]
+    DeviceIndexOutOfRange(usize),
+    /// The transducer index is out of range.
+    #[error("Transducer index ({1}) of device ({0}) is out of range")]
+    TransducerIndexOutOfRange(usize, usize),
+}
+
+/// A set of transducers, i.e., a map from the device index to the mask of its transducers.
+///
+/// This is the same format as the filter passed to [`Gain::init_full`], which can be got by dereferencing.
+/// Devices not included in the map have no selected transducers.
+///
+/// With `serde` feature, [`TransducerMask`] is serialized as a list of the device index, the number of transducers, and the indices of the selected transducers.
+///
+/// [`Gain::init_full`]: crate::gain::Gain::init_full
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deref, From, Into)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Vec<DeviceEntry>", into = "Vec<DeviceEntry>")
+)]
+pub struct TransducerMask(HashMap<usize, BitVec>);
+
+impl TransducerMask {
+    /// Creates an empty mask.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a mask selecting all transducers of enabled devices.
+    pub fn all(geometry: &Geometry) -> Self {
+        Self(
+            geometry
+                .devices()
+                .map(|dev| (dev.idx(), BitVec::from_elem(dev.num_transducers(), true)))
+                .collect(),
+        )
+    }
+
+    /// Creates a mask selecting all transducers of the devices in `devices`.
+    pub fn from_devices(
+        geometry: &Geometry,
+        devices: impl IntoIterator<Item = usize>,
+    ) -> Result<Self, MaskError> {
+        devices
+            .into_iter()
+            .map(|idx| {
+                geometry
+                    .get(idx)
+                    .map(|dev| (idx, BitVec::from_elem(dev.num_transducers(), true)))
+                    .ok_or(MaskError::DeviceIndexOutOfRange(idx))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    /// Creates a mask selecting the transducers given by pairs of the device index and the transducer index.
+    pub fn from_indices(
+        geometry: &Geometry,
+        indices: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Result<Self, MaskError> {
+        let mut map = HashMap::new();
+        indices.into_iter().try_for_each(|(dev_idx, tr_idx)| {
+            let dev = geometry
+                .get(dev_idx)
+                .ok_or(MaskError::DeviceIndexOutOfRange(dev_idx))?;
+            if tr_idx >= dev.num_transducers() {
+                return Err(MaskError::TransducerIndexOutOfRange(dev_idx, tr_idx));
+            }
+            map.entry(dev_idx)
+                .or_insert_with(|| BitVec::from_elem(dev.num_transducers(), false))
+                .set(tr_idx, true);
+            Ok(())
+        })?;
+        Ok(Self(map))
+    }
+
+    /// Returns `true` if the transducer is selected.
+    pub fn is_selected(&self, dev_idx: usize, tr_idx: usize) -> bool {
+        self.0
+            .get(&dev_idx)
+            .and_then(|mask| mask.get(tr_idx))
+            .unwrap_or(false)
+    }
+
+    /// Gets the number of selected transducers.
+    pub fn count(&self) -> usize {
+        self.0
+            .values()
+            .map(|mask| mask.iter().filter(|&b| b).count())
+            .sum()
+    }
+
+    fn combine(
+        lhs: Option<&BitVec>,
+        rhs: Option<&BitVec>,
+        f: impl Fn(bool, bool) -> bool,
+    ) -> Option<BitVec> {
+        let len = lhs.map_or(0, |m| m.len()).max(rhs.map_or(0, |m| m.len()));
+        let get = |m: Option<&BitVec>, i| m.and_then(|m| m.get(i)).unwrap_or(false);
+        let mask = BitVec::from_fn(len, |i| f(get(lhs, i), get(rhs, i)));
+        mask.any().then_some(mask)
+    }
+
+    /// Gets the union of two masks.
+    pub fn union(&self, other: &Self) -> Self {
+        Self(
+            self.0
+                .keys()
+                .chain(other.0.keys())
+                .filter_map(|&idx| {
+                    Self::combine(self.0.get(&idx), other.0.get(&idx), |a, b| a | b)
+                        .map(|mask| (idx, mask))
+                })
+                .collect(),
+        )
+    }
+
+    /// Gets the intersection of two masks.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .filter_map(|(&idx, mask)| {
+                    Self::combine(Some(mask), other.0.get(&idx), |a, b| a & b)
+                        .map(|mask| (idx, mask))
+                })
+                .collect(),
+        )
+    }
+
+    /// Gets the transducers of enabled devices in `geometry` that are not selected.
+    pub fn complement(&self, geometry: &Geometry) -> Self {
+        Self(
+            geometry
+                .devices()
+                .filter_map(|dev| {
+                    let mask =
+                        BitVec::from_fn(dev.num_transducers(), |i| !self.is_selected(dev.idx(), i));
+                    mask.any().then_some((dev.idx(), mask))
+                })
+                .collect(),
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DeviceEntry {
+    device: usize,
+    num_transducers: usize,
+    indices: Vec<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl From<TransducerMask> for Vec<DeviceEntry> {
+    fn from(mask: TransducerMask) -> Self {
+        let mut entries = mask
+            .0
+            .into_iter()
+            .map(|(device, mask)| DeviceEntry {
+                device,
+                num_transducers: mask.len(),
+                indices: mask
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, b)| b.then_some(i))
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by_key(|e| e.device);
+        entries
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<Vec<DeviceEntry>> for TransducerMask {
+    type Error = MaskError;
+
+    fn try_from(entries: Vec<DeviceEntry>) -> Result<Self, Self::Error> {
+        entries
+            .into_iter()
+            .map(|e| {
+                let mut mask = BitVec::from_elem(e.num_transducers, false);
+                e.indices.into_iter().try_for_each(|i| {
+                    if i >= e.num_transducers {
+                        return Err(MaskError::TransducerIndexOutOfRange(e.device, i));
+                    }
+                    mask.set(i, true);
+                    Ok(())
+                })?;
+                Ok((e.device, mask))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::tests::create_geometry;
+
+    use super::*;
+
+    fn selected(mask: &TransducerMask) -> Vec<(usize, usize)> {
+        let mut v = mask
+            .iter()
+            .flat_map(|(&dev, m)| {
+                m.iter()
+                    .enumerate()
+                    .filter_map(move |(i, b)| b.then_some((dev, i)))
+            })
+            .collect::<Vec<_>>();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn constructors() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(3, 2);
+
+        assert_eq!(0, TransducerMask::new().count());
+        assert_eq!(6, TransducerMask::all(&geometry).count());
+
+        let mask = TransducerMask::from_devices(&geometry, [0, 2])?;
+        assert_eq!(vec![(0, 0), (0, 1), (2, 0), (2, 1)], selected(&mask));
+
+        let mask = TransducerMask::from_indices(&geometry, [(1, 1), (0, 0), (1, 1)])?;
+        assert_eq!(vec![(0, 0), (1, 1)], selected(&mask));
+        assert!(mask.is_selected(1, 1));
+        assert!(!mask.is_selected(1, 0));
+        assert!(!mask.is_selected(2, 0));
+        assert_eq!(2, mask[&1].len());
+
+        geometry[1].enable = false;
+        assert_eq!(
+            vec![(0, 0), (0, 1), (2, 0), (2, 1)],
+            selected(&TransducerMask::all(&geometry))
+        );
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(MaskError::DeviceIndexOutOfRange(3), vec![(0, 0), (3, 0)])]
+    #[case(MaskError::TransducerIndexOutOfRange(1, 2), vec![(1, 2)])]
+    #[test]
+    fn from_indices_err(#[case] expect: MaskError, #[case] indices: Vec<(usize, usize)>) {
+        let geometry = create_geometry(3, 2);
+        assert_eq!(
+            Err(expect),
+            TransducerMask::from_indices(&geometry, indices)
+        );
+    }
+
+    #[test]
+    fn from_devices_err() {
+        let geometry = create_geometry(3, 2);
+        assert_eq!(
+            Err(MaskError::DeviceIndexOutOfRange(3)),
+            TransducerMask::from_devices(&geometry, [3])
+        );
+    }
+
+    #[test]
+    fn set_operations() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(3, 2);
+        let a = TransducerMask::from_indices(&geometry, [(0, 0), (1, 0), (1, 1)])?;
+        let b = TransducerMask::from_indices(&geometry, [(1, 1), (2, 0)])?;
+
+        assert_eq!(vec![(0, 0), (1, 0), (1, 1), (2, 0)], selected(&a.union(&b)));
+        assert_eq!(vec![(1, 1)], selected(&a.intersection(&b)));
+        assert!(!a.intersection(&b).contains_key(&0));
+        assert_eq!(
+            vec![(0, 1), (2, 0), (2, 1)],
+            selected(&a.complement(&geometry))
+        );
+        assert!(!a.complement(&geometry).contains_key(&1));
+
+        geometry[2].enable = false;
+        assert_eq!(vec![(0, 1)], selected(&a.complement(&geometry)));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() -> anyhow::Result<()> {
+        let geometry = create_geometry(3, 2);
+        let mask = TransducerMask::from_indices(&geometry, [(2, 1), (0, 0), (0, 1)])?;
+
+        let json = serde_json::to_string(&mask)?;
+        assert_eq!(
+            r#"[{"device":0,"num_transducers":2,"indices":[0,1]},{"device":2,"num_transducers":2,"indices":[1]}]"#,
+            json
+        );
+        assert_eq!(mask, serde_json::from_str(&json)?);
+
+        assert!(serde_json::from_str::<TransducerMask>(
+            r#"[{"device":0,"num_transducers":2,"indices":[2]}]"#
+        )
+        .is_err());
+
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
+mod calibration;
 pub(crate) mod device;
+mod mask;
+mod query;
 mod rotation;
 mod transducer;
 
@@ -8,6 +11,8 @@ pub type Complex = nalgebra::Complex<f32>;
 pub type Vector3 = nalgebra::Vector3<f32>;
 /// 3-dimensional unit vector.
 pub type UnitVector3 = nalgebra::UnitVector3<f32>;
+/// 2-dimensional point.
+pub type Point2 = nalgebra::Point2<f32>;
 /// 3-dimensional point.
 pub type Point3 = nalgebra::Point3<f32>;
 /// A quaternion.
@@ -20,8 +25,10 @@ pub type Translation = nalgebra::Translation3<f32>;
 pub type Isometry = nalgebra::Isometry3<f32>;
 
 pub use bvh::aabb::Aabb;
+pub use calibration::{fit_device_pose, fit_pose, CalibrationError, PoseFit};
 pub use device::*;
 use getset::CopyGetters;
+pub use mask::{MaskError, TransducerMask};
 pub use rotation::*;
 pub use transducer::*;
 
@@ -29,7 +36,14 @@ use derive_more::{Deref, IntoIterator};
 use derive_new::new;
 
 /// Geometry of the devices.
+///
+/// With `serde` feature, [`Geometry`] is serialized as a list of [`Device`]s.
 #[derive(Deref, CopyGetters, IntoIterator, new)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Geometry {
     #[deref]
     #[into_iterator(ref)]
@@ -37,6 +51,7 @@ pub struct Geometry {
     #[doc(hidden)]
     #[new(default)]
     #[getset(get_copy = "pub")]
+    #[cfg_attr(feature = "serde", serde(skip))]
     version: usize,
 }
 
@@ -52,10 +67,16 @@ impl Geometry {
     }
 
     /// Gets the center of the enabled transducers.
-    pub fn center(&self) -> Point3 {
-        Point3::from(
-            self.devices().map(|d| d.center().coords).sum::<Vector3>() / self.devices.len() as f32,
-        )
+    ///
+    /// Returns `None` if there are no enabled devices.
+    pub fn center(&self) -> Option<Point3> {
+        let num_devices = self.num_devices();
+        if num_devices == 0 {
+            return None;
+        }
+        Some(Point3::from(
+            self.devices().map(|d| d.center().coords).sum::<Vector3>() / num_devices as f32,
+        ))
     }
 
     /// Gets the iterator of enabled devices.
@@ -214,10 +235,28 @@ pub(crate) mod tests {
             .sum::<Vector3>()
             / geometry.num_devices() as f32;
         assert_eq!(0, geometry.version());
-        assert_approx_eq_vec3!(expect, geometry.center());
+        assert_approx_eq_vec3!(expect, geometry.center().unwrap());
         assert_eq!(0, geometry.version());
     }
 
+    #[test]
+    fn test_center_disabled() {
+        let mut geometry = Geometry::new(vec![
+            TestDevice::new_autd3(Point3::origin()).into_device(0),
+            TestDevice::new_autd3(Point3::new(10., 20., 30.)).into_device(1),
+        ]);
+        geometry[1].enable = false;
+        assert_approx_eq_vec3!(geometry[0].center(), geometry.center().unwrap());
+
+        geometry[0].enable = false;
+        assert_eq!(None, geometry.center());
+    }
+
+    #[test]
+    fn test_center_empty() {
+        assert_eq!(None, Geometry::new(vec![]).center());
+    }
+
     #[rstest::rstest]
     #[test]
     #[case(340.29525e3, 15.)]
@@ -258,6 +297,14 @@ pub(crate) mod tests {
         assert_eq!(1, geometry.version());
     }
 
+    #[test]
+    fn transducer_enable() {
+        let mut geometry = create_geometry(1, 1);
+        assert_eq!(0, geometry.version());
+        geometry[0].iter_mut().for_each(|tr| tr.enable = false);
+        assert_eq!(1, geometry.version());
+    }
+
     #[rstest::rstest]
     #[test]
     #[case(Aabb{min: Point3::origin(), max: Point3::new(172.72 * mm, 132.08 * mm, 0.)}, vec![TestDevice::new_autd3(Point3::origin())])]
@@ -277,4 +324,24 @@ pub(crate) mod tests {
         assert_approx_eq_vec3!(expect.min, geometry.aabb().min);
         assert_approx_eq_vec3!(expect.max, geometry.aabb().max);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(2, 3);
+        geometry[1].translate(Vector3::new(10., 20., 30.));
+
+        let json = serde_json::to_string(&geometry)?;
+        assert!(json.starts_with(r#"[{"idx":0,"#));
+        let g: Geometry = serde_json::from_str(&json)?;
+        assert_eq!(0, g.version());
+        assert_eq!(geometry.len(), g.len());
+        geometry.iter().zip(g.iter()).for_each(|(a, b)| {
+            assert_eq!(a.idx(), b.idx());
+            assert_eq!(**a, **b);
+            assert_eq!(a.center(), b.center());
+        });
+
+        Ok(())
+    }
 }
@@ -0,0 +1,140 @@
+use super::{Aabb, Device, Geometry, Point3, Transducer, TransducerMask};
+
+fn intersects(a: &Aabb<f32, 3>, b: &Aabb<f32, 3>) -> bool {
+    (0..3).all(|i| a.min[i] <= b.max[i] && b.min[i] <= a.max[i])
+}
+
+impl Geometry {
+    fn mask(
+        &self,
+        device_filter: impl Fn(&Device) -> bool,
+        transducer_filter: impl Fn(&Transducer) -> bool,
+    ) -> TransducerMask {
+        self.devices()
+            .filter(|dev| device_filter(dev))
+            .filter_map(|dev| {
+                let mask = dev
+                    .iter()
+                    .map(&transducer_filter)
+                    .collect::<bit_vec::BitVec<u32>>();
+                mask.any().then_some((dev.idx(), mask))
+            })
+            .collect::<std::collections::HashMap<_, _>>()
+            .into()
+    }
+
+    /// Gets the enabled devices whose [`Device::aabb`] intersects with `aabb`.
+    ///
+    /// All transducers of the intersecting devices are selected in the result.
+    pub fn devices_intersecting(&self, aabb: &Aabb<f32, 3>) -> TransducerMask {
+        self.mask(|dev| intersects(dev.aabb(), aabb), |_| true)
+    }
+
+    /// Gets the transducers of enabled devices within `aabb`.
+    ///
+    /// Devices without any transducers within `aabb` are not included.
+    pub fn transducers_within_aabb(&self, aabb: &Aabb<f32, 3>) -> TransducerMask {
+        self.mask(
+            |dev| intersects(dev.aabb(), aabb),
+            |tr| aabb.contains(tr.position()),
+        )
+    }
+
+    /// Gets the transducers of enabled devices within the sphere of `radius` centered at `center`.
+    ///
+    /// Devices without any transducers within the sphere are not included.
+    pub fn transducers_within_sphere(&self, center: Point3, radius: f32) -> TransducerMask {
+        let bounds = Aabb::with_bounds(
+            center - super::Vector3::from_element(radius),
+            center + super::Vector3::from_element(radius),
+        );
+        self.mask(
+            |dev| intersects(dev.aabb(), &bounds),
+            |tr| (tr.position() - center).norm_squared() <= radius * radius,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        defined::mm,
+        geometry::{tests::TestDevice, IntoDevice},
+    };
+
+    use super::*;
+
+    fn geometry() -> Geometry {
+        Geometry::new(vec![
+            TestDevice::new_autd3(Point3::origin()).into_device(0),
+            TestDevice::new_autd3(Point3::new(200. * mm, 0., 0.)).into_device(1),
+        ])
+    }
+
+    #[rstest::rstest]
+    #[case(vec![0], Aabb::with_bounds(Point3::new(-10. * mm, -10. * mm, -10. * mm), Point3::new(10. * mm, 10. * mm, 10. * mm)))]
+    #[case(vec![0, 1], Aabb::with_bounds(Point3::new(150. * mm, 0., 0.), Point3::new(250. * mm, 0., 0.)))]
+    #[case(vec![1], Aabb::with_bounds(Point3::new(190. * mm, 0., -10. * mm), Point3::new(210. * mm, 10. * mm, 10. * mm)))]
+    #[case(vec![], Aabb::with_bounds(Point3::new(0., 0., 10. * mm), Point3::new(10. * mm, 10. * mm, 20. * mm)))]
+    #[test]
+    fn devices_intersecting(#[case] expect: Vec<usize>, #[case] aabb: Aabb<f32, 3>) {
+        let geometry = geometry();
+        let filter = geometry.devices_intersecting(&aabb);
+        let mut keys = filter.keys().copied().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(expect, keys);
+        assert!(filter.values().all(|mask| mask.all()));
+    }
+
+    #[test]
+    fn transducers_within_aabb() {
+        let geometry = geometry();
+        let aabb = Aabb::with_bounds(
+            Point3::new(-0.5 * mm, -0.5 * mm, -0.5 * mm),
+            Point3::new(11. * mm, 11. * mm, 0.5 * mm),
+        );
+        let filter = geometry.transducers_within_aabb(&aabb);
+        assert_eq!(1, filter.len());
+        assert_eq!(
+            vec![0, 1, 18, 19],
+            filter[&0]
+                .iter()
+                .enumerate()
+                .filter_map(|(i, b)| b.then_some(i))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn transducers_within_sphere() {
+        let geometry = geometry();
+        let center = *geometry[1][20].position();
+        let filter = geometry.transducers_within_sphere(center, 10.5 * mm);
+        assert_eq!(1, filter.len());
+        assert_eq!(
+            vec![2, 19, 20, 21, 38],
+            filter[&1]
+                .iter()
+                .enumerate()
+                .filter_map(|(i, b)| b.then_some(i))
+                .collect::<Vec<_>>()
+        );
+
+        let filter = geometry.transducers_within_sphere(Point3::new(186. * mm, 0., 0.), 15. * mm);
+        let mut keys = filter.keys().copied().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(vec![0, 1], keys);
+    }
+
+    #[test]
+    fn disabled() {
+        let mut geometry = geometry();
+        geometry[0].enable = false;
+        assert!(geometry
+            .transducers_within_sphere(Point3::origin(), 10. * mm)
+            .is_empty());
+        assert!(geometry
+            .devices_intersecting(&geometry[0].aabb().clone())
+            .is_empty());
+    }
+}
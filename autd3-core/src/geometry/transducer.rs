@@ -6,12 +6,18 @@ use derive_new::new;
 
 /// A ultrasound transducer.
 #[derive(Clone, Debug, PartialEq, Getters, new)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transducer {
     idx: u8,
     dev_idx: u16,
     #[getset(get = "pub")]
     /// The position of the transducer.
     position: Point3,
+    /// enable flag
+    ///
+    /// If `false`, the transducer is regarded as broken and does not emit ultrasound.
+    #[new(value = "true")]
+    pub enable: bool,
 }
 
 impl Transducer {
@@ -51,6 +57,7 @@ mod tests {
         let tr = Transducer::new(1, 2, Point3::origin());
         assert_eq!(1, tr.idx());
         assert_eq!(2, tr.dev_idx());
+        assert!(tr.enable);
     }
 
     #[rstest::rstest]
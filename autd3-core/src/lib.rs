@@ -160,8 +160,8 @@ pub mod derive {
     mod modulation {
         pub use crate::datagram::{DatagramL, LoopBehavior};
         pub use crate::modulation::{
-            Modulation, ModulationError, ModulationOperationGenerator, SamplingConfig,
-            SamplingConfigError,
+            Mix, Modulation, ModulationError, ModulationOperationGenerator, RingModulation,
+            SamplingConfig, SamplingConfigError,
         };
         pub use autd3_derive::Modulation;
         pub use std::{collections::HashMap, sync::Arc};
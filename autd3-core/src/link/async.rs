@@ -13,12 +13,28 @@ mod internal {
     /// A trait that provides the interface with the device.
     #[async_trait::async_trait]
     pub trait AsyncLink: Send {
+        /// Checks the environment before opening the link, e.g., whether the required drivers or services are available.
+        ///
+        /// This is called before [`AsyncLink::open`] and must not communicate with the devices. The default implementation does nothing.
+        async fn preflight(&mut self, _: &Geometry) -> Result<(), LinkError> {
+            Ok(())
+        }
+
         /// Opens the link.
         async fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError>;
 
         /// Closes the link.
         async fn close(&mut self) -> Result<(), LinkError>;
 
+        /// Reconfigures the link for the geometry whose devices are added or removed.
+        ///
+        /// This is called after the number of devices is changed while the link is open. The default implementation returns an error, which means that the link does not support changing the devices.
+        async fn reconfigure(&mut self, _: &Geometry) -> Result<(), LinkError> {
+            Err(LinkError::new(
+                "This link does not support changing the devices".to_owned(),
+            ))
+        }
+
         #[doc(hidden)]
         async fn update(&mut self, _: &Geometry) -> Result<(), LinkError> {
             Ok(())
@@ -37,6 +53,10 @@ mod internal {
 
     #[async_trait::async_trait]
     impl AsyncLink for Box<dyn AsyncLink> {
+        async fn preflight(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+            self.as_mut().preflight(geometry).await
+        }
+
         async fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
             self.as_mut().open(geometry).await
         }
@@ -45,6 +65,10 @@ mod internal {
             self.as_mut().close().await
         }
 
+        async fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+            self.as_mut().reconfigure(geometry).await
+        }
+
         async fn update(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
             self.as_mut().update(geometry).await
         }
@@ -69,6 +93,16 @@ mod internal {
 
     /// A trait that provides the interface with the device.
     pub trait AsyncLink: Send {
+        /// Checks the environment before opening the link, e.g., whether the required drivers or services are available.
+        ///
+        /// This is called before [`AsyncLink::open`] and must not communicate with the devices. The default implementation does nothing.
+        fn preflight(
+            &mut self,
+            _: &Geometry,
+        ) -> impl std::future::Future<Output = Result<(), LinkError>> {
+            async { Ok(()) }
+        }
+
         /// Opens the link.
         fn open(
             &mut self,
@@ -78,6 +112,20 @@ mod internal {
         /// Closes the link.
         fn close(&mut self) -> impl std::future::Future<Output = Result<(), LinkError>>;
 
+        /// Reconfigures the link for the geometry whose devices are added or removed.
+        ///
+        /// This is called after the number of devices is changed while the link is open. The default implementation returns an error, which means that the link does not support changing the devices.
+        fn reconfigure(
+            &mut self,
+            _: &Geometry,
+        ) -> impl std::future::Future<Output = Result<(), LinkError>> {
+            async {
+                Err(LinkError::new(
+                    "This link does not support changing the devices".to_owned(),
+                ))
+            }
+        }
+
         #[doc(hidden)]
         fn update(
             &mut self,
@@ -11,6 +11,8 @@ pub struct Header {
     pub slot_2_offset: u16,
 }
 
+const _: () = assert!(size_of::<Header>() == 4);
+
 #[cfg(test)]
 mod tests {
     use std::mem::offset_of;
@@ -3,6 +3,8 @@ use derive_new::new;
 use getset::CopyGetters;
 use zerocopy::{FromBytes, Immutable, IntoBytes};
 
+use crate::ethercat::EC_INPUT_FRAME_SIZE;
+
 /// PDO input data representation
 #[derive(
     Clone, Copy, PartialEq, Eq, Debug, new, CopyGetters, IntoBytes, Immutable, FromBytes, Display,
@@ -18,6 +20,8 @@ pub struct RxMessage {
     ack: u8,
 }
 
+const _: () = assert!(size_of::<RxMessage>() == EC_INPUT_FRAME_SIZE);
+
 #[cfg(test)]
 mod tests {
     use std::mem::offset_of;
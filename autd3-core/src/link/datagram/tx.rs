@@ -10,7 +10,7 @@ const PAYLOAD_SIZE: usize = EC_OUTPUT_FRAME_SIZE - std::mem::size_of::<Header>()
 /// PDO output data representation
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, Eq, IntoBytes, Immutable, FromZeros, Display)]
-#[display("({:?}, TAG: {:#04X})", header, (payload[0] & 0xFF) as u8)]
+#[display("({:?}, TAG: {:#04X})", header, payload.as_bytes()[0])]
 pub struct TxMessage {
     #[doc(hidden)]
     pub header: Header,
@@ -30,3 +30,5 @@ impl TxMessage {
         self.payload.as_mut_bytes()
     }
 }
+
+const _: () = assert!(size_of::<TxMessage>() == EC_OUTPUT_FRAME_SIZE);
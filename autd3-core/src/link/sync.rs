@@ -4,12 +4,28 @@ use super::{error::LinkError, RxMessage, TxMessage};
 
 /// A trait that provides the interface with the device.
 pub trait Link: Send {
+    /// Checks the environment before opening the link, e.g., whether the required drivers or services are available.
+    ///
+    /// This is called before [`Link::open`] and must not communicate with the devices. The default implementation does nothing.
+    fn preflight(&mut self, _: &Geometry) -> Result<(), LinkError> {
+        Ok(())
+    }
+
     /// Opens the link.
     fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError>;
 
     /// Closes the link.
     fn close(&mut self) -> Result<(), LinkError>;
 
+    /// Reconfigures the link for the geometry whose devices are added or removed.
+    ///
+    /// This is called after the number of devices is changed while the link is open. The default implementation returns an error, which means that the link does not support changing the devices.
+    fn reconfigure(&mut self, _: &Geometry) -> Result<(), LinkError> {
+        Err(LinkError::new(
+            "This link does not support changing the devices".to_owned(),
+        ))
+    }
+
     #[doc(hidden)]
     fn update(&mut self, _: &Geometry) -> Result<(), LinkError> {
         Ok(())
@@ -27,6 +43,10 @@ pub trait Link: Send {
 }
 
 impl Link for Box<dyn Link> {
+    fn preflight(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.as_mut().preflight(geometry)
+    }
+
     fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
         self.as_mut().open(geometry)
     }
@@ -35,6 +55,10 @@ impl Link for Box<dyn Link> {
         self.as_mut().close()
     }
 
+    fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.as_mut().reconfigure(geometry)
+    }
+
     fn update(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
         self.as_mut().update(geometry)
     }
@@ -0,0 +1,229 @@
+use crate::derive::*;
+
+// The maximum buffer size of the modulation in the firmware.
+const BUF_SIZE_MAX: usize = 32768;
+
+fn check_config(
+    lhs: Result<SamplingConfig, ModulationError>,
+    rhs: Result<SamplingConfig, ModulationError>,
+) -> Result<SamplingConfig, ModulationError> {
+    let (lhs, rhs) = (lhs?, rhs?);
+    if lhs != rhs {
+        return Err(ModulationError::new(format!(
+            "Sampling configurations must be the same ({:?} and {:?})",
+            lhs, rhs
+        )));
+    }
+    Ok(lhs)
+}
+
+fn check_size(len: usize) -> Result<(), ModulationError> {
+    if len > BUF_SIZE_MAX {
+        return Err(ModulationError::new(format!(
+            "The combined buffer size ({}) exceeds the maximum ({})",
+            len, BUF_SIZE_MAX
+        )));
+    }
+    Ok(())
+}
+
+fn combine(
+    lhs: Vec<u8>,
+    rhs: Vec<u8>,
+    f: impl Fn(u8, u8) -> u8,
+) -> Result<Vec<u8>, ModulationError> {
+    let gcd = |mut a: usize, mut b: usize| {
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    };
+    if lhs.is_empty() || rhs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let len = lhs.len() / gcd(lhs.len(), rhs.len()) * rhs.len();
+    check_size(len)?;
+    Ok(lhs
+        .iter()
+        .cycle()
+        .zip(rhs.iter().cycle())
+        .take(len)
+        .map(|(&l, &r)| f(l, r))
+        .collect())
+}
+
+/// [`Modulation`] to mix two [`Modulation`]s, which is created by `lhs + rhs`.
+///
+/// The data is the saturated sum of the two. If the lengths are different, both are repeated to the least common multiple of the lengths.
+/// The sampling configurations of both must be the same.
+#[derive(Modulation, Debug, Clone, Copy, PartialEq)]
+pub struct Mix<L: Modulation, R: Modulation> {
+    /// The left-hand side.
+    pub lhs: L,
+    /// The right-hand side.
+    pub rhs: R,
+}
+
+impl<L: Modulation, R: Modulation> Modulation for Mix<L, R> {
+    fn calc(self) -> Result<Vec<u8>, ModulationError> {
+        combine(self.lhs.calc()?, self.rhs.calc()?, u8::saturating_add)
+    }
+
+    fn sampling_config(&self) -> Result<SamplingConfig, ModulationError> {
+        check_config(self.lhs.sampling_config(), self.rhs.sampling_config())
+    }
+}
+
+/// [`Modulation`] to multiply two [`Modulation`]s, i.e., the ring modulation, which is created by `lhs * rhs`.
+///
+/// The data is the product of the two normalized by `0xFF`. If the lengths are different, both are repeated to the least common multiple of the lengths.
+/// The sampling configurations of both must be the same.
+#[derive(Modulation, Debug, Clone, Copy, PartialEq)]
+pub struct RingModulation<L: Modulation, R: Modulation> {
+    /// The left-hand side.
+    pub lhs: L,
+    /// The right-hand side.
+    pub rhs: R,
+}
+
+impl<L: Modulation, R: Modulation> Modulation for RingModulation<L, R> {
+    fn calc(self) -> Result<Vec<u8>, ModulationError> {
+        combine(self.lhs.calc()?, self.rhs.calc()?, |l, r| {
+            ((l as u16 * r as u16 + 0x7F) / 0xFF) as u8
+        })
+    }
+
+    fn sampling_config(&self) -> Result<SamplingConfig, ModulationError> {
+        check_config(self.lhs.sampling_config(), self.rhs.sampling_config())
+    }
+}
+
+/// [`Modulation`] to concatenate two [`Modulation`]s in time, which is created by [`Modulation::then`].
+///
+/// The sampling configurations of both must be the same, and the total length must not exceed the buffer size of the firmware.
+#[derive(Modulation, Debug, Clone, Copy, PartialEq)]
+pub struct Concat<F: Modulation, S: Modulation> {
+    /// The first [`Modulation`].
+    pub first: F,
+    /// The second [`Modulation`].
+    pub second: S,
+}
+
+impl<F: Modulation, S: Modulation> Modulation for Concat<F, S> {
+    fn calc(self) -> Result<Vec<u8>, ModulationError> {
+        let mut buffer = self.first.calc()?;
+        let second = self.second.calc()?;
+        check_size(buffer.len() + second.len())?;
+        buffer.extend(second);
+        Ok(buffer)
+    }
+
+    fn sampling_config(&self) -> Result<SamplingConfig, ModulationError> {
+        check_config(self.first.sampling_config(), self.second.sampling_config())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Modulation, Debug, Clone, PartialEq)]
+    struct TestModulation {
+        buffer: Vec<u8>,
+        config: SamplingConfig,
+    }
+
+    impl Modulation for TestModulation {
+        fn calc(self) -> Result<Vec<u8>, ModulationError> {
+            Ok(self.buffer)
+        }
+
+        fn sampling_config(&self) -> Result<SamplingConfig, ModulationError> {
+            Ok(self.config)
+        }
+    }
+
+    fn m(buffer: &[u8]) -> TestModulation {
+        TestModulation {
+            buffer: buffer.to_vec(),
+            config: SamplingConfig::DIV_10,
+        }
+    }
+
+    #[rstest::rstest]
+    #[case(vec![0x20, 0xFF, 0x30, 0xFF], &[0x10, 0x80, 0x20, 0x80], &[0x10, 0x80])]
+    #[case(vec![0x00, 0x21, 0x22, 0x10, 0x11, 0x32], &[0x00, 0x10], &[0x00, 0x11, 0x22])]
+    #[test]
+    fn mix(#[case] expect: Vec<u8>, #[case] lhs: &[u8], #[case] rhs: &[u8]) -> anyhow::Result<()> {
+        let m = m(lhs) + m(rhs);
+        assert_eq!(SamplingConfig::DIV_10, m.sampling_config()?);
+        assert_eq!(expect, m.calc()?);
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(vec![0x00, 0x80, 0xFF], &[0x00, 0xFF, 0xFF], &[0xFF, 0x80, 0xFF])]
+    #[case(vec![0x00, 0x40, 0x00, 0x80], &[0x00, 0xFF], &[0x80, 0x40, 0xFF, 0x80])]
+    #[test]
+    fn ring(#[case] expect: Vec<u8>, #[case] lhs: &[u8], #[case] rhs: &[u8]) -> anyhow::Result<()> {
+        assert_eq!(expect, (m(lhs) * m(rhs)).calc()?);
+        Ok(())
+    }
+
+    #[test]
+    fn then() -> anyhow::Result<()> {
+        let m = m(&[0x00, 0x01]).then(m(&[0x02])).then(m(&[0x03, 0x04]));
+        assert_eq!(SamplingConfig::DIV_10, m.sampling_config()?);
+        assert_eq!(vec![0x00, 0x01, 0x02, 0x03, 0x04], m.calc()?);
+        Ok(())
+    }
+
+    #[test]
+    fn nested() -> anyhow::Result<()> {
+        assert_eq!(
+            vec![0x82, 0x02],
+            (m(&[0xFF, 0x00]) * m(&[0x80]) + m(&[0x02])).calc()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn different_config() -> anyhow::Result<()> {
+        let other = TestModulation {
+            buffer: vec![0x00],
+            config: SamplingConfig::FREQ_MIN,
+        };
+        let expect = Err(ModulationError::new(format!(
+            "Sampling configurations must be the same ({:?} and {:?})",
+            SamplingConfig::DIV_10,
+            SamplingConfig::FREQ_MIN
+        )));
+        assert_eq!(expect, (m(&[0x00]) + other.clone()).sampling_config());
+        assert_eq!(expect, (m(&[0x00]) * other.clone()).sampling_config());
+        assert_eq!(expect, m(&[0x00]).then(other).sampling_config());
+        Ok(())
+    }
+
+    #[test]
+    fn too_large() {
+        assert_eq!(
+            Err(ModulationError::new(
+                "The combined buffer size (65534) exceeds the maximum (32768)".to_owned()
+            )),
+            (m(&[0x00; 2]) + m(&[0x00; 32767])).calc()
+        );
+        assert_eq!(
+            Err(ModulationError::new(
+                "The combined buffer size (32769) exceeds the maximum (32768)".to_owned()
+            )),
+            m(&[0x00; 2]).then(m(&[0x00; 32767])).calc()
+        );
+        assert_eq!(
+            Ok(BUF_SIZE_MAX),
+            m(&[0x00; 1])
+                .then(m(&[0x00; 32767]))
+                .calc()
+                .map(|b| b.len())
+        );
+    }
+}
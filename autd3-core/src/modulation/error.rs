@@ -6,6 +6,8 @@ use thiserror::Error;
 
 use crate::defined::Freq;
 
+use super::FreqRatio;
+
 #[derive(new, Error, Debug, Display, PartialEq, Clone)]
 #[display("{}", msg)]
 /// An error occurred during modulation calculation.
@@ -34,6 +36,12 @@ pub enum SamplingConfigError {
     /// Sampling frequency is out of range.
     #[error("Sampling frequency ({0:?}) is out of range ([{1:?}, {2:?}])")]
     SamplingFreqOutOfRangeF(Freq<f32>, Freq<f32>, Freq<f32>),
+    /// Invalid sampling frequency.
+    #[error("Sampling frequency ({0}) must divide the ultrasound frequency, the nearest achievable frequencies are {1:?} and {2:?}")]
+    SamplingFreqRatioInvalid(FreqRatio, Freq<f32>, Freq<f32>),
+    /// Sampling frequency is out of range.
+    #[error("Sampling frequency ({0}) is out of range ([{1:?}, {2:?}])")]
+    SamplingFreqRatioOutOfRange(FreqRatio, Freq<f32>, Freq<f32>),
     /// Sampling period is out of range.
     #[error("Sampling period ({0:?}) is out of range ([{1:?}, {2:?}])")]
     SamplingPeriodOutOfRange(Duration, Duration, Duration),
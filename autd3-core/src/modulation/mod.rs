@@ -1,10 +1,14 @@
+#[cfg(feature = "derive")]
+mod combinator;
 mod error;
 mod sampling_config;
 
 use std::sync::Arc;
 
+#[cfg(feature = "derive")]
+pub use combinator::{Concat, Mix, RingModulation};
 pub use error::{ModulationError, SamplingConfigError};
-pub use sampling_config::SamplingConfig;
+pub use sampling_config::{FreqRatio, SamplingConfig};
 
 use crate::datagram::{LoopBehavior, Segment, TransitionMode};
 
@@ -19,6 +23,19 @@ pub trait Modulation: std::fmt::Debug {
 
     /// The sampling configuration.
     fn sampling_config(&self) -> Result<SamplingConfig, ModulationError>;
+
+    /// Concatenates the [`Modulation`] after this one. See [`Concat`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+    #[cfg(feature = "derive")]
+    fn then<M: Modulation>(self, next: M) -> Concat<Self, M>
+    where
+        Self: Sized,
+    {
+        Concat {
+            first: self,
+            second: next,
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -1,14 +1,12 @@
 use std::{convert::Infallible, fmt::Debug, num::NonZeroU16};
 
-use crate::{
-    defined::{ultrasound_freq, Freq, Hz},
-    utils::float::is_integer,
-};
+use crate::defined::{ultrasound_freq, Freq, Hz};
 
 use super::error::SamplingConfigError;
 
 /// The configuration for sampling.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct SamplingConfig {
     /// The division number of the sampling frequency.
@@ -52,7 +50,7 @@ impl IntoSamplingConfig for Freq<u32> {
                 ultrasound_freq(),
             ));
         }
-        if ultrasound_freq().hz() % self.hz() != 0 {
+        if !ultrasound_freq().hz().is_multiple_of(self.hz()) {
             return Err(Self::Error::SamplingFreqInvalid(self));
         }
         Ok(SamplingConfig {
@@ -72,11 +70,69 @@ impl IntoSamplingConfig for Freq<f32> {
             ));
         }
         let division = ultrasound_freq().hz() as f32 / self.hz();
-        if !is_integer(division as _) {
+        // The rounding error of `f32` grows with the division, so the tolerance must be relative to it.
+        // A few ULPs are allowed because the frequency may be the result of some arithmetic, e.g., that of STM.
+        if (division - division.round()).abs() > division * 4. * f32::EPSILON {
             return Err(Self::Error::SamplingFreqInvalidF(self));
         }
         Ok(SamplingConfig {
-            division: NonZeroU16::new(division as _).unwrap(),
+            division: NonZeroU16::new(division.round() as _).unwrap(),
+        })
+    }
+}
+
+/// A sampling frequency represented exactly as the ratio `num / den` Hz. See [`SamplingConfig::ratio`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FreqRatio {
+    /// The numerator of the frequency in Hz.
+    pub num: u64,
+    /// The denominator of the frequency in Hz.
+    pub den: u64,
+}
+
+impl FreqRatio {
+    /// Gets the frequency.
+    pub fn freq(&self) -> Freq<f32> {
+        (self.num as f64 / self.den as f64) as f32 * Hz
+    }
+}
+
+impl std::fmt::Display for FreqRatio {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} Hz", self.num, self.den)
+    }
+}
+
+impl IntoSamplingConfig for FreqRatio {
+    type Error = SamplingConfigError;
+    fn into_sampling_config(self) -> Result<SamplingConfig, Self::Error> {
+        let num = self.num as u128;
+        let den = self.den as u128;
+        // The division is `ultrasound_freq * den / num`, which must be in [1, u16::MAX].
+        let n = ultrasound_freq().hz() as u128 * den;
+        if num == 0 || den == 0 || n < num || num * (u16::MAX as u128) < n {
+            return Err(Self::Error::SamplingFreqRatioOutOfRange(
+                self,
+                SamplingConfig::FREQ_MIN.freq(),
+                SamplingConfig::FREQ_MAX.freq(),
+            ));
+        }
+        let division = (n / num) as u16;
+        if !n.is_multiple_of(num) {
+            return Err(Self::Error::SamplingFreqRatioInvalid(
+                self,
+                SamplingConfig {
+                    division: NonZeroU16::new(division + 1).unwrap(),
+                }
+                .freq(),
+                SamplingConfig {
+                    division: NonZeroU16::new(division).unwrap(),
+                }
+                .freq(),
+            ));
+        }
+        Ok(SamplingConfig {
+            division: NonZeroU16::new(division).unwrap(),
         })
     }
 }
@@ -96,7 +152,10 @@ impl IntoSamplingConfig for std::time::Duration {
                 self, period_min, period_max,
             ));
         }
-        if self.as_nanos() % ultrasound_period().as_nanos() != 0 {
+        if !self
+            .as_nanos()
+            .is_multiple_of(ultrasound_period().as_nanos())
+        {
             return Err(Self::Error::SamplingPeriodInvalid(self));
         }
         Ok(SamplingConfig {
@@ -181,6 +240,17 @@ impl SamplingConfig {
         value.into_sampling_config()
     }
 
+    /// Creates a new [`SamplingConfig`] with the sampling frequency of exactly `num / den` Hz.
+    ///
+    /// Unlike [`Freq<f32>`], the frequency has no rounding error, e.g., `SamplingConfig::ratio(40000, 3)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SamplingConfigError::SamplingFreqRatioInvalid`] with the nearest achievable frequencies below and above the requested one if it does not divide the ultrasound frequency.
+    pub fn ratio(num: u64, den: u64) -> Result<Self, SamplingConfigError> {
+        Self::new(FreqRatio { num, den })
+    }
+
     /// Creates a new [`SamplingConfig`] with the nearest frequency or period value of the possible values.
     pub fn new_nearest(value: impl IntoSamplingConfigNearest) -> Self {
         value.into_sampling_config_nearest()
@@ -215,6 +285,14 @@ impl TryInto<SamplingConfig> for Freq<f32> {
     }
 }
 
+impl TryInto<SamplingConfig> for FreqRatio {
+    type Error = SamplingConfigError;
+
+    fn try_into(self) -> Result<SamplingConfig, Self::Error> {
+        SamplingConfig::new(self)
+    }
+}
+
 #[cfg(not(feature = "dynamic_freq"))]
 impl TryInto<SamplingConfig> for std::time::Duration {
     type Error = SamplingConfigError;
@@ -274,6 +352,33 @@ mod tests {
         assert_eq!(expect, SamplingConfig::new(value).map(|c| c.division.get()));
     }
 
+    #[rstest::rstest]
+    #[test]
+    #[case(Ok(1), 40000, 1)]
+    #[case(Ok(3), 40000, 3)]
+    #[case(Ok(6), 20000, 3)]
+    #[case(Ok(u16::MAX), 40000, u16::MAX as _)]
+    #[case(Err(SamplingConfigError::SamplingFreqRatioInvalid(FreqRatio { num: 6000, den: 1 }, 40000. / 7. * Hz, 40000. / 6. * Hz)), 6000, 1)]
+    #[case(Err(SamplingConfigError::SamplingFreqRatioInvalid(FreqRatio { num: 120000, den: 3 * u16::MAX as u64 - 1 }, 40000. / u16::MAX as f32 * Hz, 40000. / (u16::MAX - 1) as f32 * Hz)), 120000, 3 * u16::MAX as u64 - 1)]
+    #[case(Err(SamplingConfigError::SamplingFreqRatioOutOfRange(FreqRatio { num: 0, den: 1 }, 40000. / u16::MAX as f32 * Hz, 40000. * Hz)), 0, 1)]
+    #[case(Err(SamplingConfigError::SamplingFreqRatioOutOfRange(FreqRatio { num: 1, den: 0 }, 40000. / u16::MAX as f32 * Hz, 40000. * Hz)), 1, 0)]
+    #[case(Err(SamplingConfigError::SamplingFreqRatioOutOfRange(FreqRatio { num: 40001, den: 1 }, 40000. / u16::MAX as f32 * Hz, 40000. * Hz)), 40001, 1)]
+    #[case(Err(SamplingConfigError::SamplingFreqRatioOutOfRange(FreqRatio { num: 40000, den: u16::MAX as u64 + 1 }, 40000. / u16::MAX as f32 * Hz, 40000. * Hz)), 40000, u16::MAX as u64 + 1)]
+    fn ratio(#[case] expect: Result<u16, SamplingConfigError>, #[case] num: u64, #[case] den: u64) {
+        assert_eq!(
+            expect,
+            SamplingConfig::ratio(num, den).map(|c| c.division.get())
+        );
+    }
+
+    #[test]
+    fn ratio_invalid_message() {
+        assert_eq!(
+            "Sampling frequency (6000/1 Hz) must divide the ultrasound frequency, the nearest achievable frequencies are 5714.2856 Hz and 6666.6665 Hz",
+            SamplingConfig::ratio(6000, 1).unwrap_err().to_string()
+        );
+    }
+
     #[rstest::rstest]
     #[test]
     #[case(Ok(40000. * Hz), NonZeroU16::MIN)]
@@ -342,4 +447,52 @@ mod tests {
     fn from_period_nearest(#[case] expected: u16, #[case] p: Duration) {
         assert_eq!(expected, SamplingConfig::new_nearest(p).division.get());
     }
+
+    proptest::proptest! {
+        #[test]
+        fn freq_roundtrip(division in 1..=u16::MAX) {
+            let config = SamplingConfig::new(division).unwrap();
+            proptest::prop_assert_eq!(Ok(config), SamplingConfig::new(config.freq()));
+            proptest::prop_assert_eq!(config, SamplingConfig::new_nearest(config.freq()));
+        }
+
+        #[cfg(not(feature = "dynamic_freq"))]
+        #[test]
+        fn period_roundtrip(division in 1..=u16::MAX) {
+            let config = SamplingConfig::new(division).unwrap();
+            proptest::prop_assert_eq!(Ok(config), SamplingConfig::new(config.period()));
+            proptest::prop_assert_eq!(config, SamplingConfig::new_nearest(config.period()));
+        }
+
+        #[test]
+        fn freq_f32_nearest_error(freq in (ultrasound_freq().hz() as f32 / u16::MAX as f32)..=ultrasound_freq().hz() as f32) {
+            let ideal = ultrasound_freq().hz() as f32 / freq;
+            let division = SamplingConfig::new_nearest(freq * Hz).division.get() as f32;
+            proptest::prop_assert!((division - ideal).abs() <= 0.5 + ideal * f32::EPSILON);
+        }
+
+        #[test]
+        fn freq_u32_nearest_error(freq in 1..=ultrasound_freq().hz()) {
+            let ideal = ultrasound_freq().hz() as f64 / freq as f64;
+            let division = SamplingConfig::new_nearest(freq * Hz).division.get() as f64;
+            proptest::prop_assert!((division - ideal).abs() <= 0.5);
+        }
+
+        #[cfg(not(feature = "dynamic_freq"))]
+        #[test]
+        fn period_nearest_error(nanos in ultrasound_period().as_nanos() as u64..=u16::MAX as u64 * ultrasound_period().as_nanos() as u64) {
+            let period = SamplingConfig::new_nearest(Duration::from_nanos(nanos)).period();
+            proptest::prop_assert!(period.as_nanos().abs_diff(nanos as u128) <= ultrasound_period().as_nanos() / 2);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() -> anyhow::Result<()> {
+        let json = serde_json::to_string(&SamplingConfig::DIV_10)?;
+        assert_eq!(r#"{"division":10}"#, json);
+        assert_eq!(SamplingConfig::DIV_10, serde_json::from_str(&json)?);
+        assert!(serde_json::from_str::<SamplingConfig>(r#"{"division":0}"#).is_err());
+        Ok(())
+    }
 }
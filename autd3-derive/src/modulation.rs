@@ -5,8 +5,8 @@ pub(crate) fn impl_mod_macro(input: syn::DeriveInput) -> TokenStream {
     let name = &input.ident;
     let generics = &input.generics;
 
-    let lifetimes = generics.lifetimes();
-    let type_params = generics.type_params();
+    let lifetimes = generics.lifetimes().collect::<Vec<_>>();
+    let type_params = generics.type_params().collect::<Vec<_>>();
     let (_, ty_generics, where_clause) = generics.split_for_impl();
     let datagram = quote! {
         impl <#(#lifetimes,)* #(#type_params,)* > DatagramL for #name #ty_generics #where_clause {
@@ -32,8 +32,27 @@ pub(crate) fn impl_mod_macro(input: syn::DeriveInput) -> TokenStream {
         }
     };
 
+    let ops = quote! {
+        impl <#(#lifetimes,)* #(#type_params,)* __R: Modulation> std::ops::Add<__R> for #name #ty_generics #where_clause {
+            type Output = Mix<Self, __R>;
+
+            fn add(self, rhs: __R) -> Self::Output {
+                Mix { lhs: self, rhs }
+            }
+        }
+
+        impl <#(#lifetimes,)* #(#type_params,)* __R: Modulation> std::ops::Mul<__R> for #name #ty_generics #where_clause {
+            type Output = RingModulation<Self, __R>;
+
+            fn mul(self, rhs: __R) -> Self::Output {
+                RingModulation { lhs: self, rhs }
+            }
+        }
+    };
+
     let generator = quote! {
         #datagram
+        #ops
     };
     generator.into()
 }
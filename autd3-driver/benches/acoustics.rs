@@ -0,0 +1,43 @@
+use autd3_core::acoustics::{directivity::Sphere, pressure};
+use autd3_driver::{
+    autd3_device::AUTD3,
+    defined::rad,
+    firmware::fpga::{Drive, EmitIntensity, Phase},
+    geometry::{IntoDevice, Point3},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+fn pressure_focus(c: &mut Criterion) {
+    let mut group = c.benchmark_group("autd3/acoustics/pressure");
+
+    [1, 10, 100].iter().for_each(|&size| {
+        let device = AUTD3::default().into_device(0);
+        let focus = Point3::new(90., 70., 150.);
+        let drives = device
+            .iter()
+            .map(|tr| Drive {
+                phase: Phase::from(-(focus - tr.position()).norm() * device.wavenumber() * rad),
+                intensity: EmitIntensity::MAX,
+            })
+            .collect::<Vec<_>>();
+        let targets = (0..size)
+            .map(|i| Point3::new(i as f32, 70., 150.))
+            .collect::<Vec<_>>();
+        group.bench_with_input(
+            BenchmarkId::new("pressure", size),
+            &targets,
+            |b, targets| {
+                b.iter(|| {
+                    targets.iter().for_each(|target| {
+                        black_box(pressure::<Sphere>(&device, &drives, black_box(target)));
+                    })
+                })
+            },
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, pressure_focus);
+criterion_main!(benches);
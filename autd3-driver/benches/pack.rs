@@ -0,0 +1,168 @@
+use std::num::NonZeroU16;
+
+use autd3_core::derive::*;
+use autd3_driver::{
+    autd3_device::AUTD3,
+    datagram::{Datagram, FociSTM, GainSTM, GainSTMOption},
+    firmware::{
+        cpu::TxMessage,
+        fpga::{Drive, EmitIntensity, Phase, SamplingConfig},
+        operation::OperationHandler,
+    },
+    geometry::{Device, Geometry, IntoDevice, Point3, Transducer},
+};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use zerocopy::FromZeros;
+
+pub fn generate_geometry(size: usize) -> Geometry {
+    Geometry::new(
+        (0..size)
+            .map(move |i| {
+                AUTD3 {
+                    pos: Point3::new(
+                        (i % 8) as f32 * AUTD3::DEVICE_WIDTH,
+                        (i / 8) as f32 * AUTD3::DEVICE_HEIGHT,
+                        0.,
+                    ),
+                    ..Default::default()
+                }
+                .into_device(i as _)
+            })
+            .collect(),
+    )
+}
+
+fn foci_stm(size: usize) -> FociSTM<1, Vec<Point3>, SamplingConfig> {
+    FociSTM {
+        foci: (0..size)
+            .map(|i| Point3::new(90. + i as f32 * 0.01, 70., 150.))
+            .collect(),
+        config: SamplingConfig {
+            division: NonZeroU16::MIN,
+        },
+    }
+}
+
+#[derive(Gain, Clone, PartialEq, Debug)]
+struct Random {
+    seed: u8,
+}
+
+struct Impl {
+    seed: u8,
+}
+
+impl GainCalculator for Impl {
+    fn calc(&self, tr: &Transducer) -> Drive {
+        Drive {
+            phase: Phase(self.seed.wrapping_add(tr.idx() as u8)),
+            intensity: EmitIntensity(self.seed.wrapping_mul(tr.idx() as u8)),
+        }
+    }
+}
+
+impl GainCalculatorGenerator for Random {
+    type Calculator = Impl;
+
+    fn generate(&mut self, _: &Device) -> Self::Calculator {
+        Impl { seed: self.seed }
+    }
+}
+
+impl Gain for Random {
+    type G = Random;
+
+    fn init(self) -> Result<Self::G, GainError> {
+        Ok(self)
+    }
+}
+
+fn pack_foci_stm(c: &mut Criterion) {
+    let mut group = c.benchmark_group("autd3/pack/foci_stm");
+
+    const FOCI_NUM: usize = 100;
+    [1, 16, 64].iter().for_each(|&size| {
+        group.throughput(Throughput::Elements(size as u64));
+        [false, true].iter().for_each(|&parallel| {
+            group.bench_with_input(
+                BenchmarkId::new(if parallel { "parallel" } else { "serial" }, size),
+                &generate_geometry(size),
+                |b, geometry| {
+                    let mut tx = vec![TxMessage::new_zeroed(); size];
+                    b.iter_batched(
+                        || {
+                            let generator = foci_stm(FOCI_NUM)
+                                .operation_generator(geometry, parallel)
+                                .unwrap();
+                            OperationHandler::generate(generator, geometry)
+                        },
+                        |mut operations| {
+                            while !OperationHandler::is_done(&operations) {
+                                OperationHandler::pack(
+                                    &mut operations,
+                                    geometry,
+                                    &mut tx,
+                                    parallel,
+                                )
+                                .unwrap();
+                            }
+                        },
+                        BatchSize::SmallInput,
+                    )
+                },
+            );
+        });
+    });
+    group.finish();
+}
+
+fn pack_gain_stm(c: &mut Criterion) {
+    let mut group = c.benchmark_group("autd3/pack/gain_stm");
+
+    const GAIN_NUM: usize = 100;
+    [1, 16, 64].iter().for_each(|&size| {
+        group.throughput(Throughput::Elements((size * GAIN_NUM) as u64));
+        [false, true].iter().for_each(|&parallel| {
+            group.bench_with_input(
+                BenchmarkId::new(if parallel { "parallel" } else { "serial" }, size),
+                &generate_geometry(size),
+                |b, geometry| {
+                    let mut tx = vec![TxMessage::new_zeroed(); size];
+                    b.iter_batched(
+                        || {
+                            let generator = GainSTM {
+                                gains: (0..GAIN_NUM)
+                                    .map(|i| Random { seed: i as u8 })
+                                    .collect::<Vec<_>>(),
+                                config: SamplingConfig {
+                                    division: NonZeroU16::MIN,
+                                },
+                                option: GainSTMOption::default(),
+                            }
+                            .operation_generator(geometry, parallel)
+                            .unwrap();
+                            OperationHandler::generate(generator, geometry)
+                        },
+                        |mut operations| {
+                            while !OperationHandler::is_done(&operations) {
+                                OperationHandler::pack(
+                                    &mut operations,
+                                    geometry,
+                                    &mut tx,
+                                    parallel,
+                                )
+                                .unwrap();
+                            }
+                        },
+                        BatchSize::SmallInput,
+                    )
+                },
+            );
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, pack_foci_stm, pack_gain_stm);
+criterion_main!(benches);
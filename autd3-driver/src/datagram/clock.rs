@@ -1,6 +1,34 @@
 use std::convert::Infallible;
 
-use crate::{datagram::*, defined::ultrasound_freq, firmware::operation::ConfigureClockOp};
+use crate::{
+    datagram::*,
+    defined::{ultrasound_freq, Freq},
+    error::AUTDDriverError,
+    firmware::operation::{mmcm_params, ConfigureClockOp},
+};
+
+/// The ultrasound frequency validated against the constraints of the FPGA clock.
+///
+/// The FPGA clock, which is 256 times the ultrasound frequency, must be a multiple of 125 Hz and achievable by the clock generator of the FPGA.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UltrasoundFreq(Freq<u32>);
+
+impl UltrasoundFreq {
+    /// Creates a new [`UltrasoundFreq`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AUTDDriverError::InvalidFrequency`] if the FPGA clock cannot be configured for `freq`.
+    pub fn new(freq: Freq<u32>) -> Result<Self, AUTDDriverError> {
+        mmcm_params(freq)?;
+        Ok(Self(freq))
+    }
+
+    /// The ultrasound frequency.
+    pub const fn freq(&self) -> Freq<u32> {
+        self.0
+    }
+}
 
 #[derive(Default, Debug)]
 #[doc(hidden)]
@@ -31,3 +59,22 @@ impl Datagram for ConfigureFPGAClock {
         Ok(ConfigureClockOpGenerator {})
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::defined::Hz;
+
+    #[rstest::rstest]
+    #[test]
+    #[case(Ok(40000 * Hz), 40000 * Hz)]
+    #[case(Ok(41000 * Hz), 41000 * Hz)]
+    #[case(Err(AUTDDriverError::InvalidFrequency(1 * Hz)), 1 * Hz)]
+    #[case(Err(AUTDDriverError::InvalidFrequency(125 * Hz)), 125 * Hz)]
+    fn ultrasound_freq(
+        #[case] expect: Result<Freq<u32>, AUTDDriverError>,
+        #[case] freq: Freq<u32>,
+    ) {
+        assert_eq!(expect, UltrasoundFreq::new(freq).map(|f| f.freq()));
+    }
+}
@@ -14,6 +14,7 @@ mod pulse_width_encoder;
 mod reads_fpga_state;
 mod segment;
 mod silencer;
+#[cfg(feature = "stm")]
 mod stm;
 mod synchronize;
 mod tuple;
@@ -22,12 +23,13 @@ mod with_segment;
 
 #[doc(inline)]
 pub use super::firmware::operation::SwapSegment;
+#[cfg(feature = "stm")]
 #[doc(inline)]
 pub use super::firmware::operation::{ControlPoint, ControlPoints};
 pub use boxed::{BoxedDatagram, IntoBoxedDatagram};
 pub use clear::Clear;
 #[cfg(feature = "dynamic_freq")]
-pub use clock::ConfigureFPGAClock;
+pub use clock::{ConfigureFPGAClock, UltrasoundFreq};
 #[doc(hidden)]
 pub use cpu_gpio_out::{CpuGPIO, CpuGPIOPort};
 pub use debug::DebugSettings;
@@ -42,9 +44,11 @@ pub use reads_fpga_state::ReadsFPGAState;
 #[cfg(not(feature = "dynamic_freq"))]
 pub use silencer::FixedCompletionTime;
 pub use silencer::{FixedCompletionSteps, FixedUpdateRate, Silencer};
+#[cfg(feature = "stm")]
 pub use stm::{
     FociSTM, FociSTMGenerator, FociSTMIterator, FociSTMIteratorGenerator, GainSTM,
-    GainSTMGenerator, GainSTMIterator, GainSTMIteratorGenerator, GainSTMOption, STMConfig,
+    GainSTMGenerator, GainSTMIterator, GainSTMIteratorGenerator, GainSTMOption, PerDeviceFoci,
+    STMConfig, StartOffset,
 };
 pub use with_loop_behavior::WithLoopBehavior;
 pub use with_segment::WithSegment;
@@ -58,7 +62,10 @@ use crate::{
     geometry::{Device, Geometry},
 };
 
-use crate::{error::AUTDDriverError, firmware::operation::OperationGenerator};
+use crate::firmware::operation::OperationGenerator;
+
+#[cfg(feature = "stm")]
+use crate::error::AUTDDriverError;
 
 #[cfg(test)]
 pub(crate) mod tests {
@@ -30,7 +30,10 @@ impl<
 
 /// Boxed [`Modulation`].
 ///
-/// This provides the ability to wrap any [`Modulation`] in a common type.
+/// Because [`Modulation::calc`] consumes `self`, [`Modulation`] cannot simply be wrapped in a [`Box`] like `Box<dyn Modulation>`.
+/// [`BoxedModulation`] provides the ability to wrap any [`Modulation`] in a common type, e.g., to send different modulations to each group of devices, or combined with [`BoxedGain`] in a tuple.
+///
+/// [`BoxedGain`]: crate::datagram::BoxedGain
 #[derive(Modulation)]
 pub struct BoxedModulation {
     m: Box<dyn DModulation>,
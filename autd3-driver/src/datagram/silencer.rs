@@ -16,6 +16,25 @@ use super::Datagram;
 pub trait SilencerConfig: std::fmt::Debug + Clone + Copy {}
 impl SilencerConfig for () {}
 
+/// Expands the given items only if [`FixedCompletionTime`] is available.
+///
+/// The `dynamic_freq` feature of this crate can be enabled by other crates independently of that of the dependent crates, so the dependent crates use this macro instead of their own `cfg` to refer to [`FixedCompletionTime`].
+#[doc(hidden)]
+#[cfg(not(feature = "dynamic_freq"))]
+#[macro_export]
+macro_rules! with_fixed_completion_time {
+    ($($item:item)*) => {
+        $($item)*
+    };
+}
+
+#[doc(hidden)]
+#[cfg(feature = "dynamic_freq")]
+#[macro_export]
+macro_rules! with_fixed_completion_time {
+    ($($item:item)*) => {};
+}
+
 #[cfg(not(feature = "dynamic_freq"))]
 /// To configure the silencer by the completion time.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -199,4 +199,46 @@ mod tests {
         );
         Ok(())
     }
+
+    #[cfg(not(feature = "dynamic_freq"))]
+    #[rstest::rstest]
+    #[test]
+    #[case(Ok((Duration::from_millis(1), vec![1, 2, 1])), 3, vec![1000, 2000, 1000])]
+    #[case(Ok((Duration::from_micros(250), vec![4, 1])), 2, vec![1000, 250])]
+    #[case(Ok((Duration::from_millis(1000), vec![2, 2])), 2, vec![2_000_000, 2_000_000])]
+    #[case(Err(AUTDDriverError::InvalidGainSTMDwell(Duration::ZERO)), 2, vec![1000, 0])]
+    #[case(Err(AUTDDriverError::InvalidGainSTMDwell(Duration::from_micros(30))), 2, vec![1000, 30])]
+    #[case(Err(AUTDDriverError::GainSTMSizeOutOfRange(1026)), 2, vec![1000, 1_025_000])]
+    #[case(Err(AUTDDriverError::GainSTMSizeOutOfRange(1)), 1, vec![1000])]
+    #[case(Err(AUTDDriverError::GainSTMDwellLengthMismatch(2, 3)), 2, vec![1000, 1000, 1000])]
+    fn with_dwell(
+        #[case] expect: Result<(Duration, Vec<usize>), AUTDDriverError>,
+        #[case] n: usize,
+        #[case] dwell_us: Vec<u64>,
+    ) {
+        let gains = (0..n)
+            .map(|i| {
+                let mut g = TestGain::null();
+                g.data.insert(i, vec![]);
+                g
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            expect,
+            GainSTM::with_dwell(
+                gains,
+                dwell_us.into_iter().map(Duration::from_micros).collect(),
+                GainSTMOption::default()
+            )
+            .map(|stm| {
+                (
+                    stm.config.period(),
+                    stm.gains
+                        .chunk_by(|a, b| a.data.keys().eq(b.data.keys()))
+                        .map(|c| c.len())
+                        .collect(),
+                )
+            })
+        );
+    }
 }
@@ -107,6 +107,73 @@ impl<T: GainSTMGenerator> GainSTM<T, Duration> {
     }
 }
 
+#[cfg(not(feature = "dynamic_freq"))]
+impl<G: autd3_core::gain::Gain + Clone> GainSTM<Vec<G>, SamplingConfig> {
+    /// Creates a [`GainSTM`] in which the `i`-th gain is output for `dwell[i]`.
+    ///
+    /// The gains are repeated internally, i.e., the sampling period is the greatest common divisor of the dwell times (divided further if it exceeds the maximum sampling period), and each gain is repeated by its dwell time divided by the sampling period.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AUTDDriverError::GainSTMDwellLengthMismatch`] if the lengths of `gains` and `dwell` are different, [`AUTDDriverError::InvalidGainSTMDwell`] if any dwell time is not a positive multiple of the ultrasound period, and [`AUTDDriverError::GainSTMSizeOutOfRange`] if the total number of the repeated gains is out of range.
+    pub fn with_dwell(
+        gains: Vec<G>,
+        dwell: Vec<Duration>,
+        option: GainSTMOption,
+    ) -> Result<Self, AUTDDriverError> {
+        use crate::{
+            defined::ultrasound_period,
+            firmware::fpga::{GAIN_STM_BUF_SIZE_MAX, STM_BUF_SIZE_MIN},
+        };
+
+        if gains.len() != dwell.len() {
+            return Err(AUTDDriverError::GainSTMDwellLengthMismatch(
+                gains.len(),
+                dwell.len(),
+            ));
+        }
+        let period = ultrasound_period().as_nanos();
+        let counts = dwell
+            .iter()
+            .map(|d| {
+                if d.is_zero() || !d.as_nanos().is_multiple_of(period) {
+                    return Err(AUTDDriverError::InvalidGainSTMDwell(*d));
+                }
+                Ok(d.as_nanos() / period)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let gcd = counts
+            .iter()
+            .copied()
+            .reduce(|mut a, mut b| {
+                while b != 0 {
+                    (a, b) = (b, a % b);
+                }
+                a
+            })
+            .unwrap_or(1);
+        let division = (gcd.div_ceil(u16::MAX as u128)..=gcd)
+            .find(|&k| gcd.is_multiple_of(k))
+            .map_or(1, |k| gcd / k);
+
+        let size = counts.iter().map(|&c| c / division).sum::<u128>() as usize;
+        if !(STM_BUF_SIZE_MIN..=GAIN_STM_BUF_SIZE_MAX).contains(&size) {
+            return Err(AUTDDriverError::GainSTMSizeOutOfRange(size));
+        }
+
+        Ok(Self {
+            gains: gains
+                .into_iter()
+                .zip(counts)
+                .flat_map(|(g, c)| std::iter::repeat_n(g, (c / division) as usize))
+                .collect(),
+            config: SamplingConfig::new(ultrasound_period() * division as u32)?,
+            option,
+        })
+    }
+}
+
 impl<T: GainSTMGenerator, C: Into<STMConfig> + Copy> GainSTM<T, C> {
     /// The sampling configuration of the STM.
     pub fn sampling_config(&self) -> Result<SamplingConfig, AUTDDriverError> {
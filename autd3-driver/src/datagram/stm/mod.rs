@@ -1,9 +1,13 @@
 mod foci;
 mod gain;
+mod offset;
+mod per_device;
 mod sampling_config;
 
 pub use foci::{FociSTM, FociSTMGenerator, FociSTMIterator, FociSTMIteratorGenerator};
 pub use gain::{
     GainSTM, GainSTMGenerator, GainSTMIterator, GainSTMIteratorGenerator, GainSTMOption,
 };
+pub use offset::StartOffset;
+pub use per_device::PerDeviceFoci;
 pub use sampling_config::STMConfig;
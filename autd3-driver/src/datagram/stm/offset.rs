@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use autd3_core::gain::{BitVec, GainCalculatorGenerator, GainError};
+
+use crate::{
+    error::AUTDDriverError,
+    firmware::operation::ControlPoints,
+    geometry::{Device, Geometry},
+};
+
+use super::{
+    FociSTMGenerator, FociSTMIterator, FociSTMIteratorGenerator, GainSTMGenerator, GainSTMIterator,
+    GainSTMIteratorGenerator,
+};
+
+/// A sequence of [`FociSTM`] or [`GainSTM`] that starts from a different index for each device.
+///
+/// The STM index is synchronized among the devices and the firmware does not support setting the initial index, so the sequence is rotated for each device instead, i.e., the device outputs the `(i + offset(dev)) % len`-th element at the `i`-th index.
+/// Since the data is sent to each device individually, this does not increase the amount of data sent.
+///
+/// This is useful for wave-like effects across tiled devices.
+///
+/// # Examples
+///
+/// ```
+/// # use autd3_driver::{datagram::*, geometry::Point3, defined::Hz};
+/// let stm = FociSTM {
+///     foci: StartOffset {
+///         inner: (0..100)
+///             .map(|i| Point3::new(i as f32, 0., 150.))
+///             .collect::<Vec<_>>(),
+///         offset: |dev| dev.idx() * 10,
+///     },
+///     config: 1. * Hz,
+/// };
+/// ```
+///
+/// [`FociSTM`]: super::FociSTM
+/// [`GainSTM`]: super::GainSTM
+#[derive(Clone)]
+pub struct StartOffset<T, F: Fn(&Device) -> usize> {
+    /// The original sequence.
+    pub inner: T,
+    /// The start index for each device.
+    pub offset: F,
+}
+
+impl<T: std::fmt::Debug, F: Fn(&Device) -> usize> std::fmt::Debug for StartOffset<T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StartOffset")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+pub struct StartOffsetIteratorGenerator<G, F: Fn(&Device) -> usize> {
+    inner: G,
+    offset: F,
+    size: usize,
+}
+
+impl<G: std::fmt::Debug, F: Fn(&Device) -> usize> std::fmt::Debug
+    for StartOffsetIteratorGenerator<G, F>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StartOffsetIteratorGenerator")
+            .field("inner", &self.inner)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+impl<G, F: Fn(&Device) -> usize> StartOffsetIteratorGenerator<G, F> {
+    fn rotate<T>(&self, device: &Device, mut items: Vec<T>) -> std::vec::IntoIter<T> {
+        if !items.is_empty() {
+            let len = items.len();
+            items.rotate_left((self.offset)(device) % len);
+        }
+        items.into_iter()
+    }
+}
+
+pub struct StartOffsetIterator<T> {
+    items: std::vec::IntoIter<T>,
+}
+
+impl<const N: usize> FociSTMIterator<N> for StartOffsetIterator<ControlPoints<N>> {
+    fn next(&mut self) -> ControlPoints<N> {
+        self.items.next().unwrap()
+    }
+}
+
+impl<const N: usize, G: FociSTMIteratorGenerator<N>, F: Fn(&Device) -> usize>
+    FociSTMIteratorGenerator<N> for StartOffsetIteratorGenerator<G, F>
+{
+    type Iterator = StartOffsetIterator<ControlPoints<N>>;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        let mut iter = self.inner.generate(device);
+        let items = (0..self.size).map(|_| iter.next()).collect();
+        StartOffsetIterator {
+            items: self.rotate(device, items),
+        }
+    }
+}
+
+impl<const N: usize, T: FociSTMGenerator<N>, F: Fn(&Device) -> usize> FociSTMGenerator<N>
+    for StartOffset<T, F>
+{
+    type T = StartOffsetIteratorGenerator<T::T, F>;
+
+    fn init(self) -> Result<Self::T, AUTDDriverError> {
+        let size = self.inner.len();
+        Ok(StartOffsetIteratorGenerator {
+            inner: self.inner.init()?,
+            offset: self.offset,
+            size,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+pub struct StartOffsetGainSTMIterator<T> {
+    items: std::vec::IntoIter<T>,
+}
+
+impl<C: autd3_core::gain::GainCalculator> GainSTMIterator for StartOffsetGainSTMIterator<C> {
+    type Calculator = C;
+
+    fn next(&mut self) -> Option<Self::Calculator> {
+        self.items.next()
+    }
+}
+
+impl<G: GainSTMIteratorGenerator, F: Fn(&Device) -> usize> GainSTMIteratorGenerator
+    for StartOffsetIteratorGenerator<G, F>
+{
+    type Gain = G::Gain;
+    type Iterator = StartOffsetGainSTMIterator<<G::Gain as GainCalculatorGenerator>::Calculator>;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        let mut iter = self.inner.generate(device);
+        let items = std::iter::from_fn(|| iter.next()).take(self.size).collect();
+        StartOffsetGainSTMIterator {
+            items: self.rotate(device, items),
+        }
+    }
+}
+
+impl<T: GainSTMGenerator, F: Fn(&Device) -> usize> GainSTMGenerator for StartOffset<T, F> {
+    type T = StartOffsetIteratorGenerator<T::T, F>;
+
+    fn init(
+        self,
+        geometry: &Geometry,
+        filter: Option<&HashMap<usize, BitVec>>,
+        parallel: bool,
+    ) -> Result<Self::T, GainError> {
+        let size = self.inner.len();
+        Ok(StartOffsetIteratorGenerator {
+            inner: self.inner.init(geometry, filter, parallel)?,
+            offset: self.offset,
+            size,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        datagram::{gain::tests::TestGain, tests::create_geometry},
+        firmware::fpga::{Drive, EmitIntensity, Phase},
+        geometry::Point3,
+    };
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(vec![0., 1., 2., 3.], 0)]
+    #[case(vec![1., 2., 3., 0.], 1)]
+    #[case(vec![3., 0., 1., 2.], 3)]
+    #[case(vec![1., 2., 3., 0.], 5)]
+    #[test]
+    fn foci(#[case] expect: Vec<f32>, #[case] offset: usize) -> anyhow::Result<()> {
+        let geometry = create_geometry(1, 1);
+        let stm = StartOffset {
+            inner: (0..4)
+                .map(|i| Point3::new(i as f32, 0., 0.))
+                .collect::<Vec<_>>(),
+            offset: |_: &Device| offset,
+        };
+        assert_eq!(4, FociSTMGenerator::<1>::len(&stm));
+
+        let mut g = FociSTMGenerator::<1>::init(stm)?;
+        let mut iter = g.generate(&geometry[0]);
+        assert_eq!(
+            expect,
+            (0..4).map(|_| iter.next()[0].point.x).collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn gain() -> anyhow::Result<()> {
+        let geometry = create_geometry(2, 1);
+        let gains = (0..3)
+            .map(|i| {
+                TestGain::new(
+                    |_| {
+                        move |_| Drive {
+                            phase: Phase(i),
+                            intensity: EmitIntensity::MAX,
+                        }
+                    },
+                    &geometry,
+                )
+            })
+            .collect::<Vec<_>>();
+        let stm = StartOffset {
+            inner: gains,
+            offset: |dev: &Device| dev.idx(),
+        };
+        assert_eq!(3, GainSTMGenerator::len(&stm));
+
+        let mut g = stm.init(&geometry, None, false)?;
+        geometry.iter().for_each(|dev| {
+            let mut iter = g.generate(dev);
+            let phases = std::iter::from_fn(|| iter.next())
+                .map(|c| autd3_core::gain::GainCalculator::calc(&c, &dev[0]).phase.0)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                (0..3)
+                    .map(|i| ((i + dev.idx()) % 3) as u8)
+                    .collect::<Vec<_>>(),
+                phases
+            );
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    error::AUTDDriverError,
+    firmware::{
+        fpga::EmitIntensity,
+        operation::{ControlPoint, ControlPoints},
+    },
+    geometry::Device,
+};
+
+use super::{FociSTMGenerator, FociSTMIterator, FociSTMIteratorGenerator};
+
+/// A sequence of [`FociSTM`] with a different path for each device.
+///
+/// The key of [`foci`] is the device index. All paths must have the same length because the STM index is synchronized among the devices, otherwise [`AUTDDriverError::FociSTMPathLengthMismatch`] is returned.
+/// The devices not contained in [`foci`] output nothing.
+///
+/// This is useful to play STMs in multiple regions simultaneously.
+///
+/// # Examples
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use autd3_driver::{datagram::*, geometry::Point3, defined::Hz};
+/// let stm = FociSTM {
+///     foci: PerDeviceFoci {
+///         foci: HashMap::from([
+///             (0, (0..100).map(|i| Point3::new(i as f32, 0., 150.)).collect::<Vec<_>>()),
+///             (1, (0..100).map(|i| Point3::new(0., i as f32, 150.)).collect::<Vec<_>>()),
+///         ]),
+///     },
+///     config: 1. * Hz,
+/// };
+/// ```
+///
+/// [`FociSTM`]: super::FociSTM
+/// [`foci`]: PerDeviceFoci::foci
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerDeviceFoci<C> {
+    /// The path for each device.
+    pub foci: HashMap<usize, Vec<C>>,
+}
+
+#[derive(Debug)]
+pub struct PerDeviceFociIteratorGenerator<C> {
+    foci: HashMap<usize, Arc<Vec<C>>>,
+}
+
+pub struct PerDeviceFociIterator<const N: usize, C> {
+    foci: Option<Arc<Vec<C>>>,
+    idle: ControlPoints<N>,
+    i: usize,
+}
+
+impl<const N: usize, C> FociSTMIterator<N> for PerDeviceFociIterator<N, C>
+where
+    C: Clone + Send + Sync,
+    ControlPoints<N>: From<C>,
+{
+    fn next(&mut self) -> ControlPoints<N> {
+        match &self.foci {
+            Some(foci) => {
+                let p = foci[self.i].clone().into();
+                self.i += 1;
+                p
+            }
+            None => self.idle.clone(),
+        }
+    }
+}
+
+impl<const N: usize, C> FociSTMIteratorGenerator<N> for PerDeviceFociIteratorGenerator<C>
+where
+    C: Clone + Send + Sync + std::fmt::Debug,
+    ControlPoints<N>: From<C>,
+{
+    type Iterator = PerDeviceFociIterator<N, C>;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        Self::Iterator {
+            foci: self.foci.get(&device.idx()).cloned(),
+            idle: ControlPoints {
+                points: [ControlPoint::from(device.center()); N],
+                intensity: EmitIntensity::MIN,
+            },
+            i: 0,
+        }
+    }
+}
+
+impl<const N: usize, C> FociSTMGenerator<N> for PerDeviceFoci<C>
+where
+    C: Clone + Send + Sync + std::fmt::Debug,
+    ControlPoints<N>: From<C>,
+{
+    type T = PerDeviceFociIteratorGenerator<C>;
+
+    fn init(self) -> Result<Self::T, AUTDDriverError> {
+        let len = FociSTMGenerator::<N>::len(&self);
+        let mut keys = self.foci.keys().copied().collect::<Vec<_>>();
+        keys.sort_unstable();
+        if let Some(&idx) = keys.iter().find(|idx| self.foci[idx].len() != len) {
+            return Err(AUTDDriverError::FociSTMPathLengthMismatch(
+                idx,
+                self.foci[&idx].len(),
+                len,
+            ));
+        }
+        Ok(PerDeviceFociIteratorGenerator {
+            foci: self
+                .foci
+                .into_iter()
+                .map(|(idx, foci)| (idx, Arc::new(foci)))
+                .collect(),
+        })
+    }
+
+    // The lengths of the paths are validated in `init`.
+    fn len(&self) -> usize {
+        self.foci
+            .iter()
+            .min_by_key(|(&idx, _)| idx)
+            .map_or(0, |(_, foci)| foci.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{datagram::tests::create_geometry, geometry::Point3};
+
+    use super::*;
+
+    #[test]
+    fn foci() -> anyhow::Result<()> {
+        let geometry = create_geometry(3, 1);
+        let stm = PerDeviceFoci {
+            foci: HashMap::from([
+                (
+                    0,
+                    (0..4)
+                        .map(|i| Point3::new(i as f32, 0., 0.))
+                        .collect::<Vec<_>>(),
+                ),
+                (
+                    1,
+                    (0..4)
+                        .map(|i| Point3::new(0., i as f32, 0.))
+                        .collect::<Vec<_>>(),
+                ),
+            ]),
+        };
+        assert_eq!(4, FociSTMGenerator::<1>::len(&stm));
+
+        let mut g = FociSTMGenerator::<1>::init(stm)?;
+        let mut iter = g.generate(&geometry[0]);
+        assert_eq!(
+            vec![0., 1., 2., 3.],
+            (0..4).map(|_| iter.next()[0].point.x).collect::<Vec<_>>()
+        );
+        let mut iter = g.generate(&geometry[1]);
+        assert_eq!(
+            vec![0., 1., 2., 3.],
+            (0..4).map(|_| iter.next()[0].point.y).collect::<Vec<_>>()
+        );
+        let mut iter = g.generate(&geometry[2]);
+        (0..4).for_each(|_| {
+            let p = iter.next();
+            assert_eq!(*geometry[2].center(), p[0].point);
+            assert_eq!(EmitIntensity::MIN, p.intensity);
+        });
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(Err(AUTDDriverError::FociSTMPathLengthMismatch(1, 3, 4)), [4, 3, 4])]
+    #[case(Err(AUTDDriverError::FociSTMPathLengthMismatch(2, 5, 4)), [4, 4, 5])]
+    #[case(Ok(()), [4, 4, 4])]
+    #[test]
+    fn length_mismatch(#[case] expect: Result<(), AUTDDriverError>, #[case] len: [usize; 3]) {
+        let stm = PerDeviceFoci {
+            foci: len
+                .into_iter()
+                .enumerate()
+                .map(|(idx, n)| (idx, vec![Point3::origin(); n]))
+                .collect(),
+        };
+        assert_eq!(expect, FociSTMGenerator::<1>::init(stm).map(|_| ()));
+    }
+}
@@ -1,5 +1,7 @@
 use std::time::Duration;
 
+use autd3_core::modulation::FreqRatio;
+
 use crate::{defined::Freq, error::AUTDDriverError, firmware::fpga::SamplingConfig};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -14,6 +16,8 @@ pub enum STMConfig {
     #[doc(hidden)]
     SamplingConfig(SamplingConfig),
     #[doc(hidden)]
+    FreqRatio(FreqRatio),
+    #[doc(hidden)]
     FreqNearest(Freq<f32>),
     #[cfg(not(feature = "dynamic_freq"))]
     #[doc(hidden)]
@@ -34,6 +38,10 @@ impl STMConfig {
                 Ok(SamplingConfig::new(p / size as u32)?)
             }
             STMConfig::SamplingConfig(s) => Ok(s),
+            STMConfig::FreqRatio(f) => Ok(SamplingConfig::new(FreqRatio {
+                num: f.num.saturating_mul(size as u64),
+                den: f.den,
+            })?),
             STMConfig::FreqNearest(freq) => Ok(SamplingConfig::new_nearest(freq * size as f32)),
             #[cfg(not(feature = "dynamic_freq"))]
             STMConfig::PeriodNearest(duration) => {
@@ -62,6 +70,12 @@ impl From<SamplingConfig> for STMConfig {
     }
 }
 
+impl From<FreqRatio> for STMConfig {
+    fn from(f: FreqRatio) -> Self {
+        Self::FreqRatio(f)
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct FreqNearest(pub Freq<f32>);
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -117,6 +131,23 @@ mod tests {
         );
     }
 
+    #[rstest::rstest]
+    #[test]
+    #[case(SamplingConfig::ratio(1000, 3), FreqRatio { num: 1000, den: 3 }, 1)]
+    #[case(SamplingConfig::ratio(20000, 3), FreqRatio { num: 1000, den: 3 }, 20)]
+    #[case(SamplingConfig::ratio(6000, 1), FreqRatio { num: 3000, den: 1 }, 2)]
+    #[case(SamplingConfig::ratio(u64::MAX, 1), FreqRatio { num: u64::MAX / 2 + 1, den: 1 }, 2)]
+    fn freq_ratio(
+        #[case] expect: Result<SamplingConfig, SamplingConfigError>,
+        #[case] freq: FreqRatio,
+        #[case] size: usize,
+    ) {
+        assert_eq!(
+            expect.map_err(AUTDDriverError::from),
+            STMConfig::from(freq).into_sampling_config(size)
+        );
+    }
+
     #[cfg(not(feature = "dynamic_freq"))]
     #[rstest::rstest]
     #[test]
@@ -198,4 +229,50 @@ mod tests {
             STMConfig::PeriodNearest(p).into_sampling_config(size)
         );
     }
+
+    proptest::proptest! {
+        #[test]
+        fn frequency_roundtrip(division in 1..=u16::MAX, size in 1..=1000usize) {
+            let config = SamplingConfig::new(division).unwrap();
+            proptest::prop_assert_eq!(
+                Ok(config),
+                STMConfig::Freq(config.freq() / size as f32).into_sampling_config(size)
+            );
+        }
+
+        #[cfg(not(feature = "dynamic_freq"))]
+        #[test]
+        fn period_roundtrip(division in 1..=u16::MAX, size in 1..=1000u32) {
+            let config = SamplingConfig::new(division).unwrap();
+            proptest::prop_assert_eq!(
+                Ok(config),
+                STMConfig::Period(config.period() * size).into_sampling_config(size as _)
+            );
+        }
+
+        #[test]
+        fn frequency_nearest_error(division in 1.0f32..=u16::MAX as f32, size in 1..=1000usize) {
+            let ultrasound_freq = crate::defined::ultrasound_freq().hz() as f32;
+            let config = STMConfig::FreqNearest(ultrasound_freq / division / size as f32 * Hz)
+                .into_sampling_config(size)
+                .unwrap();
+            proptest::prop_assert!(
+                (config.division.get() as f32 - division).abs() <= 0.5 + division * 2. * f32::EPSILON
+            );
+        }
+
+        #[cfg(not(feature = "dynamic_freq"))]
+        #[test]
+        fn period_nearest_error(division in 1.0f64..=u16::MAX as f64, size in 1..=1000u32) {
+            let ultrasound_period = crate::defined::ultrasound_period();
+            let period = ultrasound_period.mul_f64(division) * size;
+            let config = STMConfig::PeriodNearest(period)
+                .into_sampling_config(size as _)
+                .unwrap();
+            proptest::prop_assert!(
+                (config.period() * size).abs_diff(period)
+                    <= (ultrasound_period / 2 + Duration::from_nanos(1)) * size
+            );
+        }
+    }
 }
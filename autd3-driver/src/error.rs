@@ -1,4 +1,4 @@
-use std::{convert::Infallible, time::Duration};
+use std::{convert::Infallible, fmt::Display, time::Duration};
 
 use autd3_core::{
     datagram::CombinedError,
@@ -62,6 +62,12 @@ pub enum AUTDDriverError {
         z_max = FOCI_STM_FIXED_NUM_UNIT * FOCI_STM_FIXED_NUM_UPPER_Z as f32,
     )]
     FociSTMPointOutOfRange(f32, f32, f32),
+    /// The maximum displacement of the focus per sample is invalid.
+    #[error("Maximum displacement ({0}) must be positive and finite")]
+    InvalidMaxDisplacement(f32),
+    /// The numbers of foci of the devices do not match.
+    #[error("Number of foci of device {0} ({1}) must match that of the other devices ({2})")]
+    FociSTMPathLengthMismatch(usize, usize, usize),
     /// GainSTM buffer size is out of range.
     #[error(
         "GainSTM size ({0}) is out of range ([{min}, {max}])",
@@ -69,6 +75,12 @@ pub enum AUTDDriverError {
         max = GAIN_STM_BUF_SIZE_MAX
     )]
     GainSTMSizeOutOfRange(usize),
+    /// The number of dwell times does not match the number of gains.
+    #[error("Number of dwell times ({1}) must match the number of gains ({0})")]
+    GainSTMDwellLengthMismatch(usize, usize),
+    /// Invalid dwell time of GainSTM.
+    #[error("Dwell time ({0:?}) must be a positive multiple of the ultrasound period")]
+    InvalidGainSTMDwell(Duration),
     /// GainSTM mode is not supported.
     #[error("GainSTMMode ({0:?}) is not supported")]
     GainSTMModeNotSupported(GainSTMMode),
@@ -128,24 +140,121 @@ pub enum AUTDDriverError {
     /// Miss transition time.
     #[error("Miss transition time")]
     MissTransitionTime,
+    /// The transition mode cannot be used with the loop behavior.
+    #[error("Transition mode ({2:?}) to {0:?} cannot be used with {1:?} loop. Use `TransitionMode::Immediate` or `TransitionMode::Ext` for infinite loop")]
+    IncompatibleTransitionMode(Segment, LoopBehavior, TransitionMode),
+    /// The firmware does not support the segment or the transition mode.
+    #[error("Segment ({0:?}) with transition mode ({1:?}) requires firmware {2} or later")]
+    TransitionNotSupported(Segment, Option<TransitionMode>, String),
+    /// The output power exceeds the budget.
+    #[error("The output power of device {0} exceeds the budget")]
+    PowerBudgetExceeded(usize),
     /// Silencer cannot complete phase/intensity completion in the specified sampling period.
     #[error("Silencer cannot complete phase/intensity completion in the specified sampling period. Please lower the sampling frequency or make the completion time of Silencer longer than the sampling period.")]
     InvalidSilencerSettings,
+    /// The firmware of some devices returned errors.
+    #[error("Firmware error: {}", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    Device(Vec<DeviceError>),
 }
 
-impl AUTDDriverError {
+/// An error code returned by the firmware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FirmwareErrorCode {
+    /// See [`AUTDDriverError::NotSupportedTag`].
+    NotSupportedTag,
+    /// See [`AUTDDriverError::InvalidMessageID`].
+    InvalidMessageID,
+    /// See [`AUTDDriverError::InvalidInfoType`].
+    InvalidInfoType,
+    /// See [`AUTDDriverError::InvalidGainSTMMode`].
+    InvalidGainSTMMode,
+    /// See [`AUTDDriverError::InvalidSegmentTransition`].
+    InvalidSegmentTransition,
+    /// See [`AUTDDriverError::MissTransitionTime`].
+    MissTransitionTime,
+    /// See [`AUTDDriverError::InvalidSilencerSettings`].
+    InvalidSilencerSettings,
+    /// See [`AUTDDriverError::InvalidTransitionMode`].
+    InvalidTransitionMode,
+    /// An unknown error code.
+    Unknown(u8),
+}
+
+impl FirmwareErrorCode {
     #[doc(hidden)]
-    pub const fn firmware_err(ack: u8) -> Self {
+    pub const fn from_ack(ack: u8) -> Self {
         match ack {
-            0x80 => AUTDDriverError::NotSupportedTag,
-            0x81 => AUTDDriverError::InvalidMessageID,
-            0x84 => AUTDDriverError::InvalidInfoType,
-            0x85 => AUTDDriverError::InvalidGainSTMMode,
-            0x88 => AUTDDriverError::InvalidSegmentTransition,
-            0x8B => AUTDDriverError::MissTransitionTime,
-            0x8E => AUTDDriverError::InvalidSilencerSettings,
-            0x8F => AUTDDriverError::InvalidTransitionMode,
-            _ => AUTDDriverError::UnknownFirmwareError(ack),
+            0x80 => Self::NotSupportedTag,
+            0x81 => Self::InvalidMessageID,
+            0x84 => Self::InvalidInfoType,
+            0x85 => Self::InvalidGainSTMMode,
+            0x88 => Self::InvalidSegmentTransition,
+            0x8B => Self::MissTransitionTime,
+            0x8E => Self::InvalidSilencerSettings,
+            0x8F => Self::InvalidTransitionMode,
+            _ => Self::Unknown(ack),
+        }
+    }
+}
+
+impl From<FirmwareErrorCode> for AUTDDriverError {
+    fn from(code: FirmwareErrorCode) -> Self {
+        match code {
+            FirmwareErrorCode::NotSupportedTag => AUTDDriverError::NotSupportedTag,
+            FirmwareErrorCode::InvalidMessageID => AUTDDriverError::InvalidMessageID,
+            FirmwareErrorCode::InvalidInfoType => AUTDDriverError::InvalidInfoType,
+            FirmwareErrorCode::InvalidGainSTMMode => AUTDDriverError::InvalidGainSTMMode,
+            FirmwareErrorCode::InvalidSegmentTransition => {
+                AUTDDriverError::InvalidSegmentTransition
+            }
+            FirmwareErrorCode::MissTransitionTime => AUTDDriverError::MissTransitionTime,
+            FirmwareErrorCode::InvalidSilencerSettings => AUTDDriverError::InvalidSilencerSettings,
+            FirmwareErrorCode::InvalidTransitionMode => AUTDDriverError::InvalidTransitionMode,
+            FirmwareErrorCode::Unknown(ack) => AUTDDriverError::UnknownFirmwareError(ack),
+        }
+    }
+}
+
+/// An error returned by the firmware of a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceError {
+    /// The index of the device.
+    pub dev_idx: usize,
+    /// The error code.
+    pub code: FirmwareErrorCode,
+    /// The type tags of the operations in the frame sent to the device. The second one is `None` if the frame has only one operation.
+    pub tags: [Option<u8>; 2],
+}
+
+impl Display for DeviceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} in device {} (tags: {})",
+            AUTDDriverError::from(self.code),
+            self.dev_idx,
+            self.tags
+                .iter()
+                .flatten()
+                .map(|t| format!("0x{:02X}", t))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+impl AUTDDriverError {
+    #[doc(hidden)]
+    pub fn firmware_err(ack: u8) -> Self {
+        FirmwareErrorCode::from_ack(ack).into()
+    }
+
+    /// Returns the errors returned by the firmware of each device if this is [`AUTDDriverError::Device`].
+    pub fn device_errors(&self) -> &[DeviceError] {
+        match self {
+            AUTDDriverError::Device(errors) => errors,
+            _ => &[],
         }
     }
 }
@@ -184,4 +293,19 @@ mod tests {
         assert_eq!(format!("{}", err), "Unknown firmware error: 255");
         assert_eq!(format!("{:?}", err), "UnknownFirmwareError(255)");
     }
+
+    #[test]
+    fn device_error() {
+        let err = AUTDDriverError::Device(vec![DeviceError {
+            dev_idx: 1,
+            code: FirmwareErrorCode::from_ack(0x88),
+            tags: [Some(0x11), Some(0x12)],
+        }]);
+        assert_eq!(
+            "Firmware error: Invalid segment transition in device 1 (tags: 0x11, 0x12)",
+            err.to_string()
+        );
+        assert_eq!(1, err.device_errors().len());
+        assert!(AUTDDriverError::LinkClosed.device_errors().is_empty());
+    }
 }
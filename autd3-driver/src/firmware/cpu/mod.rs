@@ -1,9 +1,14 @@
 mod gain_stm_mode;
+mod rx;
 
 pub use autd3_core::link::{Header, RxMessage, TxMessage};
 pub use gain_stm_mode::*;
+pub use rx::{Ack, FirmwareInfo, RxDecoder};
 
-use crate::error::AUTDDriverError;
+use crate::{
+    error::{AUTDDriverError, DeviceError, FirmwareErrorCode},
+    geometry::Geometry,
+};
 
 pub(crate) const MSG_ID_MAX: u8 = 0x7F;
 
@@ -15,6 +20,36 @@ pub fn check_firmware_err(msg: &RxMessage) -> Result<(), AUTDDriverError> {
     Ok(())
 }
 
+#[doc(hidden)]
+pub fn check_device_errors(
+    geometry: &Geometry,
+    tx: &[TxMessage],
+    rx: &[RxMessage],
+) -> Result<(), AUTDDriverError> {
+    let errors = tx
+        .iter()
+        .zip(rx.iter())
+        .enumerate()
+        .filter(|(i, (_, r))| geometry[*i].enable && r.ack() & 0x80 != 0)
+        .map(|(dev_idx, (tx, r))| {
+            let slot_2_offset = u16::from_le(tx.header.slot_2_offset) as usize;
+            DeviceError {
+                dev_idx,
+                code: FirmwareErrorCode::from_ack(r.ack()),
+                tags: [
+                    Some(tx.payload()[0]),
+                    (slot_2_offset != 0).then(|| tx.payload()[slot_2_offset]),
+                ],
+            }
+        })
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AUTDDriverError::Device(errors))
+    }
+}
+
 #[doc(hidden)]
 pub fn check_if_msg_is_processed<'a>(
     tx: &'a [TxMessage],
@@ -60,4 +95,68 @@ mod tests {
     ) {
         assert_eq!(expect, check_if_msg_is_processed(&tx, &rx).collect_vec());
     }
+
+    #[rstest::rstest]
+    #[test]
+    fn test_check_device_errors(mut tx: Vec<TxMessage>) {
+        let mut geometry = crate::datagram::tests::create_geometry(3, 1);
+        tx[1].payload_mut()[0] = 0x10;
+        tx[1].payload_mut()[4] = 0x30;
+        tx[1].header.slot_2_offset = 4u16.to_le();
+        tx[2].payload_mut()[0] = 0x44;
+
+        assert_eq!(
+            Ok(()),
+            check_device_errors(
+                &geometry,
+                &tx,
+                &[
+                    RxMessage::new(0, 0),
+                    RxMessage::new(0, 1),
+                    RxMessage::new(0, 2)
+                ]
+            )
+        );
+        assert_eq!(
+            Err(AUTDDriverError::Device(vec![
+                DeviceError {
+                    dev_idx: 1,
+                    code: FirmwareErrorCode::MissTransitionTime,
+                    tags: [Some(0x10), Some(0x30)],
+                },
+                DeviceError {
+                    dev_idx: 2,
+                    code: FirmwareErrorCode::Unknown(0xFF),
+                    tags: [Some(0x44), None],
+                }
+            ])),
+            check_device_errors(
+                &geometry,
+                &tx,
+                &[
+                    RxMessage::new(0, 0),
+                    RxMessage::new(0, 0x8B),
+                    RxMessage::new(0, 0xFF)
+                ]
+            )
+        );
+
+        geometry[1].enable = false;
+        assert_eq!(
+            Err(AUTDDriverError::Device(vec![DeviceError {
+                dev_idx: 2,
+                code: FirmwareErrorCode::Unknown(0xFF),
+                tags: [Some(0x44), None],
+            }])),
+            check_device_errors(
+                &geometry,
+                &tx,
+                &[
+                    RxMessage::new(0, 0),
+                    RxMessage::new(0, 0x8B),
+                    RxMessage::new(0, 0xFF)
+                ]
+            )
+        );
+    }
 }
@@ -0,0 +1,187 @@
+use autd3_core::link::RxMessage;
+
+use crate::{
+    error::FirmwareErrorCode,
+    firmware::{
+        fpga::FPGAState,
+        operation::FirmwareVersionType,
+        version::{FirmwareCapabilities, FirmwareVersion, Major, Minor},
+    },
+};
+
+const ERR_BIT: u8 = 0x80;
+
+/// The acknowledgement of [`RxMessage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ack {
+    /// The message with the ID has been processed.
+    Processed(u8),
+    /// The firmware failed to process the message.
+    Error(FirmwareErrorCode),
+}
+
+/// The response to the firmware information request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirmwareInfo {
+    /// The major version of the CPU firmware.
+    CPUMajor(Major),
+    /// The minor version of the CPU firmware.
+    CPUMinor(Minor),
+    /// The major version of the FPGA firmware.
+    FPGAMajor(Major),
+    /// The minor version of the FPGA firmware.
+    FPGAMinor(Minor),
+    /// The function bits of the FPGA firmware.
+    FPGAFunctions(u8),
+}
+
+/// Decoder of [`RxMessage`] for a firmware version.
+///
+/// The layout of [`RxMessage::data`] depends on the firmware version and the last request, so use this instead of masking the bits by hand.
+///
+/// # Examples
+///
+/// ```
+/// # use autd3_driver::firmware::{cpu::{Ack, RxDecoder, RxMessage}, version::*};
+/// # let version = FirmwareVersion {
+/// #     idx: 0,
+/// #     cpu: CPUVersion { major: FirmwareVersion::LATEST_VERSION_NUM_MAJOR, minor: Minor(0) },
+/// #     fpga: FPGAVersion { major: FirmwareVersion::LATEST_VERSION_NUM_MAJOR, minor: Minor(0), function_bits: 0 },
+/// # };
+/// let decoder = RxDecoder::new(&version);
+/// let rx = RxMessage::new(0x89, 0x01);
+/// assert_eq!(Ack::Processed(0x01), decoder.ack(&rx));
+/// assert_eq!(Some(true), decoder.fpga_state(&rx).map(|s| s.is_thermal_assert()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RxDecoder {
+    caps: FirmwareCapabilities,
+}
+
+impl RxDecoder {
+    /// Creates a new [`RxDecoder`] for the firmware version.
+    pub const fn new(version: &FirmwareVersion) -> Self {
+        Self {
+            caps: version.caps(),
+        }
+    }
+
+    /// Decodes the acknowledgement.
+    pub fn ack(&self, msg: &RxMessage) -> Ack {
+        if msg.ack() & ERR_BIT != 0 {
+            Ack::Error(FirmwareErrorCode::from_ack(msg.ack()))
+        } else {
+            Ack::Processed(msg.ack())
+        }
+    }
+
+    /// Decodes the FPGA state.
+    ///
+    /// Returns `None` if the reads FPGA state mode is disabled, or the layout of the state is unknown for the firmware version, i.e., the firmware does not support [`Segment`].
+    ///
+    /// [`Segment`]: autd3_core::datagram::Segment
+    pub fn fpga_state(&self, msg: &RxMessage) -> Option<FPGAState> {
+        if !self.caps.supports_segment() {
+            return None;
+        }
+        FPGAState::from_rx(msg)
+    }
+
+    /// Decodes the response to the firmware information request `ty`.
+    ///
+    /// Returns `None` if `ty` does not have a response, i.e., [`FirmwareVersionType::Clear`].
+    pub fn firmware_info(&self, msg: &RxMessage, ty: FirmwareVersionType) -> Option<FirmwareInfo> {
+        let data = msg.data();
+        match ty {
+            FirmwareVersionType::CPUMajor => Some(FirmwareInfo::CPUMajor(Major(data))),
+            FirmwareVersionType::CPUMinor => Some(FirmwareInfo::CPUMinor(Minor(data))),
+            FirmwareVersionType::FPGAMajor => Some(FirmwareInfo::FPGAMajor(Major(data))),
+            FirmwareVersionType::FPGAMinor => Some(FirmwareInfo::FPGAMinor(Minor(data))),
+            FirmwareVersionType::FPGAFunctions => Some(FirmwareInfo::FPGAFunctions(data)),
+            FirmwareVersionType::Clear => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::firmware::version::{CPUVersion, FPGAVersion};
+
+    use super::*;
+
+    fn decoder(major: u8) -> RxDecoder {
+        RxDecoder::new(&FirmwareVersion {
+            idx: 0,
+            cpu: CPUVersion {
+                major: Major(major),
+                minor: Minor(0),
+            },
+            fpga: FPGAVersion {
+                major: Major(major),
+                minor: Minor(0),
+                function_bits: 0,
+            },
+        })
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(Ack::Processed(0x00), 0x00)]
+    #[case(Ack::Processed(0x7F), 0x7F)]
+    #[case(Ack::Error(FirmwareErrorCode::NotSupportedTag), 0x80)]
+    #[case(Ack::Error(FirmwareErrorCode::MissTransitionTime), 0x8B)]
+    #[case(Ack::Error(FirmwareErrorCode::Unknown(0xFF)), 0xFF)]
+    fn decode_ack(#[case] expect: Ack, #[case] ack: u8) {
+        assert_eq!(
+            expect,
+            decoder(FirmwareVersion::LATEST_VERSION_NUM_MAJOR.0).ack(&RxMessage::new(0, ack))
+        );
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(Some(0x89), 0x89, FirmwareVersion::LATEST_VERSION_NUM_MAJOR.0)]
+    #[case(Some(0x80), 0x80, 0x8F)]
+    #[case(None, 0x09, FirmwareVersion::LATEST_VERSION_NUM_MAJOR.0)]
+    #[case(None, 0x89, 0x8E)]
+    #[case(None, 0x89, 0xFF)]
+    fn fpga_state(#[case] expect: Option<u8>, #[case] data: u8, #[case] major: u8) {
+        assert_eq!(
+            expect,
+            decoder(major)
+                .fpga_state(&RxMessage::new(data, 0))
+                .map(|s| s.state())
+        );
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(
+        Some(FirmwareInfo::CPUMajor(Major(0xA2))),
+        FirmwareVersionType::CPUMajor
+    )]
+    #[case(
+        Some(FirmwareInfo::CPUMinor(Minor(0xA2))),
+        FirmwareVersionType::CPUMinor
+    )]
+    #[case(
+        Some(FirmwareInfo::FPGAMajor(Major(0xA2))),
+        FirmwareVersionType::FPGAMajor
+    )]
+    #[case(
+        Some(FirmwareInfo::FPGAMinor(Minor(0xA2))),
+        FirmwareVersionType::FPGAMinor
+    )]
+    #[case(
+        Some(FirmwareInfo::FPGAFunctions(0xA2)),
+        FirmwareVersionType::FPGAFunctions
+    )]
+    #[case(None, FirmwareVersionType::Clear)]
+    fn firmware_info(#[case] expect: Option<FirmwareInfo>, #[case] ty: FirmwareVersionType) {
+        assert_eq!(
+            expect,
+            decoder(FirmwareVersion::LATEST_VERSION_NUM_MAJOR.0)
+                .firmware_info(&RxMessage::new(0xA2, 0), ty)
+        );
+    }
+}
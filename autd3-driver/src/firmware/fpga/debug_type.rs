@@ -43,7 +43,7 @@ pub enum DebugType<'a> {
     Direct(bool),
 }
 
-#[bitfield_struct::bitfield(u64)]
+#[bitfield_struct::bitfield(u64, repr = u64, from = u64::from_le, into = u64::to_le)]
 #[derive(IntoBytes, Immutable)]
 pub(crate) struct DebugValue {
     #[bits(56)]
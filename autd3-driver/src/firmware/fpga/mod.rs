@@ -1,18 +1,20 @@
 mod debug_type;
 mod fpga_state;
 mod silencer_target;
+#[cfg(feature = "stm")]
 mod stm_focus;
 
 pub use autd3_core::{
     datagram::{GPIOIn, GPIOOut, LoopBehavior, Segment, TransitionMode, TRANSITION_MODE_NONE},
     gain::{Drive, EmitIntensity, Phase},
-    modulation::SamplingConfig,
+    modulation::{FreqRatio, SamplingConfig},
 };
 
 pub use debug_type::DebugType;
 pub(crate) use debug_type::DebugValue;
 pub use fpga_state::FPGAState;
 pub use silencer_target::SilencerTarget;
+#[cfg(feature = "stm")]
 pub(crate) use stm_focus::STMFocus;
 
 use crate::{defined::mm, ethercat::DcSysTime};
@@ -3,7 +3,7 @@ use crate::{error::AUTDDriverError, geometry::Point3};
 use super::*;
 use zerocopy::{Immutable, IntoBytes};
 
-#[bitfield_struct::bitfield(u64)]
+#[bitfield_struct::bitfield(u64, repr = u64, from = u64::from_le, into = u64::to_le)]
 #[derive(IntoBytes, Immutable)]
 pub(crate) struct STMFocus {
     #[bits(18)]
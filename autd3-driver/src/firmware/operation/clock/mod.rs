@@ -64,6 +64,15 @@ fn calculate_mult_div(frequency: u32) -> Option<(u64, u64, u64)> {
     })
 }
 
+/// Calculates the parameters of the MMCM, i.e., the clock divider, multiplier, and output divider, to generate the FPGA clock for `ultrasound_freq`.
+pub(crate) fn mmcm_params(ultrasound_freq: Freq<u32>) -> Result<(u64, u64, u64), AUTDDriverError> {
+    let fpga_clk_freq = ultrasound_freq.hz() * ULTRASOUND_PERIOD_COUNT as u32;
+    if !fpga_clk_freq.is_multiple_of(125) {
+        return Err(AUTDDriverError::InvalidFrequency(ultrasound_freq));
+    }
+    calculate_mult_div(fpga_clk_freq).ok_or(AUTDDriverError::InvalidFrequency(ultrasound_freq))
+}
+
 impl ConfigureClockOp {
     pub const fn new(ultrasound_freq: Freq<u32>) -> Self {
         Self {
@@ -81,12 +90,7 @@ impl Operation for ConfigureClockOp {
         let sent = DRP_ROM_SIZE - self.remains;
 
         if sent == 0 {
-            let fpga_clk_freq = self.ultrasound_freq.hz() * ULTRASOUND_PERIOD_COUNT as u32;
-            if fpga_clk_freq % 125 != 0 {
-                return Err(AUTDDriverError::InvalidFrequency(self.ultrasound_freq));
-            }
-            let (clkdiv, mult, div) = calculate_mult_div(fpga_clk_freq)
-                .ok_or(AUTDDriverError::InvalidFrequency(self.ultrasound_freq))?;
+            let (clkdiv, mult, div) = mmcm_params(self.ultrasound_freq)?;
 
             let mut rom = vec![0; DRP_ROM_SIZE];
 
@@ -169,7 +173,7 @@ impl Operation for ConfigureClockOp {
                 } else {
                     ClkControlFlags::NONE
                 },
-                size: size as _,
+                size: (size as u16).to_le(),
             },
         );
 
@@ -177,7 +181,7 @@ impl Operation for ConfigureClockOp {
             .chunks_mut(size_of::<u64>())
             .zip(self.rom[sent..].iter())
             .for_each(|(dst, &src)| {
-                super::write_to_tx(dst, src);
+                super::write_to_tx(dst, src.to_le());
             });
 
         self.remains -= size;
@@ -11,7 +11,7 @@ use crate::{
 
 use autd3_core::gain::GainCalculator;
 use derive_new::new;
-use zerocopy::{Immutable, IntoBytes};
+use zerocopy::{FromBytes, Immutable, IntoBytes};
 
 #[derive(Clone, Copy, IntoBytes, Immutable)]
 #[repr(C)]
@@ -26,9 +26,9 @@ bitflags::bitflags! {
 
 #[repr(C, align(2))]
 #[derive(IntoBytes, Immutable)]
-struct Gain {
+pub(crate) struct Gain {
     tag: TypeTag,
-    segment: u8,
+    pub(crate) segment: u8,
     flag: GainControlFlags,
     __: u8,
 }
@@ -68,12 +68,13 @@ impl<Calculator: GainCalculator> Operation for GainOp<Calculator> {
                 __: 0,
             },
         );
-        tx[size_of::<Gain>()..]
-            .chunks_mut(size_of::<Drive>())
-            .zip(device.iter())
-            .for_each(|(dst, tr)| {
-                super::write_to_tx(dst, self.calculator.calc(tr));
-            });
+        self.calculator.calc_into(
+            device,
+            <[Drive]>::mut_from_bytes(
+                &mut tx[size_of::<Gain>()..size_of::<Gain>() + device.len() * size_of::<Drive>()],
+            )
+            .unwrap(),
+        );
 
         self.is_done = true;
         Ok(size_of::<Gain>() + device.len() * size_of::<Drive>())
@@ -150,6 +151,38 @@ mod tests {
             });
     }
 
+    #[test]
+    fn disabled_transducer() {
+        let mut device = create_device(0, NUM_TRANS_IN_UNIT as _);
+        device
+            .iter_mut()
+            .filter(|tr| tr.idx() % 2 == 0)
+            .for_each(|tr| tr.enable = false);
+
+        let mut tx = vec![0x00u8; size_of::<Gain>() + NUM_TRANS_IN_UNIT * size_of::<Drive>()];
+
+        let d = Drive {
+            phase: Phase(0x80),
+            intensity: EmitIntensity(0xFF),
+        };
+        let mut op = GainOp::new(Segment::S0, None, {
+            Impl {
+                data: vec![d; NUM_TRANS_IN_UNIT],
+            }
+        });
+
+        assert!(op.pack(&device, &mut tx).is_ok());
+
+        tx[size_of::<Gain>()..]
+            .chunks(2)
+            .zip(device.iter())
+            .for_each(|(v, tr)| {
+                let expect = if tr.enable { d } else { Drive::NULL };
+                assert_eq!(v[0], expect.phase.0);
+                assert_eq!(v[1], expect.intensity.0);
+            });
+    }
+
     #[test]
     fn invalid_transition_mode() {
         let device = create_device(0, NUM_TRANS_IN_UNIT as _);
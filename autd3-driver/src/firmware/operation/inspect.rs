@@ -0,0 +1,221 @@
+use std::mem::{offset_of, size_of};
+
+use crate::firmware::{cpu::TxMessage, fpga::Segment};
+
+#[cfg(feature = "stm")]
+use super::stm::{FociSTMHead, FociSTMSubseq, GainSTMControlFlags, GainSTMHead, GainSTMSubseq};
+use super::{
+    gain::Gain,
+    modulation::{ModulationControlFlags, ModulationHead, ModulationSubseq},
+    TypeTag,
+};
+
+// The flags and segments are read before knowing whether the frame is the head or the subsequent one.
+const MOD_FLAG_OFFSET: usize = offset_of!(ModulationHead, flag);
+const _: () = assert!(MOD_FLAG_OFFSET == offset_of!(ModulationSubseq, flag));
+#[cfg(feature = "stm")]
+const GAIN_STM_FLAG_OFFSET: usize = offset_of!(GainSTMHead, flag);
+#[cfg(feature = "stm")]
+const _: () = assert!(GAIN_STM_FLAG_OFFSET == offset_of!(GainSTMSubseq, flag));
+#[cfg(feature = "stm")]
+const FOCI_STM_SEGMENT_OFFSET: usize = offset_of!(FociSTMHead, segment);
+#[cfg(feature = "stm")]
+const _: () = assert!(FOCI_STM_SEGMENT_OFFSET == offset_of!(FociSTMSubseq, segment));
+
+/// The content of a frame which affects the output intensity.
+#[doc(hidden)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum OutputContent<'a> {
+    /// All settings are cleared.
+    Clear,
+    /// The drives written by [`Gain`].
+    ///
+    /// [`Gain`]: autd3_core::gain::Gain
+    Gain {
+        /// The segment to be written.
+        segment: Segment,
+        /// The raw drive data. The odd bytes are the intensities.
+        drives: &'a mut [u8],
+    },
+    /// A part of the modulation data.
+    Modulation {
+        /// The segment to be written.
+        segment: Segment,
+        /// Whether this is the first part of the modulation data.
+        begin: bool,
+        /// The modulation data.
+        data: &'a mut [u8],
+    },
+    /// A part of [`GainSTM`] or [`FociSTM`].
+    ///
+    /// [`GainSTM`]: crate::datagram::GainSTM
+    /// [`FociSTM`]: crate::datagram::FociSTM
+    STM {
+        /// The segment to be written.
+        segment: Segment,
+    },
+}
+
+const fn segment(v: bool) -> Segment {
+    if v {
+        Segment::S1
+    } else {
+        Segment::S0
+    }
+}
+
+fn inspect_slot(slot: &mut [u8], num_transducers: usize) -> Option<OutputContent<'_>> {
+    match *slot.first()? {
+        t if t == TypeTag::Clear as u8 => Some(OutputContent::Clear),
+        t if t == TypeTag::Gain as u8 => {
+            let segment = segment(slot[offset_of!(Gain, segment)] != 0);
+            let end = (size_of::<Gain>() + num_transducers * 2).min(slot.len());
+            Some(OutputContent::Gain {
+                segment,
+                drives: &mut slot[size_of::<Gain>()..end],
+            })
+        }
+        t if t == TypeTag::Modulation as u8 => {
+            let flag = ModulationControlFlags::from_bits_retain(slot[MOD_FLAG_OFFSET]);
+            let begin = flag.contains(ModulationControlFlags::BEGIN);
+            let (offset, size) = if begin {
+                (
+                    size_of::<ModulationHead>(),
+                    slot[offset_of!(ModulationHead, size)] as usize,
+                )
+            } else {
+                let o = offset_of!(ModulationSubseq, size);
+                (
+                    size_of::<ModulationSubseq>(),
+                    u16::from_le_bytes([slot[o], slot[o + 1]]) as usize,
+                )
+            };
+            let end = (offset + size).min(slot.len());
+            Some(OutputContent::Modulation {
+                segment: segment(flag.contains(ModulationControlFlags::SEGMENT)),
+                begin,
+                data: &mut slot[offset..end],
+            })
+        }
+        #[cfg(feature = "stm")]
+        t if t == TypeTag::GainSTM as u8 => Some(OutputContent::STM {
+            segment: segment(
+                GainSTMControlFlags::from_bits_retain(slot[GAIN_STM_FLAG_OFFSET])
+                    .contains(GainSTMControlFlags::SEGMENT),
+            ),
+        }),
+        #[cfg(feature = "stm")]
+        t if t == TypeTag::FociSTM as u8 => Some(OutputContent::STM {
+            segment: segment(slot[FOCI_STM_SEGMENT_OFFSET] != 0),
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the contents of the frame which affect the output intensity.
+///
+/// `num_transducers` is the number of transducers of the device to which the frame is sent.
+#[doc(hidden)]
+pub fn inspect_output(tx: &mut TxMessage, num_transducers: usize) -> Vec<OutputContent<'_>> {
+    let slot_2_offset = u16::from_le(tx.header.slot_2_offset) as usize;
+    let (slot1, slot2) = if slot_2_offset == 0 {
+        (tx.payload_mut(), None)
+    } else {
+        let (slot1, slot2) = tx.payload_mut().split_at_mut(slot_2_offset);
+        (slot1, Some(slot2))
+    };
+    std::iter::once(slot1)
+        .chain(slot2)
+        .filter_map(|slot| inspect_slot(slot, num_transducers))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use zerocopy::FromZeros;
+
+    use super::*;
+    use crate::{
+        firmware::{
+            fpga::{Drive, EmitIntensity, LoopBehavior, Phase, SamplingConfig},
+            operation::{
+                tests::create_device, ForceFanOp, GainOp, ModulationOp, NullOp, OperationHandler,
+            },
+        },
+        geometry::Geometry,
+    };
+
+    const NUM_TRANS_IN_UNIT: usize = 249;
+
+    struct Impl {
+        drive: Drive,
+    }
+
+    impl autd3_core::gain::GainCalculator for Impl {
+        fn calc(&self, _: &autd3_core::geometry::Transducer) -> Drive {
+            self.drive
+        }
+    }
+
+    #[test]
+    fn gain_and_modulation() -> anyhow::Result<()> {
+        let geometry = Geometry::new(vec![create_device(0, NUM_TRANS_IN_UNIT as _)]);
+        let mut tx = vec![TxMessage::new_zeroed(); 1];
+
+        let mut ops = vec![Some((
+            ModulationOp::new(
+                Arc::new(vec![0x80; 10]),
+                SamplingConfig::FREQ_MIN,
+                LoopBehavior::Infinite,
+                Segment::S1,
+                None,
+            ),
+            GainOp::new(
+                Segment::S0,
+                None,
+                Impl {
+                    drive: Drive {
+                        phase: Phase(0x01),
+                        intensity: EmitIntensity(0x02),
+                    },
+                },
+            ),
+        ))];
+        OperationHandler::pack(&mut ops, &geometry, &mut tx, false)?;
+
+        let contents = inspect_output(&mut tx[0], NUM_TRANS_IN_UNIT);
+        assert_eq!(2, contents.len());
+        assert_eq!(
+            OutputContent::Modulation {
+                segment: Segment::S1,
+                begin: true,
+                data: &mut [0x80; 10],
+            },
+            contents[0]
+        );
+        assert_eq!(
+            OutputContent::Gain {
+                segment: Segment::S0,
+                drives: &mut [0x01, 0x02].repeat(NUM_TRANS_IN_UNIT),
+            },
+            contents[1]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn no_output() -> anyhow::Result<()> {
+        let geometry = Geometry::new(vec![create_device(0, NUM_TRANS_IN_UNIT as _)]);
+        let mut tx = vec![TxMessage::new_zeroed(); 1];
+
+        let mut ops = vec![Some((ForceFanOp::new(true), NullOp {}))];
+        OperationHandler::pack(&mut ops, &geometry, &mut tx, false)?;
+
+        assert!(inspect_output(&mut tx[0], NUM_TRANS_IN_UNIT).is_empty());
+
+        Ok(())
+    }
+}
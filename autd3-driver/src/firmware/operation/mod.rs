@@ -8,12 +8,14 @@ mod force_fan;
 mod gain;
 mod gpio_in;
 mod info;
+mod inspect;
 mod modulation;
 mod phase_corr;
 mod pulse_width_encoder;
 mod reads_fpga_state;
 mod segment;
 mod silencer;
+#[cfg(feature = "stm")]
 mod stm;
 mod sync;
 
@@ -29,6 +31,7 @@ pub(crate) use gain::*;
 pub(crate) use gpio_in::*;
 pub use info::FirmwareVersionType;
 pub(crate) use info::*;
+pub use inspect::{inspect_output, OutputContent};
 pub(crate) use modulation::*;
 pub(crate) use phase_corr::*;
 pub(crate) use pulse_width_encoder::*;
@@ -36,17 +39,23 @@ pub(crate) use reads_fpga_state::*;
 pub use segment::SwapSegment;
 pub(crate) use segment::*;
 pub(crate) use silencer::*;
+#[cfg(feature = "stm")]
 pub(crate) use stm::*;
+#[cfg(feature = "stm")]
 pub use stm::{ControlPoint, ControlPoints, FociSTMIterator, GainSTMIterator};
 pub(crate) use sync::*;
 use zerocopy::{Immutable, IntoBytes};
 
 use crate::{
     error::AUTDDriverError,
-    firmware::cpu::{TxMessage, MSG_ID_MAX},
+    firmware::{
+        cpu::{TxMessage, MSG_ID_MAX},
+        fpga::{LoopBehavior, Segment, TransitionMode},
+    },
     geometry::{Device, Geometry},
 };
 
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
 #[derive(PartialEq, Debug, IntoBytes, Immutable)]
@@ -63,7 +72,9 @@ pub(crate) enum TypeTag {
     Silencer = 0x21,
     Gain = 0x30,
     GainSwapSegment = 0x31,
+    #[cfg(feature = "stm")]
     GainSTM = 0x41,
+    #[cfg(feature = "stm")]
     FociSTM = 0x42,
     GainSTMSwapSegment = 0x43,
     FociSTMSwapSegment = 0x44,
@@ -85,6 +96,27 @@ pub trait OperationGenerator {
     fn generate(&mut self, device: &Device) -> (Self::O1, Self::O2);
 }
 
+/// The packing metrics of a [`Datagram`] for a device.
+///
+/// [`Datagram`]: crate::datagram::Datagram
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackingMetrics {
+    /// The number of frames containing the data for the device.
+    pub frames: usize,
+    /// The total size of the data packed into the frames for the device in bytes.
+    pub bytes: usize,
+}
+
+impl PackingMetrics {
+    fn record(&mut self, size: usize) {
+        if size > 0 {
+            self.frames += 1;
+            self.bytes += size;
+        }
+    }
+}
+
 #[doc(hidden)]
 pub struct OperationHandler {}
 
@@ -110,6 +142,9 @@ impl OperationHandler {
         })
     }
 
+    /// Packs the operations of each enabled device into `tx`.
+    ///
+    /// If `parallel` is `true`, the devices are packed in parallel. This requires the `parallel` feature, otherwise they are packed sequentially.
     pub fn pack<O1, O2>(
         operations: &mut [Option<(O1, O2)>],
         geometry: &Geometry,
@@ -121,33 +156,106 @@ impl OperationHandler {
         O2: Operation,
         AUTDDriverError: From<O1::Error> + From<O2::Error>,
     {
+        Self::pack_impl(operations, geometry, tx, parallel, None)
+    }
+
+    /// Same as [`OperationHandler::pack`], but also records the [`PackingMetrics`] of each device into `metrics`, which is indexed by the device index.
+    pub fn pack_with_metrics<O1, O2>(
+        operations: &mut [Option<(O1, O2)>],
+        geometry: &Geometry,
+        tx: &mut [TxMessage],
+        parallel: bool,
+        metrics: &mut [PackingMetrics],
+    ) -> Result<(), AUTDDriverError>
+    where
+        O1: Operation,
+        O2: Operation,
+        AUTDDriverError: From<O1::Error> + From<O2::Error>,
+    {
+        Self::pack_impl(operations, geometry, tx, parallel, Some(metrics))
+    }
+
+    fn pack_impl<O1, O2>(
+        operations: &mut [Option<(O1, O2)>],
+        geometry: &Geometry,
+        tx: &mut [TxMessage],
+        parallel: bool,
+        mut metrics: Option<&mut [PackingMetrics]>,
+    ) -> Result<(), AUTDDriverError>
+    where
+        O1: Operation,
+        O2: Operation,
+        AUTDDriverError: From<O1::Error> + From<O2::Error>,
+    {
+        let _span = tracing::trace_span!("pack", devices = operations.len(), parallel).entered();
+
+        #[cfg(feature = "parallel")]
         if parallel {
-            geometry
+            // The frames of each device are independent. If all devices are enabled, the devices, the frames and the operations are zipped as indexed parallel iterators without any allocation; otherwise, the enabled devices are distributed with `par_bridge`.
+            if geometry.iter().all(|dev| dev.enable) {
+                let frames = geometry
+                    .par_iter()
+                    .zip(tx.par_iter_mut())
+                    .zip(operations.par_iter_mut());
+                return match metrics {
+                    Some(metrics) => frames.zip(metrics.par_iter_mut()).try_for_each(
+                        |(((dev, tx), op), metrics)| {
+                            metrics.record(Self::pack_dev(op, dev, tx)?);
+                            Ok(())
+                        },
+                    ),
+                    None => frames
+                        .try_for_each(|((dev, tx), op)| Self::pack_dev(op, dev, tx).map(|_| ())),
+                };
+            }
+            let mut metrics = metrics.map(|metrics| metrics.iter_mut());
+            return geometry
                 .iter()
                 .zip(tx.iter_mut())
-                .filter(|(dev, _)| dev.enable)
+                .zip(std::iter::from_fn(|| {
+                    Some(metrics.as_mut().and_then(Iterator::next))
+                }))
+                .filter(|((dev, _), _)| dev.enable)
                 .zip(operations.iter_mut())
                 .par_bridge()
-                .try_for_each(|((dev, tx), op)| {
-                    if let Some((op1, op2)) = op {
-                        Self::pack_op2(op1, op2, dev, tx)
-                    } else {
-                        Ok(())
-                    }
-                })
-        } else {
-            geometry
-                .iter()
-                .zip(tx.iter_mut())
-                .filter(|(dev, _)| dev.enable)
-                .zip(operations.iter_mut())
-                .try_for_each(|((dev, tx), op)| {
-                    if let Some((op1, op2)) = op {
-                        Self::pack_op2(op1, op2, dev, tx)
-                    } else {
-                        Ok(())
+                .try_for_each(|(((dev, tx), metrics), op)| {
+                    let size = Self::pack_dev(op, dev, tx)?;
+                    if let Some(metrics) = metrics {
+                        metrics.record(size);
                     }
-                })
+                    Ok(())
+                });
+        }
+        #[cfg(not(feature = "parallel"))]
+        let _ = parallel;
+
+        geometry
+            .iter()
+            .zip(tx.iter_mut())
+            .filter(|(dev, _)| dev.enable)
+            .zip(operations.iter_mut())
+            .try_for_each(|((dev, tx), op)| {
+                let size = Self::pack_dev(op, dev, tx)?;
+                if let Some(metrics) = metrics.as_deref_mut() {
+                    metrics[dev.idx()].record(size);
+                }
+                Ok(())
+            })
+    }
+
+    fn pack_dev<O1, O2>(
+        op: &mut Option<(O1, O2)>,
+        dev: &Device,
+        tx: &mut TxMessage,
+    ) -> Result<usize, AUTDDriverError>
+    where
+        O1: Operation,
+        O2: Operation,
+        AUTDDriverError: From<O1::Error> + From<O2::Error>,
+    {
+        match op {
+            Some((op1, op2)) => Self::pack_op2(op1, op2, dev, tx),
+            None => Ok(0),
         }
     }
 
@@ -156,23 +264,25 @@ impl OperationHandler {
         op2: &mut O2,
         dev: &Device,
         tx: &mut TxMessage,
-    ) -> Result<(), AUTDDriverError>
+    ) -> Result<usize, AUTDDriverError>
     where
         O1: Operation,
         O2: Operation,
         AUTDDriverError: From<O1::Error> + From<O2::Error>,
     {
         match (op1.is_done(), op2.is_done()) {
-            (true, true) => Result::<_, AUTDDriverError>::Ok(()),
-            (true, false) => Self::pack_op(op2, dev, tx).map(|_| Ok(()))?,
-            (false, true) => Self::pack_op(op1, dev, tx).map(|_| Ok(()))?,
+            (true, true) => Ok(0),
+            (true, false) => Self::pack_op(op2, dev, tx),
+            (false, true) => Self::pack_op(op1, dev, tx),
             (false, false) => {
                 let op1_size = Self::pack_op(op1, dev, tx)?;
                 if tx.payload().len() - op1_size >= op2.required_size(dev) {
-                    op2.pack(dev, &mut tx.payload_mut()[op1_size..])?;
-                    tx.header.slot_2_offset = op1_size as u16;
+                    let op2_size = op2.pack(dev, &mut tx.payload_mut()[op1_size..])?;
+                    tx.header.slot_2_offset = (op1_size as u16).to_le();
+                    Ok(op1_size + op2_size)
+                } else {
+                    Ok(op1_size)
                 }
-                Ok(())
             }
         }
     }
@@ -189,8 +299,40 @@ impl OperationHandler {
     }
 }
 
+/// Validates the combination of the loop behavior and the transition mode of the data written to `segment`.
+///
+/// The firmware rejects [`TransitionMode::SyncIdx`], [`TransitionMode::SysTime`], and [`TransitionMode::GPIO`] for infinite loop regardless of the current segment, so this is checked before sending.
+pub(crate) fn validate_transition(
+    segment: Segment,
+    loop_behavior: LoopBehavior,
+    transition_mode: Option<TransitionMode>,
+) -> Result<(), AUTDDriverError> {
+    match (loop_behavior, transition_mode) {
+        (
+            LoopBehavior::Infinite,
+            Some(
+                mode @ (TransitionMode::SyncIdx
+                | TransitionMode::SysTime(_)
+                | TransitionMode::GPIO(_)),
+            ),
+        ) => Err(AUTDDriverError::IncompatibleTransitionMode(
+            segment,
+            loop_behavior,
+            mode,
+        )),
+        _ => Ok(()),
+    }
+}
+
 #[inline(always)]
 pub(crate) fn write_to_tx<T: IntoBytes + Immutable>(tx: &mut [u8], data: T) {
+    debug_assert!(
+        size_of::<T>() <= tx.len(),
+        "{} ({} bytes) does not fit in the payload ({} bytes)",
+        std::any::type_name::<T>(),
+        size_of::<T>(),
+        tx.len()
+    );
     tx[..size_of::<T>()].copy_from_slice(data.as_bytes());
 }
 
@@ -219,6 +361,43 @@ pub(crate) mod tests {
         )
     }
 
+    #[rstest::rstest]
+    #[test]
+    #[case(true, LoopBehavior::Infinite, None)]
+    #[case(true, LoopBehavior::Infinite, Some(TransitionMode::Immediate))]
+    #[case(true, LoopBehavior::Infinite, Some(TransitionMode::Ext))]
+    #[case(false, LoopBehavior::Infinite, Some(TransitionMode::SyncIdx))]
+    #[case(
+        false,
+        LoopBehavior::Infinite,
+        Some(TransitionMode::SysTime(crate::ethercat::DcSysTime::ZERO))
+    )]
+    #[case(
+        false,
+        LoopBehavior::Infinite,
+        Some(TransitionMode::GPIO(crate::firmware::fpga::GPIOIn::I0))
+    )]
+    #[case(true, LoopBehavior::ONCE, Some(TransitionMode::SyncIdx))]
+    fn test_validate_transition(
+        #[case] expect: bool,
+        #[case] loop_behavior: LoopBehavior,
+        #[case] transition_mode: Option<TransitionMode>,
+    ) {
+        let res = validate_transition(Segment::S1, loop_behavior, transition_mode);
+        if expect {
+            assert_eq!(Ok(()), res);
+        } else {
+            assert_eq!(
+                Err(AUTDDriverError::IncompatibleTransitionMode(
+                    Segment::S1,
+                    loop_behavior,
+                    transition_mode.unwrap()
+                )),
+                res
+            );
+        }
+    }
+
     struct OperationMock {
         pub pack_size: usize,
         pub required_size: usize,
@@ -304,6 +483,104 @@ pub(crate) mod tests {
         assert!(OperationHandler::is_done(&op));
     }
 
+    #[rstest::rstest]
+    #[test]
+    #[case::serial(false)]
+    #[case::parallel(true)]
+    fn test_disabled(#[case] parallel: bool) {
+        let mut geometry = Geometry::new((0..3).map(|i| create_device(i, 1)).collect());
+        geometry[1].enable = false;
+
+        let mut op = (0..2)
+            .map(|i| {
+                Some((
+                    OperationMock {
+                        pack_size: 1,
+                        required_size: 2,
+                        num_frames: i + 1,
+                        broken: false,
+                    },
+                    OperationMock {
+                        pack_size: 1,
+                        required_size: 2,
+                        num_frames: i + 1,
+                        broken: false,
+                    },
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let mut tx = vec![TxMessage::new_zeroed(); 3];
+
+        assert!(OperationHandler::pack(&mut op, &geometry, &mut tx, parallel).is_ok());
+        assert_eq!(
+            vec![1, 0, 1],
+            tx.iter().map(|tx| tx.header.msg_id).collect::<Vec<_>>()
+        );
+        assert!(op[0]
+            .as_ref()
+            .is_some_and(|(op1, op2)| op1.is_done() && op2.is_done()));
+        assert!(op[1]
+            .as_ref()
+            .is_some_and(|(op1, op2)| !op1.is_done() && !op2.is_done()));
+
+        assert!(OperationHandler::pack(&mut op, &geometry, &mut tx, parallel).is_ok());
+        assert_eq!(
+            vec![1, 0, 2],
+            tx.iter().map(|tx| tx.header.msg_id).collect::<Vec<_>>()
+        );
+        assert!(OperationHandler::is_done(&op));
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::serial(vec![PackingMetrics { frames: 1, bytes: 4 }, PackingMetrics::default(), PackingMetrics { frames: 2, bytes: 7 }], false, false)]
+    #[case::parallel(vec![PackingMetrics { frames: 1, bytes: 4 }, PackingMetrics::default(), PackingMetrics { frames: 2, bytes: 7 }], false, true)]
+    #[case::serial_all_enabled(vec![PackingMetrics { frames: 1, bytes: 4 }, PackingMetrics { frames: 2, bytes: 7 }, PackingMetrics { frames: 3, bytes: 10 }], true, false)]
+    #[case::parallel_all_enabled(vec![PackingMetrics { frames: 1, bytes: 4 }, PackingMetrics { frames: 2, bytes: 7 }, PackingMetrics { frames: 3, bytes: 10 }], true, true)]
+    fn pack_with_metrics(
+        #[case] expect: Vec<PackingMetrics>,
+        #[case] enable: bool,
+        #[case] parallel: bool,
+    ) {
+        let mut geometry = Geometry::new((0..3).map(|i| create_device(i, 1)).collect());
+        geometry[1].enable = enable;
+
+        let mut op = (0..geometry.num_devices())
+            .map(|i| {
+                Some((
+                    OperationMock {
+                        pack_size: 3,
+                        required_size: 2,
+                        num_frames: i + 1,
+                        broken: false,
+                    },
+                    OperationMock {
+                        pack_size: 1,
+                        required_size: 2,
+                        num_frames: 1,
+                        broken: false,
+                    },
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let mut tx = vec![TxMessage::new_zeroed(); 3];
+        let mut metrics = vec![PackingMetrics::default(); 3];
+
+        while !OperationHandler::is_done(&op) {
+            assert!(OperationHandler::pack_with_metrics(
+                &mut op,
+                &geometry,
+                &mut tx,
+                parallel,
+                &mut metrics
+            )
+            .is_ok());
+        }
+        assert_eq!(expect, metrics);
+    }
+
     #[test]
     fn test_first() {
         let geometry = Geometry::new(vec![Device::new(
@@ -7,7 +7,7 @@ use crate::{
             LoopBehavior, SamplingConfig, Segment, TransitionMode, MOD_BUF_SIZE_MAX,
             MOD_BUF_SIZE_MIN, TRANSITION_MODE_NONE,
         },
-        operation::{Operation, TypeTag},
+        operation::{validate_transition, Operation, TypeTag},
     },
     geometry::Device,
 };
@@ -31,10 +31,10 @@ bitflags::bitflags! {
 
 #[repr(C, align(2))]
 #[derive(IntoBytes, Immutable)]
-struct ModulationHead {
+pub(crate) struct ModulationHead {
     tag: TypeTag,
-    flag: ModulationControlFlags,
-    size: u8,
+    pub(crate) flag: ModulationControlFlags,
+    pub(crate) size: u8,
     transition_mode: u8,
     freq_div: u16,
     rep: u16,
@@ -43,10 +43,10 @@ struct ModulationHead {
 
 #[repr(C, align(2))]
 #[derive(IntoBytes, Immutable)]
-struct ModulationSubseq {
+pub(crate) struct ModulationSubseq {
     tag: TypeTag,
-    flag: ModulationControlFlags,
-    size: u16,
+    pub(crate) flag: ModulationControlFlags,
+    pub(crate) size: u16,
 }
 
 #[derive(new)]
@@ -67,6 +67,8 @@ impl Operation for ModulationOp {
     type Error = AUTDDriverError;
 
     fn pack(&mut self, _: &Device, tx: &mut [u8]) -> Result<usize, AUTDDriverError> {
+        validate_transition(self.segment, self.loop_behavior, self.transition_mode)?;
+
         let is_first = self.sent == 0;
 
         let offset = if is_first {
@@ -115,13 +117,17 @@ impl Operation for ModulationOp {
                     tag: TypeTag::Modulation,
                     flag: ModulationControlFlags::BEGIN | flag,
                     size: send_num as _,
-                    freq_div: self.config.division.get(),
-                    rep: self.loop_behavior.rep(),
+                    freq_div: self.config.division.get().to_le(),
+                    rep: self.loop_behavior.rep().to_le(),
                     transition_mode: self
                         .transition_mode
                         .map(|m| m.mode())
                         .unwrap_or(TRANSITION_MODE_NONE),
-                    transition_value: self.transition_mode.map(TransitionMode::value).unwrap_or(0),
+                    transition_value: self
+                        .transition_mode
+                        .map(TransitionMode::value)
+                        .unwrap_or(0)
+                        .to_le(),
                 },
             );
             Ok(size_of::<ModulationHead>() + ((send_num + 0x01) & !0x1))
@@ -131,7 +137,7 @@ impl Operation for ModulationOp {
                 ModulationSubseq {
                     tag: TypeTag::Modulation,
                     flag,
-                    size: send_num as _,
+                    size: (send_num as u16).to_le(),
                 },
             );
             Ok(size_of::<ModulationSubseq>() + ((send_num + 0x01) & !0x1))
@@ -174,7 +180,7 @@ mod tests {
 
         let buf: Vec<u8> = (0..MOD_SIZE).map(|_| rng.random()).collect();
         let freq_div = rng.random_range(0x0001..=0xFFFF);
-        let loop_behavior = LoopBehavior::Infinite;
+        let loop_behavior = LoopBehavior::Finite(NonZeroU16::new(0x1235).unwrap());
         let rep = loop_behavior.rep();
         let segment = Segment::S0;
         let transition_mode = TransitionMode::SysTime(
@@ -251,7 +257,7 @@ mod tests {
         let mut op = ModulationOp::new(
             Arc::new(buf.clone()),
             SamplingConfig::FREQ_MAX,
-            LoopBehavior::Infinite,
+            LoopBehavior::ONCE,
             Segment::S0,
             Some(TransitionMode::SyncIdx),
         );
@@ -374,6 +380,36 @@ mod tests {
         assert_eq!(expected, send(size));
     }
 
+    #[rstest::rstest]
+    #[test]
+    #[case(Ok(()), LoopBehavior::Infinite, Some(TransitionMode::Ext))]
+    #[case(Ok(()), LoopBehavior::ONCE, Some(TransitionMode::SyncIdx))]
+    #[case(
+        Err(AUTDDriverError::IncompatibleTransitionMode(
+            Segment::S1,
+            LoopBehavior::Infinite,
+            TransitionMode::SyncIdx
+        )),
+        LoopBehavior::Infinite,
+        Some(TransitionMode::SyncIdx)
+    )]
+    fn incompatible_transition_mode(
+        #[case] expected: Result<(), AUTDDriverError>,
+        #[case] loop_behavior: LoopBehavior,
+        #[case] transition_mode: Option<TransitionMode>,
+    ) {
+        let device = create_device(0, NUM_TRANS_IN_UNIT as _);
+        let mut tx = vec![0x00u8; size_of::<ModulationHead>() + 2];
+        let mut op = ModulationOp::new(
+            Arc::new(vec![0x00; 2]),
+            SamplingConfig::FREQ_MAX,
+            loop_behavior,
+            Segment::S1,
+            transition_mode,
+        );
+        assert_eq!(expected, op.pack(&device, &mut tx).map(|_| ()));
+    }
+
     #[rstest::rstest]
     #[test]
     #[case(3)]
@@ -391,7 +427,7 @@ mod tests {
         let mut op = ModulationOp::new(
             Arc::new(buf.clone()),
             SamplingConfig::FREQ_MAX,
-            LoopBehavior::Infinite,
+            LoopBehavior::ONCE,
             Segment::S0,
             Some(TransitionMode::SyncIdx),
         );
@@ -100,7 +100,7 @@ impl Operation for SwapSegmentOp {
                         segment: segment as u8,
                         transition_mode: transition.mode(),
                         __: [0; 5],
-                        transition_value: transition.value(),
+                        transition_value: transition.value().to_le(),
                     },
                 );
                 Ok(size_of::<SwapSegmentTWithTransition>())
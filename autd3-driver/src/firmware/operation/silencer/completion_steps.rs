@@ -49,8 +49,8 @@ impl Operation for SilencerFixedCompletionStepsOp {
                     SilencerTarget::Intensity => SilencerControlFlags::NONE,
                     SilencerTarget::PulseWidth => SilencerControlFlags::PULSE_WIDTH,
                 },
-                value_intensity: self.intensity.get(),
-                value_phase: self.phase.get(),
+                value_intensity: self.intensity.get().to_le(),
+                value_phase: self.phase.get().to_le(),
             },
         );
 
@@ -42,7 +42,7 @@ impl Operation for SilencerFixedCompletionTimeOp {
         let validate = |value: Duration| {
             const NANOSEC: u128 = 1_000_000_000;
             let v = value.as_nanos() * ultrasound_freq().hz() as u128;
-            let v = if v % NANOSEC == 0 {
+            let v = if v.is_multiple_of(NANOSEC) {
                 v / NANOSEC
             } else {
                 return Err(AUTDDriverError::InvalidSilencerCompletionTime(value));
@@ -67,8 +67,8 @@ impl Operation for SilencerFixedCompletionTimeOp {
                     SilencerTarget::Intensity => SilencerControlFlags::NONE,
                     SilencerTarget::PulseWidth => SilencerControlFlags::PULSE_WIDTH,
                 },
-                value_intensity: step_intensity,
-                value_phase: step_phase,
+                value_intensity: step_intensity.to_le(),
+                value_phase: step_phase.to_le(),
             },
         );
 
@@ -8,7 +8,7 @@ use crate::{
             LoopBehavior, STMFocus, SamplingConfig, Segment, TransitionMode, FOCI_STM_BUF_SIZE_MAX,
             FOCI_STM_FOCI_NUM_MAX, STM_BUF_SIZE_MIN, TRANSITION_MODE_NONE,
         },
-        operation::{write_to_tx, Operation, TypeTag},
+        operation::{validate_transition, write_to_tx, Operation, TypeTag},
     },
     geometry::Device,
 };
@@ -33,11 +33,11 @@ bitflags::bitflags! {
 
 #[repr(C, align(2))]
 #[derive(PartialEq, Debug, IntoBytes, Immutable)]
-struct FociSTMHead {
+pub(crate) struct FociSTMHead {
     tag: TypeTag,
     flag: FociSTMControlFlags,
     send_num: u8,
-    segment: u8,
+    pub(crate) segment: u8,
     transition_mode: u8,
     num_foci: u8,
     sound_speed: u16,
@@ -49,11 +49,11 @@ struct FociSTMHead {
 
 #[repr(C, align(2))]
 #[derive(IntoBytes, Immutable)]
-struct FociSTMSubseq {
+pub(crate) struct FociSTMSubseq {
     tag: TypeTag,
     flag: FociSTMControlFlags,
     send_num: u8,
-    segment: u8,
+    pub(crate) segment: u8,
 }
 
 /// A trait to generate a [`ControlPoints`] for  [`FociSTM`].
@@ -88,6 +88,8 @@ impl<const N: usize, Iterator: FociSTMIterator<N>> Operation for FociSTMOp<N, It
             return Err(AUTDDriverError::FociSTMPointSizeOutOfRange(self.size));
         }
 
+        validate_transition(self.segment, self.loop_behavior, self.transition_mode)?;
+
         let is_first = self.sent == 0;
 
         let send_num = {
@@ -146,12 +148,16 @@ impl<const N: usize, Iterator: FociSTMIterator<N>> Operation for FociSTMOp<N, It
                         .transition_mode
                         .map(|m| m.mode())
                         .unwrap_or(TRANSITION_MODE_NONE),
-                    transition_value: self.transition_mode.map(TransitionMode::value).unwrap_or(0),
+                    transition_value: self
+                        .transition_mode
+                        .map(TransitionMode::value)
+                        .unwrap_or(0)
+                        .to_le(),
                     send_num: send_num as _,
                     num_foci: N as u8,
-                    freq_div: self.config.division.get(),
-                    sound_speed: (device.sound_speed / METER * 64.0).round() as u16,
-                    rep: self.loop_behavior.rep(),
+                    freq_div: self.config.division.get().to_le(),
+                    sound_speed: ((device.sound_speed / METER * 64.0).round() as u16).to_le(),
+                    rep: self.loop_behavior.rep().to_le(),
                     __: [0; 4],
                 },
             );
@@ -238,7 +244,7 @@ mod tests {
                 intensity: EmitIntensity(rng.random::<u8>()),
             })
             .collect();
-        let rep = 0xFFFF;
+        let rep = 0x1234;
         let segment = Segment::S0;
         let freq_div = rng.random_range(0x0001..=0xFFFF);
         let transition_value = 0x0123456789ABCDEF;
@@ -256,7 +262,7 @@ mod tests {
             },
             FOCI_STM_SIZE,
             SamplingConfig::new(freq_div).unwrap(),
-            LoopBehavior::Infinite,
+            LoopBehavior::Finite(NonZeroU16::new(rep + 1).unwrap()),
             segment,
             Some(transition_mode),
         );
@@ -340,7 +346,7 @@ mod tests {
                 intensity: EmitIntensity(rng.random::<u8>()),
             })
             .collect();
-        let rep = 0xFFFF;
+        let rep = 0x1234;
         let segment = Segment::S0;
         let freq_div = rng.random_range(0x0001..=0xFFFF);
         let transition_value = 0x0123456789ABCDEF;
@@ -358,7 +364,7 @@ mod tests {
             },
             FOCI_STM_SIZE,
             SamplingConfig::new(freq_div).unwrap(),
-            LoopBehavior::Infinite,
+            LoopBehavior::Finite(NonZeroU16::new(rep + 1).unwrap()),
             segment,
             Some(transition_mode),
         );
@@ -621,7 +627,7 @@ mod tests {
             },
             FOCI_STM_SIZE,
             SamplingConfig::FREQ_MAX,
-            LoopBehavior::Infinite,
+            LoopBehavior::ONCE,
             Segment::S0,
             Some(TransitionMode::SyncIdx),
         );
@@ -650,7 +656,7 @@ mod tests {
             },
             n,
             SamplingConfig::FREQ_MAX,
-            LoopBehavior::Infinite,
+            LoopBehavior::ONCE,
             Segment::S0,
             Some(TransitionMode::SyncIdx),
         );
@@ -660,6 +666,44 @@ mod tests {
         assert_eq!(op.pack(&device, &mut tx).map(|_| ()), expected);
     }
 
+    #[rstest::rstest]
+    #[test]
+    #[case(Ok(()), LoopBehavior::Infinite, Some(TransitionMode::Ext))]
+    #[case(Ok(()), LoopBehavior::ONCE, Some(TransitionMode::SyncIdx))]
+    #[case(
+        Err(AUTDDriverError::IncompatibleTransitionMode(
+            Segment::S1,
+            LoopBehavior::Infinite,
+            TransitionMode::SyncIdx
+        )),
+        LoopBehavior::Infinite,
+        Some(TransitionMode::SyncIdx)
+    )]
+    fn test_incompatible_transition_mode(
+        #[case] expected: Result<(), AUTDDriverError>,
+        #[case] loop_behavior: LoopBehavior,
+        #[case] transition_mode: Option<TransitionMode>,
+    ) {
+        let device = create_device(0, NUM_TRANS_IN_UNIT);
+
+        let mut op = FociSTMOp::new(
+            TestIterator {
+                points: (0..2)
+                    .map(|_| ControlPoint::from(Point3::origin()).into())
+                    .collect::<VecDeque<_>>(),
+            },
+            2,
+            SamplingConfig::FREQ_MAX,
+            loop_behavior,
+            Segment::S1,
+            transition_mode,
+        );
+
+        let mut tx = vec![0x00u8; size_of::<FociSTMHead>() + 2 * size_of::<STMFocus>()];
+
+        assert_eq!(expected, op.pack(&device, &mut tx).map(|_| ()));
+    }
+
     #[test]
     fn test_foci_out_of_range() {
         let device = create_device(0, NUM_TRANS_IN_UNIT);
@@ -673,7 +717,7 @@ mod tests {
                 },
                 2,
                 SamplingConfig::FREQ_MAX,
-                LoopBehavior::Infinite,
+                LoopBehavior::ONCE,
                 Segment::S0,
                 Some(TransitionMode::SyncIdx),
             );
@@ -693,7 +737,7 @@ mod tests {
                 },
                 2,
                 SamplingConfig::FREQ_MAX,
-                LoopBehavior::Infinite,
+                LoopBehavior::ONCE,
                 Segment::S0,
                 Some(TransitionMode::SyncIdx),
             );
@@ -714,7 +758,7 @@ mod tests {
                 },
                 2,
                 SamplingConfig::FREQ_MAX,
-                LoopBehavior::Infinite,
+                LoopBehavior::ONCE,
                 Segment::S0,
                 Some(TransitionMode::SyncIdx),
             );
@@ -739,7 +783,7 @@ mod tests {
                 },
                 2,
                 SamplingConfig::FREQ_MAX,
-                LoopBehavior::Infinite,
+                LoopBehavior::ONCE,
                 Segment::S0,
                 Some(TransitionMode::SyncIdx),
             );
@@ -10,7 +10,7 @@ use crate::{
             Drive, LoopBehavior, SamplingConfig, Segment, TransitionMode, GAIN_STM_BUF_SIZE_MAX,
             STM_BUF_SIZE_MIN, TRANSITION_MODE_NONE,
         },
-        operation::{write_to_tx, Operation, TypeTag},
+        operation::{validate_transition, write_to_tx, Operation, TypeTag},
     },
     geometry::Device,
 };
@@ -41,7 +41,7 @@ struct PhaseFull {
     phase_1: u8,
 }
 
-#[bitfield_struct::bitfield(u16)]
+#[bitfield_struct::bitfield(u16, repr = u16, from = u16::from_le, into = u16::to_le)]
 #[derive(IntoBytes, Immutable, FromBytes, KnownLayout)]
 struct PhaseHalf {
     #[bits(4)]
@@ -56,9 +56,9 @@ struct PhaseHalf {
 
 #[repr(C, align(2))]
 #[derive(IntoBytes, Immutable)]
-struct GainSTMHead {
+pub(crate) struct GainSTMHead {
     tag: TypeTag,
-    flag: GainSTMControlFlags,
+    pub(crate) flag: GainSTMControlFlags,
     mode: GainSTMMode,
     transition_mode: u8,
     freq_div: u16,
@@ -68,9 +68,9 @@ struct GainSTMHead {
 
 #[repr(C, align(2))]
 #[derive(IntoBytes, Immutable)]
-struct GainSTMSubseq {
+pub(crate) struct GainSTMSubseq {
     tag: TypeTag,
-    flag: GainSTMControlFlags,
+    pub(crate) flag: GainSTMControlFlags,
 }
 
 /// A trait to iterate a [`GainCalculator`] for [`GainSTM`].
@@ -116,6 +116,8 @@ impl<G: GainCalculator, Iterator: GainSTMIterator<Calculator = G>> Operation
             return Err(AUTDDriverError::GainSTMSizeOutOfRange(self.size));
         }
 
+        validate_transition(self.segment, self.loop_behavior, self.transition_mode)?;
+
         let is_first = self.sent == 0;
 
         let send = {
@@ -129,12 +131,13 @@ impl<G: GainCalculator, Iterator: GainSTMIterator<Calculator = G>> Operation
             match self.mode {
                 GainSTMMode::PhaseIntensityFull => {
                     if let Some(g) = self.iter.next() {
-                        tx[offset..]
-                            .chunks_mut(size_of::<Drive>())
-                            .zip(device.iter())
-                            .for_each(|(dst, tr)| {
-                                write_to_tx(dst, g.calc(tr));
-                            });
+                        g.calc_into(
+                            device,
+                            <[Drive]>::mut_from_bytes(
+                                &mut tx[offset..offset + device.len() * size_of::<Drive>()],
+                            )
+                            .unwrap(),
+                        );
                         send += 1;
                     }
                 }
@@ -200,9 +203,13 @@ impl<G: GainCalculator, Iterator: GainSTMIterator<Calculator = G>> Operation
                         .transition_mode
                         .map(|m| m.mode())
                         .unwrap_or(TRANSITION_MODE_NONE),
-                    transition_value: self.transition_mode.map(TransitionMode::value).unwrap_or(0),
-                    freq_div: self.config.division.get(),
-                    rep: self.loop_behavior.rep(),
+                    transition_value: self
+                        .transition_mode
+                        .map(TransitionMode::value)
+                        .unwrap_or(0)
+                        .to_le(),
+                    freq_div: self.config.division.get().to_le(),
+                    rep: self.loop_behavior.rep().to_le(),
                 },
             );
         } else {
@@ -693,6 +700,42 @@ mod tests {
         }
     }
 
+    #[rstest::rstest]
+    #[test]
+    #[case(Ok(()), LoopBehavior::Infinite, Some(TransitionMode::Ext))]
+    #[case(Ok(()), LoopBehavior::ONCE, Some(TransitionMode::SyncIdx))]
+    #[case(
+        Err(AUTDDriverError::IncompatibleTransitionMode(
+            Segment::S1,
+            LoopBehavior::Infinite,
+            TransitionMode::SyncIdx
+        )),
+        LoopBehavior::Infinite,
+        Some(TransitionMode::SyncIdx)
+    )]
+    fn incompatible_transition_mode(
+        #[case] expected: Result<(), AUTDDriverError>,
+        #[case] loop_behavior: LoopBehavior,
+        #[case] transition_mode: Option<TransitionMode>,
+    ) {
+        let device = create_device(0, NUM_TRANS_IN_UNIT as _);
+        let mut tx = vec![0x00u8; size_of::<GainSTMHead>() + NUM_TRANS_IN_UNIT * 2];
+        let mut op = GainSTMOp::new(
+            STMIterator {
+                data: (0..2)
+                    .map(|_| vec![Drive::NULL; NUM_TRANS_IN_UNIT])
+                    .collect(),
+            },
+            2,
+            GainSTMMode::PhaseIntensityFull,
+            SamplingConfig::FREQ_MAX,
+            loop_behavior,
+            Segment::S1,
+            transition_mode,
+        );
+        assert_eq!(expected, op.pack(&device, &mut tx).map(|_| ()));
+    }
+
     #[rstest::rstest]
     #[test]
     #[case(Err(AUTDDriverError::GainSTMSizeOutOfRange(0)), 0)]
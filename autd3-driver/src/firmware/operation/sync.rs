@@ -38,8 +38,8 @@ impl Operation for SyncOp {
             Sync {
                 tag: TypeTag::Sync,
                 __: 0,
-                ufreq_mult: mult as _,
-                base_cnt: base_cnt as _,
+                ufreq_mult: (mult as u16).to_le(),
+                base_cnt: (base_cnt as u16).to_le(),
             },
         );
 
@@ -1,12 +1,24 @@
 use derive_more::Display;
 use itertools::Itertools;
 
+use crate::error::AUTDDriverError;
+
+use super::{
+    fpga::{
+        LoopBehavior, Segment, TransitionMode, FOCI_STM_BUF_SIZE_MAX, FOCI_STM_FOCI_NUM_MAX,
+        GAIN_STM_BUF_SIZE_MAX, MOD_BUF_SIZE_MAX,
+    },
+    operation::validate_transition,
+};
+
 /// Major version number.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Major(pub u8);
 
 /// Minor version number.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Minor(pub u8);
 
 fn version_map(major: Major, minor: Minor) -> String {
@@ -31,6 +43,7 @@ fn version_map(major: Major, minor: Minor) -> String {
 
 /// FPGA firmware version.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FPGAVersion {
     #[doc(hidden)]
     pub major: Major,
@@ -55,6 +68,11 @@ impl FPGAVersion {
     pub const fn is_emulator(&self) -> bool {
         (self.function_bits & Self::ENABLED_EMULATOR_BIT) == Self::ENABLED_EMULATOR_BIT
     }
+
+    /// Gets the [`FirmwareCapabilities`] of the FPGA firmware.
+    pub const fn caps(&self) -> FirmwareCapabilities {
+        FirmwareCapabilities { fpga: *self }
+    }
 }
 
 impl std::fmt::Display for FPGAVersion {
@@ -76,6 +94,7 @@ impl std::fmt::Display for FPGAVersion {
 
 /// CPU firmware version.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[display("{}", version_map(self.major, self.minor))]
 pub struct CPUVersion {
     #[doc(hidden)]
@@ -86,6 +105,7 @@ pub struct CPUVersion {
 
 /// Firmware version.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[display(
     "{}: CPU = {}, FPGA = {}",
     idx,
@@ -119,6 +139,144 @@ impl FirmwareVersion {
             Self::LATEST_VERSION_NUM_MINOR,
         )
     }
+
+    /// Gets the [`FirmwareCapabilities`] of the FPGA firmware.
+    pub const fn caps(&self) -> FirmwareCapabilities {
+        self.fpga.caps()
+    }
+}
+
+/// The capabilities of the device derived from the FPGA firmware version.
+///
+/// The features are reported as supported only for the known versions, i.e., the versions up to [`FirmwareVersion::latest`].
+/// The buffer limits are known only for the latest major version, which this library drives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FirmwareCapabilities {
+    fpga: FPGAVersion,
+}
+
+impl FirmwareCapabilities {
+    const V6: Major = Major(0x8F);
+    const V8: Major = Major(0x92);
+    const V9: Major = Major(0xA0);
+
+    const fn since(&self, major: Major) -> bool {
+        major.0 <= self.fpga.major.0
+            && self.fpga.major.0 <= FirmwareVersion::LATEST_VERSION_NUM_MAJOR.0
+    }
+
+    const fn is_latest(&self) -> bool {
+        self.since(FirmwareVersion::LATEST_VERSION_NUM_MAJOR)
+    }
+
+    /// Returns `true` if the firmware is the emulator.
+    pub const fn is_emulator(&self) -> bool {
+        self.fpga.is_emulator()
+    }
+
+    /// Returns `true` if the ultrasound frequency can be changed.
+    pub const fn supports_dynamic_freq(&self) -> bool {
+        self.fpga.dynamic_freq_enabled()
+    }
+
+    /// Returns `true` if the firmware supports [`PulseWidthEncoder`].
+    ///
+    /// [`PulseWidthEncoder`]: crate::datagram::PulseWidthEncoder
+    pub const fn supports_pulse_width_encoder(&self) -> bool {
+        self.since(Self::V6)
+    }
+
+    /// Returns `true` if the firmware supports [`Segment`] and [`TransitionMode`].
+    ///
+    /// [`Segment`]: autd3_core::datagram::Segment
+    /// [`TransitionMode`]: autd3_core::datagram::TransitionMode
+    pub const fn supports_segment(&self) -> bool {
+        self.since(Self::V6)
+    }
+
+    /// Validates the combination of `segment`, `loop_behavior`, and `transition_mode` for the firmware.
+    ///
+    /// Returns [`AUTDDriverError::TransitionNotSupported`] with the minimum firmware version if the firmware does not support [`Segment::S1`] or [`TransitionMode`], and [`AUTDDriverError::IncompatibleTransitionMode`] if `transition_mode` cannot be used with `loop_behavior`.
+    pub fn validate_transition(
+        &self,
+        segment: Segment,
+        loop_behavior: LoopBehavior,
+        transition_mode: Option<TransitionMode>,
+    ) -> Result<(), AUTDDriverError> {
+        if (segment != Segment::S0 || transition_mode.is_some()) && !self.supports_segment() {
+            return Err(AUTDDriverError::TransitionNotSupported(
+                segment,
+                transition_mode,
+                version_map(Self::V6, Minor(0)),
+            ));
+        }
+        validate_transition(segment, loop_behavior, transition_mode)
+    }
+
+    /// Returns `true` if the firmware supports [`SilencerTarget`].
+    ///
+    /// [`SilencerTarget`]: crate::firmware::fpga::SilencerTarget
+    pub const fn supports_silencer_target(&self) -> bool {
+        self.since(Self::V9)
+    }
+
+    /// Returns `true` if the firmware supports [`PhaseCorrection`].
+    ///
+    /// [`PhaseCorrection`]: crate::datagram::PhaseCorrection
+    pub const fn supports_phase_correction(&self) -> bool {
+        self.is_latest()
+    }
+
+    /// Returns `true` if the firmware supports the configuration of the GPIO outputs.
+    pub const fn supports_gpio_output(&self) -> bool {
+        self.is_latest()
+    }
+
+    /// The maximum number of foci of each pattern of [`FociSTM`]. `None` if the firmware is unknown.
+    ///
+    /// [`FociSTM`]: crate::datagram::FociSTM
+    pub const fn max_foci_num(&self) -> Option<usize> {
+        if self.since(Self::V8) {
+            Some(FOCI_STM_FOCI_NUM_MAX)
+        } else if self.since(Self::V6) {
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// The maximum buffer size of [`Modulation`]. `None` if the limit of the firmware is unknown.
+    ///
+    /// [`Modulation`]: autd3_core::modulation::Modulation
+    pub const fn max_modulation_size(&self) -> Option<usize> {
+        if self.is_latest() {
+            Some(MOD_BUF_SIZE_MAX)
+        } else {
+            None
+        }
+    }
+
+    /// The maximum buffer size of [`FociSTM`]. `None` if the limit of the firmware is unknown.
+    ///
+    /// [`FociSTM`]: crate::datagram::FociSTM
+    pub const fn max_foci_stm_size(&self) -> Option<usize> {
+        if self.is_latest() {
+            Some(FOCI_STM_BUF_SIZE_MAX)
+        } else {
+            None
+        }
+    }
+
+    /// The maximum buffer size of [`GainSTM`]. `None` if the limit of the firmware is unknown.
+    ///
+    /// [`GainSTM`]: crate::datagram::GainSTM
+    pub const fn max_gain_stm_size(&self) -> Option<usize> {
+        if self.is_latest() {
+            Some(GAIN_STM_BUF_SIZE_MAX)
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -217,6 +375,105 @@ mod tests {
         );
     }
 
+    #[rstest::rstest]
+    #[case(false, false, false, None, None, Major(0x8E))]
+    #[case(true, false, false, Some(1), None, Major(0x8F))]
+    #[case(true, false, false, Some(8), None, Major(0x92))]
+    #[case(true, true, false, Some(8), None, Major(0xA1))]
+    #[case(
+        true,
+        true,
+        true,
+        Some(8),
+        Some(MOD_BUF_SIZE_MAX),
+        FirmwareVersion::LATEST_VERSION_NUM_MAJOR
+    )]
+    #[case(false, false, false, None, None, Major(0xA3))]
+    #[test]
+    fn caps(
+        #[case] pulse_width_encoder: bool,
+        #[case] silencer_target: bool,
+        #[case] latest: bool,
+        #[case] max_foci_num: Option<usize>,
+        #[case] max_modulation_size: Option<usize>,
+        #[case] major: Major,
+    ) {
+        let caps = FirmwareVersion {
+            idx: 0,
+            cpu: CPUVersion {
+                major,
+                minor: Minor(0),
+            },
+            fpga: FPGAVersion {
+                major,
+                minor: Minor(0),
+                function_bits: 0,
+            },
+        }
+        .caps();
+        assert_eq!(pulse_width_encoder, caps.supports_pulse_width_encoder());
+        assert_eq!(pulse_width_encoder, caps.supports_segment());
+        assert_eq!(silencer_target, caps.supports_silencer_target());
+        assert_eq!(latest, caps.supports_phase_correction());
+        assert_eq!(latest, caps.supports_gpio_output());
+        assert_eq!(max_foci_num, caps.max_foci_num());
+        assert_eq!(max_modulation_size, caps.max_modulation_size());
+        assert_eq!(
+            latest.then_some(FOCI_STM_BUF_SIZE_MAX),
+            caps.max_foci_stm_size()
+        );
+        assert_eq!(
+            latest.then_some(GAIN_STM_BUF_SIZE_MAX),
+            caps.max_gain_stm_size()
+        );
+        assert!(!caps.is_emulator());
+        assert!(!caps.supports_dynamic_freq());
+    }
+
+    #[rstest::rstest]
+    #[case(Ok(()), Segment::S0, None, Major(0x8E))]
+    #[case(
+        Err(AUTDDriverError::TransitionNotSupported(Segment::S1, None, "v6.0.0".to_string())),
+        Segment::S1,
+        None,
+        Major(0x8E)
+    )]
+    #[case(
+        Err(AUTDDriverError::TransitionNotSupported(Segment::S0, Some(TransitionMode::Immediate), "v6.0.0".to_string())),
+        Segment::S0,
+        Some(TransitionMode::Immediate),
+        Major(0x8E)
+    )]
+    #[case(Ok(()), Segment::S1, Some(TransitionMode::Immediate), Major(0x8F))]
+    #[case(
+        Err(AUTDDriverError::IncompatibleTransitionMode(
+            Segment::S1,
+            LoopBehavior::Infinite,
+            TransitionMode::SyncIdx
+        )),
+        Segment::S1,
+        Some(TransitionMode::SyncIdx),
+        FirmwareVersion::LATEST_VERSION_NUM_MAJOR
+    )]
+    #[test]
+    fn caps_validate_transition(
+        #[case] expect: Result<(), AUTDDriverError>,
+        #[case] segment: Segment,
+        #[case] transition_mode: Option<TransitionMode>,
+        #[case] major: Major,
+    ) {
+        let fpga = FPGAVersion {
+            major,
+            minor: Minor(0),
+            function_bits: 0,
+        };
+        assert_eq!(
+            expect,
+            fpga.caps()
+                .validate_transition(segment, LoopBehavior::Infinite, transition_mode)
+        );
+    }
+
     #[rstest::rstest]
     #[test]
     #[case(
@@ -282,4 +539,28 @@ mod tests {
     fn display(#[case] expected: &str, #[case] info: FirmwareVersion) {
         assert_eq!(expected, format!("{}", info));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() -> anyhow::Result<()> {
+        let version = FirmwareVersion {
+            idx: 1,
+            cpu: CPUVersion {
+                major: Major(0xA2),
+                minor: Minor(0x01),
+            },
+            fpga: FPGAVersion {
+                major: Major(0xA2),
+                minor: Minor(0x01),
+                function_bits: FPGAVersion::ENABLED_EMULATOR_BIT,
+            },
+        };
+        let json = serde_json::to_string(&version)?;
+        assert_eq!(
+            r#"{"idx":1,"cpu":{"major":162,"minor":1},"fpga":{"major":162,"minor":1,"function_bits":128}}"#,
+            json
+        );
+        assert_eq!(version, serde_json::from_str(&json)?);
+        Ok(())
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 use autd3_driver::{
     ethercat::{DcSysTime, EC_OUTPUT_FRAME_SIZE},
     firmware::cpu::{Header, RxMessage, TxMessage},
@@ -7,7 +9,7 @@ use getset::{CopyGetters, Getters, MutGetters};
 
 use crate::fpga::emulator::FPGAEmulator;
 
-use super::params::*;
+use super::{params::*, TimingModel};
 
 #[derive(CopyGetters, Getters, MutGetters)]
 pub struct CPUEmulator {
@@ -52,6 +54,9 @@ pub struct CPUEmulator {
     pub(crate) dc_sys_time: DcSysTime,
     #[getset(get_copy = "pub")]
     pub(crate) port_a_podr: u8,
+    #[getset(get = "pub")]
+    pub(crate) timing_model: Option<TimingModel>,
+    pub(crate) pending: VecDeque<(DcSysTime, TxMessage)>,
 }
 
 impl CPUEmulator {
@@ -90,6 +95,8 @@ impl CPUEmulator {
             stm_rep: [0xFFFF, 0xFFFF],
             mod_rep: [0xFFFF, 0xFFFF],
             port_a_podr: 0x00,
+            timing_model: None,
+            pending: VecDeque::new(),
         };
         s.init();
         s
@@ -100,7 +107,33 @@ impl CPUEmulator {
     }
 
     pub fn send(&mut self, tx: &[TxMessage]) {
-        self.ecat_recv(&tx[self.idx]);
+        let tx = &tx[self.idx];
+        let Some(timing_model) = &self.timing_model else {
+            self.ecat_recv(tx);
+            return;
+        };
+        let (busy_until, last_msg_id) = self
+            .pending
+            .back()
+            .map_or((self.dc_sys_time, self.last_msg_id), |(t, tx)| {
+                (*t, tx.header.msg_id)
+            });
+        if last_msg_id == tx.header.msg_id {
+            return;
+        }
+        let completion_time = timing_model.completion_time(self.dc_sys_time, busy_until, tx);
+        self.pending.push_back((completion_time, tx.clone()));
+    }
+
+    /// Sets the timing model. The frames waiting for processing are discarded.
+    pub fn set_timing_model(&mut self, timing_model: Option<TimingModel>) {
+        self.timing_model = timing_model;
+        self.pending.clear();
+    }
+
+    /// Returns the number of frames waiting for processing with the timing model.
+    pub fn num_pending_frames(&self) -> usize {
+        self.pending.len()
     }
 
     pub fn init(&mut self) {
@@ -115,6 +148,12 @@ impl CPUEmulator {
     }
 
     pub fn update_with_sys_time(&mut self, sys_time: DcSysTime) {
+        while self.pending.front().is_some_and(|(t, _)| *t <= sys_time) {
+            let (t, tx) = self.pending.pop_front().unwrap();
+            self.fpga.update_with_sys_time(t);
+            self.dc_sys_time = t;
+            self.ecat_recv(&tx);
+        }
         self.fpga.update_with_sys_time(sys_time);
         self.read_fpga_state();
         self.dc_sys_time = sys_time;
@@ -132,6 +171,7 @@ impl CPUEmulator {
 
 impl CPUEmulator {
     pub(crate) const fn cast<T>(data: &[u8]) -> T {
+        debug_assert!(size_of::<T>() <= data.len());
         unsafe { (data.as_ptr() as *const T).read_unaligned() }
     }
 
@@ -153,7 +193,7 @@ impl CPUEmulator {
         let mut addr = Self::get_addr(select, addr_base);
         let mut src = data;
         (0..size).for_each(|_| unsafe {
-            self.fpga.write(addr, src.read());
+            self.fpga.write(addr, u16::from_le(src.read()));
             addr += 1;
             src = src.add(1);
         })
@@ -230,9 +270,10 @@ impl CPUEmulator {
             return;
         }
 
-        if header.slot_2_offset != 0 {
+        if u16::from_le(header.slot_2_offset) != 0 {
             self.ack = self.handle_payload(
-                &data[std::mem::size_of::<Header>() + header.slot_2_offset as usize..],
+                &data
+                    [std::mem::size_of::<Header>() + u16::from_le(header.slot_2_offset) as usize..],
             );
             if (self.ack & ERR_BIT) != 0 {
                 return;
@@ -286,4 +327,44 @@ mod tests {
         cpu.reads_fpga_state = true;
         assert!(cpu.should_update());
     }
+
+    #[test]
+    fn timing_model() {
+        use std::{collections::HashMap, time::Duration};
+
+        use zerocopy::FromZeros;
+
+        let mut cpu = CPUEmulator::new(0, 249);
+        cpu.set_timing_model(Some(TimingModel {
+            ecat_cycle: Duration::from_millis(1),
+            default_delay: Duration::from_micros(100),
+            delays: HashMap::from([(TAG_FORCE_FAN, Duration::from_millis(2))]),
+        }));
+        cpu.update_with_sys_time(DcSysTime::ZERO + Duration::from_micros(500));
+
+        let frame = |msg_id, tag| {
+            let mut tx = TxMessage::new_zeroed();
+            tx.header.msg_id = msg_id;
+            tx.payload_mut()[0] = tag;
+            vec![tx]
+        };
+
+        cpu.send(&frame(0x01, TAG_FORCE_FAN));
+        cpu.send(&frame(0x01, TAG_FORCE_FAN));
+        cpu.send(&frame(0x02, TAG_READS_FPGA_STATE));
+        assert_eq!(2, cpu.num_pending_frames());
+
+        cpu.update_with_sys_time(DcSysTime::ZERO + Duration::from_micros(2999));
+        assert_ne!(0x01, cpu.rx().ack());
+        cpu.update_with_sys_time(DcSysTime::ZERO + Duration::from_micros(3000));
+        assert_eq!(0x01, cpu.rx().ack());
+        assert_eq!(1, cpu.num_pending_frames());
+        cpu.update_with_sys_time(DcSysTime::ZERO + Duration::from_micros(3100));
+        assert_eq!(0x02, cpu.rx().ack());
+        assert_eq!(0, cpu.num_pending_frames());
+
+        cpu.set_timing_model(None);
+        cpu.send(&frame(0x03, TAG_FORCE_FAN));
+        assert_eq!(0x03, cpu.rx().ack());
+    }
 }
@@ -1,3 +1,6 @@
 pub mod emulator;
 mod operation;
 pub mod params;
+mod timing;
+
+pub use timing::TimingModel;
@@ -12,7 +12,7 @@ impl CPUEmulator {
     pub(crate) unsafe fn configure_clk(&mut self, data: &[u8]) -> u8 {
         let d = Self::cast::<Clk>(data);
 
-        let size = d.size;
+        let size = u16::from_le(d.size);
 
         if (d.flag & CLK_FLAG_BEGIN) == CLK_FLAG_BEGIN {
             self.clk_write = 0;
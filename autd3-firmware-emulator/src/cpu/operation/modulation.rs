@@ -49,10 +49,11 @@ impl CPUEmulator {
             return ERR_MISS_TRANSITION_TIME;
         }
         self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_MOD_TRANSITION_MODE, mode as _);
+        let value_le = value.to_le();
         self.bram_cpy(
             BRAM_SELECT_CONTROLLER,
             ADDR_MOD_TRANSITION_VALUE_0,
-            &raw const value as _,
+            &raw const value_le as _,
             std::mem::size_of::<u64>() >> 1,
         );
         self.set_and_wait_update(CTL_FLAG_MOD_SET);
@@ -82,7 +83,7 @@ impl CPUEmulator {
             if Self::validate_transition_mode(
                 self.mod_segment,
                 segment,
-                d.head.rep,
+                u16::from_le(d.head.rep),
                 d.head.transition_mode,
             ) {
                 return ERR_INVALID_TRANSITION_MODE;
@@ -90,7 +91,7 @@ impl CPUEmulator {
 
             if self.validate_silencer_settings(
                 self.stm_freq_div[self.stm_segment as usize],
-                d.head.freq_div,
+                u16::from_le(d.head.freq_div),
             ) {
                 return ERR_INVALID_SILENCER_SETTING;
             }
@@ -98,19 +99,35 @@ impl CPUEmulator {
             if d.head.transition_mode != TRANSITION_MODE_NONE {
                 self.mod_segment = segment;
             }
-            self.mod_rep[segment as usize] = d.head.rep;
-            self.mod_freq_div[segment as usize] = d.head.freq_div;
+            self.mod_rep[segment as usize] = u16::from_le(d.head.rep);
+            self.mod_freq_div[segment as usize] = u16::from_le(d.head.freq_div);
             self.mod_transition_mode = d.head.transition_mode;
-            self.mod_transition_value = d.head.transition_value;
+            self.mod_transition_value = u64::from_le(d.head.transition_value);
 
             match segment {
                 0 => {
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_MOD_FREQ_DIV0, d.head.freq_div);
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_MOD_REP0, d.head.rep);
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_MOD_FREQ_DIV0,
+                        u16::from_le(d.head.freq_div),
+                    );
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_MOD_REP0,
+                        u16::from_le(d.head.rep),
+                    );
                 }
                 1 => {
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_MOD_FREQ_DIV1, d.head.freq_div);
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_MOD_REP1, d.head.rep);
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_MOD_FREQ_DIV1,
+                        u16::from_le(d.head.freq_div),
+                    );
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_MOD_REP1,
+                        u16::from_le(d.head.rep),
+                    );
                 }
                 _ => unreachable!(),
             }
@@ -119,7 +136,7 @@ impl CPUEmulator {
 
             data[std::mem::size_of::<ModulationHead>()..].as_ptr() as *const u16
         } else {
-            write = d.subseq.size;
+            write = u16::from_le(d.subseq.size);
 
             data[std::mem::size_of::<ModulationSubseq>()..].as_ptr() as *const u16
         };
@@ -183,7 +200,11 @@ impl CPUEmulator {
         }
 
         self.mod_segment = d.segment;
-        self.mod_segment_update(d.segment, d.transition_mode, d.transition_value)
+        self.mod_segment_update(
+            d.segment,
+            d.transition_mode,
+            u64::from_le(d.transition_value),
+        )
     }
 }
 
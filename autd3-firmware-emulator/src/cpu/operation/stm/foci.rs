@@ -56,14 +56,14 @@ impl CPUEmulator {
             if Self::validate_transition_mode(
                 self.stm_segment,
                 segment,
-                d.head.rep,
+                u16::from_le(d.head.rep),
                 d.head.transition_mode,
             ) {
                 return ERR_INVALID_TRANSITION_MODE;
             }
 
             if self.validate_silencer_settings(
-                d.head.freq_div,
+                u16::from_le(d.head.freq_div),
                 self.mod_freq_div[self.mod_segment as usize],
             ) {
                 return ERR_INVALID_SILENCER_SETTING;
@@ -73,22 +73,30 @@ impl CPUEmulator {
                 self.stm_segment = segment;
             }
             self.stm_cycle[segment as usize] = 0;
-            self.stm_rep[segment as usize] = d.head.rep;
+            self.stm_rep[segment as usize] = u16::from_le(d.head.rep);
             self.stm_transition_mode = d.head.transition_mode;
-            self.stm_transition_value = d.head.transition_value;
-            self.stm_freq_div[segment as usize] = d.head.freq_div;
+            self.stm_transition_value = u64::from_le(d.head.transition_value);
+            self.stm_freq_div[segment as usize] = u16::from_le(d.head.freq_div);
             self.num_foci = d.head.num_foci;
 
             match segment {
                 0 => {
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_FREQ_DIV0, d.head.freq_div);
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_STM_FREQ_DIV0,
+                        u16::from_le(d.head.freq_div),
+                    );
                     self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_MODE0, STM_MODE_FOCUS);
                     self.bram_write(
                         BRAM_SELECT_CONTROLLER,
                         ADDR_STM_SOUND_SPEED0,
-                        d.head.sound_speed,
+                        u16::from_le(d.head.sound_speed),
+                    );
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_STM_REP0,
+                        u16::from_le(d.head.rep),
                     );
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_REP0, d.head.rep);
                     self.bram_write(
                         BRAM_SELECT_CONTROLLER,
                         ADDR_STM_NUM_FOCI0,
@@ -96,14 +104,22 @@ impl CPUEmulator {
                     );
                 }
                 1 => {
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_FREQ_DIV1, d.head.freq_div);
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_STM_FREQ_DIV1,
+                        u16::from_le(d.head.freq_div),
+                    );
                     self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_MODE1, STM_MODE_FOCUS);
                     self.bram_write(
                         BRAM_SELECT_CONTROLLER,
                         ADDR_STM_SOUND_SPEED1,
-                        d.head.sound_speed,
+                        u16::from_le(d.head.sound_speed),
+                    );
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_STM_REP1,
+                        u16::from_le(d.head.rep),
                     );
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_REP1, d.head.rep);
                     self.bram_write(
                         BRAM_SELECT_CONTROLLER,
                         ADDR_STM_NUM_FOCI1,
@@ -128,16 +144,16 @@ impl CPUEmulator {
             let mut dst = (self.stm_cycle[segment as usize] & FOCI_STM_BUF_PAGE_SIZE_MASK) << 5;
             (0..size as usize).for_each(|_| {
                 (0..self.num_foci).for_each(|_| unsafe {
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
                 });
@@ -148,16 +164,16 @@ impl CPUEmulator {
             let mut dst = (self.stm_cycle[segment as usize] & FOCI_STM_BUF_PAGE_SIZE_MASK) << 5;
             (0..page_capacity as usize).for_each(|_| {
                 (0..self.num_foci).for_each(|_| unsafe {
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
                 });
@@ -174,16 +190,16 @@ impl CPUEmulator {
             let cnt = size - page_capacity;
             (0..cnt as usize).for_each(|_| {
                 (0..self.num_foci).for_each(|_| unsafe {
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
                 });
@@ -248,7 +264,11 @@ impl CPUEmulator {
         }
 
         self.stm_segment = d.segment;
-        self.stm_segment_update(d.segment, d.transition_mode, d.transition_value)
+        self.stm_segment_update(
+            d.segment,
+            d.transition_mode,
+            u64::from_le(d.transition_value),
+        )
     }
 }
 
@@ -56,14 +56,14 @@ impl CPUEmulator {
             if Self::validate_transition_mode(
                 self.stm_segment,
                 segment,
-                d.head.rep,
+                u16::from_le(d.head.rep),
                 d.head.transition_mode,
             ) {
                 return ERR_INVALID_TRANSITION_MODE;
             }
 
             if self.validate_silencer_settings(
-                d.head.freq_div,
+                u16::from_le(d.head.freq_div),
                 self.mod_freq_div[self.mod_segment as usize],
             ) {
                 return ERR_INVALID_SILENCER_SETTING;
@@ -73,21 +73,37 @@ impl CPUEmulator {
                 self.stm_segment = segment;
             }
             self.stm_cycle[segment as usize] = 0;
-            self.stm_rep[segment as usize] = d.head.rep;
+            self.stm_rep[segment as usize] = u16::from_le(d.head.rep);
             self.stm_transition_mode = d.head.transition_mode;
-            self.stm_transition_value = d.head.transition_value;
-            self.stm_freq_div[segment as usize] = d.head.freq_div;
+            self.stm_transition_value = u64::from_le(d.head.transition_value);
+            self.stm_freq_div[segment as usize] = u16::from_le(d.head.freq_div);
 
             match segment {
                 0 => {
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_FREQ_DIV0, d.head.freq_div);
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_STM_FREQ_DIV0,
+                        u16::from_le(d.head.freq_div),
+                    );
                     self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_MODE0, STM_MODE_GAIN);
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_REP0, d.head.rep);
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_STM_REP0,
+                        u16::from_le(d.head.rep),
+                    );
                 }
                 1 => {
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_FREQ_DIV1, d.head.freq_div);
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_STM_FREQ_DIV1,
+                        u16::from_le(d.head.freq_div),
+                    );
                     self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_MODE1, STM_MODE_GAIN);
-                    self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_REP1, d.head.rep);
+                    self.bram_write(
+                        BRAM_SELECT_CONTROLLER,
+                        ADDR_STM_REP1,
+                        u16::from_le(d.head.rep),
+                    );
                 }
                 _ => unreachable!(),
             }
@@ -107,14 +123,18 @@ impl CPUEmulator {
             GAIN_STM_MODE_INTENSITY_PHASE_FULL => {
                 self.stm_cycle[segment as usize] += 1;
                 (0..self.num_transducers).for_each(|_| unsafe {
-                    self.bram_write(BRAM_SELECT_STM, dst, src.read());
+                    self.bram_write(BRAM_SELECT_STM, dst, u16::from_le(src.read()));
                     dst += 1;
                     src = src.add(1);
                 });
             }
             GAIN_STM_MODE_PHASE_FULL => {
                 (0..self.num_transducers).for_each(|_| unsafe {
-                    self.bram_write(BRAM_SELECT_STM, dst, 0xFF00 | (src.read() & 0x00FF));
+                    self.bram_write(
+                        BRAM_SELECT_STM,
+                        dst,
+                        0xFF00 | (u16::from_le(src.read()) & 0x00FF),
+                    );
                     dst += 1;
                     src = src.add(1);
                 });
@@ -128,7 +148,7 @@ impl CPUEmulator {
                         self.bram_write(
                             BRAM_SELECT_STM,
                             dst,
-                            0xFF00 | ((src.read() >> 8) & 0x00FF),
+                            0xFF00 | ((u16::from_le(src.read()) >> 8) & 0x00FF),
                         );
                         dst += 1;
                         src = src.add(1);
@@ -138,7 +158,7 @@ impl CPUEmulator {
             }
             GAIN_STM_MODE_PHASE_HALF => {
                 (0..self.num_transducers).for_each(|_| unsafe {
-                    let phase = src.read() & 0x000F;
+                    let phase = u16::from_le(src.read()) & 0x000F;
                     self.bram_write(BRAM_SELECT_STM, dst, 0xFF00 | (phase << 4) | phase);
                     dst += 1;
                     src = src.add(1);
@@ -150,7 +170,7 @@ impl CPUEmulator {
                     let mut dst =
                         (self.stm_cycle[segment as usize] & GAIN_STM_BUF_PAGE_SIZE_MASK) << 8;
                     (0..self.num_transducers).for_each(|_| unsafe {
-                        let phase = (src.read() >> 4) & 0x000F;
+                        let phase = (u16::from_le(src.read()) >> 4) & 0x000F;
                         self.bram_write(BRAM_SELECT_STM, dst, 0xFF00 | (phase << 4) | phase);
                         dst += 1;
                         src = src.add(1);
@@ -163,7 +183,7 @@ impl CPUEmulator {
                     let mut dst =
                         (self.stm_cycle[segment as usize] & GAIN_STM_BUF_PAGE_SIZE_MASK) << 8;
                     (0..self.num_transducers).for_each(|_| unsafe {
-                        let phase = (src.read() >> 8) & 0x000F;
+                        let phase = (u16::from_le(src.read()) >> 8) & 0x000F;
                         self.bram_write(BRAM_SELECT_STM, dst, 0xFF00 | (phase << 4) | phase);
                         dst += 1;
                         src = src.add(1);
@@ -176,7 +196,7 @@ impl CPUEmulator {
                     let mut dst =
                         (self.stm_cycle[segment as usize] & GAIN_STM_BUF_PAGE_SIZE_MASK) << 8;
                     (0..self.num_transducers).for_each(|_| unsafe {
-                        let phase = (src.read() >> 12) & 0x000F;
+                        let phase = (u16::from_le(src.read()) >> 12) & 0x000F;
                         self.bram_write(BRAM_SELECT_STM, dst, 0xFF00 | (phase << 4) | phase);
                         dst += 1;
                         src = src.add(1);
@@ -252,7 +272,11 @@ impl CPUEmulator {
         }
 
         self.stm_segment = d.segment;
-        self.stm_segment_update(d.segment, d.transition_mode, d.transition_value)
+        self.stm_segment_update(
+            d.segment,
+            d.transition_mode,
+            u64::from_le(d.transition_value),
+        )
     }
 }
 
@@ -16,10 +16,11 @@ impl CPUEmulator {
             return ERR_MISS_TRANSITION_TIME;
         }
         self.bram_write(BRAM_SELECT_CONTROLLER, ADDR_STM_TRANSITION_MODE, mode as _);
+        let value_le = value.to_le();
         self.bram_cpy(
             BRAM_SELECT_CONTROLLER,
             ADDR_STM_TRANSITION_VALUE_0,
-            &raw const value as _,
+            &raw const value_le as _,
             std::mem::size_of::<u64>() >> 1,
         );
         self.set_and_wait_update(CTL_FLAG_STM_SET);
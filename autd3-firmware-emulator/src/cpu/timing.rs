@@ -0,0 +1,53 @@
+use std::{collections::HashMap, time::Duration};
+
+use autd3_driver::{ethercat::DcSysTime, firmware::cpu::TxMessage};
+
+/// The timing model of [`CPUEmulator`] to simulate the latency of the devices.
+///
+/// With the timing model, a frame sent by [`CPUEmulator::send`] is received at the time of the last update and processed in [`CPUEmulator::update_with_sys_time`] when its processing is completed.
+/// Until then, the response of the previous frame is returned.
+///
+/// [`CPUEmulator`]: crate::CPUEmulator
+/// [`CPUEmulator::send`]: crate::CPUEmulator::send
+/// [`CPUEmulator::update_with_sys_time`]: crate::CPUEmulator::update_with_sys_time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimingModel {
+    /// The cycle of the EtherCAT. The processing of a frame starts at the boundary of the cycle after receiving it. If zero, the processing starts immediately.
+    pub ecat_cycle: Duration,
+    /// The processing delay of the operations whose tag is not in [`TimingModel::delays`].
+    pub default_delay: Duration,
+    /// The processing delay of each operation tag. See [`params`] for the tags.
+    ///
+    /// [`params`]: crate::cpu::params
+    pub delays: HashMap<u8, Duration>,
+}
+
+impl TimingModel {
+    fn delay(&self, tag: u8) -> Duration {
+        self.delays.get(&tag).copied().unwrap_or(self.default_delay)
+    }
+
+    pub(crate) fn completion_time(
+        &self,
+        received: DcSysTime,
+        busy_until: DcSysTime,
+        tx: &TxMessage,
+    ) -> DcSysTime {
+        let received = if self.ecat_cycle.is_zero() {
+            received
+        } else {
+            let cycle = self.ecat_cycle.as_nanos() as u64;
+            DcSysTime::ZERO + Duration::from_nanos(received.sys_time().div_ceil(cycle) * cycle)
+        };
+        let start = received.max(busy_until);
+        let payload = tx.payload();
+        let slot_2_offset = u16::from_le(tx.header.slot_2_offset) as usize;
+        let delay = self.delay(payload[0])
+            + if slot_2_offset != 0 {
+                self.delay(payload[slot_2_offset])
+            } else {
+                Duration::ZERO
+            };
+        start + delay
+    }
+}
@@ -0,0 +1,173 @@
+use std::{f32::consts::PI, path::Path};
+
+use autd3_core::acoustics::{directivity::Directivity, propagate};
+use autd3_driver::{
+    ethercat::DcSysTime,
+    firmware::fpga::Phase,
+    geometry::{Complex, Geometry, Point3},
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::CPUEmulator;
+
+/// The sound field computed from the output of the emulated devices by [`sound_field`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoundField {
+    points: Vec<Point3>,
+    times: Vec<DcSysTime>,
+    carrier_phases: Vec<Phase>,
+    pressures: Vec<Vec<Complex>>,
+}
+
+impl SoundField {
+    /// The points where the sound field is computed.
+    pub fn points(&self) -> &[Point3] {
+        &self.points
+    }
+
+    /// The sampled times.
+    pub fn times(&self) -> &[DcSysTime] {
+        &self.times
+    }
+
+    /// The complex pressure at each point for each sampled time, i.e., `pressures()[time][point]`.
+    pub fn pressures(&self) -> &[Vec<Complex>] {
+        &self.pressures
+    }
+
+    /// The instantaneous pressure at each point for each sampled time, i.e., `instantaneous()[time][point]`.
+    pub fn instantaneous(&self) -> Vec<Vec<f32>> {
+        self.pressures
+            .iter()
+            .zip(self.carrier_phases.iter())
+            .map(|(p, phase)| {
+                let rot = Complex::from_polar(1., -phase.radian());
+                p.iter().map(|p| (p * rot).re).collect()
+            })
+            .collect()
+    }
+
+    /// The RMS pressure at each point over the sampled times.
+    ///
+    /// Each sample contributes the mean square over one period of the carrier, so the result does not depend on how the sampled times are aligned with the carrier.
+    pub fn rms(&self) -> Vec<f32> {
+        let n = self.pressures.len().max(1) as f32;
+        (0..self.pressures.first().map_or(0, |p| p.len()))
+            .map(|i| {
+                (self
+                    .pressures
+                    .iter()
+                    .map(|p| p[i].norm_sqr() / 2.)
+                    .sum::<f32>()
+                    / n)
+                    .sqrt()
+            })
+            .collect()
+    }
+
+    /// Writes the sound field as a CSV file to analyze in other tools such as Python.
+    ///
+    /// Each row is `time,point,x,y,z,re,im,instantaneous` for each sampled time and point, and the time is in nanoseconds of [`DcSysTime::sys_time`].
+    pub fn write_csv(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut writer = csv::Writer::from_path(path)?;
+        writer.write_record(["time", "point", "x", "y", "z", "re", "im", "instantaneous"])?;
+        self.times
+            .iter()
+            .zip(self.pressures.iter())
+            .zip(self.instantaneous())
+            .try_for_each(|((t, pressures), instantaneous)| {
+                self.points
+                    .iter()
+                    .zip(pressures.iter())
+                    .zip(instantaneous)
+                    .enumerate()
+                    .try_for_each(|(i, ((point, p), instantaneous))| {
+                        writer.write_record([
+                            t.sys_time().to_string(),
+                            i.to_string(),
+                            point.x.to_string(),
+                            point.y.to_string(),
+                            point.z.to_string(),
+                            p.re.to_string(),
+                            p.im.to_string(),
+                            instantaneous.to_string(),
+                        ])
+                    })
+            })?;
+        writer.flush()
+    }
+}
+
+/// Computes the sound field at `points` from the pulse widths and phases of the emulated devices at each time in `times`.
+///
+/// The emulators are updated to each time in order, so the modulation and STM progress and segment transitions occur as the devices would do.
+/// The amplitude of each transducer is given by the fundamental component of its pulse width, and the propagation delay and the silencer are not taken into account.
+/// Disabled devices and transducers are ignored. With the `parallel` feature (enabled by default), the pressures at `points` are computed in parallel.
+pub fn sound_field<D: Directivity>(
+    cpus: &mut [CPUEmulator],
+    geometry: &Geometry,
+    points: &[Point3],
+    times: impl IntoIterator<Item = DcSysTime>,
+) -> SoundField {
+    let (times, (carrier_phases, pressures)) = times
+        .into_iter()
+        .map(|t| {
+            cpus.iter_mut().for_each(|cpu| cpu.update_with_sys_time(t));
+            let outputs = geometry
+                .devices()
+                .map(|dev| {
+                    (
+                        dev,
+                        cpus[dev.idx()]
+                            .fpga()
+                            .output()
+                            .into_iter()
+                            .map(|(pulse_width, phase)| {
+                                Complex::from_polar(
+                                    (PI * pulse_width as f32 / 256.).sin(),
+                                    phase.radian(),
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            #[cfg(feature = "parallel")]
+            let points_iter = points.par_iter();
+            #[cfg(not(feature = "parallel"))]
+            let points_iter = points.iter();
+            let pressures = points_iter
+                .map(|p| {
+                    outputs
+                        .iter()
+                        .map(|(dev, output)| {
+                            dev.iter()
+                                .zip(output.iter())
+                                .filter(|(tr, _)| tr.enable)
+                                .map(|(tr, d)| {
+                                    propagate::<D>(tr, dev.wavenumber(), dev.axial_direction(), p)
+                                        * d
+                                })
+                                .sum::<Complex>()
+                        })
+                        .sum::<Complex>()
+                })
+                .collect();
+            (
+                t,
+                (
+                    cpus.first()
+                        .map_or(Phase::ZERO, |cpu| cpu.fpga().carrier_phase(t)),
+                    pressures,
+                ),
+            )
+        })
+        .unzip();
+    SoundField {
+        points: points.to_vec(),
+        times,
+        carrier_phases,
+        pressures,
+    }
+}
@@ -10,6 +10,26 @@ use getset::{Getters, MutGetters};
 
 use super::super::params::*;
 
+// BRAM words are in the device byte order, i.e., multi-word values are stored from the least significant word.
+pub(crate) trait BramWords {
+    fn from_words(words: &[u16]) -> Self;
+}
+
+impl BramWords for u16 {
+    fn from_words(words: &[u16]) -> Self {
+        words[0]
+    }
+}
+
+impl BramWords for u64 {
+    fn from_words(words: &[u16]) -> Self {
+        words[..4]
+            .iter()
+            .rev()
+            .fold(0, |acc, &w| (acc << 16) | w as u64)
+    }
+}
+
 #[derive(Getters, MutGetters)]
 pub struct Memory {
     pub(crate) num_transducers: usize,
@@ -59,16 +79,12 @@ impl Memory {
                 )
             }),
             duty_table_bram: LazyCell::new(|| {
-                let mut v = vec![0x0000; 256 / std::mem::size_of::<u16>()];
-                let pwe_init_data = include_bytes!("asin.dat");
-                unsafe {
-                    std::ptr::copy_nonoverlapping(
-                        pwe_init_data.as_ptr(),
-                        v.as_mut_ptr() as _,
-                        pwe_init_data.len(),
-                    );
-                }
-                RefCell::new(v)
+                RefCell::new(
+                    include_bytes!("asin.dat")
+                        .chunks(std::mem::size_of::<u16>())
+                        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                        .collect(),
+                )
             }),
             stm_bram: LazyCell::new(|| {
                 RefCell::new(
@@ -132,8 +148,8 @@ impl Memory {
         }
     }
 
-    pub fn read_bram_as<T>(bram: &[u16], addr: usize) -> T {
-        unsafe { (bram.as_ptr().add(addr) as *const T).read_unaligned() }
+    pub(crate) fn read_bram_as<T: BramWords>(bram: &[u16], addr: usize) -> T {
+        T::from_words(&bram[addr..])
     }
 
     pub fn write(&mut self, addr: u16, data: u16) {
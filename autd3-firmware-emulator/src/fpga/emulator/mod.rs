@@ -1,11 +1,13 @@
 mod debug;
 mod memory;
 mod modulation;
+mod output;
 mod phase_corr;
 mod pwe;
 mod silencer;
 mod stm;
 mod swapchain;
+mod waveform;
 
 use autd3_driver::{ethercat::DcSysTime, firmware::fpga::Segment};
 
@@ -18,6 +20,7 @@ use super::params::{
 };
 
 pub use silencer::SilencerEmulator;
+pub use waveform::Waveform;
 
 const CTL_FLAG_MOD_SET: u16 = 1 << CTL_FLAG_MOD_SET_BIT;
 const CTL_FLAG_STM_SET: u16 = 1 << CTL_FLAG_STM_SET_BIT;
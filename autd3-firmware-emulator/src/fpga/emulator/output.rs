@@ -0,0 +1,21 @@
+use autd3_driver::{ethercat::DcSysTime, firmware::fpga::Phase};
+
+use super::FPGAEmulator;
+
+impl FPGAEmulator {
+    /// Gets the pulse width and phase of each transducer at the current modulation and STM index.
+    pub fn output(&self) -> Vec<(u8, Phase)> {
+        let m = self.modulation();
+        self.drives()
+            .into_iter()
+            .map(|d| (self.to_pulse_width(d.intensity, m), d.phase))
+            .collect()
+    }
+
+    /// Gets the phase of the ultrasound carrier at `sys_time`.
+    pub fn carrier_phase(&self, sys_time: DcSysTime) -> Phase {
+        let ticks = (sys_time.sys_time() as u128 * self.mod_swapchain.fpga_clk_freq.hz() as u128)
+            / 1000000000;
+        Phase((ticks & 0xFF) as u8)
+    }
+}
@@ -5,7 +5,11 @@ use super::FPGAEmulator;
 impl FPGAEmulator {
     fn _phase_corr(&self, idx: usize) -> Phase {
         let p = &self.mem.phase_corr_bram.borrow()[idx >> 1];
-        let p = if idx % 2 == 0 { p & 0xFF } else { p >> 8 };
+        let p = if idx.is_multiple_of(2) {
+            p & 0xFF
+        } else {
+            p >> 8
+        };
         Phase(p as _)
     }
 
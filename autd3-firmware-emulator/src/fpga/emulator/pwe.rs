@@ -5,7 +5,11 @@ use super::FPGAEmulator;
 impl FPGAEmulator {
     pub fn pulse_width_encoder_table_at(&self, idx: usize) -> u8 {
         let v = self.mem.duty_table_bram.borrow()[idx >> 1];
-        let v = if idx % 2 == 0 { v & 0xFF } else { v >> 8 };
+        let v = if idx.is_multiple_of(2) {
+            v & 0xFF
+        } else {
+            v >> 8
+        };
         v as u8
     }
 
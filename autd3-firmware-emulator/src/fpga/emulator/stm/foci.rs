@@ -1,6 +1,6 @@
 use autd3_driver::firmware::fpga::{Drive, EmitIntensity, Phase, Segment};
 
-use super::super::{super::params::*, FPGAEmulator};
+use super::super::{super::params::*, memory::Memory, FPGAEmulator};
 
 #[bitfield_struct::bitfield(u64)]
 struct STMFocus {
@@ -47,9 +47,8 @@ impl FPGAEmulator {
                 let tr_y = (tr & 0xFFFF) as i16 as i32;
                 let mut intensity = 0x00;
                 let (sin, cos) = (0..self.num_foci(segment) as usize).fold((0, 0), |acc, i| {
-                    let f = unsafe {
-                        (bram[32 * idx + 4 * i..].as_ptr() as *const STMFocus).read_unaligned()
-                    };
+                    let f =
+                        STMFocus::from_bits(Memory::read_bram_as::<u64>(bram, 32 * idx + 4 * i));
                     let x = f.x();
                     let y = f.y();
                     let z = f.z();
@@ -0,0 +1,160 @@
+use std::time::Duration;
+
+use autd3_driver::{
+    defined::ultrasound_freq,
+    ethercat::DcSysTime,
+    firmware::fpga::{EmitIntensity, Phase, SilencerTarget},
+};
+
+use super::{FPGAEmulator, SilencerEmulator};
+
+/// The output waveform of each transducer reconstructed by [`FPGAEmulator::waveform`].
+#[derive(Debug, Clone)]
+pub struct Waveform {
+    start: DcSysTime,
+    pulse_widths: Vec<Vec<u8>>,
+    phases: Vec<Vec<Phase>>,
+    intensity_silencers: Vec<SilencerEmulator<EmitIntensity>>,
+    phase_silencers: Vec<SilencerEmulator<Phase>>,
+}
+
+fn period_start(start: DcSysTime, idx: usize) -> DcSysTime {
+    start + Duration::from_nanos((idx as u64 * 1_000_000_000) / ultrasound_freq().hz() as u64)
+}
+
+impl Waveform {
+    /// The start time of each period of the ultrasound.
+    pub fn times(&self) -> Vec<DcSysTime> {
+        (0..self.pulse_widths.len())
+            .map(|i| period_start(self.start, i))
+            .collect()
+    }
+
+    /// The pulse width of each transducer after the silencer for each period, i.e., `pulse_widths()[period][transducer]`.
+    pub fn pulse_widths(&self) -> &[Vec<u8>] {
+        &self.pulse_widths
+    }
+
+    /// The phase of each transducer after the silencer for each period, i.e., `phases()[period][transducer]`.
+    pub fn phases(&self) -> &[Vec<Phase>] {
+        &self.phases
+    }
+
+    /// The PWM signal of the transducer at `idx`, sampled at 256 points per period from the carrier phase of 0.
+    ///
+    /// The pulse of each period is centered at the phase of the transducer.
+    pub fn signal(&self, idx: usize) -> Vec<bool> {
+        self.pulse_widths
+            .iter()
+            .zip(self.phases.iter())
+            .flat_map(|(pulse_widths, phases)| {
+                let pulse_width = pulse_widths[idx] as usize;
+                let phase = phases[idx].0 as usize;
+                (0..256).map(move |t| (t + 256 - phase + pulse_width / 2) % 256 < pulse_width)
+            })
+            .collect()
+    }
+}
+
+impl FPGAEmulator {
+    fn silencer_input(&self) -> Vec<(u8, u8)> {
+        let m = self.modulation();
+        let target = self.silencer_target();
+        self.drives()
+            .into_iter()
+            .map(|d| {
+                (
+                    match target {
+                        SilencerTarget::Intensity => {
+                            ((d.intensity.0 as usize * m as usize) / 255) as u8
+                        }
+                        SilencerTarget::PulseWidth => self.to_pulse_width(d.intensity, m),
+                    },
+                    d.phase.0,
+                )
+            })
+            .collect()
+    }
+
+    /// Reconstructs the output waveform of each transducer for `periods` periods of the ultrasound from `start`.
+    ///
+    /// The emulator is updated to the start of each period, and the silencer is applied once per period as the device does.
+    /// The silencer is assumed to have settled at `start`, so use [`FPGAEmulator::waveform_continue_with`] to see the transient after changing the output.
+    pub fn waveform(&mut self, start: DcSysTime, periods: usize) -> Waveform {
+        self.update_with_sys_time(start);
+        let (intensity_silencers, phase_silencers) = self
+            .silencer_input()
+            .into_iter()
+            .map(|(intensity, phase)| {
+                (
+                    self.silencer_emulator_intensity(intensity),
+                    self.silencer_emulator_phase(phase),
+                )
+            })
+            .unzip();
+        self.waveform_with(
+            Waveform {
+                start,
+                pulse_widths: Vec::new(),
+                phases: Vec::new(),
+                intensity_silencers,
+                phase_silencers,
+            },
+            periods,
+        )
+    }
+
+    /// Reconstructs the output waveform continuing from the end of `prev` with its silencer state.
+    ///
+    /// The current silencer configuration is used, so the change of the silencer settings after `prev` is also reflected.
+    pub fn waveform_continue_with(&mut self, prev: &Waveform, periods: usize) -> Waveform {
+        self.waveform_with(
+            Waveform {
+                start: period_start(prev.start, prev.pulse_widths.len()),
+                pulse_widths: Vec::new(),
+                phases: Vec::new(),
+                intensity_silencers: prev
+                    .intensity_silencers
+                    .iter()
+                    .map(|&s| self.silencer_emulator_intensity_continue_with(s))
+                    .collect(),
+                phase_silencers: prev
+                    .phase_silencers
+                    .iter()
+                    .map(|&s| self.silencer_emulator_phase_continue_with(s))
+                    .collect(),
+            },
+            periods,
+        )
+    }
+
+    fn waveform_with(&mut self, mut waveform: Waveform, periods: usize) -> Waveform {
+        (0..periods).for_each(|i| {
+            self.update_with_sys_time(period_start(waveform.start, i));
+            let target = self.silencer_target();
+            let (pulse_widths, phases) = self
+                .silencer_input()
+                .into_iter()
+                .zip(waveform.intensity_silencers.iter_mut())
+                .zip(waveform.phase_silencers.iter_mut())
+                .map(
+                    |(((intensity, phase), intensity_silencer), phase_silencer)| {
+                        let intensity = intensity_silencer.apply(intensity);
+                        (
+                            match target {
+                                SilencerTarget::Intensity => {
+                                    self.pulse_width_encoder_table_at(intensity as usize)
+                                }
+                                SilencerTarget::PulseWidth => intensity,
+                            },
+                            Phase(phase_silencer.apply(phase)),
+                        )
+                    },
+                )
+                .unzip();
+            waveform.pulse_widths.push(pulse_widths);
+            waveform.phases.push(phases);
+        });
+        waveform
+    }
+}
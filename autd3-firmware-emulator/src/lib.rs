@@ -1,5 +1,9 @@
 pub mod cpu;
+mod field;
 pub mod fpga;
+mod record;
 
-pub use cpu::emulator::CPUEmulator;
+pub use cpu::{emulator::CPUEmulator, TimingModel};
+pub use field::{sound_field, SoundField};
 pub use fpga::emulator::FPGAEmulator;
+pub use record::{record, DeviceRecord, Recording};
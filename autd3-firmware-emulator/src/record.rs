@@ -0,0 +1,167 @@
+use std::path::Path;
+
+use autd3_driver::{
+    ethercat::DcSysTime,
+    firmware::fpga::{Phase, Segment},
+    geometry::Geometry,
+};
+
+use crate::CPUEmulator;
+
+/// The state of a device at a sampled time recorded by [`record`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceRecord {
+    /// The current segment of the modulation.
+    pub modulation_segment: Segment,
+    /// The current index of the modulation.
+    pub modulation_idx: usize,
+    /// The sampling frequency division of the current modulation segment.
+    pub modulation_freq_division: u16,
+    /// The current modulation value.
+    pub modulation: u8,
+    /// The current segment of the STM.
+    pub stm_segment: Segment,
+    /// The current index of the STM.
+    pub stm_idx: usize,
+    /// The sampling frequency division of the current STM segment.
+    pub stm_freq_division: u16,
+    /// The pulse width and phase of each transducer.
+    pub output: Vec<(u8, Phase)>,
+}
+
+/// The timeline of the emulated devices recorded by [`record`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    times: Vec<DcSysTime>,
+    records: Vec<Vec<DeviceRecord>>,
+}
+
+/// Records the state of the emulated devices at each time in `times`.
+///
+/// The emulators are updated to each time in order as in [`sound_field`].
+///
+/// [`sound_field`]: crate::sound_field
+pub fn record(cpus: &mut [CPUEmulator], times: impl IntoIterator<Item = DcSysTime>) -> Recording {
+    let (times, records) = times
+        .into_iter()
+        .map(|t| {
+            cpus.iter_mut().for_each(|cpu| cpu.update_with_sys_time(t));
+            (
+                t,
+                cpus.iter()
+                    .map(|cpu| {
+                        let fpga = cpu.fpga();
+                        DeviceRecord {
+                            modulation_segment: fpga.current_mod_segment(),
+                            modulation_idx: fpga.current_mod_idx(),
+                            modulation_freq_division: fpga
+                                .modulation_freq_division(fpga.current_mod_segment()),
+                            modulation: fpga.modulation(),
+                            stm_segment: fpga.current_stm_segment(),
+                            stm_idx: fpga.current_stm_idx(),
+                            stm_freq_division: fpga.stm_freq_division(fpga.current_stm_segment()),
+                            output: fpga.output(),
+                        }
+                    })
+                    .collect(),
+            )
+        })
+        .unzip();
+    Recording { times, records }
+}
+
+impl Recording {
+    /// The sampled times.
+    pub fn times(&self) -> &[DcSysTime] {
+        &self.times
+    }
+
+    /// The state of each device for each sampled time, i.e., `records()[time][device]`.
+    pub fn records(&self) -> &[Vec<DeviceRecord>] {
+        &self.records
+    }
+
+    /// Writes the recording as CSV files in `dir` to analyze in other tools such as Python.
+    ///
+    /// The following files are written, and the time is in nanoseconds of [`DcSysTime::sys_time`].
+    /// - `geometry.csv`: `device,transducer,x,y,z` of each transducer in `geometry`
+    /// - `modulation.csv`: `time,device,modulation_segment,modulation_idx,modulation_freq_division,modulation,stm_segment,stm_idx,stm_freq_division` for each time and device
+    /// - `drive.csv`: `time,device,transducer,pulse_width,phase` for each time and transducer
+    pub fn write_csv(&self, dir: impl AsRef<Path>, geometry: &Geometry) -> std::io::Result<()> {
+        let dir = dir.as_ref();
+
+        write_geometry(dir.join("geometry.csv"), geometry)?;
+
+        let mut writer = csv::Writer::from_path(dir.join("modulation.csv"))?;
+        writer.write_record([
+            "time",
+            "device",
+            "modulation_segment",
+            "modulation_idx",
+            "modulation_freq_division",
+            "modulation",
+            "stm_segment",
+            "stm_idx",
+            "stm_freq_division",
+        ])?;
+        self.iter().try_for_each(|(t, dev, r)| {
+            writer.write_record([
+                t.sys_time().to_string(),
+                dev.to_string(),
+                (r.modulation_segment as u8).to_string(),
+                r.modulation_idx.to_string(),
+                r.modulation_freq_division.to_string(),
+                r.modulation.to_string(),
+                (r.stm_segment as u8).to_string(),
+                r.stm_idx.to_string(),
+                r.stm_freq_division.to_string(),
+            ])
+        })?;
+        writer.flush()?;
+
+        let mut writer = csv::Writer::from_path(dir.join("drive.csv"))?;
+        writer.write_record(["time", "device", "transducer", "pulse_width", "phase"])?;
+        self.iter().try_for_each(|(t, dev, r)| {
+            r.output
+                .iter()
+                .enumerate()
+                .try_for_each(|(tr, (pulse_width, phase))| {
+                    writer.write_record([
+                        t.sys_time().to_string(),
+                        dev.to_string(),
+                        tr.to_string(),
+                        pulse_width.to_string(),
+                        phase.0.to_string(),
+                    ])
+                })
+        })?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&DcSysTime, usize, &DeviceRecord)> {
+        self.times
+            .iter()
+            .zip(self.records.iter())
+            .flat_map(|(t, r)| r.iter().enumerate().map(move |(dev, r)| (t, dev, r)))
+    }
+}
+
+fn write_geometry(path: impl AsRef<Path>, geometry: &Geometry) -> std::io::Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(["device", "transducer", "x", "y", "z"])?;
+    geometry.iter().try_for_each(|dev| {
+        dev.iter().try_for_each(|tr| {
+            let p = tr.position();
+            writer.write_record([
+                dev.idx().to_string(),
+                tr.idx().to_string(),
+                p.x.to_string(),
+                p.y.to_string(),
+                p.z.to_string(),
+            ])
+        })
+    })?;
+    writer.flush()
+}
@@ -0,0 +1,272 @@
+use std::{collections::HashMap, time::Duration};
+
+use autd3_core::{
+    acoustics::{directivity::Sphere, pressure},
+    derive::*,
+};
+use autd3_driver::{
+    defined::{mm, rad},
+    ethercat::DcSysTime,
+    firmware::{
+        cpu::TxMessage,
+        fpga::{Drive, EmitIntensity, Phase},
+    },
+    geometry::{Point3, Vector3},
+};
+use autd3_firmware_emulator::{record, sound_field, CPUEmulator};
+
+use crate::{create_geometry, op::gain::TestGain, op::modulation::TestModulation, send};
+
+use zerocopy::FromZeros;
+
+fn focus(geometry: &Geometry, target: Point3) -> HashMap<usize, Vec<Drive>> {
+    geometry
+        .iter()
+        .map(|dev| {
+            (
+                dev.idx(),
+                dev.iter()
+                    .map(|tr| Drive {
+                        phase: Phase::from(
+                            -dev.wavenumber() * (target - tr.position()).norm() * rad,
+                        ),
+                        intensity: EmitIntensity::MAX,
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn sound_field_focus() -> anyhow::Result<()> {
+    let geometry = create_geometry(1);
+    let mut cpus = vec![CPUEmulator::new(0, geometry.num_transducers())];
+    let mut tx = vec![TxMessage::new_zeroed(); 1];
+
+    let target = geometry.center().unwrap() + Vector3::new(0., 0., 150. * mm);
+    let data = focus(&geometry, target);
+    let drives = data[&0].clone();
+    send(&mut cpus[0], TestGain { data }, &geometry, &mut tx)?;
+
+    let off = target + Vector3::new(30. * mm, 0., 0.);
+    let field = sound_field::<Sphere>(&mut cpus, &geometry, &[target, off], [DcSysTime::ZERO]);
+    assert_eq!(&[DcSysTime::ZERO], field.times());
+
+    let rms = field.rms();
+    let expect = pressure::<Sphere>(&geometry[0], &drives, &target).norm() / 2f32.sqrt();
+    approx::assert_relative_eq!(expect, rms[0], max_relative = 1e-2);
+    assert!(rms[1] < rms[0] / 5.);
+
+    Ok(())
+}
+
+#[test]
+fn sound_field_instantaneous() -> anyhow::Result<()> {
+    let geometry = create_geometry(2);
+    let mut cpus = (0..2)
+        .map(|i| CPUEmulator::new(i, geometry[i].num_transducers()))
+        .collect::<Vec<_>>();
+    let mut tx = vec![TxMessage::new_zeroed(); 2];
+
+    let target = geometry.center().unwrap() + Vector3::new(0., 0., 150. * mm);
+    cpus.iter_mut().try_for_each(|cpu| {
+        send(
+            cpu,
+            TestGain {
+                data: focus(&geometry, target),
+            },
+            &geometry,
+            &mut tx,
+        )
+    })?;
+
+    let period = Duration::from_micros(25);
+    let field = sound_field::<Sphere>(
+        &mut cpus,
+        &geometry,
+        &[target],
+        (0..16).map(|i| DcSysTime::ZERO + period * i / 16),
+    );
+    let instantaneous = field.instantaneous();
+    assert_eq!(16, instantaneous.len());
+    let rms = (instantaneous.iter().map(|p| p[0] * p[0]).sum::<f32>() / 16.).sqrt();
+    approx::assert_relative_eq!(field.rms()[0], rms, max_relative = 1e-3);
+    let max = instantaneous.iter().map(|p| p[0]).fold(f32::MIN, f32::max);
+    approx::assert_relative_eq!(field.pressures()[0][0].norm(), max, max_relative = 1e-1);
+
+    Ok(())
+}
+
+#[test]
+fn sound_field_modulation() -> anyhow::Result<()> {
+    let geometry = create_geometry(1);
+    let mut cpus = vec![CPUEmulator::new(0, geometry.num_transducers())];
+    let mut tx = vec![TxMessage::new_zeroed(); 1];
+
+    let target = geometry.center().unwrap() + Vector3::new(0., 0., 150. * mm);
+    send(
+        &mut cpus[0],
+        TestGain {
+            data: focus(&geometry, target),
+        },
+        &geometry,
+        &mut tx,
+    )?;
+    send(
+        &mut cpus[0],
+        TestModulation {
+            buf: vec![0xFF, 0x00],
+            sampling_config: SamplingConfig::DIV_10,
+        },
+        &geometry,
+        &mut tx,
+    )?;
+
+    // The modulation index changes every 10 periods of the ultrasound.
+    let field = sound_field::<Sphere>(
+        &mut cpus,
+        &geometry,
+        &[target],
+        (0..4).map(|i| DcSysTime::ZERO + Duration::from_micros(125 + 250 * i)),
+    );
+    let p = field
+        .pressures()
+        .iter()
+        .map(|p| p[0].norm())
+        .collect::<Vec<_>>();
+    assert!(p[0] > 0.);
+    assert_eq!(0., p[1]);
+    approx::assert_relative_eq!(p[0], p[2]);
+    assert_eq!(0., p[3]);
+    approx::assert_relative_eq!(p[0] / 2f32.sqrt(), field.rms()[0] * 2f32.sqrt());
+
+    Ok(())
+}
+
+#[test]
+fn record_write_csv() -> anyhow::Result<()> {
+    let geometry = create_geometry(1);
+    let mut cpus = vec![CPUEmulator::new(0, geometry.num_transducers())];
+    let mut tx = vec![TxMessage::new_zeroed(); 1];
+
+    let target = geometry.center().unwrap() + Vector3::new(0., 0., 150. * mm);
+    let data = focus(&geometry, target);
+    let drives = data[&0].clone();
+    send(&mut cpus[0], TestGain { data }, &geometry, &mut tx)?;
+    send(
+        &mut cpus[0],
+        TestModulation {
+            buf: vec![0xFF, 0x00],
+            sampling_config: SamplingConfig::DIV_10,
+        },
+        &geometry,
+        &mut tx,
+    )?;
+
+    let recording = record(
+        &mut cpus,
+        (0..2).map(|i| DcSysTime::ZERO + Duration::from_micros(125 + 250 * i)),
+    );
+    assert_eq!(2, recording.times().len());
+    let records = recording.records();
+    assert_eq!(0, records[0][0].modulation_idx);
+    assert_eq!(0xFF, records[0][0].modulation);
+    assert_eq!(10, records[0][0].modulation_freq_division);
+    assert_eq!(1, records[1][0].modulation_idx);
+    assert_eq!(0x00, records[1][0].modulation);
+    assert_eq!(
+        drives.iter().map(|d| d.phase).collect::<Vec<_>>(),
+        records[0][0]
+            .output
+            .iter()
+            .map(|(_, phase)| *phase)
+            .collect::<Vec<_>>()
+    );
+    assert!(records[1][0].output.iter().all(|(w, _)| *w == 0));
+
+    let dir = tempfile::tempdir()?;
+    recording.write_csv(dir.path(), &geometry)?;
+
+    let geometry_csv = std::fs::read_to_string(dir.path().join("geometry.csv"))?;
+    let mut lines = geometry_csv.lines();
+    assert_eq!(Some("device,transducer,x,y,z"), lines.next());
+    assert_eq!(geometry.num_transducers(), lines.count());
+
+    let modulation_csv = std::fs::read_to_string(dir.path().join("modulation.csv"))?;
+    assert_eq!(
+        vec![
+            "time,device,modulation_segment,modulation_idx,modulation_freq_division,modulation,stm_segment,stm_idx,stm_freq_division",
+            "125000,0,0,0,10,255,0,0,65535",
+            "375000,0,0,1,10,0,0,0,65535",
+        ],
+        modulation_csv.lines().collect::<Vec<_>>()
+    );
+
+    let drive_csv = std::fs::read_to_string(dir.path().join("drive.csv"))?;
+    let mut lines = drive_csv.lines();
+    assert_eq!(
+        Some("time,device,transducer,pulse_width,phase"),
+        lines.next()
+    );
+    assert_eq!(
+        Some(
+            format!(
+                "125000,0,0,{},{}",
+                records[0][0].output[0].0, drives[0].phase.0
+            )
+            .as_str()
+        ),
+        lines.next()
+    );
+    assert_eq!(2 * geometry.num_transducers() - 1, lines.count());
+
+    Ok(())
+}
+
+#[test]
+fn sound_field_write_csv() -> anyhow::Result<()> {
+    let geometry = create_geometry(1);
+    let mut cpus = vec![CPUEmulator::new(0, geometry.num_transducers())];
+    let mut tx = vec![TxMessage::new_zeroed(); 1];
+
+    let target = geometry.center().unwrap() + Vector3::new(0., 0., 150. * mm);
+    send(
+        &mut cpus[0],
+        TestGain {
+            data: focus(&geometry, target),
+        },
+        &geometry,
+        &mut tx,
+    )?;
+
+    let points = [target, Point3::origin()];
+    let field = sound_field::<Sphere>(
+        &mut cpus,
+        &geometry,
+        &points,
+        [DcSysTime::ZERO, DcSysTime::ZERO + Duration::from_micros(5)],
+    );
+    assert_eq!(&points, field.points());
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("field.csv");
+    field.write_csv(&path)?;
+
+    let csv = std::fs::read_to_string(path)?;
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(5, lines.len());
+    assert_eq!("time,point,x,y,z,re,im,instantaneous", lines[0]);
+    let row = lines[3].split(',').collect::<Vec<_>>();
+    assert_eq!(["5000", "0"], row[..2]);
+    let values = row[2..]
+        .iter()
+        .map(|v| v.parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!([target.x, target.y, target.z], values[..3]);
+    assert_eq!(field.pressures()[1][0].re, values[3]);
+    assert_eq!(field.pressures()[1][0].im, values[4]);
+    assert_eq!(field.instantaneous()[1][0], values[5]);
+
+    Ok(())
+}
@@ -2,10 +2,10 @@ mod clear;
 mod cpu_gpio_out;
 mod debug;
 mod force_fan;
-mod gain;
+pub(crate) mod gain;
 mod gpio_in;
 mod info;
-mod modulation;
+pub(crate) mod modulation;
 mod phase_corr;
 mod pulse_width_encoder;
 mod reads_fpga_state;
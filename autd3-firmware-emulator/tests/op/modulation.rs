@@ -1,6 +1,6 @@
 use std::{num::NonZeroU16, time::Duration};
 
-use autd3_core::derive::*;
+use autd3_core::{datagram::Datagram, derive::*};
 use autd3_driver::{
     datagram::{FixedCompletionSteps, Silencer, SwapSegment, WithLoopBehavior, WithSegment},
     error::AUTDDriverError,
@@ -11,6 +11,7 @@ use autd3_driver::{
             GPIOIn, SilencerTarget, TransitionMode, MOD_BUF_SIZE_MAX, MOD_BUF_SIZE_MIN,
             SILENCER_STEPS_INTENSITY_DEFAULT, SILENCER_STEPS_PHASE_DEFAULT,
         },
+        operation::OperationHandler,
     },
 };
 use autd3_firmware_emulator::{cpu::params::SYS_TIME_TRANSITION_MARGIN, CPUEmulator};
@@ -218,6 +219,7 @@ fn send_mod_invalid_transition_mode() -> anyhow::Result<()> {
     let mut tx = vec![TxMessage::new_zeroed(); 1];
 
     // segment 0 to 0
+    // The host rejects the infinite loop with `TransitionMode::SyncIdx`, so the transition mode in the frame is rewritten to check the firmware.
     {
         let d = WithSegment {
             inner: TestModulation {
@@ -225,11 +227,17 @@ fn send_mod_invalid_transition_mode() -> anyhow::Result<()> {
                 sampling_config: SamplingConfig::DIV_10,
             },
             segment: Segment::S0,
-            transition_mode: Some(TransitionMode::SyncIdx),
+            transition_mode: Some(TransitionMode::Immediate),
         };
+        let generator = d.operation_generator(&geometry, false)?;
+        let mut op = OperationHandler::generate(generator, &geometry);
+        OperationHandler::pack(&mut op, &geometry, &mut tx, false)?;
+        tx[0].payload_mut()[3] = TransitionMode::SyncIdx.mode();
+
+        cpu.send(&tx);
         assert_eq!(
             Err(AUTDDriverError::InvalidTransitionMode),
-            send(&mut cpu, d, &geometry, &mut tx)
+            autd3_driver::firmware::cpu::check_firmware_err(&cpu.rx())
         );
     }
 
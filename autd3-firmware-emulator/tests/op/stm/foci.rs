@@ -1,6 +1,6 @@
 use std::{collections::HashMap, num::NonZeroU16, time::Duration};
 
-use autd3_core::gain::EmitIntensity;
+use autd3_core::{datagram::Datagram, gain::EmitIntensity};
 use autd3_driver::{
     datagram::{
         ControlPoint, ControlPoints, FixedCompletionSteps, FociSTM, GainSTM, GainSTMOption,
@@ -16,6 +16,7 @@ use autd3_driver::{
             FOCI_STM_BUF_SIZE_MAX, FOCI_STM_FIXED_NUM_UNIT, SILENCER_STEPS_INTENSITY_DEFAULT,
             SILENCER_STEPS_PHASE_DEFAULT,
         },
+        operation::OperationHandler,
     },
     geometry::Point3,
 };
@@ -295,6 +296,7 @@ fn send_foci_stm_invalid_transition_mode() -> anyhow::Result<()> {
     let mut tx = vec![TxMessage::new_zeroed(); 1];
 
     // segment 0 to 0
+    // The host rejects the infinite loop with `TransitionMode::SyncIdx`, so the transition mode in the frame is rewritten to check the firmware.
     {
         let stm = WithSegment {
             inner: FociSTM {
@@ -302,11 +304,17 @@ fn send_foci_stm_invalid_transition_mode() -> anyhow::Result<()> {
                 config: SamplingConfig::FREQ_MIN,
             },
             segment: Segment::S0,
-            transition_mode: Some(TransitionMode::SyncIdx),
+            transition_mode: Some(TransitionMode::Immediate),
         };
+        let generator = stm.operation_generator(&geometry, false)?;
+        let mut op = OperationHandler::generate(generator, &geometry);
+        OperationHandler::pack(&mut op, &geometry, &mut tx, false)?;
+        tx[0].payload_mut()[4] = TransitionMode::SyncIdx.mode();
+
+        cpu.send(&tx);
         assert_eq!(
             Err(AUTDDriverError::InvalidTransitionMode),
-            send(&mut cpu, stm, &geometry, &mut tx)
+            autd3_driver::firmware::cpu::check_firmware_err(&cpu.rx())
         );
     }
 
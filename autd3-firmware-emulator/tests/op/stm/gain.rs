@@ -417,6 +417,7 @@ fn send_gain_stm_invalid_transition_mode() -> anyhow::Result<()> {
     let mut tx = vec![TxMessage::new_zeroed(); 1];
 
     // segment 0 to 0
+    // The host rejects the infinite loop with `TransitionMode::SyncIdx`, so the transition mode in the frame is rewritten to check the firmware.
     {
         let d = WithSegment {
             inner: GainSTM {
@@ -428,11 +429,17 @@ fn send_gain_stm_invalid_transition_mode() -> anyhow::Result<()> {
                 option: GainSTMOption::default(),
             },
             segment: Segment::S0,
-            transition_mode: Some(TransitionMode::SyncIdx),
+            transition_mode: Some(TransitionMode::Immediate),
         };
+        let generator = d.operation_generator(&geometry, false)?;
+        let mut op = OperationHandler::generate(generator, &geometry);
+        OperationHandler::pack(&mut op, &geometry, &mut tx, false)?;
+        tx[0].payload_mut()[3] = TransitionMode::SyncIdx.mode();
+
+        cpu.send(&tx);
         assert_eq!(
             Err(AUTDDriverError::InvalidTransitionMode),
-            send(&mut cpu, d, &geometry, &mut tx)
+            autd3_driver::firmware::cpu::check_firmware_err(&cpu.rx())
         );
     }
 
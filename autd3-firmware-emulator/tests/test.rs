@@ -12,7 +12,9 @@ use autd3_driver::{
 use autd3_firmware_emulator::{cpu::params::ERR_BIT, CPUEmulator};
 use zerocopy::FromZeros;
 
+mod field;
 mod op;
+mod waveform;
 
 pub fn create_geometry(n: usize) -> Geometry {
     Geometry::new(
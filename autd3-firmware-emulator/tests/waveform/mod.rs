@@ -0,0 +1,133 @@
+use std::{collections::HashMap, num::NonZeroU16, time::Duration};
+
+use autd3_driver::{
+    datagram::{FixedCompletionSteps, Silencer},
+    ethercat::DcSysTime,
+    firmware::{
+        cpu::TxMessage,
+        fpga::{Drive, EmitIntensity, Phase, SilencerTarget},
+    },
+};
+use autd3_firmware_emulator::CPUEmulator;
+
+use crate::{create_geometry, op::gain::TestGain, send};
+
+use zerocopy::FromZeros;
+
+fn uniform(n: usize, intensity: EmitIntensity, phase: Phase) -> TestGain {
+    TestGain {
+        data: HashMap::from([(0, vec![Drive { phase, intensity }; n])]),
+    }
+}
+
+#[rstest::rstest]
+#[case(SilencerTarget::Intensity)]
+#[case(SilencerTarget::PulseWidth)]
+#[test]
+fn waveform_rise(#[case] target: SilencerTarget) -> anyhow::Result<()> {
+    let geometry = create_geometry(1);
+    let n = geometry.num_transducers();
+    let mut cpu = CPUEmulator::new(0, n);
+    let mut tx = vec![TxMessage::new_zeroed(); 1];
+
+    send(
+        &mut cpu,
+        Silencer {
+            config: FixedCompletionSteps {
+                intensity: NonZeroU16::new(10).unwrap(),
+                phase: NonZeroU16::new(40).unwrap(),
+                strict_mode: true,
+            },
+            target,
+        },
+        &geometry,
+        &mut tx,
+    )?;
+    send(
+        &mut cpu,
+        uniform(n, EmitIntensity::MIN, Phase::ZERO),
+        &geometry,
+        &mut tx,
+    )?;
+
+    let prev = cpu.fpga_mut().waveform(DcSysTime::ZERO, 4);
+    assert_eq!(4, prev.times().len());
+    assert!(prev.pulse_widths().iter().flatten().all(|&w| w == 0));
+    assert!(prev.signal(0).iter().all(|&s| !s));
+
+    send(
+        &mut cpu,
+        uniform(n, EmitIntensity::MAX, Phase(0x80)),
+        &geometry,
+        &mut tx,
+    )?;
+    let waveform = cpu.fpga_mut().waveform_continue_with(&prev, 50);
+    assert_eq!(
+        DcSysTime::ZERO + Duration::from_micros(100),
+        waveform.times()[0]
+    );
+
+    let max = cpu.fpga().to_pulse_width(EmitIntensity::MAX, 0xFF);
+    let pulse_widths = waveform
+        .pulse_widths()
+        .iter()
+        .map(|w| w[0])
+        .collect::<Vec<_>>();
+    assert!(pulse_widths.windows(2).all(|w| w[0] <= w[1]));
+    assert!(pulse_widths[8] < max);
+    assert!(pulse_widths[9..].iter().all(|&w| w == max));
+
+    let phases = waveform.phases().iter().map(|p| p[0].0).collect::<Vec<_>>();
+    assert!(phases.windows(2).all(|p| p[0] <= p[1]));
+    assert!(phases[38] < 0x80);
+    assert!(phases[39..].iter().all(|&p| p == 0x80));
+
+    let signal = waveform.signal(0);
+    assert_eq!(50 * 256, signal.len());
+    signal
+        .chunks(256)
+        .zip(waveform.pulse_widths().iter())
+        .for_each(|(s, w)| assert_eq!(w[0] as usize, s.iter().filter(|&&s| s).count()));
+    let last = &signal[49 * 256..];
+    assert!(last[0x80]);
+    assert!(last[0x80 - max as usize / 2]);
+    assert!(!last[0x80 - max as usize / 2 - 1]);
+    assert!(last[0x80 + max as usize / 2 - 1]);
+    assert!(!last[0]);
+
+    Ok(())
+}
+
+#[test]
+fn waveform_without_silencer() -> anyhow::Result<()> {
+    let geometry = create_geometry(1);
+    let n = geometry.num_transducers();
+    let mut cpu = CPUEmulator::new(0, n);
+    let mut tx = vec![TxMessage::new_zeroed(); 1];
+
+    send(&mut cpu, Silencer::disable(), &geometry, &mut tx)?;
+    send(
+        &mut cpu,
+        uniform(n, EmitIntensity::MIN, Phase::ZERO),
+        &geometry,
+        &mut tx,
+    )?;
+    let prev = cpu.fpga_mut().waveform(DcSysTime::ZERO, 1);
+
+    send(
+        &mut cpu,
+        uniform(n, EmitIntensity::MAX, Phase(0x80)),
+        &geometry,
+        &mut tx,
+    )?;
+    let waveform = cpu.fpga_mut().waveform_continue_with(&prev, 2);
+    let max = cpu.fpga().to_pulse_width(EmitIntensity::MAX, 0xFF);
+    assert!(waveform.pulse_widths().iter().flatten().all(|&w| w == max));
+    assert!(waveform
+        .phases()
+        .iter()
+        .flatten()
+        .all(|&p| p == Phase(0x80)));
+
+    Ok(())
+}
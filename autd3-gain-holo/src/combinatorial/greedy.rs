@@ -1,6 +1,10 @@
 use std::{collections::HashMap, num::NonZeroU8};
 
-use crate::{constraint::EmissionConstraint, Amplitude, Complex};
+use crate::{
+    constraint::{EmissionConstraint, EmissionLimit},
+    helper::{balance_amplitudes, merge_transducer_enable},
+    Amplitude, Complex,
+};
 
 use autd3_core::{
     acoustics::{directivity::Directivity, propagate},
@@ -15,12 +19,16 @@ use nalgebra::ComplexField;
 use rand::prelude::*;
 
 /// The option of [`Greedy`].
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct GreedyOption<D: Directivity> {
     /// The number of phase divisions.
     pub phase_div: NonZeroU8,
     /// The transducers' emission constraint.
     pub constraint: EmissionConstraint,
+    /// The per-device limit of the emission intensity.
+    pub limit: EmissionLimit,
+    /// If `true`, the target amplitudes are scaled by the inverse of the row norms of the transfer matrix to compensate for the distance and obliquity of each focus.
+    pub balance_amplitude: bool,
     #[doc(hidden)]
     pub __phantom: std::marker::PhantomData<D>,
 }
@@ -30,6 +38,8 @@ impl<D: Directivity> Default for GreedyOption<D> {
         Self {
             phase_div: NonZeroU8::new(16).unwrap(),
             constraint: EmissionConstraint::Uniform(EmitIntensity::MAX),
+            limit: EmissionLimit::default(),
+            balance_amplitude: false,
             __phantom: std::marker::PhantomData,
         }
     }
@@ -100,7 +110,12 @@ impl<D: Directivity> Gain for Greedy<D> {
         filter: Option<&HashMap<usize, BitVec>>,
         _: bool,
     ) -> Result<Self::G, GainError> {
-        let (foci, amps): (Vec<_>, Vec<_>) = self.foci.into_iter().unzip();
+        let enable_filter = merge_transducer_enable(geometry, filter);
+        let filter = enable_filter.as_ref().or(filter);
+        let (foci, mut amps): (Vec<_>, Vec<_>) = self.foci.into_iter().unzip();
+        if self.option.balance_amplitude {
+            balance_amplitudes::<D>(geometry, &foci, &mut amps, filter);
+        }
 
         let phase_candidates = (0..self.option.phase_div.get())
             .map(|i| {
@@ -149,6 +164,8 @@ impl<D: Directivity> Gain for Greedy<D> {
                 &foci,
                 &mut tmp,
             );
+            let scale = self.option.limit.scale(dev_idx);
+            tmp.iter_mut().for_each(|t| *t *= scale);
             let (phase, _) =
                 phase_candidates
                     .iter()
@@ -171,7 +188,7 @@ impl<D: Directivity> Gain for Greedy<D> {
             });
             g.get_mut(&dev_idx).unwrap()[idx] = Drive {
                 phase: Phase::from(phase),
-                intensity: self.option.constraint.convert(1.0, 1.0),
+                intensity: EmissionLimit::apply(self.option.constraint.convert(1.0, 1.0), scale),
             };
         });
 
@@ -181,7 +198,7 @@ impl<D: Directivity> Gain for Greedy<D> {
 
 #[cfg(test)]
 mod tests {
-    use autd3_core::acoustics::directivity::Sphere;
+    use autd3_core::{acoustics::directivity::Sphere, geometry::Vector3};
 
     use crate::tests::create_geometry;
 
@@ -282,4 +299,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_greedy_limit() -> anyhow::Result<()> {
+        let geometry = create_geometry(2, 1);
+
+        let mut g = Greedy::<Sphere> {
+            foci: vec![(
+                geometry.center().unwrap() + Vector3::new(0., 0., 150.),
+                1. * Pa,
+            )],
+            option: GreedyOption {
+                limit: EmissionLimit {
+                    limits: HashMap::from([(1, EmitIntensity(0x80))]),
+                },
+                ..Default::default()
+            },
+        }
+        .init_full(&geometry, None, false)?;
+        [EmitIntensity::MAX, EmitIntensity(0x80)]
+            .into_iter()
+            .zip(geometry.iter())
+            .for_each(|(expect, dev)| {
+                let f = g.generate(dev);
+                dev.iter()
+                    .for_each(|tr| assert_eq!(expect, f.calc(tr).intensity));
+            });
+
+        Ok(())
+    }
 }
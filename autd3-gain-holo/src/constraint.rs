@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use autd3_core::gain::EmitIntensity;
 
 /// Emission constraint of transducers.
@@ -32,6 +34,32 @@ impl EmissionConstraint {
     }
 }
 
+/// Per-device limit of the emission intensity.
+///
+/// The intensity of the transducers of the devices in [`limits`] is normalized to the limit instead of [`EmitIntensity::MAX`] after applying [`EmissionConstraint`].
+/// This is useful to cap some devices, e.g., ones close to the user's face, without lowering the intensity of the others.
+///
+/// [`limits`]: EmissionLimit::limits
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EmissionLimit {
+    /// The maximum intensity of each device. The devices not contained are not limited.
+    pub limits: HashMap<usize, EmitIntensity>,
+}
+
+impl EmissionLimit {
+    #[doc(hidden)]
+    pub fn scale(&self, dev_idx: usize) -> f32 {
+        self.limits
+            .get(&dev_idx)
+            .map_or(1., |limit| limit.0 as f32 / EmitIntensity::MAX.0 as f32)
+    }
+
+    #[doc(hidden)]
+    pub fn apply(intensity: EmitIntensity, scale: f32) -> EmitIntensity {
+        EmitIntensity((intensity.0 as f32 * scale).round() as u8)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,4 +130,17 @@ mod tests {
             EmissionConstraint::Clamp(min, max).convert(value, max_value)
         );
     }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(EmitIntensity::MAX, 0, EmitIntensity::MAX)]
+    #[case(EmitIntensity(64), 1, EmitIntensity::MAX)]
+    #[case(EmitIntensity(32), 1, EmitIntensity(128))]
+    #[case(EmitIntensity::MIN, 2, EmitIntensity::MAX)]
+    fn limit(#[case] expect: EmitIntensity, #[case] dev_idx: usize, #[case] value: EmitIntensity) {
+        let limit = EmissionLimit {
+            limits: HashMap::from([(1, EmitIntensity(64)), (2, EmitIntensity::MIN)]),
+        };
+        assert_eq!(expect, EmissionLimit::apply(value, limit.scale(dev_idx)));
+    }
 }
@@ -1,14 +1,15 @@
 use std::{collections::HashMap, sync::Arc};
 
 use autd3_core::{
+    acoustics::{directivity::Directivity, propagate},
     defined::rad,
     gain::{BitVec, Drive, GainCalculator, GainCalculatorGenerator, GainError, Phase},
-    geometry::{Device, Geometry, Transducer},
+    geometry::{Device, Geometry, Point3, Transducer},
 };
 use nalgebra::ComplexField;
 use rayon::iter::Either;
 
-use crate::EmissionConstraint;
+use crate::{Amplitude, EmissionConstraint, EmissionLimit};
 
 pub trait IntoDrive {
     fn into_phase(self) -> Phase;
@@ -48,6 +49,7 @@ pub struct HoloCalculator<T: IntoDrive + Copy + Send + Sync + 'static> {
     map: Either<Option<Vec<Option<usize>>>, usize>,
     max_coefficient: f32,
     constraint: EmissionConstraint,
+    scale: f32,
 }
 
 impl<T: IntoDrive + Copy + Send + Sync + 'static> GainCalculator for HoloCalculator<T> {
@@ -59,9 +61,11 @@ impl<T: IntoDrive + Copy + Send + Sync + 'static> GainCalculator for HoloCalcula
                     map[tr.idx()].map(|idx| {
                         let x = self.q[idx];
                         let phase = x.into_phase();
-                        let intensity = self
-                            .constraint
-                            .convert(x.into_intensity(), self.max_coefficient);
+                        let intensity = EmissionLimit::apply(
+                            self.constraint
+                                .convert(x.into_intensity(), self.max_coefficient),
+                            self.scale,
+                        );
                         Drive { phase, intensity }
                     })
                 })
@@ -69,9 +73,11 @@ impl<T: IntoDrive + Copy + Send + Sync + 'static> GainCalculator for HoloCalcula
             Either::Right(base_idx) => {
                 let x = self.q[base_idx + tr.idx()];
                 let phase = x.into_phase();
-                let intensity = self
-                    .constraint
-                    .convert(x.into_intensity(), self.max_coefficient);
+                let intensity = EmissionLimit::apply(
+                    self.constraint
+                        .convert(x.into_intensity(), self.max_coefficient),
+                    self.scale,
+                );
                 Drive { phase, intensity }
             }
         }
@@ -91,6 +97,7 @@ pub struct HoloCalculatorGenerator<T: IntoDrive + Copy + Send + Sync + 'static>
     map: Either<HashMap<usize, Option<Vec<Option<usize>>>>, HashMap<usize, usize>>,
     max_coefficient: f32,
     constraint: EmissionConstraint,
+    limit: EmissionLimit,
 }
 
 impl<T: IntoDrive + Copy + Send + Sync + 'static> GainCalculatorGenerator
@@ -105,12 +112,14 @@ impl<T: IntoDrive + Copy + Send + Sync + 'static> GainCalculatorGenerator
                 map: Either::Left(map.remove(&device.idx()).unwrap()),
                 max_coefficient: self.max_coefficient,
                 constraint: self.constraint,
+                scale: self.limit.scale(device.idx()),
             },
             Either::Right(map) => HoloCalculator {
                 q: self.q.clone(),
                 map: Either::Right(map[&device.idx()]),
                 max_coefficient: self.max_coefficient,
                 constraint: self.constraint,
+                scale: self.limit.scale(device.idx()),
             },
         }
     }
@@ -126,6 +135,7 @@ pub(crate) fn generate_result<T>(
     >,
     max_coefficient: f32,
     constraint: EmissionConstraint,
+    limit: EmissionLimit,
     filter: Option<&HashMap<usize, BitVec>>,
 ) -> Result<HoloCalculatorGenerator<T>, GainError>
 where
@@ -160,6 +170,7 @@ where
             ),
             max_coefficient,
             constraint,
+            limit,
         })
     } else {
         Ok(HoloCalculatorGenerator {
@@ -176,6 +187,154 @@ where
             ),
             max_coefficient,
             constraint,
+            limit,
         })
     }
 }
+
+/// Merges the enable flags of the transducers into the `filter`.
+///
+/// Returns `None` if all transducers are enabled, in which case the `filter` can be used as is.
+pub(crate) fn merge_transducer_enable(
+    geometry: &Geometry,
+    filter: Option<&HashMap<usize, BitVec>>,
+) -> Option<HashMap<usize, BitVec>> {
+    if geometry.devices().all(|dev| dev.iter().all(|tr| tr.enable)) {
+        return None;
+    }
+    Some(
+        geometry
+            .devices()
+            .filter_map(|dev| {
+                let mut f = match filter {
+                    Some(filter) => filter.get(&dev.idx())?.clone(),
+                    None => BitVec::from_elem(dev.num_transducers(), true),
+                };
+                dev.iter()
+                    .filter(|tr| !tr.enable)
+                    .for_each(|tr| f.set(tr.idx(), false));
+                Some((dev.idx(), f))
+            })
+            .collect(),
+    )
+}
+
+pub(crate) fn balance_amplitudes<D: Directivity>(
+    geometry: &Geometry,
+    foci: &[Point3],
+    amps: &mut [Amplitude],
+    filter: Option<&HashMap<usize, BitVec>>,
+) {
+    let norms = foci
+        .iter()
+        .map(|f| {
+            geometry
+                .devices()
+                .flat_map(|dev| {
+                    let filter = filter.map(|filter| filter.get(&dev.idx()));
+                    dev.iter()
+                        .filter(move |tr| match filter {
+                            Some(Some(filter)) => filter[tr.idx()],
+                            Some(None) => false,
+                            None => true,
+                        })
+                        .map(move |tr| {
+                            propagate::<D>(tr, dev.wavenumber(), dev.axial_direction(), f)
+                                .norm_sqr()
+                        })
+                })
+                .sum::<f32>()
+                .sqrt()
+        })
+        .collect::<Vec<_>>();
+
+    let valid = norms.iter().filter(|&&n| n > 0.).count();
+    if valid == 0 {
+        return;
+    }
+    let mean = norms.iter().sum::<f32>() / valid as f32;
+
+    amps.iter_mut().zip(norms).for_each(|(amp, norm)| {
+        if norm > 0. {
+            amp.value *= mean / norm;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_core::acoustics::directivity::Sphere;
+
+    use crate::{tests::create_geometry, Pa};
+
+    use super::*;
+
+    #[test]
+    fn merge_transducer_enable_all_enabled() {
+        let geometry = create_geometry(2, 1);
+        assert_eq!(None, merge_transducer_enable(&geometry, None));
+    }
+
+    #[test]
+    fn merge_transducer_enable_disabled() {
+        let mut geometry = create_geometry(2, 1);
+        geometry[1]
+            .iter_mut()
+            .filter(|tr| tr.idx() < 10)
+            .for_each(|tr| tr.enable = false);
+
+        let merged = merge_transducer_enable(&geometry, None).unwrap();
+        assert_eq!(2, merged.len());
+        assert!(merged[&0].all());
+        assert!(merged[&1].iter().enumerate().all(|(i, b)| b == (i >= 10)));
+
+        let filter = geometry
+            .iter()
+            .map(|dev| (dev.idx(), dev.iter().map(|tr| tr.idx() % 2 == 0).collect()))
+            .filter(|(idx, _)| *idx == 1)
+            .collect::<HashMap<_, BitVec>>();
+        let merged = merge_transducer_enable(&geometry, Some(&filter)).unwrap();
+        assert_eq!(1, merged.len());
+        assert!(merged[&1]
+            .iter()
+            .enumerate()
+            .all(|(i, b)| b == (i >= 10 && i % 2 == 0)));
+    }
+
+    #[test]
+    fn balance_amplitudes_equal() {
+        let geometry = create_geometry(1, 1);
+        let center = geometry.center().unwrap();
+        let foci = [
+            center + autd3_core::geometry::Vector3::new(30., 0., 150.),
+            center + autd3_core::geometry::Vector3::new(-30., 0., 150.),
+        ];
+        let mut amps = [1. * Pa, 1. * Pa];
+        balance_amplitudes::<Sphere>(&geometry, &foci, &mut amps, None);
+        approx::assert_relative_eq!(amps[0].pascal(), amps[1].pascal(), max_relative = 1e-3);
+        approx::assert_relative_eq!(1., amps[0].pascal(), max_relative = 1e-3);
+    }
+
+    #[test]
+    fn balance_amplitudes_far_focus_is_boosted() {
+        let geometry = create_geometry(1, 1);
+        let center = geometry.center().unwrap();
+        let foci = [
+            center + autd3_core::geometry::Vector3::new(0., 0., 100.),
+            center + autd3_core::geometry::Vector3::new(0., 0., 300.),
+        ];
+        let mut amps = [1. * Pa, 1. * Pa];
+        balance_amplitudes::<Sphere>(&geometry, &foci, &mut amps, None);
+        assert!(amps[0].pascal() < 1.);
+        assert!(amps[1].pascal() > 1.);
+    }
+
+    #[test]
+    fn balance_amplitudes_filtered_out() {
+        let geometry = create_geometry(1, 1);
+        let foci = [geometry.center().unwrap() + autd3_core::geometry::Vector3::new(0., 0., 150.)];
+        let mut amps = [1. * Pa];
+        balance_amplitudes::<Sphere>(&geometry, &foci, &mut amps, Some(&HashMap::new()));
+        assert_eq!(1. * Pa, amps[0]);
+    }
+}
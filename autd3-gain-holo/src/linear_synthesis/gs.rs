@@ -1,8 +1,10 @@
 use std::{collections::HashMap, num::NonZeroUsize, sync::Arc};
 
 use crate::{
-    constraint::EmissionConstraint,
-    helper::{generate_result, HoloCalculatorGenerator},
+    constraint::{EmissionConstraint, EmissionLimit},
+    helper::{
+        balance_amplitudes, generate_result, merge_transducer_enable, HoloCalculatorGenerator,
+    },
     Amplitude, Complex, LinAlgBackend, Trans,
 };
 
@@ -12,12 +14,16 @@ use derive_new::new;
 use zerocopy::{FromBytes, IntoBytes};
 
 /// The option of [`GS`].
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct GSOption<D: Directivity> {
     /// The number of iterations.
     pub repeat: NonZeroUsize,
     /// The transducers' emission constraint.
     pub constraint: EmissionConstraint,
+    /// The per-device limit of the emission intensity.
+    pub limit: EmissionLimit,
+    /// If `true`, the target amplitudes are scaled by the inverse of the row norms of the transfer matrix to compensate for the distance and obliquity of each focus.
+    pub balance_amplitude: bool,
     #[debug(ignore)]
     #[doc(hidden)]
     pub __phantom: std::marker::PhantomData<D>,
@@ -28,6 +34,8 @@ impl<D: Directivity> Default for GSOption<D> {
         Self {
             repeat: NonZeroUsize::new(100).unwrap(),
             constraint: EmissionConstraint::Clamp(EmitIntensity::MIN, EmitIntensity::MAX),
+            limit: EmissionLimit::default(),
+            balance_amplitude: false,
             __phantom: std::marker::PhantomData,
         }
     }
@@ -62,7 +70,12 @@ impl<D: Directivity, B: LinAlgBackend<D>> Gain for GS<D, B> {
         filter: Option<&HashMap<usize, BitVec>>,
         _: bool,
     ) -> Result<Self::G, GainError> {
-        let (foci, amps): (Vec<_>, Vec<_>) = self.foci.into_iter().unzip();
+        let enable_filter = merge_transducer_enable(geometry, filter);
+        let filter = enable_filter.as_ref().or(filter);
+        let (foci, mut amps): (Vec<_>, Vec<_>) = self.foci.into_iter().unzip();
+        if self.option.balance_amplitude {
+            balance_amplitudes::<D>(geometry, &foci, &mut amps, filter);
+        }
 
         let g = self
             .backend
@@ -109,7 +122,14 @@ impl<D: Directivity, B: LinAlgBackend<D>> Gain for GS<D, B> {
         self.backend.norm_squared_cv(&q, &mut abs)?;
         let max_coefficient = self.backend.max_v(&abs)?.sqrt();
         let q = self.backend.to_host_cv(q)?;
-        generate_result(geometry, q, max_coefficient, self.option.constraint, filter)
+        generate_result(
+            geometry,
+            q,
+            max_coefficient,
+            self.option.constraint,
+            self.option.limit,
+            filter,
+        )
     }
 }
 
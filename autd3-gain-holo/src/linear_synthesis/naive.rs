@@ -1,8 +1,10 @@
 use std::{collections::HashMap, sync::Arc};
 
 use crate::{
-    constraint::EmissionConstraint,
-    helper::{generate_result, HoloCalculatorGenerator},
+    constraint::{EmissionConstraint, EmissionLimit},
+    helper::{
+        balance_amplitudes, generate_result, merge_transducer_enable, HoloCalculatorGenerator,
+    },
     Amplitude, Complex, LinAlgBackend, Trans,
 };
 
@@ -12,10 +14,14 @@ use derive_new::new;
 use zerocopy::{FromBytes, IntoBytes};
 
 /// The option of [`Naive`].
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NaiveOption<D: Directivity> {
     /// The transducers' emission constraint.
     pub constraint: EmissionConstraint,
+    /// The per-device limit of the emission intensity.
+    pub limit: EmissionLimit,
+    /// If `true`, the target amplitudes are scaled by the inverse of the row norms of the transfer matrix to compensate for the distance and obliquity of each focus.
+    pub balance_amplitude: bool,
     #[doc(hidden)]
     #[debug(ignore)]
     pub __phantom: std::marker::PhantomData<D>,
@@ -25,6 +31,8 @@ impl<D: Directivity> Default for NaiveOption<D> {
     fn default() -> Self {
         Self {
             constraint: EmissionConstraint::Clamp(EmitIntensity::MIN, EmitIntensity::MAX),
+            limit: EmissionLimit::default(),
+            balance_amplitude: false,
             __phantom: std::marker::PhantomData,
         }
     }
@@ -57,7 +65,12 @@ impl<D: Directivity, B: LinAlgBackend<D>> Gain for Naive<D, B> {
         filter: Option<&HashMap<usize, BitVec>>,
         _: bool,
     ) -> Result<Self::G, GainError> {
-        let (foci, amps): (Vec<_>, Vec<_>) = self.foci.into_iter().unzip();
+        let enable_filter = merge_transducer_enable(geometry, filter);
+        let filter = enable_filter.as_ref().or(filter);
+        let (foci, mut amps): (Vec<_>, Vec<_>) = self.foci.into_iter().unzip();
+        if self.option.balance_amplitude {
+            balance_amplitudes::<D>(geometry, &foci, &mut amps, filter);
+        }
 
         let g = self
             .backend
@@ -85,7 +98,14 @@ impl<D: Directivity, B: LinAlgBackend<D>> Gain for Naive<D, B> {
         self.backend.norm_squared_cv(&q, &mut abs)?;
         let max_coefficient = self.backend.max_v(&abs)?.sqrt();
         let q = self.backend.to_host_cv(q)?;
-        generate_result(geometry, q, max_coefficient, self.option.constraint, filter)
+        generate_result(
+            geometry,
+            q,
+            max_coefficient,
+            self.option.constraint,
+            self.option.limit,
+            filter,
+        )
     }
 }
 
@@ -151,6 +171,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_naive_transducer_disabled() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(1, 1);
+        geometry[0]
+            .iter_mut()
+            .filter(|tr| tr.idx() < 100)
+            .for_each(|tr| tr.enable = false);
+        let backend = std::sync::Arc::new(NalgebraBackend::default());
+
+        let g = Naive {
+            foci: vec![(Point3::origin(), 1. * Pa), (Point3::origin(), 1. * Pa)],
+            backend,
+            option: NaiveOption {
+                constraint: EmissionConstraint::Uniform(EmitIntensity::MAX),
+                ..Default::default()
+            },
+        };
+
+        let mut g = g.init_full(&geometry, None, false)?;
+        let f = g.generate(&geometry[0]);
+        geometry[0].iter().for_each(|tr| {
+            assert_eq!(tr.enable, f.calc(tr) != Drive::NULL);
+        });
+
+        Ok(())
+    }
+
     #[test]
     fn test_naive_filtered() {
         let geometry = create_geometry(1, 1);
@@ -212,4 +259,108 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_naive_balance_amplitude() -> anyhow::Result<()> {
+        use autd3_core::{
+            acoustics::{directivity::Sphere, propagate},
+            geometry::Vector3,
+        };
+
+        let geometry = create_geometry(1, 1);
+        let foci = [
+            geometry.center().unwrap() + Vector3::new(0., 0., 100.),
+            geometry.center().unwrap() + Vector3::new(80., 0., 300.),
+        ];
+
+        let ratio = |balance_amplitude: bool| -> anyhow::Result<f32> {
+            let mut g = Naive::<Sphere, _> {
+                foci: foci.iter().map(|&p| (p, 1. * Pa)).collect(),
+                backend: std::sync::Arc::new(NalgebraBackend::default()),
+                option: NaiveOption {
+                    constraint: EmissionConstraint::Normalize,
+                    balance_amplitude,
+                    ..Default::default()
+                },
+            }
+            .init_full(&geometry, None, false)?;
+            let f = g.generate(&geometry[0]);
+            let p = foci
+                .iter()
+                .map(|target| {
+                    geometry[0]
+                        .iter()
+                        .map(|tr| {
+                            let d = f.calc(tr);
+                            propagate::<Sphere>(
+                                tr,
+                                geometry[0].wavenumber(),
+                                geometry[0].axial_direction(),
+                                target,
+                            ) * Complex::from_polar(d.intensity.0 as f32 / 255., d.phase.radian())
+                        })
+                        .sum::<Complex>()
+                        .norm()
+                })
+                .collect::<Vec<_>>();
+            Ok(p[1] / p[0])
+        };
+
+        assert!(ratio(false)? < ratio(true)?);
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(EmissionConstraint::Normalize)]
+    #[case(EmissionConstraint::Uniform(EmitIntensity::MAX))]
+    #[case(EmissionConstraint::Clamp(EmitIntensity::MIN, EmitIntensity::MAX))]
+    fn test_naive_limit(#[case] constraint: EmissionConstraint) -> anyhow::Result<()> {
+        use autd3_core::geometry::Vector3;
+
+        let geometry = create_geometry(2, 1);
+        let foci = vec![(
+            geometry.center().unwrap() + Vector3::new(0., 0., 150.),
+            1. * Pa,
+        )];
+
+        let drives = |limit: EmissionLimit| -> anyhow::Result<Vec<Vec<Drive>>> {
+            let mut g = Naive {
+                foci: foci.clone(),
+                backend: std::sync::Arc::new(NalgebraBackend::default()),
+                option: NaiveOption {
+                    constraint,
+                    limit,
+                    ..Default::default()
+                },
+            }
+            .init_full(&geometry, None, false)?;
+            Ok(geometry
+                .iter()
+                .map(|dev| {
+                    let f = g.generate(dev);
+                    dev.iter().map(|tr| f.calc(tr)).collect()
+                })
+                .collect())
+        };
+
+        let expect = drives(EmissionLimit::default())?;
+        let limited = drives(EmissionLimit {
+            limits: HashMap::from([(1, EmitIntensity(0x40))]),
+        })?;
+        assert_eq!(expect[0], limited[0]);
+        expect[1].iter().zip(limited[1].iter()).for_each(|(e, l)| {
+            assert_eq!(e.phase, l.phase);
+            assert_eq!(
+                EmitIntensity((e.intensity.0 as f32 * 0x40 as f32 / 255.).round() as u8),
+                l.intensity
+            );
+        });
+        assert!(limited[1]
+            .iter()
+            .all(|d| d.intensity <= EmitIntensity(0x40)));
+
+        Ok(())
+    }
 }
@@ -1,8 +1,10 @@
 use std::{collections::HashMap, num::NonZeroUsize, sync::Arc};
 
 use crate::{
-    constraint::EmissionConstraint,
-    helper::{generate_result, HoloCalculatorGenerator},
+    constraint::{EmissionConstraint, EmissionLimit},
+    helper::{
+        balance_amplitudes, generate_result, merge_transducer_enable, HoloCalculatorGenerator,
+    },
     Amplitude, Complex, HoloError, LinAlgBackend, Trans,
 };
 
@@ -26,6 +28,10 @@ pub struct LMOption<D: Directivity> {
     pub initial: Vec<f32>,
     /// The transducers' emission constraint.
     pub constraint: EmissionConstraint,
+    /// The per-device limit of the emission intensity.
+    pub limit: EmissionLimit,
+    /// If `true`, the target amplitudes are scaled by the inverse of the row norms of the transfer matrix to compensate for the distance and obliquity of each focus.
+    pub balance_amplitude: bool,
     #[doc(hidden)]
     #[debug(ignore)]
     pub __phantom: std::marker::PhantomData<D>,
@@ -40,6 +46,8 @@ impl<D: Directivity> Default for LMOption<D> {
             k_max: NonZeroUsize::new(5).unwrap(),
             initial: vec![],
             constraint: EmissionConstraint::Clamp(EmitIntensity::MIN, EmitIntensity::MAX),
+            limit: EmissionLimit::default(),
+            balance_amplitude: false,
             __phantom: std::marker::PhantomData,
         }
     }
@@ -138,7 +146,12 @@ impl<D: Directivity, B: LinAlgBackend<D>> Gain for LM<D, B> {
         filter: Option<&HashMap<usize, BitVec>>,
         _: bool,
     ) -> Result<Self::G, GainError> {
-        let (foci, amps): (Vec<_>, Vec<_>) = self.foci.into_iter().unzip();
+        let enable_filter = merge_transducer_enable(geometry, filter);
+        let filter = enable_filter.as_ref().or(filter);
+        let (foci, mut amps): (Vec<_>, Vec<_>) = self.foci.into_iter().unzip();
+        if self.option.balance_amplitude {
+            balance_amplitudes::<D>(geometry, &foci, &mut amps, filter);
+        }
 
         let g = self
             .backend
@@ -276,7 +289,14 @@ impl<D: Directivity, B: LinAlgBackend<D>> Gain for LM<D, B> {
         }
 
         let x = self.backend.to_host_v(x)?;
-        generate_result(geometry, x, 1.0, self.option.constraint, filter)
+        generate_result(
+            geometry,
+            x,
+            1.0,
+            self.option.constraint,
+            self.option.limit,
+            filter,
+        )
     }
 }
 
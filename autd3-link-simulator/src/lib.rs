@@ -11,14 +11,37 @@ use autd3_core::link::{AsyncLink, LinkError, RxMessage, TxMessage};
 
 use autd3_protobuf::*;
 
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
+
+const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(1);
 
 struct SimulatorInner {
     client: simulator_client::SimulatorClient<tonic::transport::Channel>,
     last_geometry_version: usize,
+    last_geometry: Geometry,
+    supports_pose_update: bool,
 }
 
 impl SimulatorInner {
+    fn unreachable(addr: &SocketAddr, e: impl std::fmt::Display) -> LinkError {
+        LinkError::new(format!("Simulator@{} is not reachable: {}", addr, e))
+    }
+
+    #[cfg(feature = "blocking")]
+    fn preflight(addr: &SocketAddr) -> Result<(), LinkError> {
+        std::net::TcpStream::connect_timeout(addr, PREFLIGHT_TIMEOUT)
+            .map(|_| ())
+            .map_err(|e| Self::unreachable(addr, e))
+    }
+
+    async fn preflight_async(addr: &SocketAddr) -> Result<(), LinkError> {
+        tokio::time::timeout(PREFLIGHT_TIMEOUT, tokio::net::TcpStream::connect(addr))
+            .await
+            .map_err(|e| Self::unreachable(addr, e))?
+            .map(|_| ())
+            .map_err(|e| Self::unreachable(addr, e))
+    }
+
     async fn open(
         addr: &SocketAddr,
         geometry: &autd3_core::geometry::Geometry,
@@ -31,17 +54,17 @@ impl SimulatorInner {
             .map_err(AUTDProtoBufError::from)?;
         let mut client = simulator_client::SimulatorClient::new(conn);
 
-        client
-            .config_geomety(geometry.to_msg(None)?)
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to configure simulator geometry: {}", e);
-                AUTDProtoBufError::SendError("Failed to initialize simulator".to_string())
-            })?;
+        let msg = geometry.to_msg(None)?;
+        client.config_geomety(msg.clone()).await.map_err(|e| {
+            tracing::error!("Failed to configure simulator geometry: {}", e);
+            AUTDProtoBufError::SendError("Failed to initialize simulator".to_string())
+        })?;
 
         Ok(Self {
             client,
             last_geometry_version: geometry.version(),
+            last_geometry: msg,
+            supports_pose_update: true,
         })
     }
 
@@ -54,18 +77,60 @@ impl SimulatorInner {
         Ok(())
     }
 
+    async fn reconfigure(
+        &mut self,
+        geometry: &autd3_core::geometry::Geometry,
+    ) -> Result<(), LinkError> {
+        self.last_geometry_version = geometry.version();
+        let msg = geometry.to_msg(None)?;
+        self.client.config_geomety(msg.clone()).await.map_err(|e| {
+            tracing::error!("Failed to reconfigure simulator geometry: {}", e);
+            AUTDProtoBufError::SendError("Failed to reconfigure geometry".to_string())
+        })?;
+        self.last_geometry = msg;
+        Ok(())
+    }
+
     async fn update(&mut self, geometry: &autd3_core::geometry::Geometry) -> Result<(), LinkError> {
         if self.last_geometry_version == geometry.version() {
             return Ok(());
         }
         self.last_geometry_version = geometry.version();
-        self.client
-            .update_geomety(geometry.to_msg(None)?)
-            .await
-            .map_err(|e| {
-                tracing::error!("Failed to update geometry: {}", e);
-                AUTDProtoBufError::SendError("Failed to update geometry".to_string())
-            })?;
+        let msg = geometry.to_msg(None)?;
+
+        // If only a few devices are moved, send the poses of them instead of the whole geometry.
+        if self.supports_pose_update {
+            if let Some(update) = PoseUpdate::diff(&msg, &self.last_geometry) {
+                if update.poses.is_empty() {
+                    return Ok(());
+                }
+                match self.client.update_poses(update).await {
+                    Ok(_) => {
+                        self.last_geometry = msg;
+                        return Ok(());
+                    }
+                    Err(e) if e.code() == tonic::Code::Unimplemented => {
+                        tracing::warn!(
+                            "Simulator does not support pose update, fallback to geometry update"
+                        );
+                        self.supports_pose_update = false;
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to update poses: {}", e);
+                        return Err(AUTDProtoBufError::SendError(
+                            "Failed to update poses".to_string(),
+                        )
+                        .into());
+                    }
+                }
+            }
+        }
+
+        self.client.update_geomety(msg.clone()).await.map_err(|e| {
+            tracing::error!("Failed to update geometry: {}", e);
+            AUTDProtoBufError::SendError("Failed to update geometry".to_string())
+        })?;
+        self.last_geometry = msg;
         Ok(())
     }
 
@@ -121,6 +186,10 @@ impl Simulator {
 
 #[cfg_attr(feature = "async-trait", autd3_core::async_trait)]
 impl AsyncLink for Simulator {
+    async fn preflight(&mut self, _: &autd3_core::geometry::Geometry) -> Result<(), LinkError> {
+        SimulatorInner::preflight_async(&self.addr).await
+    }
+
     async fn open(&mut self, geometry: &autd3_core::geometry::Geometry) -> Result<(), LinkError> {
         self.inner = Some(SimulatorInner::open(&self.addr, geometry).await?);
         Ok(())
@@ -133,6 +202,16 @@ impl AsyncLink for Simulator {
         Ok(())
     }
 
+    async fn reconfigure(
+        &mut self,
+        geometry: &autd3_core::geometry::Geometry,
+    ) -> Result<(), LinkError> {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.reconfigure(geometry).await?;
+        }
+        Ok(())
+    }
+
     async fn update(&mut self, geometry: &autd3_core::geometry::Geometry) -> Result<(), LinkError> {
         if let Some(inner) = self.inner.as_mut() {
             inner.update(geometry).await?;
@@ -167,6 +246,10 @@ use autd3_core::link::Link;
 #[cfg_attr(docsrs, doc(cfg(feature = "blocking")))]
 #[cfg(feature = "blocking")]
 impl Link for Simulator {
+    fn preflight(&mut self, _: &autd3_core::geometry::Geometry) -> Result<(), LinkError> {
+        SimulatorInner::preflight(&self.addr)
+    }
+
     fn open(&mut self, geometry: &autd3_core::derive::Geometry) -> Result<(), LinkError> {
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
@@ -188,6 +271,17 @@ impl Link for Simulator {
         })
     }
 
+    fn reconfigure(&mut self, geometry: &autd3_core::geometry::Geometry) -> Result<(), LinkError> {
+        self.runtime.as_ref().map_or(Ok(()), |runtime| {
+            runtime.block_on(async {
+                if let Some(inner) = self.inner.as_mut() {
+                    inner.reconfigure(geometry).await?;
+                }
+                Ok(())
+            })
+        })
+    }
+
     fn update(&mut self, geometry: &autd3_core::geometry::Geometry) -> Result<(), LinkError> {
         self.runtime.as_ref().map_or(Ok(()), |runtime| {
             runtime.block_on(async {
@@ -15,6 +15,10 @@ long AdsCSyncWriteReqEx(long port, const AmsAddr* pAddr, uint32_t indexGroup, ui
   return AdsSyncWriteReqEx(port, pAddr, indexGroup, indexOffset, bufferLength, buffer);
 }
 
+long AdsCSyncReadStateReqEx(long port, const AmsAddr* pAddr, uint16_t* adsState, uint16_t* devState) {
+  return AdsSyncReadStateReqEx(port, pAddr, adsState, devState);
+}
+
 void AdsCSetLocalAddress(AmsNetId ams) { AdsSetLocalAddress(ams); }
 
 long AdsCAddRoute(AmsNetId ams, const char* ip) { return AdsAddRoute(ams, ip); }
@@ -11,6 +11,7 @@ long AdsCSyncReadReqEx2(long port, const AmsAddr* pAddr, uint32_t indexGroup, ui
                         uint32_t* bytesRead);
 
 long AdsCSyncWriteReqEx(long port, const AmsAddr* pAddr, uint32_t indexGroup, uint32_t indexOffset, uint32_t bufferLength, const void* buffer);
+long AdsCSyncReadStateReqEx(long port, const AmsAddr* pAddr, uint16_t* adsState, uint16_t* devState);
 void AdsCSetLocalAddress(AmsNetId ams);
 long AdsCAddRoute(AmsNetId ams, const char* ip);
 #ifdef __cplusplus
@@ -0,0 +1,89 @@
+/// The ADS state of the TwinCAT3 server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AdsState(pub u16);
+
+impl AdsState {
+    /// The ADS state of the running server.
+    pub const RUN: AdsState = AdsState(5);
+    /// The ADS state of the stopped server.
+    pub const STOP: AdsState = AdsState(6);
+    /// The ADS state of the server in config mode.
+    pub const CONFIG: AdsState = AdsState(15);
+
+    /// Returns `true` if the server is running.
+    pub const fn is_running(&self) -> bool {
+        self.0 == Self::RUN.0
+    }
+}
+
+/// The state of the TwinCAT3 server read by ADS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerState {
+    /// The ADS state.
+    pub ads_state: AdsState,
+    /// The device state, which is specific to the server.
+    pub device_state: u16,
+}
+
+/// The diagnostics of the TwinCAT3 links.
+///
+/// The frame counters are counted on the client side since the link was created, so frames lost in the EtherCAT network of the server are not included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Diagnostics {
+    /// Whether the ADS port is open, i.e., the AMS router is available.
+    pub is_open: bool,
+    /// The state of the server. `None` if it could not be read.
+    pub server_state: Option<ServerState>,
+    /// The number of frames sent successfully.
+    pub frames_sent: u64,
+    /// The number of frames received successfully.
+    pub frames_received: u64,
+    /// The number of failed sends.
+    pub send_errors: u64,
+    /// The number of failed receives.
+    pub receive_errors: u64,
+    /// The last ADS error code.
+    pub last_error: Option<i32>,
+}
+
+impl Diagnostics {
+    /// Returns `true` if the link is open, the server is running, and no frames have failed.
+    pub fn is_healthy(&self) -> bool {
+        self.is_open
+            && self.server_state.is_some_and(|s| s.ads_state.is_running())
+            && self.send_errors == 0
+            && self.receive_errors == 0
+    }
+
+    pub(crate) fn record_send(&mut self, err: i32) {
+        if err == 0 {
+            self.frames_sent += 1;
+        } else {
+            self.send_errors += 1;
+            self.last_error = Some(err);
+        }
+    }
+
+    pub(crate) fn record_receive(&mut self, err: i32) {
+        if err == 0 {
+            self.frames_received += 1;
+        } else {
+            self.receive_errors += 1;
+            self.last_error = Some(err);
+        }
+    }
+
+    pub(crate) fn with_state(mut self, is_open: bool, state: Result<ServerState, i32>) -> Self {
+        self.is_open = is_open;
+        match state {
+            Ok(state) => self.server_state = Some(state),
+            Err(err) => {
+                self.server_state = None;
+                if err != 0 {
+                    self.last_error = Some(err);
+                }
+            }
+        }
+        self
+    }
+}
@@ -26,6 +26,8 @@ pub enum AdsError {
     ReadData(i32),
     #[error("Invalid IP address: {0}")]
     InvalidIp(String),
+    #[error("TwinCAT3 server ({0}) is not reachable: {1}")]
+    ServerUnreachable(String, String),
 }
 
 impl From<AdsError> for LinkError {
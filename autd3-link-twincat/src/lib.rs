@@ -5,6 +5,7 @@
 
 //! This crate provides a link to AUTD using TwinCAT3.
 
+mod diagnostics;
 mod error;
 
 #[cfg(feature = "local")]
@@ -15,6 +16,7 @@ pub mod local;
 /// Using TwinCAT3 on a remote machine.
 pub mod remote;
 
+pub use diagnostics::{AdsState, Diagnostics, ServerState};
 #[cfg(feature = "local")]
 pub use local::TwinCAT;
 
@@ -23,7 +23,10 @@ struct AmsAddr {
     port: u16,
 }
 
-use crate::error::AdsError;
+use crate::{
+    diagnostics::{AdsState, Diagnostics, ServerState},
+    error::AdsError,
+};
 
 const INDEX_GROUP: u32 = 0x0304_0030;
 const INDEX_OFFSET_BASE: u32 = 0x8100_0000;
@@ -39,6 +42,7 @@ pub struct TwinCAT {
     port: i32,
     send_addr: AmsAddr,
     dll: Library,
+    diagnostics: Diagnostics,
 }
 
 impl TwinCAT {
@@ -51,11 +55,50 @@ impl TwinCAT {
                 port: 0,
             },
             dll: unsafe { lib::Library::new("TcAdsDll") }.map_err(|_| AdsError::DllNotFound)?,
+            diagnostics: Diagnostics::default(),
         })
     }
+
+    /// Gets the [`Diagnostics`] of the link, reading the state of the server if the link is open.
+    pub fn diagnostics(&self) -> Result<Diagnostics, LinkError> {
+        if !<Self as Link>::is_open(self) {
+            return Ok(self.diagnostics.with_state(false, Err(0)));
+        }
+        let mut ads_state = 0u16;
+        let mut device_state = 0u16;
+        let n_err = unsafe {
+            self.dll
+                .get::<unsafe extern "C" fn(i32, *const AmsAddr, *mut u16, *mut u16) -> i32>(
+                    b"AdsSyncReadStateReqEx",
+                )
+                .map_err(|_| AdsError::FunctionNotFound("AdsSyncReadStateReqEx".to_owned()))?(
+                self.port,
+                &raw const self.send_addr,
+                &mut ads_state as *mut _,
+                &mut device_state as *mut _,
+            )
+        };
+        Ok(self.diagnostics.with_state(
+            true,
+            if n_err == 0 {
+                Ok(ServerState {
+                    ads_state: AdsState(ads_state),
+                    device_state,
+                })
+            } else {
+                Err(n_err)
+            },
+        ))
+    }
 }
 
 impl Link for TwinCAT {
+    fn preflight(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        // Opening an ADS port fails if the TwinCAT3 ADS router is not running.
+        <Self as Link>::open(self, geometry)?;
+        <Self as Link>::close(self)
+    }
+
     fn open(&mut self, _: &Geometry) -> Result<(), LinkError> {
         let port = unsafe {
             self.dll
@@ -122,6 +165,7 @@ impl Link for TwinCAT {
                     tx.as_ptr() as _,
             );
 
+            self.diagnostics.record_send(n_err.max(0));
             if n_err > 0 {
                 Err(AdsError::SendData(n_err).into())
             } else {
@@ -154,6 +198,7 @@ impl Link for TwinCAT {
                 &mut read_bytes as *mut u32,
             );
 
+            self.diagnostics.record_receive(n_err.max(0));
             if n_err > 0 {
                 Err(AdsError::ReadData(n_err).into())
             } else {
@@ -174,6 +219,10 @@ use autd3_core::link::AsyncLink;
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 #[cfg_attr(feature = "async-trait", autd3_core::async_trait)]
 impl AsyncLink for TwinCAT {
+    async fn preflight(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        <Self as Link>::preflight(self, geometry)
+    }
+
     async fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
         <Self as Link>::open(self, geometry)
     }
@@ -38,4 +38,10 @@ extern "C" {
         buffer: *mut c_void,
         bytesRead: *mut u32,
     ) -> c_long;
+    pub fn AdsCSyncReadStateReqEx(
+        port: c_long,
+        pAddr: *const AmsAddr,
+        adsState: *mut u16,
+        devState: *mut u16,
+    ) -> c_long;
 }
@@ -1,4 +1,8 @@
-use std::ffi::{c_long, CString};
+use std::{
+    ffi::{c_long, CString},
+    net::{SocketAddr, TcpStream},
+    time::Duration,
+};
 
 use itertools::Itertools;
 
@@ -9,12 +13,18 @@ use autd3_core::{
     link::{Link, LinkError, RxMessage, TxMessage},
 };
 
-use crate::{error::AdsError, remote::native_methods::*};
+use crate::{
+    diagnostics::{AdsState, Diagnostics, ServerState},
+    error::AdsError,
+    remote::native_methods::*,
+};
 
 const INDEX_GROUP: u32 = 0x0304_0030;
 const INDEX_OFFSET_BASE: u32 = 0x8100_0000;
 const INDEX_OFFSET_BASE_READ: u32 = 0x8000_0000;
 const PORT: u16 = 301;
+const ADS_TCP_PORT: u16 = 48898;
+const PREFLIGHT_TIMEOUT: Duration = Duration::from_secs(1);
 
 /// A [`Link`] using TwinCAT3.
 ///
@@ -26,6 +36,7 @@ pub struct RemoteTwinCAT {
     option: RemoteTwinCATOption,
     port: c_long,
     net_id: AmsNetId,
+    diagnostics: Diagnostics,
 }
 
 /// The option of [`RemoteTwinCAT`].
@@ -45,56 +56,97 @@ impl RemoteTwinCAT {
             option,
             port: 0,
             net_id: AmsNetId { b: [0; 6] },
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    /// Gets the [`Diagnostics`] of the link, reading the state of the server if the link is open.
+    pub fn diagnostics(&self) -> Diagnostics {
+        if !<Self as Link>::is_open(self) {
+            return self.diagnostics.with_state(false, Err(0));
+        }
+        let addr = AmsAddr {
+            net_id: self.net_id,
+            port: PORT,
+        };
+        let mut ads_state = 0u16;
+        let mut device_state = 0u16;
+        let res = unsafe {
+            AdsCSyncReadStateReqEx(
+                self.port,
+                &addr as _,
+                &mut ads_state as _,
+                &mut device_state as _,
+            )
+        };
+        self.diagnostics.with_state(
+            true,
+            if res == 0 {
+                Ok(ServerState {
+                    ads_state: AdsState(ads_state),
+                    device_state,
+                })
+            } else {
+                Err(res as _)
+            },
+        )
+    }
+}
+
+fn parse_ams_net_id(ams_net_id: &str) -> Result<[u8; 6], AdsError> {
+    ams_net_id
+        .split('.')
+        .map(|octet| octet.parse::<u8>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| AdsError::AmsNetIdParse)?
+        .try_into()
+        .map_err(|_| AdsError::AmsNetIdParse)
+}
+
+impl RemoteTwinCAT {
+    fn preflight_addr(&self) -> Result<SocketAddr, AdsError> {
+        let octets = parse_ams_net_id(&self.server_ams_net_id)?;
+        if !self.option.client_ams_net_id.is_empty() {
+            parse_ams_net_id(&self.option.client_ams_net_id)?;
+        }
+
+        let ip = self.server_ip(&octets);
+        format!("{}:{}", ip, ADS_TCP_PORT)
+            .parse::<SocketAddr>()
+            .map_err(|_| AdsError::InvalidIp(ip))
+    }
+
+    fn server_ip(&self, octets: &[u8; 6]) -> String {
+        if self.option.server_ip.is_empty() {
+            octets[0..4].iter().map(|v| v.to_string()).join(".")
+        } else {
+            self.option.server_ip.to_owned()
         }
     }
 }
 
 impl Link for RemoteTwinCAT {
+    fn preflight(&mut self, _: &Geometry) -> Result<(), LinkError> {
+        let addr = self.preflight_addr()?;
+        TcpStream::connect_timeout(&addr, PREFLIGHT_TIMEOUT)
+            .map_err(|e| AdsError::ServerUnreachable(addr.to_string(), e.to_string()))?;
+        Ok(())
+    }
+
     fn open(&mut self, _: &Geometry) -> Result<(), LinkError> {
         tracing::info!("Connecting to TwinCAT3");
 
         let RemoteTwinCATOption {
-            server_ip,
-            client_ams_net_id,
+            client_ams_net_id, ..
         } = &self.option;
 
-        let octets = self
-            .server_ams_net_id
-            .split('.')
-            .map(|octet| octet.parse::<u8>())
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|_| AdsError::AmsNetIdParse)?;
-
-        if octets.len() != 6 {
-            return Err(AdsError::AmsNetIdParse.into());
-        }
-
-        let ip = if server_ip.is_empty() {
-            octets[0..4].iter().map(|v| v.to_string()).join(".")
-        } else {
-            server_ip.to_owned()
-        };
+        let octets = parse_ams_net_id(&self.server_ams_net_id)?;
+        let ip = self.server_ip(&octets);
         tracing::info!("Server IP: {}", ip);
 
         if !client_ams_net_id.is_empty() {
-            let local_octets = client_ams_net_id
-                .split('.')
-                .map(|octet| octet.parse::<u8>())
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|_| AdsError::AmsNetIdParse)?;
-            if local_octets.len() != 6 {
-                return Err(AdsError::AmsNetIdParse.into());
-            }
-
             let local_addr = AmsNetId {
-                b: [
-                    local_octets[0],
-                    local_octets[1],
-                    local_octets[2],
-                    local_octets[3],
-                    local_octets[4],
-                    local_octets[5],
-                ],
+                b: parse_ams_net_id(client_ams_net_id)?,
             };
             tracing::info!("Setting local AMS Net ID: {:?}", local_addr);
             unsafe {
@@ -102,11 +154,7 @@ impl Link for RemoteTwinCAT {
             }
         }
 
-        let net_id = AmsNetId {
-            b: [
-                octets[0], octets[1], octets[2], octets[3], octets[4], octets[5],
-            ],
-        };
+        let net_id = AmsNetId { b: octets };
 
         tracing::info!("Setting remote AMS Net ID: {:?}", net_id);
         let ip = CString::new(ip.clone()).map_err(|_| AdsError::InvalidIp(ip.clone()))?;
@@ -160,6 +208,7 @@ impl Link for RemoteTwinCAT {
             )
         };
 
+        self.diagnostics.record_send(res as _);
         if res == 0 {
             return Ok(true);
         }
@@ -190,6 +239,7 @@ impl Link for RemoteTwinCAT {
             )
         };
 
+        self.diagnostics.record_receive(res as _);
         if res == 0 {
             return Ok(true);
         }
@@ -209,6 +259,18 @@ use autd3_core::link::AsyncLink;
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 #[cfg_attr(feature = "async-trait", autd3_core::async_trait)]
 impl AsyncLink for RemoteTwinCAT {
+    async fn preflight(&mut self, _: &Geometry) -> Result<(), LinkError> {
+        let addr = self.preflight_addr()?;
+        let unreachable = |e: &dyn std::fmt::Display| {
+            AdsError::ServerUnreachable(addr.to_string(), e.to_string())
+        };
+        tokio::time::timeout(PREFLIGHT_TIMEOUT, tokio::net::TcpStream::connect(addr))
+            .await
+            .map_err(|e| unreachable(&e))?
+            .map_err(|e| unreachable(&e))?;
+        Ok(())
+    }
+
     async fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
         <Self as Link>::open(self, geometry)
     }
@@ -5,7 +5,7 @@ use thiserror::Error;
 #[non_exhaustive]
 pub enum AUTDProtoBufError {
     #[error("{0}")]
-    Status(#[from] tonic::Status),
+    Status(Box<tonic::Status>),
     #[error("{0}")]
     DecodeError(#[from] prost::DecodeError),
     #[error("{0}")]
@@ -25,6 +25,8 @@ pub enum AUTDProtoBufError {
     NotSupportedData,
     #[error("Failed to parse data or missing required fields")]
     DataParseError,
+    #[error("Duplicate device index: {0}")]
+    DuplicateDeviceIndex(usize),
     #[cfg(feature = "lightweight")]
     #[error("{0}")]
     UnknownEnumValue(#[from] prost::UnknownEnumValue),
@@ -38,6 +40,12 @@ pub enum AUTDProtoBufError {
 
 // GRCOV_EXCL_START
 
+impl From<tonic::Status> for AUTDProtoBufError {
+    fn from(e: tonic::Status) -> Self {
+        Self::Status(Box::new(e))
+    }
+}
+
 impl From<AUTDProtoBufError> for tonic::Status {
     fn from(e: AUTDProtoBufError) -> Self {
         tonic::Status::internal(e.to_string())
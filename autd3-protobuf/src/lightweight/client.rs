@@ -1,12 +1,35 @@
-use std::net::SocketAddr;
+use std::{net::SocketAddr, num::NonZeroUsize, time::Duration};
 
 use autd3_core::geometry::{Device, Geometry, IntoDevice};
+use prost::Message;
+use tonic::codegen::tokio_stream::{Stream, StreamExt};
 
-use crate::{traits::*, OpenRequestLightweight};
+use crate::{traits::*, DatagramChunk, OpenRequestLightweight};
+
+use super::{session::SESSION_KEY, stream::ReceiverStream};
+
+/// The option of [`LightweightClient::send_stream`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendStreamOption {
+    /// The maximum size of each chunk in bytes.
+    pub chunk_size: NonZeroUsize,
+    /// The maximum number of chunks sent before they are acknowledged.
+    pub window: NonZeroUsize,
+}
+
+impl Default for SendStreamOption {
+    fn default() -> Self {
+        Self {
+            chunk_size: NonZeroUsize::new(1024 * 1024).unwrap(),
+            window: NonZeroUsize::new(4).unwrap(),
+        }
+    }
+}
 
 pub struct LightweightClient {
     client: crate::pb::ecat_light_client::EcatLightClient<tonic::transport::Channel>,
     geometry: Geometry,
+    session: tonic::metadata::AsciiMetadataValue,
 }
 
 impl LightweightClient {
@@ -43,7 +66,21 @@ impl LightweightClient {
         if !res.success {
             return Err(crate::error::AUTDProtoBufError::SendError(res.msg));
         }
-        Ok(Self { client, geometry })
+        let session = res
+            .session
+            .parse()
+            .map_err(|_| crate::error::AUTDProtoBufError::DataParseError)?;
+        Ok(Self {
+            client,
+            geometry,
+            session,
+        })
+    }
+
+    fn request<T>(&self, msg: T) -> tonic::Request<T> {
+        let mut req = tonic::Request::new(msg);
+        req.metadata_mut().insert(SESSION_KEY, self.session.clone());
+        req
     }
 
     pub async fn firmware_version(
@@ -54,9 +91,7 @@ impl LightweightClient {
     > {
         let res = self
             .client
-            .firmware_version(tonic::Request::new(
-                crate::pb::FirmwareVersionRequestLightweight {},
-            ))
+            .firmware_version(self.request(crate::pb::FirmwareVersionRequestLightweight {}))
             .await?
             .into_inner();
         if !res.success {
@@ -65,13 +100,47 @@ impl LightweightClient {
         Vec::from_msg(&res)
     }
 
+    /// Subscribes to the FPGA state pushed each time it changes. The state is checked at the `interval`.
+    ///
+    /// The FPGA state of the devices is [`None`] unless [`ReadsFPGAState`] is enabled.
+    ///
+    /// [`ReadsFPGAState`]: autd3_driver::datagram::ReadsFPGAState
+    pub async fn watch_fpga_state(
+        &mut self,
+        interval: Duration,
+    ) -> Result<
+        impl Stream<
+            Item = Result<
+                Vec<Option<autd3_driver::firmware::fpga::FPGAState>>,
+                crate::error::AUTDProtoBufError,
+            >,
+        >,
+        crate::error::AUTDProtoBufError,
+    > {
+        let req = self.request(crate::pb::WatchFpgaStateRequestLightweight {
+            interval_us: Some(interval.as_micros() as _),
+        });
+        Ok(self
+            .client
+            .watch_fpga_state(req)
+            .await?
+            .into_inner()
+            .map(|res| {
+                let res = res?;
+                if !res.success {
+                    return Err(crate::error::AUTDProtoBufError::SendError(res.msg));
+                }
+                Vec::from_msg(&res)
+            }))
+    }
+
     pub async fn send(
         &mut self,
         datagram: impl ToMessage<Message = crate::pb::Datagram>,
     ) -> Result<bool, crate::error::AUTDProtoBufError> {
         let res = self
             .client
-            .send(tonic::Request::new(datagram.to_msg(Some(&self.geometry))?))
+            .send(self.request(datagram.to_msg(Some(&self.geometry))?))
             .await?
             .into_inner();
         if res.err {
@@ -80,10 +149,58 @@ impl LightweightClient {
         Ok(res.success)
     }
 
+    /// Sends the datagram split into chunks, e.g., a large STM exceeding the message size limit of gRPC.
+    pub async fn send_stream(
+        &mut self,
+        datagram: impl ToMessage<Message = crate::pb::Datagram>,
+        option: SendStreamOption,
+    ) -> Result<bool, crate::error::AUTDProtoBufError> {
+        let data = datagram.to_msg(Some(&self.geometry))?.encode_to_vec();
+        let total_size = data.len() as u64;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(option.window.get());
+        let mut acks = self
+            .client
+            .send_stream(self.request(ReceiverStream::new(rx)))
+            .await?
+            .into_inner();
+
+        let mut in_flight = 0;
+        for (i, chunk) in data.chunks(option.chunk_size.get()).enumerate() {
+            if in_flight == option.window.get() {
+                acks.message().await?;
+                in_flight -= 1;
+            }
+            if tx
+                .send(DatagramChunk {
+                    total_size,
+                    offset: (i * option.chunk_size.get()) as _,
+                    data: chunk.to_vec(),
+                })
+                .await
+                .is_err()
+            {
+                break;
+            }
+            in_flight += 1;
+        }
+        drop(tx);
+
+        while let Some(ack) = acks.message().await? {
+            if let Some(res) = ack.result {
+                if res.err {
+                    return Err(crate::error::AUTDProtoBufError::SendError(res.msg));
+                }
+                return Ok(res.success);
+            }
+        }
+        Err(crate::error::AUTDProtoBufError::DataParseError)
+    }
+
     pub async fn close(mut self) -> Result<(), crate::error::AUTDProtoBufError> {
         let res = self
             .client
-            .close(crate::pb::CloseRequestLightweight {})
+            .close(self.request(crate::pb::CloseRequestLightweight {}))
             .await?
             .into_inner();
         if !res.success {
@@ -91,4 +208,19 @@ impl LightweightClient {
         }
         Ok(())
     }
+
+    /// Lists the active sessions of the server.
+    pub async fn list_sessions(
+        &mut self,
+    ) -> Result<
+        Vec<crate::pb::list_sessions_response_lightweight::Session>,
+        crate::error::AUTDProtoBufError,
+    > {
+        Ok(self
+            .client
+            .list_sessions(crate::pb::ListSessionsRequestLightweight {})
+            .await?
+            .into_inner()
+            .sessions)
+    }
 }
@@ -1,5 +1,8 @@
 mod client;
 mod server;
+mod session;
+mod stream;
 
 pub use client::*;
 pub use server::*;
+pub use session::TakeoverPolicy;
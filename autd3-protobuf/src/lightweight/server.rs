@@ -1,9 +1,30 @@
 use crate::{error::*, pb::*, traits::*};
 
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
 use autd3_core::{defined::Freq, link::LinkError};
 use autd3_driver::datagram::WithLoopBehavior;
-use tokio::sync::RwLock;
-use tonic::{Request, Response, Status};
+use prost::Message;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tonic::{metadata::MetadataMap, Request, Response, Status, Streaming};
+
+use super::{
+    session::{Session, TakeoverPolicy},
+    stream::ReceiverStream,
+};
+
+const ACK_CHANNEL_SIZE: usize = 16;
+const DEFAULT_WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// The option of [`LightweightServer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LightweightServerOption {
+    /// The policy for `Open` requested while another session is active.
+    pub takeover_policy: TakeoverPolicy,
+}
 
 #[doc(hidden)]
 pub struct LightweightServer<
@@ -12,8 +33,11 @@ pub struct LightweightServer<
 > where
     L: Sync,
 {
-    autd: RwLock<Option<autd3::r#async::Controller<L>>>,
+    autd: Arc<RwLock<Option<autd3::r#async::Controller<L>>>>,
+    session: Arc<Mutex<Option<Session>>>,
+    next_session_id: AtomicU64,
     link: F,
+    option: LightweightServerOption,
 }
 
 impl<
@@ -24,12 +48,59 @@ where
     L: Sync,
 {
     pub fn new(f: F) -> Self {
+        Self::new_with_option(f, LightweightServerOption::default())
+    }
+
+    pub fn new_with_option(f: F, option: LightweightServerOption) -> Self {
         LightweightServer {
-            autd: RwLock::new(None),
+            autd: Arc::new(RwLock::new(None)),
+            session: Arc::new(Mutex::new(None)),
+            next_session_id: AtomicU64::new(0),
             link: f,
+            option,
         }
     }
 
+    /// Checks that the request belongs to the active session, and returns the id of the session.
+    async fn authorize(&self, metadata: &MetadataMap) -> Result<Option<u64>, Status> {
+        match self.session.lock().await.as_mut() {
+            Some(session) if session.is_owner(metadata) => {
+                session.touch();
+                Ok(Some(session.id()))
+            }
+            Some(session) => Err(Status::permission_denied(format!(
+                "{} is active, the session token is stale or missing",
+                session
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    async fn open_controller(&self, req: OpenRequestLightweight) -> Result<usize, String> {
+        if let Some(autd) = self.autd.write().await.take() {
+            autd.close().await.map_err(|e| format!("{}", e))?;
+        }
+        let geometry = autd3_core::geometry::Geometry::from_msg(
+            req.geometry
+                .as_ref()
+                .ok_or_else(|| "Geometry is not configured".to_string())?,
+        )
+        .map_err(|_| "Failed to parse Geometry".to_string())?;
+        let link = (self.link)().map_err(|e| format!("Failed to open link: {}", e))?;
+        let autd = autd3::r#async::Controller::open(
+            geometry.iter().map(|d| autd3::prelude::AUTD3 {
+                pos: *d[0].position(),
+                rot: *d.rotation(),
+            }),
+            link,
+        )
+        .await
+        .map_err(|e| format!("{}", e))?;
+        let num_devices = autd.geometry().num_devices();
+        *self.autd.write().await = Some(autd);
+        Ok(num_devices)
+    }
+
     fn parse_gain(gain: &Gain) -> Result<autd3_driver::datagram::BoxedGain, AUTDProtoBufError> {
         use autd3_driver::datagram::IntoBoxedGain;
         Ok(match &gain.gain {
@@ -38,6 +109,7 @@ where
             Some(gain::Gain::Plane(msg)) => autd3::gain::Plane::from_msg(msg)?.into_boxed(),
             Some(gain::Gain::Uniform(msg)) => autd3::gain::Uniform::from_msg(msg)?.into_boxed(),
             Some(gain::Gain::Null(msg)) => autd3::gain::Null::from_msg(msg)?.into_boxed(),
+            Some(gain::Gain::Raw(msg)) => raw_gain_into_boxed(msg)?,
             Some(gain::Gain::Lm(msg)) => autd3_gain_holo::LM::from_msg(msg)?.into_boxed(),
             Some(gain::Gain::Gs(msg)) => autd3_gain_holo::GS::from_msg(msg)?.into_boxed(),
             Some(gain::Gain::Naive(msg)) => autd3_gain_holo::Naive::from_msg(msg)?.into_boxed(),
@@ -84,6 +156,9 @@ where
             Some(modulation::Modulation::Static(msg)) => {
                 autd3::prelude::Static::from_msg(msg)?.into_boxed()
             }
+            Some(modulation::Modulation::Raw(msg)) => {
+                autd3::modulation::Custom::from_msg(msg)?.into_boxed()
+            }
             Some(modulation::Modulation::SineNearest(msg)) => {
                 autd3::prelude::Sine::<autd3::modulation::sampling_mode::Nearest>::from_msg(msg)?
                     .into_boxed()
@@ -144,123 +219,12 @@ where
             loop_behavior,
         })
     }
-}
-
-#[tonic::async_trait]
-impl<
-        L: autd3_core::link::AsyncLink + 'static,
-        F: Fn() -> Result<L, LinkError> + Send + Sync + 'static,
-    > ecat_light_server::EcatLight for LightweightServer<L, F>
-where
-    L: Sync,
-{
-    async fn open(
-        &self,
-        req: Request<OpenRequestLightweight>,
-    ) -> Result<Response<SendResponseLightweight>, Status> {
-        if let Some(autd) = self.autd.write().await.take() {
-            if let Err(e) = autd.close().await {
-                return Ok(Response::new(SendResponseLightweight {
-                    success: false,
-                    err: true,
-                    msg: format!("{}", e),
-                }));
-            }
-        }
-        let req = req.into_inner();
-        if let Some(ref geometry) = req.geometry {
-            if let Ok(geometry) = autd3_core::geometry::Geometry::from_msg(geometry) {
-                *self.autd.write().await = match autd3::r#async::Controller::open(
-                    geometry.iter().map(|d| autd3::prelude::AUTD3 {
-                        pos: *d[0].position(),
-                        rot: *d.rotation(),
-                    }),
-                    match (self.link)() {
-                        Ok(link) => link,
-                        Err(e) => {
-                            return Ok(Response::new(SendResponseLightweight {
-                                success: false,
-                                err: true,
-                                msg: format!("Failed to open link: {}", e),
-                            }))
-                        }
-                    },
-                )
-                .await
-                {
-                    Ok(autd) => Some(autd),
-                    Err(e) => {
-                        return Ok(Response::new(SendResponseLightweight {
-                            success: false,
-                            err: true,
-                            msg: format!("{}", e),
-                        }))
-                    }
-                };
-                Ok(Response::new(SendResponseLightweight {
-                    success: true,
-                    err: false,
-                    msg: String::new(),
-                }))
-            } else {
-                return Ok(Response::new(SendResponseLightweight {
-                    success: false,
-                    err: true,
-                    msg: "Failed to parse Geometry".to_string(),
-                }));
-            }
-        } else {
-            Ok(Response::new(SendResponseLightweight {
-                success: false,
-                err: true,
-                msg: "Geometry is not configured".to_string(),
-            }))
-        }
-    }
-
-    async fn firmware_version(
-        &self,
-        _req: Request<FirmwareVersionRequestLightweight>,
-    ) -> Result<Response<FirmwareVersionResponseLightweight>, Status> {
-        if let Some(autd) = self.autd.write().await.as_mut() {
-            match autd.firmware_version().await {
-                Ok(list) => Ok(Response::new(FirmwareVersionResponseLightweight {
-                    success: true,
-                    msg: String::new(),
-                    firmware_version_list: list
-                        .iter()
-                        .map(|f| firmware_version_response_lightweight::FirmwareVersion {
-                            cpu_major_version: f.cpu.major.0 as _,
-                            cpu_minor_version: f.cpu.minor.0 as _,
-                            fpga_major_version: f.fpga.major.0 as _,
-                            fpga_minor_version: f.fpga.minor.0 as _,
-                            fpga_function_bits: f.fpga.function_bits as _,
-                        })
-                        .collect(),
-                })),
-                Err(e) => {
-                    return Ok(Response::new(FirmwareVersionResponseLightweight {
-                        success: false,
-                        msg: format!("{}", e),
-                        firmware_version_list: Vec::new(),
-                    }))
-                }
-            }
-        } else {
-            Ok(Response::new(FirmwareVersionResponseLightweight {
-                success: false,
-                msg: "Geometry is not configured".to_string(),
-                firmware_version_list: Vec::new(),
-            }))
-        }
-    }
 
-    async fn send(
-        &self,
-        req: Request<Datagram>,
-    ) -> Result<Response<SendResponseLightweight>, Status> {
-        if let Some(autd) = self.autd.write().await.as_mut() {
-            let datagram = req.into_inner();
+    async fn send_datagram(
+        autd: &RwLock<Option<autd3::r#async::Controller<L>>>,
+        datagram: Datagram,
+    ) -> Result<SendResponseLightweight, Status> {
+        if let Some(autd) = autd.write().await.as_mut() {
             let res = match datagram.datagram {
                 Some(datagram::Datagram::Gain(ref msg)) => autd.send(Self::parse_gain(msg)?).await,
                 Some(datagram::Datagram::GainWithSegment(ref msg)) => {
@@ -402,41 +366,295 @@ where
                 None => return Err(AUTDProtoBufError::NotSupportedData.into()),
             };
             match res {
-                Ok(_) => Ok(Response::new(SendResponseLightweight {
+                Ok(_) => Ok(SendResponseLightweight {
                     success: true,
                     err: false,
                     msg: String::new(),
-                })),
-                Err(e) => Ok(Response::new(SendResponseLightweight {
+                    session: String::new(),
+                }),
+                Err(e) => Ok(SendResponseLightweight {
                     success: false,
                     err: true,
                     msg: format!("{}", e),
-                })),
+                    session: String::new(),
+                }),
             }
         } else {
-            Ok(Response::new(SendResponseLightweight {
+            Ok(SendResponseLightweight {
                 success: false,
                 err: true,
                 msg: "Geometry is not configured".to_string(),
+                session: String::new(),
+            })
+        }
+    }
+
+    async fn receive_chunks(
+        autd: &RwLock<Option<autd3::r#async::Controller<L>>>,
+        chunks: &mut Streaming<DatagramChunk>,
+        ack: &mpsc::Sender<Result<SendStreamResponseLightweight, Status>>,
+    ) -> Result<SendStreamResponseLightweight, Status> {
+        let mut buffer = Vec::new();
+        let mut total_size = None;
+        while let Some(chunk) = chunks.message().await? {
+            let total_size = *total_size.get_or_insert(chunk.total_size);
+            if chunk.total_size != total_size
+                || chunk.offset != buffer.len() as u64
+                || buffer.len() as u64 + chunk.data.len() as u64 > total_size
+            {
+                return Err(Status::invalid_argument(format!(
+                    "Invalid chunk: offset {}, size {}, total size {}, received {}",
+                    chunk.offset,
+                    chunk.data.len(),
+                    chunk.total_size,
+                    buffer.len()
+                )));
+            }
+            buffer.extend_from_slice(&chunk.data);
+            if buffer.len() as u64 == total_size {
+                let datagram =
+                    Datagram::decode(buffer.as_slice()).map_err(AUTDProtoBufError::from)?;
+                return Ok(SendStreamResponseLightweight {
+                    received: total_size,
+                    result: Some(Self::send_datagram(autd, datagram).await?),
+                });
+            }
+            if ack
+                .send(Ok(SendStreamResponseLightweight {
+                    received: buffer.len() as _,
+                    result: None,
+                }))
+                .await
+                .is_err()
+            {
+                return Err(Status::cancelled("Client disconnected"));
+            }
+        }
+        Err(Status::invalid_argument(format!(
+            "Stream ended before all chunks were received: received {}, total size {}",
+            buffer.len(),
+            total_size.unwrap_or_default()
+        )))
+    }
+
+    async fn fpga_state(
+        autd: &RwLock<Option<autd3::r#async::Controller<L>>>,
+    ) -> FpgaStateResponseLightweight {
+        match autd.write().await.as_mut() {
+            Some(autd) => match autd.fpga_state().await {
+                Ok(state) => FpgaStateResponseLightweight {
+                    success: true,
+                    msg: String::new(),
+                    fpga_state_list: state
+                        .iter()
+                        .map(|s| fpga_state_response_lightweight::FpgaState {
+                            state: s.map(|s| s.state() as _),
+                        })
+                        .collect(),
+                },
+                Err(e) => FpgaStateResponseLightweight {
+                    success: false,
+                    msg: format!("{}", e),
+                    fpga_state_list: Vec::new(),
+                },
+            },
+            None => FpgaStateResponseLightweight {
+                success: false,
+                msg: "Controller is not opened".to_string(),
+                fpga_state_list: Vec::new(),
+            },
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<
+        L: autd3_core::link::AsyncLink + 'static,
+        F: Fn() -> Result<L, LinkError> + Send + Sync + 'static,
+    > ecat_light_server::EcatLight for LightweightServer<L, F>
+where
+    L: Sync,
+{
+    async fn open(
+        &self,
+        req: Request<OpenRequestLightweight>,
+    ) -> Result<Response<SendResponseLightweight>, Status> {
+        let mut session = self.session.lock().await;
+        if let Some(active) = session.as_ref() {
+            if !active.is_owner(req.metadata())
+                && !active.can_be_taken_over(self.option.takeover_policy)
+            {
+                return Ok(Response::new(SendResponseLightweight {
+                    success: false,
+                    err: true,
+                    msg: format!("{} is active", active),
+                    session: String::new(),
+                }));
+            }
+        }
+        *session = None;
+        let peer = req.remote_addr();
+        Ok(Response::new(
+            match self.open_controller(req.into_inner()).await {
+                Ok(num_devices) => {
+                    let new = Session::new(
+                        self.next_session_id.fetch_add(1, Ordering::Relaxed),
+                        peer,
+                        num_devices,
+                    );
+                    let token = new.token().to_string();
+                    *session = Some(new);
+                    SendResponseLightweight {
+                        success: true,
+                        err: false,
+                        msg: String::new(),
+                        session: token,
+                    }
+                }
+                Err(msg) => SendResponseLightweight {
+                    success: false,
+                    err: true,
+                    msg,
+                    session: String::new(),
+                },
+            },
+        ))
+    }
+
+    async fn firmware_version(
+        &self,
+        req: Request<FirmwareVersionRequestLightweight>,
+    ) -> Result<Response<FirmwareVersionResponseLightweight>, Status> {
+        self.authorize(req.metadata()).await?;
+        if let Some(autd) = self.autd.write().await.as_mut() {
+            match autd.firmware_version().await {
+                Ok(list) => Ok(Response::new(FirmwareVersionResponseLightweight {
+                    success: true,
+                    msg: String::new(),
+                    firmware_version_list: list
+                        .iter()
+                        .map(|f| firmware_version_response_lightweight::FirmwareVersion {
+                            cpu_major_version: f.cpu.major.0 as _,
+                            cpu_minor_version: f.cpu.minor.0 as _,
+                            fpga_major_version: f.fpga.major.0 as _,
+                            fpga_minor_version: f.fpga.minor.0 as _,
+                            fpga_function_bits: f.fpga.function_bits as _,
+                        })
+                        .collect(),
+                })),
+                Err(e) => {
+                    return Ok(Response::new(FirmwareVersionResponseLightweight {
+                        success: false,
+                        msg: format!("{}", e),
+                        firmware_version_list: Vec::new(),
+                    }))
+                }
+            }
+        } else {
+            Ok(Response::new(FirmwareVersionResponseLightweight {
+                success: false,
+                msg: "Geometry is not configured".to_string(),
+                firmware_version_list: Vec::new(),
             }))
         }
     }
 
+    type WatchFPGAStateStream = tonic::codegen::BoxStream<FpgaStateResponseLightweight>;
+
+    async fn watch_fpga_state(
+        &self,
+        req: Request<WatchFpgaStateRequestLightweight>,
+    ) -> Result<Response<Self::WatchFPGAStateStream>, Status> {
+        let id = self.authorize(req.metadata()).await?;
+        let interval = req
+            .into_inner()
+            .interval_us
+            .map(std::time::Duration::from_micros)
+            .unwrap_or(DEFAULT_WATCH_INTERVAL);
+        if interval.is_zero() {
+            return Err(Status::invalid_argument("Interval must be greater than 0"));
+        }
+        let autd = self.autd.clone();
+        let session = self.session.clone();
+        let (tx, rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            let mut prev = None;
+            while !tx.is_closed() {
+                ticker.tick().await;
+                if session.lock().await.as_ref().map(Session::id) != id {
+                    _ = tx
+                        .send(Ok(FpgaStateResponseLightweight {
+                            success: false,
+                            msg: "Session is closed".to_string(),
+                            fpga_state_list: Vec::new(),
+                        }))
+                        .await;
+                    break;
+                }
+                let res = Self::fpga_state(&autd).await;
+                if !res.success {
+                    _ = tx.send(Ok(res)).await;
+                    break;
+                }
+                if prev.as_ref() == Some(&res.fpga_state_list) {
+                    continue;
+                }
+                prev = Some(res.fpga_state_list.clone());
+                if tx.send(Ok(res)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn send(
+        &self,
+        req: Request<Datagram>,
+    ) -> Result<Response<SendResponseLightweight>, Status> {
+        self.authorize(req.metadata()).await?;
+        Ok(Response::new(
+            Self::send_datagram(&self.autd, req.into_inner()).await?,
+        ))
+    }
+
+    type SendStreamStream = tonic::codegen::BoxStream<SendStreamResponseLightweight>;
+
+    async fn send_stream(
+        &self,
+        req: Request<Streaming<DatagramChunk>>,
+    ) -> Result<Response<Self::SendStreamStream>, Status> {
+        self.authorize(req.metadata()).await?;
+        let mut chunks = req.into_inner();
+        let autd = self.autd.clone();
+        let (tx, rx) = mpsc::channel(ACK_CHANNEL_SIZE);
+        tokio::spawn(async move {
+            let res = Self::receive_chunks(&autd, &mut chunks, &tx).await;
+            _ = tx.send(res).await;
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
     async fn close(
         &self,
-        _: Request<CloseRequestLightweight>,
+        req: Request<CloseRequestLightweight>,
     ) -> Result<Response<SendResponseLightweight>, Status> {
+        self.authorize(req.metadata()).await?;
+        *self.session.lock().await = None;
         if let Some(autd) = self.autd.write().await.take() {
             match autd.close().await {
                 Ok(_) => Ok(Response::new(SendResponseLightweight {
                     success: true,
                     err: false,
                     msg: String::new(),
+                    session: String::new(),
                 })),
                 Err(e) => Ok(Response::new(SendResponseLightweight {
                     success: false,
                     err: true,
                     msg: format!("{}", e),
+                    session: String::new(),
                 })),
             }
         } else {
@@ -444,7 +662,258 @@ where
                 success: false,
                 err: true,
                 msg: "Controller is not opened".to_string(),
+                session: String::new(),
             }))
         }
     }
+
+    async fn list_sessions(
+        &self,
+        _: Request<ListSessionsRequestLightweight>,
+    ) -> Result<Response<ListSessionsResponseLightweight>, Status> {
+        Ok(Response::new(ListSessionsResponseLightweight {
+            sessions: self
+                .session
+                .lock()
+                .await
+                .iter()
+                .map(Session::to_msg)
+                .collect(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, num::NonZeroUsize, sync::Mutex};
+
+    use autd3::link::{Audit, AuditOption};
+    use autd3_core::{geometry::Geometry, link::Link};
+    use autd3_driver::firmware::{
+        cpu::{RxMessage, TxMessage},
+        fpga::{EmitIntensity, Phase},
+    };
+
+    use super::*;
+    use crate::lightweight::{LightweightClient, SendStreamOption};
+
+    struct SyncAudit(Mutex<Audit>);
+
+    #[autd3_core::async_trait]
+    impl autd3_core::link::AsyncLink for SyncAudit {
+        async fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+            Link::open(self.0.get_mut().unwrap(), geometry)
+        }
+
+        async fn close(&mut self) -> Result<(), LinkError> {
+            Link::close(self.0.get_mut().unwrap())
+        }
+
+        async fn send(&mut self, tx: &[TxMessage]) -> Result<bool, LinkError> {
+            Link::send(self.0.get_mut().unwrap(), tx)
+        }
+
+        async fn receive(&mut self, rx: &mut [RxMessage]) -> Result<bool, LinkError> {
+            Link::receive(self.0.get_mut().unwrap(), rx)
+        }
+
+        fn is_open(&self) -> bool {
+            Link::is_open(&*self.0.lock().unwrap())
+        }
+    }
+
+    async fn run_server_with_option(
+        option: LightweightServerOption,
+    ) -> Result<SocketAddr, AUTDProtoBufError> {
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|l| l.local_addr())
+            .map_err(|e| AUTDProtoBufError::SendError(e.to_string()))?;
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(ecat_light_server::EcatLightServer::new(
+                    LightweightServer::new_with_option(
+                        || Ok(SyncAudit(Mutex::new(Audit::new(AuditOption::default())))),
+                        option,
+                    ),
+                ))
+                .serve(addr),
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        Ok(addr)
+    }
+
+    async fn run_server() -> Result<SocketAddr, AUTDProtoBufError> {
+        run_server_with_option(LightweightServerOption::default()).await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_stream() -> Result<(), AUTDProtoBufError> {
+        let addr = run_server().await?;
+        let mut client = LightweightClient::open([autd3::prelude::AUTD3::default()], addr).await?;
+
+        let stm = autd3_driver::datagram::GainSTM {
+            gains: (0..100)
+                .map(|i| autd3::gain::Uniform {
+                    intensity: EmitIntensity(i),
+                    phase: Phase(i),
+                })
+                .collect::<Vec<_>>(),
+            config: autd3_driver::firmware::fpga::SamplingConfig::new(u16::MAX).unwrap(),
+            option: Default::default(),
+        };
+        struct Stm<G>(G);
+        impl<G: ToMessage<Message = GainStm>> ToMessage for Stm<G> {
+            type Message = Datagram;
+
+            fn to_msg(
+                &self,
+                geometry: Option<&Geometry>,
+            ) -> Result<Self::Message, AUTDProtoBufError> {
+                Ok(Datagram {
+                    datagram: Some(datagram::Datagram::GainStm(self.0.to_msg(geometry)?)),
+                })
+            }
+        }
+
+        assert!(
+            client
+                .send_stream(
+                    Stm(stm),
+                    SendStreamOption {
+                        chunk_size: NonZeroUsize::new(64).unwrap(),
+                        window: NonZeroUsize::new(2).unwrap(),
+                    },
+                )
+                .await?
+        );
+
+        client.close().await
+    }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn watch_fpga_state() -> Result<(), AUTDProtoBufError> {
+        use tonic::codegen::tokio_stream::StreamExt;
+
+        let addr = run_server().await?;
+        let mut client = LightweightClient::open([autd3::prelude::AUTD3::default()], addr).await?;
+
+        let mut states = client
+            .watch_fpga_state(std::time::Duration::from_millis(1))
+            .await?;
+        assert_eq!(Some(vec![None]), states.next().await.transpose()?);
+
+        assert!(
+            client
+                .send(autd3_driver::datagram::ReadsFPGAState::new(|_| true))
+                .await?
+        );
+        assert!(states
+            .next()
+            .await
+            .transpose()?
+            .is_some_and(|s| s.len() == 1 && s[0].is_some()));
+
+        client.close().await?;
+        assert!(states.next().await.transpose().is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn session_reject() -> Result<(), AUTDProtoBufError> {
+        let addr = run_server().await?;
+        let mut a = LightweightClient::open([autd3::prelude::AUTD3::default()], addr).await?;
+
+        assert!(matches!(
+            LightweightClient::open([autd3::prelude::AUTD3::default()], addr).await,
+            Err(AUTDProtoBufError::SendError(msg)) if msg.starts_with("Session 0 from 127.0.0.1:")
+        ));
+        assert!(a.send(autd3_driver::datagram::Clear::new()).await?);
+
+        let sessions = a.list_sessions().await?;
+        assert_eq!(1, sessions.len());
+        assert_eq!(0, sessions[0].id);
+        assert_eq!(1, sessions[0].num_devices);
+
+        a.close().await?;
+        let b = LightweightClient::open([autd3::prelude::AUTD3::default()], addr).await?;
+        b.close().await
+    }
+
+    #[rstest::rstest]
+    #[case(TakeoverPolicy::Takeover)]
+    #[case(TakeoverPolicy::Idle(std::time::Duration::ZERO))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn session_takeover(#[case] policy: TakeoverPolicy) -> Result<(), AUTDProtoBufError> {
+        use tonic::codegen::tokio_stream::StreamExt;
+
+        let addr = run_server_with_option(LightweightServerOption {
+            takeover_policy: policy,
+        })
+        .await?;
+        let mut a = LightweightClient::open([autd3::prelude::AUTD3::default()], addr).await?;
+        let mut states = a
+            .watch_fpga_state(std::time::Duration::from_millis(1))
+            .await?;
+        assert!(states.next().await.transpose()?.is_some());
+
+        let mut b = LightweightClient::open([autd3::prelude::AUTD3::default()], addr).await?;
+        assert!(matches!(
+            a.send(autd3_driver::datagram::Clear::new()).await,
+            Err(AUTDProtoBufError::Status(s)) if s.code() == tonic::Code::PermissionDenied
+        ));
+        assert!(b.send(autd3_driver::datagram::Clear::new()).await?);
+        assert!(matches!(
+            states.next().await,
+            Some(Err(AUTDProtoBufError::SendError(msg))) if msg == "Session is closed"
+        ));
+
+        assert_eq!(
+            vec![1],
+            b.list_sessions()
+                .await?
+                .iter()
+                .map(|s| s.id)
+                .collect::<Vec<_>>()
+        );
+        b.close().await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn send_stream_invalid_chunk() -> Result<(), AUTDProtoBufError> {
+        let addr = run_server().await?;
+        let mut client = ecat_light_client::EcatLightClient::new(
+            tonic::transport::Endpoint::new(format!("http://{}", addr))?
+                .connect()
+                .await?,
+        );
+
+        let mut acks = client
+            .send_stream(tonic::codegen::tokio_stream::iter([
+                DatagramChunk {
+                    total_size: 4,
+                    offset: 0,
+                    data: vec![0; 2],
+                },
+                DatagramChunk {
+                    total_size: 4,
+                    offset: 1,
+                    data: vec![0; 2],
+                },
+            ]))
+            .await?
+            .into_inner();
+        assert_eq!(
+            Some(SendStreamResponseLightweight {
+                received: 2,
+                result: None,
+            }),
+            acks.message().await?
+        );
+        assert_eq!(
+            Some(tonic::Code::InvalidArgument),
+            acks.message().await.err().map(|e| e.code())
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,132 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::BuildHasher,
+    net::SocketAddr,
+    time::{Duration, Instant, SystemTime},
+};
+
+use tonic::metadata::MetadataMap;
+
+use crate::pb::list_sessions_response_lightweight;
+
+/// The metadata key of the session token.
+pub(crate) const SESSION_KEY: &str = "autd3-session";
+
+/// The policy of [`LightweightServer`] for `Open` requested while another session is active.
+///
+/// [`LightweightServer`]: super::LightweightServer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TakeoverPolicy {
+    /// Rejects the request.
+    #[default]
+    Reject,
+    /// Closes the active session and opens a new one.
+    Takeover,
+    /// Takes over the active session only if it has not been used for the duration.
+    Idle(Duration),
+}
+
+pub(crate) struct Session {
+    id: u64,
+    token: String,
+    peer: Option<SocketAddr>,
+    num_devices: usize,
+    opened: Instant,
+    last_active: Instant,
+}
+
+impl Session {
+    pub(crate) fn new(id: u64, peer: Option<SocketAddr>, num_devices: usize) -> Self {
+        let s = RandomState::new();
+        let now = Instant::now();
+        Self {
+            id,
+            token: format!(
+                "{:016x}{:016x}",
+                s.hash_one(id),
+                s.hash_one((id, SystemTime::now()))
+            ),
+            peer,
+            num_devices,
+            opened: now,
+            last_active: now,
+        }
+    }
+
+    pub(crate) const fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub(crate) fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub(crate) fn is_owner(&self, metadata: &MetadataMap) -> bool {
+        token(metadata) == Some(self.token())
+    }
+
+    pub(crate) fn can_be_taken_over(&self, policy: TakeoverPolicy) -> bool {
+        match policy {
+            TakeoverPolicy::Reject => false,
+            TakeoverPolicy::Takeover => true,
+            TakeoverPolicy::Idle(timeout) => timeout <= self.last_active.elapsed(),
+        }
+    }
+
+    pub(crate) fn touch(&mut self) {
+        self.last_active = Instant::now();
+    }
+
+    pub(crate) fn to_msg(&self) -> list_sessions_response_lightweight::Session {
+        list_sessions_response_lightweight::Session {
+            id: self.id,
+            peer: self.peer.map(|p| p.to_string()).unwrap_or_default(),
+            num_devices: self.num_devices as _,
+            uptime_us: self.opened.elapsed().as_micros() as _,
+            idle_us: self.last_active.elapsed().as_micros() as _,
+        }
+    }
+}
+
+impl std::fmt::Display for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Session {}", self.id)?;
+        if let Some(peer) = self.peer {
+            write!(f, " from {}", peer)?;
+        }
+        Ok(())
+    }
+}
+
+fn token(metadata: &MetadataMap) -> Option<&str> {
+    metadata.get(SESSION_KEY).and_then(|v| v.to_str().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(false, TakeoverPolicy::Reject)]
+    #[case(true, TakeoverPolicy::Takeover)]
+    #[case(true, TakeoverPolicy::Idle(Duration::ZERO))]
+    #[case(false, TakeoverPolicy::Idle(Duration::from_secs(60)))]
+    #[test]
+    fn can_be_taken_over(#[case] expect: bool, #[case] policy: TakeoverPolicy) {
+        assert_eq!(expect, Session::new(0, None, 1).can_be_taken_over(policy));
+    }
+
+    #[test]
+    fn is_owner() {
+        let a = Session::new(0, None, 1);
+        let b = Session::new(1, None, 1);
+        assert_eq!(32, a.token().len());
+        assert_ne!(a.token(), b.token());
+
+        let mut metadata = MetadataMap::new();
+        assert!(!a.is_owner(&metadata));
+        metadata.insert(SESSION_KEY, a.token().parse().unwrap());
+        assert!(a.is_owner(&metadata));
+        assert!(!b.is_owner(&metadata));
+    }
+}
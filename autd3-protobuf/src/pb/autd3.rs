@@ -78,6 +78,20 @@ pub struct CloseResponse {
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct GeometryResponse {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct Pose {
+    #[prost(uint32, tag = "1")]
+    pub idx: u32,
+    #[prost(message, optional, tag = "2")]
+    pub pos: ::core::option::Option<Point3>,
+    #[prost(message, optional, tag = "3")]
+    pub rot: ::core::option::Option<Quaternion>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PoseUpdate {
+    #[prost(message, repeated, tag = "1")]
+    pub poses: ::prost::alloc::vec::Vec<Pose>,
+}
 /// Generated client implementations.
 pub mod simulator_client {
     #![allow(
@@ -185,6 +199,20 @@ pub mod simulator_client {
                 .insert(GrpcMethod::new("autd3.Simulator", "UpdateGeomety"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn update_poses(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PoseUpdate>,
+        ) -> std::result::Result<tonic::Response<super::GeometryResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/autd3.Simulator/UpdatePoses");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("autd3.Simulator", "UpdatePoses"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn send_data(
             &mut self,
             request: impl tonic::IntoRequest<super::TxRawData>,
@@ -370,6 +398,10 @@ pub mod simulator_server {
             &self,
             request: tonic::Request<super::Geometry>,
         ) -> std::result::Result<tonic::Response<super::GeometryResponse>, tonic::Status>;
+        async fn update_poses(
+            &self,
+            request: tonic::Request<super::PoseUpdate>,
+        ) -> std::result::Result<tonic::Response<super::GeometryResponse>, tonic::Status>;
         async fn send_data(
             &self,
             request: tonic::Request<super::TxRawData>,
@@ -534,6 +566,45 @@ pub mod simulator_server {
                     };
                     Box::pin(fut)
                 }
+                "/autd3.Simulator/UpdatePoses" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdatePosesSvc<T: Simulator>(pub Arc<T>);
+                    impl<T: Simulator> tonic::server::UnaryService<super::PoseUpdate> for UpdatePosesSvc<T> {
+                        type Response = super::GeometryResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PoseUpdate>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Simulator>::update_poses(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdatePosesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/autd3.Simulator/SendData" => {
                     #[allow(non_camel_case_types)]
                     struct SendDataSvc<T: Simulator>(pub Arc<T>);
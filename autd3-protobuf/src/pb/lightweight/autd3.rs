@@ -78,6 +78,20 @@ pub struct CloseResponse {
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct GeometryResponse {}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct Pose {
+    #[prost(uint32, tag = "1")]
+    pub idx: u32,
+    #[prost(message, optional, tag = "2")]
+    pub pos: ::core::option::Option<Point3>,
+    #[prost(message, optional, tag = "3")]
+    pub rot: ::core::option::Option<Quaternion>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PoseUpdate {
+    #[prost(message, repeated, tag = "1")]
+    pub poses: ::prost::alloc::vec::Vec<Pose>,
+}
 /// Generated client implementations.
 pub mod simulator_client {
     #![allow(
@@ -185,6 +199,20 @@ pub mod simulator_client {
                 .insert(GrpcMethod::new("autd3.Simulator", "UpdateGeomety"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn update_poses(
+            &mut self,
+            request: impl tonic::IntoRequest<super::PoseUpdate>,
+        ) -> std::result::Result<tonic::Response<super::GeometryResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/autd3.Simulator/UpdatePoses");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("autd3.Simulator", "UpdatePoses"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn send_data(
             &mut self,
             request: impl tonic::IntoRequest<super::TxRawData>,
@@ -370,6 +398,10 @@ pub mod simulator_server {
             &self,
             request: tonic::Request<super::Geometry>,
         ) -> std::result::Result<tonic::Response<super::GeometryResponse>, tonic::Status>;
+        async fn update_poses(
+            &self,
+            request: tonic::Request<super::PoseUpdate>,
+        ) -> std::result::Result<tonic::Response<super::GeometryResponse>, tonic::Status>;
         async fn send_data(
             &self,
             request: tonic::Request<super::TxRawData>,
@@ -534,6 +566,45 @@ pub mod simulator_server {
                     };
                     Box::pin(fut)
                 }
+                "/autd3.Simulator/UpdatePoses" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdatePosesSvc<T: Simulator>(pub Arc<T>);
+                    impl<T: Simulator> tonic::server::UnaryService<super::PoseUpdate> for UpdatePosesSvc<T> {
+                        type Response = super::GeometryResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::PoseUpdate>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Simulator>::update_poses(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = UpdatePosesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/autd3.Simulator/SendData" => {
                     #[allow(non_camel_case_types)]
                     struct SendDataSvc<T: Simulator>(pub Arc<T>);
@@ -1078,6 +1149,8 @@ pub struct Focus {
     pub intensity: ::core::option::Option<EmitIntensity>,
     #[prost(message, optional, tag = "3")]
     pub phase_offset: ::core::option::Option<Phase>,
+    #[prost(enumeration = "NearFieldCompensation", optional, tag = "4")]
+    pub near_field_compensation: ::core::option::Option<i32>,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct Null {}
@@ -1097,6 +1170,20 @@ pub struct Uniform {
     #[prost(message, optional, tag = "2")]
     pub phase: ::core::option::Option<Phase>,
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RawDrives {
+    #[prost(uint32, tag = "1")]
+    pub dev_idx: u32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub phase: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub intensity: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RawGain {
+    #[prost(message, repeated, tag = "1")]
+    pub drives: ::prost::alloc::vec::Vec<RawDrives>,
+}
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct Amplitude {
     #[prost(float, tag = "1")]
@@ -1201,7 +1288,10 @@ pub struct Greedy {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Gain {
-    #[prost(oneof = "gain::Gain", tags = "1, 2, 3, 4, 5, 101, 102, 103, 104, 105")]
+    #[prost(
+        oneof = "gain::Gain",
+        tags = "1, 2, 3, 4, 5, 6, 101, 102, 103, 104, 105"
+    )]
     pub gain: ::core::option::Option<gain::Gain>,
 }
 /// Nested message and enum types in `Gain`.
@@ -1219,6 +1309,8 @@ pub mod gain {
         Plane(super::Plane),
         #[prost(message, tag = "5")]
         Uniform(super::Uniform),
+        #[prost(message, tag = "6")]
+        Raw(super::RawGain),
         #[prost(message, tag = "101")]
         Naive(super::Naive),
         #[prost(message, tag = "102")]
@@ -1240,11 +1332,48 @@ pub struct GainWithSegment {
     #[prost(message, optional, tag = "3")]
     pub transition_mode: ::core::option::Option<TransitionMode>,
 }
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NearFieldCompensation {
+    None = 0,
+    InverseDistance = 1,
+    Directivity = 2,
+}
+impl NearFieldCompensation {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::InverseDistance => "InverseDistance",
+            Self::Directivity => "Directivity",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "None" => Some(Self::None),
+            "InverseDistance" => Some(Self::InverseDistance),
+            "Directivity" => Some(Self::Directivity),
+            _ => None,
+        }
+    }
+}
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct Static {
     #[prost(uint32, optional, tag = "1")]
     pub intensity: ::core::option::Option<u32>,
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RawModulation {
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag = "2")]
+    pub config: ::core::option::Option<SamplingConfig>,
+}
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct SineOption {
     #[prost(message, optional, tag = "1")]
@@ -1311,18 +1440,23 @@ pub struct SquareNearest {
     #[prost(message, optional, tag = "2")]
     pub option: ::core::option::Option<SquareOption>,
 }
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Modulation {
-    #[prost(oneof = "modulation::Modulation", tags = "1, 10, 11, 12, 20, 21, 22")]
+    #[prost(
+        oneof = "modulation::Modulation",
+        tags = "1, 2, 10, 11, 12, 20, 21, 22"
+    )]
     pub modulation: ::core::option::Option<modulation::Modulation>,
 }
 /// Nested message and enum types in `Modulation`.
 pub mod modulation {
     #[non_exhaustive]
-    #[derive(Clone, Copy, PartialEq, ::prost::Oneof)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Modulation {
         #[prost(message, tag = "1")]
         Static(super::Static),
+        #[prost(message, tag = "2")]
+        Raw(super::RawModulation),
         #[prost(message, tag = "10")]
         SineExact(super::SineExact),
         #[prost(message, tag = "11")]
@@ -1337,7 +1471,7 @@ pub mod modulation {
         SquareNearest(super::SquareNearest),
     }
 }
-#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ModulationWithLoopBehavior {
     #[prost(message, optional, tag = "1")]
     pub modulation: ::core::option::Option<Modulation>,
@@ -1692,6 +1826,8 @@ pub struct SendResponseLightweight {
     pub err: bool,
     #[prost(string, tag = "3")]
     pub msg: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub session: ::prost::alloc::string::String,
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct FirmwareVersionRequestLightweight {}
@@ -1722,12 +1858,73 @@ pub mod firmware_version_response_lightweight {
     }
 }
 #[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct WatchFpgaStateRequestLightweight {
+    #[prost(uint64, optional, tag = "1")]
+    pub interval_us: ::core::option::Option<u64>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FpgaStateResponseLightweight {
+    #[prost(bool, tag = "1")]
+    pub success: bool,
+    #[prost(string, tag = "2")]
+    pub msg: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub fpga_state_list: ::prost::alloc::vec::Vec<fpga_state_response_lightweight::FpgaState>,
+}
+/// Nested message and enum types in `FPGAStateResponseLightweight`.
+pub mod fpga_state_response_lightweight {
+    #[derive(Clone, Copy, PartialEq, ::prost::Message)]
+    pub struct FpgaState {
+        #[prost(uint32, optional, tag = "1")]
+        pub state: ::core::option::Option<u32>,
+    }
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
+pub struct ListSessionsRequestLightweight {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListSessionsResponseLightweight {
+    #[prost(message, repeated, tag = "1")]
+    pub sessions: ::prost::alloc::vec::Vec<list_sessions_response_lightweight::Session>,
+}
+/// Nested message and enum types in `ListSessionsResponseLightweight`.
+pub mod list_sessions_response_lightweight {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Session {
+        #[prost(uint64, tag = "1")]
+        pub id: u64,
+        #[prost(string, tag = "2")]
+        pub peer: ::prost::alloc::string::String,
+        #[prost(uint32, tag = "3")]
+        pub num_devices: u32,
+        #[prost(uint64, tag = "4")]
+        pub uptime_us: u64,
+        #[prost(uint64, tag = "5")]
+        pub idle_us: u64,
+    }
+}
+#[derive(Clone, Copy, PartialEq, ::prost::Message)]
 pub struct CloseRequestLightweight {}
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct OpenRequestLightweight {
     #[prost(message, optional, tag = "1")]
     pub geometry: ::core::option::Option<Geometry>,
 }
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DatagramChunk {
+    #[prost(uint64, tag = "1")]
+    pub total_size: u64,
+    #[prost(uint64, tag = "2")]
+    pub offset: u64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SendStreamResponseLightweight {
+    #[prost(uint64, tag = "1")]
+    pub received: u64,
+    #[prost(message, optional, tag = "2")]
+    pub result: ::core::option::Option<SendResponseLightweight>,
+}
 /// Generated client implementations.
 pub mod ecat_light_client {
     #![allow(
@@ -1839,6 +2036,23 @@ pub mod ecat_light_client {
                 .insert(GrpcMethod::new("autd3.ECATLight", "FirmwareVersion"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn watch_fpga_state(
+            &mut self,
+            request: impl tonic::IntoRequest<super::WatchFpgaStateRequestLightweight>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::FpgaStateResponseLightweight>>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/autd3.ECATLight/WatchFPGAState");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("autd3.ECATLight", "WatchFPGAState"));
+            self.inner.server_streaming(req, path, codec).await
+        }
         pub async fn send(
             &mut self,
             request: impl tonic::IntoRequest<super::Datagram>,
@@ -1854,6 +2068,23 @@ pub mod ecat_light_client {
                 .insert(GrpcMethod::new("autd3.ECATLight", "Send"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn send_stream(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::DatagramChunk>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::SendStreamResponseLightweight>>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/autd3.ECATLight/SendStream");
+            let mut req = request.into_streaming_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("autd3.ECATLight", "SendStream"));
+            self.inner.streaming(req, path, codec).await
+        }
         pub async fn close(
             &mut self,
             request: impl tonic::IntoRequest<super::CloseRequestLightweight>,
@@ -1869,6 +2100,23 @@ pub mod ecat_light_client {
                 .insert(GrpcMethod::new("autd3.ECATLight", "Close"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn list_sessions(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListSessionsRequestLightweight>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListSessionsResponseLightweight>,
+            tonic::Status,
+        > {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::unknown(format!("Service was not ready: {}", e.into()))
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/autd3.ECATLight/ListSessions");
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("autd3.ECATLight", "ListSessions"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -1895,14 +2143,39 @@ pub mod ecat_light_server {
             tonic::Response<super::FirmwareVersionResponseLightweight>,
             tonic::Status,
         >;
+        /// Server streaming response type for the WatchFPGAState method.
+        type WatchFPGAStateStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::FpgaStateResponseLightweight, tonic::Status>,
+            > + std::marker::Send
+            + 'static;
+        async fn watch_fpga_state(
+            &self,
+            request: tonic::Request<super::WatchFpgaStateRequestLightweight>,
+        ) -> std::result::Result<tonic::Response<Self::WatchFPGAStateStream>, tonic::Status>;
         async fn send(
             &self,
             request: tonic::Request<super::Datagram>,
         ) -> std::result::Result<tonic::Response<super::SendResponseLightweight>, tonic::Status>;
+        /// Server streaming response type for the SendStream method.
+        type SendStreamStream: tonic::codegen::tokio_stream::Stream<
+                Item = std::result::Result<super::SendStreamResponseLightweight, tonic::Status>,
+            > + std::marker::Send
+            + 'static;
+        async fn send_stream(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::DatagramChunk>>,
+        ) -> std::result::Result<tonic::Response<Self::SendStreamStream>, tonic::Status>;
         async fn close(
             &self,
             request: tonic::Request<super::CloseRequestLightweight>,
         ) -> std::result::Result<tonic::Response<super::SendResponseLightweight>, tonic::Status>;
+        async fn list_sessions(
+            &self,
+            request: tonic::Request<super::ListSessionsRequestLightweight>,
+        ) -> std::result::Result<
+            tonic::Response<super::ListSessionsResponseLightweight>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct EcatLightServer<T> {
@@ -2056,6 +2329,51 @@ pub mod ecat_light_server {
                     };
                     Box::pin(fut)
                 }
+                "/autd3.ECATLight/WatchFPGAState" => {
+                    #[allow(non_camel_case_types)]
+                    struct WatchFPGAStateSvc<T: EcatLight>(pub Arc<T>);
+                    impl<T: EcatLight>
+                        tonic::server::ServerStreamingService<
+                            super::WatchFpgaStateRequestLightweight,
+                        > for WatchFPGAStateSvc<T>
+                    {
+                        type Response = super::FpgaStateResponseLightweight;
+                        type ResponseStream = T::WatchFPGAStateStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::WatchFpgaStateRequestLightweight>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as EcatLight>::watch_fpga_state(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = WatchFPGAStateSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/autd3.ECATLight/Send" => {
                     #[allow(non_camel_case_types)]
                     struct SendSvc<T: EcatLight>(pub Arc<T>);
@@ -2093,6 +2411,46 @@ pub mod ecat_light_server {
                     };
                     Box::pin(fut)
                 }
+                "/autd3.ECATLight/SendStream" => {
+                    #[allow(non_camel_case_types)]
+                    struct SendStreamSvc<T: EcatLight>(pub Arc<T>);
+                    impl<T: EcatLight> tonic::server::StreamingService<super::DatagramChunk> for SendStreamSvc<T> {
+                        type Response = super::SendStreamResponseLightweight;
+                        type ResponseStream = T::SendStreamStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<tonic::Streaming<super::DatagramChunk>>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut =
+                                async move { <T as EcatLight>::send_stream(&inner, request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = SendStreamSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/autd3.ECATLight/Close" => {
                     #[allow(non_camel_case_types)]
                     struct CloseSvc<T: EcatLight>(pub Arc<T>);
@@ -2130,6 +2488,48 @@ pub mod ecat_light_server {
                     };
                     Box::pin(fut)
                 }
+                "/autd3.ECATLight/ListSessions" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListSessionsSvc<T: EcatLight>(pub Arc<T>);
+                    impl<T: EcatLight>
+                        tonic::server::UnaryService<super::ListSessionsRequestLightweight>
+                        for ListSessionsSvc<T>
+                    {
+                        type Response = super::ListSessionsResponseLightweight;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListSessionsRequestLightweight>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as EcatLight>::list_sessions(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = ListSessionsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => Box::pin(async move {
                     let mut response = http::Response::new(empty_body());
                     let headers = response.headers_mut();
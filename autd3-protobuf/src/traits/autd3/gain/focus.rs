@@ -4,6 +4,16 @@ use crate::{
     AUTDProtoBufError,
 };
 
+fn near_field_compensation_to(
+    v: i32,
+) -> Result<autd3::gain::NearFieldCompensation, AUTDProtoBufError> {
+    use autd3::gain::NearFieldCompensation::*;
+    [None, InverseDistance, Directivity]
+        .into_iter()
+        .find(|c| *c as u8 as i32 == v)
+        .ok_or(AUTDProtoBufError::DataParseError)
+}
+
 impl ToMessage for autd3::gain::Focus {
     type Message = Datagram;
 
@@ -17,6 +27,7 @@ impl ToMessage for autd3::gain::Focus {
                     pos: Some(self.pos.to_msg(None)?),
                     intensity: Some(self.option.intensity.to_msg(None)?),
                     phase_offset: Some(self.option.phase_offset.to_msg(None)?),
+                    near_field_compensation: Some(self.option.near_field_compensation as u8 as _),
                 })),
             })),
         })
@@ -40,6 +51,11 @@ impl FromMessage<Focus> for autd3::gain::Focus {
                     .map(autd3_driver::firmware::fpga::Phase::from_msg)
                     .transpose()?
                     .unwrap_or(autd3::gain::FocusOption::default().phase_offset),
+                near_field_compensation: msg
+                    .near_field_compensation
+                    .map(near_field_compensation_to)
+                    .transpose()?
+                    .unwrap_or_default(),
             },
         })
     }
@@ -61,6 +77,7 @@ mod tests {
             option: autd3::gain::FocusOption {
                 intensity: EmitIntensity(rng.random()),
                 phase_offset: Phase(rng.random()),
+                near_field_compensation: autd3::gain::NearFieldCompensation::Directivity,
             },
         };
         let msg = g.to_msg(None).unwrap();
@@ -76,6 +93,10 @@ mod tests {
                 assert_eq!(g.pos.z, g2.pos.z);
                 assert_eq!(g.option.intensity, g2.option.intensity);
                 assert_eq!(g.option.phase_offset, g2.option.phase_offset);
+                assert_eq!(
+                    g.option.near_field_compensation,
+                    g2.option.near_field_compensation
+                );
             }
             _ => panic!("unexpected datagram type"),
         }
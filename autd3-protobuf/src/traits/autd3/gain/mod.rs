@@ -2,4 +2,7 @@ mod bessel;
 mod focus;
 mod null;
 mod plane;
+mod raw;
 mod uniform;
+
+pub(crate) use raw::raw_gain_into_boxed;
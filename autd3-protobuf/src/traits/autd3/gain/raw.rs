@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use autd3_driver::{
+    datagram::{BoxedGain, IntoBoxedGain},
+    firmware::fpga::{Drive, EmitIntensity, Phase},
+};
+
+use crate::{pb::*, traits::FromMessage, AUTDProtoBufError};
+
+impl FromMessage<RawGain> for HashMap<usize, Vec<Drive>> {
+    fn from_msg(msg: &RawGain) -> Result<Self, AUTDProtoBufError> {
+        msg.drives.iter().try_fold(HashMap::new(), |mut drives, d| {
+            if d.phase.len() != d.intensity.len() {
+                return Err(AUTDProtoBufError::DataParseError);
+            }
+            let dev_idx = d.dev_idx as usize;
+            if drives
+                .insert(
+                    dev_idx,
+                    d.phase
+                        .iter()
+                        .zip(d.intensity.iter())
+                        .map(|(&phase, &intensity)| Drive {
+                            phase: Phase(phase),
+                            intensity: EmitIntensity(intensity),
+                        })
+                        .collect(),
+                )
+                .is_some()
+            {
+                return Err(AUTDProtoBufError::DuplicateDeviceIndex(dev_idx));
+            }
+            Ok(drives)
+        })
+    }
+}
+
+/// Converts [`RawGain`] into [`autd3::gain::Custom`]. The transducers without drives are not driven.
+///
+/// Returns [`AUTDProtoBufError::DuplicateDeviceIndex`] if the drives of a device are given more than once.
+pub(crate) fn raw_gain_into_boxed(msg: &RawGain) -> Result<BoxedGain, AUTDProtoBufError> {
+    let drives = HashMap::<usize, Vec<Drive>>::from_msg(msg)?;
+    Ok(autd3::gain::Custom::new(move |dev| {
+        let drives = drives.get(&dev.idx()).cloned().unwrap_or_default();
+        move |tr| drives.get(tr.idx()).copied().unwrap_or(Drive::NULL)
+    })
+    .into_boxed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autd3_core::{
+        derive::{GainCalculator, GainCalculatorGenerator},
+        gain::Gain,
+        geometry::{Geometry, IntoDevice, Point3},
+    };
+    use autd3_driver::autd3_device::AUTD3;
+    use rand::Rng;
+
+    #[test]
+    fn test_raw() {
+        let mut rng = rand::rng();
+
+        let phase = (0..10).map(|_| rng.random()).collect::<Vec<u8>>();
+        let intensity = (0..10).map(|_| rng.random()).collect::<Vec<u8>>();
+        let msg = RawGain {
+            drives: vec![RawDrives {
+                dev_idx: 1,
+                phase: phase.clone(),
+                intensity: intensity.clone(),
+            }],
+        };
+        let drives = HashMap::<usize, Vec<Drive>>::from_msg(&msg).unwrap();
+        assert_eq!(1, drives.len());
+        assert_eq!(
+            phase
+                .into_iter()
+                .zip(intensity)
+                .map(|(p, i)| Drive {
+                    phase: Phase(p),
+                    intensity: EmitIntensity(i),
+                })
+                .collect::<Vec<_>>(),
+            drives[&1]
+        );
+    }
+
+    #[test]
+    fn test_raw_length_mismatch() {
+        let msg = RawGain {
+            drives: vec![RawDrives {
+                dev_idx: 0,
+                phase: vec![0; 2],
+                intensity: vec![0; 1],
+            }],
+        };
+        assert!(HashMap::<usize, Vec<Drive>>::from_msg(&msg).is_err());
+    }
+
+    #[test]
+    fn test_raw_duplicate_dev_idx() {
+        let msg = RawGain {
+            drives: vec![
+                RawDrives {
+                    dev_idx: 1,
+                    phase: vec![0; 2],
+                    intensity: vec![0; 2],
+                },
+                RawDrives {
+                    dev_idx: 1,
+                    phase: vec![1; 2],
+                    intensity: vec![1; 2],
+                },
+            ],
+        };
+        assert!(matches!(
+            raw_gain_into_boxed(&msg),
+            Err(AUTDProtoBufError::DuplicateDeviceIndex(1))
+        ));
+    }
+
+    #[test]
+    fn test_raw_gain_into_boxed() {
+        let geometry = Geometry::new(
+            (0..2)
+                .map(|i| {
+                    AUTD3 {
+                        pos: Point3::origin(),
+                        ..Default::default()
+                    }
+                    .into_device(i)
+                })
+                .collect(),
+        );
+        let msg = RawGain {
+            drives: vec![RawDrives {
+                dev_idx: 1,
+                phase: vec![0x01, 0x02],
+                intensity: vec![0x03, 0x04],
+            }],
+        };
+
+        let mut g = raw_gain_into_boxed(&msg)
+            .unwrap()
+            .init_full(&geometry, None, false)
+            .unwrap();
+        let drives = geometry
+            .iter()
+            .map(|dev| {
+                let f = GainCalculatorGenerator::generate(&mut g, dev);
+                dev.iter().map(|tr| f.calc(tr)).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(drives[0].iter().all(|&d| d == Drive::NULL));
+        assert_eq!(
+            Drive {
+                phase: Phase(0x01),
+                intensity: EmitIntensity(0x03),
+            },
+            drives[1][0]
+        );
+        assert_eq!(
+            Drive {
+                phase: Phase(0x02),
+                intensity: EmitIntensity(0x04),
+            },
+            drives[1][1]
+        );
+        assert!(drives[1][2..].iter().all(|&d| d == Drive::NULL));
+    }
+}
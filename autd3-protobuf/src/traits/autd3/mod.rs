@@ -1,2 +1,4 @@
 mod gain;
 mod modulation;
+
+pub(crate) use gain::raw_gain_into_boxed;
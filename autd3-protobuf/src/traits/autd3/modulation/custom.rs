@@ -0,0 +1,83 @@
+use std::fmt::Debug;
+
+use autd3_core::modulation::SamplingConfigError;
+
+use crate::{
+    pb::*,
+    traits::{FromMessage, ToMessage},
+    AUTDProtoBufError,
+};
+
+impl<Config, E> ToMessage for autd3::modulation::Custom<Config, E>
+where
+    E: Debug,
+    SamplingConfigError: From<E>,
+    Config: TryInto<autd3_core::modulation::SamplingConfig, Error = E> + Debug + Copy,
+{
+    type Message = Datagram;
+
+    fn to_msg(
+        &self,
+        _: Option<&autd3_core::geometry::Geometry>,
+    ) -> Result<Self::Message, AUTDProtoBufError> {
+        Ok(Self::Message {
+            datagram: Some(datagram::Datagram::Modulation(Modulation {
+                modulation: Some(modulation::Modulation::Raw(RawModulation {
+                    data: self.buffer.clone(),
+                    config: Some(
+                        autd3_core::modulation::Modulation::sampling_config(self)
+                            .map_err(autd3_driver::error::AUTDDriverError::from)?
+                            .to_msg(None)?,
+                    ),
+                })),
+            })),
+        })
+    }
+}
+
+impl FromMessage<RawModulation>
+    for autd3::modulation::Custom<
+        autd3_driver::firmware::fpga::SamplingConfig,
+        std::convert::Infallible,
+    >
+{
+    fn from_msg(msg: &RawModulation) -> Result<Self, AUTDProtoBufError> {
+        Ok(Self {
+            buffer: msg.data.clone(),
+            sampling_config: autd3_driver::firmware::fpga::SamplingConfig::from_msg(
+                msg.config
+                    .as_ref()
+                    .ok_or(AUTDProtoBufError::DataParseError)?,
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use autd3_driver::firmware::fpga::SamplingConfig;
+    use rand::Rng;
+
+    #[test]
+    fn test_custom() {
+        let mut rng = rand::rng();
+
+        let m = autd3::modulation::Custom {
+            buffer: (0..10).map(|_| rng.random()).collect(),
+            sampling_config: SamplingConfig::new(rng.random_range(0x0001..=0xFFFF)).unwrap(),
+        };
+        let msg = m.to_msg(None).unwrap();
+        match msg.datagram {
+            Some(datagram::Datagram::Modulation(Modulation {
+                modulation: Some(modulation::Modulation::Raw(modulation)),
+                ..
+            })) => {
+                let m2 = autd3::modulation::Custom::from_msg(&modulation).unwrap();
+                assert_eq!(m.buffer, m2.buffer);
+                assert_eq!(m.sampling_config, m2.sampling_config);
+            }
+            _ => panic!("unexpected datagram type"),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+mod custom;
 mod sine;
 mod square;
 mod r#static;
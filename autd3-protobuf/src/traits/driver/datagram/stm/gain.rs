@@ -57,6 +57,7 @@ impl FromMessage<GainStm>
                     Some(gain::Gain::Uniform(msg)) => {
                         autd3::prelude::Uniform::from_msg(msg).map(|g| g.into_boxed())
                     }
+                    Some(gain::Gain::Raw(msg)) => crate::traits::raw_gain_into_boxed(msg),
                     Some(gain::Gain::Naive(msg)) => {
                         autd3_gain_holo::Naive::from_msg(msg).map(|g| g.into_boxed())
                     }
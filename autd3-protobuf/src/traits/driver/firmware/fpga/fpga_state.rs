@@ -0,0 +1,43 @@
+use crate::{pb::*, traits::FromMessage, AUTDProtoBufError};
+
+impl FromMessage<FpgaStateResponseLightweight>
+    for Vec<Option<autd3_driver::firmware::fpga::FPGAState>>
+{
+    fn from_msg(msg: &FpgaStateResponseLightweight) -> Result<Self, AUTDProtoBufError> {
+        msg.fpga_state_list
+            .iter()
+            .map(|v| {
+                v.state
+                    .map(|state| {
+                        Ok(autd3_driver::firmware::fpga::FPGAState::from_rx(
+                            &autd3_core::link::RxMessage::new(u8::try_from(state)?, 0),
+                        ))
+                    })
+                    .transpose()
+                    .map(Option::flatten)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fpga_state() {
+        let response = FpgaStateResponseLightweight {
+            success: true,
+            msg: String::new(),
+            fpga_state_list: vec![
+                fpga_state_response_lightweight::FpgaState { state: Some(0x81) },
+                fpga_state_response_lightweight::FpgaState { state: None },
+            ],
+        };
+        let state =
+            Vec::<Option<autd3_driver::firmware::fpga::FPGAState>>::from_msg(&response).unwrap();
+        assert_eq!(2, state.len());
+        assert_eq!(Some(true), state[0].map(|s| s.is_thermal_assert()));
+        assert_eq!(None, state[1]);
+    }
+}
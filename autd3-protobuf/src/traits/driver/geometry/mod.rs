@@ -74,6 +74,54 @@ impl ToMessage for autd3_core::geometry::Geometry {
     }
 }
 
+impl ToMessage for autd3_core::geometry::Device {
+    type Message = Pose;
+
+    fn to_msg(
+        &self,
+        _: Option<&autd3_core::geometry::Geometry>,
+    ) -> Result<Self::Message, AUTDProtoBufError> {
+        Ok(Self::Message {
+            idx: self.idx() as _,
+            pos: Some(self[0].position().to_msg(None)?),
+            rot: Some(self.rotation().to_msg(None)?),
+        })
+    }
+}
+
+impl PoseUpdate {
+    /// Creates the differential update from `last` to `current`, which contains the poses of the moved devices.
+    ///
+    /// Returns `None` if the whole geometry should be sent instead, i.e., the number of devices or the sound speed is changed, or all devices are moved.
+    pub fn diff(current: &Geometry, last: &Geometry) -> Option<Self> {
+        if current.devices.len() != last.devices.len()
+            || current
+                .devices
+                .iter()
+                .zip(last.devices.iter())
+                .any(|(dev, last)| dev.sound_speed != last.sound_speed)
+        {
+            return None;
+        }
+        let poses = current
+            .devices
+            .iter()
+            .zip(last.devices.iter())
+            .enumerate()
+            .filter(|(_, (dev, last))| dev != last)
+            .map(|(idx, (dev, _))| Pose {
+                idx: idx as _,
+                pos: dev.pos,
+                rot: dev.rot,
+            })
+            .collect::<Vec<_>>();
+        if !poses.is_empty() && poses.len() == current.devices.len() {
+            return None;
+        }
+        Some(Self { poses })
+    }
+}
+
 impl FromMessage<Option<UnitVector3>> for autd3_core::geometry::UnitVector3 {
     fn from_msg(msg: &Option<UnitVector3>) -> Result<Self, AUTDProtoBufError> {
         msg.as_ref()
@@ -209,4 +257,76 @@ mod tests {
                 });
             });
     }
+
+    #[test]
+    fn pose() {
+        let mut rng = rand::rng();
+        let pos = Point3::new(rng.random(), rng.random(), rng.random());
+        let rot = UnitQuaternion::from_quaternion(Quaternion::new(
+            rng.random(),
+            rng.random(),
+            rng.random(),
+            rng.random(),
+        ));
+        let dev = AUTD3 { pos, rot }.into_device(3);
+        let msg = dev.to_msg(None).unwrap();
+        assert_eq!(3, msg.idx);
+        let pos2 = Point3::from_msg(&msg.pos).unwrap();
+        let rot2 = UnitQuaternion::from_msg(&msg.rot).unwrap();
+        approx::assert_abs_diff_eq!(pos.x, pos2.x, epsilon = 1e-5);
+        approx::assert_abs_diff_eq!(pos.y, pos2.y, epsilon = 1e-5);
+        approx::assert_abs_diff_eq!(pos.z, pos2.z, epsilon = 1e-5);
+        approx::assert_abs_diff_eq!(rot.w, rot2.w, epsilon = 1e-5);
+        approx::assert_abs_diff_eq!(rot.i, rot2.i, epsilon = 1e-5);
+        approx::assert_abs_diff_eq!(rot.j, rot2.j, epsilon = 1e-5);
+        approx::assert_abs_diff_eq!(rot.k, rot2.k, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn pose_update_diff() {
+        let geometry = |pos: [f32; 3]| {
+            Geometry::new(
+                pos.into_iter()
+                    .enumerate()
+                    .map(|(i, x)| {
+                        AUTD3 {
+                            pos: Point3::new(x, 0., 0.),
+                            rot: UnitQuaternion::identity(),
+                        }
+                        .into_device(i as _)
+                    })
+                    .collect(),
+            )
+            .to_msg(None)
+            .unwrap()
+        };
+        let last = geometry([0., 10., 20.]);
+
+        assert_eq!(
+            Some(PoseUpdate { poses: vec![] }),
+            PoseUpdate::diff(&last, &last)
+        );
+
+        let current = geometry([0., 15., 20.]);
+        assert_eq!(
+            Some(PoseUpdate {
+                poses: vec![Pose {
+                    idx: 1,
+                    pos: current.devices[1].pos,
+                    rot: current.devices[1].rot,
+                }]
+            }),
+            PoseUpdate::diff(&current, &last)
+        );
+
+        assert_eq!(None, PoseUpdate::diff(&geometry([1., 11., 21.]), &last));
+
+        let mut current = last.clone();
+        current.devices[0].sound_speed += 1.;
+        assert_eq!(None, PoseUpdate::diff(&current, &last));
+
+        let mut current = last.clone();
+        current.devices.pop();
+        assert_eq!(None, PoseUpdate::diff(&current, &last));
+    }
 }
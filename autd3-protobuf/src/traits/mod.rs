@@ -6,6 +6,9 @@ mod driver;
 #[cfg(feature = "lightweight")]
 mod holo;
 
+#[cfg(feature = "lightweight")]
+pub(crate) use autd3::raw_gain_into_boxed;
+
 pub trait ToMessage {
     type Message: prost::Message;
 
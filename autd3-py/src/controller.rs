@@ -0,0 +1,114 @@
+use std::sync::Mutex;
+
+use autd3::driver::{
+    autd3_device::AUTD3,
+    geometry::{Point3, Quaternion, UnitQuaternion},
+};
+use autd3_core::link::Link;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+use crate::{
+    error::{to_py_err, AUTDError},
+    Gain, Modulation, PyLink, STM,
+};
+
+/// The controller of the devices.
+///
+/// The GIL is released while communicating with the devices. The controller is closed when it is garbage-collected if `close` is not called.
+#[pyclass(module = "autd3_py", frozen)]
+pub struct Controller(Mutex<Option<autd3::Controller<Box<dyn Link>>>>);
+
+impl Controller {
+    fn with<R: Send>(
+        &self,
+        py: Python<'_>,
+        f: impl FnOnce(&mut autd3::Controller<Box<dyn Link>>) -> PyResult<R> + Send,
+    ) -> PyResult<R> {
+        py.allow_threads(|| {
+            let mut cnt = self.0.lock().unwrap();
+            let cnt = cnt
+                .as_mut()
+                .ok_or_else(|| AUTDError::new_err("The controller is closed"))?;
+            f(cnt)
+        })
+    }
+}
+
+#[pymethods]
+impl Controller {
+    /// Opens a controller with [`AUTD3`] devices at `pos` and `link`.
+    ///
+    /// `rot` is the rotations of the devices as quaternions `(w, x, y, z)`. If `rot` is `None`, the devices are not rotated.
+    /// `link` is consumed even if opening fails.
+    #[staticmethod]
+    #[pyo3(signature = (pos, link, rot = None))]
+    fn open(
+        py: Python<'_>,
+        pos: Vec<[f32; 3]>,
+        link: &PyLink,
+        rot: Option<Vec<[f32; 4]>>,
+    ) -> PyResult<Self> {
+        let link = link.take()?;
+        if rot.as_ref().is_some_and(|rot| rot.len() != pos.len()) {
+            return Err(PyValueError::new_err(
+                "The number of rotations must be the same as the number of positions",
+            ));
+        }
+        let devices = pos
+            .into_iter()
+            .enumerate()
+            .map(|(i, pos)| AUTD3 {
+                pos: Point3::from(pos),
+                rot: rot.as_ref().map_or(UnitQuaternion::identity(), |rot| {
+                    let [w, x, y, z] = rot[i];
+                    UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z))
+                }),
+            })
+            .collect::<Vec<_>>();
+        py.allow_threads(move || autd3::Controller::open(devices, link))
+            .map(|cnt| Self(Mutex::new(Some(cnt))))
+            .map_err(to_py_err)
+    }
+
+    /// The number of devices.
+    #[getter]
+    fn num_devices(&self, py: Python<'_>) -> PyResult<usize> {
+        self.with(py, |cnt| Ok(cnt.geometry().num_devices()))
+    }
+
+    /// Sends the modulation `m` and the gain `g` to the devices.
+    ///
+    /// Either `m` or `g` may be `None` to send only the other one.
+    #[pyo3(signature = (m = None, g = None))]
+    fn send(&self, py: Python<'_>, m: Option<Modulation>, g: Option<Gain>) -> PyResult<()> {
+        self.with(py, move |cnt| {
+            match (m, g) {
+                (Some(m), Some(g)) => cnt.send((m.to_boxed(), g.to_boxed())),
+                (Some(m), None) => cnt.send(m.to_boxed()),
+                (None, Some(g)) => cnt.send(g.to_boxed()),
+                (None, None) => return Err(PyValueError::new_err("Either m or g must be given")),
+            }
+            .map_err(to_py_err)
+        })
+    }
+
+    /// Sends the `stm` to the devices.
+    fn send_stm(&self, py: Python<'_>, stm: STM) -> PyResult<()> {
+        self.with(py, move |cnt| cnt.send(stm.to_boxed()).map_err(to_py_err))
+    }
+
+    /// Closes the controller.
+    ///
+    /// The controller cannot be used after this call even if closing fails.
+    fn close(&self, py: Python<'_>) -> PyResult<()> {
+        py.allow_threads(|| {
+            self.0
+                .lock()
+                .unwrap()
+                .take()
+                .ok_or_else(|| AUTDError::new_err("The controller is closed"))?
+                .close()
+                .map_err(to_py_err)
+        })
+    }
+}
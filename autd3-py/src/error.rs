@@ -0,0 +1,12 @@
+use pyo3::{create_exception, exceptions::PyException, PyErr};
+
+create_exception!(
+    autd3_py,
+    AUTDError,
+    PyException,
+    "The error raised by autd3, e.g., when the link fails or the data is invalid."
+);
+
+pub(crate) fn to_py_err(e: impl std::fmt::Display) -> PyErr {
+    AUTDError::new_err(e.to_string())
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use autd3::{
+    driver::{
+        datagram::{BoxedGain, IntoBoxedGain},
+        defined::rad,
+        firmware::fpga::{EmitIntensity, Phase},
+        geometry::{Point3, UnitVector3, Vector3},
+    },
+    gain::{
+        Bessel, BesselOption, Focus, FocusOption, NearFieldCompensation, Null, Plane, PlaneOption,
+        Uniform,
+    },
+};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// A primitive gain.
+///
+/// The gain can be sent many times, because the Rust gain is created every time it is sent.
+#[pyclass(module = "autd3_py", frozen)]
+#[derive(Clone)]
+pub struct Gain(Arc<dyn Fn() -> BoxedGain + Send + Sync>);
+
+impl Gain {
+    fn new<G: IntoBoxedGain + Clone + Send + Sync + 'static>(g: G) -> Self {
+        Self(Arc::new(move || g.clone().into_boxed()))
+    }
+
+    pub(crate) fn to_boxed(&self) -> BoxedGain {
+        (self.0)()
+    }
+}
+
+fn dir(dir: [f32; 3]) -> PyResult<UnitVector3> {
+    UnitVector3::try_new(Vector3::from(dir), 0.)
+        .ok_or_else(|| PyValueError::new_err("Direction must be a non-zero vector"))
+}
+
+#[pymethods]
+impl Gain {
+    /// Creates a [`Null`] gain.
+    #[staticmethod]
+    fn null() -> Self {
+        Self::new(Null::new())
+    }
+
+    /// Creates a [`Uniform`] gain.
+    #[staticmethod]
+    #[pyo3(signature = (intensity = 0xFF, phase = 0))]
+    fn uniform(intensity: u8, phase: u8) -> Self {
+        Self::new(Uniform {
+            intensity: EmitIntensity(intensity),
+            phase: Phase(phase),
+        })
+    }
+
+    /// Creates a [`Focus`] gain at `pos`.
+    ///
+    /// `near_field_compensation` is `0` for [`NearFieldCompensation::None`], `1` for [`NearFieldCompensation::InverseDistance`], and `2` for [`NearFieldCompensation::Directivity`].
+    #[staticmethod]
+    #[pyo3(signature = (pos, intensity = 0xFF, phase_offset = 0, near_field_compensation = 0))]
+    fn focus(
+        pos: [f32; 3],
+        intensity: u8,
+        phase_offset: u8,
+        near_field_compensation: u8,
+    ) -> PyResult<Self> {
+        let near_field_compensation = match near_field_compensation {
+            0 => NearFieldCompensation::None,
+            1 => NearFieldCompensation::InverseDistance,
+            2 => NearFieldCompensation::Directivity,
+            v => {
+                return Err(PyValueError::new_err(format!(
+                    "Invalid near field compensation: {}",
+                    v
+                )))
+            }
+        };
+        Ok(Self::new(Focus {
+            pos: Point3::from(pos),
+            option: FocusOption {
+                intensity: EmitIntensity(intensity),
+                phase_offset: Phase(phase_offset),
+                near_field_compensation,
+            },
+        }))
+    }
+
+    /// Creates a [`Plane`] gain in the direction `dir`, which is normalized.
+    #[staticmethod]
+    #[pyo3(signature = (dir, intensity = 0xFF, phase_offset = 0))]
+    fn plane(dir: [f32; 3], intensity: u8, phase_offset: u8) -> PyResult<Self> {
+        Ok(Self::new(Plane {
+            dir: self::dir(dir)?,
+            option: PlaneOption {
+                intensity: EmitIntensity(intensity),
+                phase_offset: Phase(phase_offset),
+            },
+        }))
+    }
+
+    /// Creates a [`Bessel`] gain with the vertex `pos`, the direction `dir`, which is normalized, and the angle `theta` in radians.
+    #[staticmethod]
+    #[pyo3(signature = (pos, dir, theta, intensity = 0xFF, phase_offset = 0))]
+    fn bessel(
+        pos: [f32; 3],
+        dir: [f32; 3],
+        theta: f32,
+        intensity: u8,
+        phase_offset: u8,
+    ) -> PyResult<Self> {
+        Ok(Self::new(Bessel {
+            pos: Point3::from(pos),
+            dir: self::dir(dir)?,
+            theta: theta * rad,
+            option: BesselOption {
+                intensity: EmitIntensity(intensity),
+                phase_offset: Phase(phase_offset),
+            },
+        }))
+    }
+}
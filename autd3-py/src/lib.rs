@@ -0,0 +1,36 @@
+#![warn(missing_docs)]
+#![warn(rustdoc::missing_crate_level_docs)]
+#![warn(rustdoc::unescaped_backticks)]
+
+//! This crate provides a Python binding of autd3 by [PyO3](https://pyo3.rs).
+//!
+//! The module `autd3_py` exposes [`Controller`], primitive [`Gain`]s and [`Modulation`]s, [`STM`], and the links.
+//! The GIL is released while opening, sending to, and closing the devices, so that other Python threads can run in the meantime.
+//! The errors of autd3 are raised as [`AUTDError`].
+
+mod controller;
+mod error;
+mod gain;
+mod link;
+mod modulation;
+mod stm;
+
+use pyo3::prelude::*;
+
+pub use controller::Controller;
+pub use error::AUTDError;
+pub use gain::Gain;
+pub use link::PyLink;
+pub use modulation::Modulation;
+pub use stm::STM;
+
+#[pymodule]
+fn autd3_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Controller>()?;
+    m.add_class::<Gain>()?;
+    m.add_class::<Modulation>()?;
+    m.add_class::<STM>()?;
+    m.add_class::<PyLink>()?;
+    m.add("AUTDError", m.py().get_type::<AUTDError>())?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use std::sync::Mutex;
+
+use autd3::link::Nop;
+use autd3_core::link::Link;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// The link to the devices.
+///
+/// The link is consumed by `Controller.open` of [`Controller`], so that it cannot be used twice.
+///
+/// [`Controller`]: crate::Controller
+#[pyclass(name = "Link", module = "autd3_py", frozen)]
+pub struct PyLink(Mutex<Option<Box<dyn Link>>>);
+
+impl PyLink {
+    fn new(link: impl Link + 'static) -> Self {
+        Self(Mutex::new(Some(Box::new(link))))
+    }
+
+    pub(crate) fn take(&self) -> PyResult<Box<dyn Link>> {
+        self.0
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| PyValueError::new_err("The link has already been used"))
+    }
+}
+
+#[pymethods]
+impl PyLink {
+    /// Creates a [`Nop`] link.
+    #[staticmethod]
+    fn nop() -> Self {
+        Self::new(Nop::new())
+    }
+
+    /// Creates a [`Simulator`] link to the server at `addr`, e.g., `"127.0.0.1:8080"`.
+    ///
+    /// [`Simulator`]: autd3_link_simulator::Simulator
+    #[cfg(feature = "simulator")]
+    #[staticmethod]
+    fn simulator(addr: &str) -> PyResult<Self> {
+        addr.parse()
+            .map(|addr| Self::new(autd3_link_simulator::Simulator::new(addr)))
+            .map_err(|e: std::net::AddrParseError| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Creates a [`RemoteTwinCAT`] link to the server of `server_ams_net_id`.
+    ///
+    /// Empty `server_ip` and `client_ams_net_id` mean the default value of [`RemoteTwinCATOption`].
+    ///
+    /// [`RemoteTwinCAT`]: autd3_link_twincat::RemoteTwinCAT
+    /// [`RemoteTwinCATOption`]: autd3_link_twincat::RemoteTwinCATOption
+    #[cfg(feature = "remote")]
+    #[staticmethod]
+    #[pyo3(signature = (server_ams_net_id, server_ip = String::new(), client_ams_net_id = String::new()))]
+    fn remote_twincat(
+        server_ams_net_id: &str,
+        server_ip: String,
+        client_ams_net_id: String,
+    ) -> Self {
+        Self::new(autd3_link_twincat::RemoteTwinCAT::new(
+            server_ams_net_id,
+            autd3_link_twincat::RemoteTwinCATOption {
+                server_ip,
+                client_ams_net_id,
+            },
+        ))
+    }
+}
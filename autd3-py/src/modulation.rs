@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use autd3::{
+    driver::{
+        datagram::{BoxedModulation, IntoBoxedModulation},
+        defined::Hz,
+    },
+    modulation::{Sine, SineOption, Square, SquareOption, Static},
+};
+use pyo3::prelude::*;
+
+/// A primitive modulation.
+///
+/// The modulation can be sent many times, because the Rust modulation is created every time it is sent.
+#[pyclass(module = "autd3_py", frozen)]
+#[derive(Clone)]
+pub struct Modulation(Arc<dyn Fn() -> BoxedModulation + Send + Sync>);
+
+impl Modulation {
+    fn new<M: IntoBoxedModulation + Clone + Send + Sync + 'static>(m: M) -> Self {
+        Self(Arc::new(move || m.clone().into_boxed()))
+    }
+
+    pub(crate) fn to_boxed(&self) -> BoxedModulation {
+        (self.0)()
+    }
+}
+
+fn sine_option(intensity: u8, offset: u8) -> SineOption {
+    SineOption {
+        intensity,
+        offset,
+        ..Default::default()
+    }
+}
+
+fn square_option(low: u8, high: u8, duty: f32) -> SquareOption {
+    SquareOption {
+        low,
+        high,
+        duty,
+        ..Default::default()
+    }
+}
+
+#[pymethods]
+impl Modulation {
+    /// Creates a [`Static`] modulation.
+    #[staticmethod]
+    #[pyo3(name = "static", signature = (intensity = 0xFF))]
+    fn static_(intensity: u8) -> Self {
+        Self::new(Static { intensity })
+    }
+
+    /// Creates a [`Sine`] modulation of the exact frequency `freq` Hz.
+    ///
+    /// If the frequency cannot be output exactly, sending the modulation fails.
+    #[staticmethod]
+    #[pyo3(signature = (freq, intensity = 0xFF, offset = 0x80))]
+    fn sine_exact(freq: u32, intensity: u8, offset: u8) -> Self {
+        Self::new(Sine {
+            freq: freq * Hz,
+            option: sine_option(intensity, offset),
+        })
+    }
+
+    /// Creates a [`Sine`] modulation of the nearest frequency to `freq` Hz that can be output.
+    #[staticmethod]
+    #[pyo3(signature = (freq, intensity = 0xFF, offset = 0x80))]
+    fn sine_nearest(freq: f32, intensity: u8, offset: u8) -> Self {
+        Self::new(
+            Sine {
+                freq: freq * Hz,
+                option: sine_option(intensity, offset),
+            }
+            .into_nearest(),
+        )
+    }
+
+    /// Creates a [`Square`] modulation of the exact frequency `freq` Hz.
+    ///
+    /// If the frequency cannot be output exactly, sending the modulation fails.
+    #[staticmethod]
+    #[pyo3(signature = (freq, low = 0x00, high = 0xFF, duty = 0.5))]
+    fn square_exact(freq: u32, low: u8, high: u8, duty: f32) -> Self {
+        Self::new(Square {
+            freq: freq * Hz,
+            option: square_option(low, high, duty),
+        })
+    }
+
+    /// Creates a [`Square`] modulation of the nearest frequency to `freq` Hz that can be output.
+    #[staticmethod]
+    #[pyo3(signature = (freq, low = 0x00, high = 0xFF, duty = 0.5))]
+    fn square_nearest(freq: f32, low: u8, high: u8, duty: f32) -> Self {
+        Self::new(
+            Square {
+                freq: freq * Hz,
+                option: square_option(low, high, duty),
+            }
+            .into_nearest(),
+        )
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::Arc;
+
+use autd3::driver::{
+    datagram::{BoxedDatagram, FociSTM, GainSTM, GainSTMOption, IntoBoxedDatagram},
+    defined::Hz,
+    firmware::{
+        fpga::{EmitIntensity, Phase},
+        operation::{ControlPoint, ControlPoints},
+    },
+    geometry::Point3,
+};
+use pyo3::prelude::*;
+
+use crate::Gain;
+
+/// A spatio-temporal modulation, i.e., [`FociSTM`] or [`GainSTM`].
+///
+/// The STM can be sent many times, because the Rust STM is created every time it is sent.
+#[pyclass(module = "autd3_py", frozen)]
+#[derive(Clone)]
+pub struct STM(Arc<dyn Fn() -> BoxedDatagram + Send + Sync>);
+
+impl STM {
+    pub(crate) fn to_boxed(&self) -> BoxedDatagram {
+        (self.0)()
+    }
+}
+
+fn foci(points: Vec<[f32; 3]>, intensity: u8) -> Vec<ControlPoints<1>> {
+    points
+        .into_iter()
+        .map(|p| ControlPoints {
+            points: [ControlPoint {
+                point: Point3::from(p),
+                phase_offset: Phase::ZERO,
+            }],
+            intensity: EmitIntensity(intensity),
+        })
+        .collect()
+}
+
+#[pymethods]
+impl STM {
+    /// Creates a [`FociSTM`] of the exact frequency `freq` Hz through the foci `points`.
+    ///
+    /// If the frequency cannot be output exactly, sending the STM fails.
+    #[staticmethod]
+    #[pyo3(signature = (points, freq, intensity = 0xFF))]
+    fn foci_exact(points: Vec<[f32; 3]>, freq: f32, intensity: u8) -> Self {
+        let stm = FociSTM {
+            foci: foci(points, intensity),
+            config: freq * Hz,
+        };
+        Self(Arc::new(move || stm.clone().into_boxed()))
+    }
+
+    /// Same as [`STM::foci_exact`], but with the nearest frequency to `freq` Hz that can be output.
+    #[staticmethod]
+    #[pyo3(signature = (points, freq, intensity = 0xFF))]
+    fn foci_nearest(points: Vec<[f32; 3]>, freq: f32, intensity: u8) -> Self {
+        let stm = FociSTM {
+            foci: foci(points, intensity),
+            config: freq * Hz,
+        }
+        .into_nearest();
+        Self(Arc::new(move || stm.clone().into_boxed()))
+    }
+
+    /// Creates a [`GainSTM`] of the exact frequency `freq` Hz from the `gains`.
+    ///
+    /// If the frequency cannot be output exactly, sending the STM fails.
+    #[staticmethod]
+    fn gain_exact(gains: Vec<Gain>, freq: f32) -> Self {
+        Self(Arc::new(move || {
+            GainSTM {
+                gains: gains.iter().map(Gain::to_boxed).collect::<Vec<_>>(),
+                config: freq * Hz,
+                option: GainSTMOption::default(),
+            }
+            .into_boxed()
+        }))
+    }
+
+    /// Same as [`STM::gain_exact`], but with the nearest frequency to `freq` Hz that can be output.
+    #[staticmethod]
+    fn gain_nearest(gains: Vec<Gain>, freq: f32) -> Self {
+        Self(Arc::new(move || {
+            GainSTM {
+                gains: gains.iter().map(Gain::to_boxed).collect::<Vec<_>>(),
+                config: freq * Hz,
+                option: GainSTMOption::default(),
+            }
+            .into_nearest()
+            .into_boxed()
+        }))
+    }
+}
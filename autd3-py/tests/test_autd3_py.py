@@ -0,0 +1,98 @@
+import threading
+
+import pytest
+
+from autd3_py import STM, AUTDError, Controller, Gain, Link, Modulation
+
+
+def open_nop(n: int = 1) -> Controller:
+    return Controller.open([(0.0, 0.0, 0.0)] * n, Link.nop())
+
+
+def test_open_send_close():
+    autd = open_nop(2)
+    assert autd.num_devices == 2
+
+    autd.send(Modulation.static())
+    autd.send(g=Gain.focus((0.0, 0.0, 150.0)))
+    autd.send(Modulation.sine_exact(150), Gain.uniform(0xFF, 0))
+    with pytest.raises(ValueError):
+        autd.send()
+
+    autd.close()
+    with pytest.raises(AUTDError):
+        autd.send(Modulation.static())
+    with pytest.raises(AUTDError):
+        autd.close()
+
+
+def test_open_rot():
+    autd = Controller.open([(0.0, 0.0, 0.0)] * 2, Link.nop(), rot=[(1.0, 0.0, 0.0, 0.0), (0.0, 0.0, 0.0, 1.0)])
+    autd.close()
+    with pytest.raises(ValueError):
+        Controller.open([(0.0, 0.0, 0.0)] * 2, Link.nop(), rot=[(1.0, 0.0, 0.0, 0.0)])
+
+
+def test_link_used_twice():
+    link = Link.nop()
+    Controller.open([(0.0, 0.0, 0.0)], link).close()
+    with pytest.raises(ValueError):
+        Controller.open([(0.0, 0.0, 0.0)], link)
+
+
+def test_gain():
+    autd = open_nop()
+    for g in [
+        Gain.null(),
+        Gain.uniform(),
+        Gain.focus((0.0, 0.0, 150.0), near_field_compensation=2),
+        Gain.plane((0.0, 0.0, 1.0)),
+        Gain.bessel((0.0, 0.0, 0.0), (0.0, 0.0, 1.0), 0.1),
+    ]:
+        autd.send(g=g)
+    with pytest.raises(ValueError):
+        Gain.focus((0.0, 0.0, 150.0), near_field_compensation=3)
+    with pytest.raises(ValueError):
+        Gain.plane((0.0, 0.0, 0.0))
+    autd.close()
+
+
+def test_modulation():
+    autd = open_nop()
+    for m in [
+        Modulation.static(0x80),
+        Modulation.sine_exact(150),
+        Modulation.sine_nearest(150.0),
+        Modulation.square_exact(150),
+        Modulation.square_nearest(150.0, duty=0.3),
+    ]:
+        autd.send(m)
+    with pytest.raises(AUTDError):
+        autd.send(Modulation.sine_exact(10000))
+    autd.close()
+
+
+@pytest.mark.parametrize(("ok", "freq"), [(True, 1.0), (False, 0.3)])
+def test_stm(ok: bool, freq: float):
+    autd = open_nop()
+    points = [(0.0, 0.0, 150.0), (10.0, 0.0, 150.0)]
+    gains = [Gain.null(), Gain.null()]
+    for stm in [STM.foci_exact(points, freq), STM.gain_exact(gains, freq)]:
+        if ok:
+            autd.send_stm(stm)
+        else:
+            with pytest.raises(AUTDError):
+                autd.send_stm(stm)
+    autd.send_stm(STM.foci_nearest(points, freq))
+    autd.send_stm(STM.gain_nearest(gains, freq))
+    autd.close()
+
+
+def test_send_from_threads():
+    autd = open_nop()
+    threads = [threading.Thread(target=lambda: [autd.send(g=Gain.null()) for _ in range(10)]) for _ in range(4)]
+    for t in threads:
+        t.start()
+    for t in threads:
+        t.join()
+    autd.close()
@@ -0,0 +1,96 @@
+use std::{num::NonZeroU64, time::Duration};
+
+use autd3::{
+    link::{Bench, BenchOption},
+    prelude::*,
+};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn generate_devices(size: usize) -> impl Iterator<Item = AUTD3> {
+    (0..size).map(move |i| AUTD3 {
+        pos: Point3::new(
+            (i % 8) as f32 * AUTD3::DEVICE_WIDTH,
+            (i / 8) as f32 * AUTD3::DEVICE_HEIGHT,
+            0.,
+        ),
+        ..Default::default()
+    })
+}
+
+fn links() -> [(&'static str, BenchOption); 2] {
+    [
+        ("ideal", BenchOption::default()),
+        (
+            "ethercat",
+            BenchOption {
+                latency: Duration::from_micros(500),
+                jitter: Duration::from_micros(100),
+                throughput: NonZeroU64::new(12_500_000),
+                seed: 0,
+            },
+        ),
+    ]
+}
+
+fn option() -> SenderOption<SpinSleeper> {
+    SenderOption {
+        send_interval: Duration::ZERO,
+        receive_interval: Duration::ZERO,
+        ..Default::default()
+    }
+}
+
+fn send_focus(c: &mut Criterion) {
+    let mut group = c.benchmark_group("autd3/sender/focus");
+
+    [1, 16, 64].iter().for_each(|&size| {
+        group.throughput(Throughput::Elements(size as u64));
+        links().into_iter().for_each(|(name, link)| {
+            let mut autd =
+                Controller::open_with_option(generate_devices(size), Bench::new(link), option())
+                    .unwrap();
+            let pos = autd.center().unwrap() + Vector3::new(0., 0., 150. * mm);
+            group.bench_function(BenchmarkId::new(name, size), |b| {
+                b.iter(|| {
+                    autd.sender(option())
+                        .send(Focus {
+                            pos,
+                            option: FocusOption::default(),
+                        })
+                        .unwrap()
+                })
+            });
+            autd.close().unwrap();
+        });
+    });
+    group.finish();
+}
+
+fn send_modulation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("autd3/sender/modulation");
+
+    [1, 16, 64].iter().for_each(|&size| {
+        group.throughput(Throughput::Elements(size as u64));
+        links().into_iter().for_each(|(name, link)| {
+            let mut autd =
+                Controller::open_with_option(generate_devices(size), Bench::new(link), option())
+                    .unwrap();
+            group.bench_function(BenchmarkId::new(name, size), |b| {
+                b.iter(|| {
+                    autd.sender(option())
+                        .send(Sine {
+                            freq: 150. * Hz,
+                            option: SineOption::default(),
+                        })
+                        .unwrap()
+                })
+            });
+            autd.close().unwrap();
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, send_focus, send_modulation);
+criterion_main!(benches);
@@ -1,16 +1,18 @@
-use std::{collections::HashMap, fmt::Debug, hash::Hash, time::Duration};
+use std::{collections::HashMap, fmt::Debug, hash::Hash, time::Instant};
 
-use autd3_core::{derive::DatagramOption, link::AsyncLink};
+use autd3_core::link::AsyncLink;
 use autd3_driver::{
     datagram::Datagram,
     error::AUTDDriverError,
     firmware::operation::{Operation, OperationGenerator},
     geometry::Device,
 };
-use bit_vec::BitVec;
-use itertools::Itertools;
 
-use crate::{error::AUTDError, prelude::SenderOption};
+use crate::{
+    controller::{group_operations, GroupSendReport},
+    error::AUTDError,
+    prelude::SenderOption,
+};
 
 use super::{sender::Sender, AsyncSleep, AsyncSleeper, Controller};
 
@@ -34,6 +36,26 @@ impl<L: AsyncLink> Controller<L> {
             .group_send(key_map, datagram_map)
             .await
     }
+
+    /// Please see [`crate::controller::Sender::group_send_with_report`].
+    pub async fn group_send_with_report<K, D, F>(
+        &mut self,
+        key_map: F,
+        datagram_map: HashMap<K, D>,
+    ) -> Result<GroupSendReport<K>, AUTDError>
+    where
+        K: Hash + Eq + Debug,
+        D: Datagram,
+        F: Fn(&Device) -> Option<K>,
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        self.sender(SenderOption::<AsyncSleeper>::default())
+            .group_send_with_report(key_map, datagram_map)
+            .await
+    }
 }
 
 impl<L: AsyncLink, S: AsyncSleep> Sender<'_, L, S> {
@@ -52,95 +74,86 @@ impl<L: AsyncLink, S: AsyncSleep> Sender<'_, L, S> {
         AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
             + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
     {
-        let mut datagram_map = datagram_map;
-
-        let filters = {
-            let num_devices = self.geometry.iter().len();
-            let mut filters: HashMap<K, BitVec> = HashMap::new();
-            self.geometry.devices().for_each(|dev| {
-                if let Some(key) = key_map(dev) {
-                    if let Some(v) = filters.get_mut(&key) {
-                        v.set(dev.idx(), true);
-                    } else {
-                        filters.insert(key, BitVec::from_fn(num_devices, |i| i == dev.idx()));
-                    }
-                }
-            });
-            filters
-        };
-
-        let enable_store = self
-            .geometry
-            .iter()
-            .map(|dev| dev.enable)
-            .collect::<Vec<_>>();
+        let start = Instant::now();
+        let res = self.group_send_impl(key_map, datagram_map).await;
+        self.events
+            .push_send(std::any::type_name::<D>(), start, &res);
+        res
+    }
 
-        let mut operations: Vec<_> = self.geometry.devices().map(|_| None).collect();
-        let mut datagram_option = DatagramOption {
-            timeout: Duration::ZERO,
-            parallel_threshold: usize::MAX,
+    /// Please see [`crate::controller::Sender::group_send_with_report`].
+    pub async fn group_send_with_report<K, D, F>(
+        &mut self,
+        key_map: F,
+        datagram_map: HashMap<K, D>,
+    ) -> Result<GroupSendReport<K>, AUTDError>
+    where
+        K: Hash + Eq + Debug,
+        D: Datagram,
+        F: Fn(&Device) -> Option<K>,
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let start = Instant::now();
+        let res = match group_operations(
+            self.geometry,
+            self.option.parallel,
+            self.option.timeout,
+            key_map,
+            datagram_map,
+        ) {
+            Ok(ops) => {
+                let res = self
+                    .send_impl(ops.operations, ops.timeout, ops.parallel)
+                    .await;
+                Ok(GroupSendReport::new(
+                    ops.filters,
+                    res,
+                    self.geometry,
+                    self.tx,
+                    self.rx,
+                ))
+            }
+            Err(e) => Err(e),
         };
-        filters
-            .into_iter()
-            .try_for_each(|(k, filter)| -> Result<(), AUTDError> {
-                {
-                    // set enable flag for each device
-                    // This is not required for the operation except `Gain`s which cannot be calculated independently for each device, such as `autd3-gain-holo`.
-                    self.geometry.devices_mut().for_each(|dev| {
-                        dev.enable = filter[dev.idx()];
-                    });
-
-                    let datagram = datagram_map
-                        .remove(&k)
-                        .ok_or(AUTDError::UnkownKey(format!("{:?}", k)))?;
-                    datagram_option = DatagramOption {
-                        timeout: datagram_option.timeout.max(datagram.option().timeout),
-                        parallel_threshold: datagram_option
-                            .parallel_threshold
-                            .min(datagram.option().parallel_threshold),
-                    };
-                    let parallel = self.option.parallel.is_parallel(
-                        self.geometry.num_devices(),
-                        datagram.option().parallel_threshold,
-                    );
-                    let mut generator = datagram
-                        .operation_generator(self.geometry, parallel)
-                        .map_err(AUTDDriverError::from)?;
-
-                    // restore enable flag
-                    self.geometry
-                        .iter_mut()
-                        .zip(enable_store.iter())
-                        .for_each(|(dev, &enable)| {
-                            dev.enable = enable;
-                        });
-
-                    operations
-                        .iter_mut()
-                        .zip(self.geometry.devices())
-                        .filter(|(_, dev)| filter[dev.idx()])
-                        .for_each(|(op, dev)| {
-                            tracing::debug!("Generate operation for device {}", dev.idx());
-                            let (op1, op2) = generator.generate(dev);
-                            *op = Some((op1, op2));
-                        });
-                    Ok(())
-                }
-            })?;
-
-        if !datagram_map.is_empty() {
-            return Err(AUTDError::UnusedKey(
-                datagram_map.keys().map(|k| format!("{:?}", k)).join(", "),
-            ));
+        match &res {
+            Ok(report) if !report.is_ok() => {
+                self.events
+                    .push_send(std::any::type_name::<D>(), start, &Err::<(), _>(report))
+            }
+            _ => self
+                .events
+                .push_send(std::any::type_name::<D>(), start, &res),
         }
+        res
+    }
 
-        let timeout = self.option.timeout.unwrap_or(datagram_option.timeout);
-        let parallel = self.option.parallel.is_parallel(
-            self.geometry.num_devices(),
-            datagram_option.parallel_threshold,
-        );
-        tracing::debug!("timeout: {:?}, parallel: {:?}", timeout, parallel);
-        Ok(self.send_impl(operations, timeout, parallel).await?)
+    async fn group_send_impl<K, D, F>(
+        &mut self,
+        key_map: F,
+        datagram_map: HashMap<K, D>,
+    ) -> Result<(), AUTDError>
+    where
+        K: Hash + Eq + Debug,
+        D: Datagram,
+        F: Fn(&Device) -> Option<K>,
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let ops = group_operations(
+            self.geometry,
+            self.option.parallel,
+            self.option.timeout,
+            key_map,
+            datagram_map,
+        )?;
+        Ok(self
+            .send_impl(ops.operations, ops.timeout, ops.parallel)
+            .await?)
     }
 }
 
@@ -152,12 +165,12 @@ mod tests {
     use autd3_driver::{
         datagram::{GainSTM, IntoBoxedDatagram, SwapSegment},
         defined::Hz,
-        error::AUTDDriverError,
+        error::{AUTDDriverError, DeviceError, FirmwareErrorCode},
         firmware::fpga::{Drive, EmitIntensity, Phase},
     };
 
     use crate::{
-        controller::tests::TestGain,
+        controller::{tests::TestGain, GroupResult},
         error::AUTDError,
         gain::{Null, Uniform},
         modulation::{Sine, Static},
@@ -291,7 +304,13 @@ mod tests {
         let mut autd = create_controller(2).await?;
 
         assert_eq!(
-            Err(AUTDError::Driver(AUTDDriverError::InvalidSegmentTransition)),
+            Err(AUTDError::Driver(AUTDDriverError::Device(vec![
+                DeviceError {
+                    dev_idx: 1,
+                    code: FirmwareErrorCode::InvalidSegmentTransition,
+                    tags: [Some(0x44), None],
+                }
+            ]))),
             autd.group_send(
                 |dev| Some(dev.idx()),
                 HashMap::from([
@@ -390,4 +409,44 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn group_send_with_report() -> anyhow::Result<()> {
+        let mut autd = create_controller(2).await?;
+
+        let report = autd
+            .group_send_with_report(
+                |dev| Some(dev.idx()),
+                HashMap::from([
+                    (0, Null {}.into_boxed()),
+                    (
+                        1,
+                        SwapSegment::FociSTM(Segment::S1, TransitionMode::SyncIdx).into_boxed(),
+                    ),
+                ]),
+            )
+            .await?;
+        assert_eq!(
+            Some(&GroupResult {
+                devices: vec![0],
+                failed: vec![],
+                result: Ok(()),
+            }),
+            report.groups.get(&0)
+        );
+        assert_eq!(
+            Some(&GroupResult {
+                devices: vec![1],
+                failed: vec![1],
+                result: Err(AUTDDriverError::Device(vec![DeviceError {
+                    dev_idx: 1,
+                    code: FirmwareErrorCode::InvalidSegmentTransition,
+                    tags: [Some(0x44), None],
+                }])),
+            }),
+            report.groups.get(&1)
+        );
+
+        Ok(())
+    }
 }
@@ -1,9 +1,25 @@
 mod group;
+mod rate_limiter;
 mod sender;
 
-use crate::{controller::SenderOption, error::AUTDError, gain::Null, modulation::Static};
+use crate::{
+    controller::{
+        into_geometry, reindex, CloseReport, ConfigSnapshot, ConfigTracker, ControllerEvent,
+        DiagnosticsReport, EventLog, HapticOptions, PackingReport, PowerMonitor, RestorePlan,
+        RttTracker, SenderOption, Sequence, SyncOption,
+    },
+    error::AUTDError,
+    gain::Null,
+    modulation::Static,
+};
 
-use autd3_core::{defined::DEFAULT_TIMEOUT, geometry::IntoDevice, link::AsyncLink};
+use autd3_core::{
+    datagram::{DatagramL, LoopBehavior, Segment, TransitionMode},
+    defined::DEFAULT_TIMEOUT,
+    ethercat::DcSysTime,
+    geometry::IntoDevice,
+    link::AsyncLink,
+};
 
 use autd3_driver::{
     datagram::{Clear, Datagram, FixedCompletionSteps, ForceFan, Silencer, Synchronize},
@@ -14,9 +30,10 @@ use autd3_driver::{
         operation::{FirmwareVersionType, Operation, OperationGenerator},
         version::FirmwareVersion,
     },
-    geometry::{Device, Geometry},
+    geometry::{Device, Geometry, Point3, UnitQuaternion},
 };
 
+pub use rate_limiter::RateLimiter;
 pub use sender::{AsyncSleeper, Sender};
 
 use derive_more::{Deref, DerefMut};
@@ -40,6 +57,11 @@ pub struct Controller<L: AsyncLink> {
     geometry: Geometry,
     tx_buf: Vec<TxMessage>,
     rx_buf: Vec<RxMessage>,
+    events: EventLog,
+    power: PowerMonitor,
+    rtt: RttTracker,
+    packing: Option<PackingReport>,
+    config: ConfigTracker,
 }
 
 impl<L: AsyncLink> Controller<L> {
@@ -69,24 +91,49 @@ impl<L: AsyncLink> Controller<L> {
     ) -> Result<Self, AUTDError> {
         tracing::debug!("Opening a controller with option {:?})", option);
 
-        let devices = devices
-            .into_iter()
-            .enumerate()
-            .map(|(i, d)| d.into_device(i as _))
-            .collect();
-
-        let geometry = Geometry::new(devices);
+        let geometry = into_geometry(devices)?;
+        link.preflight(&geometry).await?;
         link.open(&geometry).await?;
         Controller {
             link,
             tx_buf: vec![TxMessage::new_zeroed(); geometry.len()], // Do not use `num_devices` here because the devices may be disabled.
             rx_buf: vec![RxMessage::new(0, 0); geometry.len()],
+            events: EventLog::default(),
+            power: PowerMonitor::default(),
+            rtt: RttTracker::default(),
+            packing: None,
+            config: ConfigTracker::default(),
             geometry,
         }
         .open_impl(option)
         .await
     }
 
+    /// Please see [`crate::controller::Controller::open_with_freq`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "dynamic_freq")))]
+    #[cfg(feature = "dynamic_freq")]
+    pub async fn open_with_freq<D: IntoDevice, F: IntoIterator<Item = D>, S: AsyncSleep>(
+        devices: F,
+        link: L,
+        freq: autd3_driver::datagram::UltrasoundFreq,
+        option: SenderOption<S>,
+    ) -> Result<Self, AUTDError> {
+        autd3_core::defined::set_ultrasound_freq(freq.freq())
+            .map_err(|current| AUTDError::UltrasoundFreqAlreadyFixed(freq.freq(), current))?;
+        Self::open_with_option(devices, link, option).await
+    }
+
+    /// Checks the environment with [`AsyncLink::preflight`] without opening the link.
+    ///
+    /// [`Self::open`] also performs this check before opening the link. This is useful to report setup issues, e.g., missing drivers or unreachable servers, before any device traffic.
+    pub async fn preflight<D: IntoDevice, F: IntoIterator<Item = D>>(
+        devices: F,
+        link: &mut L,
+    ) -> Result<(), AUTDError> {
+        link.preflight(&into_geometry(devices)?).await?;
+        Ok(())
+    }
+
     /// Returns the [`Sender`] to send data to the devices.
     pub fn sender<S: AsyncSleep>(&mut self, option: SenderOption<S>) -> Sender<'_, L, S> {
         Sender {
@@ -94,12 +141,16 @@ impl<L: AsyncLink> Controller<L> {
             geometry: &mut self.geometry,
             tx: &mut self.tx_buf,
             rx: &mut self.rx_buf,
+            events: &mut self.events,
+            power: &mut self.power,
+            rtt: &mut self.rtt,
+            packing: &mut self.packing,
+            config: &mut self.config,
             option,
         }
     }
 
     /// Sends a data to the devices. This is a shortcut for [`Sender::send`].
-    #[tracing::instrument(level = "debug", skip(self))]
     pub async fn send<D: Datagram>(&mut self, s: D) -> Result<(), AUTDDriverError>
     where
         AUTDDriverError: From<D::Error>,
@@ -112,10 +163,93 @@ impl<L: AsyncLink> Controller<L> {
             .await
     }
 
+    /// Renders a haptic point at `pos`. See [`crate::controller::Controller::render_point`] for details.
+    pub async fn render_point(
+        &mut self,
+        pos: Point3,
+        option: HapticOptions,
+    ) -> Result<(), AUTDDriverError> {
+        self.send(Silencer::<FixedCompletionSteps>::default())
+            .await?;
+        self.send(option.stimulus(pos)).await
+    }
+
+    /// Sends data to the devices in order. This is a shortcut for [`Sender::send_all`].
+    #[tracing::instrument(level = "debug", skip(self, s))]
+    pub async fn send_all<D: Datagram>(
+        &mut self,
+        s: impl IntoIterator<Item = D>,
+    ) -> Result<(), (usize, AUTDDriverError)>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        self.sender(SenderOption::<AsyncSleeper>::default())
+            .send_all(s)
+            .await
+    }
+
+    /// Sends `a` to this controller and `b` to `other` so that both switch to the `segment` at the same system time. See [`crate::controller::Controller::send_synchronized`] for details.
+    #[tracing::instrument(level = "debug", skip(self, other))]
+    pub async fn send_synchronized<L2: AsyncLink, A: DatagramL, B: DatagramL>(
+        &mut self,
+        a: A,
+        other: &mut Controller<L2>,
+        b: B,
+        segment: Segment,
+        loop_behavior: LoopBehavior,
+        option: SyncOption,
+    ) -> Result<DcSysTime, (usize, AUTDDriverError)>
+    where
+        AUTDDriverError: From<A::Error> + From<B::Error>,
+        A::G: OperationGenerator,
+        B::G: OperationGenerator,
+        AUTDDriverError: From<<<A::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<A::G as OperationGenerator>::O2 as Operation>::Error>
+            + From<<<B::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<B::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let (time_a, time_b) = option.transition_time();
+        option.check(time_a).map_err(|e| (0, e))?;
+        option.check(time_b).map_err(|e| (1, e))?;
+        self.sender(SenderOption::<AsyncSleeper>::default())
+            .send_at(a, segment, loop_behavior, time_a)
+            .await
+            .map_err(|e| (0, e))?;
+        option.check(time_b).map_err(|e| (1, e))?;
+        other
+            .sender(SenderOption::<AsyncSleeper>::default())
+            .send_at(b, segment, loop_behavior, time_b)
+            .await
+            .map_err(|e| (1, e))?;
+        Ok(time_a)
+    }
+
+    /// Sends a sequence of data to the devices. This is a shortcut for [`Sender::send_sequence`].
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub async fn send_sequence(&mut self, s: Sequence) -> Result<(), AUTDDriverError> {
+        self.sender(SenderOption::<AsyncSleeper>::default())
+            .send_sequence(s)
+            .await
+    }
+
     pub(crate) async fn open_impl<S: AsyncSleep>(
         mut self,
         option: SenderOption<S>,
     ) -> Result<Self, AUTDError> {
+        self.initialize(option).await?;
+        self.events.push(ControllerEvent::Opened {
+            num_devices: self.geometry.len(),
+        });
+        Ok(self)
+    }
+
+    async fn initialize<S: AsyncSleep>(
+        &mut self,
+        option: SenderOption<S>,
+    ) -> Result<(), AUTDError> {
         let mut sender = self.sender(option);
 
         // If the device is used continuously without powering off, the first data may be ignored because the first msg_id equals to the remaining msg_id in the device.
@@ -134,10 +268,102 @@ impl<L: AsyncLink> Controller<L> {
         }
 
         sender.send((Clear::new(), Synchronize::new())).await?;
-        Ok(self)
+        self.config.cleared();
+        Ok(())
     }
 
-    async fn close_impl(&mut self) -> Result<(), AUTDDriverError> {
+    /// Enables or disables the devices at `indices`.
+    ///
+    /// Disabled devices are skipped consistently by all [`Datagram`]s, i.e., no data is generated or sent to them, and their responses including errors are ignored when confirming that the data has been processed.
+    /// Note that disabled devices keep their last output, so send [`Null`] to them beforehand if they should be stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AUTDError::DeviceIndexOutOfRange`] if any index is out of range. In this case, no device is changed.
+    ///
+    /// [`Datagram`]: autd3_driver::datagram::Datagram
+    /// [`Null`]: crate::gain::Null
+    pub fn set_enabled(
+        &mut self,
+        indices: impl IntoIterator<Item = usize>,
+        enable: bool,
+    ) -> Result<(), AUTDError> {
+        let indices = indices.into_iter().collect::<Vec<_>>();
+        if let Some(&idx) = indices.iter().find(|&&idx| idx >= self.geometry.len()) {
+            return Err(AUTDError::DeviceIndexOutOfRange(idx));
+        }
+        indices.into_iter().for_each(|idx| {
+            self.geometry[idx].enable = enable;
+        });
+        Ok(())
+    }
+
+    /// Updates the poses of the devices without reconfiguring the link. See [`crate::controller::Controller::update_poses`] for details.
+    pub async fn update_poses(
+        &mut self,
+        poses: impl IntoIterator<Item = (usize, Point3, UnitQuaternion)>,
+    ) -> Result<(), AUTDError> {
+        let poses = poses.into_iter().collect::<Vec<_>>();
+        if let Some(&(idx, _, _)) = poses.iter().find(|(idx, _, _)| *idx >= self.geometry.len()) {
+            return Err(AUTDError::DeviceIndexOutOfRange(idx));
+        }
+        let devices: &mut Vec<Device> = &mut self.geometry;
+        poses
+            .into_iter()
+            .for_each(|(idx, pos, rot)| devices[idx].set_pose(pos, rot));
+        self.link.update(&self.geometry).await?;
+        Ok(())
+    }
+
+    /// Adds a device to the end of the geometry without reopening the controller. See [`crate::controller::Controller::add_device`] for details.
+    pub async fn add_device<D: IntoDevice>(&mut self, dev: D) -> Result<(), AUTDError> {
+        let idx = self.geometry.len();
+        self.geometry.push(dev.into_device(idx as _));
+        if let Err(e) = self.link.reconfigure(&self.geometry).await {
+            self.geometry.pop();
+            return Err(e.into());
+        }
+        self.reinitialize().await
+    }
+
+    /// Removes the device at `idx` without reopening the controller, and returns the removed device. See [`crate::controller::Controller::remove_device`] for details.
+    pub async fn remove_device(&mut self, idx: usize) -> Result<Device, AUTDError> {
+        if idx >= self.geometry.len() {
+            return Err(AUTDError::DeviceIndexOutOfRange(idx));
+        }
+        if self.geometry.len() == 1 {
+            return Err(AUTDError::EmptyGeometry);
+        }
+        let dev = self.geometry.remove(idx);
+        reindex(&mut self.geometry);
+        if let Err(e) = self.link.reconfigure(&self.geometry).await {
+            self.geometry.insert(idx, dev);
+            reindex(&mut self.geometry);
+            return Err(e.into());
+        }
+        self.reinitialize().await?;
+        Ok(dev)
+    }
+
+    async fn reinitialize(&mut self) -> Result<(), AUTDError> {
+        self.tx_buf = vec![TxMessage::new_zeroed(); self.geometry.len()];
+        self.rx_buf = vec![RxMessage::new(0, 0); self.geometry.len()];
+        self.power = PowerMonitor::default();
+        self.rtt = RttTracker::default();
+        self.packing = None;
+        self.config = self.config.reset(&self.geometry);
+        self.initialize(SenderOption::<AsyncSleeper> {
+            timeout: Some(DEFAULT_TIMEOUT),
+            ..Default::default()
+        })
+        .await?;
+        self.events.push(ControllerEvent::DevicesChanged {
+            num_devices: self.geometry.len(),
+        });
+        Ok(())
+    }
+
+    async fn close_impl(&mut self) -> Result<(), AUTDError> {
         tracing::info!("Closing controller");
 
         if !self.link.is_open() {
@@ -146,26 +372,36 @@ impl<L: AsyncLink> Controller<L> {
         }
 
         self.geometry.iter_mut().for_each(|dev| dev.enable = true);
-        [
-            self.send(Silencer {
-                config: FixedCompletionSteps {
-                    strict_mode: false,
-                    ..Default::default()
-                },
-                target: autd3_driver::firmware::fpga::SilencerTarget::Intensity,
-            })
-            .await,
-            self.send((Static::default(), Null)).await,
-            self.send(Clear {}).await,
-            Ok(self.link.close().await?),
-        ]
-        .into_iter()
-        .try_fold((), |_, x| x)
+        let report = CloseReport {
+            silencer: self
+                .send(Silencer {
+                    config: FixedCompletionSteps {
+                        strict_mode: false,
+                        ..Default::default()
+                    },
+                    target: autd3_driver::firmware::fpga::SilencerTarget::Intensity,
+                })
+                .await,
+            null: self.send((Static::default(), Null)).await,
+            clear: self.send(Clear {}).await,
+            link_close: self.link.close().await.map_err(AUTDDriverError::from),
+        };
+        let res = if report.is_ok() {
+            Ok(())
+        } else {
+            Err(AUTDError::CloseFailed(Box::new(report)))
+        };
+        self.events.push(ControllerEvent::Closed {
+            error: res.as_ref().err().map(ToString::to_string),
+        });
+        res
     }
 
     /// Closes the controller.
+    ///
+    /// All stages of closing are run even if some of them fail. If any stage fails, [`AUTDError::CloseFailed`] with [`CloseReport`] is returned.
     #[tracing::instrument(level = "debug", skip(self))]
-    pub async fn close(mut self) -> Result<(), AUTDDriverError> {
+    pub async fn close(mut self) -> Result<(), AUTDError> {
         self.close_impl().await
     }
 
@@ -209,6 +445,26 @@ impl<L: AsyncLink> Controller<L> {
             .collect())
     }
 
+    /// Validates the combination of `segment`, `loop_behavior`, and `transition_mode` against the firmware of the devices.
+    ///
+    /// This reads the firmware version of the devices first, so that [`WithSegment`] and [`WithLoopBehavior`] which the firmware does not support can be rejected before sending. See [`FirmwareCapabilities::validate_transition`] for the errors.
+    ///
+    /// [`WithSegment`]: autd3_driver::datagram::WithSegment
+    /// [`WithLoopBehavior`]: autd3_driver::datagram::WithLoopBehavior
+    /// [`FirmwareCapabilities::validate_transition`]: autd3_driver::firmware::version::FirmwareCapabilities::validate_transition
+    pub async fn validate_transition(
+        &mut self,
+        segment: Segment,
+        loop_behavior: LoopBehavior,
+        transition_mode: Option<TransitionMode>,
+    ) -> Result<(), AUTDError> {
+        self.firmware_version().await?.iter().try_for_each(|v| {
+            v.caps()
+                .validate_transition(segment, loop_behavior, transition_mode)
+        })?;
+        Ok(())
+    }
+
     /// Returns the FPGA state of the devices.
     ///
     /// To get the state of devices, enable reads FPGA state mode by [`ReadsFPGAState`] before calling this method.
@@ -241,6 +497,81 @@ impl<L: AsyncLink> Controller<L> {
             Err(AUTDError::ReadFPGAStateFailed)
         }
     }
+
+    /// Inspects what the devices emit after the [`Datagram`] is sent. This is a shortcut for [`inspect`].
+    ///
+    /// [`inspect`]: crate::inspect::inspect
+    #[cfg(feature = "inspect")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "inspect")))]
+    pub fn inspect<D: Datagram>(
+        &self,
+        d: D,
+    ) -> Result<Vec<Option<crate::inspect::DeviceInspection>>, AUTDDriverError>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        crate::inspect::inspect(&self.geometry, d)
+    }
+
+    /// Returns the diagnostics report. See [`crate::controller::Controller::dump_diagnostics`] for details.
+    pub fn dump_diagnostics(&self) -> DiagnosticsReport {
+        self.events.report(&self.geometry, self.link.is_open())
+    }
+
+    /// Sets the capacity of the event log. See [`crate::controller::Controller::set_event_log_capacity`] for details.
+    pub fn set_event_log_capacity(&mut self, capacity: usize) {
+        self.events.set_capacity(capacity);
+    }
+
+    /// Returns the [`PackingReport`] of the last [`Datagram`] sent with [`SenderOption::metrics`]. See [`crate::controller::Controller::packing_report`] for details.
+    pub const fn packing_report(&self) -> Option<&PackingReport> {
+        self.packing.as_ref()
+    }
+
+    /// Enables tracking the configuration for [`Self::snapshot`]. See [`crate::controller::Controller::enable_snapshot`] for details.
+    pub fn enable_snapshot(&mut self) -> Result<(), AUTDError> {
+        self.config.enable(&self.geometry)
+    }
+
+    /// Captures the configuration currently applied to the devices. See [`crate::controller::Controller::snapshot`] for details.
+    pub fn snapshot(&self) -> Result<ConfigSnapshot, AUTDError> {
+        self.config.snapshot()
+    }
+
+    /// Restores the configuration captured by [`Self::snapshot`] to the enabled devices. See [`crate::controller::Controller::restore`] for details.
+    pub async fn restore(&mut self, snapshot: &ConfigSnapshot) -> Result<(), AUTDError> {
+        let RestorePlan {
+            silencer,
+            modulation,
+            stm,
+            strict_silencer,
+        } = self.config.plan(snapshot, &self.geometry)?;
+
+        let mut sender = self.sender(SenderOption::<AsyncSleeper>::default());
+        let keys = RestorePlan::keys(&silencer);
+        sender
+            .group_send(|dev| keys.get(&dev.idx()).copied(), silencer)
+            .await?;
+        sender.send(snapshot.tables()).await?;
+        for swap in [modulation, stm] {
+            if !swap.is_empty() {
+                let keys = RestorePlan::keys(&swap);
+                sender
+                    .group_send(|dev| keys.get(&dev.idx()).copied(), swap)
+                    .await?;
+            }
+        }
+        if !strict_silencer.is_empty() {
+            let keys = RestorePlan::keys(&strict_silencer);
+            sender
+                .group_send(|dev| keys.get(&dev.idx()).copied(), strict_silencer)
+                .await?;
+        }
+        Ok(())
+    }
 }
 
 impl<'a, L: AsyncLink> IntoIterator for &'a Controller<L> {
@@ -270,11 +601,21 @@ impl<L: AsyncLink + 'static> Controller<L> {
         let geometry = unsafe { std::ptr::read(&cnt.geometry) };
         let tx_buf = unsafe { std::ptr::read(&cnt.tx_buf) };
         let rx_buf = unsafe { std::ptr::read(&cnt.rx_buf) };
+        let events = unsafe { std::ptr::read(&cnt.events) };
+        let power = unsafe { std::ptr::read(&cnt.power) };
+        let rtt = unsafe { std::ptr::read(&cnt.rtt) };
+        let packing = unsafe { std::ptr::read(&cnt.packing) };
+        let config = unsafe { std::ptr::read(&cnt.config) };
         Controller {
             link: Box::new(link) as _,
             geometry,
             tx_buf,
             rx_buf,
+            events,
+            power,
+            rtt,
+            packing,
+            config,
         }
     }
 
@@ -289,11 +630,21 @@ impl<L: AsyncLink + 'static> Controller<L> {
         let geometry = unsafe { std::ptr::read(&cnt.geometry) };
         let tx_buf = unsafe { std::ptr::read(&cnt.tx_buf) };
         let rx_buf = unsafe { std::ptr::read(&cnt.rx_buf) };
+        let events = unsafe { std::ptr::read(&cnt.events) };
+        let power = unsafe { std::ptr::read(&cnt.power) };
+        let rtt = unsafe { std::ptr::read(&cnt.rtt) };
+        let packing = unsafe { std::ptr::read(&cnt.packing) };
+        let config = unsafe { std::ptr::read(&cnt.config) };
         Controller {
             link: unsafe { *Box::from_raw(Box::into_raw(link) as *mut L) },
             geometry,
             tx_buf,
             rx_buf,
+            events,
+            power,
+            rtt,
+            packing,
+            config,
         }
     }
 }
@@ -327,9 +678,11 @@ mod tests {
         autd3_device::AUTD3,
         datagram::{GainSTM, ReadsFPGAState},
         defined::Hz,
+        firmware::fpga::TransitionMode,
     };
 
     use crate::{
+        controller::LatencyModel,
         gain::Uniform,
         link::{Audit, AuditOption},
         modulation::Sine,
@@ -363,6 +716,38 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn preflight() -> anyhow::Result<()> {
+        let mut link = Audit::new(AuditOption::default());
+        Controller::preflight([AUTD3::default()], &mut link).await?;
+        assert!(!AsyncLink::is_open(&link));
+
+        link.break_down();
+        assert_eq!(
+            Err(AUTDError::from(LinkError::new("broken".to_owned()))),
+            Controller::preflight([AUTD3::default()], &mut link).await
+        );
+        assert_eq!(
+            Some(AUTDError::from(LinkError::new("broken".to_owned()))),
+            Controller::open([AUTD3::default()], link).await.err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn open_empty() {
+        assert_eq!(
+            Some(AUTDError::EmptyGeometry),
+            Controller::open(
+                std::iter::empty::<AUTD3>(),
+                Audit::new(AuditOption::default())
+            )
+            .await
+            .err()
+        );
+    }
+
     #[tokio::test]
     async fn send() -> anyhow::Result<()> {
         let mut autd = create_controller(1).await?;
@@ -425,6 +810,283 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn send_at() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+
+        let time = autd3_driver::ethercat::DcSysTime::now() + std::time::Duration::from_secs(1);
+        autd.sender(SenderOption::<AsyncSleeper>::default())
+            .send_at(
+                Static::default(),
+                Segment::S1,
+                autd3_driver::firmware::fpga::LoopBehavior::ONCE,
+                time,
+            )
+            .await?;
+        assert_eq!(Segment::S1, autd.link[0].fpga().req_modulation_segment());
+        assert_eq!(
+            autd3_driver::firmware::fpga::TransitionMode::SysTime(time),
+            autd.link[0].fpga().modulation_transition_mode()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_modulation() -> anyhow::Result<()> {
+        use autd3_driver::firmware::fpga::{LoopBehavior, MOD_BUF_SIZE_MAX};
+
+        let mut autd = create_controller(1).await?;
+
+        let buffer = (0..MOD_BUF_SIZE_MAX + 2)
+            .map(|i| (i % 256) as u8)
+            .collect::<Vec<_>>();
+        let m = crate::modulation::Custom {
+            buffer: buffer.clone(),
+            sampling_config: Static::default().sampling_config()?,
+        };
+        assert_eq!(
+            Err(AUTDDriverError::ModulationSizeOutOfRange(
+                MOD_BUF_SIZE_MAX + 2
+            )),
+            autd.sender(SenderOption::<AsyncSleeper>::default())
+                .send_modulation(m.clone())
+                .await
+        );
+
+        autd.sender(SenderOption::<AsyncSleeper> {
+            modulation_split: crate::controller::ModulationSplit::Split {
+                lead_time: std::time::Duration::from_millis(200),
+            },
+            ..Default::default()
+        })
+        .send_modulation(m)
+        .await?;
+        assert_eq!(
+            buffer[..MOD_BUF_SIZE_MAX / 2 + 1],
+            autd.link[0].fpga().modulation_buffer(Segment::S1)
+        );
+        assert_eq!(
+            buffer[MOD_BUF_SIZE_MAX / 2 + 1..],
+            autd.link[0].fpga().modulation_buffer(Segment::S0)
+        );
+        assert_eq!(
+            LoopBehavior::ONCE,
+            autd.link[0].fpga().modulation_loop_behavior(Segment::S0)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn render_point() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+
+        let pos = autd.center().unwrap() + autd3_driver::geometry::Vector3::new(0., 0., 150.);
+        autd.render_point(pos, HapticOptions::default()).await?;
+
+        assert_eq!(
+            *Sine {
+                freq: 200. * Hz,
+                option: Default::default(),
+            }
+            .into_nearest()
+            .calc()?,
+            autd.link[0].fpga().modulation_buffer(Segment::S0)
+        );
+        assert!(autd.link[0]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == EmitIntensity::MAX));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn restore() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+        autd.enable_snapshot()?;
+
+        autd.send(Sine {
+            freq: 150. * Hz,
+            option: Default::default(),
+        })
+        .await?;
+        let snapshot = autd.snapshot()?;
+
+        autd.send(autd3_driver::datagram::WithSegment {
+            inner: Static::default(),
+            segment: Segment::S1,
+            transition_mode: Some(TransitionMode::Immediate),
+        })
+        .await?;
+        assert_eq!(Segment::S1, autd.link[0].fpga().req_modulation_segment());
+
+        autd.restore(&snapshot).await?;
+        assert_eq!(snapshot, autd.snapshot()?);
+        assert_eq!(Segment::S0, autd.link[0].fpga().req_modulation_segment());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_sequence() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+
+        assert_eq!(
+            Err(AUTDDriverError::FociSTMPointSizeOutOfRange(1)),
+            autd.send_sequence(
+                Sequence::new()
+                    .push(
+                        Uniform {
+                            intensity: EmitIntensity(0x81),
+                            phase: Phase(0x02),
+                        },
+                        None,
+                    )
+                    .push(
+                        autd3_driver::datagram::FociSTM {
+                            foci: vec![autd3_driver::datagram::ControlPoints::<1>::default()],
+                            config: 1. * Hz,
+                        },
+                        None,
+                    )
+                    .push(Uniform::new(EmitIntensity(0x82), Phase(0x03)), None),
+            )
+            .await
+        );
+        assert!(autd.link[0]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == EmitIntensity(0x81) && d.phase == Phase(0x02)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_all() -> anyhow::Result<()> {
+        use autd3_driver::datagram::IntoBoxedDatagram;
+
+        let mut autd = create_controller(1).await?;
+
+        assert_eq!(
+            Err((1, AUTDDriverError::FociSTMPointSizeOutOfRange(1))),
+            autd.send_all([
+                Uniform::new(EmitIntensity(0x81), Phase(0x02)).into_boxed(),
+                autd3_driver::datagram::FociSTM {
+                    foci: vec![autd3_driver::datagram::ControlPoints::<1>::default()],
+                    config: 1. * Hz,
+                }
+                .into_boxed(),
+                Uniform::new(EmitIntensity(0x82), Phase(0x03)).into_boxed(),
+            ])
+            .await
+        );
+        assert!(autd.link[0]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == EmitIntensity(0x81) && d.phase == Phase(0x02)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn packing_report() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+        assert_eq!(None, autd.packing_report());
+
+        autd.sender(SenderOption::<AsyncSleeper> {
+            metrics: true,
+            ..Default::default()
+        })
+        .send(crate::modulation::Custom {
+            buffer: vec![0xFF; 4000],
+            sampling_config: autd3_driver::firmware::fpga::SamplingConfig::new(10)?,
+        })
+        .await?;
+        let report = autd.packing_report().unwrap();
+        assert!(report.passes > 1);
+        assert_eq!(report.passes, report.devices[0].frames);
+        assert!(report.devices[0].bytes > 4000);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_synchronized() -> anyhow::Result<()> {
+        let mut a = create_controller(1).await?;
+        let mut b = create_controller(1).await?;
+
+        let time = a
+            .send_synchronized(
+                Static::default(),
+                &mut b,
+                Static::default(),
+                Segment::S1,
+                LoopBehavior::ONCE,
+                SyncOption::default(),
+            )
+            .await
+            .map_err(|(_, e)| e)?;
+        [&a.link[0], &b.link[0]].iter().for_each(|cpu| {
+            assert_eq!(Segment::S1, cpu.fpga().req_modulation_segment());
+            assert_eq!(
+                TransitionMode::SysTime(time),
+                cpu.fpga().modulation_transition_mode()
+            );
+        });
+
+        assert_eq!(
+            Err((1, AUTDDriverError::MissTransitionTime)),
+            a.send_synchronized(
+                Static::default(),
+                &mut b,
+                Static::default(),
+                Segment::S0,
+                LoopBehavior::ONCE,
+                SyncOption {
+                    clock_offset: -100_000_000,
+                    ..Default::default()
+                },
+            )
+            .await
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn validate_transition() -> anyhow::Result<()> {
+        let mut autd = create_controller(2).await?;
+
+        autd.validate_transition(
+            Segment::S1,
+            LoopBehavior::ONCE,
+            Some(TransitionMode::SyncIdx),
+        )
+        .await?;
+        assert_eq!(
+            Err(AUTDError::Driver(
+                AUTDDriverError::IncompatibleTransitionMode(
+                    Segment::S1,
+                    LoopBehavior::Infinite,
+                    TransitionMode::SyncIdx
+                )
+            )),
+            autd.validate_transition(
+                Segment::S1,
+                LoopBehavior::Infinite,
+                Some(TransitionMode::SyncIdx)
+            )
+            .await
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn firmware_version() -> anyhow::Result<()> {
         use autd3_driver::firmware::version::{CPUVersion, FPGAVersion};
@@ -459,6 +1121,24 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dump_diagnostics() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+        autd.send(Static::default()).await?;
+
+        let report = autd.dump_diagnostics();
+        assert!(report.link_open);
+        assert!(matches!(
+            report.events.last().map(|e| &e.event),
+            Some(ControllerEvent::Send { datagram, error: None, .. }) if datagram.ends_with("Static")
+        ));
+
+        autd.set_event_log_capacity(0);
+        assert!(autd.dump_diagnostics().events.is_empty());
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn close() -> anyhow::Result<()> {
         {
@@ -470,8 +1150,14 @@ mod tests {
         {
             let mut autd = create_controller(1).await?;
             autd.link_mut().break_down();
+            let err = Err(AUTDDriverError::Link(LinkError::new("broken".to_owned())));
             assert_eq!(
-                Err(AUTDDriverError::Link(LinkError::new("broken".to_owned()))),
+                Err(AUTDError::CloseFailed(Box::new(CloseReport {
+                    silencer: err.clone(),
+                    null: err.clone(),
+                    clear: err,
+                    link_close: Ok(()),
+                }))),
                 autd.close().await
             );
         }
@@ -479,7 +1165,15 @@ mod tests {
         {
             let mut autd = create_controller(1).await?;
             autd.link_mut().down();
-            assert_eq!(Err(AUTDDriverError::SendDataFailed), autd.close().await);
+            assert_eq!(
+                Err(AUTDError::CloseFailed(Box::new(CloseReport {
+                    silencer: Err(AUTDDriverError::SendDataFailed),
+                    null: Err(AUTDDriverError::SendDataFailed),
+                    clear: Err(AUTDDriverError::SendDataFailed),
+                    link_close: Ok(()),
+                }))),
+                autd.close().await
+            );
         }
 
         {
@@ -631,4 +1325,94 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn add_remove_device() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+
+        autd.add_device(AUTD3 {
+            pos: Point3::new(0., 0., 100. * mm),
+            ..Default::default()
+        })
+        .await?;
+        assert_eq!(2, autd.len());
+        assert_eq!(2, autd.link().len());
+        autd.send(Null {}).await?;
+
+        let removed = autd.remove_device(0).await?;
+        assert_eq!(0, removed.idx());
+        assert_eq!(1, autd.link().len());
+        assert_eq!(0, autd[0].idx());
+        assert_eq!(Point3::new(0., 0., 100. * mm), *autd[0][0].position());
+        assert_eq!(
+            Err(AUTDError::EmptyGeometry),
+            autd.remove_device(0).await.map(|_| ())
+        );
+
+        autd.link_mut().break_down();
+        assert_eq!(
+            Err(AUTDError::from(LinkError::new("broken".to_owned()))),
+            autd.add_device(AUTD3::default()).await
+        );
+        assert_eq!(1, autd.len());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn update_poses() -> anyhow::Result<()> {
+        let mut autd = create_controller(2).await?;
+
+        let pos = Point3::new(10., 20., 30.);
+        let rot = UnitQuaternion::identity();
+        assert_eq!(
+            Err(AUTDError::DeviceIndexOutOfRange(2)),
+            autd.update_poses([(2, pos, rot)]).await
+        );
+
+        let version = autd.version();
+        autd.update_poses([(1, pos, rot)]).await?;
+        assert_eq!(version + 1, autd.version());
+        assert_eq!(pos, *autd[1][0].position());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn set_enabled() -> anyhow::Result<()> {
+        let mut autd = create_controller(2).await?;
+
+        assert_eq!(
+            Err(AUTDError::DeviceIndexOutOfRange(2)),
+            autd.set_enabled([2], false)
+        );
+
+        autd.set_enabled([0], false)?;
+        autd.send(Static { intensity: 0x80 }).await?;
+        assert_eq!(
+            vec![0xFF, 0xFF],
+            autd.link()[0].fpga().modulation_buffer(Segment::S0)
+        );
+        assert_eq!(
+            vec![0x80, 0x80],
+            autd.link()[1].fpga().modulation_buffer(Segment::S0)
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn estimate_latency() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+        let estimate = autd
+            .sender(SenderOption::<AsyncSleeper>::default())
+            .estimate_latency(Static { intensity: 0x80 }, &LatencyModel::default())?;
+        assert_eq!(1, estimate.frames);
+        assert_eq!(
+            vec![0xFF, 0xFF],
+            autd.link()[0].fpga().modulation_buffer(Segment::S0)
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,176 @@
+use std::{future::Future, pin::Pin, time::Instant};
+
+use autd3_core::link::AsyncLink;
+use autd3_driver::{
+    datagram::Datagram,
+    defined::Freq,
+    error::AUTDDriverError,
+    firmware::operation::{Operation, OperationGenerator},
+};
+
+use crate::{controller::RateSchedule, error::AUTDError};
+
+use super::Controller;
+
+type Pending<L> = Box<
+    dyn for<'a> FnOnce(
+        &'a mut Controller<L>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AUTDDriverError>> + 'a>>,
+>;
+
+/// Asynchronous version of [`crate::controller::RateLimiter`].
+pub struct RateLimiter<L: AsyncLink> {
+    schedule: RateSchedule<Pending<L>>,
+}
+
+impl<L: AsyncLink> std::fmt::Debug for RateLimiter<L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.schedule.fmt("RateLimiter", f)
+    }
+}
+
+impl<L: AsyncLink> RateLimiter<L> {
+    /// Please see [`crate::controller::RateLimiter::new`].
+    pub fn new(max_rate: Freq<f32>) -> Result<Self, AUTDError> {
+        Ok(Self {
+            schedule: RateSchedule::new(Some(max_rate))?,
+        })
+    }
+
+    /// Please see [`crate::controller::RateLimiter::unlimited`].
+    pub fn unlimited() -> Self {
+        Self {
+            schedule: RateSchedule::new(None).unwrap(),
+        }
+    }
+
+    /// Please see [`crate::controller::RateLimiter::set_max_rate`].
+    pub fn set_max_rate<D: 'static>(
+        &mut self,
+        max_rate: Option<Freq<f32>>,
+    ) -> Result<(), AUTDError> {
+        self.schedule.set_max_rate::<D>(max_rate)
+    }
+
+    /// Please see [`crate::controller::RateLimiter::num_pending`].
+    pub fn num_pending(&self) -> usize {
+        self.schedule.num_pending()
+    }
+
+    /// Please see [`crate::controller::RateLimiter::send`].
+    pub async fn send<D: Datagram + 'static>(
+        &mut self,
+        autd: &mut Controller<L>,
+        s: D,
+    ) -> Result<bool, AUTDDriverError>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        if self.schedule.acquire::<D>() {
+            autd.send(s).await?;
+            Ok(true)
+        } else {
+            self.schedule
+                .push::<D>(Box::new(move |autd| Box::pin(autd.send(s))));
+            Ok(false)
+        }
+    }
+
+    /// Please see [`crate::controller::RateLimiter::flush`].
+    pub async fn flush(&mut self, autd: &mut Controller<L>) -> Result<(), AUTDDriverError> {
+        let mut ready = self.schedule.take_ready();
+        while let Some((_, f)) = ready.next() {
+            if let Err(e) = f(autd).await {
+                self.schedule.restore(ready);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Please see [`crate::controller::RateLimiter::next_deadline`].
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.schedule.next_deadline()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use autd3_driver::{
+        datagram::{FixedCompletionSteps, Silencer},
+        defined::Hz,
+        firmware::fpga::{EmitIntensity, Phase, Segment},
+    };
+
+    use crate::{gain::Uniform, r#async::controller::tests::create_controller};
+
+    use super::*;
+
+    fn uniform(intensity: u8) -> Uniform {
+        Uniform {
+            intensity: EmitIntensity(intensity),
+            phase: Phase::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn coalesce() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+        let mut limiter = RateLimiter::new(10. * Hz)?;
+
+        assert!(limiter.send(&mut autd, uniform(0x01)).await?);
+        assert!(!limiter.send(&mut autd, uniform(0x02)).await?);
+        assert!(!limiter.send(&mut autd, uniform(0x03)).await?);
+        assert_eq!(1, limiter.num_pending());
+
+        tokio::time::sleep(
+            limiter
+                .next_deadline()
+                .unwrap()
+                .saturating_duration_since(Instant::now()),
+        )
+        .await;
+        limiter.flush(&mut autd).await?;
+        assert_eq!(0, limiter.num_pending());
+        assert_eq!(
+            EmitIntensity(0x03),
+            autd.link()[0].fpga().drives_at(Segment::S0, 0)[0].intensity
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn flush_failed() -> anyhow::Result<()> {
+        let mut autd = create_controller(1).await?;
+        let mut limiter = RateLimiter::new(100. * Hz)?;
+
+        assert!(limiter.send(&mut autd, uniform(0x01)).await?);
+        assert!(
+            limiter
+                .send(&mut autd, Silencer::<FixedCompletionSteps>::default())
+                .await?
+        );
+        assert!(!limiter.send(&mut autd, uniform(0x02)).await?);
+        assert!(
+            !limiter
+                .send(&mut autd, Silencer::<FixedCompletionSteps>::default())
+                .await?
+        );
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        autd.link_mut().down();
+        assert_eq!(
+            Err(AUTDDriverError::SendDataFailed),
+            limiter.flush(&mut autd).await
+        );
+        assert_eq!(1, limiter.num_pending());
+
+        Ok(())
+    }
+}
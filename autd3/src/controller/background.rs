@@ -0,0 +1,247 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{mpsc, Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread::JoinHandle,
+};
+
+use autd3_core::link::Link;
+use autd3_driver::{
+    datagram::Datagram,
+    error::AUTDDriverError,
+    firmware::operation::{Operation, OperationGenerator},
+};
+
+use super::Controller;
+
+pub(super) type Job<L> = Box<dyn FnOnce(&mut Controller<L>) + Send>;
+
+#[derive(Default)]
+struct State {
+    result: Option<Result<(), AUTDDriverError>>,
+    waker: Option<Waker>,
+}
+
+struct Completer {
+    state: Arc<Mutex<State>>,
+}
+
+impl Completer {
+    fn complete(self, result: Result<(), AUTDDriverError>) {
+        let mut state = self.state.lock().unwrap();
+        state.result = Some(result);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Drop for Completer {
+    fn drop(&mut self) {
+        let mut state = self.state.lock().unwrap();
+        if state.result.is_none() {
+            state.result = Some(Err(AUTDDriverError::LinkClosed));
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// A [`Future`] that is resolved when the enqueued [`Datagram`] has been sent by [`BackgroundSender`].
+///
+/// If the [`BackgroundSender`] is stopped before the [`Datagram`] is sent, the future is resolved with [`AUTDDriverError::LinkClosed`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct SendFuture {
+    state: Arc<Mutex<State>>,
+}
+
+impl Future for SendFuture {
+    type Output = Result<(), AUTDDriverError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+pub(super) fn job<L: Link, F>(f: F) -> (Job<L>, SendFuture)
+where
+    F: FnOnce(&mut Controller<L>) -> Result<(), AUTDDriverError> + Send + 'static,
+{
+    let state = Arc::new(Mutex::new(State::default()));
+    let completer = Completer {
+        state: state.clone(),
+    };
+    (
+        Box::new(move |autd: &mut Controller<L>| completer.complete(f(autd))),
+        SendFuture { state },
+    )
+}
+
+pub(super) fn send_job<L: Link, D: Datagram + Send + 'static>(s: D) -> (Job<L>, SendFuture)
+where
+    AUTDDriverError: From<D::Error>,
+    D::G: OperationGenerator,
+    AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+        + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+{
+    job(move |autd: &mut Controller<L>| autd.send(s))
+}
+
+/// A sender that owns the [`Controller`] in a background thread.
+///
+/// [`BackgroundSender::enqueue`] does not block the caller. The enqueued [`Datagram`]s are sent in order by the background thread.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// let sender = BackgroundSender::new(autd);
+/// let future = sender.enqueue(Static::default());
+/// // do something else
+/// # let _ = future;
+/// let autd = sender.into_controller();
+/// autd.close()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct BackgroundSender<L: Link + Send + 'static> {
+    tx: Option<mpsc::Sender<Job<L>>>,
+    handle: Option<JoinHandle<Controller<L>>>,
+}
+
+impl<L: Link + Send + 'static> BackgroundSender<L> {
+    /// Creates a new [`BackgroundSender`] and spawns the background thread.
+    pub fn new(mut autd: Controller<L>) -> Self {
+        let (tx, rx) = mpsc::channel::<Job<L>>();
+        let handle = std::thread::spawn(move || {
+            rx.into_iter().for_each(|job| job(&mut autd));
+            autd
+        });
+        Self {
+            tx: Some(tx),
+            handle: Some(handle),
+        }
+    }
+
+    /// Enqueues the [`Datagram`] to be sent by the background thread.
+    ///
+    /// The returned [`SendFuture`] is resolved with the result of [`Controller::send`]. Dropping the [`SendFuture`] does not cancel the transmission.
+    pub fn enqueue<D: Datagram + Send + 'static>(&self, s: D) -> SendFuture
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let (job, future) = send_job(s);
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(job);
+        }
+        future
+    }
+
+    /// Stops the background thread after all enqueued [`Datagram`]s are sent, and returns the [`Controller`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the background thread panicked.
+    pub fn into_controller(mut self) -> Controller<L> {
+        self.tx.take();
+        match self.handle.take().unwrap().join() {
+            Ok(autd) => autd,
+            Err(e) => std::panic::resume_unwind(e),
+        }
+    }
+}
+
+impl<L: Link + Send + 'static> Drop for BackgroundSender<L> {
+    fn drop(&mut self) {
+        self.tx.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::firmware::fpga::{Drive, EmitIntensity, Phase, Segment};
+
+    use crate::{controller::tests::create_controller, gain::Uniform};
+
+    use super::*;
+
+    fn uniform(intensity: u8) -> Uniform {
+        Uniform {
+            intensity: EmitIntensity(intensity),
+            phase: Phase::ZERO,
+        }
+    }
+
+    #[test]
+    fn enqueue() -> anyhow::Result<()> {
+        let autd = create_controller(1)?;
+
+        let sender = BackgroundSender::new(autd);
+        let futures = (0..10)
+            .map(|i| sender.enqueue(uniform(i)))
+            .collect::<Vec<_>>();
+        futures.into_iter().try_for_each(tokio_test::block_on)?;
+
+        let autd = sender.into_controller();
+        assert_eq!(
+            vec![
+                Drive {
+                    intensity: EmitIntensity(9),
+                    phase: Phase::ZERO,
+                };
+                autd[0].num_transducers()
+            ],
+            autd.link()[0].fpga().drives_at(Segment::S0, 0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn enqueue_failed() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        autd.link_mut().break_down();
+
+        let sender = BackgroundSender::new(autd);
+        assert!(matches!(
+            tokio_test::block_on(sender.enqueue(uniform(0))),
+            Err(AUTDDriverError::Link(_))
+        ));
+
+        let mut autd = sender.into_controller();
+        autd.link_mut().repair();
+
+        Ok(())
+    }
+
+    #[test]
+    fn completer_dropped() {
+        let state = Arc::new(Mutex::new(State::default()));
+        drop(Completer {
+            state: state.clone(),
+        });
+        assert_eq!(
+            Err(AUTDDriverError::LinkClosed),
+            tokio_test::block_on(SendFuture { state })
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use std::fmt::Display;
+
+use autd3_driver::error::AUTDDriverError;
+
+/// A stage of closing the controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseStage {
+    /// Restoring the [`Silencer`] to stop the output smoothly.
+    ///
+    /// [`Silencer`]: autd3_driver::datagram::Silencer
+    Silencer,
+    /// Sending [`Null`] gain and [`Static`] modulation.
+    ///
+    /// [`Null`]: crate::gain::Null
+    /// [`Static`]: crate::modulation::Static
+    Null,
+    /// Sending [`Clear`].
+    ///
+    /// [`Clear`]: autd3_driver::datagram::Clear
+    Clear,
+    /// Closing the link.
+    LinkClose,
+}
+
+impl Display for CloseStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloseStage::Silencer => write!(f, "silencer restore"),
+            CloseStage::Null => write!(f, "null gain"),
+            CloseStage::Clear => write!(f, "clear"),
+            CloseStage::LinkClose => write!(f, "link close"),
+        }
+    }
+}
+
+/// The results of each stage of closing the controller.
+///
+/// All stages are run even if some of them fail, and this is returned in [`AUTDError::CloseFailed`] if any stage fails.
+///
+/// [`AUTDError::CloseFailed`]: crate::error::AUTDError::CloseFailed
+#[derive(Debug, Clone, PartialEq)]
+pub struct CloseReport {
+    /// The result of [`CloseStage::Silencer`].
+    pub silencer: Result<(), AUTDDriverError>,
+    /// The result of [`CloseStage::Null`].
+    pub null: Result<(), AUTDDriverError>,
+    /// The result of [`CloseStage::Clear`].
+    pub clear: Result<(), AUTDDriverError>,
+    /// The result of [`CloseStage::LinkClose`].
+    pub link_close: Result<(), AUTDDriverError>,
+}
+
+impl CloseReport {
+    /// Returns `true` if all stages succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Returns the failed stages and their errors in the order of execution.
+    pub fn failures(&self) -> impl Iterator<Item = (CloseStage, &AUTDDriverError)> {
+        [
+            (CloseStage::Silencer, &self.silencer),
+            (CloseStage::Null, &self.null),
+            (CloseStage::Clear, &self.clear),
+            (CloseStage::LinkClose, &self.link_close),
+        ]
+        .into_iter()
+        .filter_map(|(stage, res)| res.as_ref().err().map(|e| (stage, e)))
+    }
+}
+
+impl Display for CloseReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.failures()
+                .map(|(stage, e)| format!("{}: {}", stage, e))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report() {
+        let report = CloseReport {
+            silencer: Ok(()),
+            null: Err(AUTDDriverError::SendDataFailed),
+            clear: Ok(()),
+            link_close: Err(AUTDDriverError::LinkClosed),
+        };
+        assert!(!report.is_ok());
+        assert_eq!(
+            vec![
+                (CloseStage::Null, &AUTDDriverError::SendDataFailed),
+                (CloseStage::LinkClose, &AUTDDriverError::LinkClosed)
+            ],
+            report.failures().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            format!(
+                "null gain: {}, link close: {}",
+                AUTDDriverError::SendDataFailed,
+                AUTDDriverError::LinkClosed
+            ),
+            report.to_string()
+        );
+    }
+}
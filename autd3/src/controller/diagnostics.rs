@@ -0,0 +1,182 @@
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use autd3_driver::geometry::Geometry;
+
+/// The default capacity of the event log of the controller.
+pub const DEFAULT_EVENT_LOG_CAPACITY: usize = 256;
+
+/// An event of the controller recorded for diagnostics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ControllerEvent {
+    /// The controller is opened.
+    Opened {
+        /// The number of devices.
+        num_devices: usize,
+    },
+    /// A [`Datagram`] is sent.
+    ///
+    /// [`Datagram`]: autd3_driver::datagram::Datagram
+    Send {
+        /// The type name of the [`Datagram`].
+        ///
+        /// [`Datagram`]: autd3_driver::datagram::Datagram
+        datagram: &'static str,
+        /// The time taken to send the [`Datagram`].
+        ///
+        /// [`Datagram`]: autd3_driver::datagram::Datagram
+        elapsed: Duration,
+        /// The error message if sending failed.
+        error: Option<String>,
+    },
+    /// The devices are added or removed.
+    DevicesChanged {
+        /// The number of devices after the change.
+        num_devices: usize,
+    },
+    /// The controller is closed.
+    Closed {
+        /// The error message if closing failed.
+        error: Option<String>,
+    },
+}
+
+/// A [`ControllerEvent`] with the time when it is recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EventRecord {
+    /// The elapsed time since the controller is created.
+    pub timestamp: Duration,
+    /// The event.
+    pub event: ControllerEvent,
+}
+
+/// A diagnostics report of the controller returned by [`Controller::dump_diagnostics`].
+///
+/// If the `serde` feature is enabled, this implements `serde::Serialize` so that it can be attached to bug reports as a single file.
+///
+/// [`Controller::dump_diagnostics`]: crate::controller::Controller::dump_diagnostics
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DiagnosticsReport {
+    /// The version of this library.
+    pub version: &'static str,
+    /// The number of devices.
+    pub num_devices: usize,
+    /// The number of enabled devices.
+    pub num_enabled_devices: usize,
+    /// Whether the link is open.
+    pub link_open: bool,
+    /// The number of events discarded because the event log is full.
+    pub dropped_events: usize,
+    /// The recent events in chronological order.
+    pub events: Vec<EventRecord>,
+}
+
+/// A ring buffer of the recent [`ControllerEvent`]s.
+#[derive(Debug, Clone)]
+pub(crate) struct EventLog {
+    capacity: usize,
+    start: Instant,
+    events: VecDeque<EventRecord>,
+    dropped: usize,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_EVENT_LOG_CAPACITY,
+            start: Instant::now(),
+            events: VecDeque::with_capacity(DEFAULT_EVENT_LOG_CAPACITY),
+            dropped: 0,
+        }
+    }
+}
+
+impl EventLog {
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.events.len() > capacity {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+    }
+
+    pub(crate) fn push(&mut self, event: ControllerEvent) {
+        if self.capacity == 0 {
+            self.dropped += 1;
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(EventRecord {
+            timestamp: self.start.elapsed(),
+            event,
+        });
+    }
+
+    pub(crate) fn push_send<T, E: Display>(
+        &mut self,
+        datagram: &'static str,
+        start: Instant,
+        res: &Result<T, E>,
+    ) {
+        self.push(ControllerEvent::Send {
+            datagram,
+            elapsed: start.elapsed(),
+            error: res.as_ref().err().map(ToString::to_string),
+        });
+    }
+
+    pub(crate) fn report(&self, geometry: &Geometry, link_open: bool) -> DiagnosticsReport {
+        DiagnosticsReport {
+            version: env!("CARGO_PKG_VERSION"),
+            num_devices: geometry.len(),
+            num_enabled_devices: geometry.num_devices(),
+            link_open,
+            dropped_events: self.dropped,
+            events: self.events.iter().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer() {
+        let mut log = EventLog::default();
+        log.set_capacity(2);
+        (0..3).for_each(|num_devices| log.push(ControllerEvent::Opened { num_devices }));
+
+        let report = log.report(&crate::tests::create_geometry(1), true);
+        assert_eq!(1, report.dropped_events);
+        assert_eq!(
+            vec![
+                ControllerEvent::Opened { num_devices: 1 },
+                ControllerEvent::Opened { num_devices: 2 }
+            ],
+            report
+                .events
+                .into_iter()
+                .map(|e| e.event)
+                .collect::<Vec<_>>()
+        );
+
+        log.set_capacity(1);
+        assert_eq!(1, log.events.len());
+        assert_eq!(2, log.dropped);
+
+        log.set_capacity(0);
+        log.push(ControllerEvent::Closed { error: None });
+        assert!(log.events.is_empty());
+        assert_eq!(4, log.dropped);
+    }
+}
@@ -0,0 +1,155 @@
+use autd3_core::link::Link;
+use autd3_driver::{
+    datagram::DebugSettings,
+    firmware::{
+        fpga::{DebugType, GPIOOut},
+        version::FPGAVersion,
+    },
+    geometry::Device,
+};
+
+use crate::error::AUTDError;
+
+use super::Controller;
+
+/// The output of each GPIO Out pin of a device configured by [`Controller::configure_gpio`].
+///
+/// All pins output nothing ([`DebugType::None`]) by default.
+#[derive(Clone, Debug)]
+pub struct GPIOPlan<'a> {
+    /// The output of [`GPIOOut::O0`].
+    pub gpio0: DebugType<'a>,
+    /// The output of [`GPIOOut::O1`].
+    pub gpio1: DebugType<'a>,
+    /// The output of [`GPIOOut::O2`].
+    pub gpio2: DebugType<'a>,
+    /// The output of [`GPIOOut::O3`].
+    pub gpio3: DebugType<'a>,
+}
+
+impl Default for GPIOPlan<'_> {
+    fn default() -> Self {
+        Self {
+            gpio0: DebugType::None,
+            gpio1: DebugType::None,
+            gpio2: DebugType::None,
+            gpio3: DebugType::None,
+        }
+    }
+}
+
+impl<'a> GPIOPlan<'a> {
+    fn get(&self, gpio: GPIOOut) -> DebugType<'a> {
+        match gpio {
+            GPIOOut::O0 => self.gpio0.clone(),
+            GPIOOut::O1 => self.gpio1.clone(),
+            GPIOOut::O2 => self.gpio2.clone(),
+            GPIOOut::O3 => self.gpio3.clone(),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &DebugType<'a>> {
+        [&self.gpio0, &self.gpio1, &self.gpio2, &self.gpio3].into_iter()
+    }
+
+    pub(crate) fn validate(&self, dev: &Device, fpga: &FPGAVersion) -> Result<(), AUTDError> {
+        if !fpga.caps().supports_gpio_output() {
+            return Err(AUTDError::UnsupportedFirmware(dev.idx(), fpga.to_string()));
+        }
+        self.iter().try_for_each(|ty| match ty {
+            DebugType::PwmOut(tr) if tr.dev_idx() != dev.idx() => {
+                Err(AUTDError::InvalidGPIOOutput(dev.idx(), format!("{:?}", ty)))
+            }
+            _ => Ok(()),
+        })
+    }
+}
+
+impl<L: Link> Controller<L> {
+    /// Configures the outputs of the GPIO Out pins of each device.
+    ///
+    /// This reads the firmware version of the devices first and returns [`AUTDError::UnsupportedFirmware`] if the FPGA firmware of any device does not support the outputs.
+    /// If [`DebugType::PwmOut`] specifies a transducer of another device, [`AUTDError::InvalidGPIOOutput`] is returned. Nothing is sent if the validation fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use autd3::prelude::*;
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+    ///
+    /// autd.configure_gpio(|dev| GPIOPlan {
+    ///     gpio0: DebugType::BaseSignal,
+    ///     gpio1: DebugType::Sync,
+    ///     gpio2: DebugType::PwmOut(&dev[0]),
+    ///     ..Default::default()
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn configure_gpio<F>(&mut self, f: F) -> Result<(), AUTDError>
+    where
+        F: for<'a> Fn(&'a Device) -> GPIOPlan<'a> + Send + Sync,
+    {
+        self.firmware_version()?
+            .iter()
+            .try_for_each(|v| f(&self[v.idx]).validate(&self[v.idx], &v.fpga))?;
+        self.send(DebugSettings::new(move |dev, gpio| f(dev).get(gpio)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::firmware::version::{FirmwareVersion, Major, Minor};
+
+    use crate::controller::tests::create_controller;
+
+    use super::*;
+
+    #[test]
+    fn configure_gpio() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+
+        autd.configure_gpio(|dev| GPIOPlan {
+            gpio0: DebugType::BaseSignal,
+            gpio1: DebugType::Sync,
+            gpio2: DebugType::PwmOut(&dev[1]),
+            gpio3: DebugType::Direct(true),
+        })?;
+
+        autd.iter().for_each(|dev| {
+            assert_eq!(
+                [0x01, 0x10, 0xE0, 0xF0],
+                autd.link()[dev.idx()].fpga().debug_types()
+            );
+            assert_eq!([0, 0, 1, 1], autd.link()[dev.idx()].fpga().debug_values());
+        });
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(Ok(()), 0, FirmwareVersion::LATEST_VERSION_NUM_MAJOR)]
+    #[case(Err(AUTDError::InvalidGPIOOutput(0, "PwmOut(0)".to_string())), 1, FirmwareVersion::LATEST_VERSION_NUM_MAJOR)]
+    #[case(Err(AUTDError::UnsupportedFirmware(0, "v9.1.1".to_string())), 0, Major(0xA1))]
+    #[test]
+    fn validate(
+        #[case] expect: Result<(), AUTDError>,
+        #[case] tr_dev: usize,
+        #[case] major: Major,
+    ) -> anyhow::Result<()> {
+        let autd = create_controller(2)?;
+        let fpga = FPGAVersion {
+            major,
+            minor: Minor(1),
+            function_bits: 0,
+        };
+        let plan = GPIOPlan {
+            gpio0: DebugType::PwmOut(&autd[tr_dev][0]),
+            ..Default::default()
+        };
+        assert_eq!(expect, plan.validate(&autd[0], &fpga));
+        Ok(())
+    }
+}
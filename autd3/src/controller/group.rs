@@ -1,10 +1,18 @@
-use std::{collections::HashMap, fmt::Debug, hash::Hash, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+    hash::Hash,
+    time::{Duration, Instant},
+};
 
-use autd3_core::{derive::DatagramOption, link::Link};
+use autd3_core::{derive::DatagramOption, geometry::Geometry, link::Link};
 use autd3_driver::{
     datagram::Datagram,
     error::AUTDDriverError,
-    firmware::operation::{Operation, OperationGenerator},
+    firmware::{
+        cpu::{check_if_msg_is_processed, RxMessage, TxMessage},
+        operation::{Operation, OperationGenerator},
+    },
     geometry::Device,
 };
 use bit_vec::BitVec;
@@ -14,10 +22,241 @@ use spin_sleep::SpinSleeper;
 use crate::error::AUTDError;
 
 use super::{
-    sender::{Sender, SenderOption},
+    sender::{ParallelMode, Sender, SenderOption},
     Controller, Sleep,
 };
 
+/// The result of a group of [`Sender::group_send_with_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupResult {
+    /// The indices of the devices in the group.
+    pub devices: Vec<usize>,
+    /// The indices of the devices in the group which failed.
+    pub failed: Vec<usize>,
+    /// The result of the group. If the error is caused by some devices, only the errors of the devices in the group are included.
+    pub result: Result<(), AUTDDriverError>,
+}
+
+/// The results of each group of [`Sender::group_send_with_report`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupSendReport<K: Hash + Eq> {
+    /// The result of each group.
+    pub groups: HashMap<K, GroupResult>,
+}
+
+impl<K: Hash + Eq> GroupSendReport<K> {
+    /// Returns `true` if all groups succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Returns the failed groups.
+    pub fn failures(&self) -> impl Iterator<Item = (&K, &GroupResult)> {
+        self.groups.iter().filter(|(_, res)| res.result.is_err())
+    }
+
+    /// Returns the indices of the failed devices in ascending order.
+    pub fn failed_devices(&self) -> Vec<usize> {
+        self.groups
+            .values()
+            .flat_map(|res| res.failed.iter().copied())
+            .sorted()
+            .collect()
+    }
+
+    pub(crate) fn new(
+        filters: HashMap<K, BitVec>,
+        res: Result<(), AUTDDriverError>,
+        geometry: &Geometry,
+        tx: &[TxMessage],
+        rx: &[RxMessage],
+    ) -> Self {
+        let processed = check_if_msg_is_processed(tx, rx).collect::<Vec<_>>();
+        Self {
+            groups: filters
+                .into_iter()
+                .map(|(k, filter)| {
+                    let devices = geometry
+                        .devices()
+                        .map(|dev| dev.idx())
+                        .filter(|&idx| filter[idx])
+                        .collect::<Vec<_>>();
+                    let (failed, result) = match &res {
+                        Ok(()) => (Vec::new(), Ok(())),
+                        Err(AUTDDriverError::Device(errors)) => {
+                            let errors = errors
+                                .iter()
+                                .filter(|e| filter.get(e.dev_idx).unwrap_or(false))
+                                .cloned()
+                                .collect::<Vec<_>>();
+                            if errors.is_empty() {
+                                (Vec::new(), Ok(()))
+                            } else {
+                                (
+                                    errors.iter().map(|e| e.dev_idx).collect(),
+                                    Err(AUTDDriverError::Device(errors)),
+                                )
+                            }
+                        }
+                        Err(AUTDDriverError::ConfirmResponseFailed) => {
+                            let failed = devices
+                                .iter()
+                                .copied()
+                                .filter(|&idx| !processed[idx])
+                                .collect::<Vec<_>>();
+                            if failed.is_empty() {
+                                (failed, Ok(()))
+                            } else {
+                                (failed, Err(AUTDDriverError::ConfirmResponseFailed))
+                            }
+                        }
+                        Err(e) => (devices.clone(), Err(e.clone())),
+                    };
+                    (
+                        k,
+                        GroupResult {
+                            devices,
+                            failed,
+                            result,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<K: Hash + Eq + Debug> Display for GroupSendReport<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            self.failures()
+                .filter_map(|(k, res)| res.result.as_ref().err().map(|e| format!(
+                    "{:?}: {} (devices: {})",
+                    k,
+                    e,
+                    res.failed.iter().join(", ")
+                )))
+                .join(", ")
+        )
+    }
+}
+
+pub(crate) struct GroupOperations<K, O1, O2> {
+    pub(crate) operations: Vec<Option<(O1, O2)>>,
+    pub(crate) filters: HashMap<K, BitVec>,
+    pub(crate) timeout: Duration,
+    pub(crate) parallel: bool,
+}
+
+#[allow(clippy::type_complexity)]
+pub(crate) fn group_operations<K, D, F>(
+    geometry: &mut Geometry,
+    parallel_mode: ParallelMode,
+    timeout: Option<Duration>,
+    key_map: F,
+    datagram_map: HashMap<K, D>,
+) -> Result<
+    GroupOperations<K, <D::G as OperationGenerator>::O1, <D::G as OperationGenerator>::O2>,
+    AUTDError,
+>
+where
+    K: Hash + Eq + Debug,
+    D: Datagram,
+    F: Fn(&Device) -> Option<K>,
+    AUTDDriverError: From<D::Error>,
+    D::G: OperationGenerator,
+{
+    let mut datagram_map = datagram_map;
+
+    let filters = {
+        let num_devices = geometry.iter().len();
+        let mut filters: HashMap<K, BitVec> = HashMap::new();
+        geometry.devices().for_each(|dev| {
+            if let Some(key) = key_map(dev) {
+                if let Some(v) = filters.get_mut(&key) {
+                    v.set(dev.idx(), true);
+                } else {
+                    filters.insert(key, BitVec::from_fn(num_devices, |i| i == dev.idx()));
+                }
+            }
+        });
+        filters
+    };
+
+    let enable_store = geometry.iter().map(|dev| dev.enable).collect::<Vec<_>>();
+
+    let mut operations: Vec<_> = geometry.devices().map(|_| None).collect();
+    let mut datagram_option = DatagramOption {
+        timeout: Duration::ZERO,
+        parallel_threshold: usize::MAX,
+    };
+    filters
+        .iter()
+        .try_for_each(|(k, filter)| -> Result<(), AUTDError> {
+            {
+                let datagram = datagram_map
+                    .remove(k)
+                    .ok_or(AUTDError::UnkownKey(format!("{:?}", k)))?;
+                datagram_option = DatagramOption {
+                    timeout: datagram_option.timeout.max(datagram.option().timeout),
+                    parallel_threshold: datagram_option
+                        .parallel_threshold
+                        .min(datagram.option().parallel_threshold),
+                };
+
+                // set enable flag for each device
+                // This is not required for the operation except `Gain`s which cannot be calculated independently for each device, such as `autd3-gain-holo`.
+                geometry.devices_mut().for_each(|dev| {
+                    dev.enable = filter[dev.idx()];
+                });
+
+                let parallel = parallel_mode
+                    .is_parallel(geometry.num_devices(), datagram.option().parallel_threshold);
+                let generator = datagram.operation_generator(geometry, parallel);
+
+                // restore enable flag
+                geometry
+                    .iter_mut()
+                    .zip(enable_store.iter())
+                    .for_each(|(dev, &enable)| {
+                        dev.enable = enable;
+                    });
+
+                let mut generator = generator.map_err(AUTDDriverError::from)?;
+
+                operations
+                    .iter_mut()
+                    .zip(geometry.devices())
+                    .filter(|(_, dev)| filter[dev.idx()])
+                    .for_each(|(op, dev)| {
+                        tracing::debug!("Generate operation for device {}", dev.idx());
+                        let (op1, op2) = generator.generate(dev);
+                        *op = Some((op1, op2));
+                    });
+                Ok(())
+            }
+        })?;
+
+    if !datagram_map.is_empty() {
+        return Err(AUTDError::UnusedKey(
+            datagram_map.keys().map(|k| format!("{:?}", k)).join(", "),
+        ));
+    }
+
+    let timeout = timeout.unwrap_or(datagram_option.timeout);
+    let parallel =
+        parallel_mode.is_parallel(geometry.num_devices(), datagram_option.parallel_threshold);
+    tracing::debug!("timeout: {:?}, parallel: {:?}", timeout, parallel);
+    Ok(GroupOperations {
+        operations,
+        filters,
+        timeout,
+        parallel,
+    })
+}
+
 impl<L: Link> Controller<L> {
     /// Groups the devices by given function and send different data to each group. This is a shortcut for [`Sender::group_send`].
     pub fn group_send<K, D, F>(
@@ -37,6 +276,25 @@ impl<L: Link> Controller<L> {
         self.sender(SenderOption::<SpinSleeper>::default())
             .group_send(key_map, datagram_map)
     }
+
+    /// Groups the devices by given function and send different data to each group, and returns the result of each group. This is a shortcut for [`Sender::group_send_with_report`].
+    pub fn group_send_with_report<K, D, F>(
+        &mut self,
+        key_map: F,
+        datagram_map: HashMap<K, D>,
+    ) -> Result<GroupSendReport<K>, AUTDError>
+    where
+        K: Hash + Eq + Debug,
+        D: Datagram,
+        F: Fn(&Device) -> Option<K>,
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        self.sender(SenderOption::<SpinSleeper>::default())
+            .group_send_with_report(key_map, datagram_map)
+    }
 }
 
 impl<L: Link, S: Sleep> Sender<'_, L, S> {
@@ -90,95 +348,77 @@ impl<L: Link, S: Sleep> Sender<'_, L, S> {
         AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
             + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
     {
-        let mut datagram_map = datagram_map;
-
-        let filters = {
-            let num_devices = self.geometry.iter().len();
-            let mut filters: HashMap<K, BitVec> = HashMap::new();
-            self.geometry.devices().for_each(|dev| {
-                if let Some(key) = key_map(dev) {
-                    if let Some(v) = filters.get_mut(&key) {
-                        v.set(dev.idx(), true);
-                    } else {
-                        filters.insert(key, BitVec::from_fn(num_devices, |i| i == dev.idx()));
-                    }
-                }
-            });
-            filters
-        };
-
-        let enable_store = self
-            .geometry
-            .iter()
-            .map(|dev| dev.enable)
-            .collect::<Vec<_>>();
-
-        let mut operations: Vec<_> = self.geometry.devices().map(|_| None).collect();
-        let mut datagram_option = DatagramOption {
-            timeout: Duration::ZERO,
-            parallel_threshold: usize::MAX,
-        };
-        filters
-            .into_iter()
-            .try_for_each(|(k, filter)| -> Result<(), AUTDError> {
-                {
-                    // set enable flag for each device
-                    // This is not required for the operation except `Gain`s which cannot be calculated independently for each device, such as `autd3-gain-holo`.
-                    self.geometry.devices_mut().for_each(|dev| {
-                        dev.enable = filter[dev.idx()];
-                    });
-
-                    let datagram = datagram_map
-                        .remove(&k)
-                        .ok_or(AUTDError::UnkownKey(format!("{:?}", k)))?;
-                    datagram_option = DatagramOption {
-                        timeout: datagram_option.timeout.max(datagram.option().timeout),
-                        parallel_threshold: datagram_option
-                            .parallel_threshold
-                            .min(datagram.option().parallel_threshold),
-                    };
-                    let parallel = self.option.parallel.is_parallel(
-                        self.geometry.num_devices(),
-                        datagram.option().parallel_threshold,
-                    );
-                    let mut generator = datagram
-                        .operation_generator(self.geometry, parallel)
-                        .map_err(AUTDDriverError::from)?;
-
-                    // restore enable flag
-                    self.geometry
-                        .iter_mut()
-                        .zip(enable_store.iter())
-                        .for_each(|(dev, &enable)| {
-                            dev.enable = enable;
-                        });
-
-                    operations
-                        .iter_mut()
-                        .zip(self.geometry.devices())
-                        .filter(|(_, dev)| filter[dev.idx()])
-                        .for_each(|(op, dev)| {
-                            tracing::debug!("Generate operation for device {}", dev.idx());
-                            let (op1, op2) = generator.generate(dev);
-                            *op = Some((op1, op2));
-                        });
-                    Ok(())
-                }
-            })?;
+        let start = Instant::now();
+        let res = self.group_send_impl(key_map, datagram_map);
+        self.events
+            .push_send(std::any::type_name::<D>(), start, &res);
+        res
+    }
 
-        if !datagram_map.is_empty() {
-            return Err(AUTDError::UnusedKey(
-                datagram_map.keys().map(|k| format!("{:?}", k)).join(", "),
-            ));
+    /// Same as [`Sender::group_send`], but returns the result of each group.
+    ///
+    /// The keys are validated and the operations are generated before sending, and the errors in this step are returned as `Err`.
+    /// The errors in sending are returned in [`GroupSendReport`] with the devices which failed, so that the groups not affected by the error can be distinguished.
+    pub fn group_send_with_report<K, D, F>(
+        &mut self,
+        key_map: F,
+        datagram_map: HashMap<K, D>,
+    ) -> Result<GroupSendReport<K>, AUTDError>
+    where
+        K: Hash + Eq + Debug,
+        D: Datagram,
+        F: Fn(&Device) -> Option<K>,
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let start = Instant::now();
+        let res = group_operations(
+            self.geometry,
+            self.option.parallel,
+            self.option.timeout,
+            key_map,
+            datagram_map,
+        )
+        .map(|ops| {
+            let res = self.send_impl(ops.operations, ops.timeout, ops.parallel);
+            GroupSendReport::new(ops.filters, res, self.geometry, self.tx, self.rx)
+        });
+        match &res {
+            Ok(report) if !report.is_ok() => {
+                self.events
+                    .push_send(std::any::type_name::<D>(), start, &Err::<(), _>(report))
+            }
+            _ => self
+                .events
+                .push_send(std::any::type_name::<D>(), start, &res),
         }
+        res
+    }
 
-        let timeout = self.option.timeout.unwrap_or(datagram_option.timeout);
-        let parallel = self.option.parallel.is_parallel(
-            self.geometry.num_devices(),
-            datagram_option.parallel_threshold,
-        );
-        tracing::debug!("timeout: {:?}, parallel: {:?}", timeout, parallel);
-        Ok(self.send_impl(operations, timeout, parallel)?)
+    fn group_send_impl<K, D, F>(
+        &mut self,
+        key_map: F,
+        datagram_map: HashMap<K, D>,
+    ) -> Result<(), AUTDError>
+    where
+        K: Hash + Eq + Debug,
+        D: Datagram,
+        F: Fn(&Device) -> Option<K>,
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let ops = group_operations(
+            self.geometry,
+            self.option.parallel,
+            self.option.timeout,
+            key_map,
+            datagram_map,
+        )?;
+        Ok(self.send_impl(ops.operations, ops.timeout, ops.parallel)?)
     }
 }
 
@@ -186,11 +426,15 @@ impl<L: Link, S: Sleep> Sender<'_, L, S> {
 mod tests {
     use std::sync::Mutex;
 
+    #[cfg(feature = "stm")]
+    use crate::controller::ParallelMode;
     use autd3_core::derive::*;
+    #[cfg(feature = "stm")]
+    use autd3_driver::datagram::GainSTM;
     use autd3_driver::{
-        datagram::{GainSTM, IntoBoxedDatagram, SwapSegment},
+        datagram::{IntoBoxedDatagram, SwapSegment},
         defined::Hz,
-        error::AUTDDriverError,
+        error::{AUTDDriverError, DeviceError, FirmwareErrorCode},
         firmware::fpga::{Drive, EmitIntensity, Phase},
     };
     use spin_sleep::SpinSleeper;
@@ -198,13 +442,16 @@ mod tests {
     use crate::{
         controller::{
             tests::{create_controller, TestGain},
-            ParallelMode, SenderOption,
+            Controller, GroupResult, SenderOption,
         },
         error::AUTDError,
         gain::{Null, Uniform},
+        link::{Audit, AuditOption, Scenario},
         modulation::{Sine, Static},
+        prelude::AUTD3,
     };
 
+    #[cfg(feature = "stm")]
     #[rstest::rstest]
     #[case(ParallelMode::On)]
     #[case(ParallelMode::Off)]
@@ -313,6 +560,65 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_group_boxed_modulation() -> anyhow::Result<()> {
+        use autd3_driver::datagram::{IntoBoxedGain, IntoBoxedModulation};
+
+        let mut autd = create_controller(2)?;
+
+        let sine = Sine {
+            freq: 150. * Hz,
+            option: Default::default(),
+        };
+        autd.group_send(
+            |dev| Some(dev.idx()),
+            HashMap::from([
+                (
+                    0,
+                    (
+                        IntoBoxedModulation::into_boxed(Static { intensity: 0x80 }),
+                        IntoBoxedGain::into_boxed(Null {}),
+                    ),
+                ),
+                (
+                    1,
+                    (
+                        IntoBoxedModulation::into_boxed(sine.clone()),
+                        IntoBoxedGain::into_boxed(Uniform {
+                            intensity: EmitIntensity(0x80),
+                            phase: Phase::PI,
+                        }),
+                    ),
+                ),
+            ]),
+        )?;
+
+        assert_eq!(
+            vec![0x80, 0x80],
+            autd.link[0].fpga().modulation_buffer(Segment::S0)
+        );
+        assert_eq!(
+            vec![Drive::NULL; autd.geometry[0].num_transducers()],
+            autd.link[0].fpga().drives_at(Segment::S0, 0)
+        );
+        assert_eq!(
+            *sine.calc()?,
+            autd.link[1].fpga().modulation_buffer(Segment::S0)
+        );
+        assert_eq!(
+            vec![
+                Drive {
+                    phase: Phase::PI,
+                    intensity: EmitIntensity(0x80)
+                };
+                autd.geometry[1].num_transducers()
+            ],
+            autd.link[1].fpga().drives_at(Segment::S0, 0)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_send_failed() -> anyhow::Result<()> {
         let mut autd = create_controller(1)?;
@@ -335,7 +641,13 @@ mod tests {
         let mut autd = create_controller(2)?;
 
         assert_eq!(
-            Err(AUTDError::Driver(AUTDDriverError::InvalidSegmentTransition)),
+            Err(AUTDError::Driver(AUTDDriverError::Device(vec![
+                DeviceError {
+                    dev_idx: 1,
+                    code: FirmwareErrorCode::InvalidSegmentTransition,
+                    tags: [Some(0x44), None],
+                }
+            ]))),
             autd.group_send(
                 |dev| Some(dev.idx()),
                 HashMap::from([
@@ -429,4 +741,147 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn group_send_with_report() -> anyhow::Result<()> {
+        let mut autd = create_controller(3)?;
+
+        let report = autd.group_send_with_report(
+            |dev| match dev.idx() {
+                0 => Some("a"),
+                1 | 2 => Some("b"),
+                _ => None,
+            },
+            HashMap::from([("a", Null {}), ("b", Null {})]),
+        )?;
+        assert!(report.is_ok());
+        assert_eq!(
+            Some(&GroupResult {
+                devices: vec![1, 2],
+                failed: vec![],
+                result: Ok(()),
+            }),
+            report.groups.get("b")
+        );
+
+        let report = autd.group_send_with_report(
+            |dev| match dev.idx() {
+                0 => Some("a"),
+                1 | 2 => Some("b"),
+                _ => None,
+            },
+            HashMap::from([
+                ("a", Null {}.into_boxed()),
+                (
+                    "b",
+                    SwapSegment::FociSTM(Segment::S1, TransitionMode::SyncIdx).into_boxed(),
+                ),
+            ]),
+        )?;
+        assert!(!report.is_ok());
+        assert_eq!(
+            Some(&GroupResult {
+                devices: vec![0],
+                failed: vec![],
+                result: Ok(()),
+            }),
+            report.groups.get("a")
+        );
+        assert_eq!(
+            Some(&GroupResult {
+                devices: vec![1, 2],
+                failed: vec![1, 2],
+                result: Err(AUTDDriverError::Device(
+                    [1, 2]
+                        .into_iter()
+                        .map(|dev_idx| DeviceError {
+                            dev_idx,
+                            code: FirmwareErrorCode::InvalidSegmentTransition,
+                            tags: [Some(0x44), None],
+                        })
+                        .collect()
+                )),
+            }),
+            report.groups.get("b")
+        );
+        assert_eq!(vec![1, 2], report.failed_devices());
+        assert!(report.to_string().starts_with("\"b\": "));
+        assert!(report.to_string().ends_with("(devices: 1, 2)"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn group_send_with_report_timeout() -> anyhow::Result<()> {
+        let stale = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let mut autd = Controller::open(
+            [AUTD3::default(), AUTD3::default()],
+            Audit::new(AuditOption {
+                scenario: Scenario::new().on_response(1, {
+                    let stale = stale.clone();
+                    move |ctx, rx| {
+                        if stale.load(std::sync::atomic::Ordering::Relaxed) {
+                            *rx = ctx.previous;
+                        }
+                    }
+                }),
+                ..Default::default()
+            }),
+        )?;
+
+        stale.store(true, std::sync::atomic::Ordering::Relaxed);
+        let report = autd
+            .sender(SenderOption::<SpinSleeper> {
+                timeout: Some(std::time::Duration::from_millis(10)),
+                ..Default::default()
+            })
+            .group_send_with_report(
+                |dev| Some(dev.idx()),
+                HashMap::from([
+                    (0, Static { intensity: 0x80 }),
+                    (1, Static { intensity: 0x80 }),
+                ]),
+            )?;
+        assert_eq!(
+            Some(&GroupResult {
+                devices: vec![0],
+                failed: vec![],
+                result: Ok(()),
+            }),
+            report.groups.get(&0)
+        );
+        assert_eq!(
+            Some(&GroupResult {
+                devices: vec![1],
+                failed: vec![1],
+                result: Err(AUTDDriverError::ConfirmResponseFailed),
+            }),
+            report.groups.get(&1)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn group_send_with_report_failed() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+
+        assert_eq!(
+            Some(AUTDError::UnkownKey("1".to_owned())),
+            autd.group_send_with_report(|dev| Some(dev.idx()), HashMap::from([(0, Null {})]))
+                .err()
+        );
+
+        autd.link_mut().down();
+        let report = autd.group_send_with_report(
+            |dev| Some(dev.idx()),
+            HashMap::from([(0, Null {}), (1, Null {})]),
+        )?;
+        assert_eq!(vec![0, 1], report.failed_devices());
+        assert!(report
+            .failures()
+            .all(|(_, res)| res.result == Err(AUTDDriverError::SendDataFailed)));
+
+        Ok(())
+    }
 }
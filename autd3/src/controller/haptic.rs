@@ -0,0 +1,121 @@
+use autd3_core::{defined::Freq, link::Link};
+use autd3_driver::{
+    datagram::{FixedCompletionSteps, Silencer},
+    defined::Hz,
+    error::AUTDDriverError,
+    firmware::fpga::EmitIntensity,
+    geometry::Point3,
+};
+
+use crate::{
+    gain::{Focus, FocusOption},
+    modulation::{sampling_mode::Nearest, Sine},
+};
+
+use super::Controller;
+
+/// The option of [`Controller::render_point`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HapticOptions {
+    /// The frequency of the amplitude modulation. The frequency is rounded to the nearest one that can be output. The default value is 200 Hz.
+    pub frequency: Freq<f32>,
+    /// The intensity of the focus. The default value is [`EmitIntensity::MAX`].
+    pub intensity: EmitIntensity,
+}
+
+impl Default for HapticOptions {
+    fn default() -> Self {
+        Self {
+            frequency: 200. * Hz,
+            intensity: EmitIntensity::MAX,
+        }
+    }
+}
+
+impl HapticOptions {
+    pub(crate) fn stimulus(&self, pos: Point3) -> (Sine<Nearest>, Focus) {
+        (
+            Sine {
+                freq: self.frequency,
+                option: Default::default(),
+            }
+            .into_nearest(),
+            Focus {
+                pos,
+                option: FocusOption {
+                    intensity: self.intensity,
+                    ..Default::default()
+                },
+            },
+        )
+    }
+}
+
+impl<L: Link> Controller<L> {
+    /// Renders a haptic point at `pos` with the default silencer, [`Sine`] modulation and [`Focus`].
+    ///
+    /// This is a shortcut for sending [`Silencer::default`] and then ([`Sine`], [`Focus`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use autd3::prelude::*;
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+    ///
+    /// let center = autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm);
+    /// autd.render_point(center, HapticOptions::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn render_point(
+        &mut self,
+        pos: Point3,
+        option: HapticOptions,
+    ) -> Result<(), AUTDDriverError> {
+        self.send(Silencer::<FixedCompletionSteps>::default())?;
+        self.send(option.stimulus(pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_core::{
+        gain::{Gain, GainCalculator, GainCalculatorGenerator},
+        modulation::Modulation,
+    };
+    use autd3_driver::firmware::fpga::Segment;
+
+    use crate::controller::tests::create_controller;
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(HapticOptions::default())]
+    #[case(HapticOptions { frequency: 150. * Hz, intensity: EmitIntensity(0x80) })]
+    #[test]
+    fn render_point(#[case] option: HapticOptions) -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let pos = autd.center().unwrap() + autd3_driver::geometry::Vector3::new(0., 0., 150.);
+
+        autd.render_point(pos, option)?;
+
+        let (m, g) = option.stimulus(pos);
+        assert_eq!(
+            *m.calc()?,
+            autd.link()[0].fpga().modulation_buffer(Segment::S0)
+        );
+        let c = g.init()?.generate(&autd[0]);
+        assert_eq!(
+            autd[0].iter().map(|tr| c.calc(tr)).collect::<Vec<_>>(),
+            autd.link()[0].fpga().drives_at(Segment::S0, 0)
+        );
+        assert!(autd.link()[0]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == option.intensity));
+
+        Ok(())
+    }
+}
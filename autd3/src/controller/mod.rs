@@ -1,9 +1,24 @@
+mod background;
+mod close;
+mod diagnostics;
+mod gpio;
 mod group;
+mod haptic;
+mod monitor;
+mod rate_limiter;
 mod sender;
+mod snapshot;
+mod synchronized;
+mod watchdog;
 
 use crate::{error::AUTDError, gain::Null, modulation::Static};
 
-use autd3_core::{defined::DEFAULT_TIMEOUT, geometry::IntoDevice, link::Link};
+use autd3_core::{
+    datagram::{LoopBehavior, Segment, TransitionMode},
+    defined::DEFAULT_TIMEOUT,
+    geometry::IntoDevice,
+    link::Link,
+};
 use autd3_driver::{
     datagram::{Clear, Datagram, FixedCompletionSteps, ForceFan, Silencer, Synchronize},
     error::AUTDDriverError,
@@ -13,14 +28,42 @@ use autd3_driver::{
         operation::{FirmwareVersionType, Operation, OperationGenerator},
         version::FirmwareVersion,
     },
-    geometry::{Device, Geometry},
+    geometry::{Device, Geometry, Point3, Transducer, UnitQuaternion},
 };
 
+pub use background::{BackgroundSender, SendFuture};
+pub use close::{CloseReport, CloseStage};
+pub(crate) use diagnostics::EventLog;
+pub use diagnostics::{
+    ControllerEvent, DiagnosticsReport, EventRecord, DEFAULT_EVENT_LOG_CAPACITY,
+};
+pub use gpio::GPIOPlan;
+#[cfg(feature = "async")]
+pub(crate) use group::group_operations;
+pub use group::{GroupResult, GroupSendReport};
+pub use haptic::HapticOptions;
+pub use monitor::{FPGAStateEvent, FPGAStateMonitor, FPGAStateMonitorOption};
+pub use rate_limiter::RateLimiter;
+#[cfg(feature = "async")]
+pub(crate) use rate_limiter::RateSchedule;
+#[cfg(feature = "async")]
+pub(crate) use sender::count_frames;
 #[cfg(target_os = "windows")]
 pub use sender::WaitableSleeper;
 pub use sender::{
-    sleep::Sleep, ParallelMode, Sender, SenderOption, SpinSleeper, SpinStrategy, StdSleeper,
+    sleep::Sleep, AdaptiveTimeout, LatencyEstimate, LatencyModel, ModulationSplit, MsgIdPolicy,
+    PackingReport, ParallelMode, PowerBudget, PowerBudgetAction, Sender, SenderOption, Sequence,
+    SilencerLatency, SpinSleeper, SpinStrategy, StdSleeper,
 };
+#[cfg(feature = "async")]
+pub(crate) use sender::{split, telemetry, to_instant};
+pub(crate) use sender::{PowerMonitor, RttTracker};
+pub(crate) use snapshot::ConfigTracker;
+#[cfg(feature = "async")]
+pub(crate) use snapshot::RestorePlan;
+pub use snapshot::{ConfigSnapshot, DeviceSnapshot, DriveKind, SegmentSnapshot, SilencerSnapshot};
+pub use synchronized::SyncOption;
+pub use watchdog::{Watchdog, WatchdogOption};
 
 use derive_more::{Deref, DerefMut};
 use getset::{Getters, MutGetters};
@@ -42,6 +85,48 @@ pub struct Controller<L: Link> {
     geometry: Geometry,
     tx_buf: Vec<TxMessage>,
     rx_buf: Vec<RxMessage>,
+    events: EventLog,
+    power: PowerMonitor,
+    rtt: RttTracker,
+    packing: Option<PackingReport>,
+    config: ConfigTracker,
+}
+
+pub(crate) fn into_geometry<D: IntoDevice, F: IntoIterator<Item = D>>(
+    devices: F,
+) -> Result<Geometry, AUTDError> {
+    let devices = devices
+        .into_iter()
+        .enumerate()
+        .map(|(i, d)| d.into_device(i as _))
+        .collect::<Vec<_>>();
+    if devices.is_empty() {
+        return Err(AUTDError::EmptyGeometry);
+    }
+    Ok(Geometry::new(devices))
+}
+
+pub(crate) fn reindex(geometry: &mut Geometry) {
+    let devices = std::mem::take(&mut **geometry);
+    **geometry = devices
+        .into_iter()
+        .enumerate()
+        .map(|(i, d)| {
+            // The device index held by each transducer must be also updated.
+            let transducers = d
+                .iter()
+                .map(|tr| {
+                    let mut t = Transducer::new(tr.idx() as _, i as _, *tr.position());
+                    t.enable = tr.enable;
+                    t
+                })
+                .collect();
+            let mut dev = Device::new(i as _, *d.rotation(), transducers);
+            dev.enable = d.enable;
+            dev.sound_speed = d.sound_speed;
+            dev
+        })
+        .collect();
 }
 
 impl<L: Link> Controller<L> {
@@ -70,23 +155,57 @@ impl<L: Link> Controller<L> {
     ) -> Result<Self, AUTDError> {
         tracing::debug!("Opening a controller with option {:?})", option);
 
-        let devices = devices
-            .into_iter()
-            .enumerate()
-            .map(|(i, d)| d.into_device(i as _))
-            .collect();
-
-        let geometry = Geometry::new(devices);
+        let geometry = into_geometry(devices)?;
+        link.preflight(&geometry)?;
         link.open(&geometry)?;
         Controller {
             link,
             tx_buf: vec![TxMessage::new_zeroed(); geometry.len()], // Do not use `num_devices` here because the devices may be disabled.
             rx_buf: vec![RxMessage::new(0, 0); geometry.len()],
+            events: EventLog::default(),
+            power: PowerMonitor::default(),
+            rtt: RttTracker::default(),
+            packing: None,
+            config: ConfigTracker::default(),
             geometry,
         }
         .open_impl(option)
     }
 
+    /// Opens a controller driving the devices at the ultrasound frequency `freq` with a [`SenderOption`].
+    ///
+    /// The ultrasound frequency is set by [`set_ultrasound_freq`] before opening the link, so that [`SamplingConfig`], the wavelength of [`Device`], and so on are calculated with `freq`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AUTDError::UltrasoundFreqAlreadyFixed`] if the ultrasound frequency has already been fixed to another value, e.g., by calculating a [`SamplingConfig`] before opening.
+    ///
+    /// [`set_ultrasound_freq`]: autd3_core::defined::set_ultrasound_freq
+    /// [`SamplingConfig`]: autd3_driver::firmware::fpga::SamplingConfig
+    #[cfg_attr(docsrs, doc(cfg(feature = "dynamic_freq")))]
+    #[cfg(feature = "dynamic_freq")]
+    pub fn open_with_freq<D: IntoDevice, F: IntoIterator<Item = D>, S: Sleep>(
+        devices: F,
+        link: L,
+        freq: autd3_driver::datagram::UltrasoundFreq,
+        option: SenderOption<S>,
+    ) -> Result<Self, AUTDError> {
+        autd3_core::defined::set_ultrasound_freq(freq.freq())
+            .map_err(|current| AUTDError::UltrasoundFreqAlreadyFixed(freq.freq(), current))?;
+        Self::open_with_option(devices, link, option)
+    }
+
+    /// Checks the environment with [`Link::preflight`] without opening the link.
+    ///
+    /// [`Self::open`] also performs this check before opening the link. This is useful to report setup issues, e.g., missing drivers or unreachable servers, before any device traffic.
+    pub fn preflight<D: IntoDevice, F: IntoIterator<Item = D>>(
+        devices: F,
+        link: &mut L,
+    ) -> Result<(), AUTDError> {
+        link.preflight(&into_geometry(devices)?)?;
+        Ok(())
+    }
+
     /// Returns the [`Sender`] to send data to the devices.
     pub fn sender<S: Sleep>(&mut self, option: SenderOption<S>) -> Sender<'_, L, S> {
         Sender {
@@ -94,12 +213,16 @@ impl<L: Link> Controller<L> {
             geometry: &mut self.geometry,
             tx: &mut self.tx_buf,
             rx: &mut self.rx_buf,
+            events: &mut self.events,
+            power: &mut self.power,
+            rtt: &mut self.rtt,
+            packing: &mut self.packing,
+            config: &mut self.config,
             option,
         }
     }
 
     /// Sends a data to the devices. This is a shortcut for [`Sender::send`].
-    #[tracing::instrument(level = "debug", skip(self))]
     pub fn send<D: Datagram>(&mut self, s: D) -> Result<(), AUTDDriverError>
     where
         AUTDDriverError: From<D::Error>,
@@ -110,10 +233,41 @@ impl<L: Link> Controller<L> {
         self.sender(SenderOption::<SpinSleeper>::default()).send(s)
     }
 
+    /// Sends data to the devices in order. This is a shortcut for [`Sender::send_all`].
+    #[tracing::instrument(level = "debug", skip(self, s))]
+    pub fn send_all<D: Datagram>(
+        &mut self,
+        s: impl IntoIterator<Item = D>,
+    ) -> Result<(), (usize, AUTDDriverError)>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        self.sender(SenderOption::<SpinSleeper>::default())
+            .send_all(s)
+    }
+
+    /// Sends a sequence of data to the devices. This is a shortcut for [`Sender::send_sequence`].
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn send_sequence(&mut self, s: Sequence) -> Result<(), AUTDDriverError> {
+        self.sender(SenderOption::<SpinSleeper>::default())
+            .send_sequence(s)
+    }
+
     pub(crate) fn open_impl<S: Sleep>(
         mut self,
         option: SenderOption<S>,
     ) -> Result<Self, AUTDError> {
+        self.initialize(option)?;
+        self.events.push(ControllerEvent::Opened {
+            num_devices: self.geometry.len(),
+        });
+        Ok(self)
+    }
+
+    fn initialize<S: Sleep>(&mut self, option: SenderOption<S>) -> Result<(), AUTDError> {
         let mut sender = self.sender(option);
 
         // If the device is used continuously without powering off, the first data may be ignored because the first msg_id equals to the remaining msg_id in the device.
@@ -130,10 +284,121 @@ impl<L: Link> Controller<L> {
         }
 
         sender.send((Clear::new(), Synchronize::new()))?;
-        Ok(self)
+        self.config.cleared();
+        Ok(())
     }
 
-    fn close_impl<S: Sleep>(&mut self, option: SenderOption<S>) -> Result<(), AUTDDriverError> {
+    /// Enables or disables the devices at `indices`.
+    ///
+    /// Disabled devices are skipped consistently by all [`Datagram`]s, i.e., no data is generated or sent to them, and their responses including errors are ignored when confirming that the data has been processed.
+    /// Note that disabled devices keep their last output, so send [`Null`] to them beforehand if they should be stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AUTDError::DeviceIndexOutOfRange`] if any index is out of range. In this case, no device is changed.
+    ///
+    /// [`Datagram`]: autd3_driver::datagram::Datagram
+    /// [`Null`]: crate::gain::Null
+    pub fn set_enabled(
+        &mut self,
+        indices: impl IntoIterator<Item = usize>,
+        enable: bool,
+    ) -> Result<(), AUTDError> {
+        let indices = indices.into_iter().collect::<Vec<_>>();
+        if let Some(&idx) = indices.iter().find(|&&idx| idx >= self.geometry.len()) {
+            return Err(AUTDError::DeviceIndexOutOfRange(idx));
+        }
+        indices.into_iter().for_each(|idx| {
+            self.geometry[idx].enable = enable;
+        });
+        Ok(())
+    }
+
+    /// Updates the poses of the devices without reconfiguring the link.
+    ///
+    /// Each item is the index of the device, the position of its first transducer, and its rotation, which are applied by [`Device::set_pose`].
+    /// This is intended to stream the poses at a high rate, e.g., when the devices are mounted on a robot arm. The devices are not reallocated, the version of the geometry is bumped only once, and the link is notified by [`Link::update`] immediately, e.g., `Simulator` sends only the changed poses.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AUTDError::DeviceIndexOutOfRange`] if any index is out of range. In this case, no device is changed.
+    pub fn update_poses(
+        &mut self,
+        poses: impl IntoIterator<Item = (usize, Point3, UnitQuaternion)>,
+    ) -> Result<(), AUTDError> {
+        let poses = poses.into_iter().collect::<Vec<_>>();
+        if let Some(&(idx, _, _)) = poses.iter().find(|(idx, _, _)| *idx >= self.geometry.len()) {
+            return Err(AUTDError::DeviceIndexOutOfRange(idx));
+        }
+        let devices: &mut Vec<Device> = &mut self.geometry;
+        poses
+            .into_iter()
+            .for_each(|(idx, pos, rot)| devices[idx].set_pose(pos, rot));
+        self.link.update(&self.geometry)?;
+        Ok(())
+    }
+
+    /// Adds a device to the end of the geometry without reopening the controller.
+    ///
+    /// The link is reconfigured with [`Link::reconfigure`], and then all devices are initialized and synchronized again as in [`Self::open`].
+    /// If the link fails to reconfigure, e.g., the link does not support changing the devices, the geometry is not changed.
+    pub fn add_device<D: IntoDevice>(&mut self, dev: D) -> Result<(), AUTDError> {
+        let idx = self.geometry.len();
+        self.geometry.push(dev.into_device(idx as _));
+        if let Err(e) = self.link.reconfigure(&self.geometry) {
+            self.geometry.pop();
+            return Err(e.into());
+        }
+        self.reinitialize()
+    }
+
+    /// Removes the device at `idx` without reopening the controller, and returns the removed device.
+    ///
+    /// The indices of the subsequent devices are shifted down by one. The link is reconfigured with [`Link::reconfigure`], and then all devices are initialized and synchronized again as in [`Self::open`].
+    /// If the link fails to reconfigure, e.g., the link does not support changing the devices, the geometry is not changed.
+    ///
+    /// Note that nothing is sent to the removed device, so it should be stopped beforehand if it is still connected.
+    ///
+    /// # Errors
+    ///
+    /// - [`AUTDError::DeviceIndexOutOfRange`] if `idx` is out of range.
+    /// - [`AUTDError::EmptyGeometry`] if the device is the last one.
+    pub fn remove_device(&mut self, idx: usize) -> Result<Device, AUTDError> {
+        if idx >= self.geometry.len() {
+            return Err(AUTDError::DeviceIndexOutOfRange(idx));
+        }
+        if self.geometry.len() == 1 {
+            return Err(AUTDError::EmptyGeometry);
+        }
+        let dev = self.geometry.remove(idx);
+        reindex(&mut self.geometry);
+        if let Err(e) = self.link.reconfigure(&self.geometry) {
+            self.geometry.insert(idx, dev);
+            reindex(&mut self.geometry);
+            return Err(e.into());
+        }
+        self.reinitialize()?;
+        Ok(dev)
+    }
+
+    fn reinitialize(&mut self) -> Result<(), AUTDError> {
+        self.tx_buf = vec![TxMessage::new_zeroed(); self.geometry.len()];
+        self.rx_buf = vec![RxMessage::new(0, 0); self.geometry.len()];
+        self.power = PowerMonitor::default();
+        self.rtt = RttTracker::default();
+        self.packing = None;
+        self.config = self.config.reset(&self.geometry);
+        self.initialize(SenderOption::<SpinSleeper> {
+            timeout: Some(DEFAULT_TIMEOUT),
+            ..Default::default()
+        })?;
+        self.events.push(ControllerEvent::DevicesChanged {
+            num_devices: self.geometry.len(),
+        });
+        Ok(())
+    }
+
+    fn close_impl<S: Sleep>(&mut self, option: SenderOption<S>) -> Result<(), AUTDError> {
         tracing::info!("Closing controller");
 
         if !self.link.is_open() {
@@ -145,25 +410,34 @@ impl<L: Link> Controller<L> {
 
         let mut sender = self.sender(option);
 
-        [
-            sender.send(Silencer {
+        let report = CloseReport {
+            silencer: sender.send(Silencer {
                 config: FixedCompletionSteps {
                     strict_mode: false,
                     ..Default::default()
                 },
                 target: autd3_driver::firmware::fpga::SilencerTarget::Intensity,
             }),
-            sender.send((Static::default(), Null)),
-            sender.send(Clear {}),
-            Ok(self.link.close()?),
-        ]
-        .into_iter()
-        .try_fold((), |_, x| x)
+            null: sender.send((Static::default(), Null)),
+            clear: sender.send(Clear {}),
+            link_close: self.link.close().map_err(AUTDDriverError::from),
+        };
+        let res = if report.is_ok() {
+            Ok(())
+        } else {
+            Err(AUTDError::CloseFailed(Box::new(report)))
+        };
+        self.events.push(ControllerEvent::Closed {
+            error: res.as_ref().err().map(ToString::to_string),
+        });
+        res
     }
 
     /// Closes the controller.
+    ///
+    /// All stages of closing are run even if some of them fail. If any stage fails, [`AUTDError::CloseFailed`] with [`CloseReport`] is returned.
     #[tracing::instrument(level = "debug", skip(self))]
-    pub fn close(mut self) -> Result<(), AUTDDriverError> {
+    pub fn close(mut self) -> Result<(), AUTDError> {
         self.close_impl(SenderOption::<SpinSleeper>::default())
     }
 
@@ -207,6 +481,26 @@ impl<L: Link> Controller<L> {
             .collect())
     }
 
+    /// Validates the combination of `segment`, `loop_behavior`, and `transition_mode` against the firmware of the devices.
+    ///
+    /// This reads the firmware version of the devices first, so that [`WithSegment`] and [`WithLoopBehavior`] which the firmware does not support can be rejected before sending. See [`FirmwareCapabilities::validate_transition`] for the errors.
+    ///
+    /// [`WithSegment`]: autd3_driver::datagram::WithSegment
+    /// [`WithLoopBehavior`]: autd3_driver::datagram::WithLoopBehavior
+    /// [`FirmwareCapabilities::validate_transition`]: autd3_driver::firmware::version::FirmwareCapabilities::validate_transition
+    pub fn validate_transition(
+        &mut self,
+        segment: Segment,
+        loop_behavior: LoopBehavior,
+        transition_mode: Option<TransitionMode>,
+    ) -> Result<(), AUTDError> {
+        self.firmware_version()?.iter().try_for_each(|v| {
+            v.caps()
+                .validate_transition(segment, loop_behavior, transition_mode)
+        })?;
+        Ok(())
+    }
+
     /// Returns the FPGA state of the devices.
     ///
     /// To get the state of devices, enable reads FPGA state mode by [`ReadsFPGAState`] before calling this method.
@@ -239,6 +533,56 @@ impl<L: Link> Controller<L> {
             Err(AUTDError::ReadFPGAStateFailed)
         }
     }
+
+    /// Inspects what the devices emit after the [`Datagram`] is sent. This is a shortcut for [`inspect`].
+    ///
+    /// [`inspect`]: crate::inspect::inspect
+    #[cfg(feature = "inspect")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "inspect")))]
+    pub fn inspect<D: Datagram>(
+        &self,
+        d: D,
+    ) -> Result<Vec<Option<crate::inspect::DeviceInspection>>, AUTDDriverError>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        crate::inspect::inspect(&self.geometry, d)
+    }
+
+    /// Returns the diagnostics report which contains the recent events of the controller, such as sending [`Datagram`]s, errors, and opening/closing.
+    ///
+    /// The events are kept in a ring buffer, whose capacity is [`DEFAULT_EVENT_LOG_CAPACITY`] by default. If the `serde` feature is enabled, the report can be serialized to attach to bug reports.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use autd3::prelude::*;
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+    ///
+    /// autd.send(Static::default())?;
+    ///
+    /// let report = autd.dump_diagnostics();
+    /// println!("{:#?}", report);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dump_diagnostics(&self) -> DiagnosticsReport {
+        self.events.report(&self.geometry, self.link.is_open())
+    }
+
+    /// Sets the capacity of the event log. If the number of recorded events exceeds the capacity, the oldest events are discarded.
+    pub fn set_event_log_capacity(&mut self, capacity: usize) {
+        self.events.set_capacity(capacity);
+    }
+
+    /// Returns the [`PackingReport`] of the last [`Datagram`] sent with [`SenderOption::metrics`], or `None` if no such [`Datagram`] has been sent.
+    pub const fn packing_report(&self) -> Option<&PackingReport> {
+        self.packing.as_ref()
+    }
 }
 
 impl<'a, L: Link> IntoIterator for &'a Controller<L> {
@@ -267,11 +611,21 @@ impl<L: Link + 'static> Controller<L> {
         let geometry = unsafe { std::ptr::read(&cnt.geometry) };
         let tx_buf = unsafe { std::ptr::read(&cnt.tx_buf) };
         let rx_buf = unsafe { std::ptr::read(&cnt.rx_buf) };
+        let events = unsafe { std::ptr::read(&cnt.events) };
+        let power = unsafe { std::ptr::read(&cnt.power) };
+        let rtt = unsafe { std::ptr::read(&cnt.rtt) };
+        let packing = unsafe { std::ptr::read(&cnt.packing) };
+        let config = unsafe { std::ptr::read(&cnt.config) };
         Controller {
             link: Box::new(link) as _,
             geometry,
             tx_buf,
             rx_buf,
+            events,
+            power,
+            rtt,
+            packing,
+            config,
         }
     }
 
@@ -286,11 +640,21 @@ impl<L: Link + 'static> Controller<L> {
         let geometry = unsafe { std::ptr::read(&cnt.geometry) };
         let tx_buf = unsafe { std::ptr::read(&cnt.tx_buf) };
         let rx_buf = unsafe { std::ptr::read(&cnt.rx_buf) };
+        let events = unsafe { std::ptr::read(&cnt.events) };
+        let power = unsafe { std::ptr::read(&cnt.power) };
+        let rtt = unsafe { std::ptr::read(&cnt.rtt) };
+        let packing = unsafe { std::ptr::read(&cnt.packing) };
+        let config = unsafe { std::ptr::read(&cnt.config) };
         Controller {
             link: unsafe { *Box::from_raw(Box::into_raw(link) as *mut L) },
             geometry,
             tx_buf,
             rx_buf,
+            events,
+            power,
+            rtt,
+            packing,
+            config,
         }
     }
 }
@@ -306,25 +670,30 @@ impl<L: Link> Drop for Controller<L> {
 
 #[cfg(test)]
 pub(crate) mod tests {
-    use std::sync::Mutex;
+    use std::{sync::Mutex, time::Duration};
 
     use crate::{
-        core::{
-            defined::mm,
-            derive::*,
-            gain::{Gain, GainCalculator, GainCalculatorGenerator},
-            link::LinkError,
-        },
+        core::{defined::mm, derive::*, gain::Gain, link::LinkError},
         driver::{
             autd3_device::AUTD3,
-            datagram::{GainSTM, ReadsFPGAState},
-            defined::Hz,
+            datagram::{IntoBoxedDatagram, ReadsFPGAState, SwapSegment},
+            defined::{Hz, PI},
+            error::{DeviceError, FirmwareErrorCode},
+            ethercat::DcSysTime,
+            firmware::{fpga::DebugType, operation::PackingMetrics},
+            geometry::{Point3, Vector3},
         },
         gain::Uniform,
         link::{Audit, AuditOption},
         modulation::Sine,
     };
 
+    #[cfg(feature = "stm")]
+    use crate::{
+        core::gain::{GainCalculator, GainCalculatorGenerator},
+        driver::datagram::{ControlPoints, FociSTM, GainSTM},
+    };
+
     use super::*;
 
     // GRCOV_EXCL_START
@@ -378,6 +747,107 @@ pub(crate) mod tests {
         );
     }
 
+    #[cfg(feature = "dynamic_freq")]
+    #[test]
+    fn open_with_freq() -> anyhow::Result<()> {
+        use autd3_driver::{datagram::UltrasoundFreq, defined::ultrasound_freq};
+
+        let freq = ultrasound_freq();
+        let autd = Controller::open_with_freq(
+            [AUTD3::default()],
+            Audit::new(AuditOption::default()),
+            UltrasoundFreq::new(freq)?,
+            SenderOption::<SpinSleeper>::default(),
+        )?;
+        autd.close()?;
+
+        let other = if freq == 41000 * Hz {
+            40000 * Hz
+        } else {
+            41000 * Hz
+        };
+        assert_eq!(
+            Some(AUTDError::UltrasoundFreqAlreadyFixed(other, freq)),
+            Controller::open_with_freq(
+                [AUTD3::default()],
+                Audit::new(AuditOption::default()),
+                UltrasoundFreq::new(other)?,
+                SenderOption::<SpinSleeper>::default(),
+            )
+            .err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn timing_model() -> anyhow::Result<()> {
+        let mut autd = Controller::open(
+            [AUTD3::default()],
+            Audit::new(AuditOption {
+                timing_model: Some(autd3_firmware_emulator::TimingModel {
+                    default_delay: Duration::from_millis(5),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        )?;
+
+        assert_eq!(
+            Err(AUTDDriverError::ConfirmResponseFailed),
+            autd.sender(SenderOption::<SpinSleeper> {
+                timeout: Some(Duration::from_millis(1)),
+                ..Default::default()
+            })
+            .send(Static { intensity: 0x80 })
+        );
+        assert_eq!(1, autd.link()[0].num_pending_frames());
+
+        autd.sender(SenderOption::<SpinSleeper> {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        })
+        .send(Static { intensity: 0x40 })?;
+        assert_eq!(0, autd.link()[0].num_pending_frames());
+        assert_eq!(
+            vec![0x40, 0x40],
+            autd.link()[0].fpga().modulation_buffer(Segment::S0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn preflight() -> anyhow::Result<()> {
+        let mut link = Audit::new(AuditOption::default());
+        Controller::preflight([AUTD3::default()], &mut link)?;
+        assert!(!link.is_open());
+
+        link.break_down();
+        assert_eq!(
+            Err(AUTDError::from(LinkError::new("broken".to_owned()))),
+            Controller::preflight([AUTD3::default()], &mut link)
+        );
+        assert_eq!(
+            Some(AUTDError::from(LinkError::new("broken".to_owned()))),
+            Controller::open([AUTD3::default()], link).err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn open_empty() {
+        assert_eq!(
+            Some(AUTDError::EmptyGeometry),
+            Controller::open(
+                std::iter::empty::<AUTD3>(),
+                Audit::new(AuditOption::default())
+            )
+            .err()
+        );
+    }
+
+    #[cfg(feature = "stm")]
     #[test]
     fn send() -> anyhow::Result<()> {
         let mut autd = create_controller(1)?;
@@ -439,6 +909,246 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[test]
+    fn send_at() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        let m = Sine {
+            freq: 150. * Hz,
+            option: Default::default(),
+        };
+        let time = DcSysTime::now() + Duration::from_secs(1);
+        autd.sender(SenderOption::<SpinSleeper>::default())
+            .send_at(m.clone(), Segment::S1, LoopBehavior::ONCE, time)?;
+        assert_eq!(
+            *m.clone().calc()?,
+            autd.link[0].fpga().modulation_buffer(Segment::S1)
+        );
+        assert_eq!(Segment::S1, autd.link[0].fpga().req_modulation_segment());
+        assert_eq!(
+            TransitionMode::SysTime(time),
+            autd.link[0].fpga().modulation_transition_mode()
+        );
+
+        assert_eq!(
+            Err(AUTDDriverError::MissTransitionTime),
+            autd.sender(SenderOption::<SpinSleeper>::default()).send_at(
+                m.clone(),
+                Segment::S0,
+                LoopBehavior::ONCE,
+                DcSysTime::now() - Duration::from_millis(1)
+            )
+        );
+        assert_eq!(
+            Err(AUTDDriverError::IncompatibleTransitionMode(
+                Segment::S0,
+                LoopBehavior::Infinite,
+                TransitionMode::SysTime(time)
+            )),
+            autd.sender(SenderOption::<SpinSleeper>::default()).send_at(
+                m.clone(),
+                Segment::S0,
+                LoopBehavior::Infinite,
+                time
+            )
+        );
+        assert_eq!(
+            Err(AUTDDriverError::Device(vec![DeviceError {
+                dev_idx: 0,
+                code: FirmwareErrorCode::MissTransitionTime,
+                tags: [Some(0x10), None],
+            }])),
+            autd.sender(SenderOption::<SpinSleeper> {
+                timeout: Some(Duration::from_millis(10)),
+                ..Default::default()
+            })
+            .send_at(
+                m,
+                Segment::S0,
+                LoopBehavior::ONCE,
+                DcSysTime::now() + Duration::from_millis(1),
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_transition() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+
+        autd.validate_transition(
+            Segment::S1,
+            LoopBehavior::ONCE,
+            Some(TransitionMode::SyncIdx),
+        )?;
+        assert_eq!(
+            Err(AUTDError::Driver(
+                AUTDDriverError::IncompatibleTransitionMode(
+                    Segment::S1,
+                    LoopBehavior::Infinite,
+                    TransitionMode::SyncIdx
+                )
+            )),
+            autd.validate_transition(
+                Segment::S1,
+                LoopBehavior::Infinite,
+                Some(TransitionMode::SyncIdx)
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn send_incompatible_transition_mode() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        assert_eq!(
+            Err(AUTDDriverError::IncompatibleTransitionMode(
+                Segment::S1,
+                LoopBehavior::Infinite,
+                TransitionMode::SyncIdx
+            )),
+            autd.send(crate::driver::datagram::WithLoopBehavior {
+                inner: Sine {
+                    freq: 150. * Hz,
+                    option: Default::default(),
+                },
+                loop_behavior: LoopBehavior::Infinite,
+                segment: Segment::S1,
+                transition_mode: Some(TransitionMode::SyncIdx),
+            })
+        );
+        assert_eq!(Segment::S0, autd.link[0].fpga().req_modulation_segment());
+
+        Ok(())
+    }
+
+    #[cfg(feature = "stm")]
+    #[test]
+    fn send_sequence() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        autd.send_sequence(
+            Sequence::new()
+                .push(Clear::new(), None)
+                .push(Synchronize::new(), Some(Duration::from_millis(100)))
+                .push(
+                    Uniform {
+                        intensity: EmitIntensity(0x80),
+                        phase: Phase(0x01),
+                    },
+                    None,
+                ),
+        )?;
+        assert_eq!(
+            vec![
+                Drive {
+                    intensity: EmitIntensity(0x80),
+                    phase: Phase(0x01),
+                };
+                autd[0].num_transducers()
+            ],
+            autd.link[0].fpga().drives_at(Segment::S0, 0)
+        );
+
+        assert_eq!(
+            Err(AUTDDriverError::FociSTMPointSizeOutOfRange(1)),
+            autd.send_sequence(
+                Sequence::new()
+                    .push(
+                        Uniform {
+                            intensity: EmitIntensity(0x81),
+                            phase: Phase(0x02),
+                        },
+                        None,
+                    )
+                    .push(
+                        FociSTM {
+                            foci: vec![ControlPoints::<1>::default()],
+                            config: 1. * Hz,
+                        },
+                        None,
+                    )
+                    .push(
+                        Uniform {
+                            intensity: EmitIntensity(0x82),
+                            phase: Phase(0x03),
+                        },
+                        None,
+                    ),
+            )
+        );
+        assert_eq!(
+            vec![
+                Drive {
+                    intensity: EmitIntensity(0x81),
+                    phase: Phase(0x02),
+                };
+                autd[0].num_transducers()
+            ],
+            autd.link[0].fpga().drives_at(Segment::S0, 0)
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "stm")]
+    #[test]
+    fn send_all() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        autd.send_all([0x80, 0x81].map(|intensity| Uniform {
+            intensity: EmitIntensity(intensity),
+            phase: Phase(0x01),
+        }))
+        .map_err(|(_, e)| e)?;
+        assert_eq!(
+            vec![
+                Drive {
+                    intensity: EmitIntensity(0x81),
+                    phase: Phase(0x01),
+                };
+                autd[0].num_transducers()
+            ],
+            autd.link[0].fpga().drives_at(Segment::S0, 0)
+        );
+
+        assert_eq!(
+            Err((1, AUTDDriverError::FociSTMPointSizeOutOfRange(1))),
+            autd.send_all([
+                Uniform {
+                    intensity: EmitIntensity(0x82),
+                    phase: Phase(0x02),
+                }
+                .into_boxed(),
+                FociSTM {
+                    foci: vec![ControlPoints::<1>::default()],
+                    config: 1. * Hz,
+                }
+                .into_boxed(),
+                Uniform {
+                    intensity: EmitIntensity(0x83),
+                    phase: Phase(0x03),
+                }
+                .into_boxed(),
+            ])
+        );
+        assert_eq!(
+            vec![
+                Drive {
+                    intensity: EmitIntensity(0x82),
+                    phase: Phase(0x02),
+                };
+                autd[0].num_transducers()
+            ],
+            autd.link[0].fpga().drives_at(Segment::S0, 0)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn firmware_version() -> anyhow::Result<()> {
         use autd3_driver::firmware::version::{CPUVersion, FPGAVersion};
@@ -484,8 +1194,14 @@ pub(crate) mod tests {
         {
             let mut autd = create_controller(1)?;
             autd.link_mut().break_down();
+            let err = Err(AUTDDriverError::Link(LinkError::new("broken".to_owned())));
             assert_eq!(
-                Err(AUTDDriverError::Link(LinkError::new("broken".to_owned()))),
+                Err(AUTDError::CloseFailed(Box::new(CloseReport {
+                    silencer: err.clone(),
+                    null: err.clone(),
+                    clear: err,
+                    link_close: Ok(()),
+                }))),
                 autd.close()
             );
         }
@@ -493,12 +1209,93 @@ pub(crate) mod tests {
         {
             let mut autd = create_controller(1)?;
             autd.link_mut().down();
-            assert_eq!(Err(AUTDDriverError::SendDataFailed), autd.close());
+            assert_eq!(
+                Err(AUTDError::CloseFailed(Box::new(CloseReport {
+                    silencer: Err(AUTDDriverError::SendDataFailed),
+                    null: Err(AUTDDriverError::SendDataFailed),
+                    clear: Err(AUTDDriverError::SendDataFailed),
+                    link_close: Ok(()),
+                }))),
+                autd.close()
+            );
         }
 
         Ok(())
     }
 
+    #[test]
+    fn packing_report() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        autd[1].enable = false;
+        let option = SenderOption::<SpinSleeper> {
+            metrics: true,
+            ..Default::default()
+        };
+
+        autd.send(Static::default())?;
+        assert_eq!(None, autd.packing_report());
+
+        autd.sender(option).send(crate::modulation::Custom {
+            buffer: vec![0xFF; 4000],
+            sampling_config: SamplingConfig::new(10)?,
+        })?;
+        let report = autd.packing_report().unwrap();
+        assert!(report.passes > 1);
+        assert_eq!(report.passes, report.devices[0].frames);
+        assert!(report.devices[0].bytes > 4000);
+        assert_eq!(PackingMetrics::default(), report.devices[1]);
+
+        autd.link_mut().down();
+        assert!(autd.sender(option).send(Static::default()).is_err());
+        autd.link_mut().up();
+        let report = autd.packing_report().unwrap();
+        assert_eq!(1, report.passes);
+        assert_eq!(1, report.devices[0].frames);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dump_diagnostics() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        autd.send(Static::default())?;
+        autd.link_mut().down();
+        assert!(autd.send(Null).is_err());
+        autd.link_mut().up();
+
+        let report = autd.dump_diagnostics();
+        assert_eq!(1, report.num_devices);
+        assert_eq!(1, report.num_enabled_devices);
+        assert!(report.link_open);
+        assert_eq!(0, report.dropped_events);
+
+        let events = report
+            .events
+            .iter()
+            .map(|e| &e.event)
+            .skip_while(|e| !matches!(e, ControllerEvent::Opened { .. }))
+            .collect::<Vec<_>>();
+        assert_eq!(3, events.len());
+        assert_eq!(&ControllerEvent::Opened { num_devices: 1 }, events[0]);
+        assert!(matches!(
+            events[1],
+            ControllerEvent::Send { datagram, error: None, .. } if datagram.ends_with("Static")
+        ));
+        assert!(matches!(
+            events[2],
+            ControllerEvent::Send { datagram, error: Some(e), .. } if datagram.ends_with("Null") && e == &AUTDDriverError::SendDataFailed.to_string()
+        ));
+        assert!(report
+            .events
+            .windows(2)
+            .all(|w| w[0].timestamp <= w[1].timestamp));
+
+        autd.set_event_log_capacity(1);
+        assert_eq!(1, autd.dump_diagnostics().events.len());
+
+        Ok(())
+    }
+
     #[test]
     fn fpga_state() -> anyhow::Result<()> {
         let mut autd = Controller::open(
@@ -562,6 +1359,35 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "stm")]
+    #[test]
+    fn sound_speed_per_device() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+
+        autd[0].set_sound_speed_from_temp(15.);
+        autd[1].set_sound_speed_from_temp(30.);
+        autd.send(FociSTM {
+            foci: vec![Point3::origin(), Point3::origin()],
+            config: SamplingConfig::FREQ_MIN,
+        })?;
+        let sound_speed =
+            |dev: &Device| (dev.sound_speed / crate::core::defined::METER * 64.0).round() as u16;
+        assert_eq!(
+            sound_speed(&autd[0]),
+            autd.link[0].fpga().sound_speed(Segment::S0)
+        );
+        assert_eq!(
+            sound_speed(&autd[1]),
+            autd.link[1].fpga().sound_speed(Segment::S0)
+        );
+        assert!(
+            autd.link[0].fpga().sound_speed(Segment::S0)
+                < autd.link[1].fpga().sound_speed(Segment::S0)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn with_boxed_link() -> anyhow::Result<()> {
         let link: Box<dyn Link> = Box::new(Audit::new(AuditOption::default()));
@@ -577,6 +1403,7 @@ pub(crate) mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "stm")]
     #[test]
     fn into_boxed_link_unsafe() -> anyhow::Result<()> {
         let option = SenderOption {
@@ -662,4 +1489,226 @@ pub(crate) mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn add_remove_device() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        let pos = Point3::new(0., 0., 100. * mm);
+
+        autd.add_device(AUTD3 {
+            pos,
+            ..Default::default()
+        })?;
+        assert_eq!(3, autd.len());
+        assert_eq!(3, autd.link().len());
+        assert_eq!(2, autd[2].idx());
+        autd.send(Uniform {
+            intensity: EmitIntensity::MAX,
+            phase: Phase::ZERO,
+        })?;
+        autd.iter().try_for_each(|dev| {
+            anyhow::ensure!(autd.link()[dev.idx()]
+                .fpga()
+                .drives_at(Segment::S0, 0)
+                .iter()
+                .all(|d| d.intensity == EmitIntensity::MAX));
+            Ok(())
+        })?;
+
+        let removed = autd.remove_device(0)?;
+        assert_eq!(0, removed.idx());
+        assert_eq!(2, autd.len());
+        assert_eq!(2, autd.link().len());
+        assert_eq!(
+            vec![0, 1],
+            autd.iter().map(|dev| dev.idx()).collect::<Vec<_>>()
+        );
+        assert_eq!(pos, *autd[1][0].position());
+        autd.send(Null {})?;
+
+        assert_eq!(
+            Some(&ControllerEvent::DevicesChanged { num_devices: 2 }),
+            autd.dump_diagnostics()
+                .events
+                .iter()
+                .map(|r| &r.event)
+                .rfind(|e| matches!(e, ControllerEvent::DevicesChanged { .. }))
+        );
+
+        assert_eq!(
+            Err(AUTDError::DeviceIndexOutOfRange(2)),
+            autd.remove_device(2).map(|_| ())
+        );
+        autd.remove_device(1)?;
+        assert_eq!(
+            Err(AUTDError::EmptyGeometry),
+            autd.remove_device(0).map(|_| ())
+        );
+
+        autd.close()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn remove_middle_device() -> anyhow::Result<()> {
+        let mut autd = create_controller(3)?;
+        autd[2].sound_speed = 350e3;
+        autd.geometry[2]
+            .iter_mut()
+            .take(1)
+            .for_each(|tr| tr.enable = false);
+
+        autd.remove_device(1)?;
+        assert_eq!(2, autd.len());
+        assert!(autd
+            .iter()
+            .all(|dev| dev.iter().all(|tr| tr.dev_idx() == dev.idx())));
+        assert_eq!(350e3, autd[1].sound_speed);
+        assert!(!autd[1][0].enable);
+
+        autd.configure_gpio(|dev| GPIOPlan {
+            gpio0: DebugType::PwmOut(&dev[1]),
+            ..Default::default()
+        })?;
+
+        #[cfg(feature = "serde")]
+        {
+            let dev: Device = serde_json::from_str(&serde_json::to_string(&autd[1])?)?;
+            assert_eq!(1, dev.idx());
+            assert_eq!(
+                autd[1].iter().collect::<Vec<_>>(),
+                dev.iter().collect::<Vec<_>>()
+            );
+        }
+
+        autd.close()?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_remove_device_failed() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        let center = *autd[1][0].position();
+
+        autd.link_mut().break_down();
+        assert_eq!(
+            Err(AUTDError::from(LinkError::new("broken".to_owned()))),
+            autd.add_device(AUTD3::default())
+        );
+        assert_eq!(2, autd.len());
+        assert_eq!(
+            Err(AUTDError::from(LinkError::new("broken".to_owned()))),
+            autd.remove_device(0).map(|_| ())
+        );
+        assert_eq!(2, autd.len());
+        assert_eq!(
+            vec![0, 1],
+            autd.iter().map(|dev| dev.idx()).collect::<Vec<_>>()
+        );
+        assert_eq!(center, *autd[1][0].position());
+
+        autd.link_mut().repair();
+        autd.send(Null {})?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_poses() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+
+        let pos = Point3::new(10., 20., 30.);
+        let rot = UnitQuaternion::from_axis_angle(&Vector3::z_axis(), PI / 2.);
+        assert_eq!(
+            Err(AUTDError::DeviceIndexOutOfRange(2)),
+            autd.update_poses([(1, pos, rot), (2, pos, rot)])
+        );
+        assert_eq!(Point3::origin(), *autd[1][0].position());
+
+        let version = autd.version();
+        autd.update_poses([(1, pos, rot)])?;
+        assert_eq!(version + 1, autd.version());
+        assert_eq!(Point3::origin(), *autd[0][0].position());
+        assert_eq!(pos, *autd[1][0].position());
+        assert_eq!(rot, *autd[1].rotation());
+        approx::assert_abs_diff_eq!(
+            pos + rot * (autd[0][1].position() - autd[0][0].position()),
+            *autd[1][1].position(),
+            epsilon = 1e-3
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_enabled() -> anyhow::Result<()> {
+        let mut autd = create_controller(3)?;
+
+        assert_eq!(
+            Err(AUTDError::DeviceIndexOutOfRange(3)),
+            autd.set_enabled([0, 3], false)
+        );
+        assert!(autd.iter().all(|dev| dev.enable));
+
+        let version = autd.version();
+        autd.set_enabled([0, 2], false)?;
+        assert!(autd.version() > version);
+        assert_eq!(
+            vec![false, true, false],
+            autd.iter().map(|dev| dev.enable).collect::<Vec<_>>()
+        );
+        autd.send((Static { intensity: 0x80 }, Null {}))?;
+        assert_eq!(
+            vec![vec![0xFF, 0xFF], vec![0x80, 0x80], vec![0xFF, 0xFF]],
+            autd.link()
+                .iter()
+                .map(|cpu| cpu.fpga().modulation_buffer(Segment::S0))
+                .collect::<Vec<_>>()
+        );
+
+        autd.set_enabled([0, 2], true)?;
+        autd.send(Static { intensity: 0x40 })?;
+        assert!(autd
+            .link()
+            .iter()
+            .all(|cpu| cpu.fpga().modulation_buffer(Segment::S0) == vec![0x40, 0x40]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_enabled_ignore_error() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+
+        assert!(autd
+            .group_send(
+                |dev| Some(dev.idx()),
+                HashMap::from([
+                    (0, Null {}.into_boxed()),
+                    (
+                        1,
+                        SwapSegment::FociSTM(Segment::S1, TransitionMode::SyncIdx).into_boxed()
+                    )
+                ])
+            )
+            .is_err());
+
+        autd.set_enabled([1], false)?;
+        autd.send(Static { intensity: 0x80 })?;
+        assert_eq!(
+            vec![0x80, 0x80],
+            autd.link()[0].fpga().modulation_buffer(Segment::S0)
+        );
+
+        autd.set_enabled([1], true)?;
+        autd.send(Static { intensity: 0x40 })?;
+        assert_eq!(
+            vec![0x40, 0x40],
+            autd.link()[1].fpga().modulation_buffer(Segment::S0)
+        );
+
+        Ok(())
+    }
 }
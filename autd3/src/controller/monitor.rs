@@ -0,0 +1,332 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::mpsc,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use autd3_core::link::Link;
+use autd3_driver::{
+    datagram::{Datagram, ReadsFPGAState},
+    error::AUTDDriverError,
+    firmware::{
+        fpga::FPGAState,
+        operation::{Operation, OperationGenerator},
+    },
+};
+
+use crate::gain::Null;
+
+use super::{
+    background::{send_job, Job, SendFuture},
+    Controller,
+};
+
+/// An event reported by [`FPGAStateMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FPGAStateEvent {
+    /// The state of the device is changed. This is reported for any change of the state.
+    Changed(FPGAState),
+    /// The thermal sensor of the device is asserted.
+    ThermalAssert,
+    /// The thermal sensor of the device is deasserted.
+    ThermalDeassert,
+}
+
+/// The option of [`FPGAStateMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FPGAStateMonitorOption {
+    /// The interval of polling the FPGA state.
+    pub interval: Duration,
+    /// The number of consecutive polls in which a new state must be observed before it is reported.
+    pub hysteresis: NonZeroUsize,
+    /// If `true`, [`Null`] is sent to the device whose thermal sensor is asserted.
+    pub null_on_thermal_assert: bool,
+}
+
+impl Default for FPGAStateMonitorOption {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(100),
+            hysteresis: NonZeroUsize::new(3).unwrap(),
+            null_on_thermal_assert: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct StateTracker {
+    reported: Option<FPGAState>,
+    candidate: Option<(FPGAState, usize)>,
+}
+
+impl StateTracker {
+    fn update(&mut self, state: FPGAState, hysteresis: NonZeroUsize) -> Vec<FPGAStateEvent> {
+        if self.reported == Some(state) {
+            self.candidate = None;
+            return Vec::new();
+        }
+        let count = match self.candidate {
+            Some((s, count)) if s == state => count + 1,
+            _ => 1,
+        };
+        if count < hysteresis.get() {
+            self.candidate = Some((state, count));
+            return Vec::new();
+        }
+
+        let was_thermal_assert = self.reported.is_some_and(|s| s.is_thermal_assert());
+        self.reported = Some(state);
+        self.candidate = None;
+
+        let mut events = vec![FPGAStateEvent::Changed(state)];
+        match (was_thermal_assert, state.is_thermal_assert()) {
+            (false, true) => events.push(FPGAStateEvent::ThermalAssert),
+            (true, false) => events.push(FPGAStateEvent::ThermalDeassert),
+            _ => {}
+        }
+        events
+    }
+}
+
+fn poll<L: Link, F: FnMut(usize, FPGAStateEvent)>(
+    autd: &mut Controller<L>,
+    trackers: &mut [StateTracker],
+    option: &FPGAStateMonitorOption,
+    callback: &mut F,
+) {
+    let states = match autd.fpga_state() {
+        Ok(states) => states,
+        Err(e) => {
+            tracing::warn!("Failed to read FPGA state: {}", e);
+            return;
+        }
+    };
+    let thermal_asserted = trackers
+        .iter_mut()
+        .zip(states)
+        .enumerate()
+        .filter_map(|(idx, (tracker, state))| {
+            Some((idx, tracker.update(state?, option.hysteresis)))
+        })
+        .filter_map(|(idx, events)| {
+            events.iter().for_each(|&e| callback(idx, e));
+            events
+                .contains(&FPGAStateEvent::ThermalAssert)
+                .then_some(idx)
+        })
+        .collect::<Vec<_>>();
+
+    if option.null_on_thermal_assert && !thermal_asserted.is_empty() {
+        if let Err(e) = autd.group_send(
+            |dev| thermal_asserted.contains(&dev.idx()).then_some(()),
+            HashMap::from([((), Null)]),
+        ) {
+            tracing::error!("Failed to send Null on thermal assert: {}", e);
+        }
+    }
+}
+
+/// A monitor that owns the [`Controller`] in a background thread and polls the FPGA state.
+///
+/// The FPGA state is polled at [`FPGAStateMonitorOption::interval`] after enabling [`ReadsFPGAState`] for all devices, and the callback is invoked with the index of the device and [`FPGAStateEvent`] when the state changes.
+/// A new state is reported only after it is observed in [`FPGAStateMonitorOption::hysteresis`] consecutive polls, and the first stable state of each device is always reported.
+///
+/// [`Datagram`]s can be sent while monitoring with [`FPGAStateMonitor::enqueue`], as with [`BackgroundSender`].
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// let monitor = FPGAStateMonitor::new(
+///     autd,
+///     FPGAStateMonitorOption {
+///         null_on_thermal_assert: true,
+///         ..Default::default()
+///     },
+///     |idx, event| {
+///         if event == FPGAStateEvent::ThermalAssert {
+///             eprintln!("Device {} is overheated", idx);
+///         }
+///     },
+/// );
+/// let future = monitor.enqueue(Static::default());
+/// # let _ = future;
+/// let autd = monitor.into_controller();
+/// autd.close()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`BackgroundSender`]: super::BackgroundSender
+pub struct FPGAStateMonitor<L: Link + Send + 'static> {
+    tx: Option<mpsc::Sender<Job<L>>>,
+    handle: Option<JoinHandle<Controller<L>>>,
+}
+
+impl<L: Link + Send + 'static> FPGAStateMonitor<L> {
+    /// Creates a new [`FPGAStateMonitor`] and spawns the background thread.
+    pub fn new<F>(mut autd: Controller<L>, option: FPGAStateMonitorOption, mut callback: F) -> Self
+    where
+        F: FnMut(usize, FPGAStateEvent) + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel::<Job<L>>();
+        let handle = std::thread::spawn(move || {
+            if let Err(e) = autd.send(ReadsFPGAState::new(|_| true)) {
+                tracing::error!("Failed to enable reading FPGA state: {}", e);
+            }
+            let mut trackers = vec![StateTracker::default(); autd.len()];
+            let mut next_poll = Instant::now();
+            loop {
+                match rx.recv_timeout(next_poll.saturating_duration_since(Instant::now())) {
+                    Ok(job) => job(&mut autd),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+                if Instant::now() >= next_poll {
+                    poll(&mut autd, &mut trackers, &option, &mut callback);
+                    next_poll = Instant::now() + option.interval;
+                }
+            }
+            autd
+        });
+        Self {
+            tx: Some(tx),
+            handle: Some(handle),
+        }
+    }
+
+    /// Enqueues the [`Datagram`] to be sent by the background thread. See also [`BackgroundSender::enqueue`].
+    ///
+    /// [`BackgroundSender::enqueue`]: super::BackgroundSender::enqueue
+    pub fn enqueue<D: Datagram + Send + 'static>(&self, s: D) -> SendFuture
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let (job, future) = send_job(s);
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(job);
+        }
+        future
+    }
+
+    /// Stops monitoring after all enqueued [`Datagram`]s are sent, and returns the [`Controller`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the background thread panicked.
+    pub fn into_controller(mut self) -> Controller<L> {
+        self.tx.take();
+        match self.handle.take().unwrap().join() {
+            Ok(autd) => autd,
+            Err(e) => std::panic::resume_unwind(e),
+        }
+    }
+}
+
+impl<L: Link + Send + 'static> Drop for FPGAStateMonitor<L> {
+    fn drop(&mut self) {
+        self.tx.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_core::link::RxMessage;
+    use autd3_driver::firmware::fpga::{Drive, EmitIntensity, Phase, Segment};
+
+    use crate::{controller::tests::create_controller, gain::Uniform};
+
+    use super::*;
+
+    fn state(thermal: bool) -> FPGAState {
+        FPGAState::from_rx(&RxMessage::new(0x88 | thermal as u8, 0)).unwrap()
+    }
+
+    #[test]
+    fn tracker() {
+        let hysteresis = NonZeroUsize::new(2).unwrap();
+        let mut tracker = StateTracker::default();
+
+        assert!(tracker.update(state(false), hysteresis).is_empty());
+        assert_eq!(
+            vec![FPGAStateEvent::Changed(state(false))],
+            tracker.update(state(false), hysteresis)
+        );
+        assert!(tracker.update(state(false), hysteresis).is_empty());
+
+        assert!(tracker.update(state(true), hysteresis).is_empty());
+        assert!(tracker.update(state(false), hysteresis).is_empty());
+        assert!(tracker.update(state(true), hysteresis).is_empty());
+        assert_eq!(
+            vec![
+                FPGAStateEvent::Changed(state(true)),
+                FPGAStateEvent::ThermalAssert
+            ],
+            tracker.update(state(true), hysteresis)
+        );
+
+        assert!(tracker.update(state(false), hysteresis).is_empty());
+        assert_eq!(
+            vec![
+                FPGAStateEvent::Changed(state(false)),
+                FPGAStateEvent::ThermalDeassert
+            ],
+            tracker.update(state(false), hysteresis)
+        );
+    }
+
+    #[test]
+    fn null_on_thermal_assert() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        autd.send(Uniform {
+            intensity: EmitIntensity::MAX,
+            phase: Phase::ZERO,
+        })?;
+        autd.link_mut()[0].fpga_mut().assert_thermal_sensor();
+
+        let (tx, rx) = mpsc::channel();
+        let monitor = FPGAStateMonitor::new(
+            autd,
+            FPGAStateMonitorOption {
+                interval: Duration::from_millis(1),
+                hysteresis: NonZeroUsize::new(2).unwrap(),
+                null_on_thermal_assert: true,
+            },
+            move |idx, event| {
+                let _ = tx.send((idx, event));
+            },
+        );
+        let events = (0..3)
+            .map(|_| rx.recv_timeout(Duration::from_secs(1)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let autd = monitor.into_controller();
+
+        assert!(events.contains(&(0, FPGAStateEvent::ThermalAssert)));
+        assert!(events
+            .iter()
+            .all(|&(idx, e)| idx == 0 || e != FPGAStateEvent::ThermalAssert));
+        assert_eq!(
+            vec![Drive::NULL; autd[0].num_transducers()],
+            autd.link()[0].fpga().drives_at(Segment::S0, 0)
+        );
+        assert!(autd.link()[1]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == EmitIntensity::MAX));
+
+        Ok(())
+    }
+}
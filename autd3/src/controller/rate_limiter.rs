@@ -0,0 +1,372 @@
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use autd3_core::link::Link;
+use autd3_driver::{
+    datagram::Datagram,
+    defined::Freq,
+    error::AUTDDriverError,
+    firmware::operation::{Operation, OperationGenerator},
+};
+
+use crate::error::AUTDError;
+
+use super::Controller;
+
+type Pending<L> = Box<dyn FnOnce(&mut Controller<L>) -> Result<(), AUTDDriverError>>;
+
+/// A rate limiter for sending [`Datagram`]s.
+///
+/// The rate is limited for each type of [`Datagram`]. If a [`Datagram`] is sent faster than the maximum rate, it is not sent immediately but kept as pending, and the pending one is replaced by the newer one of the same type.
+/// The pending [`Datagram`]s are sent by [`RateLimiter::flush`] after the interval has elapsed.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+/// use autd3::driver::datagram::FixedCompletionSteps;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// let mut limiter = RateLimiter::new(60. * Hz)?;
+/// limiter.set_max_rate::<Silencer<FixedCompletionSteps>>(None)?;
+///
+/// for i in 0..10 {
+///     limiter.send(
+///         &mut autd,
+///         Focus {
+///             pos: Point3::new(i as f32 * mm, 0., 150. * mm),
+///             option: FocusOption::default(),
+///         },
+///     )?;
+/// }
+/// limiter.flush(&mut autd)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RateLimiter<L: Link> {
+    schedule: RateSchedule<Pending<L>>,
+}
+
+impl<L: Link> std::fmt::Debug for RateLimiter<L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.schedule.fmt("RateLimiter", f)
+    }
+}
+
+pub(crate) struct RateSchedule<P> {
+    default_interval: Option<Duration>,
+    intervals: HashMap<TypeId, Option<Duration>>,
+    last_sent: HashMap<TypeId, Instant>,
+    pending: Vec<(TypeId, P)>,
+}
+
+fn interval(max_rate: Freq<f32>) -> Result<Duration, AUTDError> {
+    let hz = max_rate.hz();
+    if !(hz.is_finite() && hz > 0.) {
+        return Err(AUTDError::InvalidMaxRate(max_rate));
+    }
+    Duration::try_from_secs_f32(1. / hz).map_err(|_| AUTDError::InvalidMaxRate(max_rate))
+}
+
+impl<P> RateSchedule<P> {
+    pub(crate) fn new(max_rate: Option<Freq<f32>>) -> Result<Self, AUTDError> {
+        Ok(Self {
+            default_interval: max_rate.map(interval).transpose()?,
+            intervals: HashMap::new(),
+            last_sent: HashMap::new(),
+            pending: Vec::new(),
+        })
+    }
+
+    pub(crate) fn set_max_rate<D: 'static>(
+        &mut self,
+        max_rate: Option<Freq<f32>>,
+    ) -> Result<(), AUTDError> {
+        self.intervals
+            .insert(TypeId::of::<D>(), max_rate.map(interval).transpose()?);
+        Ok(())
+    }
+
+    fn interval_of(&self, key: &TypeId) -> Option<Duration> {
+        self.intervals
+            .get(key)
+            .copied()
+            .unwrap_or(self.default_interval)
+    }
+
+    fn is_ready(&self, key: &TypeId, now: Instant) -> bool {
+        match (self.interval_of(key), self.last_sent.get(key)) {
+            (Some(interval), Some(last)) => now.duration_since(*last) >= interval,
+            _ => true,
+        }
+    }
+
+    pub(crate) fn num_pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    // Drops the pending datagram of type `D`, and returns `true` if a new one can be sent now.
+    pub(crate) fn acquire<D: 'static>(&mut self) -> bool {
+        let key = TypeId::of::<D>();
+        let now = Instant::now();
+        self.pending.retain(|(k, _)| *k != key);
+        if self.is_ready(&key, now) {
+            self.last_sent.insert(key, now);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn push<D: 'static>(&mut self, pending: P) {
+        tracing::trace!("Datagram is pending due to rate limit");
+        self.pending.push((TypeId::of::<D>(), pending));
+    }
+
+    // Takes the pending datagrams whose interval has elapsed.
+    pub(crate) fn take_ready(&mut self) -> std::vec::IntoIter<(TypeId, P)> {
+        let now = Instant::now();
+        let (ready, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|(k, _)| self.is_ready(k, now));
+        self.pending = pending;
+        ready
+            .iter()
+            .for_each(|(k, _)| _ = self.last_sent.insert(*k, now));
+        ready.into_iter()
+    }
+
+    // Puts back the datagrams not sent by `flush` to the front of the queue.
+    pub(crate) fn restore(&mut self, remaining: impl Iterator<Item = (TypeId, P)>) {
+        let pending = std::mem::take(&mut self.pending);
+        self.pending = remaining.chain(pending).collect();
+    }
+
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.pending
+            .iter()
+            .filter_map(|(k, _)| Some(*self.last_sent.get(k)? + self.interval_of(k)?))
+            .min()
+    }
+
+    pub(crate) fn fmt(&self, name: &str, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct(name)
+            .field("default_interval", &self.default_interval)
+            .field("intervals", &self.intervals)
+            .field("pending", &self.pending.len())
+            .finish()
+    }
+}
+
+impl<L: Link> RateLimiter<L> {
+    /// Creates a new [`RateLimiter`] with the maximum rate applied to all types of [`Datagram`].
+    ///
+    /// Returns [`AUTDError::InvalidMaxRate`] if `max_rate` is not positive and finite.
+    pub fn new(max_rate: Freq<f32>) -> Result<Self, AUTDError> {
+        Ok(Self {
+            schedule: RateSchedule::new(Some(max_rate))?,
+        })
+    }
+
+    /// Creates a new [`RateLimiter`] without the default limit. Only the types set by [`RateLimiter::set_max_rate`] are limited.
+    pub fn unlimited() -> Self {
+        Self {
+            schedule: RateSchedule::new(None).unwrap(),
+        }
+    }
+
+    /// Sets the maximum rate for the [`Datagram`] type `D`. If `None`, `D` is not limited.
+    ///
+    /// Returns [`AUTDError::InvalidMaxRate`] if `max_rate` is not positive and finite.
+    pub fn set_max_rate<D: 'static>(
+        &mut self,
+        max_rate: Option<Freq<f32>>,
+    ) -> Result<(), AUTDError> {
+        self.schedule.set_max_rate::<D>(max_rate)
+    }
+
+    /// Returns the number of pending [`Datagram`]s.
+    pub fn num_pending(&self) -> usize {
+        self.schedule.num_pending()
+    }
+
+    /// Sends the [`Datagram`] if the interval has elapsed since the last transmission of the same type. Otherwise, the [`Datagram`] is kept as pending and replaces the previous pending one of the same type.
+    ///
+    /// Returns `true` if the [`Datagram`] is sent immediately.
+    pub fn send<D: Datagram + 'static>(
+        &mut self,
+        autd: &mut Controller<L>,
+        s: D,
+    ) -> Result<bool, AUTDDriverError>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        if self.schedule.acquire::<D>() {
+            autd.send(s)?;
+            Ok(true)
+        } else {
+            self.schedule.push::<D>(Box::new(move |autd| autd.send(s)));
+            Ok(false)
+        }
+    }
+
+    /// Sends the pending [`Datagram`]s whose interval has elapsed.
+    ///
+    /// If sending a [`Datagram`] fails, the error is returned and the [`Datagram`]s after the failed one are kept as pending.
+    pub fn flush(&mut self, autd: &mut Controller<L>) -> Result<(), AUTDDriverError> {
+        let mut ready = self.schedule.take_ready();
+        while let Some((_, f)) = ready.next() {
+            if let Err(e) = f(autd) {
+                self.schedule.restore(ready);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the earliest time when a pending [`Datagram`] can be sent by [`RateLimiter::flush`].
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.schedule.next_deadline()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{
+        datagram::{FixedCompletionSteps, Silencer},
+        defined::Hz,
+        firmware::fpga::{EmitIntensity, Phase, Segment},
+    };
+
+    use crate::{controller::tests::create_controller, gain::Uniform};
+
+    use super::*;
+
+    fn uniform(intensity: u8) -> Uniform {
+        Uniform {
+            intensity: EmitIntensity(intensity),
+            phase: Phase::ZERO,
+        }
+    }
+
+    fn intensity(autd: &Controller<crate::link::Audit>) -> EmitIntensity {
+        autd.link()[0].fpga().drives_at(Segment::S0, 0)[0].intensity
+    }
+
+    #[test]
+    fn coalesce() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let mut limiter = RateLimiter::new(10. * Hz)?;
+
+        assert!(limiter.send(&mut autd, uniform(0x01))?);
+        assert_eq!(EmitIntensity(0x01), intensity(&autd));
+        assert!(limiter.next_deadline().is_none());
+
+        assert!(!limiter.send(&mut autd, uniform(0x02))?);
+        assert!(!limiter.send(&mut autd, uniform(0x03))?);
+        assert_eq!(1, limiter.num_pending());
+        assert_eq!(EmitIntensity(0x01), intensity(&autd));
+
+        limiter.flush(&mut autd)?;
+        assert_eq!(1, limiter.num_pending());
+        assert_eq!(EmitIntensity(0x01), intensity(&autd));
+
+        std::thread::sleep(
+            limiter
+                .next_deadline()
+                .unwrap()
+                .saturating_duration_since(Instant::now()),
+        );
+        limiter.flush(&mut autd)?;
+        assert_eq!(0, limiter.num_pending());
+        assert_eq!(EmitIntensity(0x03), intensity(&autd));
+
+        Ok(())
+    }
+
+    #[test]
+    fn per_type() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let mut limiter = RateLimiter::new(1. * Hz)?;
+        limiter.set_max_rate::<Uniform>(None)?;
+
+        assert!(limiter.send(&mut autd, uniform(0x01))?);
+        assert!(limiter.send(&mut autd, uniform(0x02))?);
+        assert_eq!(EmitIntensity(0x02), intensity(&autd));
+
+        assert!(limiter.send(&mut autd, Silencer::<FixedCompletionSteps>::default())?);
+        assert!(!limiter.send(&mut autd, Silencer::<FixedCompletionSteps>::default())?);
+        assert_eq!(1, limiter.num_pending());
+
+        Ok(())
+    }
+
+    #[test]
+    fn unlimited() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let mut limiter = RateLimiter::unlimited();
+        limiter.set_max_rate::<Uniform>(Some(1. * Hz))?;
+
+        assert!(limiter.send(&mut autd, Silencer::<FixedCompletionSteps>::default())?);
+        assert!(limiter.send(&mut autd, Silencer::<FixedCompletionSteps>::default())?);
+
+        assert!(limiter.send(&mut autd, uniform(0x01))?);
+        assert!(!limiter.send(&mut autd, uniform(0x02))?);
+        assert_eq!(EmitIntensity(0x01), intensity(&autd));
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(0. * Hz)]
+    #[case(-1. * Hz)]
+    #[case(f32::NAN * Hz)]
+    #[case(f32::INFINITY * Hz)]
+    #[case(f32::MIN_POSITIVE / 4. * Hz)]
+    #[test]
+    fn invalid_max_rate(#[case] max_rate: Freq<f32>) {
+        assert!(matches!(
+            RateLimiter::<crate::link::Audit>::new(max_rate),
+            Err(AUTDError::InvalidMaxRate(_))
+        ));
+        assert!(matches!(
+            RateLimiter::<crate::link::Audit>::unlimited().set_max_rate::<Uniform>(Some(max_rate)),
+            Err(AUTDError::InvalidMaxRate(_))
+        ));
+    }
+
+    #[test]
+    fn flush_failed() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let mut limiter = RateLimiter::new(100. * Hz)?;
+
+        assert!(limiter.send(&mut autd, uniform(0x01))?);
+        assert!(limiter.send(&mut autd, Silencer::<FixedCompletionSteps>::default())?);
+        assert!(!limiter.send(&mut autd, uniform(0x02))?);
+        assert!(!limiter.send(&mut autd, Silencer::<FixedCompletionSteps>::default())?);
+        assert_eq!(2, limiter.num_pending());
+
+        std::thread::sleep(Duration::from_millis(20));
+        autd.link_mut().down();
+        assert_eq!(
+            Err(AUTDDriverError::SendDataFailed),
+            limiter.flush(&mut autd)
+        );
+        assert_eq!(1, limiter.num_pending());
+
+        std::thread::sleep(Duration::from_millis(20));
+        autd.link_mut().up();
+        limiter.flush(&mut autd)?;
+        assert_eq!(0, limiter.num_pending());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use std::{collections::VecDeque, time::Duration};
+
+/// The adaptive timeout based on the measured round trip time of the link. See also [`SenderOption::adaptive_timeout`].
+///
+/// The timeout is the maximum of the latest [`window`] round trip times multiplied by [`multiplier`], clamped to [`min`]..=[`max`].
+/// The round trip time is the time from sending a frame to confirming that all enabled devices have processed it.
+/// If no round trip has been measured yet, [`max`] is used.
+///
+/// [`SenderOption::adaptive_timeout`]: crate::controller::SenderOption::adaptive_timeout
+/// [`window`]: AdaptiveTimeout::window
+/// [`multiplier`]: AdaptiveTimeout::multiplier
+/// [`min`]: AdaptiveTimeout::min
+/// [`max`]: AdaptiveTimeout::max
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveTimeout {
+    /// The minimum timeout.
+    pub min: Duration,
+    /// The maximum timeout.
+    pub max: Duration,
+    /// The multiplier of the measured round trip time.
+    pub multiplier: u32,
+    /// The number of the latest round trips used for the estimation.
+    pub window: usize,
+}
+
+impl Default for AdaptiveTimeout {
+    fn default() -> Self {
+        Self {
+            min: Duration::from_millis(10),
+            max: Duration::from_millis(200),
+            multiplier: 4,
+            window: 32,
+        }
+    }
+}
+
+impl AdaptiveTimeout {
+    pub(crate) fn timeout(&self, rtt: &RttTracker) -> Duration {
+        rtt.latest(self.window).max().map_or(self.max, |rtt| {
+            (rtt * self.multiplier).clamp(self.min, self.max)
+        })
+    }
+}
+
+/// The latest round trip times of the link.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RttTracker {
+    history: VecDeque<Duration>,
+}
+
+impl RttTracker {
+    pub(crate) fn record(&mut self, rtt: Duration) {
+        if self.history.len() == HISTORY_CAPACITY {
+            self.history.pop_front();
+        }
+        self.history.push_back(rtt);
+    }
+
+    fn latest(&self, n: usize) -> impl Iterator<Item = Duration> + '_ {
+        self.history.iter().rev().take(n).copied()
+    }
+}
+
+const HISTORY_CAPACITY: usize = 1024;
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{autd3_device::AUTD3, error::AUTDDriverError};
+    use autd3_firmware_emulator::TimingModel;
+
+    use super::*;
+
+    use crate::{
+        controller::{Controller, SenderOption, SpinSleeper},
+        link::{Audit, AuditOption},
+        modulation::Static,
+    };
+
+    #[rstest::rstest]
+    #[case(Duration::from_millis(200), &[])]
+    #[case(Duration::from_millis(10), &[1])]
+    #[case(Duration::from_millis(20), &[5])]
+    #[case(Duration::from_millis(20), &[1, 5, 2])]
+    #[case(Duration::from_millis(200), &[100])]
+    #[case(Duration::from_millis(12), &[50, 3, 3])]
+    #[test]
+    fn timeout(#[case] expect: Duration, #[case] rtt_ms: &[u64]) {
+        let option = AdaptiveTimeout {
+            window: 2,
+            ..Default::default()
+        };
+        let mut rtt = RttTracker::default();
+        rtt_ms
+            .iter()
+            .for_each(|&ms| rtt.record(Duration::from_millis(ms)));
+        assert_eq!(expect, option.timeout(&rtt));
+    }
+
+    #[test]
+    fn history_capacity() {
+        let mut rtt = RttTracker::default();
+        (0..HISTORY_CAPACITY + 1).for_each(|i| rtt.record(Duration::from_nanos(i as _)));
+        assert_eq!(HISTORY_CAPACITY, rtt.history.len());
+        assert_eq!(Some(Duration::from_nanos(1)), rtt.history.front().copied());
+    }
+
+    #[test]
+    fn send() -> anyhow::Result<()> {
+        let mut autd = Controller::open(
+            [AUTD3::default()],
+            Audit::new(AuditOption {
+                timing_model: Some(TimingModel {
+                    default_delay: Duration::from_millis(5),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        )?;
+        autd.rtt = RttTracker::default();
+
+        autd.sender(SenderOption::<SpinSleeper> {
+            timeout: Some(Duration::from_millis(1)),
+            adaptive_timeout: Some(AdaptiveTimeout {
+                min: Duration::ZERO,
+                max: Duration::from_secs(1),
+                multiplier: 2,
+                window: 4,
+            }),
+            ..Default::default()
+        })
+        .send(Static::default())?;
+        assert!(!autd.rtt.history.is_empty());
+        assert!(autd
+            .rtt
+            .history
+            .iter()
+            .all(|&rtt| Duration::from_millis(5) <= rtt));
+
+        assert_eq!(
+            Err(AUTDDriverError::ConfirmResponseFailed),
+            autd.sender(SenderOption::<SpinSleeper> {
+                timeout: Some(Duration::from_secs(1)),
+                adaptive_timeout: Some(AdaptiveTimeout {
+                    min: Duration::ZERO,
+                    max: Duration::from_millis(1),
+                    multiplier: 2,
+                    window: 4,
+                }),
+                ..Default::default()
+            })
+            .send(Static::default())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn recover_from_timeout() -> anyhow::Result<()> {
+        let mut autd = Controller::open(
+            [AUTD3::default()],
+            Audit::new(AuditOption {
+                timing_model: Some(TimingModel {
+                    default_delay: Duration::from_millis(5),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        )?;
+        autd.rtt = RttTracker::default();
+        (0..4).for_each(|_| autd.rtt.record(Duration::from_millis(1)));
+
+        let option = SenderOption::<SpinSleeper> {
+            timeout: Some(Duration::from_secs(1)),
+            adaptive_timeout: Some(AdaptiveTimeout {
+                min: Duration::ZERO,
+                max: Duration::from_secs(1),
+                multiplier: 2,
+                window: 4,
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(AUTDDriverError::ConfirmResponseFailed),
+            autd.sender(option).send(Static::default())
+        );
+        assert!(autd
+            .rtt
+            .latest(1)
+            .all(|rtt| Duration::from_millis(2) <= rtt));
+
+        assert!((0..4).any(|_| autd.sender(option).send(Static::default()).is_ok()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,302 @@
+use std::{fmt::Debug, time::Duration};
+
+use autd3_core::{datagram::Datagram, defined::ultrasound_freq, geometry::Geometry};
+use autd3_driver::{
+    datagram::{FixedCompletionSteps, FixedUpdateRate, Silencer},
+    error::AUTDDriverError,
+    firmware::{
+        cpu::TxMessage,
+        operation::{Operation, OperationGenerator, OperationHandler},
+    },
+};
+use zerocopy::FromZeros;
+
+use super::SenderOption;
+
+/// A trait to get the worst-case completion time of the silencer.
+pub trait SilencerLatency {
+    /// Returns the time to complete the largest change of the intensity or phase.
+    fn completion_time(&self) -> Duration;
+}
+
+fn periods(n: u32) -> Duration {
+    Duration::from_nanos((n as u64 * 1_000_000_000).div_ceil(ultrasound_freq().hz() as u64))
+}
+
+impl SilencerLatency for FixedCompletionSteps {
+    fn completion_time(&self) -> Duration {
+        periods(self.intensity.get().max(self.phase.get()) as _)
+    }
+}
+
+impl SilencerLatency for FixedUpdateRate {
+    fn completion_time(&self) -> Duration {
+        // The intensity changes by 255 at most and the phase changes by 128 at most, both in 1/256 units per period.
+        let intensity = (0xFFu32 << 8).div_ceil(self.intensity.get() as u32);
+        let phase = (0x80u32 << 8).div_ceil(self.phase.get() as u32);
+        periods(intensity.max(phase))
+    }
+}
+
+autd3_driver::with_fixed_completion_time! {
+    impl SilencerLatency for autd3_driver::datagram::FixedCompletionTime {
+        fn completion_time(&self) -> Duration {
+            self.intensity.max(self.phase)
+        }
+    }
+
+    impl SilencerLatency for Silencer<autd3_driver::datagram::FixedCompletionTime> {
+        fn completion_time(&self) -> Duration {
+            self.config.completion_time()
+        }
+    }
+}
+
+impl SilencerLatency for Silencer<FixedCompletionSteps> {
+    fn completion_time(&self) -> Duration {
+        self.config.completion_time()
+    }
+}
+
+impl SilencerLatency for Silencer<FixedUpdateRate> {
+    fn completion_time(&self) -> Duration {
+        self.config.completion_time()
+    }
+}
+
+/// The characteristics of the link and the devices to estimate the latency by [`Sender::estimate_latency`].
+///
+/// [`Sender::estimate_latency`]: crate::controller::Sender::estimate_latency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyModel {
+    /// The measured round trip time of the link, i.e., the time from sending a frame to receiving the response to it.
+    ///
+    /// This depends on the link; for example, it is typically about 1 ms or less for a local EtherCAT link such as TwinCAT and includes the network delay for remote links such as Simulator or RemoteTwinCAT.
+    pub rtt: Duration,
+    /// The completion time of the silencer configured on the devices. See [`SilencerLatency`].
+    pub silencer: Duration,
+}
+
+impl Default for LatencyModel {
+    fn default() -> Self {
+        Self {
+            rtt: Duration::from_millis(1),
+            silencer: Silencer::default().completion_time(),
+        }
+    }
+}
+
+/// The estimated latency from calling `send` to the change of the acoustic output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyEstimate {
+    /// The number of frames to send the [`Datagram`].
+    pub frames: usize,
+    /// The time until the last frame arrives at the devices, when the output starts to change.
+    pub transmission: Duration,
+    /// The time for the silencer to complete the change.
+    pub silencer: Duration,
+}
+
+impl LatencyEstimate {
+    /// The total latency, i.e., the sum of [`LatencyEstimate::transmission`] and [`LatencyEstimate::silencer`].
+    pub fn total(&self) -> Duration {
+        self.transmission + self.silencer
+    }
+
+    /// Returns `true` if the total latency is within the `budget`.
+    pub fn within(&self, budget: Duration) -> bool {
+        self.total() <= budget
+    }
+}
+
+impl LatencyModel {
+    /// Estimates the latency to send `frames` frames with the `option` of the [`Sender`] and the `timeout`.
+    ///
+    /// Each frame is assumed to arrive at the devices after half of [`LatencyModel::rtt`].
+    /// If `timeout` is not zero, the response to each frame is polled every [`SenderOption::receive_interval`], so the next frame is sent after the response and the [`SenderOption::send_interval`] elapse.
+    ///
+    /// [`Sender`]: crate::controller::Sender
+    pub fn estimate<S: Debug>(
+        &self,
+        frames: usize,
+        option: &SenderOption<S>,
+        timeout: Duration,
+    ) -> LatencyEstimate {
+        let confirm = if timeout.is_zero() {
+            Duration::ZERO
+        } else if option.receive_interval.is_zero() {
+            self.rtt
+        } else {
+            option.receive_interval
+                * (self
+                    .rtt
+                    .as_nanos()
+                    .div_ceil(option.receive_interval.as_nanos()) as u32)
+        };
+        let last_start = (1..frames).fold(Duration::ZERO, |start, i| {
+            (start + confirm).max(option.send_interval * i as u32)
+        });
+        LatencyEstimate {
+            frames,
+            transmission: last_start + self.rtt / 2,
+            silencer: self.silencer,
+        }
+    }
+}
+
+pub(crate) fn count_frames<D: Datagram>(
+    geometry: &Geometry,
+    s: D,
+    parallel: bool,
+) -> Result<usize, AUTDDriverError>
+where
+    AUTDDriverError: From<D::Error>,
+    D::G: OperationGenerator,
+    AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+        + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+{
+    let mut operations =
+        OperationHandler::generate(s.operation_generator(geometry, parallel)?, geometry);
+    let mut tx = vec![TxMessage::new_zeroed(); geometry.len()];
+    let mut frames = 0;
+    loop {
+        OperationHandler::pack(&mut operations, geometry, &mut tx, parallel)?;
+        frames += 1;
+        if OperationHandler::is_done(&operations) {
+            return Ok(frames);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+
+    use autd3_core::derive::Segment;
+
+    use super::*;
+
+    use crate::{
+        controller::{tests::create_controller, SpinSleeper},
+        modulation::{Custom, Static},
+    };
+
+    #[rstest::rstest]
+    #[case(Duration::from_micros(250), FixedCompletionSteps { intensity: NonZeroU16::new(10).unwrap(), phase: NonZeroU16::new(5).unwrap(), strict_mode: true })]
+    #[case(Duration::from_millis(1), FixedCompletionSteps { intensity: NonZeroU16::new(10).unwrap(), phase: NonZeroU16::new(40).unwrap(), strict_mode: true })]
+    #[case(Duration::from_micros(25), FixedCompletionSteps { intensity: NonZeroU16::MIN, phase: NonZeroU16::MIN, strict_mode: true })]
+    #[test]
+    fn completion_steps(#[case] expect: Duration, #[case] config: FixedCompletionSteps) {
+        assert_eq!(expect, config.completion_time());
+    }
+
+    #[rstest::rstest]
+    #[case(Duration::from_micros(25 * 255), 256, 256)]
+    #[case(Duration::from_micros(25 * 256), 256, 128)]
+    #[case(Duration::from_micros(25), 0xFFFF, 0xFFFF)]
+    #[test]
+    fn update_rate(#[case] expect: Duration, #[case] intensity: u16, #[case] phase: u16) {
+        assert_eq!(
+            expect,
+            Silencer {
+                config: FixedUpdateRate {
+                    intensity: NonZeroU16::new(intensity).unwrap(),
+                    phase: NonZeroU16::new(phase).unwrap(),
+                },
+                target: Default::default(),
+            }
+            .completion_time()
+        );
+    }
+
+    #[rstest::rstest]
+    // With confirmation, each frame waits for the response polled every 1 ms.
+    #[case(
+        Duration::from_micros(500),
+        1,
+        Duration::from_millis(1),
+        Duration::from_millis(1),
+        Duration::from_millis(1)
+    )]
+    #[case(
+        Duration::from_micros(2500),
+        3,
+        Duration::from_millis(1),
+        Duration::from_millis(1),
+        Duration::from_millis(1)
+    )]
+    #[case(
+        Duration::from_micros(4750),
+        3,
+        Duration::from_micros(1500),
+        Duration::from_millis(1),
+        Duration::from_millis(1)
+    )]
+    #[case(
+        Duration::from_micros(3750),
+        3,
+        Duration::from_micros(1500),
+        Duration::ZERO,
+        Duration::from_millis(1)
+    )]
+    // Without confirmation, frames are sent every `send_interval`.
+    #[case(
+        Duration::from_micros(2750),
+        3,
+        Duration::from_micros(1500),
+        Duration::from_millis(1),
+        Duration::ZERO
+    )]
+    #[test]
+    fn estimate(
+        #[case] expect: Duration,
+        #[case] frames: usize,
+        #[case] rtt: Duration,
+        #[case] receive_interval: Duration,
+        #[case] timeout: Duration,
+    ) {
+        let model = LatencyModel {
+            rtt,
+            silencer: Duration::from_millis(1),
+        };
+        let option = SenderOption::<SpinSleeper> {
+            receive_interval,
+            ..Default::default()
+        };
+        let estimate = model.estimate(frames, &option, timeout);
+        assert_eq!(frames, estimate.frames);
+        assert_eq!(expect, estimate.transmission);
+        assert_eq!(expect + Duration::from_millis(1), estimate.total());
+        assert!(estimate.within(estimate.total()));
+        assert!(!estimate.within(estimate.total() - Duration::from_nanos(1)));
+    }
+
+    #[test]
+    fn estimate_latency() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        let model = LatencyModel::default();
+        let sender = autd.sender(SenderOption::<SpinSleeper>::default());
+
+        let single = sender.estimate_latency(Static { intensity: 0x80 }, &model)?;
+        assert_eq!(1, single.frames);
+        assert_eq!(Duration::from_micros(500), single.transmission);
+        assert_eq!(Duration::from_millis(1), single.silencer);
+
+        let multiple = sender.estimate_latency(
+            Custom {
+                buffer: vec![0xFF; 4000],
+                sampling_config: autd3_driver::firmware::fpga::SamplingConfig::FREQ_MIN,
+            },
+            &model,
+        )?;
+        assert!(multiple.frames > 1);
+        assert!(multiple.total() > single.total());
+
+        assert_eq!(
+            vec![0xFF, 0xFF],
+            autd.link()[0].fpga().modulation_buffer(Segment::S0)
+        );
+
+        Ok(())
+    }
+}
@@ -1,27 +1,55 @@
+mod adaptive_timeout;
+mod latency;
+mod msg_id;
+mod packing;
+mod power_budget;
+mod sequence;
 pub(crate) mod sleep;
-
+mod split;
+pub(crate) mod telemetry;
+
+pub use adaptive_timeout::AdaptiveTimeout;
+pub(crate) use adaptive_timeout::RttTracker;
+pub(crate) use latency::count_frames;
+pub use latency::{LatencyEstimate, LatencyModel, SilencerLatency};
+pub use msg_id::MsgIdPolicy;
+pub use packing::PackingReport;
+pub(crate) use power_budget::PowerMonitor;
+pub use power_budget::{PowerBudget, PowerBudgetAction};
+pub use sequence::Sequence;
 use sleep::Sleep;
 #[cfg(target_os = "windows")]
 pub use sleep::WaitableSleeper;
 pub use sleep::{SpinSleeper, StdSleeper};
 pub use spin_sleep::SpinStrategy;
+pub use split::ModulationSplit;
+#[cfg(feature = "async")]
+pub(crate) use split::{split, to_instant};
 
 use std::{
     fmt::Debug,
     time::{Duration, Instant},
 };
 
-use autd3_core::{datagram::Datagram, geometry::Geometry, link::Link};
+use autd3_core::{
+    datagram::{Datagram, DatagramL, LoopBehavior, Segment, TransitionMode},
+    ethercat::DcSysTime,
+    geometry::Geometry,
+    link::Link,
+};
 use autd3_driver::{
+    datagram::WithLoopBehavior,
     error::AUTDDriverError,
     firmware::{
-        cpu::{check_if_msg_is_processed, RxMessage, TxMessage},
+        cpu::{RxMessage, TxMessage},
         operation::{Operation, OperationGenerator, OperationHandler},
     },
 };
 
 use itertools::Itertools;
 
+use super::{ConfigTracker, EventLog};
+
 /// The parallel processing mode.
 #[repr(u8)]
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
@@ -60,6 +88,21 @@ pub struct SenderOption<S: Debug> {
     ///
     /// [`Datagram`]: autd3_driver::datagram::Datagram
     pub parallel: ParallelMode,
+    /// The safety policy to limit the output power of each device. If `None`, the output power is not limited.
+    pub power_budget: Option<PowerBudget>,
+    /// The behavior of [`Sender::send_modulation`] for a modulation exceeding the buffer size of the firmware.
+    pub modulation_split: ModulationSplit,
+    /// If `Some`, a non-zero timeout is replaced by the one estimated from the measured round trip time of the link. See [`AdaptiveTimeout`].
+    pub adaptive_timeout: Option<AdaptiveTimeout>,
+    /// The policy to confirm the responses from the devices. The default value is [`MsgIdPolicy::Strict`].
+    pub msg_id_policy: MsgIdPolicy,
+    /// If `true`, the [`PackingReport`] of each [`Datagram`] is recorded and emitted via tracing. The default value is `false`.
+    ///
+    /// The last report can be retrieved by [`Controller::packing_report`].
+    ///
+    /// [`Datagram`]: autd3_driver::datagram::Datagram
+    /// [`Controller::packing_report`]: crate::controller::Controller::packing_report
+    pub metrics: bool,
     /// The sleeper to manage the sending/receiving timing.
     pub sleeper: S,
 }
@@ -71,6 +114,11 @@ impl<S: Default + Debug> Default for SenderOption<S> {
             receive_interval: Duration::from_millis(1),
             timeout: None,
             parallel: ParallelMode::Auto,
+            power_budget: None,
+            modulation_split: ModulationSplit::Reject,
+            adaptive_timeout: None,
+            msg_id_policy: MsgIdPolicy::Strict,
+            metrics: false,
             sleeper: S::default(),
         }
     }
@@ -82,6 +130,11 @@ pub struct Sender<'a, L: Link, S: Sleep> {
     pub(crate) geometry: &'a mut Geometry,
     pub(crate) tx: &'a mut [TxMessage],
     pub(crate) rx: &'a mut [RxMessage],
+    pub(crate) events: &'a mut EventLog,
+    pub(crate) power: &'a mut PowerMonitor,
+    pub(crate) rtt: &'a mut RttTracker,
+    pub(crate) packing: &'a mut Option<PackingReport>,
+    pub(crate) config: &'a mut ConfigTracker,
     pub(crate) option: SenderOption<S>,
 }
 
@@ -93,8 +146,100 @@ impl<L: Link, S: Sleep> Sender<'_, L, S> {
     /// - 0, this function does not check whether the sent data has been processed by the device.
     ///
     /// The calculation of each [`Datagram`] is executed in parallel for each device if the number of enabled devices is greater than the `parallel_threshold`.
-    #[tracing::instrument(level = "debug", skip(self))]
     pub fn send<D: Datagram>(&mut self, s: D) -> Result<(), AUTDDriverError>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        self.send_with_timeout(s, self.option.timeout)
+    }
+
+    /// Send the [`DatagramL`] to the `segment` of the devices. The segment is switched at the specified system time `time`.
+    ///
+    /// This is equivalent to sending [`WithLoopBehavior`] with [`TransitionMode::SysTime`]. Note that the `segment` must be different from the current segment and `loop_behavior` must be finite.
+    ///
+    /// # Errors
+    ///
+    /// - [`AUTDDriverError::IncompatibleTransitionMode`] if `loop_behavior` is [`LoopBehavior::Infinite`].
+    /// - [`AUTDDriverError::MissTransitionTime`] if `time` has already passed. The devices also return [`AUTDDriverError::Device`] with [`FirmwareErrorCode::MissTransitionTime`] if `time` is too close to the current time.
+    ///
+    /// [`WithLoopBehavior`]: autd3_driver::datagram::WithLoopBehavior
+    /// [`FirmwareErrorCode::MissTransitionTime`]: autd3_driver::error::FirmwareErrorCode::MissTransitionTime
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn send_at<D: DatagramL>(
+        &mut self,
+        s: D,
+        segment: Segment,
+        loop_behavior: LoopBehavior,
+        time: DcSysTime,
+    ) -> Result<(), AUTDDriverError>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        if loop_behavior == LoopBehavior::Infinite {
+            return Err(AUTDDriverError::IncompatibleTransitionMode(
+                segment,
+                loop_behavior,
+                TransitionMode::SysTime(time),
+            ));
+        }
+        if time <= DcSysTime::now() {
+            return Err(AUTDDriverError::MissTransitionTime);
+        }
+        self.send(WithLoopBehavior {
+            inner: s,
+            loop_behavior,
+            segment,
+            transition_mode: Some(TransitionMode::SysTime(time)),
+        })
+    }
+
+    /// Send the [`Datagram`]s to the devices in order.
+    ///
+    /// Each [`Datagram`] is sent as [`Sender::send`]. If sending any [`Datagram`] fails, this function returns the index of the [`Datagram`] and the error immediately and the remaining [`Datagram`]s are not sent.
+    /// To send different types of [`Datagram`]s, use [`IntoBoxedDatagram::into_boxed`] or [`Sequence`].
+    ///
+    /// [`IntoBoxedDatagram::into_boxed`]: autd3_driver::datagram::IntoBoxedDatagram::into_boxed
+    #[tracing::instrument(level = "debug", skip(self, s))]
+    pub fn send_all<D: Datagram>(
+        &mut self,
+        s: impl IntoIterator<Item = D>,
+    ) -> Result<(), (usize, AUTDDriverError)>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        s.into_iter()
+            .enumerate()
+            .try_for_each(|(i, d)| self.send(d).map_err(|e| (i, e)))
+    }
+
+    /// Send the [`Sequence`] to the devices.
+    ///
+    /// The [`Datagram`]s in the [`Sequence`] are sent in order with their own timeouts. If sending any [`Datagram`] fails, this function returns the error immediately and the remaining [`Datagram`]s are not sent.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn send_sequence(&mut self, s: Sequence) -> Result<(), AUTDDriverError> {
+        for (d, timeout) in s.datagrams {
+            self.send_with_timeout(d, timeout.or(self.option.timeout))?;
+        }
+        Ok(())
+    }
+
+    /// Estimates the latency from calling [`Sender::send`] with the [`Datagram`] to the change of the acoustic output under the option of this [`Sender`].
+    ///
+    /// The [`Datagram`] is not sent, but packed to count the number of frames. See [`LatencyModel::estimate`] for the model of the latency.
+    pub fn estimate_latency<D: Datagram>(
+        &self,
+        s: D,
+        model: &LatencyModel,
+    ) -> Result<LatencyEstimate, AUTDDriverError>
     where
         AUTDDriverError: From<D::Error>,
         D::G: OperationGenerator,
@@ -106,7 +251,48 @@ impl<L: Link, S: Sleep> Sender<'_, L, S> {
             .option
             .parallel
             .is_parallel(self.geometry.num_devices(), s.option().parallel_threshold);
-        tracing::debug!("timeout: {:?}, parallel: {:?}", timeout, parallel);
+        let frames = count_frames(self.geometry, s, parallel)?;
+        Ok(model.estimate(frames, &self.option, timeout))
+    }
+
+    fn send_with_timeout<D: Datagram>(
+        &mut self,
+        s: D,
+        timeout: Option<Duration>,
+    ) -> Result<(), AUTDDriverError>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let span = telemetry::send_span::<D>(self.geometry);
+        let _enter = span.enter();
+        let start = Instant::now();
+        let res = self.send_datagram(s, timeout);
+        telemetry::record_result(&span, start, &res);
+        self.events
+            .push_send(std::any::type_name::<D>(), start, &res);
+        res
+    }
+
+    fn send_datagram<D: Datagram>(
+        &mut self,
+        s: D,
+        timeout: Option<Duration>,
+    ) -> Result<(), AUTDDriverError>
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let timeout = timeout.unwrap_or(s.option().timeout);
+        let parallel = self
+            .option
+            .parallel
+            .is_parallel(self.geometry.num_devices(), s.option().parallel_threshold);
+        telemetry::record_option(timeout, parallel);
 
         self.send_impl(
             OperationHandler::generate(
@@ -131,38 +317,145 @@ impl<L: Link, S: Sleep> Sender<'_, L, S> {
     {
         self.link.update(self.geometry)?;
 
+        let timeout = match self.option.adaptive_timeout {
+            Some(adaptive) if !timeout.is_zero() => adaptive.timeout(self.rtt),
+            _ => timeout,
+        };
+
+        let mut report = self
+            .option
+            .metrics
+            .then(|| PackingReport::new(self.geometry.len()));
+        // With a power budget, all frames are packed in advance so that the budget is checked once for the whole datagram before the first frame is sent.
+        let mut packed = match self.option.power_budget {
+            Some(budget) => {
+                match self.pack_all(&mut operations, parallel, report.as_mut(), &budget) {
+                    Ok(frames) => Some(frames.into_iter()),
+                    Err(e) => {
+                        if let Some(report) = report {
+                            *self.packing = Some(report);
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+            None => None,
+        };
         // We prioritize average behavior for the transmission timing. That is, not the interval from the previous transmission, but ensuring that T/`send_interval` transmissions are performed in a sufficiently long time T.
         // For example, if the `send_interval` is 1ms and it takes 1.5ms to transmit due to some reason, the next transmission will be performed not 1ms later but 0.5ms later.
         let mut send_timing = Instant::now();
-        loop {
-            OperationHandler::pack(&mut operations, self.geometry, self.tx, parallel)?;
+        let mut frames = 0;
+        let res = loop {
+            if let Err(e) = match packed.as_mut().and_then(|packed| packed.next()) {
+                Some(frame) => {
+                    self.tx.clone_from_slice(&frame);
+                    Ok(())
+                }
+                None => self.pack(&mut operations, parallel, report.as_mut()),
+            } {
+                break Err(e);
+            }
 
-            self.send_receive(timeout)?;
+            frames += 1;
+            if let Err(e) = self.send_receive(timeout) {
+                break Err(e);
+            }
 
-            if OperationHandler::is_done(&operations) {
-                return Ok(());
+            if match packed.as_ref() {
+                Some(packed) => packed.len() == 0,
+                None => OperationHandler::is_done(&operations),
+            } {
+                break Ok(());
             }
 
             send_timing += self.option.send_interval;
             self.option.sleeper.sleep_until(send_timing);
+        };
+        telemetry::record_frames(frames);
+        if let Some(report) = report {
+            tracing::debug!("packing: {:?}", report);
+            *self.packing = Some(report);
+        }
+        res
+    }
+
+    fn pack<O1, O2>(
+        &mut self,
+        operations: &mut [Option<(O1, O2)>],
+        parallel: bool,
+        report: Option<&mut PackingReport>,
+    ) -> Result<(), AUTDDriverError>
+    where
+        O1: Operation,
+        O2: Operation,
+        AUTDDriverError: From<O1::Error> + From<O2::Error>,
+    {
+        match report {
+            Some(report) => {
+                report.passes += 1;
+                OperationHandler::pack_with_metrics(
+                    operations,
+                    self.geometry,
+                    self.tx,
+                    parallel,
+                    &mut report.devices,
+                )
+            }
+            None => OperationHandler::pack(operations, self.geometry, self.tx, parallel),
         }
     }
 
+    fn pack_all<O1, O2>(
+        &mut self,
+        operations: &mut [Option<(O1, O2)>],
+        parallel: bool,
+        mut report: Option<&mut PackingReport>,
+        budget: &PowerBudget,
+    ) -> Result<Vec<Vec<TxMessage>>, AUTDDriverError>
+    where
+        O1: Operation,
+        O2: Operation,
+        AUTDDriverError: From<O1::Error> + From<O2::Error>,
+    {
+        let last = self.tx.to_vec();
+        let mut frames = Vec::new();
+        let res = loop {
+            if let Err(e) = self.pack(operations, parallel, report.as_deref_mut()) {
+                break Err(e);
+            }
+            frames.push(self.tx.to_vec());
+            if OperationHandler::is_done(operations) {
+                break self.power.inspect(self.geometry, &mut frames, budget);
+            }
+        };
+        // The message IDs of the frames not sent must not be used.
+        self.tx.clone_from_slice(&last);
+        res.map(|_| frames)
+    }
+
     fn send_receive(&mut self, timeout: Duration) -> Result<(), AUTDDriverError> {
         if !self.link.is_open() {
             return Err(AUTDDriverError::LinkClosed);
         }
 
         tracing::trace!("send: {}", self.tx.iter().join(", "));
+        let sent = Instant::now();
         if !self.link.send(self.tx)? {
             return Err(AUTDDriverError::SendDataFailed);
         }
-        self.wait_msg_processed(timeout)
+        self.wait_msg_processed(sent, timeout)?;
+        self.config.record(self.tx);
+        Ok(())
     }
 
-    fn wait_msg_processed(&mut self, timeout: Duration) -> Result<(), AUTDDriverError> {
+    fn wait_msg_processed(
+        &mut self,
+        sent: Instant,
+        timeout: Duration,
+    ) -> Result<(), AUTDDriverError> {
         let start = Instant::now();
         let mut receive_timing = start;
+        let mut confirmed = vec![false; self.tx.len()];
         loop {
             if !self.link.is_open() {
                 return Err(AUTDDriverError::LinkClosed);
@@ -170,7 +463,18 @@ impl<L: Link, S: Sleep> Sender<'_, L, S> {
             let res = self.link.receive(self.rx)?;
             tracing::trace!("recv: {}", self.rx.iter().join(", "));
 
-            if res && check_if_msg_is_processed(self.tx, self.rx).all(std::convert::identity) {
+            if res {
+                self.option
+                    .msg_id_policy
+                    .confirm(self.tx, self.rx, &mut confirmed);
+            }
+            if res
+                && confirmed
+                    .iter()
+                    .zip(self.geometry.iter())
+                    .all(|(&confirmed, dev)| confirmed || !dev.enable)
+            {
+                self.rtt.record(sent.elapsed());
                 return Ok(());
             }
             if start.elapsed() > timeout {
@@ -179,19 +483,20 @@ impl<L: Link, S: Sleep> Sender<'_, L, S> {
             receive_timing += self.option.receive_interval;
             self.option.sleeper.sleep_until(receive_timing);
         }
-        self.rx
-            .iter()
-            .try_fold((), |_, r| {
-                autd3_driver::firmware::cpu::check_firmware_err(r)
-            })
-            .and_then(|e| {
+        // The elapsed time is recorded as the lower bound of the round trip time, so that the adaptive timeout grows after timeouts.
+        if !timeout.is_zero() {
+            self.rtt.record(sent.elapsed());
+        }
+        autd3_driver::firmware::cpu::check_device_errors(self.geometry, self.tx, self.rx).and_then(
+            |e| {
                 if timeout == Duration::ZERO {
                     Ok(())
                 } else {
                     tracing::error!("Failed to confirm the response from the device: {:?}", e);
                     Err(AUTDDriverError::ConfirmResponseFailed)
                 }
-            })
+            },
+        )
     }
 }
 
@@ -301,11 +606,21 @@ mod tests {
             geometry: &mut geometry,
             tx: &mut tx,
             rx: &mut rx,
+            events: &mut EventLog::default(),
+            power: &mut PowerMonitor::default(),
+            rtt: &mut RttTracker::default(),
+            packing: &mut None,
+            config: &mut ConfigTracker::default(),
             option: SenderOption {
                 send_interval: Duration::from_millis(1),
                 receive_interval: Duration::from_millis(1),
                 timeout: None,
                 parallel: ParallelMode::Auto,
+                power_budget: None,
+                modulation_split: ModulationSplit::Reject,
+                adaptive_timeout: None,
+                msg_id_policy: MsgIdPolicy::Strict,
+                metrics: false,
                 sleeper,
             },
         };
@@ -347,22 +662,35 @@ mod tests {
             geometry: &mut geometry,
             tx: &mut tx,
             rx: &mut rx,
+            events: &mut EventLog::default(),
+            power: &mut PowerMonitor::default(),
+            rtt: &mut RttTracker::default(),
+            packing: &mut None,
+            config: &mut ConfigTracker::default(),
             option: SenderOption {
                 send_interval: Duration::from_millis(1),
                 receive_interval: Duration::from_millis(1),
                 timeout: None,
                 parallel: ParallelMode::Auto,
+                power_budget: None,
+                modulation_split: ModulationSplit::Reject,
+                adaptive_timeout: None,
+                msg_id_policy: MsgIdPolicy::Strict,
+                metrics: false,
                 sleeper,
             },
         };
 
-        assert_eq!(sender.wait_msg_processed(Duration::from_millis(10)), Ok(()));
+        assert_eq!(
+            sender.wait_msg_processed(Instant::now(), Duration::from_millis(10)),
+            Ok(())
+        );
 
         sender.link.recv_cnt = 0;
         sender.link.is_open = false;
         assert_eq!(
             Err(AUTDDriverError::LinkClosed),
-            sender.wait_msg_processed(Duration::from_millis(10))
+            sender.wait_msg_processed(Instant::now(), Duration::from_millis(10))
         );
 
         sender.link.recv_cnt = 0;
@@ -370,20 +698,23 @@ mod tests {
         sender.link.down = true;
         assert_eq!(
             Err(AUTDDriverError::ConfirmResponseFailed),
-            sender.wait_msg_processed(Duration::from_millis(10)),
+            sender.wait_msg_processed(Instant::now(), Duration::from_millis(10)),
         );
 
         sender.link.recv_cnt = 0;
         sender.link.is_open = true;
         sender.link.down = true;
-        assert_eq!(Ok(()), sender.wait_msg_processed(Duration::ZERO),);
+        assert_eq!(
+            Ok(()),
+            sender.wait_msg_processed(Instant::now(), Duration::ZERO),
+        );
 
         sender.link.down = false;
         sender.link.recv_cnt = 0;
         sender.tx[0].header.msg_id = 20;
         assert_eq!(
             Err(AUTDDriverError::Link(LinkError::new("too many".to_owned()))),
-            sender.wait_msg_processed(Duration::from_secs(10))
+            sender.wait_msg_processed(Instant::now(), Duration::from_secs(10))
         );
     }
 }
@@ -0,0 +1,106 @@
+use autd3_driver::firmware::cpu::{RxMessage, TxMessage};
+
+const MSG_ID_MASK: u8 = 0x7F;
+const ERR_BIT: u8 = 0x80;
+
+/// The policy to confirm that the devices have processed the sent data by the message ID in the response.
+///
+/// Some links reorder or duplicate frames, so the response may temporarily contain the message ID of an older frame even after the device has processed the latest one.
+/// With [`MsgIdPolicy::Strict`], such a response may cause [`AUTDDriverError::ConfirmResponseFailed`] although the data has been processed.
+///
+/// [`AUTDDriverError::ConfirmResponseFailed`]: autd3_driver::error::AUTDDriverError::ConfirmResponseFailed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MsgIdPolicy {
+    /// The data is confirmed only if all devices return the message ID of the sent frame at the same poll.
+    #[default]
+    Strict,
+    /// The ack of a device is accepted if its message ID is that of the sent frame or one of the previous `n` frames.
+    ///
+    /// Note that the data may not have been processed if the sent frame has been lost.
+    Window(u8),
+    /// The ack of each device is tracked between polls, i.e., once a device returns the message ID of the sent frame, it is regarded as confirmed even if a later response contains an older one.
+    Tracking,
+}
+
+impl MsgIdPolicy {
+    fn accepts(&self, tx: &TxMessage, rx: &RxMessage) -> bool {
+        let ack = rx.ack();
+        match self {
+            MsgIdPolicy::Strict | MsgIdPolicy::Tracking => ack == tx.header.msg_id,
+            MsgIdPolicy::Window(n) => {
+                ack & ERR_BIT == 0
+                    && tx.header.msg_id.wrapping_sub(ack) & MSG_ID_MASK <= (*n).min(MSG_ID_MASK)
+            }
+        }
+    }
+
+    /// Updates `confirmed` of each device with the responses `rx` to `tx`.
+    pub(crate) fn confirm(&self, tx: &[TxMessage], rx: &[RxMessage], confirmed: &mut [bool]) {
+        tx.iter()
+            .zip(rx.iter())
+            .zip(confirmed.iter_mut())
+            .for_each(|((tx, rx), confirmed)| {
+                let accepted = self.accepts(tx, rx);
+                *confirmed = match self {
+                    MsgIdPolicy::Tracking => *confirmed || accepted,
+                    _ => accepted,
+                };
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zerocopy::FromZeros;
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(true, MsgIdPolicy::Strict, 0x05, 0x05)]
+    #[case(false, MsgIdPolicy::Strict, 0x05, 0x04)]
+    #[case(false, MsgIdPolicy::Strict, 0x05, 0x85)]
+    #[case(true, MsgIdPolicy::Window(0), 0x05, 0x05)]
+    #[case(false, MsgIdPolicy::Window(0), 0x05, 0x04)]
+    #[case(true, MsgIdPolicy::Window(2), 0x05, 0x03)]
+    #[case(false, MsgIdPolicy::Window(2), 0x05, 0x02)]
+    #[case(false, MsgIdPolicy::Window(2), 0x05, 0x06)]
+    #[case(false, MsgIdPolicy::Window(2), 0x05, 0x85)]
+    #[case(true, MsgIdPolicy::Window(2), 0x00, 0x7F)]
+    #[case(true, MsgIdPolicy::Window(u8::MAX), 0x05, 0x06)]
+    #[case(true, MsgIdPolicy::Tracking, 0x05, 0x05)]
+    #[case(false, MsgIdPolicy::Tracking, 0x05, 0x04)]
+    #[test]
+    fn accepts(
+        #[case] expect: bool,
+        #[case] policy: MsgIdPolicy,
+        #[case] msg_id: u8,
+        #[case] ack: u8,
+    ) {
+        let mut tx = TxMessage::new_zeroed();
+        tx.header.msg_id = msg_id;
+        assert_eq!(expect, policy.accepts(&tx, &RxMessage::new(0, ack)));
+    }
+
+    #[rstest::rstest]
+    #[case(vec![true, false], MsgIdPolicy::Strict)]
+    #[case(vec![true, true], MsgIdPolicy::Window(1))]
+    #[case(vec![true, true], MsgIdPolicy::Tracking)]
+    #[test]
+    fn confirm(#[case] expect: Vec<bool>, #[case] policy: MsgIdPolicy) {
+        let mut tx = vec![TxMessage::new_zeroed(); 2];
+        tx.iter_mut().for_each(|tx| tx.header.msg_id = 0x05);
+        let mut confirmed = vec![false; 2];
+
+        policy.confirm(
+            &tx,
+            &[RxMessage::new(0, 0x04), RxMessage::new(0, 0x05)],
+            &mut confirmed,
+        );
+        policy.confirm(
+            &tx,
+            &[RxMessage::new(0, 0x05), RxMessage::new(0, 0x04)],
+            &mut confirmed,
+        );
+        assert_eq!(expect, confirmed);
+    }
+}
@@ -0,0 +1,25 @@
+use autd3_driver::firmware::operation::PackingMetrics;
+
+/// The packing metrics of the last [`Datagram`] sent with [`SenderOption::metrics`].
+///
+/// This is useful to find out why a [`Datagram`] takes many EtherCAT cycles to send.
+///
+/// [`Datagram`]: autd3_driver::datagram::Datagram
+/// [`SenderOption::metrics`]: super::SenderOption::metrics
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackingReport {
+    /// The number of packing passes, i.e., the number of frames sent to the link.
+    pub passes: usize,
+    /// The metrics of each device indexed by the device index.
+    pub devices: Vec<PackingMetrics>,
+}
+
+impl PackingReport {
+    pub(crate) fn new(num_devices: usize) -> Self {
+        Self {
+            passes: 0,
+            devices: vec![PackingMetrics::default(); num_devices],
+        }
+    }
+}
@@ -0,0 +1,534 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use autd3_core::geometry::Geometry;
+use autd3_driver::{
+    error::AUTDDriverError,
+    firmware::{
+        cpu::TxMessage,
+        fpga::{EmitIntensity, Segment},
+        operation::{inspect_output, OutputContent},
+    },
+};
+
+/// The action of [`PowerBudget`] when a [`Datagram`] exceeds the budget.
+///
+/// [`Datagram`]: autd3_driver::datagram::Datagram
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PowerBudgetAction {
+    /// Rejects the [`Datagram`] with [`AUTDDriverError::PowerBudgetExceeded`].
+    ///
+    /// [`Datagram`]: autd3_driver::datagram::Datagram
+    #[default]
+    Reject,
+    /// Attenuates the intensities of [`Gain`] or the values of [`Modulation`] to fit in the budget.
+    ///
+    /// [`GainSTM`] and [`FociSTM`] cannot be attenuated and are rejected.
+    ///
+    /// [`Gain`]: autd3_core::gain::Gain
+    /// [`Modulation`]: autd3_core::modulation::Modulation
+    /// [`GainSTM`]: autd3_driver::datagram::GainSTM
+    /// [`FociSTM`]: autd3_driver::datagram::FociSTM
+    Attenuate,
+}
+
+/// A safety policy to limit the output power of each device. See also [`SenderOption::power_budget`].
+///
+/// The output level of a device is the mean intensity of the transducers multiplied by the mean of the modulation data.
+/// The outgoing [`Gain`] and [`Modulation`] data are inspected before sending, and a [`Datagram`] that makes the output level exceed the budget is handled according to [`action`].
+///
+/// - The output level must not exceed [`max_intensity`], which limits the duty cycle of each device.
+/// - If the time-weighted average of the output level over the last [`window`] exceeds [`max_average_intensity`], the output level must not exceed [`max_average_intensity`] until the average falls below it.
+///
+/// Since both segments may be used, the maximum of the output levels of both segments is considered. [`GainSTM`] and [`FociSTM`] are assumed to output at the maximum intensity.
+/// Note that the output of the data which has already been sent cannot be reduced by this policy.
+///
+/// When a budget is set, all frames of a [`Datagram`] are packed before the first frame is sent, and the budget is checked once for the whole [`Datagram`]. That is, a [`Datagram`] is rejected before any frame is sent, or all frames are attenuated by the same ratio.
+///
+/// [`Gain`]: autd3_core::gain::Gain
+/// [`Modulation`]: autd3_core::modulation::Modulation
+/// [`Datagram`]: autd3_driver::datagram::Datagram
+/// [`GainSTM`]: autd3_driver::datagram::GainSTM
+/// [`FociSTM`]: autd3_driver::datagram::FociSTM
+/// [`SenderOption::power_budget`]: crate::controller::SenderOption::power_budget
+/// [`action`]: PowerBudget::action
+/// [`max_intensity`]: PowerBudget::max_intensity
+/// [`max_average_intensity`]: PowerBudget::max_average_intensity
+/// [`window`]: PowerBudget::window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerBudget {
+    /// The maximum output level of each device.
+    pub max_intensity: EmitIntensity,
+    /// The maximum average output level of each device over [`window`].
+    ///
+    /// [`window`]: PowerBudget::window
+    pub max_average_intensity: EmitIntensity,
+    /// The length of the sliding window.
+    pub window: Duration,
+    /// The action when the budget is exceeded.
+    pub action: PowerBudgetAction,
+}
+
+impl Default for PowerBudget {
+    fn default() -> Self {
+        Self {
+            max_intensity: EmitIntensity::MAX,
+            max_average_intensity: EmitIntensity::MAX,
+            window: Duration::from_secs(1),
+            action: PowerBudgetAction::Reject,
+        }
+    }
+}
+
+const HISTORY_CAPACITY: usize = 1024;
+
+const fn segment_idx(segment: Segment) -> usize {
+    match segment {
+        Segment::S0 => 0,
+        Segment::S1 => 1,
+    }
+}
+
+fn mean(data: impl Iterator<Item = u8>) -> Option<f32> {
+    let (sum, n) = data.fold((0u64, 0usize), |(sum, n), v| (sum + v as u64, n + 1));
+    (n > 0).then(|| sum as f32 / n as f32 / EmitIntensity::MAX.0 as f32)
+}
+
+fn attenuate<'a>(data: impl Iterator<Item = &'a mut u8>, ratio: f32) {
+    data.for_each(|v| *v = (*v as f32 * ratio).floor() as u8);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Levels {
+    gain: [f32; 2],
+    modulation: [f32; 2],
+    modulation_acc: [(u64, usize); 2],
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        Self {
+            gain: [0.; 2],
+            modulation: [1.; 2],
+            modulation_acc: [(0, 0); 2],
+        }
+    }
+}
+
+impl Levels {
+    fn level(&self) -> f32 {
+        self.gain[0].max(self.gain[1]) * self.modulation[0].max(self.modulation[1])
+    }
+
+    fn apply(&mut self, content: &OutputContent) {
+        match content {
+            OutputContent::Clear => *self = Self::default(),
+            OutputContent::Gain { segment, drives } => {
+                self.gain[segment_idx(*segment)] =
+                    mean(drives.iter().skip(1).step_by(2).copied()).unwrap_or(0.);
+            }
+            OutputContent::Modulation {
+                segment,
+                begin,
+                data,
+            } => {
+                let idx = segment_idx(*segment);
+                let (sum, n) = if *begin {
+                    (0, 0)
+                } else {
+                    self.modulation_acc[idx]
+                };
+                let sum = sum + data.iter().map(|&v| v as u64).sum::<u64>();
+                let n = n + data.len();
+                self.modulation_acc[idx] = (sum, n);
+                if n > 0 {
+                    self.modulation[idx] = sum as f32 / n as f32 / EmitIntensity::MAX.0 as f32;
+                }
+            }
+            OutputContent::STM { segment } => {
+                self.gain[segment_idx(*segment)] = 1.;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DeviceState {
+    levels: Levels,
+    history: VecDeque<(Instant, f32)>,
+}
+
+impl DeviceState {
+    fn new(now: Instant) -> Self {
+        Self {
+            levels: Levels::default(),
+            history: VecDeque::from([(now, 0.)]),
+        }
+    }
+
+    fn average(&self, now: Instant, window: Duration) -> f32 {
+        if window.is_zero() {
+            return self.history.back().map_or(0., |&(_, l)| l);
+        }
+        let start = now.checked_sub(window).unwrap_or(now);
+        let integral = self
+            .history
+            .iter()
+            .zip(
+                self.history
+                    .iter()
+                    .skip(1)
+                    .map(|&(t, _)| t)
+                    .chain(std::iter::once(now)),
+            )
+            .map(|(&(t, level), end)| {
+                end.saturating_duration_since(t.max(start)).as_secs_f32() * level
+            })
+            .sum::<f32>();
+        integral / window.as_secs_f32()
+    }
+
+    fn record(&mut self, now: Instant, level: f32, window: Option<Duration>) {
+        if self.history.back().is_some_and(|&(_, l)| l == level) {
+            return;
+        }
+        self.history.push_back((now, level));
+        if let Some(start) = window.and_then(|w| now.checked_sub(w)) {
+            while self.history.len() > 1 && self.history[1].0 <= start {
+                self.history.pop_front();
+            }
+        }
+        while self.history.len() > HISTORY_CAPACITY {
+            self.history.pop_front();
+        }
+    }
+}
+
+/// The output levels of the devices tracked for [`PowerBudget`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PowerMonitor {
+    devices: Vec<DeviceState>,
+}
+
+impl PowerMonitor {
+    /// Inspects all outgoing frames of a [`Datagram`] and updates the output levels of the devices.
+    ///
+    /// The frames exceeding `budget` are rejected or attenuated as a whole, so that the decision is made once for each [`Datagram`]. If any device is rejected, no output level is updated.
+    ///
+    /// [`Datagram`]: autd3_driver::datagram::Datagram
+    pub(crate) fn inspect(
+        &mut self,
+        geometry: &Geometry,
+        frames: &mut [Vec<TxMessage>],
+        budget: &PowerBudget,
+    ) -> Result<(), AUTDDriverError> {
+        let now = Instant::now();
+        if self.devices.len() != geometry.len() {
+            self.devices = vec![DeviceState::new(now); geometry.len()];
+        }
+
+        let max_intensity = budget.max_intensity.0 as f32 / EmitIntensity::MAX.0 as f32;
+        let max_average = budget.max_average_intensity.0 as f32 / EmitIntensity::MAX.0 as f32;
+
+        let updates = geometry
+            .iter()
+            .filter(|dev| dev.enable)
+            .map(|dev| {
+                let idx = dev.idx();
+                let state = &self.devices[idx];
+
+                // The frame is not updated for the device whose operations have been completed, so the same message ID as the previous frame means the same content.
+                let mut last_msg_id = None;
+                let mut contents = frames
+                    .iter_mut()
+                    .filter_map(|frame| {
+                        let tx = &mut frame[idx];
+                        if last_msg_id == Some(tx.header.msg_id) {
+                            return None;
+                        }
+                        last_msg_id = Some(tx.header.msg_id);
+                        Some(inspect_output(tx, dev.num_transducers()))
+                    })
+                    .flatten()
+                    .collect::<Vec<_>>();
+
+                let peak = |contents: &[OutputContent]| {
+                    contents
+                        .iter()
+                        .fold((state.levels, 0f32), |(mut levels, peak), c| {
+                            levels.apply(c);
+                            (levels, peak.max(levels.level()))
+                        })
+                };
+                let (mut levels, level) = peak(&contents);
+
+                let limit = if state.average(now, budget.window) > max_average {
+                    max_intensity.min(max_average)
+                } else {
+                    max_intensity
+                };
+                if level > limit {
+                    if budget.action == PowerBudgetAction::Reject {
+                        return Err(AUTDDriverError::PowerBudgetExceeded(idx));
+                    }
+                    let ratio = limit / level;
+                    if contents
+                        .iter()
+                        .any(|c| matches!(c, OutputContent::Gain { .. }))
+                    {
+                        contents.iter_mut().for_each(|c| {
+                            if let OutputContent::Gain { drives, .. } = c {
+                                attenuate(drives.iter_mut().skip(1).step_by(2), ratio)
+                            }
+                        });
+                    } else if contents
+                        .iter()
+                        .any(|c| matches!(c, OutputContent::Modulation { .. }))
+                    {
+                        contents.iter_mut().for_each(|c| {
+                            if let OutputContent::Modulation { data, .. } = c {
+                                attenuate(data.iter_mut(), ratio)
+                            }
+                        });
+                    } else {
+                        return Err(AUTDDriverError::PowerBudgetExceeded(idx));
+                    }
+                    let (attenuated, level) = peak(&contents);
+                    if level > limit {
+                        return Err(AUTDDriverError::PowerBudgetExceeded(idx));
+                    }
+                    levels = attenuated;
+                }
+                Ok((idx, levels))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        updates.into_iter().for_each(|(idx, levels)| {
+            let state = &mut self.devices[idx];
+            state.levels = levels;
+            state.record(now, levels.level(), Some(budget.window));
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{
+        datagram::WithSegment,
+        firmware::fpga::{Phase, SamplingConfig},
+    };
+
+    use crate::{
+        controller::{tests::create_controller, SenderOption, SpinSleeper},
+        gain::Uniform,
+        modulation::{Custom, Static},
+    };
+
+    use super::*;
+
+    fn uniform(intensity: u8) -> Uniform {
+        Uniform {
+            intensity: EmitIntensity(intensity),
+            phase: Phase::ZERO,
+        }
+    }
+
+    fn static_s1(intensity: u8) -> WithSegment<Static> {
+        WithSegment {
+            inner: Static { intensity },
+            segment: Segment::S1,
+            transition_mode: None,
+        }
+    }
+
+    fn option(budget: PowerBudget) -> SenderOption<SpinSleeper> {
+        SenderOption {
+            power_budget: Some(budget),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reject() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        let budget = PowerBudget {
+            max_intensity: EmitIntensity(0x80),
+            ..Default::default()
+        };
+
+        autd.sender(option(budget)).send(uniform(0x80))?;
+        assert_eq!(
+            Err(AUTDDriverError::PowerBudgetExceeded(0)),
+            autd.sender(option(budget)).send(uniform(0x81))
+        );
+        assert!(autd.link()[0]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == EmitIntensity(0x80)));
+
+        autd.sender(option(budget)).send(static_s1(0x80))?;
+        autd.sender(option(budget))
+            .send((Static { intensity: 0x80 }, uniform(0xFF)))?;
+        assert_eq!(
+            Err(AUTDDriverError::PowerBudgetExceeded(0)),
+            autd.sender(option(budget)).send(Static { intensity: 0xFF })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn attenuate() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let budget = PowerBudget {
+            max_intensity: EmitIntensity(0x80),
+            action: PowerBudgetAction::Attenuate,
+            ..Default::default()
+        };
+
+        autd.sender(option(budget)).send(uniform(0xFF))?;
+        assert!(autd.link()[0]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == EmitIntensity(0x80)));
+
+        autd.sender(option(budget))
+            .send((Static { intensity: 0x40 }, static_s1(0x40)))?;
+        autd.sender(option(budget)).send(uniform(0xFF))?;
+        assert!(autd.link()[0]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == EmitIntensity(0xFF)));
+
+        autd.sender(option(budget))
+            .send(Static { intensity: 0xFF })?;
+        assert_eq!(
+            vec![0x80; 2],
+            autd.link()[0].fpga().modulation_buffer(Segment::S0)
+        );
+
+        #[cfg(feature = "stm")]
+        assert_eq!(
+            Err(AUTDDriverError::PowerBudgetExceeded(0)),
+            autd.sender(option(PowerBudget {
+                max_intensity: EmitIntensity(0x40),
+                ..budget
+            }))
+            .send(autd3_driver::datagram::FociSTM {
+                foci: vec![autd3_driver::geometry::Point3::origin(); 2],
+                config: autd3_driver::firmware::fpga::SamplingConfig::FREQ_MIN,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn average() {
+        let now = Instant::now();
+        let mut state = DeviceState::new(now - Duration::from_secs(2));
+        state.record(
+            now - Duration::from_millis(500),
+            1.,
+            Some(Duration::from_secs(1)),
+        );
+        approx::assert_abs_diff_eq!(
+            0.5,
+            state.average(now, Duration::from_secs(1)),
+            epsilon = 1e-3
+        );
+        approx::assert_abs_diff_eq!(1., state.average(now, Duration::ZERO));
+
+        state.record(now, 0.5, Some(Duration::from_secs(1)));
+        assert_eq!(3, state.history.len());
+        state.record(
+            now + Duration::from_secs(2),
+            0.,
+            Some(Duration::from_secs(1)),
+        );
+        assert_eq!(2, state.history.len());
+    }
+
+    #[test]
+    fn average_limit() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let budget = PowerBudget {
+            max_average_intensity: EmitIntensity(0x80),
+            window: Duration::from_millis(100),
+            ..Default::default()
+        };
+
+        autd.sender(option(budget)).send(uniform(0xFF))?;
+        std::thread::sleep(Duration::from_millis(100));
+        autd.sender(option(budget)).send(uniform(0x80))?;
+        assert_eq!(
+            Err(AUTDDriverError::PowerBudgetExceeded(0)),
+            autd.sender(option(budget)).send(uniform(0xFF))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn without_budget() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        autd.send(uniform(0xFF))?;
+        assert!(autd.power.devices.is_empty());
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(PowerBudgetAction::Reject)]
+    #[case(PowerBudgetAction::Attenuate)]
+    #[test]
+    fn multi_frame_modulation(#[case] action: PowerBudgetAction) -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let budget = PowerBudget {
+            max_intensity: EmitIntensity(0x80),
+            action,
+            ..Default::default()
+        };
+        autd.sender(option(budget)).send(uniform(0x80))?;
+        autd.sender(option(budget)).send(static_s1(0x80))?;
+        autd.sender(option(budget))
+            .send((Static { intensity: 0x80 }, uniform(0xFF)))?;
+
+        // Only the later frames exceed the budget.
+        let m = Custom {
+            buffer: (0..4000)
+                .map(|i| if i < 2000 { 0x80 } else { 0xFF })
+                .collect::<Vec<u8>>(),
+            sampling_config: SamplingConfig::FREQ_MIN,
+        };
+        match action {
+            PowerBudgetAction::Reject => {
+                assert_eq!(
+                    Err(AUTDDriverError::PowerBudgetExceeded(0)),
+                    autd.sender(option(budget)).send(m)
+                );
+                assert_eq!(
+                    vec![0x80; 2],
+                    autd.link()[0].fpga().modulation_buffer(Segment::S0)
+                );
+            }
+            PowerBudgetAction::Attenuate => {
+                autd.sender(option(budget)).send(m)?;
+                let buffer = autd.link()[0].fpga().modulation_buffer(Segment::S0);
+                assert_eq!(4000, buffer.len());
+                assert!(buffer[..2000].iter().all(|&v| v < 0x80));
+                assert!(
+                    buffer.iter().map(|&v| v as u64).sum::<u64>() <= 0x80 * buffer.len() as u64
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
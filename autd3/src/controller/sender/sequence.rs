@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use autd3_driver::datagram::{BoxedDatagram, IntoBoxedDatagram};
+
+/// A sequence of [`Datagram`]s sent in order by [`Sender::send_sequence`].
+///
+/// Each [`Datagram`] can have its own timeout. If the timeout of the element is `None`, the timeout of the [`SenderOption`] is used, and if it is also `None`, [`Datagram::option`] is used.
+/// If sending any [`Datagram`] fails, the remaining [`Datagram`]s are not sent.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use autd3::prelude::*;
+/// use autd3::driver::datagram::Synchronize;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// autd.send_sequence(
+///     Sequence::new()
+///         .push(Clear::new(), None)
+///         .push(Synchronize::new(), Some(Duration::from_millis(100)))
+///         .push(Silencer::default(), None)
+///         .push(Uniform::new(EmitIntensity::MAX, Phase::ZERO), None),
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Datagram`]: autd3_driver::datagram::Datagram
+/// [`Datagram::option`]: autd3_driver::datagram::Datagram::option
+/// [`Sender::send_sequence`]: crate::controller::Sender::send_sequence
+/// [`SenderOption`]: crate::controller::SenderOption
+#[derive(Debug, Default)]
+pub struct Sequence {
+    /// The [`Datagram`]s and their timeouts.
+    ///
+    /// [`Datagram`]: autd3_driver::datagram::Datagram
+    pub datagrams: Vec<(BoxedDatagram, Option<Duration>)>,
+}
+
+impl Sequence {
+    /// Creates an empty [`Sequence`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a [`Datagram`] with the timeout.
+    ///
+    /// [`Datagram`]: autd3_driver::datagram::Datagram
+    pub fn push(mut self, d: impl IntoBoxedDatagram, timeout: Option<Duration>) -> Self {
+        self.datagrams.push((d.into_boxed(), timeout));
+        self
+    }
+}
@@ -0,0 +1,241 @@
+use std::time::{Duration, Instant};
+
+use autd3_core::{
+    datagram::{LoopBehavior, Segment},
+    defined::ultrasound_freq,
+    ethercat::DcSysTime,
+    link::Link,
+    modulation::{Modulation, SamplingConfig},
+};
+use autd3_driver::{error::AUTDDriverError, firmware::fpga::MOD_BUF_SIZE_MAX};
+
+use crate::modulation::Custom;
+
+use super::{Sender, Sleep};
+
+/// The behavior of [`Sender::send_modulation`] for a [`Modulation`] exceeding the buffer size of the firmware.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModulationSplit {
+    /// Returns [`AUTDDriverError::ModulationSizeOutOfRange`] with the size of the modulation if it exceeds [`MOD_BUF_SIZE_MAX`].
+    #[default]
+    Reject,
+    /// Splits the modulation into chunks within [`MOD_BUF_SIZE_MAX`] and plays them once in order.
+    ///
+    /// The chunks are written to [`Segment::S1`] and [`Segment::S0`] alternately with [`LoopBehavior::ONCE`] and [`TransitionMode::SysTime`] at the end of the previous chunk.
+    /// Each chunk after the first one is written after the previous chunk has started, so [`Sender::send_modulation`] blocks until the last chunk is written.
+    ///
+    /// [`TransitionMode::SysTime`]: autd3_core::datagram::TransitionMode::SysTime
+    Split {
+        /// The time from calling [`Sender::send_modulation`] to the start of the first chunk. This must be long enough to write the first chunk.
+        lead_time: Duration,
+    },
+}
+
+/// A chunk of the split modulation and its start time.
+pub(crate) struct Chunk {
+    pub(crate) buffer: Vec<u8>,
+    pub(crate) segment: Segment,
+    pub(crate) start: DcSysTime,
+}
+
+/// Splits `buffer` into chunks of the same size as possible, so that the last chunk is not too short.
+pub(crate) fn split(
+    buffer: Vec<u8>,
+    config: SamplingConfig,
+    lead_time: Duration,
+) -> impl Iterator<Item = Chunk> {
+    let n = buffer.len().div_ceil(MOD_BUF_SIZE_MAX);
+    let size = buffer.len().div_ceil(n);
+    let start = DcSysTime::now() + lead_time;
+    let mut offset = 0;
+    buffer
+        .chunks(size)
+        .map(<[u8]>::to_vec)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .enumerate()
+        .map(move |(i, buffer)| {
+            let chunk = Chunk {
+                segment: if i % 2 == 0 { Segment::S1 } else { Segment::S0 },
+                start: start + samples_duration(offset, config),
+                buffer,
+            };
+            offset += chunk.buffer.len();
+            chunk
+        })
+}
+
+fn samples_duration(samples: usize, config: SamplingConfig) -> Duration {
+    Duration::from_nanos(
+        (samples as u128 * config.division.get() as u128 * 1_000_000_000
+            / ultrasound_freq().hz() as u128) as u64,
+    )
+}
+
+/// Converts the system time to [`Instant`] for the sleepers.
+pub(crate) fn to_instant(time: DcSysTime) -> Instant {
+    let now = DcSysTime::now();
+    Instant::now() + Duration::from_nanos(time.sys_time().saturating_sub(now.sys_time()))
+}
+
+impl<L: Link, S: Sleep> Sender<'_, L, S> {
+    /// Send the [`Modulation`] to the devices according to [`SenderOption::modulation_split`].
+    ///
+    /// If the size of the modulation is within [`MOD_BUF_SIZE_MAX`], this is the same as [`Sender::send`].
+    /// Otherwise, [`AUTDDriverError::ModulationSizeOutOfRange`] is returned or the modulation is split as described in [`ModulationSplit::Split`].
+    /// The split modulation requires that the current segment of the modulation is [`Segment::S0`] when this is called, and the last sample is kept after the last chunk.
+    ///
+    /// [`SenderOption::modulation_split`]: super::SenderOption::modulation_split
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn send_modulation<M: Modulation>(&mut self, m: M) -> Result<(), AUTDDriverError> {
+        let sampling_config = m.sampling_config()?;
+        let buffer = m.calc()?;
+        if buffer.len() <= MOD_BUF_SIZE_MAX {
+            return self.send(Custom {
+                buffer,
+                sampling_config,
+            });
+        }
+        let lead_time = match self.option.modulation_split {
+            ModulationSplit::Reject => {
+                return Err(AUTDDriverError::ModulationSizeOutOfRange(buffer.len()))
+            }
+            ModulationSplit::Split { lead_time } => lead_time,
+        };
+
+        let mut prev_start = None;
+        for chunk in split(buffer, sampling_config, lead_time) {
+            // The segment of this chunk is in use until the previous chunk starts.
+            if let Some(prev_start) = prev_start {
+                self.option.sleeper.sleep_until(to_instant(prev_start));
+            }
+            prev_start = Some(chunk.start);
+            self.send_at(
+                Custom {
+                    buffer: chunk.buffer,
+                    sampling_config,
+                },
+                chunk.segment,
+                LoopBehavior::ONCE,
+                chunk.start,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU16;
+
+    use autd3_driver::firmware::fpga::MOD_BUF_SIZE_MIN;
+
+    use super::*;
+
+    use crate::controller::{tests::create_controller, SenderOption, SpinSleeper};
+
+    #[rstest::rstest]
+    #[case(vec![MOD_BUF_SIZE_MAX / 2 + 1, MOD_BUF_SIZE_MAX / 2], MOD_BUF_SIZE_MAX + 1)]
+    #[case(vec![MOD_BUF_SIZE_MAX; 2], MOD_BUF_SIZE_MAX * 2)]
+    #[case(vec![MOD_BUF_SIZE_MAX * 2 / 3 + 1, MOD_BUF_SIZE_MAX * 2 / 3 + 1, MOD_BUF_SIZE_MAX * 2 / 3], MOD_BUF_SIZE_MAX * 2 + 1)]
+    #[test]
+    fn split_size(#[case] expect: Vec<usize>, #[case] size: usize) {
+        let config = SamplingConfig {
+            division: NonZeroU16::new(10).unwrap(),
+        };
+        let chunks = split(vec![0; size], config, Duration::ZERO).collect::<Vec<_>>();
+        assert_eq!(
+            expect,
+            chunks.iter().map(|c| c.buffer.len()).collect::<Vec<_>>()
+        );
+        assert!(chunks.iter().all(|c| MOD_BUF_SIZE_MIN <= c.buffer.len()));
+        chunks.windows(2).for_each(|w| {
+            assert_ne!(w[0].segment, w[1].segment);
+            assert_eq!(
+                w[0].start + Duration::from_micros(250) * w[0].buffer.len() as u32,
+                w[1].start
+            );
+        });
+        assert_eq!(Segment::S1, chunks[0].segment);
+    }
+
+    #[test]
+    fn samples_duration_overflow() {
+        let config = SamplingConfig {
+            division: NonZeroU16::MAX,
+        };
+        assert_eq!(
+            Duration::from_nanos(
+                (MOD_BUF_SIZE_MAX as u128 * 1000 * 65535 * 1_000_000_000
+                    / ultrasound_freq().hz() as u128) as u64
+            ),
+            samples_duration(MOD_BUF_SIZE_MAX * 1000, config)
+        );
+    }
+
+    #[test]
+    fn send_modulation() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        let buffer = (0..MOD_BUF_SIZE_MAX + 2)
+            .map(|i| (i % 256) as u8)
+            .collect::<Vec<_>>();
+        let m = Custom {
+            buffer: buffer.clone(),
+            sampling_config: SamplingConfig {
+                division: NonZeroU16::new(10).unwrap(),
+            },
+        };
+
+        assert_eq!(
+            Err(AUTDDriverError::ModulationSizeOutOfRange(
+                MOD_BUF_SIZE_MAX + 2
+            )),
+            autd.sender(SenderOption::<SpinSleeper>::default())
+                .send_modulation(m.clone())
+        );
+        assert!(
+            AUTDDriverError::ModulationSizeOutOfRange(MOD_BUF_SIZE_MAX + 2)
+                .to_string()
+                .contains(&MOD_BUF_SIZE_MAX.to_string())
+        );
+
+        autd.sender(SenderOption::<SpinSleeper> {
+            modulation_split: ModulationSplit::Split {
+                lead_time: Duration::from_millis(200),
+            },
+            ..Default::default()
+        })
+        .send_modulation(m)?;
+        assert_eq!(
+            buffer[..MOD_BUF_SIZE_MAX / 2 + 1],
+            autd.link[0].fpga().modulation_buffer(Segment::S1)
+        );
+        assert_eq!(
+            buffer[MOD_BUF_SIZE_MAX / 2 + 1..],
+            autd.link[0].fpga().modulation_buffer(Segment::S0)
+        );
+        assert_eq!(
+            LoopBehavior::ONCE,
+            autd.link[0].fpga().modulation_loop_behavior(Segment::S1)
+        );
+        assert_eq!(
+            LoopBehavior::ONCE,
+            autd.link[0].fpga().modulation_loop_behavior(Segment::S0)
+        );
+
+        autd.sender(SenderOption::<SpinSleeper>::default())
+            .send_modulation(Custom {
+                buffer: vec![0x80; 10],
+                sampling_config: SamplingConfig {
+                    division: NonZeroU16::new(10).unwrap(),
+                },
+            })?;
+        assert_eq!(
+            vec![0x80; 10],
+            autd.link[0].fpga().modulation_buffer(Segment::S0)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::time::{Duration, Instant};
+
+use autd3_core::geometry::Geometry;
+use autd3_driver::error::AUTDDriverError;
+use tracing::{field::Empty, Span};
+
+/// Creates the `send` span of the datagram `D`.
+///
+/// The span has the following fields, so that the latency of each datagram can be monitored with a structured subscriber:
+/// - `datagram`: the type name of the datagram.
+/// - `devices`: the number of enabled devices.
+/// - `parallel`: whether the operations are generated and packed in parallel.
+/// - `timeout_us`: the timeout to confirm the response in microseconds.
+/// - `frames`: the number of frames sent.
+/// - `duration_us`: the duration of sending in microseconds.
+/// - `success`: whether the datagram is sent successfully.
+/// - `id`: the unique ID of the send, which is recorded only with the `telemetry` feature.
+pub(crate) fn send_span<D>(geometry: &Geometry) -> Span {
+    let span = tracing::info_span!(
+        "send",
+        datagram = std::any::type_name::<D>(),
+        devices = geometry.num_devices(),
+        parallel = Empty,
+        timeout_us = Empty,
+        frames = Empty,
+        duration_us = Empty,
+        success = Empty,
+        id = Empty,
+    );
+    #[cfg(feature = "telemetry")]
+    {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static ID: AtomicU64 = AtomicU64::new(0);
+        span.record("id", ID.fetch_add(1, Ordering::Relaxed));
+    }
+    span
+}
+
+pub(crate) fn record_option(timeout: Duration, parallel: bool) {
+    Span::current()
+        .record("parallel", parallel)
+        .record("timeout_us", timeout.as_micros() as u64);
+}
+
+pub(crate) fn record_frames(frames: usize) {
+    Span::current().record("frames", frames);
+}
+
+pub(crate) fn record_result(span: &Span, start: Instant, res: &Result<(), AUTDDriverError>) {
+    span.record("duration_us", start.elapsed().as_micros() as u64)
+        .record("success", res.is_ok());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Subscriber,
+    };
+    use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+
+    use crate::controller::tests::create_controller;
+
+    type Fields = HashMap<&'static str, String>;
+
+    struct Visitor<'a>(&'a mut Fields);
+
+    impl Visit for Visitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name(), format!("{value:?}"));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name(), value.to_string());
+        }
+    }
+
+    #[derive(Default, Clone)]
+    struct SendSpans(Arc<Mutex<Vec<Fields>>>);
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SendSpans {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+            let span = ctx.span(id).unwrap();
+            if span.name() == "send" {
+                let mut fields = Fields::new();
+                attrs.record(&mut Visitor(&mut fields));
+                span.extensions_mut().insert(fields);
+            }
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+            if let Some(fields) = ctx.span(id).unwrap().extensions_mut().get_mut::<Fields>() {
+                values.record(&mut Visitor(fields));
+            }
+        }
+
+        fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+            if let Some(fields) = ctx.span(&id).unwrap().extensions_mut().remove::<Fields>() {
+                self.0.lock().unwrap().push(fields);
+            }
+        }
+    }
+
+    fn collect(f: impl FnOnce()) -> Vec<Fields> {
+        let layer = SendSpans::default();
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer.clone()), f);
+        let spans = layer.0.lock().unwrap().clone();
+        spans
+    }
+
+    fn check(spans: &[Fields]) {
+        assert_eq!(2, spans.len());
+        assert!(spans[0]["datagram"].ends_with("::Null"));
+        assert!(spans[1]["datagram"].ends_with("::Static"));
+        spans.iter().for_each(|fields| {
+            assert_eq!("2", fields["devices"]);
+            assert_eq!("false", fields["parallel"]);
+            assert!(fields.contains_key("timeout_us"));
+            assert_eq!("1", fields["frames"]);
+            assert_eq!("true", fields["success"]);
+            assert!(fields.contains_key("duration_us"));
+            assert_eq!(cfg!(feature = "telemetry"), fields.contains_key("id"));
+        });
+    }
+
+    #[test]
+    fn send_span() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        let spans = collect(|| {
+            autd.send(crate::gain::Null).unwrap();
+            autd.send(crate::modulation::Static::default()).unwrap();
+        });
+        check(&spans);
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn send_span_async() -> anyhow::Result<()> {
+        use tracing::instrument::WithSubscriber;
+
+        let layer = SendSpans::default();
+        let mut autd = crate::r#async::Controller::open(
+            [autd3_driver::autd3_device::AUTD3::default(); 2],
+            crate::link::Audit::new(Default::default()),
+        )
+        .await?;
+        async {
+            autd.send(crate::gain::Null).await.unwrap();
+            autd.send(crate::modulation::Static::default())
+                .await
+                .unwrap();
+        }
+        .with_subscriber(tracing_subscriber::registry().with(layer.clone()))
+        .await;
+        check(&layer.0.lock().unwrap());
+        Ok(())
+    }
+}
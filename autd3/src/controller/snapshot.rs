@@ -0,0 +1,674 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, MutexGuard},
+};
+
+use autd3_core::{
+    datagram::{LoopBehavior, Segment, TransitionMode},
+    link::Link,
+};
+use autd3_driver::{
+    datagram::{
+        BoxedDatagram, FixedCompletionSteps, FixedUpdateRate, IntoBoxedDatagram, PhaseCorrection,
+        PulseWidthEncoder, Silencer, SwapSegment,
+    },
+    firmware::{
+        cpu::TxMessage,
+        fpga::{Phase, SilencerTarget},
+    },
+    geometry::Geometry,
+};
+use autd3_firmware_emulator::CPUEmulator;
+
+use crate::error::AUTDError;
+
+use super::{Controller, SenderOption, SpinSleeper};
+
+/// The silencer configuration captured by [`Controller::snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SilencerSnapshot {
+    /// The silencer with [`FixedCompletionSteps`].
+    FixedCompletionSteps(FixedCompletionSteps),
+    /// The silencer with [`FixedUpdateRate`].
+    FixedUpdateRate(FixedUpdateRate),
+}
+
+/// The kind of the data in the active STM segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriveKind {
+    /// A single [`Gain`].
+    ///
+    /// [`Gain`]: autd3_core::gain::Gain
+    Gain,
+    /// [`FociSTM`].
+    ///
+    /// [`FociSTM`]: autd3_driver::datagram::FociSTM
+    FociSTM,
+    /// [`GainSTM`].
+    ///
+    /// [`GainSTM`]: autd3_driver::datagram::GainSTM
+    GainSTM,
+}
+
+/// The metadata of a segment captured by [`Controller::snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentSnapshot {
+    /// The segment.
+    pub segment: Segment,
+    /// The sampling frequency division.
+    pub freq_division: u16,
+    /// The number of samples.
+    pub cycle: usize,
+    /// The loop behavior.
+    pub loop_behavior: LoopBehavior,
+}
+
+/// The configuration of a device captured by [`Controller::snapshot`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceSnapshot {
+    /// The silencer configuration.
+    pub silencer: SilencerSnapshot,
+    /// The target of the silencer.
+    pub silencer_target: SilencerTarget,
+    /// The active modulation segment.
+    pub modulation: SegmentSnapshot,
+    /// The active STM segment, which also holds a single [`Gain`].
+    ///
+    /// [`Gain`]: autd3_core::gain::Gain
+    pub stm: SegmentSnapshot,
+    /// The kind of the data in [`Self::stm`].
+    pub drive: DriveKind,
+    /// The phase correction of each transducer.
+    pub phase_correction: Vec<Phase>,
+    /// The pulse width encoder table.
+    pub pulse_width_encoder: Vec<u8>,
+}
+
+/// The configuration of the devices captured by [`Controller::snapshot`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigSnapshot {
+    devices: Vec<DeviceSnapshot>,
+}
+
+impl ConfigSnapshot {
+    /// Returns the configuration of each device.
+    pub fn devices(&self) -> &[DeviceSnapshot] {
+        &self.devices
+    }
+}
+
+/// The local record of the configuration applied to the devices.
+///
+/// If enabled by [`Controller::enable_snapshot`], every frame confirmed by the devices is also fed to the emulators here, so that the configuration can be captured without reading back from the devices.
+#[derive(Default)]
+pub(crate) struct ConfigTracker {
+    // `CPUEmulator` is not `Sync`, so `Mutex` is used to keep `Controller` `Sync`.
+    cpus: Option<Mutex<Vec<CPUEmulator>>>,
+    modified: bool,
+}
+
+impl ConfigTracker {
+    fn emulators(geometry: &Geometry) -> Mutex<Vec<CPUEmulator>> {
+        Mutex::new(
+            geometry
+                .iter()
+                .map(|dev| CPUEmulator::new(dev.idx(), dev.num_transducers()))
+                .collect(),
+        )
+    }
+
+    /// Creates a tracker for the devices just cleared, which inherits whether tracking is enabled from `self`.
+    pub(crate) fn reset(&self, geometry: &Geometry) -> Self {
+        Self {
+            cpus: self.cpus.as_ref().map(|_| Self::emulators(geometry)),
+            modified: false,
+        }
+    }
+
+    /// Marks the devices as cleared, i.e., in the same state as the emulators just created.
+    pub(crate) fn cleared(&mut self) {
+        self.modified = false;
+    }
+
+    pub(crate) fn enable(&mut self, geometry: &Geometry) -> Result<(), AUTDError> {
+        if self.cpus.is_some() {
+            return Ok(());
+        }
+        if self.modified {
+            return Err(AUTDError::SnapshotEnabledAfterSend);
+        }
+        self.cpus = Some(Self::emulators(geometry));
+        Ok(())
+    }
+
+    pub(crate) fn record(&mut self, tx: &[TxMessage]) {
+        match self.cpus.as_mut() {
+            Some(cpus) => cpus.get_mut().unwrap().iter_mut().for_each(|cpu| {
+                cpu.send(tx);
+                cpu.update();
+            }),
+            None => self.modified = true,
+        }
+    }
+
+    fn cpus(&self) -> Result<MutexGuard<'_, Vec<CPUEmulator>>, AUTDError> {
+        self.cpus
+            .as_ref()
+            .map(|cpus| cpus.lock().unwrap())
+            .ok_or(AUTDError::SnapshotDisabled)
+    }
+
+    fn modulation(cpu: &CPUEmulator, segment: Segment) -> SegmentSnapshot {
+        let fpga = cpu.fpga();
+        SegmentSnapshot {
+            segment,
+            freq_division: fpga.modulation_freq_division(segment),
+            cycle: fpga.modulation_cycle(segment),
+            loop_behavior: fpga.modulation_loop_behavior(segment),
+        }
+    }
+
+    fn stm(cpu: &CPUEmulator, segment: Segment) -> (SegmentSnapshot, DriveKind) {
+        let fpga = cpu.fpga();
+        let stm = SegmentSnapshot {
+            segment,
+            freq_division: fpga.stm_freq_division(segment),
+            cycle: fpga.stm_cycle(segment),
+            loop_behavior: fpga.stm_loop_behavior(segment),
+        };
+        let drive = match (fpga.is_stm_gain_mode(segment), stm.cycle) {
+            (true, 1) => DriveKind::Gain,
+            (true, _) => DriveKind::GainSTM,
+            (false, _) => DriveKind::FociSTM,
+        };
+        (stm, drive)
+    }
+
+    pub(crate) fn snapshot(&self) -> Result<ConfigSnapshot, AUTDError> {
+        Ok(ConfigSnapshot {
+            devices: self
+                .cpus()?
+                .iter()
+                .map(|cpu| {
+                    let fpga = cpu.fpga();
+                    let (stm, drive) = Self::stm(cpu, fpga.req_stm_segment());
+                    DeviceSnapshot {
+                        silencer: if fpga.silencer_fixed_update_rate_mode() {
+                            SilencerSnapshot::FixedUpdateRate(fpga.silencer_update_rate())
+                        } else {
+                            SilencerSnapshot::FixedCompletionSteps(FixedCompletionSteps {
+                                strict_mode: cpu.silencer_strict_mode(),
+                                ..fpga.silencer_completion_steps()
+                            })
+                        },
+                        silencer_target: fpga.silencer_target(),
+                        modulation: Self::modulation(cpu, fpga.req_modulation_segment()),
+                        stm,
+                        drive,
+                        phase_correction: fpga.phase_correction(),
+                        pulse_width_encoder: fpga.pulse_width_encoder_table(),
+                    }
+                })
+                .collect(),
+        })
+    }
+
+    pub(crate) fn plan(
+        &self,
+        snapshot: &ConfigSnapshot,
+        geometry: &Geometry,
+    ) -> Result<RestorePlan, AUTDError> {
+        let cpus = self.cpus()?;
+        if snapshot.devices.len() != cpus.len() {
+            return Err(AUTDError::SnapshotDeviceMismatch(
+                snapshot.devices.len(),
+                cpus.len(),
+            ));
+        }
+
+        let mut plan = RestorePlan::default();
+        geometry.devices().try_for_each(|dev| {
+            let idx = dev.idx();
+            let cpu = &cpus[idx];
+            let s = &snapshot.devices[idx];
+
+            if Self::modulation(cpu, s.modulation.segment) != s.modulation {
+                return Err(AUTDError::SnapshotOverwritten(
+                    idx,
+                    format!("modulation in {:?}", s.modulation.segment),
+                ));
+            }
+            if Self::stm(cpu, s.stm.segment) != (s.stm, s.drive) {
+                return Err(AUTDError::SnapshotOverwritten(
+                    idx,
+                    format!("{:?} in {:?}", s.drive, s.stm.segment),
+                ));
+            }
+
+            // Strict mode is applied after the segments are restored, because the current silencer may reject the segments of the snapshot and vice versa.
+            plan.silencer.insert(
+                idx,
+                match s.silencer {
+                    SilencerSnapshot::FixedCompletionSteps(config) => {
+                        if config.strict_mode {
+                            plan.strict_silencer.insert(
+                                idx,
+                                Silencer {
+                                    config,
+                                    target: s.silencer_target,
+                                },
+                            );
+                        }
+                        Silencer {
+                            config: FixedCompletionSteps {
+                                strict_mode: false,
+                                ..config
+                            },
+                            target: s.silencer_target,
+                        }
+                        .into_boxed()
+                    }
+                    SilencerSnapshot::FixedUpdateRate(config) => Silencer {
+                        config,
+                        target: s.silencer_target,
+                    }
+                    .into_boxed(),
+                },
+            );
+
+            // The firmware accepts `Immediate` only for infinite loops, and the synchronized modes only for finite loops.
+            let transition = |loop_behavior| match loop_behavior {
+                LoopBehavior::Infinite => TransitionMode::Immediate,
+                LoopBehavior::Finite(_) => TransitionMode::SyncIdx,
+            };
+            if cpu.fpga().req_modulation_segment() != s.modulation.segment {
+                plan.modulation.insert(
+                    idx,
+                    SwapSegment::Modulation(
+                        s.modulation.segment,
+                        transition(s.modulation.loop_behavior),
+                    ),
+                );
+            }
+            if cpu.fpga().req_stm_segment() != s.stm.segment {
+                plan.stm.insert(
+                    idx,
+                    match s.drive {
+                        DriveKind::Gain => {
+                            SwapSegment::Gain(s.stm.segment, TransitionMode::Immediate)
+                        }
+                        DriveKind::FociSTM => {
+                            SwapSegment::FociSTM(s.stm.segment, transition(s.stm.loop_behavior))
+                        }
+                        DriveKind::GainSTM => {
+                            SwapSegment::GainSTM(s.stm.segment, transition(s.stm.loop_behavior))
+                        }
+                    },
+                );
+            }
+            Ok(())
+        })?;
+        Ok(plan)
+    }
+}
+
+/// The datagrams to restore a [`ConfigSnapshot`], which are sent in the order of the fields.
+#[derive(Default)]
+pub(crate) struct RestorePlan {
+    pub(crate) silencer: HashMap<usize, BoxedDatagram>,
+    pub(crate) modulation: HashMap<usize, SwapSegment>,
+    pub(crate) stm: HashMap<usize, SwapSegment>,
+    pub(crate) strict_silencer: HashMap<usize, Silencer<FixedCompletionSteps>>,
+}
+
+impl RestorePlan {
+    pub(crate) fn keys<D>(map: &HashMap<usize, D>) -> HashSet<usize> {
+        map.keys().copied().collect()
+    }
+}
+
+impl ConfigSnapshot {
+    pub(crate) fn tables(&self) -> BoxedDatagram {
+        let phase_correction = self
+            .devices
+            .iter()
+            .map(|d| d.phase_correction.clone())
+            .collect::<Vec<_>>();
+        let pulse_width_encoder = self
+            .devices
+            .iter()
+            .map(|d| d.pulse_width_encoder.clone())
+            .collect::<Vec<_>>();
+        (
+            PhaseCorrection::new(move |dev| {
+                let table = phase_correction[dev.idx()].clone();
+                move |tr| table[tr.idx()]
+            }),
+            PulseWidthEncoder::new(move |dev| {
+                let table = pulse_width_encoder[dev.idx()].clone();
+                move |i| table[i as usize]
+            }),
+        )
+            .into_boxed()
+    }
+}
+
+impl<L: Link> Controller<L> {
+    /// Enables tracking the configuration for [`Self::snapshot`].
+    ///
+    /// Tracking feeds every frame sent to an emulator of each device, so it is disabled by default.
+    /// Because the emulators start from the state just after opening, this must be called before sending any data.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AUTDError::SnapshotEnabledAfterSend`] if any data has already been sent.
+    pub fn enable_snapshot(&mut self) -> Result<(), AUTDError> {
+        self.config.enable(&self.geometry)
+    }
+
+    /// Captures the configuration currently applied to the devices.
+    ///
+    /// Returns [`AUTDError::SnapshotDisabled`] if tracking is not enabled by [`Self::enable_snapshot`].
+    /// The configuration is recorded locally from the data sent by this controller, so no communication with the devices is needed.
+    /// The snapshot holds the silencer, the active modulation and STM segments, the phase correction, and the pulse width encoder table.
+    /// For the segments, only the metadata is captured, and the data is restored by [`Self::restore`] from the segment in the devices.
+    /// Therefore, write the temporary data to the other segment with [`TransitionMode`] not to overwrite the captured segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use autd3::prelude::*;
+    /// use autd3::driver::datagram::WithSegment;
+    ///
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+    /// autd.enable_snapshot()?;
+    /// autd.send((Sine { freq: 150. * Hz, option: Default::default() }, Uniform { intensity: EmitIntensity::MAX, phase: Phase::ZERO }))?;
+    ///
+    /// let snapshot = autd.snapshot()?;
+    ///
+    /// autd.send(WithSegment {
+    ///     inner: Static { intensity: 0x80 },
+    ///     segment: Segment::S1,
+    ///     transition_mode: Some(TransitionMode::Immediate),
+    /// })?;
+    ///
+    /// autd.restore(&snapshot)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn snapshot(&self) -> Result<ConfigSnapshot, AUTDError> {
+        self.config.snapshot()
+    }
+
+    /// Restores the configuration captured by [`Self::snapshot`] to the enabled devices.
+    ///
+    /// If the captured segment has been overwritten with the different metadata, [`AUTDError::SnapshotOverwritten`] is returned without sending anything.
+    pub fn restore(&mut self, snapshot: &ConfigSnapshot) -> Result<(), AUTDError> {
+        let RestorePlan {
+            silencer,
+            modulation,
+            stm,
+            strict_silencer,
+        } = self.config.plan(snapshot, &self.geometry)?;
+
+        let mut sender = self.sender(SenderOption::<SpinSleeper>::default());
+        let keys = RestorePlan::keys(&silencer);
+        sender.group_send(|dev| keys.get(&dev.idx()).copied(), silencer)?;
+        sender.send(snapshot.tables())?;
+        for swap in [modulation, stm] {
+            if !swap.is_empty() {
+                let keys = RestorePlan::keys(&swap);
+                sender.group_send(|dev| keys.get(&dev.idx()).copied(), swap)?;
+            }
+        }
+        if !strict_silencer.is_empty() {
+            let keys = RestorePlan::keys(&strict_silencer);
+            sender.group_send(|dev| keys.get(&dev.idx()).copied(), strict_silencer)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroU16,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+
+    use autd3_driver::{
+        datagram::WithSegment, defined::Hz, error::AUTDDriverError, firmware::fpga::EmitIntensity,
+    };
+    #[cfg(feature = "stm")]
+    use autd3_driver::{
+        datagram::{FociSTM, GainSTM},
+        firmware::fpga::SamplingConfig,
+        geometry::Point3,
+    };
+
+    use crate::{
+        controller::tests::create_controller,
+        gain::{Null, Uniform},
+        link::{Audit, AuditOption, Scenario},
+        modulation::{Sine, Static},
+        prelude::AUTD3,
+    };
+
+    use super::*;
+
+    fn uniform(intensity: u8) -> Uniform {
+        Uniform {
+            intensity: EmitIntensity(intensity),
+            phase: Phase::ZERO,
+        }
+    }
+
+    #[rstest::rstest]
+    #[case(DriveKind::Gain, uniform(0x80).into_boxed())]
+    #[cfg_attr(feature = "stm", case(DriveKind::FociSTM, FociSTM { foci: vec![Point3::origin(), Point3::origin()], config: SamplingConfig::FREQ_MIN }.into_boxed()))]
+    #[cfg_attr(feature = "stm", case(DriveKind::GainSTM, GainSTM { gains: vec![uniform(0x80), uniform(0x81)], config: SamplingConfig::FREQ_MIN, option: Default::default() }.into_boxed()))]
+    #[test]
+    fn restore(#[case] drive: DriveKind, #[case] d: BoxedDatagram) -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        autd.enable_snapshot()?;
+
+        autd.send((
+            Silencer {
+                config: FixedUpdateRate {
+                    intensity: NonZeroU16::new(256).unwrap(),
+                    phase: NonZeroU16::new(256).unwrap(),
+                },
+                target: SilencerTarget::PulseWidth,
+            },
+            PhaseCorrection::new(|_| |tr| Phase(tr.idx() as _)),
+        ))?;
+        autd.send(Sine {
+            freq: 150. * Hz,
+            option: Default::default(),
+        })?;
+        autd.send(d)?;
+        let snapshot = autd.snapshot()?;
+        assert!(snapshot.devices().iter().all(|dev| dev.drive == drive));
+
+        autd.send((
+            Silencer::<FixedCompletionSteps>::default(),
+            PhaseCorrection::new(|_| |_| Phase::ZERO),
+        ))?;
+        autd.send((
+            WithSegment {
+                inner: Static { intensity: 0x80 },
+                segment: Segment::S1,
+                transition_mode: Some(TransitionMode::Immediate),
+            },
+            WithSegment {
+                inner: Null,
+                segment: Segment::S1,
+                transition_mode: Some(TransitionMode::Immediate),
+            },
+        ))?;
+        assert_ne!(snapshot, autd.snapshot()?);
+
+        autd.restore(&snapshot)?;
+
+        assert_eq!(snapshot, autd.snapshot()?);
+        autd.link()
+            .iter()
+            .try_for_each(|cpu| -> anyhow::Result<()> {
+                let fpga = cpu.fpga();
+                assert!(fpga.silencer_fixed_update_rate_mode());
+                assert_eq!(SilencerTarget::PulseWidth, fpga.silencer_target());
+                assert_eq!(Segment::S0, fpga.req_modulation_segment());
+                assert_eq!(Segment::S0, fpga.req_stm_segment());
+                assert_eq!(
+                    (0..cpu.num_transducers())
+                        .map(|i| Phase(i as _))
+                        .collect::<Vec<_>>(),
+                    fpga.phase_correction()
+                );
+                Ok(())
+            })?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_strict_silencer() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        autd.enable_snapshot()?;
+
+        let snapshot = autd.snapshot()?;
+        assert_eq!(
+            SilencerSnapshot::FixedCompletionSteps(FixedCompletionSteps::default()),
+            snapshot.devices()[0].silencer
+        );
+
+        autd.send(Silencer {
+            config: FixedCompletionSteps {
+                strict_mode: false,
+                ..Default::default()
+            },
+            target: SilencerTarget::Intensity,
+        })?;
+        assert_ne!(snapshot, autd.snapshot()?);
+
+        autd.restore(&snapshot)?;
+        assert_eq!(snapshot, autd.snapshot()?);
+        assert!(autd.link()[0].silencer_strict_mode());
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_overwritten() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        autd.enable_snapshot()?;
+
+        autd.send(Sine {
+            freq: 150. * Hz,
+            option: Default::default(),
+        })?;
+        let snapshot = autd.snapshot()?;
+
+        autd.send(Static { intensity: 0x80 })?;
+        let current = autd.snapshot()?;
+
+        assert_eq!(
+            Err(AUTDError::SnapshotOverwritten(
+                0,
+                "modulation in S0".to_string()
+            )),
+            autd.restore(&snapshot)
+        );
+        assert_eq!(current, autd.snapshot()?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn restore_device_mismatch() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        autd.enable_snapshot()?;
+
+        let snapshot = autd.snapshot()?;
+        autd.add_device(AUTD3::default())?;
+
+        assert_eq!(
+            Err(AUTDError::SnapshotDeviceMismatch(1, 2)),
+            autd.restore(&snapshot)
+        );
+        assert_eq!(2, autd.snapshot()?.devices().len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_disabled() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        assert_eq!(Err(AUTDError::SnapshotDisabled), autd.snapshot());
+        assert_eq!(
+            Err(AUTDError::SnapshotDisabled),
+            autd.restore(&ConfigSnapshot { devices: vec![] })
+        );
+
+        autd.send(Static::default())?;
+        assert_eq!(
+            Err(AUTDError::SnapshotEnabledAfterSend),
+            autd.enable_snapshot()
+        );
+
+        autd.add_device(AUTD3::default())?;
+        autd.enable_snapshot()?;
+        assert_eq!(2, autd.snapshot()?.devices().len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_failed_send() -> anyhow::Result<()> {
+        let stale = Arc::new(AtomicBool::new(false));
+        let mut autd = Controller::open(
+            [AUTD3::default()],
+            Audit::new(AuditOption {
+                scenario: Scenario::new().on_response(0, {
+                    let stale = stale.clone();
+                    move |ctx, rx| {
+                        if stale.load(Ordering::Relaxed) {
+                            *rx = ctx.previous;
+                        }
+                    }
+                }),
+                ..Default::default()
+            }),
+        )?;
+        autd.enable_snapshot()?;
+        let snapshot = autd.snapshot()?;
+
+        stale.store(true, Ordering::Relaxed);
+        assert_eq!(
+            Err(AUTDDriverError::ConfirmResponseFailed),
+            autd.sender(SenderOption::<SpinSleeper> {
+                timeout: Some(std::time::Duration::from_millis(10)),
+                ..Default::default()
+            })
+            .send(Sine {
+                freq: 150. * Hz,
+                option: Default::default(),
+            })
+        );
+        assert_eq!(snapshot, autd.snapshot()?);
+
+        stale.store(false, Ordering::Relaxed);
+        autd.send(Sine {
+            freq: 150. * Hz,
+            option: Default::default(),
+        })?;
+        assert_ne!(snapshot, autd.snapshot()?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,309 @@
+use std::time::Duration;
+
+use autd3_core::{
+    datagram::{DatagramL, LoopBehavior, Segment},
+    ethercat::DcSysTime,
+    link::Link,
+};
+use autd3_driver::{
+    error::AUTDDriverError,
+    firmware::operation::{Operation, OperationGenerator},
+};
+
+use super::{Controller, SenderOption, SpinSleeper};
+
+/// The option of [`Controller::send_synchronized`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncOption {
+    /// The time from now to the transition, which must be long enough to send the data to both controllers. The default value is 100 ms.
+    pub lead_time: Duration,
+    /// The minimum time remaining until the transition when sending to each controller. The default value is 1 ms.
+    ///
+    /// If less time remains, the data is not sent so that the transition is not triggered on one controller only because the other one missed it.
+    pub tolerance: Duration,
+    /// The DcSysTime of the other controller minus that of this controller in nanoseconds. The default value is `0`.
+    ///
+    /// The DcSysTime of each EtherCAT segment is based on the host clock when the link is opened, so this is zero if both segments are driven by the same host.
+    /// Otherwise, e.g., with a remote link, measure the offset by [`DebugType::SysTimeEq`] on both segments.
+    ///
+    /// [`DebugType::SysTimeEq`]: autd3_driver::firmware::fpga::DebugType::SysTimeEq
+    pub clock_offset: i64,
+}
+
+impl Default for SyncOption {
+    fn default() -> Self {
+        Self {
+            lead_time: Duration::from_millis(100),
+            tolerance: Duration::from_millis(1),
+            clock_offset: 0,
+        }
+    }
+}
+
+fn shift(time: DcSysTime, offset: i64) -> DcSysTime {
+    if offset >= 0 {
+        time + Duration::from_nanos(offset as u64)
+    } else {
+        time - Duration::from_nanos(offset.unsigned_abs())
+    }
+}
+
+impl SyncOption {
+    /// Sets [`SyncOption::clock_offset`] to the difference between `this` and `other`, which are the DcSysTimes of this and the other controller observed at the same moment.
+    pub fn aligned(self, this: DcSysTime, other: DcSysTime) -> Self {
+        Self {
+            clock_offset: other.sys_time() as i64 - this.sys_time() as i64,
+            ..self
+        }
+    }
+
+    /// Converts the DcSysTime `time` of this controller into that of the other controller with [`SyncOption::clock_offset`].
+    pub fn align(&self, time: DcSysTime) -> DcSysTime {
+        shift(time, self.clock_offset)
+    }
+
+    pub(crate) fn transition_time(&self) -> (DcSysTime, DcSysTime) {
+        let time = DcSysTime::now() + self.lead_time;
+        (time, self.align(time))
+    }
+
+    pub(crate) fn check(&self, time: DcSysTime) -> Result<(), AUTDDriverError> {
+        if time <= DcSysTime::now() + self.tolerance {
+            return Err(AUTDDriverError::MissTransitionTime);
+        }
+        Ok(())
+    }
+}
+
+impl<L: Link> Controller<L> {
+    /// Sends `a` to this controller and `b` to `other` so that both switch to the `segment` at the same system time.
+    ///
+    /// This is useful to drive the devices on multiple EtherCAT segments, each of which has its own [`Controller`].
+    /// The transition time is [`SyncOption::lead_time`] from now, aligned by [`SyncOption::clock_offset`] on `other`, and is returned on success.
+    /// Note that `segment` must be different from the current segment and `loop_behavior` must be finite as [`Sender::send_at`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the index of the controller (0 for this controller and 1 for `other`) and the error if sending fails.
+    /// If less than [`SyncOption::tolerance`] remains until the transition on either controller, [`AUTDDriverError::MissTransitionTime`] is returned without sending.
+    ///
+    /// If sending to `other` fails, the transition of this controller has already been scheduled and is not canceled automatically.
+    /// To cancel it, send [`SwapSegment`] with the current segment and [`TransitionMode::Immediate`] to this controller before the transition time, e.g., `SwapSegment::Modulation(Segment::S0, TransitionMode::Immediate)` for a [`Modulation`] sent to [`Segment::S1`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use autd3::prelude::*;
+    /// # fn main() -> Result<(), AUTDError> {
+    /// let mut a = Controller::open([AUTD3::default()], Nop::new())?;
+    /// let mut b = Controller::open([AUTD3::default()], Nop::new())?;
+    ///
+    /// a.send_synchronized(
+    ///     Static::default(),
+    ///     &mut b,
+    ///     Static::default(),
+    ///     Segment::S1,
+    ///     LoopBehavior::ONCE,
+    ///     SyncOption::default(),
+    /// )
+    /// .map_err(|(_, e)| e)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Sender::send_at`]: super::Sender::send_at
+    /// [`SwapSegment`]: autd3_driver::datagram::SwapSegment
+    /// [`TransitionMode::Immediate`]: autd3_driver::firmware::fpga::TransitionMode::Immediate
+    /// [`Modulation`]: autd3_core::modulation::Modulation
+    #[tracing::instrument(level = "debug", skip(self, other))]
+    pub fn send_synchronized<L2: Link, A: DatagramL, B: DatagramL>(
+        &mut self,
+        a: A,
+        other: &mut Controller<L2>,
+        b: B,
+        segment: Segment,
+        loop_behavior: LoopBehavior,
+        option: SyncOption,
+    ) -> Result<DcSysTime, (usize, AUTDDriverError)>
+    where
+        AUTDDriverError: From<A::Error> + From<B::Error>,
+        A::G: OperationGenerator,
+        B::G: OperationGenerator,
+        AUTDDriverError: From<<<A::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<A::G as OperationGenerator>::O2 as Operation>::Error>
+            + From<<<B::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<B::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let (time_a, time_b) = option.transition_time();
+        option.check(time_a).map_err(|e| (0, e))?;
+        option.check(time_b).map_err(|e| (1, e))?;
+        self.sender(SenderOption::<SpinSleeper>::default())
+            .send_at(a, segment, loop_behavior, time_a)
+            .map_err(|e| (0, e))?;
+        option
+            .check(time_b)
+            .and_then(|_| {
+                other
+                    .sender(SenderOption::<SpinSleeper>::default())
+                    .send_at(b, segment, loop_behavior, time_b)
+            })
+            .map_err(|e| (1, e))?;
+        Ok(time_a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{datagram::SwapSegment, firmware::fpga::TransitionMode};
+
+    use crate::{controller::tests::create_controller, modulation::Static};
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(0)]
+    #[case(1_000_000)]
+    #[case(-1_000_000)]
+    #[test]
+    fn send_synchronized(#[case] clock_offset: i64) -> anyhow::Result<()> {
+        let mut a = create_controller(1)?;
+        let mut b = create_controller(2)?;
+
+        let time = a
+            .send_synchronized(
+                Static::default(),
+                &mut b,
+                Static::default(),
+                Segment::S1,
+                LoopBehavior::ONCE,
+                SyncOption {
+                    clock_offset,
+                    ..Default::default()
+                },
+            )
+            .map_err(|(_, e)| e)?;
+
+        assert_eq!(
+            TransitionMode::SysTime(time),
+            a.link()[0].fpga().modulation_transition_mode()
+        );
+        b.link().iter().for_each(|cpu| {
+            assert_eq!(Segment::S1, cpu.fpga().req_modulation_segment());
+            assert_eq!(
+                TransitionMode::SysTime(shift(time, clock_offset)),
+                cpu.fpga().modulation_transition_mode()
+            );
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn send_synchronized_miss() -> anyhow::Result<()> {
+        let mut a = create_controller(1)?;
+        let mut b = create_controller(1)?;
+
+        assert_eq!(
+            Err((0, AUTDDriverError::MissTransitionTime)),
+            a.send_synchronized(
+                Static::default(),
+                &mut b,
+                Static::default(),
+                Segment::S1,
+                LoopBehavior::ONCE,
+                SyncOption {
+                    lead_time: Duration::from_millis(1),
+                    tolerance: Duration::from_millis(10),
+                    ..Default::default()
+                },
+            )
+        );
+        assert_eq!(
+            Err((1, AUTDDriverError::MissTransitionTime)),
+            a.send_synchronized(
+                Static::default(),
+                &mut b,
+                Static::default(),
+                Segment::S1,
+                LoopBehavior::ONCE,
+                SyncOption {
+                    clock_offset: -100_000_000,
+                    ..Default::default()
+                },
+            )
+        );
+        assert_eq!(Segment::S0, a.link()[0].fpga().req_modulation_segment());
+        assert_eq!(Segment::S0, b.link()[0].fpga().req_modulation_segment());
+
+        Ok(())
+    }
+
+    #[test]
+    fn aligned() {
+        let now = DcSysTime::now();
+        let option = SyncOption::default().aligned(now, now + Duration::from_millis(1));
+        assert_eq!(1_000_000, option.clock_offset);
+        assert_eq!(now + Duration::from_millis(1), option.align(now));
+
+        let option = SyncOption::default().aligned(now + Duration::from_millis(1), now);
+        assert_eq!(-1_000_000, option.clock_offset);
+        assert_eq!(now, option.align(now + Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn send_synchronized_infinite() -> anyhow::Result<()> {
+        let mut a = create_controller(1)?;
+        let mut b = create_controller(1)?;
+
+        assert!(matches!(
+            a.send_synchronized(
+                Static::default(),
+                &mut b,
+                Static::default(),
+                Segment::S1,
+                LoopBehavior::Infinite,
+                SyncOption::default(),
+            ),
+            Err((0, AUTDDriverError::IncompatibleTransitionMode(..)))
+        ));
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(Segment::S1, false)]
+    #[case(Segment::S0, true)]
+    #[test]
+    fn send_synchronized_cancel(
+        #[case] expect: Segment,
+        #[case] cancel: bool,
+    ) -> anyhow::Result<()> {
+        let mut a = create_controller(1)?;
+        let mut b = create_controller(1)?;
+
+        b.link_mut().break_down();
+
+        assert!(matches!(
+            a.send_synchronized(
+                Static::default(),
+                &mut b,
+                Static::default(),
+                Segment::S1,
+                LoopBehavior::ONCE,
+                SyncOption::default(),
+            ),
+            Err((1, AUTDDriverError::Link(_)))
+        ));
+        if cancel {
+            a.send(SwapSegment::Modulation(
+                Segment::S0,
+                TransitionMode::Immediate,
+            ))?;
+        }
+        a.link_mut()[0].update_with_sys_time(DcSysTime::now() + Duration::from_secs(1));
+
+        assert_eq!(expect, a.link()[0].fpga().current_mod_segment());
+
+        Ok(())
+    }
+}
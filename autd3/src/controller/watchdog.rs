@@ -0,0 +1,284 @@
+use std::{
+    sync::mpsc,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use autd3_core::link::Link;
+use autd3_driver::{
+    datagram::{Datagram, ForceFan},
+    error::AUTDDriverError,
+    firmware::operation::{Operation, OperationGenerator},
+};
+
+use crate::gain::Null;
+
+use super::{
+    background::{job, send_job, Job, SendFuture},
+    Controller, Sequence,
+};
+
+/// The option of [`Watchdog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchdogOption {
+    /// The output is muted if [`Watchdog::feed`] is not called within this duration. The default is 1 s.
+    ///
+    /// If `None`, the output is muted only when the [`Watchdog`] is dropped.
+    pub timeout: Option<Duration>,
+    /// The interval of sending the keep-alive datagram. The default is 100 ms.
+    pub keep_alive_interval: Duration,
+    /// The value of [`ForceFan`] sent as the keep-alive datagram. The default is `false`.
+    pub force_fan: bool,
+}
+
+impl Default for WatchdogOption {
+    fn default() -> Self {
+        Self {
+            timeout: Some(Duration::from_secs(1)),
+            keep_alive_interval: Duration::from_millis(100),
+            force_fan: false,
+        }
+    }
+}
+
+enum Message<L: Link> {
+    Job(Job<L>),
+    Feed,
+    Release,
+}
+
+fn mute<L: Link>(autd: &mut Controller<L>) {
+    if let Err(e) = autd.send(Null) {
+        tracing::error!("Failed to mute the output: {}", e);
+    }
+}
+
+/// A host-side watchdog that owns the [`Controller`] in a background thread and mutes the output if the application stops feeding it.
+///
+/// The background thread sends [`ForceFan`] with [`WatchdogOption::force_fan`] as a lightweight keep-alive datagram at [`WatchdogOption::keep_alive_interval`], so that a broken link is reported even if the application sends nothing.
+/// If [`Watchdog::feed`] is not called within [`WatchdogOption::timeout`], [`Null`] is sent to all devices. Feeding again does not restore the output, so send the data again after feeding.
+/// [`Null`] is also sent when the [`Watchdog`] is dropped, e.g., when the application thread panics, but not when the [`Controller`] is taken back by [`Watchdog::into_controller`].
+///
+/// The firmware keeps the last output by itself, so this cannot mute the output if the host process is killed or the host loses power.
+///
+/// # Examples
+///
+/// Feed the watchdog in the main loop of the application.
+///
+/// ```
+/// use std::time::Duration;
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// let watchdog = Watchdog::new(
+///     autd,
+///     WatchdogOption {
+///         timeout: Some(Duration::from_millis(500)),
+///         ..Default::default()
+///     },
+/// );
+/// let future = watchdog.enqueue(Static::default());
+/// # let _ = future;
+/// for _ in 0..3 {
+///     // do something, and the output is muted if this takes more than 500 ms
+///     watchdog.feed();
+/// }
+/// let autd = watchdog.into_controller();
+/// autd.close()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Watchdog<L: Link + Send + 'static> {
+    tx: Option<mpsc::Sender<Message<L>>>,
+    handle: Option<JoinHandle<Controller<L>>>,
+}
+
+impl<L: Link + Send + 'static> Watchdog<L> {
+    /// Creates a new [`Watchdog`] and spawns the background thread. The timeout starts at this point.
+    pub fn new(mut autd: Controller<L>, option: WatchdogOption) -> Self {
+        let (tx, rx) = mpsc::channel::<Message<L>>();
+        let handle = std::thread::spawn(move || {
+            let mut deadline = option.timeout.map(|t| Instant::now() + t);
+            let mut next_keep_alive = Instant::now();
+            loop {
+                let wake = deadline.map_or(next_keep_alive, |d| d.min(next_keep_alive));
+                match rx.recv_timeout(wake.saturating_duration_since(Instant::now())) {
+                    Ok(Message::Job(job)) => job(&mut autd),
+                    Ok(Message::Feed) => deadline = option.timeout.map(|t| Instant::now() + t),
+                    Ok(Message::Release) => break,
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        mute(&mut autd);
+                        break;
+                    }
+                }
+                let now = Instant::now();
+                if deadline.is_some_and(|d| d <= now) {
+                    tracing::warn!("Watchdog is not fed within {:?}", option.timeout);
+                    mute(&mut autd);
+                    deadline = None;
+                }
+                if next_keep_alive <= now {
+                    let force_fan = option.force_fan;
+                    if let Err(e) = autd.send(ForceFan::new(move |_| force_fan)) {
+                        tracing::error!("Failed to send the keep-alive datagram: {}", e);
+                    }
+                    next_keep_alive = now + option.keep_alive_interval;
+                }
+            }
+            autd
+        });
+        Self {
+            tx: Some(tx),
+            handle: Some(handle),
+        }
+    }
+
+    fn post(&self, msg: Message<L>) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(msg);
+        }
+    }
+
+    /// Restarts the timeout.
+    pub fn feed(&self) {
+        self.post(Message::Feed);
+    }
+
+    /// Enqueues the [`Datagram`] to be sent by the background thread. See also [`BackgroundSender::enqueue`].
+    ///
+    /// This does not feed the watchdog.
+    ///
+    /// [`BackgroundSender::enqueue`]: super::BackgroundSender::enqueue
+    pub fn enqueue<D: Datagram + Send + 'static>(&self, s: D) -> SendFuture
+    where
+        AUTDDriverError: From<D::Error>,
+        D::G: OperationGenerator,
+        AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+            + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+    {
+        let (job, future) = send_job(s);
+        self.post(Message::Job(job));
+        future
+    }
+
+    /// Enqueues the [`Sequence`] returned by `f` to be sent by the background thread with [`Controller::send_sequence`].
+    ///
+    /// Since [`Sequence`] is not [`Send`], `f` is called in the background thread. This does not feed the watchdog.
+    pub fn enqueue_sequence<F>(&self, f: F) -> SendFuture
+    where
+        F: FnOnce() -> Sequence + Send + 'static,
+    {
+        let (job, future) = job(move |autd: &mut Controller<L>| autd.send_sequence(f()));
+        self.post(Message::Job(job));
+        future
+    }
+
+    /// Stops the watchdog after all enqueued [`Datagram`]s are sent, and returns the [`Controller`] without muting the output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the background thread panicked.
+    pub fn into_controller(mut self) -> Controller<L> {
+        self.post(Message::Release);
+        self.tx.take();
+        match self.handle.take().unwrap().join() {
+            Ok(autd) => autd,
+            Err(e) => std::panic::resume_unwind(e),
+        }
+    }
+}
+
+impl<L: Link + Send + 'static> Drop for Watchdog<L> {
+    fn drop(&mut self) {
+        self.tx.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::firmware::fpga::{Drive, EmitIntensity, Phase, Segment};
+
+    use crate::{controller::tests::create_controller, gain::Uniform};
+
+    use super::*;
+
+    fn uniform() -> Uniform {
+        Uniform {
+            intensity: EmitIntensity::MAX,
+            phase: Phase::ZERO,
+        }
+    }
+
+    fn option(timeout: Option<Duration>) -> WatchdogOption {
+        WatchdogOption {
+            timeout,
+            keep_alive_interval: Duration::from_millis(1),
+            force_fan: true,
+        }
+    }
+
+    #[test]
+    fn mute_on_timeout() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        autd.send(uniform())?;
+
+        let watchdog = Watchdog::new(autd, option(Some(Duration::from_millis(10))));
+        std::thread::sleep(Duration::from_millis(100));
+        let autd = watchdog.into_controller();
+
+        autd.iter().for_each(|dev| {
+            assert_eq!(
+                vec![Drive::NULL; dev.num_transducers()],
+                autd.link()[dev.idx()].fpga().drives_at(Segment::S0, 0)
+            );
+            assert!(autd.link()[dev.idx()].fpga().is_force_fan());
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn feed() -> anyhow::Result<()> {
+        let autd = create_controller(1)?;
+
+        let watchdog = Watchdog::new(autd, option(Some(Duration::from_millis(500))));
+        tokio_test::block_on(watchdog.enqueue(uniform()))?;
+        (0..10).for_each(|_| {
+            std::thread::sleep(Duration::from_millis(10));
+            watchdog.feed();
+        });
+        let autd = watchdog.into_controller();
+
+        assert!(autd.link()[0]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == EmitIntensity::MAX));
+
+        Ok(())
+    }
+
+    #[test]
+    fn without_timeout() -> anyhow::Result<()> {
+        let autd = create_controller(1)?;
+
+        let watchdog = Watchdog::new(autd, option(None));
+        tokio_test::block_on(watchdog.enqueue_sequence(|| Sequence::new().push(uniform(), None)))?;
+        std::thread::sleep(Duration::from_millis(10));
+        let autd = watchdog.into_controller();
+
+        assert!(autd.link()[0]
+            .fpga()
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == EmitIntensity::MAX));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,642 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::Hash,
+    path::Path,
+    sync::Arc,
+};
+
+use autd3_core::{gain::Gain, link::Link};
+use autd3_driver::{
+    datagram::PhaseCorrection,
+    firmware::fpga::Phase,
+    geometry::{Complex, Device, Geometry, Transducer},
+};
+
+use crate::{
+    controller::Controller,
+    error::AUTDError,
+    gain::{Group, Null},
+};
+
+const HEADER: &str = "# autd3 phase correction";
+const MIN_SAMPLES: usize = 3;
+const DISABLED: &str = "x";
+
+type TransducerPhase = Box<dyn Fn(&Transducer) -> Phase + Send + Sync>;
+
+/// Phase correction values of each transducer obtained by calibration.
+///
+/// The values can be saved to a file with [`PhaseCalibration::save`] and reloaded at startup with [`PhaseCalibration::load`].
+/// The file is a text file which has a header line followed by one line per device, and each line has the phase values of the transducers in `0..=255` separated by spaces.
+/// The disabled transducers, i.e., known-dead elements, are written as `x`, and [`PhaseCalibration::apply_enable`] disables them on the geometry after loading.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// // The pressure amplitudes measured while sweeping the phase offset of each transducer.
+/// let sweep = |_tr: &autd3::driver::geometry::Transducer| {
+///     (0..8u8).map(|i| {
+///         let phase = Phase(i * 32);
+///         (phase, 1. + (phase.radian() - PI / 2.).cos())
+///     })
+/// };
+/// let calibration = PhaseCalibration::from_sweep(&autd, sweep)?;
+/// # let path = std::env::temp_dir().join("autd3_phase_calibration_doctest.txt");
+/// calibration.save(&path)?;
+///
+/// let calibration = PhaseCalibration::load(&path, &autd)?;
+/// autd.send(calibration.into_datagram(&autd)?)?;
+/// # std::fs::remove_file(&path).unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PhaseCalibration {
+    phases: Vec<Vec<Phase>>,
+    enable: Vec<Vec<bool>>,
+}
+
+impl PhaseCalibration {
+    /// Solves the phase correction values from the results of phase sweeps.
+    ///
+    /// For each transducer, `samples` returns the pairs of the phase offset and the pressure amplitude measured at the calibration point, e.g., with a microphone, while the phase offset of only that transducer is swept and the others are fixed.
+    /// The amplitude is maximized when the transducer is in phase with the others, so the correction value is the phase of the first harmonic of the amplitudes over the phase offset.
+    ///
+    /// At least 3 samples with distinct phase offsets are required for each enabled transducer of the enabled devices, otherwise [`AUTDError::InsufficientCalibrationSamples`] is returned. The correction values of the disabled devices and transducers are zero, and `samples` is not called for them.
+    /// The enable flags of the transducers are recorded to be saved with the correction values.
+    pub fn from_sweep<F, S>(geometry: &Geometry, samples: F) -> Result<Self, AUTDError>
+    where
+        F: Fn(&Transducer) -> S,
+        S: IntoIterator<Item = (Phase, f32)>,
+    {
+        Ok(Self {
+            phases: geometry
+                .iter()
+                .map(|dev| {
+                    if !dev.enable {
+                        return Ok(vec![Phase::ZERO; dev.num_transducers()]);
+                    }
+                    dev.iter()
+                        .map(|tr| {
+                            if !tr.enable {
+                                return Ok(Phase::ZERO);
+                            }
+                            let mut offsets = HashSet::new();
+                            let harmonic = samples(tr)
+                                .into_iter()
+                                .inspect(|(phase, _)| {
+                                    offsets.insert(phase.0);
+                                })
+                                .map(|(phase, amp)| Complex::from_polar(amp, phase.radian()))
+                                .sum::<Complex>();
+                            if offsets.len() < MIN_SAMPLES {
+                                return Err(AUTDError::InsufficientCalibrationSamples(
+                                    dev.idx(),
+                                    tr.idx(),
+                                ));
+                            }
+                            Ok(Phase::from(harmonic))
+                        })
+                        .collect()
+                })
+                .collect::<Result<_, _>>()?,
+            enable: geometry
+                .iter()
+                .map(|dev| dev.iter().map(|tr| tr.enable).collect())
+                .collect(),
+        })
+    }
+
+    /// Returns the phase correction values of each transducer of each device.
+    pub fn phases(&self) -> &[Vec<Phase>] {
+        &self.phases
+    }
+
+    /// Returns the enable flags of each transducer of each device.
+    pub fn enable(&self) -> &[Vec<bool>] {
+        &self.enable
+    }
+
+    /// Sets the enable flags of the transducers of `geometry` to the recorded ones.
+    ///
+    /// Returns [`AUTDError::PhaseCorrectionFile`] if the numbers of devices and transducers do not match the geometry.
+    pub fn apply_enable(&self, geometry: &mut Geometry) -> Result<(), AUTDError> {
+        Self::check_size(&self.enable, geometry)?;
+        geometry
+            .iter_mut()
+            .zip(self.enable.iter())
+            .for_each(|(dev, enable)| {
+                dev.iter_mut()
+                    .zip(enable.iter())
+                    .for_each(|(tr, &enable)| tr.enable = enable);
+            });
+        Ok(())
+    }
+
+    fn check_size<T>(values: &[Vec<T>], geometry: &Geometry) -> Result<(), AUTDError> {
+        if values.len() != geometry.len() {
+            return Err(AUTDError::PhaseCorrectionFile(format!(
+                "The number of devices ({}) does not match the geometry ({})",
+                values.len(),
+                geometry.len()
+            )));
+        }
+        geometry.iter().zip(values.iter()).try_for_each(|(dev, v)| {
+            if v.len() != dev.num_transducers() {
+                return Err(AUTDError::PhaseCorrectionFile(format!(
+                    "The number of transducers of device {} ({}) does not match the geometry ({})",
+                    dev.idx(),
+                    v.len(),
+                    dev.num_transducers()
+                )));
+            }
+            Ok(())
+        })
+    }
+
+    /// Saves the phase correction values to a file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AUTDError> {
+        let mut content = String::from(HEADER);
+        content.push('\n');
+        self.phases
+            .iter()
+            .zip(self.enable.iter())
+            .for_each(|(phases, enable)| {
+                content.push_str(
+                    &phases
+                        .iter()
+                        .zip(enable.iter())
+                        .map(|(p, &enable)| {
+                            if enable {
+                                p.0.to_string()
+                            } else {
+                                DISABLED.to_string()
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                );
+                content.push('\n');
+            });
+        std::fs::write(path, content).map_err(|e| AUTDError::PhaseCorrectionFile(e.to_string()))
+    }
+
+    /// Loads the phase correction values saved by [`PhaseCalibration::save`].
+    ///
+    /// Returns [`AUTDError::PhaseCorrectionFile`] if the file cannot be read or parsed, or if the numbers of devices and transducers do not match the geometry.
+    pub fn load(path: impl AsRef<Path>, geometry: &Geometry) -> Result<Self, AUTDError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AUTDError::PhaseCorrectionFile(e.to_string()))?;
+        let mut lines = content.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(AUTDError::PhaseCorrectionFile("Invalid header".to_string()));
+        }
+        let values = lines
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(i, line)| {
+                line.split_whitespace()
+                    .map(|v| match v {
+                        DISABLED => Ok(None),
+                        v => v.parse::<u8>().map(|v| Some(Phase(v))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| AUTDError::PhaseCorrectionFile(format!("Device {}: {}", i, e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::check_size(&values, geometry)?;
+        Ok(Self {
+            phases: values
+                .iter()
+                .map(|v| v.iter().map(|p| p.unwrap_or(Phase::ZERO)).collect())
+                .collect(),
+            enable: values
+                .iter()
+                .map(|v| v.iter().map(Option::is_some).collect())
+                .collect(),
+        })
+    }
+
+    /// Converts into [`PhaseCorrection`] to upload the correction values to the devices of `geometry`.
+    ///
+    /// Returns [`AUTDError::PhaseCorrectionFile`] if the numbers of devices and transducers do not match the geometry.
+    pub fn into_datagram(
+        self,
+        geometry: &Geometry,
+    ) -> Result<PhaseCorrection<TransducerPhase, impl Fn(&Device) -> TransducerPhase>, AUTDError>
+    {
+        Self::check_size(&self.phases, geometry)?;
+        Ok(PhaseCorrection::new(
+            move |dev: &Device| -> TransducerPhase {
+                let phases = self.phases[dev.idx()].clone();
+                Box::new(move |tr: &Transducer| phases[tr.idx()])
+            },
+        ))
+    }
+}
+
+/// Intensity scaling factors of each transducer obtained by amplitude calibration.
+///
+/// The output of the devices varies from device to device. [`AmplitudeCalibration::measure`] drives each device in turn with a reference gain, and the scaling factors attenuate all devices down to the weakest one.
+/// The factors are applied to any [`Gain`] by [`Calibrated`].
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default(), AUTD3::default()], Nop::new())?;
+///
+/// // The pressure amplitudes measured at the calibration point, e.g., with a microphone.
+/// let measured = [1.0, 0.8];
+/// let calibration = AmplitudeCalibration::measure(
+///     &mut autd,
+///     Uniform {
+///         intensity: EmitIntensity::MAX,
+///         phase: Phase::ZERO,
+///     },
+///     |dev| measured[dev.idx()],
+/// )?;
+///
+/// let pos = Point3::new(0., 0., 150. * mm);
+/// autd.send(Calibrated::new(
+///     Focus {
+///         pos,
+///         option: Default::default(),
+///     },
+///     calibration,
+/// ))?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Calibrated`]: crate::gain::Calibrated
+#[derive(Clone, Debug, PartialEq)]
+pub struct AmplitudeCalibration {
+    scales: Arc<Vec<Vec<f32>>>,
+}
+
+impl AmplitudeCalibration {
+    /// Measures the output of each device and solves the scaling factors.
+    ///
+    /// The enabled devices are driven one at a time with `reference` while the others output nothing, and `pressure` is called for each device to return the pressure amplitude measured externally, e.g., with a microphone.
+    /// The scaling factor of each device is the ratio of the minimum pressure to its pressure. After the measurement, [`Null`] is sent to all devices.
+    ///
+    /// Returns [`AUTDError::InvalidCalibrationPressure`] if the measured pressure is not positive and finite.
+    pub fn measure<L: Link, G: Gain + Clone>(
+        autd: &mut Controller<L>,
+        reference: G,
+        mut pressure: impl FnMut(&Device) -> f32,
+    ) -> Result<Self, AUTDError> {
+        Self::measure_with(
+            autd,
+            |dev| {
+                let idx = dev.idx();
+                move |_| Some(idx)
+            },
+            reference,
+            |geometry, &idx| pressure(&geometry[idx]),
+        )
+    }
+
+    /// Same as [`AmplitudeCalibration::measure`], but measures each group of transducers instead of each device.
+    ///
+    /// The transducers are grouped by `key_map` in the same way as [`Group`], and `pressure` is called for each group key. The scaling factors of the transducers not belonging to any group are 1.
+    pub fn measure_group<L, K, FK, F, G>(
+        autd: &mut Controller<L>,
+        key_map: F,
+        reference: G,
+        mut pressure: impl FnMut(&K) -> f32,
+    ) -> Result<Self, AUTDError>
+    where
+        L: Link,
+        K: Hash + Eq + Debug,
+        FK: Fn(&Transducer) -> Option<K>,
+        F: Fn(&Device) -> FK,
+        G: Gain + Clone,
+    {
+        Self::measure_with(autd, key_map, reference, |_, k| pressure(k))
+    }
+
+    fn measure_with<L, K, FK, F, G>(
+        autd: &mut Controller<L>,
+        key_map: F,
+        reference: G,
+        mut pressure: impl FnMut(&Geometry, &K) -> f32,
+    ) -> Result<Self, AUTDError>
+    where
+        L: Link,
+        K: Hash + Eq + Debug,
+        FK: Fn(&Transducer) -> Option<K>,
+        F: Fn(&Device) -> FK,
+        G: Gain + Clone,
+    {
+        let mut keys = Vec::new();
+        autd.devices().for_each(|dev| {
+            let f = key_map(dev);
+            dev.iter().filter_map(&f).for_each(|k| {
+                if !keys.contains(&k) {
+                    keys.push(k);
+                }
+            });
+        });
+
+        let pressures = keys
+            .into_iter()
+            .map(|k| {
+                autd.send(Group::new(
+                    |dev| {
+                        let f = key_map(dev);
+                        let k = &k;
+                        move |tr| (f(tr).as_ref() == Some(k)).then_some(())
+                    },
+                    HashMap::from([((), reference.clone())]),
+                ))?;
+                let p = pressure(autd, &k);
+                if !(p.is_finite() && p > 0.) {
+                    return Err(AUTDError::InvalidCalibrationPressure(format!("{:?}", k), p));
+                }
+                Ok((k, p))
+            })
+            .collect::<Result<HashMap<_, _>, AUTDError>>();
+        autd.send(Null)?;
+        let pressures = pressures?;
+
+        let min = pressures.values().copied().fold(f32::INFINITY, f32::min);
+        Ok(Self {
+            scales: Arc::new(
+                autd.iter()
+                    .map(|dev| {
+                        if !dev.enable {
+                            return vec![1.; dev.num_transducers()];
+                        }
+                        let f = key_map(dev);
+                        dev.iter()
+                            .map(|tr| f(tr).map_or(1., |k| min / pressures[&k]))
+                            .collect()
+                    })
+                    .collect(),
+            ),
+        })
+    }
+
+    /// Returns the scaling factors of each transducer of each device.
+    pub fn scales(&self) -> &[Vec<f32>] {
+        &self.scales
+    }
+
+    pub(crate) fn scales_arc(&self) -> Arc<Vec<Vec<f32>>> {
+        self.scales.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{controller::tests::create_controller, gain::Uniform, tests::create_geometry};
+
+    use autd3_driver::firmware::fpga::EmitIntensity;
+
+    use super::*;
+
+    fn sweep(tr: &Transducer) -> impl Iterator<Item = (Phase, f32)> {
+        let optimal = Phase(tr.idx() as u8);
+        (0..16u8).map(move |i| {
+            let offset = Phase(i * 16);
+            (
+                offset,
+                1. + 0.5 * (offset.radian() - optimal.radian()).cos(),
+            )
+        })
+    }
+
+    #[test]
+    fn from_sweep() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(2);
+        geometry[1].enable = false;
+
+        let calibration = PhaseCalibration::from_sweep(&geometry, sweep)?;
+
+        assert_eq!(
+            geometry[0]
+                .iter()
+                .map(|tr| Phase(tr.idx() as u8))
+                .collect::<Vec<_>>(),
+            calibration.phases()[0]
+        );
+        assert_eq!(
+            vec![Phase::ZERO; geometry[1].num_transducers()],
+            calibration.phases()[1]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_sweep_insufficient_samples() {
+        let geometry = create_geometry(1);
+        assert_eq!(
+            Err(AUTDError::InsufficientCalibrationSamples(0, 0)),
+            PhaseCalibration::from_sweep(&geometry, |_| [
+                (Phase::ZERO, 1.),
+                (Phase::PI, 0.),
+                (Phase::PI, 0.)
+            ])
+        );
+    }
+
+    #[test]
+    fn save_load() -> anyhow::Result<()> {
+        let geometry = create_geometry(2);
+        let path = std::env::temp_dir().join(format!(
+            "autd3_phase_calibration_save_load_{}.txt",
+            std::process::id()
+        ));
+
+        let calibration = PhaseCalibration::from_sweep(&geometry, sweep)?;
+        calibration.save(&path)?;
+        assert_eq!(calibration, PhaseCalibration::load(&path, &geometry)?);
+        assert!(matches!(
+            PhaseCalibration::load(&path, &create_geometry(1)),
+            Err(AUTDError::PhaseCorrectionFile(_))
+        ));
+
+        std::fs::write(&path, format!("{}\n0 1 x\n", HEADER))?;
+        assert!(matches!(
+            PhaseCalibration::load(&path, &geometry),
+            Err(AUTDError::PhaseCorrectionFile(_))
+        ));
+
+        std::fs::write(&path, "0 1 2\n")?;
+        assert_eq!(
+            Err(AUTDError::PhaseCorrectionFile("Invalid header".to_string())),
+            PhaseCalibration::load(&path, &geometry)
+        );
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn save_load_disabled_transducer() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(2);
+        geometry[1].iter_mut().skip(1).step_by(2).for_each(|tr| {
+            tr.enable = false;
+        });
+        let path = std::env::temp_dir().join(format!(
+            "autd3_phase_calibration_save_load_disabled_transducer_{}.txt",
+            std::process::id()
+        ));
+
+        let calibration = PhaseCalibration::from_sweep(&geometry, |tr| {
+            assert!(tr.enable);
+            sweep(tr)
+        })?;
+        assert!(calibration.enable()[0].iter().all(|&e| e));
+        assert!(calibration.enable()[1]
+            .iter()
+            .enumerate()
+            .all(|(i, &e)| e == (i % 2 == 0)));
+        assert_eq!(Phase::ZERO, calibration.phases()[1][1]);
+        calibration.save(&path)?;
+        assert!(std::fs::read_to_string(&path)?
+            .lines()
+            .nth(2)
+            .is_some_and(|line| line.starts_with("0 x 2 x ")));
+
+        let calibration = PhaseCalibration::load(&path, &geometry)?;
+        let mut geometry = create_geometry(2);
+        let version = geometry.version();
+        calibration.apply_enable(&mut geometry)?;
+        assert!(geometry.version() > version);
+        assert!(geometry[0].iter().all(|tr| tr.enable));
+        assert!(geometry[1]
+            .iter()
+            .all(|tr| tr.enable == (tr.idx() % 2 == 0)));
+
+        assert!(matches!(
+            calibration.apply_enable(&mut create_geometry(1)),
+            Err(AUTDError::PhaseCorrectionFile(_))
+        ));
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn into_datagram() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        let calibration = PhaseCalibration::from_sweep(&autd, sweep)?;
+        autd.send(calibration.clone().into_datagram(&autd)?)?;
+
+        assert_eq!(
+            calibration.phases()[0],
+            autd.link()[0].fpga().phase_correction()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_datagram_geometry_mismatch() -> anyhow::Result<()> {
+        let calibration = PhaseCalibration::from_sweep(&create_geometry(1), sweep)?;
+        assert!(matches!(
+            calibration.into_datagram(&create_geometry(2)),
+            Err(AUTDError::PhaseCorrectionFile(_))
+        ));
+        Ok(())
+    }
+
+    const REFERENCE: Uniform = Uniform {
+        intensity: EmitIntensity::MAX,
+        phase: Phase::ZERO,
+    };
+
+    #[test]
+    fn measure() -> anyhow::Result<()> {
+        let mut autd = create_controller(3)?;
+        autd[2].enable = false;
+
+        let mut measured = Vec::new();
+        let calibration = AmplitudeCalibration::measure(&mut autd, REFERENCE, |dev| {
+            measured.push(dev.idx());
+            [1.0, 0.5][dev.idx()]
+        })?;
+
+        assert_eq!(vec![0, 1], measured);
+        assert_eq!(
+            vec![
+                vec![0.5; autd[0].num_transducers()],
+                vec![1.; autd[1].num_transducers()],
+                vec![1.; autd[2].num_transducers()],
+            ],
+            calibration.scales()
+        );
+        autd.link().iter().take(2).for_each(|cpu| {
+            assert!(cpu
+                .fpga()
+                .drives()
+                .iter()
+                .all(|d| d.intensity == EmitIntensity::MIN));
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn measure_group() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        let calibration = AmplitudeCalibration::measure_group(
+            &mut autd,
+            |_| |tr| (tr.idx() >= 10).then_some(tr.idx() < 100),
+            REFERENCE,
+            |&k| if k { 2. } else { 4. },
+        )?;
+
+        autd[0].iter().for_each(|tr| {
+            assert_eq!(
+                if tr.idx() < 100 { 1. } else { 0.5 },
+                calibration.scales()[0][tr.idx()]
+            );
+        });
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(0.)]
+    #[case(-1.)]
+    #[case(f32::INFINITY)]
+    #[case(f32::NAN)]
+    fn measure_invalid_pressure(#[case] p: f32) -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+
+        let r = AmplitudeCalibration::measure(&mut autd, REFERENCE, |dev| {
+            if dev.idx() == 1 {
+                p
+            } else {
+                1.
+            }
+        });
+        assert!(matches!(
+            r,
+            Err(AUTDError::InvalidCalibrationPressure(k, _)) if k == "1"
+        ));
+        assert!(autd.link()[1]
+            .fpga()
+            .drives()
+            .iter()
+            .all(|d| d.intensity == EmitIntensity::MIN));
+
+        Ok(())
+    }
+}
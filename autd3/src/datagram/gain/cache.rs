@@ -15,6 +15,8 @@ pub struct Cache<G: Gain> {
     #[debug("{}", !self.cache.borrow().is_empty())]
     /// Cached phases and intensities.
     cache: Rc<RefCell<HashMap<usize, Arc<Vec<Drive>>>>>,
+    #[debug(ignore)]
+    enable: Rc<RefCell<HashMap<usize, BitVec>>>,
 }
 
 impl<G: Gain> Clone for Cache<G> {
@@ -22,6 +24,7 @@ impl<G: Gain> Clone for Cache<G> {
         Self {
             gain: self.gain.clone(),
             cache: self.cache.clone(),
+            enable: self.enable.clone(),
         }
     }
 }
@@ -32,6 +35,7 @@ impl<G: Gain> Cache<G> {
         Self {
             gain: Rc::new(RefCell::new(Some(gain))),
             cache: Default::default(),
+            enable: Default::default(),
         }
     }
 
@@ -39,7 +43,7 @@ impl<G: Gain> Cache<G> {
     ///
     /// # Errors
     ///
-    /// Returns [`GainError`] if you initialize with some devices disabled and then reinitialize after enabling the devices.
+    /// Returns [`GainError`] if you initialize with some devices disabled and then reinitialize after enabling the devices, or if the enable flags of the transducers are changed after initialization.
     pub fn init(
         &self,
         geometry: &Geometry,
@@ -58,6 +62,9 @@ impl<G: Gain> Cache<G> {
                         dev.idx(),
                         Arc::new(dev.iter().map(|tr| f.calc(tr)).collect()),
                     );
+                    self.enable
+                        .borrow_mut()
+                        .insert(dev.idx(), dev.iter().map(|tr| tr.enable).collect());
                 });
         }
 
@@ -71,6 +78,14 @@ impl<G: Gain> Cache<G> {
             ));
         }
 
+        if geometry.devices().any(|dev| {
+            self.enable.borrow()[&dev.idx()] != dev.iter().map(|tr| tr.enable).collect::<BitVec>()
+        }) {
+            return Err(GainError::new(
+                "Cache is initialized with different transducer enable flags".to_string(),
+            ));
+        }
+
         Ok(())
     }
 
@@ -80,6 +95,15 @@ impl<G: Gain> Cache<G> {
     }
 }
 
+/// Copies the precomputed `drives` to `dst` and writes [`Drive::NULL`] for the disabled transducers.
+pub(crate) fn copy_drives(drives: &[Drive], device: &Device, dst: &mut [Drive]) {
+    dst.copy_from_slice(&drives[..dst.len()]);
+    dst.iter_mut()
+        .zip(device.iter())
+        .filter(|(_, tr)| !tr.enable)
+        .for_each(|(dst, _)| *dst = Drive::NULL);
+}
+
 pub struct Impl {
     g: Arc<Vec<Drive>>,
 }
@@ -88,6 +112,10 @@ impl GainCalculator for Impl {
     fn calc(&self, tr: &Transducer) -> Drive {
         self.g[tr.idx()]
     }
+
+    fn calc_into(&self, device: &Device, dst: &mut [Drive]) {
+        copy_drives(&self.g, device, dst);
+    }
 }
 
 impl<G: Gain> GainCalculatorGenerator for Cache<G> {
@@ -165,6 +193,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn calc_into() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(1);
+        if let Some(tr) = geometry[0].iter_mut().next() {
+            tr.enable = false;
+        }
+
+        let d = Drive {
+            phase: Phase(0x80),
+            intensity: EmitIntensity(0x90),
+        };
+        let mut gc = Cache::new(Uniform {
+            intensity: d.intensity,
+            phase: d.phase,
+        })
+        .init_full(&geometry, None, false)?;
+
+        let mut dst = vec![Drive::NULL; geometry[0].num_transducers()];
+        gc.generate(&geometry[0]).calc_into(&geometry[0], &mut dst);
+        assert_eq!(Drive::NULL, dst[0]);
+        assert!(dst[1..].iter().all(|&v| v == d));
+
+        Ok(())
+    }
+
     #[test]
     fn different_geometry() -> anyhow::Result<()> {
         let mut geometry = create_geometry(2);
@@ -189,6 +242,33 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn different_transducer_enable() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(1);
+
+        let gain = Uniform {
+            intensity: EmitIntensity::MIN,
+            phase: Phase::ZERO,
+        };
+        let cache = Cache::new(gain);
+
+        cache.clone().init_full(&geometry, None, false)?;
+        cache.clone().init_full(&geometry, None, false)?;
+
+        if let Some(tr) = geometry[0].iter_mut().next() {
+            tr.enable = false;
+        }
+
+        assert_eq!(
+            Some(GainError::new(
+                "Cache is initialized with different transducer enable flags".to_string()
+            )),
+            cache.init_full(&geometry, None, false).err()
+        );
+
+        Ok(())
+    }
+
     #[derive(Gain, Clone, self::Debug)]
     struct CacheTestGain {
         pub calc_cnt: Arc<AtomicUsize>,
@@ -0,0 +1,136 @@
+use autd3_core::derive::*;
+use autd3_driver::firmware::fpga::EmitIntensity;
+
+use derive_new::new;
+
+use crate::datagram::calibration::AmplitudeCalibration;
+
+/// Amplitude calibration for [`Gain`]
+///
+/// This [`Gain`] scales the intensity of each transducer of the inner gain by the factors of [`AmplitudeCalibration`].
+#[derive(Gain, Clone, Debug, new)]
+pub struct Calibrated<G: Gain> {
+    /// The inner gain.
+    pub gain: G,
+    /// The calibration to be applied.
+    pub calibration: AmplitudeCalibration,
+}
+
+pub struct Impl<C: GainCalculator> {
+    calc: C,
+    scales: Arc<Vec<Vec<f32>>>,
+    dev_idx: usize,
+}
+
+impl<C: GainCalculator> GainCalculator for Impl<C> {
+    fn calc(&self, tr: &Transducer) -> Drive {
+        let d = self.calc.calc(tr);
+        Drive {
+            phase: d.phase,
+            intensity: EmitIntensity(
+                (d.intensity.0 as f32 * self.scales[self.dev_idx][tr.idx()]).round() as u8,
+            ),
+        }
+    }
+}
+
+pub struct Generator<G: GainCalculatorGenerator> {
+    generator: G,
+    scales: Arc<Vec<Vec<f32>>>,
+}
+
+impl<G: GainCalculatorGenerator> GainCalculatorGenerator for Generator<G> {
+    type Calculator = Impl<G::Calculator>;
+
+    fn generate(&mut self, device: &Device) -> Self::Calculator {
+        Impl {
+            calc: self.generator.generate(device),
+            scales: self.scales.clone(),
+            dev_idx: device.idx(),
+        }
+    }
+}
+
+impl<G: Gain> Gain for Calibrated<G> {
+    type G = Generator<G::G>;
+
+    fn init(self) -> Result<Self::G, GainError> {
+        Ok(Generator {
+            generator: self.gain.init()?,
+            scales: self.calibration.scales_arc(),
+        })
+    }
+
+    fn init_full(
+        self,
+        geometry: &Geometry,
+        filter: Option<&HashMap<usize, BitVec>>,
+        parallel: bool,
+    ) -> Result<Self::G, GainError> {
+        let scales = self.calibration.scales();
+        if scales.len() != geometry.len()
+            || geometry
+                .iter()
+                .zip(scales.iter())
+                .any(|(dev, s)| s.len() != dev.num_transducers())
+        {
+            return Err(GainError::new(
+                "Calibration is obtained with different geometry".to_string(),
+            ));
+        }
+        Ok(Generator {
+            generator: self.gain.init_full(geometry, filter, parallel)?,
+            scales: self.calibration.scales_arc(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{controller::tests::create_controller, gain::Uniform, tests::create_geometry};
+
+    use super::*;
+
+    use autd3_driver::firmware::fpga::Phase;
+
+    #[test]
+    fn test_calibrated() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        let calibration = AmplitudeCalibration::measure(
+            &mut autd,
+            Uniform {
+                intensity: EmitIntensity::MAX,
+                phase: Phase::ZERO,
+            },
+            |dev| [1.0, 0.5][dev.idx()],
+        )?;
+
+        let g = Calibrated::new(
+            Uniform {
+                intensity: EmitIntensity::MAX,
+                phase: Phase(0x80),
+            },
+            calibration.clone(),
+        );
+        let mut g = g.init_full(&autd, None, false)?;
+        autd.iter().for_each(|dev| {
+            let c = g.generate(dev);
+            dev.iter().for_each(|tr| {
+                let d = c.calc(tr);
+                assert_eq!(Phase(0x80), d.phase);
+                assert_eq!(EmitIntensity([128, 255][dev.idx()]), d.intensity);
+            });
+        });
+
+        assert_eq!(
+            Some(GainError::new(
+                "Calibration is obtained with different geometry".to_string()
+            )),
+            Calibrated::new(Uniform::new(EmitIntensity::MAX, Phase::ZERO), calibration)
+                .init_full(&create_geometry(1), None, false)
+                .err()
+        );
+
+        Ok(())
+    }
+}
@@ -1,13 +1,39 @@
-use autd3_core::derive::*;
+use autd3_core::{
+    acoustics::directivity::{Directivity, T4010A1},
+    derive::*,
+};
 
 use autd3_driver::{
     defined::rad,
     firmware::fpga::{EmitIntensity, Phase},
-    geometry::Point3,
+    geometry::{Point3, UnitVector3, Vector3},
 };
 
 use derive_new::new;
 
+/// The amplitude compensation of [`Focus`] for the focus close to the array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[repr(u8)]
+pub enum NearFieldCompensation {
+    /// The intensity of all transducers is uniform.
+    #[default]
+    None = 0,
+    /// The intensity of each transducer is weighted by `1/r`, where `r` is the distance from the transducer to the focus.
+    InverseDistance = 1,
+    /// The intensity of each transducer is weighted by `D(θ)/r`, where `D` is the directivity of [`T4010A1`] and `θ` is the angle between the axial direction and the direction to the focus.
+    Directivity = 2,
+}
+
+impl NearFieldCompensation {
+    fn weight(&self, axial_direction: &UnitVector3, r: &Vector3) -> f32 {
+        match self {
+            Self::None => 1.,
+            Self::InverseDistance => 1. / r.norm(),
+            Self::Directivity => T4010A1::directivity_from_dir(axial_direction, r) / r.norm(),
+        }
+    }
+}
+
 /// The option of [`Focus`].
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[repr(C)]
@@ -16,6 +42,12 @@ pub struct FocusOption {
     pub intensity: EmitIntensity,
     /// The phase offset of the beam.
     pub phase_offset: Phase,
+    /// The amplitude compensation. The weights are normalized so that the transducer with the largest weight outputs [`intensity`].
+    ///
+    /// Weighting the transducers close to the focus more strongly tapers the aperture and reduces the sidelobes when the focus is close to the array.
+    ///
+    /// [`intensity`]: FocusOption::intensity
+    pub near_field_compensation: NearFieldCompensation,
 }
 
 impl Default for FocusOption {
@@ -23,6 +55,7 @@ impl Default for FocusOption {
         Self {
             intensity: EmitIntensity::MAX,
             phase_offset: Phase::ZERO,
+            near_field_compensation: NearFieldCompensation::None,
         }
     }
 }
@@ -36,41 +69,111 @@ pub struct Focus {
     pub option: FocusOption,
 }
 
+#[derive(Clone, Copy)]
+pub(crate) struct Compensation {
+    kind: NearFieldCompensation,
+    axial_direction: UnitVector3,
+    max_weight: f32,
+}
+
 pub struct Impl {
     pub(crate) pos: Point3,
     pub(crate) intensity: EmitIntensity,
     pub(crate) phase_offset: Phase,
     pub(crate) wavenumber: f32,
+    pub(crate) compensation: Option<Compensation>,
 }
 
 impl GainCalculator for Impl {
     fn calc(&self, tr: &Transducer) -> Drive {
+        let r = self.pos - tr.position();
         Drive {
-            phase: Phase::from(-(self.pos - tr.position()).norm() * self.wavenumber * rad)
-                + self.phase_offset,
-            intensity: self.intensity,
+            phase: Phase::from(-r.norm() * self.wavenumber * rad) + self.phase_offset,
+            intensity: match &self.compensation {
+                Some(c) => EmitIntensity(
+                    (self.intensity.0 as f32 * c.kind.weight(&c.axial_direction, &r) / c.max_weight)
+                        .round() as u8,
+                ),
+                None => self.intensity,
+            },
         }
     }
 }
 
-impl GainCalculatorGenerator for Focus {
+pub struct Generator {
+    focus: Focus,
+    max_weight: Option<f32>,
+}
+
+impl Generator {
+    fn max_weight<'a>(&self, device: &Device, trs: impl Iterator<Item = &'a Transducer>) -> f32 {
+        trs.map(|tr| {
+            self.focus
+                .option
+                .near_field_compensation
+                .weight(device.axial_direction(), &(self.focus.pos - tr.position()))
+        })
+        .fold(0., f32::max)
+    }
+}
+
+impl GainCalculatorGenerator for Generator {
     type Calculator = Impl;
 
     fn generate(&mut self, device: &Device) -> Self::Calculator {
         Impl {
-            pos: self.pos,
-            intensity: self.option.intensity,
-            phase_offset: self.option.phase_offset,
+            pos: self.focus.pos,
+            intensity: self.focus.option.intensity,
+            phase_offset: self.focus.option.phase_offset,
             wavenumber: device.wavenumber(),
+            compensation: match self.focus.option.near_field_compensation {
+                NearFieldCompensation::None => None,
+                kind => Some(Compensation {
+                    kind,
+                    axial_direction: *device.axial_direction(),
+                    max_weight: self
+                        .max_weight
+                        .unwrap_or_else(|| self.max_weight(device, device.iter())),
+                }),
+            },
         }
     }
 }
 
 impl Gain for Focus {
-    type G = Focus;
+    type G = Generator;
 
     fn init(self) -> Result<Self::G, GainError> {
-        Ok(self)
+        Ok(Generator {
+            focus: self,
+            max_weight: None,
+        })
+    }
+
+    fn init_full(
+        self,
+        geometry: &Geometry,
+        filter: Option<&HashMap<usize, BitVec>>,
+        _parallel: bool,
+    ) -> Result<Self::G, GainError> {
+        let mut g = self.init()?;
+        if g.focus.option.near_field_compensation != NearFieldCompensation::None {
+            g.max_weight = Some(
+                geometry
+                    .devices()
+                    .map(|dev| {
+                        g.max_weight(
+                            dev,
+                            dev.iter().filter(|tr| {
+                                filter
+                                    .is_none_or(|f| f.get(&dev.idx()).is_some_and(|f| f[tr.idx()]))
+                            }),
+                        )
+                    })
+                    .fold(0., f32::max),
+            );
+        }
+        Ok(g)
     }
 }
 
@@ -124,10 +227,87 @@ mod tests {
             option: FocusOption {
                 intensity,
                 phase_offset,
+                ..Default::default()
             },
         };
         focus_check(g, pos, intensity, phase_offset, &geometry)?;
 
         Ok(())
     }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(NearFieldCompensation::InverseDistance)]
+    #[case(NearFieldCompensation::Directivity)]
+    fn test_focus_near_field_compensation(
+        #[case] compensation: NearFieldCompensation,
+    ) -> anyhow::Result<()> {
+        let geometry = create_geometry(2);
+
+        let pos = Point3::new(20., 30., 30.);
+        let intensity = EmitIntensity(0xF0);
+        let g = Focus {
+            pos,
+            option: FocusOption {
+                intensity,
+                near_field_compensation: compensation,
+                ..Default::default()
+            },
+        };
+
+        let weight = |dev: &Device, tr: &Transducer| {
+            compensation.weight(dev.axial_direction(), &(pos - tr.position()))
+        };
+        let max_weight = geometry
+            .iter()
+            .flat_map(|dev| dev.iter().map(move |tr| weight(dev, tr)))
+            .fold(0., f32::max);
+
+        let mut b = g.init_full(&geometry, None, false)?;
+        let mut intensities = Vec::new();
+        geometry.iter().for_each(|dev| {
+            let d = b.generate(dev);
+            dev.iter().for_each(|tr| {
+                let d = d.calc(tr);
+                assert_eq!(
+                    Phase::from(-(tr.position() - pos).norm() * dev.wavenumber() * rad),
+                    d.phase
+                );
+                assert_eq!(
+                    EmitIntensity(
+                        (intensity.0 as f32 * weight(dev, tr) / max_weight).round() as u8
+                    ),
+                    d.intensity
+                );
+                intensities.push(d.intensity);
+            });
+        });
+        assert_eq!(Some(&intensity), intensities.iter().max());
+        assert!(intensities.iter().any(|&i| i < intensity));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_focus_near_field_compensation_filter() -> anyhow::Result<()> {
+        let geometry = create_geometry(1);
+
+        let pos = geometry[0][0].position() + Vector3::new(0., 0., 10.);
+        let g = Focus {
+            pos,
+            option: FocusOption {
+                near_field_compensation: NearFieldCompensation::InverseDistance,
+                ..Default::default()
+            },
+        };
+        let filter =
+            HashMap::from([(0, BitVec::from_fn(geometry[0].num_transducers(), |i| i > 0))]);
+
+        let mut b = g.init_full(&geometry, Some(&filter), false)?;
+        let d = b.generate(&geometry[0]);
+        assert_eq!(EmitIntensity::MAX, d.calc(&geometry[0][1]).intensity);
+        assert_eq!(EmitIntensity::MAX, d.calc(&geometry[0][0]).intensity);
+
+        Ok(())
+    }
 }
@@ -1,18 +1,26 @@
 mod bessel;
 mod cache;
+mod calibrated;
 mod custom;
 pub(crate) mod focus;
 mod group;
 mod null;
 mod plane;
+mod plane_wave;
 mod uniform;
+mod within_aperture;
 
 pub use autd3_driver::datagram::IntoBoxedGain;
 pub use bessel::{Bessel, BesselOption};
+#[cfg(feature = "stm")]
+pub(crate) use cache::copy_drives;
 pub use cache::Cache as GainCache;
+pub use calibrated::Calibrated;
 pub use custom::Custom;
-pub use focus::{Focus, FocusOption};
+pub use focus::{Focus, FocusOption, NearFieldCompensation};
 pub use group::Group;
 pub use null::Null;
 pub use plane::{Plane, PlaneOption};
+pub use plane_wave::{PlaneWave, PlaneWaveOption};
 pub use uniform::Uniform;
+pub use within_aperture::WithinAperture;
@@ -0,0 +1,244 @@
+use autd3_core::derive::*;
+
+use autd3_driver::{
+    defined::rad,
+    firmware::fpga::{EmitIntensity, Phase},
+    geometry::{Point3, UnitVector3},
+};
+
+use derive_new::new;
+
+/// The option of [`PlaneWave`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct PlaneWaveOption {
+    /// The intensity of the beam.
+    pub intensity: EmitIntensity,
+    /// The phase offset of the beam.
+    pub phase_offset: Phase,
+    /// If `true`, the phases are calculated from the positions of the transducers in the geometry, so that the wavefronts of all devices are aligned even if the devices are not placed on the same plane.
+    /// If `false`, the phases of each device are calculated from the positions relative to the center of the device, i.e., each device emits the plane wave independently.
+    pub compensate_device_position: bool,
+}
+
+impl Default for PlaneWaveOption {
+    fn default() -> Self {
+        Self {
+            intensity: EmitIntensity::MAX,
+            phase_offset: Phase::ZERO,
+            compensate_device_position: true,
+        }
+    }
+}
+
+/// Plane wave steered to a direction
+///
+/// The phase of each transducer is `k (dir · r)`, where `k` is the wavenumber of the device and `r` is the position of the transducer, so that the wave propagates toward [`dir`].
+/// This is consistent with [`Focus`] placed far away in the direction of [`dir`].
+///
+/// [`dir`]: PlaneWave::dir
+/// [`Focus`]: crate::gain::Focus
+#[derive(Gain, Clone, PartialEq, Debug, new)]
+pub struct PlaneWave {
+    /// The propagation direction of the plane wave.
+    pub dir: UnitVector3,
+    /// The option of the gain.
+    pub option: PlaneWaveOption,
+}
+
+pub struct Impl {
+    dir: UnitVector3,
+    origin: Point3,
+    intensity: EmitIntensity,
+    phase_offset: Phase,
+    wavenumber: f32,
+}
+
+impl GainCalculator for Impl {
+    fn calc(&self, tr: &Transducer) -> Drive {
+        Drive {
+            phase: Phase::from(
+                self.dir.dot(&(tr.position() - self.origin)) * self.wavenumber * rad,
+            ) + self.phase_offset,
+            intensity: self.intensity,
+        }
+    }
+}
+
+impl GainCalculatorGenerator for PlaneWave {
+    type Calculator = Impl;
+
+    fn generate(&mut self, device: &Device) -> Self::Calculator {
+        Impl {
+            dir: self.dir,
+            origin: if self.option.compensate_device_position {
+                Point3::origin()
+            } else {
+                *device.center()
+            },
+            intensity: self.option.intensity,
+            phase_offset: self.option.phase_offset,
+            wavenumber: device.wavenumber(),
+        }
+    }
+}
+
+impl Gain for PlaneWave {
+    type G = PlaneWave;
+
+    fn init(self) -> Result<Self::G, GainError> {
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_core::{
+        acoustics::{directivity::Sphere, pressure},
+        geometry::IntoDevice,
+    };
+    use autd3_driver::{
+        autd3_device::AUTD3,
+        defined::{mm, PI},
+        geometry::{UnitQuaternion, Vector3},
+    };
+    use rand::Rng;
+
+    use super::*;
+
+    use crate::{
+        gain::{Focus, FocusOption},
+        tests::{create_geometry, random_vector3},
+    };
+
+    fn geometry(z_err: f32) -> Geometry {
+        Geometry::new(vec![
+            AUTD3 {
+                pos: Point3::origin(),
+                rot: UnitQuaternion::identity(),
+            }
+            .into_device(0),
+            AUTD3 {
+                pos: Point3::new(AUTD3::DEVICE_WIDTH, 0., z_err),
+                rot: UnitQuaternion::identity(),
+            }
+            .into_device(1),
+        ])
+    }
+
+    fn field(g: impl Gain, geometry: &Geometry, target: &Point3) -> anyhow::Result<f32> {
+        let mut g = g.init_full(geometry, None, false)?;
+        Ok(geometry
+            .iter()
+            .map(|dev| {
+                let c = g.generate(dev);
+                let drives = dev.iter().map(|tr| c.calc(tr)).collect::<Vec<_>>();
+                pressure::<Sphere>(dev, &drives, target)
+            })
+            .sum::<autd3_driver::geometry::Complex>()
+            .norm())
+    }
+
+    #[test]
+    fn test_plane_wave() -> anyhow::Result<()> {
+        let mut rng = rand::rng();
+
+        let geometry = create_geometry(2);
+
+        let dir = UnitVector3::new_normalize(random_vector3(-1.0..1.0, -1.0..1.0, -1.0..1.0));
+        let intensity = EmitIntensity(rng.random());
+        let phase_offset = Phase(rng.random());
+        let mut g = PlaneWave {
+            dir,
+            option: PlaneWaveOption {
+                intensity,
+                phase_offset,
+                ..Default::default()
+            },
+        }
+        .init()?;
+        geometry.iter().for_each(|dev| {
+            let d = g.generate(dev);
+            dev.iter().for_each(|tr| {
+                let d = d.calc(tr);
+                assert_eq!(
+                    Phase::from(dir.dot(&tr.position().coords) * dev.wavenumber() * rad)
+                        + phase_offset,
+                    d.phase
+                );
+                assert_eq!(intensity, d.intensity);
+            });
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plane_wave_direction() -> anyhow::Result<()> {
+        let geometry = geometry(0.);
+        let theta = PI / 9.;
+        let center = geometry.center().unwrap();
+        let far = |x: f32| center + Vector3::new(x, 0., theta.cos()) * 50000. * mm;
+
+        let g = PlaneWave::new(
+            UnitVector3::new_normalize(Vector3::new(theta.sin(), 0., theta.cos())),
+            PlaneWaveOption::default(),
+        );
+        let forward = field(g.clone(), &geometry, &far(theta.sin()))?;
+        let backward = field(g.clone(), &geometry, &far(-theta.sin()))?;
+        assert!(forward > 2. * backward);
+
+        let focus = Focus::new(far(theta.sin()), FocusOption::default());
+        approx::assert_relative_eq!(
+            field(focus, &geometry, &far(theta.sin()))?,
+            forward,
+            max_relative = 0.05
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_plane_wave_compensation() -> anyhow::Result<()> {
+        let geometry = geometry(3. * mm);
+        let dir = UnitVector3::new_normalize(Vector3::z());
+        let target = geometry.center().unwrap() + dir.into_inner() * 2000. * mm;
+
+        let compensated = field(
+            PlaneWave::new(dir, PlaneWaveOption::default()),
+            &geometry,
+            &target,
+        )?;
+        let uncompensated = field(
+            PlaneWave::new(
+                dir,
+                PlaneWaveOption {
+                    compensate_device_position: false,
+                    ..Default::default()
+                },
+            ),
+            &geometry,
+            &target,
+        )?;
+        assert!(compensated > 1.2 * uncompensated);
+
+        let mut g = PlaneWave::new(
+            dir,
+            PlaneWaveOption {
+                compensate_device_position: false,
+                ..Default::default()
+            },
+        )
+        .init()?;
+        let dev = &geometry[1];
+        let d = g.generate(dev);
+        dev.iter().for_each(|tr| {
+            assert_eq!(
+                Phase::from(dir.dot(&(tr.position() - dev.center())) * dev.wavenumber() * rad),
+                d.calc(tr).phase
+            );
+        });
+
+        Ok(())
+    }
+}
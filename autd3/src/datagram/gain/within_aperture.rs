@@ -0,0 +1,164 @@
+use autd3_core::derive::*;
+use autd3_driver::{
+    defined::Angle,
+    firmware::fpga::EmitIntensity,
+    geometry::{Point3, UnitVector3},
+};
+
+use derive_new::new;
+
+/// Field-of-view clipping for [`Gain`]
+///
+/// This [`Gain`] sets the intensity to zero for transducers whose angle between the axial direction and the direction to the [`target`] exceeds [`max_angle`].
+///
+/// [`target`]: WithinAperture::target
+/// [`max_angle`]: WithinAperture::max_angle
+#[derive(Gain, Clone, PartialEq, Debug, new)]
+pub struct WithinAperture<G: Gain> {
+    /// The inner gain.
+    pub gain: G,
+    /// The target position.
+    pub target: Point3,
+    /// The maximum angle between the axial direction of the transducer and the direction to the target.
+    pub max_angle: Angle,
+}
+
+pub struct Impl<C: GainCalculator> {
+    calc: C,
+    target: Point3,
+    axial_direction: UnitVector3,
+    cos_max_angle: f32,
+}
+
+impl<C: GainCalculator> GainCalculator for Impl<C> {
+    fn calc(&self, tr: &Transducer) -> Drive {
+        let d = self.calc.calc(tr);
+        let r = self.target - tr.position();
+        let norm = r.norm();
+        if norm != 0. && self.axial_direction.dot(&r) < self.cos_max_angle * norm {
+            Drive {
+                phase: d.phase,
+                intensity: EmitIntensity::MIN,
+            }
+        } else {
+            d
+        }
+    }
+}
+
+pub struct Generator<G: GainCalculatorGenerator> {
+    generator: G,
+    target: Point3,
+    cos_max_angle: f32,
+}
+
+impl<G: GainCalculatorGenerator> GainCalculatorGenerator for Generator<G> {
+    type Calculator = Impl<G::Calculator>;
+
+    fn generate(&mut self, device: &Device) -> Self::Calculator {
+        Impl {
+            calc: self.generator.generate(device),
+            target: self.target,
+            axial_direction: *device.axial_direction(),
+            cos_max_angle: self.cos_max_angle,
+        }
+    }
+}
+
+impl<G: Gain> Gain for WithinAperture<G> {
+    type G = Generator<G::G>;
+
+    fn init(self) -> Result<Self::G, GainError> {
+        Ok(Generator {
+            generator: self.gain.init()?,
+            target: self.target,
+            cos_max_angle: self.max_angle.radian().cos(),
+        })
+    }
+
+    fn init_full(
+        self,
+        geometry: &Geometry,
+        filter: Option<&HashMap<usize, BitVec>>,
+        parallel: bool,
+    ) -> Result<Self::G, GainError> {
+        Ok(Generator {
+            generator: self.gain.init_full(geometry, filter, parallel)?,
+            target: self.target,
+            cos_max_angle: self.max_angle.radian().cos(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{gain::Uniform, tests::create_geometry};
+
+    use super::*;
+
+    use autd3_driver::{defined::deg, firmware::fpga::Phase, geometry::Vector3};
+
+    #[rstest::rstest]
+    #[test]
+    #[case(0.0 * deg)]
+    #[case(30.0 * deg)]
+    #[case(60.0 * deg)]
+    #[case(90.0 * deg)]
+    fn test_within_aperture(#[case] max_angle: Angle) -> anyhow::Result<()> {
+        let geometry = create_geometry(1);
+
+        let d = Drive {
+            phase: Phase(0x80),
+            intensity: EmitIntensity::MAX,
+        };
+        let target = geometry.center().unwrap() + Vector3::new(0., 0., 50.);
+        let g = WithinAperture::new(
+            Uniform {
+                intensity: d.intensity,
+                phase: d.phase,
+            },
+            target,
+            max_angle,
+        );
+
+        let mut g = g.init_full(&geometry, None, false)?;
+        geometry.iter().for_each(|dev| {
+            let c = g.generate(dev);
+            dev.iter().for_each(|tr| {
+                let r = target - tr.position();
+                let angle = dev.axial_direction().angle(&r);
+                let r = c.calc(tr);
+                assert_eq!(d.phase, r.phase);
+                if angle > max_angle.radian() + 1e-3 {
+                    assert_eq!(EmitIntensity::MIN, r.intensity);
+                } else if angle < max_angle.radian() - 1e-3 {
+                    assert_eq!(d.intensity, r.intensity);
+                }
+            });
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_within_aperture_behind() -> anyhow::Result<()> {
+        let geometry = create_geometry(1);
+
+        let target = geometry.center().unwrap() - Vector3::new(0., 0., 50.);
+        let g = WithinAperture::new(
+            Uniform::new(EmitIntensity::MAX, Phase::ZERO),
+            target,
+            90.0 * deg,
+        );
+
+        let mut g = g.init()?;
+        geometry.iter().for_each(|dev| {
+            let c = g.generate(dev);
+            dev.iter().for_each(|tr| {
+                assert_eq!(EmitIntensity::MIN, c.calc(tr).intensity);
+            });
+        });
+
+        Ok(())
+    }
+}
@@ -1,8 +1,28 @@
+/// Calibration of [`PhaseCorrection`] and output amplitude
+///
+/// [`PhaseCorrection`]: autd3_driver::datagram::PhaseCorrection
+pub mod calibration;
+
 /// Primitive [`Gain`]
 ///
 /// [`Gain`]: autd3_core::gain::Gain
 pub mod gain;
 
+/// Intensity to pulse width mapping of [`PulseWidthEncoder`]
+///
+/// [`PulseWidthEncoder`]: autd3_driver::datagram::PulseWidthEncoder
+pub mod pulse_width;
+
+/// Configuration of [`Silencer`] from the datagrams to be sent
+///
+/// [`Silencer`]: autd3_driver::datagram::Silencer
+pub mod silencer;
+
+/// Un-modulated output of [`Gain`] with safety limits
+///
+/// [`Gain`]: autd3_core::gain::Gain
+pub mod static_pressure;
+
 /// Primitive [`Modulation`]
 ///
 /// [`Modulation`]: autd3_core::modulation::Modulation
@@ -12,6 +32,28 @@ pub mod modulation;
 ///
 /// [`GainSTM`]: autd3_driver::datagram::GainSTM
 /// [`FociSTM`]: autd3_driver::datagram::FociSTM
+#[cfg_attr(docsrs, doc(cfg(feature = "stm")))]
+#[cfg(feature = "stm")]
 pub mod stm;
 
+/// High-level haptic sensations compiled into [`FociSTM`] and [`Modulation`]
+///
+/// [`FociSTM`]: autd3_driver::datagram::FociSTM
+/// [`Modulation`]: autd3_core::modulation::Modulation
+#[cfg_attr(docsrs, doc(cfg(feature = "stm")))]
+#[cfg(feature = "stm")]
+pub mod sensations;
+
+/// Ready-made tactile patterns built on [`Sensation`]
+///
+/// [`Sensation`]: crate::datagram::sensations::Sensation
+#[cfg_attr(docsrs, doc(cfg(feature = "stm")))]
+#[cfg(feature = "stm")]
+pub mod patterns;
+
+/// Fluent builder of the segment and the transition of [`Datagram`]s
+///
+/// [`Datagram`]: autd3_core::datagram::Datagram
+pub mod transition;
+
 pub use autd3_driver::datagram::IntoBoxedDatagram;
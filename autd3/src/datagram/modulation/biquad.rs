@@ -0,0 +1,318 @@
+use autd3_core::{
+    defined::{Freq, PI},
+    derive::*,
+};
+use derive_new::new;
+
+use super::fir::check_cutoff;
+
+/// The coefficients of a biquad filter normalized so that `a0` is 1.
+///
+/// The transfer function is `H(z) = (b0 + b1 z^-1 + b2 z^-2) / (1 + a1 z^-1 + a2 z^-2)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BiquadCoef {
+    #[allow(missing_docs)]
+    pub b0: f32,
+    #[allow(missing_docs)]
+    pub b1: f32,
+    #[allow(missing_docs)]
+    pub b2: f32,
+    #[allow(missing_docs)]
+    pub a1: f32,
+    #[allow(missing_docs)]
+    pub a2: f32,
+}
+
+impl BiquadCoef {
+    /// Low-pass filter coefficients based on [Audio EQ Cookbook](https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html).
+    pub fn low_pass(cutoff: Freq<f32>, q: f32, fs: Freq<f32>) -> Self {
+        let (cos, alpha) = Self::params(cutoff, q, fs);
+        Self::normalize(
+            (1. - cos) / 2.,
+            1. - cos,
+            (1. - cos) / 2.,
+            1. + alpha,
+            -2. * cos,
+            1. - alpha,
+        )
+    }
+
+    /// High-pass filter coefficients based on [Audio EQ Cookbook](https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html).
+    pub fn high_pass(cutoff: Freq<f32>, q: f32, fs: Freq<f32>) -> Self {
+        let (cos, alpha) = Self::params(cutoff, q, fs);
+        Self::normalize(
+            (1. + cos) / 2.,
+            -(1. + cos),
+            (1. + cos) / 2.,
+            1. + alpha,
+            -2. * cos,
+            1. - alpha,
+        )
+    }
+
+    /// Band-pass filter (constant 0 dB peak gain) coefficients based on [Audio EQ Cookbook](https://webaudio.github.io/Audio-EQ-Cookbook/audio-eq-cookbook.html).
+    pub fn band_pass(center: Freq<f32>, q: f32, fs: Freq<f32>) -> Self {
+        let (cos, alpha) = Self::params(center, q, fs);
+        Self::normalize(alpha, 0., -alpha, 1. + alpha, -2. * cos, 1. - alpha)
+    }
+
+    fn params(freq: Freq<f32>, q: f32, fs: Freq<f32>) -> (f32, f32) {
+        let w0 = 2. * PI * freq.hz() / fs.hz();
+        (w0.cos(), w0.sin() / (2. * q))
+    }
+
+    fn normalize(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+}
+
+/// [`Modulation`] that applies a biquad (second-order IIR) filter to the original [`Modulation`].
+///
+/// Since the modulation data is played repeatedly, the filter is applied to the periodic extension of the original data and the steady-state response is used.
+#[derive(Modulation, Debug, new)]
+pub struct Biquad<M: Modulation> {
+    /// The target [`Modulation`] to apply the filter.
+    pub target: M,
+    /// The coefficients of the filter.
+    pub coef: BiquadCoef,
+}
+
+impl<M: Modulation> Biquad<M> {
+    const MAX_WARMUP_PASSES: usize = 64;
+
+    /// Creates a low-pass [`Biquad`] with the cutoff frequency and quality factor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModulationError`] if `cutoff` is not in the range (0, Nyquist frequency) or `q` is not positive.
+    pub fn low_pass(target: M, cutoff: Freq<f32>, q: f32) -> Result<Self, ModulationError> {
+        let fs = Self::check(&target, cutoff, q)?;
+        Ok(Self {
+            target,
+            coef: BiquadCoef::low_pass(cutoff, q, fs),
+        })
+    }
+
+    /// Creates a high-pass [`Biquad`] with the cutoff frequency and quality factor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModulationError`] if `cutoff` is not in the range (0, Nyquist frequency) or `q` is not positive.
+    pub fn high_pass(target: M, cutoff: Freq<f32>, q: f32) -> Result<Self, ModulationError> {
+        let fs = Self::check(&target, cutoff, q)?;
+        Ok(Self {
+            target,
+            coef: BiquadCoef::high_pass(cutoff, q, fs),
+        })
+    }
+
+    /// Creates a band-pass [`Biquad`] with the center frequency and quality factor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModulationError`] if `center` is not in the range (0, Nyquist frequency) or `q` is not positive.
+    pub fn band_pass(target: M, center: Freq<f32>, q: f32) -> Result<Self, ModulationError> {
+        let fs = Self::check(&target, center, q)?;
+        Ok(Self {
+            target,
+            coef: BiquadCoef::band_pass(center, q, fs),
+        })
+    }
+
+    fn check(target: &M, freq: Freq<f32>, q: f32) -> Result<Freq<f32>, ModulationError> {
+        let fs = target.sampling_config()?.freq();
+        check_cutoff(freq, fs)?;
+        if q.is_nan() || q <= 0. {
+            return Err(ModulationError::new(format!(
+                "Quality factor ({}) must be positive",
+                q
+            )));
+        }
+        Ok(fs)
+    }
+}
+
+impl<M: Modulation> Modulation for Biquad<M> {
+    fn calc(self) -> Result<Vec<u8>, ModulationError> {
+        let src = self.target.calc()?;
+        let BiquadCoef { b0, b1, b2, a1, a2 } = self.coef;
+
+        let mut x1 = 0.;
+        let mut x2 = 0.;
+        let mut y1 = 0.;
+        let mut y2 = 0.;
+        let mut out = vec![0.0f32; src.len()];
+        for pass in 0..Self::MAX_WARMUP_PASSES {
+            let mut diff = 0.0f32;
+            src.iter().zip(out.iter_mut()).for_each(|(&x, o)| {
+                let x = x as f32;
+                let y = b0 * x + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+                x2 = x1;
+                x1 = x;
+                y2 = y1;
+                y1 = y;
+                diff = diff.max((y - *o).abs());
+                *o = y;
+            });
+            if pass > 0 && diff < 1e-3 {
+                break;
+            }
+        }
+
+        Ok(out
+            .into_iter()
+            .map(|v| v.round().clamp(u8::MIN as f32, u8::MAX as f32) as u8)
+            .collect())
+    }
+
+    fn sampling_config(&self) -> Result<SamplingConfig, ModulationError> {
+        self.target.sampling_config()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::modulation::{Custom, Sine, SineOption};
+    use autd3_driver::defined::{kHz, Hz};
+
+    use super::*;
+
+    fn sine(freq: Freq<u32>) -> Sine<Freq<u32>> {
+        Sine {
+            freq,
+            option: SineOption {
+                sampling_config: SamplingConfig::new(20 * kHz).unwrap(),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn amplitude(buf: &[u8]) -> i32 {
+        *buf.iter().max().unwrap() as i32 - *buf.iter().min().unwrap() as i32
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::freq_4k(SamplingConfig::new_nearest(4. * kHz))]
+    #[case::freq_8k(SamplingConfig::new_nearest(8. * kHz))]
+    fn test_sampling_config(#[case] config: SamplingConfig) {
+        assert_eq!(
+            Ok(config),
+            Biquad {
+                target: Custom {
+                    buffer: [u8::MIN; 2].to_vec(),
+                    sampling_config: config,
+                },
+                coef: BiquadCoef {
+                    b0: 1.,
+                    b1: 0.,
+                    b2: 0.,
+                    a1: 0.,
+                    a2: 0.,
+                }
+            }
+            .sampling_config()
+        );
+    }
+
+    #[test]
+    fn identity() -> anyhow::Result<()> {
+        let expect = sine(150 * Hz).calc()?;
+        assert_eq!(
+            expect,
+            Biquad {
+                target: sine(150 * Hz),
+                coef: BiquadCoef {
+                    b0: 1.,
+                    b1: 0.,
+                    b2: 0.,
+                    a1: 0.,
+                    a2: 0.,
+                }
+            }
+            .calc()?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn low_pass_coef() {
+        let coef = BiquadCoef::low_pass(1000. * Hz, std::f32::consts::FRAC_1_SQRT_2, 20. * kHz);
+        approx::assert_abs_diff_eq!(
+            1.,
+            (coef.b0 + coef.b1 + coef.b2) / (1. + coef.a1 + coef.a2),
+            epsilon = 1e-4
+        );
+    }
+
+    #[test]
+    fn high_pass_coef() {
+        let coef = BiquadCoef::high_pass(1000. * Hz, std::f32::consts::FRAC_1_SQRT_2, 20. * kHz);
+        approx::assert_abs_diff_eq!(0., coef.b0 + coef.b1 + coef.b2, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn band_pass_coef() {
+        let coef = BiquadCoef::band_pass(1000. * Hz, 1., 20. * kHz);
+        approx::assert_abs_diff_eq!(0., coef.b0 + coef.b1 + coef.b2, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn low_pass() -> anyhow::Result<()> {
+        let q = std::f32::consts::FRAC_1_SQRT_2;
+        let low = Biquad::low_pass(sine(100 * Hz), 500. * Hz, q)?.calc()?;
+        let high = Biquad::low_pass(sine(4000 * Hz), 500. * Hz, q)?.calc()?;
+        assert!(amplitude(&low) > 200);
+        assert!(amplitude(&high) < 10);
+        Ok(())
+    }
+
+    #[test]
+    fn high_pass() -> anyhow::Result<()> {
+        let q = std::f32::consts::FRAC_1_SQRT_2;
+        let low = Biquad::high_pass(sine(50 * Hz), 2000. * Hz, q)?.calc()?;
+        let high = Biquad::high_pass(sine(5000 * Hz), 200. * Hz, q)?.calc()?;
+        assert!(amplitude(&low) < amplitude(&high));
+        Ok(())
+    }
+
+    #[test]
+    fn band_pass() -> anyhow::Result<()> {
+        let center = Biquad::band_pass(sine(1000 * Hz), 1000. * Hz, 2.)?.calc()?;
+        let off = Biquad::band_pass(sine(100 * Hz), 1000. * Hz, 2.)?.calc()?;
+        assert!(amplitude(&center) > amplitude(&off));
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(
+        Err(ModulationError::new("Cutoff frequency (10000 Hz) must be in the range (0 Hz, 10000 Hz)".to_owned())),
+        10. * kHz,
+        1.
+    )]
+    #[case(
+        Err(ModulationError::new("Quality factor (0) must be positive".to_owned())),
+        100. * Hz,
+        0.
+    )]
+    #[test]
+    fn preset_err(
+        #[case] expect: Result<(), ModulationError>,
+        #[case] freq: Freq<f32>,
+        #[case] q: f32,
+    ) {
+        let target = || Custom {
+            buffer: vec![0; 2],
+            sampling_config: SamplingConfig::new(20 * kHz).unwrap(),
+        };
+        assert_eq!(expect, Biquad::low_pass(target(), freq, q).map(|_| ()));
+        assert_eq!(expect, Biquad::high_pass(target(), freq, q).map(|_| ()));
+        assert_eq!(expect, Biquad::band_pass(target(), freq, q).map(|_| ()));
+    }
+}
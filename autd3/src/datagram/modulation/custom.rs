@@ -37,7 +37,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use autd3_driver::defined::kHz;
+    use autd3_driver::{defined::kHz, firmware::fpga::FreqRatio};
     use rand::Rng;
 
     use super::*;
@@ -57,4 +57,22 @@ mod tests {
 
         Ok(())
     }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(SamplingConfig::ratio(20000, 3), FreqRatio { num: 20000, den: 3 })]
+    #[case(SamplingConfig::ratio(6000, 1), FreqRatio { num: 6000, den: 1 })]
+    fn freq_ratio(
+        #[case] expect: Result<SamplingConfig, SamplingConfigError>,
+        #[case] sampling_config: FreqRatio,
+    ) {
+        let custom = Custom {
+            buffer: vec![0; 2],
+            sampling_config,
+        };
+        assert_eq!(
+            expect.map_err(ModulationError::from),
+            custom.sampling_config()
+        );
+    }
 }
@@ -1,4 +1,7 @@
-use autd3_core::derive::*;
+use autd3_core::{
+    defined::{Freq, PI},
+    derive::*,
+};
 use derive_new::new;
 
 /// [`Modulation`] that applies FIR filter to the original [`Modulation`].
@@ -32,6 +35,110 @@ impl<M: Modulation> Modulation for Fir<M> {
     }
 }
 
+impl<M: Modulation> Fir<M> {
+    /// Creates a low-pass [`Fir`] designed by the windowed-sinc method with Hamming window.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModulationError`] if `n_taps` is not odd or `cutoff` is not in the range (0, Nyquist frequency).
+    pub fn low_pass(target: M, cutoff: Freq<f32>, n_taps: usize) -> Result<Self, ModulationError> {
+        let fs = target.sampling_config()?.freq();
+        check_taps(n_taps)?;
+        check_cutoff(cutoff, fs)?;
+        Ok(Self {
+            target,
+            coef: windowed_sinc(cutoff.hz() / fs.hz(), n_taps),
+        })
+    }
+
+    /// Creates a high-pass [`Fir`] designed by the windowed-sinc method with Hamming window and spectral inversion.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModulationError`] if `n_taps` is not odd or `cutoff` is not in the range (0, Nyquist frequency).
+    pub fn high_pass(target: M, cutoff: Freq<f32>, n_taps: usize) -> Result<Self, ModulationError> {
+        let fs = target.sampling_config()?.freq();
+        check_taps(n_taps)?;
+        check_cutoff(cutoff, fs)?;
+        let mut coef = windowed_sinc(cutoff.hz() / fs.hz(), n_taps);
+        coef.iter_mut().for_each(|c| *c = -*c);
+        coef[n_taps / 2] += 1.;
+        Ok(Self { target, coef })
+    }
+
+    /// Creates a band-pass [`Fir`] designed by the windowed-sinc method with Hamming window.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ModulationError`] if `n_taps` is not odd, `low` and `high` are not in the range (0, Nyquist frequency), or `low` is not less than `high`.
+    pub fn band_pass(
+        target: M,
+        low: Freq<f32>,
+        high: Freq<f32>,
+        n_taps: usize,
+    ) -> Result<Self, ModulationError> {
+        let fs = target.sampling_config()?.freq();
+        check_taps(n_taps)?;
+        check_cutoff(low, fs)?;
+        check_cutoff(high, fs)?;
+        if low >= high {
+            return Err(ModulationError::new(format!(
+                "Lower cutoff frequency ({:?}) must be less than higher cutoff frequency ({:?})",
+                low, high
+            )));
+        }
+        let l = windowed_sinc(low.hz() / fs.hz(), n_taps);
+        let h = windowed_sinc(high.hz() / fs.hz(), n_taps);
+        Ok(Self {
+            target,
+            coef: h.into_iter().zip(l).map(|(h, l)| h - l).collect(),
+        })
+    }
+}
+
+fn check_taps(n_taps: usize) -> Result<(), ModulationError> {
+    if n_taps.is_multiple_of(2) {
+        return Err(ModulationError::new(format!(
+            "The number of taps ({}) must be odd",
+            n_taps
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_cutoff(cutoff: Freq<f32>, fs: Freq<f32>) -> Result<(), ModulationError> {
+    let nyquist = fs / 2.;
+    if cutoff.hz() <= 0. || cutoff >= nyquist {
+        return Err(ModulationError::new(format!(
+            "Cutoff frequency ({:?}) must be in the range (0 Hz, {:?})",
+            cutoff, nyquist
+        )));
+    }
+    Ok(())
+}
+
+fn windowed_sinc(fc: f32, n_taps: usize) -> Vec<f32> {
+    let m = (n_taps - 1) as f32;
+    let coef = (0..n_taps)
+        .map(|i| {
+            let x = i as f32 - m / 2.;
+            let sinc = if x == 0. {
+                2. * fc
+            } else {
+                (2. * PI * fc * x).sin() / (PI * x)
+            };
+            let window = if n_taps == 1 {
+                1.
+            } else {
+                0.54 - 0.46 * (2. * PI * i as f32 / m).cos()
+            };
+            sinc * window
+        })
+        .collect::<Vec<_>>();
+    let sum = coef.iter().sum::<f32>();
+    coef.into_iter().map(|c| c / sum).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::modulation::{Custom, Fourier, Sine};
@@ -190,4 +297,127 @@ mod tests {
 
         Ok(())
     }
+
+    #[rstest::rstest]
+    #[case(1)]
+    #[case(31)]
+    #[case(199)]
+    #[test]
+    fn low_pass_coef(#[case] n_taps: usize) -> anyhow::Result<()> {
+        let m = Fir::low_pass(
+            Custom {
+                buffer: vec![0; 2],
+                sampling_config: SamplingConfig::new(20 * kHz)?,
+            },
+            200. * Hz,
+            n_taps,
+        )?;
+        assert_eq!(n_taps, m.coef.len());
+        approx::assert_abs_diff_eq!(1., m.coef.iter().sum::<f32>(), epsilon = 1e-4);
+        m.coef
+            .iter()
+            .zip(m.coef.iter().rev())
+            .for_each(|(a, b)| approx::assert_abs_diff_eq!(a, b, epsilon = 1e-6));
+        Ok(())
+    }
+
+    #[test]
+    fn high_pass_coef() -> anyhow::Result<()> {
+        let m = Fir::high_pass(
+            Custom {
+                buffer: vec![0; 2],
+                sampling_config: SamplingConfig::new(20 * kHz)?,
+            },
+            200. * Hz,
+            31,
+        )?;
+        approx::assert_abs_diff_eq!(0., m.coef.iter().sum::<f32>(), epsilon = 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn band_pass_coef() -> anyhow::Result<()> {
+        let m = Fir::band_pass(
+            Custom {
+                buffer: vec![0; 2],
+                sampling_config: SamplingConfig::new(20 * kHz)?,
+            },
+            100. * Hz,
+            1000. * Hz,
+            31,
+        )?;
+        approx::assert_abs_diff_eq!(0., m.coef.iter().sum::<f32>(), epsilon = 1e-4);
+        Ok(())
+    }
+
+    #[test]
+    fn low_pass_attenuates_high_freq() -> anyhow::Result<()> {
+        let sine = |freq| Sine {
+            freq,
+            option: crate::modulation::SineOption {
+                sampling_config: SamplingConfig::new(20 * kHz).unwrap(),
+                ..Default::default()
+            },
+        };
+        let amplitude =
+            |buf: Vec<u8>| *buf.iter().max().unwrap() as i32 - *buf.iter().min().unwrap() as i32;
+
+        let low = amplitude(Fir::low_pass(sine(100 * Hz), 500. * Hz, 199)?.calc()?);
+        let high = amplitude(Fir::low_pass(sine(2000 * Hz), 500. * Hz, 199)?.calc()?);
+        assert!(low > 200);
+        assert!(high < 10);
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(
+        Err(ModulationError::new("The number of taps (30) must be odd".to_owned())),
+        200. * Hz,
+        30
+    )]
+    #[case(
+        Err(ModulationError::new("Cutoff frequency (0 Hz) must be in the range (0 Hz, 10000 Hz)".to_owned())),
+        0. * Hz,
+        31
+    )]
+    #[case(
+        Err(ModulationError::new("Cutoff frequency (10000 Hz) must be in the range (0 Hz, 10000 Hz)".to_owned())),
+        10. * kHz,
+        31
+    )]
+    #[test]
+    fn preset_err(
+        #[case] expect: Result<(), ModulationError>,
+        #[case] cutoff: Freq<f32>,
+        #[case] n_taps: usize,
+    ) -> anyhow::Result<()> {
+        let target = || Custom {
+            buffer: vec![0; 2],
+            sampling_config: SamplingConfig::new(20 * kHz).unwrap(),
+        };
+        assert_eq!(expect, Fir::low_pass(target(), cutoff, n_taps).map(|_| ()));
+        assert_eq!(expect, Fir::high_pass(target(), cutoff, n_taps).map(|_| ()));
+        Ok(())
+    }
+
+    #[test]
+    fn band_pass_err() {
+        assert_eq!(
+            Err(ModulationError::new(
+                "Lower cutoff frequency (1000 Hz) must be less than higher cutoff frequency (100 Hz)"
+                    .to_owned()
+            )),
+            Fir::band_pass(
+                Custom {
+                    buffer: vec![0; 2],
+                    sampling_config: SamplingConfig::new(20 * kHz).unwrap(),
+                },
+                1000. * Hz,
+                100. * Hz,
+                31,
+            )
+            .map(|_| ())
+        );
+    }
 }
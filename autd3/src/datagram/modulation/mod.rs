@@ -1,3 +1,4 @@
+mod biquad;
 mod cache;
 mod custom;
 mod fir;
@@ -9,7 +10,9 @@ mod sine;
 mod square;
 mod r#static;
 
+pub use autd3_core::modulation::{Concat, Mix, RingModulation};
 pub use autd3_driver::datagram::IntoBoxedModulation;
+pub use biquad::{Biquad, BiquadCoef};
 pub use cache::Cache as ModulationCache;
 pub use custom::Custom;
 pub use fir::Fir;
@@ -94,7 +94,7 @@ impl SamplingMode {
             }
             let fnd = (fd * n as f64) as u64;
             let fs = ultrasound_freq().hz() as u64;
-            if fnd % fs != 0 {
+            if !fnd.is_multiple_of(fs) {
                 continue;
             }
             let k = fnd / fs;
@@ -0,0 +1,389 @@
+use std::{f32::consts::PI, time::Duration};
+
+use autd3_core::defined::Freq;
+use autd3_driver::{
+    defined::{mm, Hz},
+    geometry::{Point3, UnitVector3},
+};
+
+use derive_new::new;
+
+use crate::{
+    datagram::sensations::{basis, check, Sensation, SensationBundle, SensationOption},
+    error::AUTDError,
+    modulation::{sampling_mode::Nearest, Sine, Square, SquareOption, Static},
+};
+
+/// A stationary focus turned on and off periodically.
+///
+/// The default frequency is 200 Hz with the duty ratio of `0.5`.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// let center = autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm);
+/// autd.send(Silencer::disable())?;
+/// autd.send(PulseTrain::new(center).compile()?)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, new)]
+pub struct PulseTrain {
+    /// The position of the focus.
+    pub pos: Point3,
+    /// The frequency of the pulses. The frequency is rounded to the nearest one that can be output. The default value is 200 Hz.
+    #[new(value = "200. * Hz")]
+    pub freq: Freq<f32>,
+    /// The ratio of the on-time to the period. The default value is `0.5`.
+    #[new(value = "0.5")]
+    pub duty: f32,
+    /// The option of the sensation.
+    #[new(default)]
+    pub option: SensationOption,
+}
+
+impl Sensation for PulseTrain {
+    type Modulation = Square<Nearest>;
+
+    fn compile(&self) -> Result<SensationBundle<Self::Modulation>, AUTDError> {
+        check(self.freq.hz() > 0., "The frequency must be positive")?;
+        check(
+            (0.0..=1.0).contains(&self.duty),
+            "The duty ratio must be in range from 0 to 1",
+        )?;
+        Ok((
+            Square {
+                freq: self.freq,
+                option: SquareOption {
+                    duty: self.duty,
+                    ..Default::default()
+                },
+            }
+            .into_nearest(),
+            self.option.stm(0., |_| self.pos),
+        ))
+    }
+}
+
+/// A focus stroking along a path like a brush.
+///
+/// The focus moves along [`path`] at [`speed`] and then returns backward, and the amplitude is modulated by [`Sine`] at [`am_freq`] so that the slow movement is perceivable.
+/// The default speed is 500 mm/s and the default modulation frequency is 200 Hz.
+///
+/// [`path`]: BrushStroke::path
+/// [`speed`]: BrushStroke::speed
+/// [`am_freq`]: BrushStroke::am_freq
+#[derive(Debug, Clone, PartialEq, new)]
+pub struct BrushStroke {
+    /// The vertices of the path.
+    pub path: Vec<Point3>,
+    /// The speed of the focus per second. The default value is 500 mm/s.
+    #[new(value = "500. * mm")]
+    pub speed: f32,
+    /// The frequency of the amplitude modulation. The frequency is rounded to the nearest one that can be output. The default value is 200 Hz.
+    #[new(value = "200. * Hz")]
+    pub am_freq: Freq<f32>,
+    /// The option of the sensation.
+    #[new(default)]
+    pub option: SensationOption,
+}
+
+impl BrushStroke {
+    fn pos(&self, s: f32) -> Point3 {
+        let mut s = s;
+        for w in self.path.windows(2) {
+            let l = (w[1] - w[0]).norm();
+            if s <= l {
+                return w[0] + (w[1] - w[0]) * (s / l);
+            }
+            s -= l;
+        }
+        self.path[self.path.len() - 1]
+    }
+}
+
+impl Sensation for BrushStroke {
+    type Modulation = Sine<Nearest>;
+
+    fn compile(&self) -> Result<SensationBundle<Self::Modulation>, AUTDError> {
+        let length = self
+            .path
+            .windows(2)
+            .map(|w| (w[1] - w[0]).norm())
+            .sum::<f32>();
+        check(length > 0., "The length of the path must be positive")?;
+        check(self.speed > 0., "The speed must be positive")?;
+        check(
+            self.am_freq.hz() > 0.,
+            "The modulation frequency must be positive",
+        )?;
+        Ok((
+            Sine {
+                freq: self.am_freq,
+                option: Default::default(),
+            }
+            .into_nearest(),
+            self.option.stm(2. * length / self.speed, |t| {
+                self.pos(length * (1. - (2. * t - 1.).abs()))
+            }),
+        ))
+    }
+}
+
+/// A ring expanding from the center.
+///
+/// The focus goes around the circle at [`rotation_freq`] while the radius grows from [`start_radius`] to [`end_radius`] in [`duration`], and then the ring starts expanding again.
+/// The number of rotations in [`duration`] is rounded to an integer. The default radius grows from 0 to 20 mm in 500 ms, and the default rotation frequency is 100 Hz.
+/// The modulation is [`Static`].
+///
+/// [`rotation_freq`]: ExpandingRing::rotation_freq
+/// [`start_radius`]: ExpandingRing::start_radius
+/// [`end_radius`]: ExpandingRing::end_radius
+/// [`duration`]: ExpandingRing::duration
+#[derive(Debug, Clone, Copy, PartialEq, new)]
+pub struct ExpandingRing {
+    /// The center of the ring.
+    pub center: Point3,
+    /// The normal vector of the ring.
+    pub n: UnitVector3,
+    /// The radius at the beginning. The default value is 0 mm.
+    #[new(value = "0.")]
+    pub start_radius: f32,
+    /// The radius at the end. The default value is 20 mm.
+    #[new(value = "20. * mm")]
+    pub end_radius: f32,
+    /// The time to grow from [`start_radius`] to [`end_radius`]. The default value is 500 ms.
+    ///
+    /// [`start_radius`]: ExpandingRing::start_radius
+    /// [`end_radius`]: ExpandingRing::end_radius
+    #[new(value = "Duration::from_millis(500)")]
+    pub duration: Duration,
+    /// The frequency of the rotation. The default value is 100 Hz.
+    #[new(value = "100. * Hz")]
+    pub rotation_freq: Freq<f32>,
+    /// The option of the sensation.
+    #[new(default)]
+    pub option: SensationOption,
+}
+
+impl Sensation for ExpandingRing {
+    type Modulation = Static;
+
+    fn compile(&self) -> Result<SensationBundle<Self::Modulation>, AUTDError> {
+        check(
+            self.start_radius >= 0. && self.end_radius >= 0.,
+            "The radius must not be negative",
+        )?;
+        check(!self.duration.is_zero(), "The duration must be positive")?;
+        check(
+            self.rotation_freq.hz() > 0.,
+            "The rotation frequency must be positive",
+        )?;
+        let duration = self.duration.as_secs_f32();
+        let rotations = (duration * self.rotation_freq.hz()).round().max(1.);
+        let (u, v) = basis(&self.n);
+        Ok((
+            Static::default(),
+            self.option.stm(duration, |t| {
+                let theta = 2. * PI * rotations * t;
+                let r = self.start_radius + (self.end_radius - self.start_radius) * t;
+                self.center + r * (theta.cos() * u + theta.sin() * v)
+            }),
+        ))
+    }
+}
+
+/// A noisy texture rendered by a focus jumping randomly within a disk.
+///
+/// The focus visits the pseudo-random positions uniformly distributed within the disk at every sampling period, and the sequence repeats every [`duration`].
+/// The positions are determined by [`seed`], so the same texture is reproduced with the same seed. The default radius is 5 mm and the default duration is 100 ms.
+/// The modulation is [`Static`].
+///
+/// [`duration`]: TextureNoise::duration
+/// [`seed`]: TextureNoise::seed
+#[derive(Debug, Clone, Copy, PartialEq, new)]
+pub struct TextureNoise {
+    /// The center of the disk.
+    pub center: Point3,
+    /// The normal vector of the disk.
+    pub n: UnitVector3,
+    /// The radius of the disk. The default value is 5 mm.
+    #[new(value = "5. * mm")]
+    pub radius: f32,
+    /// The period of the repetition. The default value is 100 ms.
+    #[new(value = "Duration::from_millis(100)")]
+    pub duration: Duration,
+    /// The seed of the pseudo-random positions. The default value is `0`.
+    #[new(value = "0")]
+    pub seed: u64,
+    /// The option of the sensation.
+    #[new(default)]
+    pub option: SensationOption,
+}
+
+// SplitMix64
+fn next_f32(state: &mut u64) -> f32 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 40) as f32 / (1u64 << 24) as f32
+}
+
+impl Sensation for TextureNoise {
+    type Modulation = Static;
+
+    fn compile(&self) -> Result<SensationBundle<Self::Modulation>, AUTDError> {
+        check(self.radius >= 0., "The radius must not be negative")?;
+        check(!self.duration.is_zero(), "The duration must be positive")?;
+        let (u, v) = basis(&self.n);
+        let mut state = self.seed;
+        Ok((
+            Static::default(),
+            self.option.stm(self.duration.as_secs_f32(), |_| {
+                let r = self.radius * next_f32(&mut state).sqrt();
+                let theta = 2. * PI * next_f32(&mut state);
+                self.center + r * (theta.cos() * u + theta.sin() * v)
+            }),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{
+        datagram::{ControlPoints, FociSTM, Silencer},
+        firmware::fpga::{EmitIntensity, SamplingConfig, Segment},
+        geometry::Vector3,
+    };
+
+    use crate::{assert_near_vector3, controller::tests::create_controller};
+
+    use super::*;
+
+    fn points(stm: &FociSTM<1, Vec<ControlPoints<1>>, SamplingConfig>) -> Vec<Point3> {
+        stm.foci.iter().map(|c| c.points[0].point).collect()
+    }
+
+    #[test]
+    fn pulse_train() -> anyhow::Result<()> {
+        let pos = Point3::new(0., 0., 150. * mm);
+        let (m, stm) = PulseTrain::new(pos).compile()?;
+
+        assert_eq!(200. * Hz, m.freq.0);
+        assert_eq!(0.5, m.option.duty);
+        assert_eq!(2, stm.foci.len());
+        points(&stm).iter().for_each(|p| {
+            assert_near_vector3!(&pos, p);
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn brush_stroke() -> anyhow::Result<()> {
+        let (m, stm) = BrushStroke {
+            speed: 10000. * mm,
+            ..BrushStroke::new(vec![
+                Point3::origin(),
+                Point3::new(5. * mm, 0., 0.),
+                Point3::new(5. * mm, 5. * mm, 0.),
+            ])
+        }
+        .compile()?;
+
+        assert_eq!(200. * Hz, m.freq.0);
+        let points = points(&stm);
+        assert_eq!(8, points.len());
+        assert_near_vector3!(&Point3::origin(), &points[0]);
+        assert_near_vector3!(&Point3::new(5. * mm, 0., 0.), &points[2]);
+        assert_near_vector3!(&Point3::new(5. * mm, 5. * mm, 0.), &points[4]);
+        assert_near_vector3!(&Point3::new(5. * mm, 2.5 * mm, 0.), &points[5]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn expanding_ring() -> anyhow::Result<()> {
+        let ring = ExpandingRing {
+            duration: Duration::from_millis(10),
+            ..ExpandingRing::new(Point3::origin(), Vector3::z_axis())
+        };
+        let (_, stm) = ring.compile()?;
+
+        let points = points(&stm);
+        assert_eq!(40, points.len());
+        points.iter().enumerate().for_each(|(i, p)| {
+            approx::assert_abs_diff_eq!(
+                ring.end_radius * i as f32 / 40.,
+                p.coords.norm(),
+                epsilon = 1e-3
+            );
+            approx::assert_abs_diff_eq!(0., p.z);
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn texture_noise() -> anyhow::Result<()> {
+        let noise = TextureNoise {
+            option: SensationOption {
+                intensity: EmitIntensity(0x80),
+                ..Default::default()
+            },
+            ..TextureNoise::new(Point3::origin(), Vector3::z_axis())
+        };
+        let (_, stm) = noise.compile()?;
+
+        assert_eq!(400, stm.foci.len());
+        assert!(stm.foci.iter().all(|c| c.intensity == EmitIntensity(0x80)));
+        let points = points(&stm);
+        assert!(points.iter().all(|p| p.coords.norm() <= noise.radius));
+        assert!(points.windows(2).all(|w| w[0] != w[1]));
+
+        assert_eq!(points, self::points(&noise.compile()?.1));
+        assert_ne!(
+            points,
+            self::points(&TextureNoise { seed: 1, ..noise }.compile()?.1)
+        );
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(PulseTrain { freq: 0. * Hz, ..PulseTrain::new(Point3::origin()) }.compile().err())]
+    #[case(PulseTrain { duty: 1.5, ..PulseTrain::new(Point3::origin()) }.compile().err())]
+    #[case(BrushStroke::new(vec![Point3::origin()]).compile().err())]
+    #[case(BrushStroke { speed: 0., ..BrushStroke::new(vec![Point3::origin(), Point3::new(1., 0., 0.)]) }.compile().err())]
+    #[case(ExpandingRing { end_radius: -1., ..ExpandingRing::new(Point3::origin(), Vector3::z_axis()) }.compile().err())]
+    #[case(ExpandingRing { duration: Duration::ZERO, ..ExpandingRing::new(Point3::origin(), Vector3::z_axis()) }.compile().err())]
+    #[case(TextureNoise { radius: -1., ..TextureNoise::new(Point3::origin(), Vector3::z_axis()) }.compile().err())]
+    #[test]
+    fn invalid(#[case] err: Option<AUTDError>) {
+        assert!(matches!(err, Some(AUTDError::InvalidSensationParameter(_))));
+    }
+
+    #[test]
+    fn send() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let center = autd.center().unwrap() + Vector3::new(0., 0., 150. * mm);
+
+        autd.send(Silencer::disable())?;
+        autd.send(ExpandingRing::new(center, Vector3::z_axis()).compile()?)?;
+        assert_eq!(2000, autd.link()[0].fpga().stm_cycle(Segment::S0));
+
+        autd.send(
+            BrushStroke::new(vec![center, center + Vector3::new(10. * mm, 0., 0.)]).compile()?,
+        )?;
+        assert_eq!(160, autd.link()[0].fpga().stm_cycle(Segment::S0));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,237 @@
+use std::sync::Arc;
+
+use autd3_driver::{
+    datagram::PulseWidthEncoder,
+    defined::PI,
+    firmware::fpga::{EmitIntensity, PWE_BUF_SIZE},
+    geometry::Device,
+};
+use derive_more::Debug;
+
+use crate::error::AUTDError;
+
+type IntensityPulseWidth = Box<dyn Fn(u8) -> u8 + Send + Sync>;
+
+/// Curve of the mapping from the intensity to the duty ratio.
+///
+/// The output amplitude of a transducer is proportional to `sin(π × duty ratio)`, so it is not proportional to the duty ratio itself.
+#[derive(Clone, Debug)]
+pub enum PulseWidthCurve {
+    /// The duty ratio is proportional to the intensity, i.e., `table[i] = round(128 i / 255)`.
+    Linear,
+    /// The output amplitude is proportional to the intensity, i.e., `table[i] = round(256 arcsin(i / 255) / π)`.
+    ///
+    /// This compensates for the nonlinearity between the duty ratio and the measured sound pressure, and is the same as the default table of [`PulseWidthEncoder`].
+    LoudnessCompensated,
+    /// A custom curve which maps the normalized intensity `i / 255` in `[0, 1]` to the duty ratio in `[0, 1)`.
+    ///
+    /// The curve is sampled at each entry of the table. The maximum output is obtained at the duty ratio of `0.5`.
+    Custom(#[debug(ignore)] Arc<dyn Fn(f32) -> f32 + Send + Sync>),
+}
+
+impl PulseWidthCurve {
+    /// Creates a [`PulseWidthCurve::Custom`] from a closure.
+    pub fn custom(f: impl Fn(f32) -> f32 + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(f))
+    }
+}
+
+/// Pulse width encoder table validated against the table size of the firmware.
+///
+/// The firmware uses the intensity as the index of the table to determine the pulse width, where the period of the ultrasound is mapped to 256.
+/// The table can be built from a named [`PulseWidthCurve`] with [`PulseWidthTable::new`], or from raw values with [`PulseWidthTable::from_table`].
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// let table = PulseWidthTable::new(PulseWidthCurve::custom(|x| 0.5 * x * x))?;
+/// assert_eq!(128, table.pulse_width(EmitIntensity::MAX));
+/// assert_eq!(EmitIntensity(180), table.intensity(64));
+///
+/// autd.send(table.into_datagram())?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PulseWidthTable {
+    table: [u8; PWE_BUF_SIZE],
+}
+
+impl PulseWidthTable {
+    /// Samples the curve into the table.
+    ///
+    /// Returns [`AUTDError::InvalidDutyRatio`] if [`PulseWidthCurve::Custom`] returns a duty ratio out of `[0, 1)` for any entry.
+    pub fn new(curve: PulseWidthCurve) -> Result<Self, AUTDError> {
+        let max = (PWE_BUF_SIZE - 1) as f32;
+        let duty = |i: usize| -> f32 {
+            let x = i as f32 / max;
+            match &curve {
+                PulseWidthCurve::Linear => x / 2.,
+                PulseWidthCurve::LoudnessCompensated => x.asin() / PI,
+                PulseWidthCurve::Custom(f) => f(x),
+            }
+        };
+        let mut table = [0; PWE_BUF_SIZE];
+        table.iter_mut().enumerate().try_for_each(|(i, v)| {
+            let d = duty(i);
+            if !(0.0..1.0).contains(&d) {
+                return Err(AUTDError::InvalidDutyRatio(i as u8, d));
+            }
+            *v = ((d * 256.).round() as u16).min(u8::MAX as u16) as u8;
+            Ok(())
+        })?;
+        Ok(Self { table })
+    }
+
+    /// Creates the table from raw pulse widths.
+    ///
+    /// Returns [`AUTDError::InvalidPulseWidthTableSize`] if the length of `table` is not [`PWE_BUF_SIZE`].
+    pub fn from_table(table: &[u8]) -> Result<Self, AUTDError> {
+        Ok(Self {
+            table: table
+                .try_into()
+                .map_err(|_| AUTDError::InvalidPulseWidthTableSize(table.len(), PWE_BUF_SIZE))?,
+        })
+    }
+
+    /// Returns the pulse widths of the table.
+    pub const fn table(&self) -> &[u8; PWE_BUF_SIZE] {
+        &self.table
+    }
+
+    /// Returns the pulse width for the intensity.
+    pub const fn pulse_width(&self, intensity: EmitIntensity) -> u8 {
+        self.table[intensity.0 as usize]
+    }
+
+    /// Returns the intensity whose pulse width is the closest to `pulse_width`.
+    ///
+    /// If multiple intensities have the same distance, the smallest one is returned. This is useful to check which intensity outputs the pulse width observed, e.g., with an oscilloscope.
+    pub fn intensity(&self, pulse_width: u8) -> EmitIntensity {
+        EmitIntensity(
+            self.table
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, &v)| v.abs_diff(pulse_width))
+                .map_or(0, |(i, _)| i as u8),
+        )
+    }
+
+    /// Converts into [`PulseWidthEncoder`] to upload the table to all devices.
+    pub fn into_datagram(
+        self,
+    ) -> PulseWidthEncoder<IntensityPulseWidth, impl Fn(&Device) -> IntensityPulseWidth> {
+        PulseWidthEncoder::new(move |_: &Device| -> IntensityPulseWidth {
+            let table = self.table;
+            Box::new(move |i| table[i as usize])
+        })
+    }
+}
+
+impl Default for PulseWidthTable {
+    fn default() -> Self {
+        Self::new(PulseWidthCurve::LoudnessCompensated).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controller::tests::create_controller;
+
+    use super::*;
+
+    #[test]
+    fn default_table() -> anyhow::Result<()> {
+        let autd = create_controller(1)?;
+        assert_eq!(
+            autd.link()[0].fpga().pulse_width_encoder_table(),
+            PulseWidthTable::default().table()
+        );
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(0, 0, PulseWidthCurve::Linear)]
+    #[case(64, 127, PulseWidthCurve::Linear)]
+    #[case(128, 255, PulseWidthCurve::Linear)]
+    #[case(0, 0, PulseWidthCurve::LoudnessCompensated)]
+    #[case(43, 128, PulseWidthCurve::LoudnessCompensated)]
+    #[case(128, 255, PulseWidthCurve::LoudnessCompensated)]
+    #[case(0, 0, PulseWidthCurve::custom(|x| x * x / 2.))]
+    #[case(32, 128, PulseWidthCurve::custom(|x| x * x / 2.))]
+    #[case(255, 255, PulseWidthCurve::custom(|x| x.min(0.999)))]
+    #[test]
+    fn sample(
+        #[case] expect: u8,
+        #[case] intensity: u8,
+        #[case] curve: PulseWidthCurve,
+    ) -> anyhow::Result<()> {
+        assert_eq!(
+            expect,
+            PulseWidthTable::new(curve)?.pulse_width(EmitIntensity(intensity))
+        );
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(AUTDError::InvalidDutyRatio(255, 1.), PulseWidthCurve::custom(|x| x))]
+    #[case(AUTDError::InvalidDutyRatio(0, -0.5), PulseWidthCurve::custom(|x| x - 0.5))]
+    #[test]
+    fn invalid_duty_ratio(#[case] expect: AUTDError, #[case] curve: PulseWidthCurve) {
+        assert_eq!(Some(expect), PulseWidthTable::new(curve).err());
+    }
+
+    #[test]
+    fn invalid_duty_ratio_nan() {
+        assert!(matches!(
+            PulseWidthTable::new(PulseWidthCurve::custom(|_| f32::NAN)),
+            Err(AUTDError::InvalidDutyRatio(0, d)) if d.is_nan()
+        ));
+    }
+
+    #[rstest::rstest]
+    #[case(Ok(()), PWE_BUF_SIZE)]
+    #[case(Err(AUTDError::InvalidPulseWidthTableSize(255, PWE_BUF_SIZE)), PWE_BUF_SIZE - 1)]
+    #[case(Err(AUTDError::InvalidPulseWidthTableSize(257, PWE_BUF_SIZE)), PWE_BUF_SIZE + 1)]
+    #[test]
+    fn from_table(#[case] expect: Result<(), AUTDError>, #[case] len: usize) {
+        assert_eq!(
+            expect,
+            PulseWidthTable::from_table(&vec![0; len]).map(|_| ())
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(0, 0)]
+    #[case(1, 1)]
+    #[case(127, 64)]
+    #[case(255, 128)]
+    #[case(255, 255)]
+    #[test]
+    fn intensity(#[case] expect: u8, #[case] pulse_width: u8) -> anyhow::Result<()> {
+        assert_eq!(
+            EmitIntensity(expect),
+            PulseWidthTable::new(PulseWidthCurve::Linear)?.intensity(pulse_width)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn into_datagram() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        let table = PulseWidthTable::new(PulseWidthCurve::Linear)?;
+        autd.send(table.clone().into_datagram())?;
+        autd.iter().for_each(|dev| {
+            assert_eq!(
+                autd.link()[dev.idx()].fpga().pulse_width_encoder_table(),
+                table.table()
+            );
+        });
+        Ok(())
+    }
+}
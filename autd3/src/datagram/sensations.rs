@@ -0,0 +1,368 @@
+use std::f32::consts::PI;
+
+use autd3_core::{defined::Freq, modulation::Modulation};
+use autd3_driver::{
+    datagram::{ControlPoint, ControlPoints, FociSTM},
+    defined::Hz,
+    firmware::fpga::{EmitIntensity, SamplingConfig},
+    geometry::{Point3, UnitVector3, Vector3},
+};
+
+use crate::{
+    error::AUTDError,
+    modulation::{sampling_mode::Nearest, Sine, Static},
+};
+
+/// A pair of [`Modulation`] and [`FociSTM`] compiled from a [`Sensation`], which can be sent as a [`Datagram`].
+///
+/// [`Datagram`]: autd3_driver::datagram::Datagram
+pub type SensationBundle<M> = (M, FociSTM<1, Vec<ControlPoints<1>>, SamplingConfig>);
+
+/// A high-level haptic sensation compiled into [`SensationBundle`].
+///
+/// Note that the completion time of [`Silencer`] must be shorter than the sampling period of the compiled [`FociSTM`], e.g., by sending [`Silencer::disable`] beforehand.
+///
+/// [`Silencer`]: autd3_driver::datagram::Silencer
+/// [`Silencer::disable`]: autd3_driver::datagram::Silencer::disable
+pub trait Sensation {
+    /// The [`Modulation`] of the compiled [`SensationBundle`].
+    type Modulation: Modulation;
+
+    /// Compiles into [`SensationBundle`].
+    ///
+    /// Returns [`AUTDError::InvalidSensationParameter`] if the parameters are invalid.
+    fn compile(&self) -> Result<SensationBundle<Self::Modulation>, AUTDError>;
+}
+
+/// The common option of [`Sensation`]s.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensationOption {
+    /// The intensity of the focus. The default value is [`EmitIntensity::MAX`].
+    pub intensity: EmitIntensity,
+    /// The sampling frequency of the focus position. The frequency is rounded to the nearest one that can be output. The default value is 4 kHz.
+    pub sampling_freq: Freq<f32>,
+}
+
+impl Default for SensationOption {
+    fn default() -> Self {
+        Self {
+            intensity: EmitIntensity::MAX,
+            sampling_freq: 4000. * Hz,
+        }
+    }
+}
+
+impl SensationOption {
+    pub(crate) fn stm(
+        &self,
+        period: f32,
+        mut pos: impl FnMut(f32) -> Point3,
+    ) -> FociSTM<1, Vec<ControlPoints<1>>, SamplingConfig> {
+        let config = SamplingConfig::new_nearest(self.sampling_freq);
+        let num_points = ((period * config.freq().hz()).round() as usize).max(2);
+        FociSTM {
+            foci: (0..num_points)
+                .map(|i| ControlPoints {
+                    points: [ControlPoint::from(pos(i as f32 / num_points as f32))],
+                    intensity: self.intensity,
+                })
+                .collect(),
+            config,
+        }
+    }
+}
+
+pub(crate) fn check(valid: bool, msg: &str) -> Result<(), AUTDError> {
+    if valid {
+        Ok(())
+    } else {
+        Err(AUTDError::InvalidSensationParameter(msg.to_string()))
+    }
+}
+
+pub(crate) fn basis(n: &UnitVector3) -> (Vector3, Vector3) {
+    let v = if n.dot(&Vector3::z()).abs() < 0.9 {
+        Vector3::z()
+    } else {
+        Vector3::y()
+    };
+    let u = n.cross(&v).normalize();
+    let v = n.cross(&u).normalize();
+    (u, v)
+}
+
+/// A focus moving back and forth along a line at a constant speed.
+///
+/// The focus moves from [`start`] to [`end`] and then returns to [`start`], so the period is twice the length of the line divided by [`speed`]. The modulation is [`Static`].
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// let center = autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm);
+/// autd.send(Silencer::disable())?;
+/// autd.send(
+///     LineSensation {
+///         start: center - Vector3::new(10.0 * mm, 0., 0.),
+///         end: center + Vector3::new(10.0 * mm, 0., 0.),
+///         speed: 1000.0 * mm,
+///         option: Default::default(),
+///     }
+///     .compile()?,
+/// )?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`start`]: LineSensation::start
+/// [`end`]: LineSensation::end
+/// [`speed`]: LineSensation::speed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineSensation {
+    /// The start point of the line.
+    pub start: Point3,
+    /// The end point of the line.
+    pub end: Point3,
+    /// The speed of the focus per second.
+    pub speed: f32,
+    /// The option of the sensation.
+    pub option: SensationOption,
+}
+
+impl Sensation for LineSensation {
+    type Modulation = Static;
+
+    fn compile(&self) -> Result<SensationBundle<Self::Modulation>, AUTDError> {
+        let dir = self.end - self.start;
+        check(dir.norm() > 0., "The length of the line must be positive")?;
+        check(self.speed > 0., "The speed must be positive")?;
+        Ok((
+            Static::default(),
+            self.option.stm(2. * dir.norm() / self.speed, |t| {
+                self.start + dir * (1. - (2. * t - 1.).abs())
+            }),
+        ))
+    }
+}
+
+/// A focus moving around a circle at a constant speed.
+///
+/// The period is the circumference divided by [`speed`]. The modulation is [`Static`].
+///
+/// [`speed`]: CircleSensation::speed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircleSensation {
+    /// The center of the circle.
+    pub center: Point3,
+    /// The radius of the circle.
+    pub radius: f32,
+    /// The normal vector of the circle.
+    pub n: UnitVector3,
+    /// The speed of the focus per second.
+    pub speed: f32,
+    /// The option of the sensation.
+    pub option: SensationOption,
+}
+
+impl Sensation for CircleSensation {
+    type Modulation = Static;
+
+    fn compile(&self) -> Result<SensationBundle<Self::Modulation>, AUTDError> {
+        check(self.radius > 0., "The radius must be positive")?;
+        check(self.speed > 0., "The speed must be positive")?;
+        let (u, v) = basis(&self.n);
+        Ok((
+            Static::default(),
+            self.option.stm(2. * PI * self.radius / self.speed, |t| {
+                let theta = 2. * PI * t;
+                self.center + self.radius * (theta.cos() * u + theta.sin() * v)
+            }),
+        ))
+    }
+}
+
+/// A textured surface rendered by the lateral modulation of the focus with the amplitude modulation.
+///
+/// The focus oscillates sinusoidally along [`direction`] with the peak-to-peak [`width`] at [`lateral_freq`], which makes the focus perceivable without the amplitude modulation.
+/// In addition, the amplitude is modulated by [`Sine`] at [`texture_freq`] to present the roughness of the surface. The frequencies are rounded to the nearest ones that can be output.
+///
+/// [`direction`]: TextureSensation::direction
+/// [`width`]: TextureSensation::width
+/// [`lateral_freq`]: TextureSensation::lateral_freq
+/// [`texture_freq`]: TextureSensation::texture_freq
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureSensation {
+    /// The center of the lateral modulation.
+    pub center: Point3,
+    /// The direction of the lateral modulation.
+    pub direction: UnitVector3,
+    /// The peak-to-peak width of the lateral modulation.
+    pub width: f32,
+    /// The frequency of the lateral modulation.
+    pub lateral_freq: Freq<f32>,
+    /// The frequency of the amplitude modulation.
+    pub texture_freq: Freq<f32>,
+    /// The option of the sensation.
+    pub option: SensationOption,
+}
+
+impl Sensation for TextureSensation {
+    type Modulation = Sine<Nearest>;
+
+    fn compile(&self) -> Result<SensationBundle<Self::Modulation>, AUTDError> {
+        check(self.width >= 0., "The width must not be negative")?;
+        check(
+            self.lateral_freq.hz() > 0.,
+            "The lateral modulation frequency must be positive",
+        )?;
+        check(
+            self.texture_freq.hz() > 0.,
+            "The texture frequency must be positive",
+        )?;
+        Ok((
+            Sine {
+                freq: self.texture_freq,
+                option: Default::default(),
+            }
+            .into_nearest(),
+            self.option.stm(1. / self.lateral_freq.hz(), |t| {
+                self.center + self.direction.into_inner() * self.width / 2. * (2. * PI * t).sin()
+            }),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{datagram::Silencer, defined::mm, firmware::fpga::Segment};
+
+    use crate::{assert_near_vector3, controller::tests::create_controller};
+
+    use super::*;
+
+    fn points(stm: &FociSTM<1, Vec<ControlPoints<1>>, SamplingConfig>) -> Vec<Point3> {
+        stm.foci.iter().map(|c| c.points[0].point).collect()
+    }
+
+    #[test]
+    fn line() -> anyhow::Result<()> {
+        let (m, stm) = LineSensation {
+            start: Point3::origin(),
+            end: Point3::new(10. * mm, 0., 0.),
+            speed: 10000. * mm,
+            option: Default::default(),
+        }
+        .compile()?;
+
+        assert_eq!(Static::default(), m);
+        assert_eq!(SamplingConfig::DIV_10, stm.config);
+        let points = points(&stm);
+        assert_eq!(8, points.len());
+        assert_near_vector3!(&Point3::origin(), &points[0]);
+        assert_near_vector3!(&Point3::new(10. * mm, 0., 0.), &points[4]);
+        assert_near_vector3!(&Point3::new(2.5 * mm, 0., 0.), &points[7]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn circle() -> anyhow::Result<()> {
+        let radius = 10. * mm;
+        let (_, stm) = CircleSensation {
+            center: Point3::origin(),
+            radius,
+            n: Vector3::z_axis(),
+            speed: 2. * PI * radius * 100.,
+            option: Default::default(),
+        }
+        .compile()?;
+
+        let points = points(&stm);
+        assert_eq!(40, points.len());
+        points.iter().for_each(|p| {
+            approx::assert_abs_diff_eq!(radius, p.coords.norm(), epsilon = 1e-3);
+            approx::assert_abs_diff_eq!(0., p.z);
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn texture() -> anyhow::Result<()> {
+        let (m, stm) = TextureSensation {
+            center: Point3::origin(),
+            direction: Vector3::x_axis(),
+            width: 4. * mm,
+            lateral_freq: 100. * Hz,
+            texture_freq: 20. * Hz,
+            option: SensationOption {
+                intensity: EmitIntensity(0x80),
+                ..Default::default()
+            },
+        }
+        .compile()?;
+
+        assert_eq!(20. * Hz, m.freq.0);
+        assert_eq!(40, stm.foci.len());
+        assert!(stm.foci.iter().all(|c| c.intensity == EmitIntensity(0x80)));
+        let points = points(&stm);
+        assert_near_vector3!(&Point3::origin(), &points[0]);
+        assert_near_vector3!(&Point3::new(2. * mm, 0., 0.), &points[10]);
+        assert_near_vector3!(&Point3::new(-2. * mm, 0., 0.), &points[30]);
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(Point3::origin(), 1.)]
+    #[case(Point3::new(1., 0., 0.), 0.)]
+    #[test]
+    fn invalid_line(#[case] end: Point3, #[case] speed: f32) {
+        assert!(matches!(
+            LineSensation {
+                start: Point3::origin(),
+                end,
+                speed,
+                option: Default::default(),
+            }
+            .compile(),
+            Err(AUTDError::InvalidSensationParameter(_))
+        ));
+    }
+
+    #[test]
+    fn send() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        autd.send(Silencer::disable())?;
+        autd.send(
+            TextureSensation {
+                center: autd.center().unwrap() + Vector3::new(0., 0., 150. * mm),
+                direction: Vector3::x_axis(),
+                width: 4. * mm,
+                lateral_freq: 100. * Hz,
+                texture_freq: 20. * Hz,
+                option: Default::default(),
+            }
+            .compile()?,
+        )?;
+
+        assert_eq!(40, autd.link()[0].fpga().stm_cycle(Segment::S0));
+        assert_eq!(
+            Sine {
+                freq: 20. * Hz,
+                option: Default::default(),
+            }
+            .into_nearest()
+            .calc()?
+            .len(),
+            autd.link()[0].fpga().modulation_cycle(Segment::S0)
+        );
+
+        Ok(())
+    }
+}
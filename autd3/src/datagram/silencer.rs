@@ -0,0 +1,300 @@
+use std::num::NonZeroU16;
+
+use autd3_core::modulation::Modulation;
+use autd3_driver::{
+    datagram::{FixedCompletionSteps, Silencer},
+    error::AUTDDriverError,
+    firmware::fpga::{
+        SamplingConfig, SilencerTarget, SILENCER_STEPS_INTENSITY_DEFAULT,
+        SILENCER_STEPS_PHASE_DEFAULT,
+    },
+};
+
+use crate::error::AUTDError;
+
+/// Sampling configurations of the datagrams which constrain the silencer in strict mode.
+///
+/// In strict mode, the firmware rejects the silencer, [`Modulation`] and STM if the completion steps of the intensity exceed the sampling division of [`Modulation`] or STM, or the completion steps of the phase exceed the sampling division of STM.
+/// The completion steps are counted in the ultrasound period, i.e., the completion time divided by the ultrasound period.
+/// [`FixedUpdateRate`] is not constrained.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// let m = Sine {
+///     freq: 150. * Hz,
+///     option: SineOption {
+///         sampling_config: SamplingConfig {
+///             division: std::num::NonZeroU16::new(4).unwrap(),
+///         },
+///         ..Default::default()
+///     },
+/// };
+/// let requirement = SilencerRequirement::default().with_modulation(&m)?;
+/// assert_eq!(4, requirement.silencer().config.intensity.get());
+///
+/// autd.send((requirement.silencer(), m))?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`FixedUpdateRate`]: autd3_driver::datagram::FixedUpdateRate
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SilencerRequirement {
+    /// The sampling configuration of [`Modulation`].
+    pub modulation: Option<SamplingConfig>,
+    /// The sampling configuration of [`FociSTM`] or [`GainSTM`].
+    ///
+    /// [`FociSTM`]: autd3_driver::datagram::FociSTM
+    /// [`GainSTM`]: autd3_driver::datagram::GainSTM
+    pub stm: Option<SamplingConfig>,
+}
+
+impl SilencerRequirement {
+    /// Adds the sampling configuration of the modulation.
+    ///
+    /// If multiple modulations are added, the one with the shortest sampling period is used.
+    pub fn with_modulation(self, m: &impl Modulation) -> Result<Self, AUTDError> {
+        let config = m.sampling_config().map_err(AUTDDriverError::from)?;
+        Ok(Self {
+            modulation: Some(Self::shortest(self.modulation, config)),
+            ..self
+        })
+    }
+
+    /// Adds the sampling configuration of the [`FociSTM`].
+    ///
+    /// If multiple STMs are added, the one with the shortest sampling period is used.
+    ///
+    /// [`FociSTM`]: autd3_driver::datagram::FociSTM
+    #[cfg(feature = "stm")]
+    pub fn with_foci_stm<
+        const N: usize,
+        T: autd3_driver::datagram::FociSTMGenerator<N>,
+        C: Into<autd3_driver::datagram::STMConfig> + Copy,
+    >(
+        self,
+        stm: &autd3_driver::datagram::FociSTM<N, T, C>,
+    ) -> Result<Self, AUTDError> {
+        Ok(self.with_stm(stm.sampling_config()?))
+    }
+
+    /// Adds the sampling configuration of the [`GainSTM`].
+    ///
+    /// If multiple STMs are added, the one with the shortest sampling period is used.
+    ///
+    /// [`GainSTM`]: autd3_driver::datagram::GainSTM
+    #[cfg(feature = "stm")]
+    pub fn with_gain_stm<
+        T: autd3_driver::datagram::GainSTMGenerator,
+        C: Into<autd3_driver::datagram::STMConfig> + Copy,
+    >(
+        self,
+        stm: &autd3_driver::datagram::GainSTM<T, C>,
+    ) -> Result<Self, AUTDError> {
+        Ok(self.with_stm(stm.sampling_config()?))
+    }
+
+    #[cfg(feature = "stm")]
+    fn with_stm(self, config: SamplingConfig) -> Self {
+        Self {
+            stm: Some(Self::shortest(self.stm, config)),
+            ..self
+        }
+    }
+
+    fn shortest(current: Option<SamplingConfig>, config: SamplingConfig) -> SamplingConfig {
+        current.map_or(config, |c| {
+            if c.division <= config.division {
+                c
+            } else {
+                config
+            }
+        })
+    }
+
+    /// Returns the maximum completion steps of the intensity and the phase allowed in strict mode.
+    pub fn max_completion_steps(&self) -> (NonZeroU16, NonZeroU16) {
+        let stm = self.stm.map_or(NonZeroU16::MAX, |c| c.division);
+        let modulation = self.modulation.map_or(NonZeroU16::MAX, |c| c.division);
+        (stm.min(modulation), stm)
+    }
+
+    /// Returns the [`Silencer`] which satisfies strict mode.
+    ///
+    /// The completion steps are the default ones of the firmware if they are allowed, otherwise the maximum ones allowed, so that the noise is suppressed as much as possible.
+    pub fn silencer(&self) -> Silencer<FixedCompletionSteps> {
+        let (intensity, phase) = self.max_completion_steps();
+        Silencer {
+            config: FixedCompletionSteps {
+                intensity: intensity
+                    .min(NonZeroU16::new(SILENCER_STEPS_INTENSITY_DEFAULT).unwrap()),
+                phase: phase.min(NonZeroU16::new(SILENCER_STEPS_PHASE_DEFAULT).unwrap()),
+                strict_mode: true,
+            },
+            target: SilencerTarget::Intensity,
+        }
+    }
+
+    /// Checks whether the configuration satisfies strict mode.
+    ///
+    /// Returns [`AUTDError::SilencerStrictModeViolation`] with the maximum completion steps allowed if not. The configuration with strict mode disabled is always valid.
+    pub fn validate(&self, config: &FixedCompletionSteps) -> Result<(), AUTDError> {
+        let (max_intensity, max_phase) = self.max_completion_steps();
+        if config.strict_mode && (config.intensity > max_intensity || config.phase > max_phase) {
+            return Err(AUTDError::SilencerStrictModeViolation(
+                config.intensity.get(),
+                config.phase.get(),
+                max_intensity.get(),
+                max_phase.get(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::defined::Hz;
+    #[cfg(feature = "stm")]
+    use autd3_driver::{datagram::FociSTM, geometry::Point3};
+
+    use crate::{
+        controller::tests::create_controller,
+        modulation::{Sine, SineOption},
+    };
+
+    use super::*;
+
+    fn config(division: u16) -> Option<SamplingConfig> {
+        Some(SamplingConfig {
+            division: NonZeroU16::new(division).unwrap(),
+        })
+    }
+
+    fn steps(intensity: u16, phase: u16, strict_mode: bool) -> FixedCompletionSteps {
+        FixedCompletionSteps {
+            intensity: NonZeroU16::new(intensity).unwrap(),
+            phase: NonZeroU16::new(phase).unwrap(),
+            strict_mode,
+        }
+    }
+
+    #[rstest::rstest]
+    #[case((10, 40), None, None)]
+    #[case((4, 40), config(4), None)]
+    #[case((10, 40), config(0xFFFF), None)]
+    #[case((8, 8), None, config(8))]
+    #[case((4, 8), config(4), config(8))]
+    #[case((10, 20), config(40), config(20))]
+    #[test]
+    fn silencer(
+        #[case] expect: (u16, u16),
+        #[case] modulation: Option<SamplingConfig>,
+        #[case] stm: Option<SamplingConfig>,
+    ) -> anyhow::Result<()> {
+        let requirement = SilencerRequirement { modulation, stm };
+        let s = requirement.silencer();
+        assert_eq!(expect, (s.config.intensity.get(), s.config.phase.get()));
+        assert!(s.config.strict_mode);
+        requirement.validate(&s.config)?;
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(Ok(()), steps(4, 8, true))]
+    #[case(Ok(()), steps(5, 9, false))]
+    #[case(
+        Err(AUTDError::SilencerStrictModeViolation(5, 8, 4, 8)),
+        steps(5, 8, true)
+    )]
+    #[case(
+        Err(AUTDError::SilencerStrictModeViolation(4, 9, 4, 8)),
+        steps(4, 9, true)
+    )]
+    #[test]
+    fn validate(#[case] expect: Result<(), AUTDError>, #[case] steps: FixedCompletionSteps) {
+        assert_eq!(
+            expect,
+            SilencerRequirement {
+                modulation: config(4),
+                stm: config(8),
+            }
+            .validate(&steps)
+        );
+    }
+
+    #[cfg(feature = "stm")]
+    #[test]
+    fn with_datagrams() -> anyhow::Result<()> {
+        let m = |division| Sine {
+            freq: 100. * Hz,
+            option: SineOption {
+                sampling_config: config(division).unwrap(),
+                ..Default::default()
+            },
+        };
+        let stm = |division| FociSTM {
+            foci: vec![Point3::origin(); 2],
+            config: config(division).unwrap(),
+        };
+
+        let requirement = SilencerRequirement::default()
+            .with_modulation(&m(8))?
+            .with_modulation(&m(4))?
+            .with_modulation(&m(16))?
+            .with_foci_stm(&stm(30))?
+            .with_foci_stm(&stm(20))?;
+        assert_eq!(config(4), requirement.modulation);
+        assert_eq!(config(20), requirement.stm);
+
+        let mut autd = create_controller(1)?;
+        autd.send((requirement.silencer(), m(4)))?;
+        autd.send(stm(20))?;
+        assert_eq!(
+            4,
+            autd.link()[0]
+                .fpga()
+                .silencer_completion_steps()
+                .intensity
+                .get()
+        );
+        assert_eq!(
+            20,
+            autd.link()[0]
+                .fpga()
+                .silencer_completion_steps()
+                .phase
+                .get()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn strict_mode_fails_without_requirement() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        let requirement = SilencerRequirement {
+            modulation: config(4),
+            stm: None,
+        };
+        let default = Silencer::default();
+        assert!(requirement.validate(&default.config).is_err());
+        autd.send(default)?;
+        assert!(autd
+            .send(Sine {
+                freq: 100. * Hz,
+                option: SineOption {
+                    sampling_config: config(4).unwrap(),
+                    ..Default::default()
+                },
+            })
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use std::time::Duration;
+
+use autd3_core::{derive::*, link::Link};
+use autd3_driver::{datagram::Silencer, firmware::fpga::EmitIntensity};
+
+use crate::{
+    controller::{Controller, SendFuture, Sequence, Watchdog, WatchdogOption},
+    modulation::Static,
+};
+
+/// The option of [`StaticPressure`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticPressureOption {
+    /// The ceiling of the intensity. The intensity of each transducer is clamped to this value. The default is `EmitIntensity(0x80)`.
+    ///
+    /// If `None`, the intensity of the gain is output as it is.
+    pub intensity_limit: Option<EmitIntensity>,
+    /// The maximum duration of the output. The default is 10 s.
+    ///
+    /// This is enforced by the [`Watchdog`] returned by [`StaticPressure::start`], i.e., the output is muted when this duration elapses unless [`Watchdog::feed`] is called.
+    /// If `None`, the output continues until the [`Watchdog`] is dropped.
+    pub max_duration: Option<Duration>,
+}
+
+impl Default for StaticPressureOption {
+    fn default() -> Self {
+        Self {
+            intensity_limit: Some(EmitIntensity(0x80)),
+            max_duration: Some(Duration::from_secs(10)),
+        }
+    }
+}
+
+/// Un-modulated output of a [`Gain`] for a long duration
+///
+/// [`StaticPressure::into_sequence`] bundles the following datagrams into a [`Sequence`], which are sent in order:
+///
+/// 1. [`Silencer::disable`], because the output does not change after it starts, and the watchdog mutes the output immediately.
+/// 2. [`Static`] modulation and the gain whose intensity is clamped to [`intensity_limit`].
+///
+/// [`StaticPressure::start`] sends the [`Sequence`] via [`Watchdog`] with the timeout of [`max_duration`] to stop the output after it.
+/// Since the [`Watchdog`] runs on the host, see its documentation for the cases where the output cannot be stopped.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use autd3::prelude::*;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// let pos = autd.center().unwrap() + Vector3::new(0., 0., 150. * mm);
+/// let (watchdog, future) = StaticPressure {
+///     gain: Focus::new(pos, FocusOption::default()),
+///     option: StaticPressureOption {
+///         max_duration: Some(Duration::from_secs(60)),
+///         ..Default::default()
+///     },
+/// }
+/// .start(autd);
+/// # tokio_test::block_on(future)?;
+/// // the output is muted after 60 s
+/// # let autd = watchdog.into_controller();
+/// # autd.close()?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`max_duration`]: StaticPressureOption::max_duration
+/// [`intensity_limit`]: StaticPressureOption::intensity_limit
+#[derive(Clone, Debug)]
+pub struct StaticPressure<G: Gain> {
+    /// The gain to be output.
+    pub gain: G,
+    /// The option of the output.
+    pub option: StaticPressureOption,
+}
+
+impl<G: Gain + Send + Sync + 'static> StaticPressure<G> {
+    /// Converts into the [`Sequence`] to start the output.
+    ///
+    /// The [`Sequence`] itself does not stop the output after [`StaticPressureOption::max_duration`]. Use [`StaticPressure::start`] to enforce it.
+    pub fn into_sequence(self) -> Sequence {
+        Sequence::new().push(Silencer::disable(), None).push(
+            (
+                Static::default(),
+                Limited {
+                    gain: self.gain,
+                    limit: self.option.intensity_limit.unwrap_or(EmitIntensity::MAX),
+                },
+            ),
+            None,
+        )
+    }
+}
+
+impl<G: Gain + Send + Sync + 'static> StaticPressure<G> {
+    /// Starts the output by sending [`StaticPressure::into_sequence`] via [`Watchdog`] whose timeout is [`StaticPressureOption::max_duration`].
+    ///
+    /// Returns the [`Watchdog`] and the [`SendFuture`] resolved with the result of sending the [`Sequence`]. Dropping the [`Watchdog`] mutes the output.
+    pub fn start<L: Link + Send + 'static>(self, autd: Controller<L>) -> (Watchdog<L>, SendFuture) {
+        let watchdog = Watchdog::new(
+            autd,
+            WatchdogOption {
+                timeout: self.option.max_duration,
+                ..Default::default()
+            },
+        );
+        let future = watchdog.enqueue_sequence(move || self.into_sequence());
+        (watchdog, future)
+    }
+}
+
+#[derive(Gain, Debug)]
+struct Limited<G: Gain> {
+    gain: G,
+    limit: EmitIntensity,
+}
+
+struct Impl<C: GainCalculator> {
+    calc: C,
+    limit: EmitIntensity,
+}
+
+impl<C: GainCalculator> GainCalculator for Impl<C> {
+    fn calc(&self, tr: &Transducer) -> Drive {
+        let d = self.calc.calc(tr);
+        Drive {
+            phase: d.phase,
+            intensity: d.intensity.min(self.limit),
+        }
+    }
+}
+
+struct Generator<G: GainCalculatorGenerator> {
+    generator: G,
+    limit: EmitIntensity,
+}
+
+impl<G: GainCalculatorGenerator> GainCalculatorGenerator for Generator<G> {
+    type Calculator = Impl<G::Calculator>;
+
+    fn generate(&mut self, device: &Device) -> Self::Calculator {
+        Impl {
+            calc: self.generator.generate(device),
+            limit: self.limit,
+        }
+    }
+}
+
+impl<G: Gain> Gain for Limited<G> {
+    type G = Generator<G::G>;
+
+    fn init(self) -> Result<Self::G, GainError> {
+        Ok(Generator {
+            generator: self.gain.init()?,
+            limit: self.limit,
+        })
+    }
+
+    fn init_full(
+        self,
+        geometry: &Geometry,
+        filter: Option<&HashMap<usize, BitVec>>,
+        parallel: bool,
+    ) -> Result<Self::G, GainError> {
+        Ok(Generator {
+            generator: self.gain.init_full(geometry, filter, parallel)?,
+            limit: self.limit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::firmware::fpga::{Phase, Segment};
+
+    use crate::{controller::tests::create_controller, gain::Uniform};
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(EmitIntensity(0x80), StaticPressureOption::default())]
+    #[case(EmitIntensity(0x40), StaticPressureOption { intensity_limit: Some(EmitIntensity(0x40)), ..Default::default() })]
+    #[case(EmitIntensity(0xFF), StaticPressureOption { intensity_limit: None, max_duration: None })]
+    #[test]
+    fn static_pressure(
+        #[case] expect: EmitIntensity,
+        #[case] option: StaticPressureOption,
+    ) -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        let s = StaticPressure {
+            gain: Uniform::new(EmitIntensity::MAX, Phase(0x80)),
+            option,
+        }
+        .into_sequence();
+        assert_eq!(2, s.datagrams.len());
+        autd.send_sequence(s)?;
+
+        let fpga = autd.link()[0].fpga();
+        assert_eq!(1, fpga.silencer_completion_steps().intensity.get());
+        assert_eq!(1, fpga.silencer_completion_steps().phase.get());
+        assert_eq!(vec![0xFF, 0xFF], fpga.modulation_buffer(Segment::S0));
+        assert!(fpga
+            .drives_at(Segment::S0, 0)
+            .iter()
+            .all(|d| d.intensity == expect && d.phase == Phase(0x80)));
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(EmitIntensity(0x20), EmitIntensity(0x20))]
+    #[case(EmitIntensity(0x80), EmitIntensity(0xFF))]
+    #[test]
+    fn limited(
+        #[case] expect: EmitIntensity,
+        #[case] intensity: EmitIntensity,
+    ) -> anyhow::Result<()> {
+        let autd = create_controller(1)?;
+        let mut g = Limited {
+            gain: Uniform::new(intensity, Phase::ZERO),
+            limit: EmitIntensity(0x80),
+        }
+        .init_full(&autd, None, false)?;
+        let c = g.generate(&autd[0]);
+        assert!(autd[0].iter().all(|tr| c.calc(tr).intensity == expect));
+        Ok(())
+    }
+
+    #[test]
+    fn start() -> anyhow::Result<()> {
+        let autd = create_controller(1)?;
+
+        let (watchdog, future) = StaticPressure {
+            gain: Uniform::new(EmitIntensity::MAX, Phase::ZERO),
+            option: StaticPressureOption {
+                max_duration: Some(Duration::from_millis(100)),
+                ..Default::default()
+            },
+        }
+        .start(autd);
+        tokio_test::block_on(future)?;
+        std::thread::sleep(Duration::from_millis(200));
+        let autd = watchdog.into_controller();
+
+        let fpga = autd.link()[0].fpga();
+        assert_eq!(1, fpga.silencer_completion_steps().intensity.get());
+        assert_eq!(
+            vec![Drive::NULL; autd[0].num_transducers()],
+            fpga.drives_at(Segment::S0, 0)
+        );
+
+        Ok(())
+    }
+}
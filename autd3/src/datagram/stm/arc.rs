@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use autd3_core::{
+    derive::{Device, Geometry},
+    gain::{BitVec, EmitIntensity, GainError, Phase},
+};
+use autd3_driver::{
+    datagram::{
+        ControlPoint, ControlPoints, FociSTMGenerator, FociSTMIterator, FociSTMIteratorGenerator,
+        GainSTMGenerator, GainSTMIterator, GainSTMIteratorGenerator,
+    },
+    defined::Angle,
+    error::AUTDDriverError,
+    geometry::{Point3, UnitQuaternion, Vector3},
+};
+
+/// Utility for generating an arc trajectory STM.
+///
+/// The arc is defined on the local xy-plane and then rotated by [`rotation`] around [`center`].
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// FociSTM {
+///     config: 1.0 * Hz,
+///     foci: ArcPath {
+///         center: Point3::origin(),
+///         radius: 30.0 * mm,
+///         start_angle: 0.0 * deg,
+///         end_angle: 180.0 * deg,
+///         num_points: 50,
+///         rotation: UnitQuaternion::identity(),
+///         intensity: EmitIntensity::MAX,
+///     },
+/// };
+/// ```
+///
+/// [`rotation`]: ArcPath::rotation
+/// [`center`]: ArcPath::center
+#[derive(Clone, Debug)]
+pub struct ArcPath {
+    /// The center of the arc.
+    pub center: Point3,
+    /// The radius of the arc.
+    pub radius: f32,
+    /// The angle of the first point.
+    pub start_angle: Angle,
+    /// The angle of the last point.
+    pub end_angle: Angle,
+    /// The number of points on the arc. Both end points are included.
+    pub num_points: usize,
+    /// The rotation of the arc plane.
+    pub rotation: UnitQuaternion,
+    /// The intensity of the emitted ultrasound.
+    pub intensity: EmitIntensity,
+}
+
+pub struct ArcSTMIterator {
+    center: Point3,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    num_points: usize,
+    rotation: UnitQuaternion,
+    wavenumber: f32,
+    intensity: EmitIntensity,
+    i: usize,
+}
+
+impl ArcSTMIterator {
+    fn next(&mut self) -> Option<Point3> {
+        if self.i >= self.num_points {
+            return None;
+        }
+        let t = if self.num_points == 1 {
+            0.
+        } else {
+            self.i as f32 / (self.num_points - 1) as f32
+        };
+        let theta = self.start_angle + (self.end_angle - self.start_angle) * t;
+        self.i += 1;
+        Some(
+            self.center
+                + self.rotation * (self.radius * Vector3::new(theta.cos(), theta.sin(), 0.)),
+        )
+    }
+}
+
+impl FociSTMIterator<1> for ArcSTMIterator {
+    fn next(&mut self) -> ControlPoints<1> {
+        ControlPoints {
+            points: [ControlPoint::from(self.next().unwrap())],
+            intensity: self.intensity,
+        }
+    }
+}
+
+impl GainSTMIterator for ArcSTMIterator {
+    type Calculator = crate::gain::focus::Impl;
+
+    fn next(&mut self) -> Option<Self::Calculator> {
+        Some(Self::Calculator {
+            pos: self.next()?,
+            intensity: self.intensity,
+            phase_offset: Phase::ZERO,
+            wavenumber: self.wavenumber,
+            compensation: None,
+        })
+    }
+}
+
+impl FociSTMIteratorGenerator<1> for ArcPath {
+    type Iterator = ArcSTMIterator;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        Self::Iterator {
+            center: self.center,
+            radius: self.radius,
+            start_angle: self.start_angle.radian(),
+            end_angle: self.end_angle.radian(),
+            num_points: self.num_points,
+            rotation: self.rotation,
+            wavenumber: device.wavenumber(),
+            intensity: self.intensity,
+            i: 0,
+        }
+    }
+}
+
+impl GainSTMIteratorGenerator for ArcPath {
+    type Gain = crate::gain::focus::Generator;
+    type Iterator = ArcSTMIterator;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        FociSTMIteratorGenerator::<1>::generate(self, device)
+    }
+}
+
+impl FociSTMGenerator<1> for ArcPath {
+    type T = Self;
+
+    // GRCOV_EXCL_START
+    fn init(self) -> Result<Self::T, AUTDDriverError> {
+        Ok(self)
+    }
+    // GRCOV_EXCL_STOP
+
+    fn len(&self) -> usize {
+        self.num_points
+    }
+}
+
+impl GainSTMGenerator for ArcPath {
+    type T = Self;
+
+    // GRCOV_EXCL_START
+    fn init(
+        self,
+        _: &Geometry,
+        _filter: Option<&HashMap<usize, BitVec>>,
+        _: bool,
+    ) -> Result<Self::T, GainError> {
+        Ok(self)
+    }
+    // GRCOV_EXCL_STOP
+
+    fn len(&self) -> usize {
+        self.num_points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::DerefMut;
+
+    use autd3_core::modulation::SamplingConfig;
+    use autd3_driver::{
+        datagram::{FociSTM, GainSTM, GainSTMOption},
+        defined::{deg, mm, PI},
+        geometry::IntoDevice,
+    };
+
+    use crate::assert_near_vector3;
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(
+        vec![
+            Point3::new(30.0 * mm, 0., 0.),
+            Point3::new(0., 30.0 * mm, 0.),
+            Point3::new(-30.0 * mm, 0., 0.),
+        ],
+        UnitQuaternion::identity()
+    )]
+    #[case(
+        vec![
+            Point3::new(30.0 * mm, 0., 0.),
+            Point3::new(0., 0., 30.0 * mm),
+            Point3::new(-30.0 * mm, 0., 0.),
+        ],
+        UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI / 2.)
+    )]
+    #[test]
+    fn arc(#[case] expect: Vec<Point3>, #[case] rotation: UnitQuaternion) {
+        let arc = ArcPath {
+            center: Point3::origin(),
+            radius: 30.0 * mm,
+            start_angle: 0.0 * deg,
+            end_angle: 180.0 * deg,
+            num_points: 3,
+            rotation,
+            intensity: EmitIntensity::MAX,
+        };
+        assert_eq!(3, FociSTMGenerator::len(&arc));
+        assert_eq!(3, GainSTMGenerator::len(&arc));
+
+        let device = autd3_driver::autd3_device::AUTD3::default().into_device(0);
+        {
+            let mut stm = FociSTM {
+                foci: arc.clone(),
+                config: SamplingConfig::DIV_10,
+            };
+            let mut iterator = FociSTMIteratorGenerator::generate(stm.deref_mut(), &device);
+            expect.iter().for_each(|e| {
+                let f = FociSTMIterator::<1>::next(&mut iterator).points[0];
+                assert_near_vector3!(e, f.point);
+            });
+            assert!(iterator.next().is_none());
+        }
+        {
+            let mut stm = GainSTM {
+                gains: arc.clone(),
+                config: SamplingConfig::DIV_10,
+                option: GainSTMOption::default(),
+            };
+            let mut iterator = GainSTMIteratorGenerator::generate(stm.deref_mut(), &device);
+            expect.iter().for_each(|e| {
+                let f = GainSTMIterator::next(&mut iterator).unwrap();
+                assert_near_vector3!(e, &f.pos);
+            });
+            assert!(iterator.next().is_none());
+        }
+    }
+
+    #[test]
+    fn arc_single_point() {
+        let mut arc = ArcPath {
+            center: Point3::new(1., 2., 3.),
+            radius: 10.0 * mm,
+            start_angle: 90.0 * deg,
+            end_angle: 180.0 * deg,
+            num_points: 1,
+            rotation: UnitQuaternion::identity(),
+            intensity: EmitIntensity::MAX,
+        };
+        let device = autd3_driver::autd3_device::AUTD3::default().into_device(0);
+        let mut iterator = FociSTMIteratorGenerator::generate(&mut arc, &device);
+        assert_near_vector3!(
+            &Point3::new(1., 2. + 10.0 * mm, 3.),
+            &iterator.next().unwrap()
+        );
+        assert!(iterator.next().is_none());
+    }
+}
@@ -0,0 +1,200 @@
+use autd3_core::{gain::EmitIntensity, modulation::SamplingConfig};
+use autd3_driver::{
+    datagram::{ControlPoint, ControlPoints, FociSTM},
+    defined::{Freq, Hz},
+    error::AUTDDriverError,
+    firmware::fpga::{FOCI_STM_BUF_SIZE_MAX, STM_BUF_SIZE_MIN},
+    geometry::Point3,
+};
+
+type BoundedFociSTM = FociSTM<1, Vec<ControlPoints<1>>, SamplingConfig>;
+
+/// Utility for bounding the displacement of the focus per sample of [`FociSTM`].
+///
+/// Rapid jumps between distant foci produce audible noise.
+/// This utility inserts linearly interpolated points between the given points so that the distance between adjacent samples does not exceed [`max_displacement`].
+/// Since the STM is periodic, the last point is also connected to the first point.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDDriverError> {
+/// let (stm, freq) = BoundedTrajectory {
+///     points: vec![
+///         Point3::new(-10.0 * mm, 0., 150.0 * mm),
+///         Point3::new(10.0 * mm, 0., 150.0 * mm),
+///     ],
+///     max_displacement: 0.125 * mm,
+///     intensity: EmitIntensity::MAX,
+/// }
+/// .into_foci_stm(SamplingConfig::new(4000. * Hz)?)?;
+/// assert_eq!(320, stm.foci.len());
+/// assert_eq!(12.5 * Hz, freq);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`max_displacement`]: BoundedTrajectory::max_displacement
+#[derive(Clone, Debug)]
+pub struct BoundedTrajectory {
+    /// The points of the target trajectory.
+    pub points: Vec<Point3>,
+    /// The maximum displacement of the focus per sample. Must be positive and finite.
+    pub max_displacement: f32,
+    /// The intensity of the emitted ultrasound.
+    pub intensity: EmitIntensity,
+}
+
+impl BoundedTrajectory {
+    fn segments(&self) -> impl Iterator<Item = (&Point3, &Point3)> {
+        self.points.iter().zip(self.points.iter().cycle().skip(1))
+    }
+
+    fn num_divisions(&self, a: &Point3, b: &Point3) -> usize {
+        ((b - a).norm() / self.max_displacement).ceil().max(1.) as usize
+    }
+
+    /// Converts into [`FociSTM`] with the given sampling configuration.
+    ///
+    /// Returns the [`FociSTM`] and its resulting frequency.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`AUTDDriverError::InvalidMaxDisplacement`] if [`max_displacement`] is not positive and finite, or [`AUTDDriverError::FociSTMPointSizeOutOfRange`] if the number of the resulting points is out of range.
+    ///
+    /// [`max_displacement`]: BoundedTrajectory::max_displacement
+    pub fn into_foci_stm(
+        self,
+        config: SamplingConfig,
+    ) -> Result<(BoundedFociSTM, Freq<f32>), AUTDDriverError> {
+        if !(self.max_displacement.is_finite() && self.max_displacement > 0.) {
+            return Err(AUTDDriverError::InvalidMaxDisplacement(
+                self.max_displacement,
+            ));
+        }
+        let size = self.segments().fold(0usize, |acc, (a, b)| {
+            acc.saturating_add(self.num_divisions(a, b))
+        });
+        if !(STM_BUF_SIZE_MIN..=FOCI_STM_BUF_SIZE_MAX).contains(&size) {
+            return Err(AUTDDriverError::FociSTMPointSizeOutOfRange(size));
+        }
+
+        let foci = self
+            .segments()
+            .flat_map(|(a, b)| {
+                let n = self.num_divisions(a, b);
+                (0..n).map(move |i| a + (b - a) * (i as f32 / n as f32))
+            })
+            .map(|p| ControlPoints {
+                points: [ControlPoint::from(p)],
+                intensity: self.intensity,
+            })
+            .collect::<Vec<_>>();
+        let freq = config.freq().hz() / size as f32 * Hz;
+
+        Ok((FociSTM { foci, config }, freq))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::defined::mm;
+
+    use crate::assert_near_vector3;
+
+    use super::*;
+
+    #[test]
+    fn bounded_trajectory() -> anyhow::Result<()> {
+        let points = vec![
+            Point3::new(0., 0., 0.),
+            Point3::new(3.0 * mm, 0., 0.),
+            Point3::new(3.0 * mm, 0.5 * mm, 0.),
+        ];
+        let (stm, freq) = BoundedTrajectory {
+            points,
+            max_displacement: 1.0 * mm,
+            intensity: EmitIntensity(0x80),
+        }
+        .into_foci_stm(SamplingConfig::new(10).unwrap())?;
+
+        let expect = [
+            Point3::new(0., 0., 0.),
+            Point3::new(1.0 * mm, 0., 0.),
+            Point3::new(2.0 * mm, 0., 0.),
+            Point3::new(3.0 * mm, 0., 0.),
+            Point3::new(3.0 * mm, 0.5 * mm, 0.),
+            Point3::new(2.25 * mm, 0.375 * mm, 0.),
+            Point3::new(1.5 * mm, 0.25 * mm, 0.),
+            Point3::new(0.75 * mm, 0.125 * mm, 0.),
+        ];
+        assert_eq!(expect.len(), stm.foci.len());
+        expect.iter().zip(stm.foci.iter()).for_each(|(e, f)| {
+            assert_near_vector3!(e, &f.points[0].point);
+            assert_eq!(EmitIntensity(0x80), f.intensity);
+        });
+        assert_eq!(SamplingConfig::new(10).unwrap(), stm.config);
+        assert_eq!(500. * Hz, freq);
+
+        stm.foci
+            .iter()
+            .zip(stm.foci.iter().cycle().skip(1))
+            .for_each(|(a, b)| {
+                assert!((b.points[0].point - a.points[0].point).norm() <= 1.0 * mm + 1e-3);
+            });
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(AUTDDriverError::FociSTMPointSizeOutOfRange(1), vec![Point3::origin()], 1.0 * mm)]
+    #[case(AUTDDriverError::FociSTMPointSizeOutOfRange(0), vec![], 1.0 * mm)]
+    #[case(
+        AUTDDriverError::FociSTMPointSizeOutOfRange(32768),
+        vec![Point3::origin(), Point3::new(1024., 0., 0.)],
+        0.0625
+    )]
+    fn bounded_trajectory_out_of_range(
+        #[case] expect: AUTDDriverError,
+        #[case] points: Vec<Point3>,
+        #[case] max_displacement: f32,
+    ) {
+        assert_eq!(
+            Some(expect),
+            BoundedTrajectory {
+                points,
+                max_displacement,
+                intensity: EmitIntensity::MAX,
+            }
+            .into_foci_stm(SamplingConfig::new(10).unwrap())
+            .err()
+        );
+    }
+
+    #[rstest::rstest]
+    #[case("Maximum displacement (0) must be positive and finite", 0.)]
+    #[case("Maximum displacement (-1) must be positive and finite", -1.)]
+    #[case("Maximum displacement (NaN) must be positive and finite", f32::NAN)]
+    #[case(
+        "Maximum displacement (inf) must be positive and finite",
+        f32::INFINITY
+    )]
+    #[test]
+    fn bounded_trajectory_invalid_max_displacement(
+        #[case] expect: &str,
+        #[case] max_displacement: f32,
+    ) {
+        let err = BoundedTrajectory {
+            points: vec![Point3::origin(), Point3::new(1.0 * mm, 0., 0.)],
+            max_displacement,
+            intensity: EmitIntensity::MAX,
+        }
+        .into_foci_stm(SamplingConfig::new(10).unwrap())
+        .unwrap_err();
+        assert!(matches!(err, AUTDDriverError::InvalidMaxDisplacement(_)));
+        assert_eq!(expect, err.to_string());
+    }
+}
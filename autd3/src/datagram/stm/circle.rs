@@ -13,8 +13,6 @@ use autd3_driver::{
     geometry::{Point3, UnitVector3, Vector3},
 };
 
-use crate::gain::Focus;
-
 /// Utility for generating a circular trajectory STM.
 ///
 /// # Examples
@@ -87,6 +85,7 @@ impl GainSTMIterator for CircleSTMIterator {
             intensity: self.intensity,
             phase_offset: Phase::ZERO,
             wavenumber: self.wavenumber,
+            compensation: None,
         })
     }
 }
@@ -116,7 +115,7 @@ impl FociSTMIteratorGenerator<1> for Circle {
 }
 
 impl GainSTMIteratorGenerator for Circle {
-    type Gain = Focus;
+    type Gain = crate::gain::focus::Generator;
     type Iterator = CircleSTMIterator;
 
     fn generate(&mut self, device: &Device) -> Self::Iterator {
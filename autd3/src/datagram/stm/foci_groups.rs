@@ -0,0 +1,223 @@
+use autd3_core::derive::Geometry;
+use autd3_driver::{
+    datagram::{ControlPoints, PerDeviceFoci},
+    error::AUTDDriverError,
+    firmware::version::FirmwareVersion,
+};
+
+use crate::error::AUTDError;
+
+/// A path of [`FociSTM`] with more foci per point than the firmware supports.
+///
+/// The firmware outputs at most [`max_foci_num`] foci per point. [`FociGroups::decompose`] splits the `M` foci of each point into groups of `N` foci and assigns the groups to the enabled devices in turn, so that all foci are output by the devices together.
+/// If `M` is not a multiple of `N`, the last group is filled up by repeating its own foci.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default(), AUTD3::default()], Nop::new())?;
+/// let firmware = autd.firmware_version()?;
+///
+/// let foci = FociGroups {
+///     foci: (0..100)
+///         .map(|i| {
+///             let z = 150. * mm + i as f32;
+///             ControlPoints::from(std::array::from_fn::<_, 16, _>(|j| {
+///                 Point3::new(j as f32 * 10. * mm, 0., z)
+///             }))
+///         })
+///         .collect(),
+/// }
+/// .decompose::<8>(&autd, &firmware)?;
+/// autd.send(FociSTM {
+///     foci,
+///     config: 1. * Hz,
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`FociSTM`]: autd3_driver::datagram::FociSTM
+/// [`max_foci_num`]: autd3_driver::firmware::version::FirmwareCapabilities::max_foci_num
+#[derive(Clone, Debug, PartialEq)]
+pub struct FociGroups<const M: usize> {
+    /// The sequence of foci.
+    pub foci: Vec<ControlPoints<M>>,
+}
+
+impl<const M: usize> FociGroups<M> {
+    /// Decomposes the foci into groups of `N` foci for each enabled device.
+    ///
+    /// `firmware` is the firmware versions of the devices obtained by [`Controller::firmware_version`].
+    /// Returns [`AUTDError::FociNumNotSupported`] if `N` exceeds the limit of the firmware of any enabled device, and [`AUTDError::InsufficientDevicesForFoci`] if the enabled devices are fewer than the groups.
+    ///
+    /// [`Controller::firmware_version`]: crate::controller::Controller::firmware_version
+    pub fn decompose<const N: usize>(
+        self,
+        geometry: &Geometry,
+        firmware: &[FirmwareVersion],
+    ) -> Result<PerDeviceFoci<ControlPoints<N>>, AUTDError> {
+        if M == 0 {
+            return Err(AUTDDriverError::FociSTMNumFociOutOfRange(M).into());
+        }
+        let devices = geometry.devices().collect::<Vec<_>>();
+        devices.iter().try_for_each(|dev| {
+            let max = firmware
+                .get(dev.idx())
+                .and_then(|f| f.caps().max_foci_num())
+                .unwrap_or(0);
+            if N == 0 || N > max {
+                return Err(AUTDError::FociNumNotSupported(dev.idx(), N, max));
+            }
+            Ok(())
+        })?;
+
+        let groups = M.div_ceil(N);
+        if devices.len() < groups {
+            return Err(AUTDError::InsufficientDevicesForFoci(groups, devices.len()));
+        }
+
+        Ok(PerDeviceFoci {
+            foci: devices
+                .iter()
+                .enumerate()
+                .map(|(i, dev)| {
+                    let start = (i % groups) * N;
+                    let len = N.min(M - start);
+                    (
+                        dev.idx(),
+                        self.foci
+                            .iter()
+                            .map(|p| ControlPoints {
+                                points: std::array::from_fn(|j| p.points[start + j % len]),
+                                intensity: p.intensity,
+                            })
+                            .collect(),
+                    )
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{
+        datagram::ControlPoint,
+        firmware::{
+            fpga::EmitIntensity,
+            version::{CPUVersion, FPGAVersion, Major, Minor},
+        },
+        geometry::Point3,
+    };
+
+    use crate::tests::create_geometry;
+
+    use super::*;
+
+    fn path<const M: usize>() -> FociGroups<M> {
+        FociGroups {
+            foci: (0..2)
+                .map(|i| ControlPoints {
+                    points: std::array::from_fn(|j| {
+                        ControlPoint::from(Point3::new(j as f32, i as f32, 0.))
+                    }),
+                    intensity: EmitIntensity(0x80),
+                })
+                .collect(),
+        }
+    }
+
+    fn firmware(major: u8, n: usize) -> Vec<FirmwareVersion> {
+        (0..n)
+            .map(|idx| FirmwareVersion {
+                idx,
+                cpu: CPUVersion {
+                    major: Major(major),
+                    minor: Minor(0),
+                },
+                fpga: FPGAVersion {
+                    major: Major(major),
+                    minor: Minor(0),
+                    function_bits: 0,
+                },
+            })
+            .collect()
+    }
+
+    fn latest(n: usize) -> Vec<FirmwareVersion> {
+        firmware(FirmwareVersion::LATEST_VERSION_NUM_MAJOR.0, n)
+    }
+
+    #[test]
+    fn decompose() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(4);
+        geometry[3].enable = false;
+
+        let foci = path::<5>().decompose::<2>(&geometry, &latest(4))?.foci;
+
+        assert_eq!(3, foci.len());
+        [[0., 1.], [2., 3.], [4., 4.]]
+            .into_iter()
+            .enumerate()
+            .for_each(|(dev, expect)| {
+                assert_eq!(2, foci[&dev].len());
+                foci[&dev].iter().enumerate().for_each(|(i, p)| {
+                    assert_eq!(EmitIntensity(0x80), p.intensity);
+                    assert_eq!(expect, p.points.map(|c| c.point.x));
+                    assert!(p.points.iter().all(|c| c.point.y == i as f32));
+                });
+            });
+
+        Ok(())
+    }
+
+    #[test]
+    fn decompose_cycle() -> anyhow::Result<()> {
+        let geometry = create_geometry(3);
+
+        let foci = path::<2>().decompose::<1>(&geometry, &latest(3))?.foci;
+
+        assert_eq!(0., foci[&0][0].points[0].point.x);
+        assert_eq!(1., foci[&1][0].points[0].point.x);
+        assert_eq!(0., foci[&2][0].points[0].point.x);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decompose_insufficient_devices() {
+        let geometry = create_geometry(2);
+        assert_eq!(
+            Err(AUTDError::InsufficientDevicesForFoci(3, 2)),
+            path::<5>().decompose::<2>(&geometry, &latest(2))
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(Err(AUTDError::FociNumNotSupported(0, 9, 8)), latest(1))]
+    #[case(Err(AUTDError::FociNumNotSupported(0, 9, 1)), firmware(0x8F, 1))]
+    #[case(Err(AUTDError::FociNumNotSupported(0, 9, 0)), vec![])]
+    #[test]
+    fn decompose_not_supported(
+        #[case] expect: Result<PerDeviceFoci<ControlPoints<9>>, AUTDError>,
+        #[case] firmware: Vec<FirmwareVersion>,
+    ) {
+        let geometry = create_geometry(1);
+        assert_eq!(expect, path::<18>().decompose::<9>(&geometry, &firmware));
+    }
+
+    #[test]
+    fn decompose_empty() {
+        let geometry = create_geometry(1);
+        assert_eq!(
+            Err(AUTDError::Driver(
+                AUTDDriverError::FociSTMNumFociOutOfRange(0)
+            )),
+            path::<0>().decompose::<1>(&geometry, &latest(1))
+        );
+    }
+}
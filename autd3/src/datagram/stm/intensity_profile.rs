@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use autd3_core::{derive::Device, gain::EmitIntensity};
+use autd3_driver::{
+    datagram::{ControlPoints, FociSTMGenerator, FociSTMIterator, FociSTMIteratorGenerator},
+    error::AUTDDriverError,
+};
+
+use derive_more::Debug;
+use derive_new::new;
+
+/// Adapter to modulate the intensity of [`FociSTM`] along the trajectory.
+///
+/// The intensity of the `i`-th [`ControlPoints`] is replaced by `profile(i)`.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// let envelope = [EmitIntensity::MIN, EmitIntensity(0x80), EmitIntensity::MAX, EmitIntensity(0x80)];
+/// FociSTM {
+///     config: 1.0 * Hz,
+///     foci: IntensityProfile {
+///         foci: Circle {
+///             center: Point3::origin(),
+///             radius: 30.0 * mm,
+///             num_points: envelope.len(),
+///             n: Vector3::z_axis(),
+///             intensity: EmitIntensity::MAX,
+///         },
+///         profile: move |i| envelope[i],
+///     },
+/// };
+/// ```
+///
+/// [`FociSTM`]: autd3_driver::datagram::FociSTM
+#[derive(Debug, new)]
+pub struct IntensityProfile<const N: usize, G, F>
+where
+    G: FociSTMGenerator<N>,
+    F: Fn(usize) -> EmitIntensity + Send + Sync,
+{
+    /// The original foci sequence.
+    pub foci: G,
+    /// The intensity profile along the sequence.
+    #[debug(ignore)]
+    pub profile: F,
+}
+
+#[derive(Debug)]
+pub struct IntensityProfileGenerator<const N: usize, G, F>
+where
+    G: FociSTMIteratorGenerator<N>,
+    F: Fn(usize) -> EmitIntensity + Send + Sync,
+{
+    generator: G,
+    #[debug(ignore)]
+    profile: Arc<F>,
+}
+
+pub struct IntensityProfileIterator<const N: usize, I, F>
+where
+    I: FociSTMIterator<N>,
+    F: Fn(usize) -> EmitIntensity + Send + Sync,
+{
+    iterator: I,
+    profile: Arc<F>,
+    i: usize,
+}
+
+impl<const N: usize, I, F> FociSTMIterator<N> for IntensityProfileIterator<N, I, F>
+where
+    I: FociSTMIterator<N>,
+    F: Fn(usize) -> EmitIntensity + Send + Sync,
+{
+    fn next(&mut self) -> ControlPoints<N> {
+        let p = self.iterator.next();
+        let intensity = (self.profile)(self.i);
+        self.i += 1;
+        ControlPoints { intensity, ..p }
+    }
+}
+
+impl<const N: usize, G, F> FociSTMIteratorGenerator<N> for IntensityProfileGenerator<N, G, F>
+where
+    G: FociSTMIteratorGenerator<N>,
+    F: Fn(usize) -> EmitIntensity + Send + Sync,
+{
+    type Iterator = IntensityProfileIterator<N, G::Iterator, F>;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        Self::Iterator {
+            iterator: self.generator.generate(device),
+            profile: self.profile.clone(),
+            i: 0,
+        }
+    }
+}
+
+impl<const N: usize, G, F> FociSTMGenerator<N> for IntensityProfile<N, G, F>
+where
+    G: FociSTMGenerator<N>,
+    F: Fn(usize) -> EmitIntensity + Send + Sync,
+{
+    type T = IntensityProfileGenerator<N, G::T, F>;
+
+    fn init(self) -> Result<Self::T, AUTDDriverError> {
+        Ok(IntensityProfileGenerator {
+            generator: self.foci.init()?,
+            profile: Arc::new(self.profile),
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.foci.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{
+        datagram::ControlPoint,
+        geometry::{IntoDevice, Point3},
+    };
+
+    use super::*;
+
+    #[test]
+    fn intensity_profile() -> anyhow::Result<()> {
+        let foci = (0..4)
+            .map(|i| ControlPoints {
+                points: [ControlPoint::from(Point3::new(i as f32, 0., 0.))],
+                intensity: EmitIntensity::MAX,
+            })
+            .collect::<Vec<_>>();
+        let envelope = [0x00, 0x40, 0x80, 0xFF].map(EmitIntensity);
+        let profile = IntensityProfile {
+            foci: foci.clone(),
+            profile: move |i| envelope[i],
+        };
+        assert_eq!(4, profile.len());
+
+        let device = autd3_driver::autd3_device::AUTD3::default().into_device(0);
+        let mut g = profile.init()?;
+        let mut iterator = g.generate(&device);
+        foci.iter().zip(envelope.iter()).for_each(|(f, e)| {
+            let p = iterator.next();
+            assert_eq!(f.points, p.points);
+            assert_eq!(*e, p.intensity);
+        });
+
+        Ok(())
+    }
+}
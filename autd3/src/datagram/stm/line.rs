@@ -11,8 +11,6 @@ use autd3_driver::{
     geometry::{Point3, Vector3},
 };
 
-use crate::gain::Focus;
-
 /// Utility for generating a line STM.
 ///
 /// # Examples
@@ -80,6 +78,7 @@ impl GainSTMIterator for LineSTMIterator {
             intensity: self.intensity,
             phase_offset: Phase::ZERO,
             wavenumber: self.wavenumber,
+            compensation: None,
         })
     }
 }
@@ -100,7 +99,7 @@ impl FociSTMIteratorGenerator<1> for Line {
 }
 
 impl GainSTMIteratorGenerator for Line {
-    type Gain = Focus;
+    type Gain = crate::gain::focus::Generator;
     type Iterator = LineSTMIterator;
 
     fn generate(&mut self, device: &Device) -> Self::Iterator {
@@ -0,0 +1,281 @@
+use std::{collections::HashMap, f32::consts::PI};
+
+use autd3_core::{
+    derive::{Device, Geometry},
+    gain::{BitVec, EmitIntensity, GainError, Phase},
+};
+use autd3_driver::{
+    datagram::{
+        ControlPoint, ControlPoints, FociSTMGenerator, FociSTMIterator, FociSTMIteratorGenerator,
+        GainSTMGenerator, GainSTMIterator, GainSTMIteratorGenerator,
+    },
+    defined::Angle,
+    error::AUTDDriverError,
+    geometry::{Point3, UnitQuaternion, Vector3},
+};
+
+/// Utility for generating a Lissajous trajectory STM.
+///
+/// The points are given by `(amplitude_x * sin(a * t + delta), amplitude_y * sin(b * t), 0)` for `t` in `[0, 2π)`.
+/// The figure is defined on the local xy-plane and then rotated by [`rotation`] around [`center`].
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// FociSTM {
+///     config: 1.0 * Hz,
+///     foci: Lissajous {
+///         center: Point3::origin(),
+///         amplitude_x: 30.0 * mm,
+///         amplitude_y: 30.0 * mm,
+///         a: 3,
+///         b: 2,
+///         delta: 90.0 * deg,
+///         num_points: 200,
+///         rotation: UnitQuaternion::identity(),
+///         intensity: EmitIntensity::MAX,
+///     },
+/// };
+/// ```
+///
+/// [`rotation`]: Lissajous::rotation
+/// [`center`]: Lissajous::center
+#[derive(Clone, Debug)]
+pub struct Lissajous {
+    /// The center of the figure.
+    pub center: Point3,
+    /// The amplitude along the local x-axis.
+    pub amplitude_x: f32,
+    /// The amplitude along the local y-axis.
+    pub amplitude_y: f32,
+    /// The frequency ratio along the local x-axis.
+    pub a: u32,
+    /// The frequency ratio along the local y-axis.
+    pub b: u32,
+    /// The phase difference of the local x-axis.
+    pub delta: Angle,
+    /// The number of points on the figure.
+    pub num_points: usize,
+    /// The rotation of the figure plane.
+    pub rotation: UnitQuaternion,
+    /// The intensity of the emitted ultrasound.
+    pub intensity: EmitIntensity,
+}
+
+pub struct LissajousSTMIterator {
+    center: Point3,
+    amplitude_x: f32,
+    amplitude_y: f32,
+    a: f32,
+    b: f32,
+    delta: f32,
+    num_points: usize,
+    rotation: UnitQuaternion,
+    wavenumber: f32,
+    intensity: EmitIntensity,
+    i: usize,
+}
+
+impl LissajousSTMIterator {
+    fn next(&mut self) -> Option<Point3> {
+        if self.i >= self.num_points {
+            return None;
+        }
+        let t = 2.0 * PI * self.i as f32 / self.num_points as f32;
+        self.i += 1;
+        Some(
+            self.center
+                + self.rotation
+                    * Vector3::new(
+                        self.amplitude_x * (self.a * t + self.delta).sin(),
+                        self.amplitude_y * (self.b * t).sin(),
+                        0.,
+                    ),
+        )
+    }
+}
+impl FociSTMIterator<1> for LissajousSTMIterator {
+    fn next(&mut self) -> ControlPoints<1> {
+        ControlPoints {
+            points: [ControlPoint::from(self.next().unwrap())],
+            intensity: self.intensity,
+        }
+    }
+}
+
+impl GainSTMIterator for LissajousSTMIterator {
+    type Calculator = crate::gain::focus::Impl;
+
+    fn next(&mut self) -> Option<Self::Calculator> {
+        Some(Self::Calculator {
+            pos: self.next()?,
+            intensity: self.intensity,
+            phase_offset: Phase::ZERO,
+            wavenumber: self.wavenumber,
+            compensation: None,
+        })
+    }
+}
+
+impl FociSTMIteratorGenerator<1> for Lissajous {
+    type Iterator = LissajousSTMIterator;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        Self::Iterator {
+            center: self.center,
+            amplitude_x: self.amplitude_x,
+            amplitude_y: self.amplitude_y,
+            a: self.a as f32,
+            b: self.b as f32,
+            delta: self.delta.radian(),
+            num_points: self.num_points,
+            rotation: self.rotation,
+            wavenumber: device.wavenumber(),
+            intensity: self.intensity,
+            i: 0,
+        }
+    }
+}
+
+impl GainSTMIteratorGenerator for Lissajous {
+    type Gain = crate::gain::focus::Generator;
+    type Iterator = LissajousSTMIterator;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        FociSTMIteratorGenerator::<1>::generate(self, device)
+    }
+}
+
+impl FociSTMGenerator<1> for Lissajous {
+    type T = Self;
+
+    // GRCOV_EXCL_START
+    fn init(self) -> Result<Self::T, AUTDDriverError> {
+        Ok(self)
+    }
+    // GRCOV_EXCL_STOP
+
+    fn len(&self) -> usize {
+        self.num_points
+    }
+}
+
+impl GainSTMGenerator for Lissajous {
+    type T = Self;
+
+    // GRCOV_EXCL_START
+    fn init(
+        self,
+        _: &Geometry,
+        _filter: Option<&HashMap<usize, BitVec>>,
+        _: bool,
+    ) -> Result<Self::T, GainError> {
+        Ok(self)
+    }
+    // GRCOV_EXCL_STOP
+
+    fn len(&self) -> usize {
+        self.num_points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::DerefMut;
+
+    use autd3_core::modulation::SamplingConfig;
+    use autd3_driver::{
+        datagram::{FociSTM, GainSTM, GainSTMOption},
+        defined::{deg, mm},
+        geometry::IntoDevice,
+    };
+
+    use crate::assert_near_vector3;
+
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(
+        vec![
+            Point3::new(0., 0., 0.),
+            Point3::new(30.0 * mm, 20.0 * mm, 0.),
+            Point3::new(0., 0., 0.),
+            Point3::new(-30.0 * mm, -20.0 * mm, 0.),
+        ],
+        1,
+        1,
+        0.0 * deg
+    )]
+    #[case(
+        vec![
+            Point3::new(30.0 * mm, 0., 0.),
+            Point3::new(-30.0 * mm, 0., 0.),
+            Point3::new(30.0 * mm, 0., 0.),
+            Point3::new(-30.0 * mm, 0., 0.),
+        ],
+        2,
+        2,
+        90.0 * deg
+    )]
+    #[case(
+        vec![
+            Point3::new(30.0 * mm, 0., 0.),
+            Point3::new(0., 20.0 * mm, 0.),
+            Point3::new(-30.0 * mm, 0., 0.),
+            Point3::new(0., -20.0 * mm, 0.),
+        ],
+        1,
+        1,
+        90.0 * deg
+    )]
+    #[test]
+    fn lissajous(
+        #[case] expect: Vec<Point3>,
+        #[case] a: u32,
+        #[case] b: u32,
+        #[case] delta: Angle,
+    ) {
+        let lissajous = Lissajous {
+            center: Point3::origin(),
+            amplitude_x: 30.0 * mm,
+            amplitude_y: 20.0 * mm,
+            a,
+            b,
+            delta,
+            num_points: 4,
+            rotation: UnitQuaternion::identity(),
+            intensity: EmitIntensity::MAX,
+        };
+        assert_eq!(4, FociSTMGenerator::len(&lissajous));
+        assert_eq!(4, GainSTMGenerator::len(&lissajous));
+
+        let device = autd3_driver::autd3_device::AUTD3::default().into_device(0);
+        {
+            let mut stm = FociSTM {
+                foci: lissajous.clone(),
+                config: SamplingConfig::DIV_10,
+            };
+            let mut iterator = FociSTMIteratorGenerator::generate(stm.deref_mut(), &device);
+            expect.iter().for_each(|e| {
+                let f = FociSTMIterator::<1>::next(&mut iterator).points[0];
+                assert_near_vector3!(e, f.point);
+            });
+            assert!(iterator.next().is_none());
+        }
+        {
+            let mut stm = GainSTM {
+                gains: lissajous.clone(),
+                config: SamplingConfig::DIV_10,
+                option: GainSTMOption::default(),
+            };
+            let mut iterator = GainSTMIteratorGenerator::generate(stm.deref_mut(), &device);
+            expect.iter().for_each(|e| {
+                let f = GainSTMIterator::next(&mut iterator).unwrap();
+                assert_near_vector3!(e, &f.pos);
+            });
+            assert!(iterator.next().is_none());
+        }
+    }
+}
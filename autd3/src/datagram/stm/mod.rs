@@ -1,5 +1,25 @@
+mod arc;
+mod bounded;
 mod circle;
+mod foci_groups;
+mod intensity_profile;
 mod line;
+mod lissajous;
+mod moving_focus;
+mod polyline;
+mod precomputed;
+mod raster;
+mod spiral;
 
+pub use arc::ArcPath;
+pub use bounded::BoundedTrajectory;
 pub use circle::Circle;
+pub use foci_groups::FociGroups;
+pub use intensity_profile::IntensityProfile;
 pub use line::Line;
+pub use lissajous::Lissajous;
+pub use moving_focus::{MovingFocus, MovingPoint};
+pub use polyline::Polyline;
+pub use precomputed::{CancellationToken, PrecomputedGains};
+pub use raster::{LineOrder, Raster};
+pub use spiral::Spiral;
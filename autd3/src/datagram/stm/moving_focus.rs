@@ -0,0 +1,309 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use autd3_core::{
+    derive::{Device, Geometry, Transducer},
+    gain::{
+        BitVec, Drive, EmitIntensity, GainCalculator, GainCalculatorGenerator, GainError, Phase,
+    },
+};
+use autd3_driver::{
+    datagram::{GainSTMGenerator, GainSTMIterator, GainSTMIteratorGenerator},
+    defined::rad,
+    geometry::{Point3, Vector3},
+};
+
+/// A sample of [`MovingFocus`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MovingPoint {
+    /// The position of the focus.
+    pub pos: Point3,
+    /// The velocity of the focus per second.
+    pub velocity: Vector3,
+}
+
+/// Utility for generating a [`GainSTM`] of a fast-moving focus with Doppler compensation.
+///
+/// The ultrasound emitted by a transducer takes `d / c` to reach the focus, where `d` is the distance to the focus and `c` is the sound speed, and the focus moves during this time.
+/// If [`doppler_compensation`] is `true`, each transducer is focused on `pos + velocity * d / c` instead of `pos`, i.e., the first-order compensation of the motion, which reduces the blurring of fast strokes.
+/// Because the compensation differs for each transducer, this can be used only with [`GainSTM`].
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+/// use std::time::Duration;
+///
+/// let points = (0..100).map(|i| {
+///     let theta = 2. * PI * i as f32 / 100.;
+///     Point3::new(30. * mm * theta.cos(), 30. * mm * theta.sin(), 150. * mm)
+/// });
+/// GainSTM {
+///     gains: MovingFocus::from_closed_trajectory(points, Duration::from_micros(100), EmitIntensity::MAX),
+///     config: SamplingConfig::new(Duration::from_micros(100)).unwrap(),
+///     option: GainSTMOption::default(),
+/// };
+/// ```
+///
+/// [`GainSTM`]: autd3_driver::datagram::GainSTM
+/// [`doppler_compensation`]: MovingFocus::doppler_compensation
+#[derive(Clone, Debug)]
+pub struct MovingFocus {
+    /// The samples of the trajectory.
+    pub points: Vec<MovingPoint>,
+    /// The intensity of the emitted ultrasound.
+    pub intensity: EmitIntensity,
+    /// If `true`, the motion of the focus during the propagation is compensated.
+    pub doppler_compensation: bool,
+}
+
+impl MovingFocus {
+    /// Creates a [`MovingFocus`] with the Doppler compensation from the positions on a closed trajectory sampled every `sampling_period`.
+    ///
+    /// The velocities are estimated by the central difference, where the last position is followed by the first one.
+    pub fn from_closed_trajectory(
+        points: impl IntoIterator<Item = Point3>,
+        sampling_period: Duration,
+        intensity: EmitIntensity,
+    ) -> Self {
+        let points = points.into_iter().collect::<Vec<_>>();
+        let n = points.len();
+        let dt = 2. * sampling_period.as_secs_f32();
+        Self {
+            points: points
+                .iter()
+                .enumerate()
+                .map(|(i, &pos)| MovingPoint {
+                    pos,
+                    velocity: if dt == 0. {
+                        Vector3::zeros()
+                    } else {
+                        (points[(i + 1) % n] - points[(i + n - 1) % n]) / dt
+                    },
+                })
+                .collect(),
+            intensity,
+            doppler_compensation: true,
+        }
+    }
+}
+
+pub struct Impl {
+    point: MovingPoint,
+    intensity: EmitIntensity,
+    wavenumber: f32,
+    sound_speed: Option<f32>,
+}
+
+impl GainCalculator for Impl {
+    fn calc(&self, tr: &Transducer) -> Drive {
+        let d = (self.point.pos - tr.position()).norm();
+        let pos = match self.sound_speed {
+            Some(c) => self.point.pos + self.point.velocity * (d / c),
+            None => self.point.pos,
+        };
+        Drive {
+            phase: Phase::from(-(pos - tr.position()).norm() * self.wavenumber * rad),
+            intensity: self.intensity,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MovingFocusGain {
+    point: MovingPoint,
+    intensity: EmitIntensity,
+    doppler_compensation: bool,
+}
+
+impl GainCalculatorGenerator for MovingFocusGain {
+    type Calculator = Impl;
+
+    fn generate(&mut self, device: &Device) -> Self::Calculator {
+        Impl {
+            point: self.point,
+            intensity: self.intensity,
+            wavenumber: device.wavenumber(),
+            sound_speed: self.doppler_compensation.then_some(device.sound_speed),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MovingFocusSTMGenerator {
+    points: Arc<Vec<MovingPoint>>,
+    intensity: EmitIntensity,
+    doppler_compensation: bool,
+}
+
+pub struct MovingFocusSTMIterator {
+    points: Arc<Vec<MovingPoint>>,
+    intensity: EmitIntensity,
+    wavenumber: f32,
+    sound_speed: Option<f32>,
+    i: usize,
+}
+
+impl GainSTMIterator for MovingFocusSTMIterator {
+    type Calculator = Impl;
+
+    fn next(&mut self) -> Option<Self::Calculator> {
+        let point = self.points.get(self.i).copied()?;
+        self.i += 1;
+        Some(Impl {
+            point,
+            intensity: self.intensity,
+            wavenumber: self.wavenumber,
+            sound_speed: self.sound_speed,
+        })
+    }
+}
+
+impl GainSTMIteratorGenerator for MovingFocusSTMGenerator {
+    type Gain = MovingFocusGain;
+    type Iterator = MovingFocusSTMIterator;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        Self::Iterator {
+            points: self.points.clone(),
+            intensity: self.intensity,
+            wavenumber: device.wavenumber(),
+            sound_speed: self.doppler_compensation.then_some(device.sound_speed),
+            i: 0,
+        }
+    }
+}
+
+impl GainSTMGenerator for MovingFocus {
+    type T = MovingFocusSTMGenerator;
+
+    fn init(
+        self,
+        _: &Geometry,
+        _filter: Option<&HashMap<usize, BitVec>>,
+        _: bool,
+    ) -> Result<Self::T, GainError> {
+        Ok(MovingFocusSTMGenerator {
+            points: Arc::new(self.points),
+            intensity: self.intensity,
+            doppler_compensation: self.doppler_compensation,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.points.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{
+        autd3_device::AUTD3,
+        defined::{mm, PI},
+        geometry::IntoDevice,
+    };
+
+    use crate::assert_near_vector3;
+
+    use super::*;
+
+    #[test]
+    fn from_closed_trajectory() {
+        let m = MovingFocus::from_closed_trajectory(
+            [
+                Point3::new(0., 0., 0.),
+                Point3::new(1. * mm, 0., 0.),
+                Point3::new(1. * mm, 1. * mm, 0.),
+                Point3::new(0., 1. * mm, 0.),
+            ],
+            Duration::from_millis(1),
+            EmitIntensity::MAX,
+        );
+        assert!(m.doppler_compensation);
+        assert_eq!(4, m.points.len());
+        assert_near_vector3!(
+            &Vector3::new(500. * mm, -500. * mm, 0.),
+            &m.points[0].velocity
+        );
+        assert_near_vector3!(
+            &Vector3::new(500. * mm, 500. * mm, 0.),
+            &m.points[1].velocity
+        );
+
+        let m = MovingFocus::from_closed_trajectory(
+            [Point3::origin()],
+            Duration::ZERO,
+            EmitIntensity::MAX,
+        );
+        assert_eq!(Vector3::zeros(), m.points[0].velocity);
+    }
+
+    #[rstest::rstest]
+    #[case(false)]
+    #[case(true)]
+    #[test]
+    fn moving_focus(#[case] doppler_compensation: bool) -> anyhow::Result<()> {
+        let geometry = Geometry::new(vec![AUTD3::default().into_device(0)]);
+        let points = (0..4)
+            .map(|i| {
+                let theta = 2. * PI * i as f32 / 4.;
+                MovingPoint {
+                    pos: Point3::new(30. * mm * theta.cos(), 30. * mm * theta.sin(), 150. * mm),
+                    velocity: Vector3::new(-theta.sin(), theta.cos(), 0.) * 10000. * mm,
+                }
+            })
+            .collect::<Vec<_>>();
+        let m = MovingFocus {
+            points: points.clone(),
+            intensity: EmitIntensity(0x80),
+            doppler_compensation,
+        };
+        assert_eq!(4, GainSTMGenerator::len(&m));
+
+        let dev = &geometry[0];
+        let mut g = GainSTMGenerator::init(m, &geometry, None, false)?;
+        let mut iterator = GainSTMIteratorGenerator::generate(&mut g, dev);
+        points.iter().for_each(|p| {
+            let calc = iterator.next().unwrap();
+            dev.iter().for_each(|tr| {
+                let d = (p.pos - tr.position()).norm();
+                let pos = if doppler_compensation {
+                    p.pos + p.velocity * d / dev.sound_speed
+                } else {
+                    p.pos
+                };
+                assert_eq!(
+                    Drive {
+                        phase: Phase::from(-(pos - tr.position()).norm() * dev.wavenumber() * rad),
+                        intensity: EmitIntensity(0x80),
+                    },
+                    calc.calc(tr)
+                );
+            });
+        });
+        assert!(iterator.next().is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn doppler_compensation_changes_phase() {
+        let geometry = Geometry::new(vec![AUTD3::default().into_device(0)]);
+        let point = MovingPoint {
+            pos: Point3::new(0., 0., 150. * mm),
+            velocity: Vector3::new(10000. * mm, 0., 0.),
+        };
+        let drives = |doppler_compensation| {
+            let mut g = MovingFocusGain {
+                point,
+                intensity: EmitIntensity::MAX,
+                doppler_compensation,
+            };
+            let calc = g.generate(&geometry[0]);
+            geometry[0]
+                .iter()
+                .map(|tr| calc.calc(tr))
+                .collect::<Vec<_>>()
+        };
+        assert_ne!(drives(false), drives(true));
+    }
+}
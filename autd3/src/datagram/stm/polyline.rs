@@ -0,0 +1,416 @@
+use std::{collections::HashMap, sync::Arc};
+
+use autd3_core::{
+    derive::{Device, Geometry},
+    gain::{BitVec, EmitIntensity, GainError, Phase},
+};
+use autd3_driver::{
+    datagram::{
+        ControlPoint, ControlPoints, FociSTMGenerator, FociSTMIterator, FociSTMIteratorGenerator,
+        GainSTMGenerator, GainSTMIterator, GainSTMIteratorGenerator,
+    },
+    error::AUTDDriverError,
+    firmware::fpga::{FOCI_STM_BUF_SIZE_MAX, STM_BUF_SIZE_MIN},
+    geometry::{Point2, Point3, UnitQuaternion, Vector3},
+};
+
+/// Utility for generating a polyline trajectory STM.
+///
+/// The vertices are given on the local xy-plane, which is rotated by [`rotation`] and translated to [`center`].
+/// The path is sampled at equal intervals along its length, where the interval is the largest value less than or equal to [`resolution`] that divides the path evenly.
+/// This can be used to draw arbitrary shapes, e.g., vertices taken from an SVG polyline.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// FociSTM {
+///     config: 1.0 * Hz,
+///     foci: Polyline {
+///         points: vec![
+///             Point2::new(-10.0 * mm, -10.0 * mm),
+///             Point2::new(10.0 * mm, -10.0 * mm),
+///             Point2::new(0., 10.0 * mm),
+///         ],
+///         closed: true,
+///         resolution: 1.0 * mm,
+///         center: Point3::new(0., 0., 150.0 * mm),
+///         rotation: UnitQuaternion::identity(),
+///         intensity: EmitIntensity::MAX,
+///     },
+/// };
+/// ```
+///
+/// [`rotation`]: Polyline::rotation
+/// [`center`]: Polyline::center
+/// [`resolution`]: Polyline::resolution
+#[derive(Clone, Debug)]
+pub struct Polyline {
+    /// The vertices of the polyline on the local xy-plane.
+    pub points: Vec<Point2>,
+    /// If `true`, the last vertex is connected to the first vertex.
+    pub closed: bool,
+    /// The maximum distance between adjacent sampling points. Must be positive and finite.
+    pub resolution: f32,
+    /// The origin of the local xy-plane.
+    pub center: Point3,
+    /// The rotation of the local xy-plane.
+    pub rotation: UnitQuaternion,
+    /// The intensity of the emitted ultrasound.
+    pub intensity: EmitIntensity,
+}
+
+impl Polyline {
+    fn vertices(&self) -> impl Iterator<Item = &Point2> {
+        self.points
+            .iter()
+            .chain(self.points.first().filter(|_| self.closed))
+    }
+
+    fn length(&self) -> f32 {
+        self.vertices()
+            .zip(self.vertices().skip(1))
+            .map(|(a, b)| (b - a).norm())
+            .sum()
+    }
+
+    fn num_points(&self) -> Result<usize, GainError> {
+        if !(self.resolution.is_finite() && self.resolution > 0.) {
+            return Err(GainError::new(format!(
+                "Resolution ({}) must be positive and finite",
+                self.resolution
+            )));
+        }
+        if self.points.is_empty() {
+            return Ok(0);
+        }
+        let n = (self.length() / self.resolution).ceil();
+        if n > FOCI_STM_BUF_SIZE_MAX as f32 {
+            return Err(GainError::new(format!(
+                "Resolution ({}) is too small for the path length ({})",
+                self.resolution,
+                self.length()
+            )));
+        }
+        let n = n as usize;
+        Ok(if self.closed { n.max(1) } else { n + 1 })
+    }
+
+    // The invalid resolution is reported by `init`, so `len` returns the minimum size here to keep the sampling configuration computable.
+    fn len(&self) -> usize {
+        self.num_points().unwrap_or(STM_BUF_SIZE_MIN)
+    }
+
+    fn sample(&self) -> Result<Vec<Point3>, GainError> {
+        let num_points = self.num_points()?;
+        if num_points == 0 {
+            return Ok(Vec::new());
+        }
+        let vertices = self.vertices().collect::<Vec<_>>();
+        let length = self.length();
+        let step = if self.closed {
+            length / num_points as f32
+        } else if num_points > 1 {
+            length / (num_points - 1) as f32
+        } else {
+            0.
+        };
+
+        let mut seg = 0;
+        let mut seg_start = 0.;
+        Ok((0..num_points)
+            .map(|i| {
+                let s = step * i as f32;
+                let p = loop {
+                    if seg + 1 >= vertices.len() {
+                        break *vertices[vertices.len() - 1];
+                    }
+                    let d = vertices[seg + 1] - vertices[seg];
+                    let l = d.norm();
+                    if s <= seg_start + l || seg + 2 >= vertices.len() {
+                        let t = if l == 0. {
+                            0.
+                        } else {
+                            ((s - seg_start) / l).clamp(0., 1.)
+                        };
+                        break vertices[seg] + d * t;
+                    }
+                    seg_start += l;
+                    seg += 1;
+                };
+                self.center + self.rotation * Vector3::new(p.x, p.y, 0.)
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug)]
+pub struct PolylineSTMGenerator {
+    points: Arc<Vec<Point3>>,
+    intensity: EmitIntensity,
+}
+
+pub struct PolylineSTMIterator {
+    points: Arc<Vec<Point3>>,
+    wavenumber: f32,
+    intensity: EmitIntensity,
+    i: usize,
+}
+
+impl PolylineSTMIterator {
+    fn next(&mut self) -> Option<Point3> {
+        let p = self.points.get(self.i).copied();
+        self.i += 1;
+        p
+    }
+}
+
+impl FociSTMIterator<1> for PolylineSTMIterator {
+    fn next(&mut self) -> ControlPoints<1> {
+        ControlPoints {
+            points: [ControlPoint::from(self.next().unwrap())],
+            intensity: self.intensity,
+        }
+    }
+}
+
+impl GainSTMIterator for PolylineSTMIterator {
+    type Calculator = crate::gain::focus::Impl;
+
+    fn next(&mut self) -> Option<Self::Calculator> {
+        Some(Self::Calculator {
+            pos: self.next()?,
+            intensity: self.intensity,
+            phase_offset: Phase::ZERO,
+            wavenumber: self.wavenumber,
+            compensation: None,
+        })
+    }
+}
+
+impl FociSTMIteratorGenerator<1> for PolylineSTMGenerator {
+    type Iterator = PolylineSTMIterator;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        Self::Iterator {
+            points: self.points.clone(),
+            wavenumber: device.wavenumber(),
+            intensity: self.intensity,
+            i: 0,
+        }
+    }
+}
+
+impl GainSTMIteratorGenerator for PolylineSTMGenerator {
+    type Gain = crate::gain::focus::Generator;
+    type Iterator = PolylineSTMIterator;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        FociSTMIteratorGenerator::<1>::generate(self, device)
+    }
+}
+
+impl FociSTMGenerator<1> for Polyline {
+    type T = PolylineSTMGenerator;
+
+    fn init(self) -> Result<Self::T, AUTDDriverError> {
+        Ok(PolylineSTMGenerator {
+            points: Arc::new(self.sample()?),
+            intensity: self.intensity,
+        })
+    }
+
+    fn len(&self) -> usize {
+        Polyline::len(self)
+    }
+}
+
+impl GainSTMGenerator for Polyline {
+    type T = PolylineSTMGenerator;
+
+    fn init(
+        self,
+        _: &Geometry,
+        _filter: Option<&HashMap<usize, BitVec>>,
+        _: bool,
+    ) -> Result<Self::T, GainError> {
+        Ok(PolylineSTMGenerator {
+            points: Arc::new(self.sample()?),
+            intensity: self.intensity,
+        })
+    }
+
+    fn len(&self) -> usize {
+        Polyline::len(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{
+        defined::{mm, PI},
+        geometry::IntoDevice,
+    };
+
+    use crate::assert_near_vector3;
+
+    use super::*;
+
+    fn square(closed: bool) -> Polyline {
+        Polyline {
+            points: vec![
+                Point2::new(0., 0.),
+                Point2::new(10.0 * mm, 0.),
+                Point2::new(10.0 * mm, 10.0 * mm),
+            ],
+            closed,
+            resolution: 5.0 * mm,
+            center: Point3::origin(),
+            rotation: UnitQuaternion::identity(),
+            intensity: EmitIntensity::MAX,
+        }
+    }
+
+    #[rstest::rstest]
+    #[case(
+        vec![
+            Point3::new(0., 0., 0.),
+            Point3::new(5.0 * mm, 0., 0.),
+            Point3::new(10.0 * mm, 0., 0.),
+            Point3::new(10.0 * mm, 5.0 * mm, 0.),
+            Point3::new(10.0 * mm, 10.0 * mm, 0.),
+        ],
+        square(false)
+    )]
+    #[case(
+        vec![Point3::new(0., 0., 0.)],
+        Polyline { points: vec![Point2::new(0., 0.)], ..square(false) }
+    )]
+    #[case(
+        vec![],
+        Polyline { points: vec![], ..square(false) }
+    )]
+    #[case(
+        vec![
+            Point3::new(0., 0., 0.),
+            Point3::new(10.0 * mm, 0., 0.),
+            Point3::new(10.0 * mm, 10.0 * mm, 0.),
+        ],
+        Polyline { resolution: 10.0 * mm, ..square(false) }
+    )]
+    #[case(
+        vec![
+            Point3::new(0., 0., 0.),
+            Point3::new(20.0 / 3.0 * mm, 0., 0.),
+            Point3::new(10.0 * mm, 10.0 / 3.0 * mm, 0.),
+            Point3::new(10.0 * mm, 10.0 * mm, 0.),
+        ],
+        Polyline { resolution: 7.0 * mm, ..square(false) }
+    )]
+    #[case(
+        vec![
+            Point3::new(0., 0., 0.),
+            Point3::new(5.0 * mm, 0., 0.),
+            Point3::new(10.0 * mm, 0., 0.),
+            Point3::new(10.0 * mm, 0., 5.0 * mm),
+            Point3::new(10.0 * mm, 0., 10.0 * mm),
+        ],
+        Polyline {
+            rotation: UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI / 2.),
+            ..square(false)
+        }
+    )]
+    #[test]
+    fn polyline(#[case] expect: Vec<Point3>, #[case] target: Polyline) -> anyhow::Result<()> {
+        assert_eq!(expect.len(), FociSTMGenerator::len(&target));
+        assert_eq!(expect.len(), GainSTMGenerator::len(&target));
+
+        let device = autd3_driver::autd3_device::AUTD3::default().into_device(0);
+        {
+            let mut g = FociSTMGenerator::init(target.clone())?;
+            let mut iterator = FociSTMIteratorGenerator::generate(&mut g, &device);
+            expect.iter().for_each(|e| {
+                let f = FociSTMIterator::<1>::next(&mut iterator).points[0];
+                assert_near_vector3!(e, f.point);
+            });
+            assert!(iterator.next().is_none());
+        }
+        {
+            let geometry = Geometry::new(vec![device]);
+            let mut g = GainSTMGenerator::init(target.clone(), &geometry, None, false)?;
+            let mut iterator = GainSTMIteratorGenerator::generate(&mut g, &geometry[0]);
+            expect.iter().for_each(|e| {
+                let f = GainSTMIterator::next(&mut iterator).unwrap();
+                assert_near_vector3!(e, &f.pos);
+            });
+            assert!(iterator.next().is_none());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn polyline_closed() -> anyhow::Result<()> {
+        let polyline = Polyline {
+            points: vec![
+                Point2::new(0., 0.),
+                Point2::new(10.0 * mm, 0.),
+                Point2::new(10.0 * mm, 10.0 * mm),
+                Point2::new(0., 10.0 * mm),
+            ],
+            ..square(true)
+        };
+        assert_eq!(8, FociSTMGenerator::len(&polyline));
+
+        let expect = [
+            Point3::new(0., 0., 0.),
+            Point3::new(5.0 * mm, 0., 0.),
+            Point3::new(10.0 * mm, 0., 0.),
+            Point3::new(10.0 * mm, 5.0 * mm, 0.),
+            Point3::new(10.0 * mm, 10.0 * mm, 0.),
+            Point3::new(5.0 * mm, 10.0 * mm, 0.),
+            Point3::new(0., 10.0 * mm, 0.),
+            Point3::new(0., 5.0 * mm, 0.),
+        ];
+
+        let device = autd3_driver::autd3_device::AUTD3::default().into_device(0);
+        let mut g = FociSTMGenerator::init(polyline)?;
+        let mut iterator = FociSTMIteratorGenerator::generate(&mut g, &device);
+        expect.iter().for_each(|e| {
+            let f = FociSTMIterator::<1>::next(&mut iterator).points[0];
+            assert_near_vector3!(e, f.point);
+        });
+        assert!(iterator.next().is_none());
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case("Resolution (0) must be positive and finite", 0.)]
+    #[case("Resolution (-1) must be positive and finite", -1.)]
+    #[case("Resolution (NaN) must be positive and finite", f32::NAN)]
+    #[case("Resolution (inf) must be positive and finite", f32::INFINITY)]
+    #[case("Resolution (0.000001) is too small for the path length (20)", 1e-6)]
+    #[test]
+    fn polyline_invalid_resolution(#[case] expect: &str, #[case] resolution: f32) {
+        let target = Polyline {
+            resolution,
+            ..square(false)
+        };
+        assert_eq!(STM_BUF_SIZE_MIN, FociSTMGenerator::len(&target));
+        assert_eq!(STM_BUF_SIZE_MIN, GainSTMGenerator::len(&target));
+
+        let expect = GainError::new(expect.to_string());
+        assert_eq!(
+            Some(AUTDDriverError::Gain(expect.clone())),
+            FociSTMGenerator::init(target.clone()).err()
+        );
+        let geometry = Geometry::new(vec![
+            autd3_driver::autd3_device::AUTD3::default().into_device(0)
+        ]);
+        assert_eq!(
+            Some(expect),
+            GainSTMGenerator::init(target, &geometry, None, false).err()
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use autd3_core::{
+    derive::{Device, Geometry, Transducer},
+    gain::{BitVec, Drive, Gain, GainCalculator, GainCalculatorGenerator, GainError},
+};
+use autd3_driver::datagram::GainSTMGenerator;
+
+use crate::datagram::gain::copy_drives;
+
+use derive_more::Debug;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A token to cancel [`PrecomputedGains::new`].
+///
+/// The token can be cloned and shared with other threads. Calling [`cancel`] on any clone cancels the precomputation.
+///
+/// [`cancel`]: CancellationToken::cancel
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a new [`CancellationToken`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if cancellation has been requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// A sequence of gains whose drives are calculated in advance for [`GainSTM`].
+///
+/// Calculating a [`GainSTM`] with many heavy gains (e.g., holographic gains) inside `send` can take a long time.
+/// [`PrecomputedGains::new`] calculates the drives of all gains in parallel with reporting the progress, and the result can be sent as a [`GainSTM`] without any further calculation.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+/// use autd3::core::gain::GainError;
+/// use autd3::driver::geometry::IntoDevice;
+///
+/// # fn main() -> Result<(), GainError> {
+/// let geometry = Geometry::new(vec![AUTD3::default().into_device(0)]);
+/// let center = geometry.center().unwrap();
+/// let gains = (0..10)
+///     .map(|i| Focus {
+///         pos: center + Vector3::new(i as f32 * mm, 0., 150. * mm),
+///         option: FocusOption::default(),
+///     })
+///     .collect::<Vec<_>>();
+/// let token = CancellationToken::new();
+/// let gains = PrecomputedGains::new(
+///     gains,
+///     &geometry,
+///     |completed, total| println!("{}/{}", completed, total),
+///     &token,
+/// )?;
+/// GainSTM {
+///     gains,
+///     config: 1.0 * Hz,
+///     option: GainSTMOption::default(),
+/// };
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`GainSTM`]: autd3_driver::datagram::GainSTM
+#[derive(Clone, Debug)]
+pub struct PrecomputedGains {
+    #[debug("{}", self.gains.len())]
+    gains: Vec<PrecomputedGain>,
+    #[debug(ignore)]
+    enable: HashMap<usize, BitVec>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PrecomputedGain {
+    #[debug(ignore)]
+    drives: HashMap<usize, Arc<Vec<Drive>>>,
+}
+
+impl PrecomputedGains {
+    /// Calculates the drives of `gains` for all enabled devices of `geometry` in parallel. Without the `parallel` feature, the gains are calculated sequentially.
+    ///
+    /// `progress` is called with the number of completed gains and the total number of gains every time the calculation of a gain is completed.
+    /// Note that `progress` may be called from multiple threads in any order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GainError`] if the calculation of any gain fails or `token` is cancelled.
+    pub fn new<G, F>(
+        gains: Vec<G>,
+        geometry: &Geometry,
+        progress: F,
+        token: &CancellationToken,
+    ) -> Result<Self, GainError>
+    where
+        G: Gain + Send,
+        F: Fn(usize, usize) + Send + Sync,
+    {
+        let total = gains.len();
+        let completed = AtomicUsize::new(0);
+        #[cfg(feature = "parallel")]
+        let gains = gains.into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let gains = gains.into_iter();
+        let gains = gains
+            .map(|gain| {
+                if token.is_cancelled() {
+                    return Err(GainError::new("Precomputation is cancelled".to_string()));
+                }
+                let mut generator = gain.init_full(geometry, None, false)?;
+                let drives = geometry
+                    .devices()
+                    .map(|dev| {
+                        let c = generator.generate(dev);
+                        (
+                            dev.idx(),
+                            Arc::new(dev.iter().map(|tr| c.calc(tr)).collect()),
+                        )
+                    })
+                    .collect();
+                progress(completed.fetch_add(1, Ordering::Relaxed) + 1, total);
+                Ok(PrecomputedGain { drives })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if token.is_cancelled() {
+            return Err(GainError::new("Precomputation is cancelled".to_string()));
+        }
+
+        Ok(Self {
+            gains,
+            enable: geometry
+                .devices()
+                .map(|dev| (dev.idx(), dev.iter().map(|tr| tr.enable).collect()))
+                .collect(),
+        })
+    }
+}
+
+pub struct Impl {
+    g: Arc<Vec<Drive>>,
+}
+
+impl GainCalculator for Impl {
+    fn calc(&self, tr: &Transducer) -> Drive {
+        self.g[tr.idx()]
+    }
+
+    fn calc_into(&self, device: &Device, dst: &mut [Drive]) {
+        copy_drives(&self.g, device, dst);
+    }
+}
+
+impl GainCalculatorGenerator for PrecomputedGain {
+    type Calculator = Impl;
+
+    fn generate(&mut self, device: &Device) -> Self::Calculator {
+        Impl {
+            g: self.drives[&device.idx()].clone(),
+        }
+    }
+}
+
+impl GainSTMGenerator for PrecomputedGains {
+    type T = Vec<PrecomputedGain>;
+
+    fn init(
+        self,
+        geometry: &Geometry,
+        _filter: Option<&HashMap<usize, BitVec>>,
+        _: bool,
+    ) -> Result<Self::T, GainError> {
+        if self.gains.iter().any(|g| {
+            geometry
+                .devices()
+                .any(|dev| !g.drives.contains_key(&dev.idx()))
+        }) {
+            return Err(GainError::new(
+                "PrecomputedGains is calculated with different geometry".to_string(),
+            ));
+        }
+        if geometry.devices().any(|dev| {
+            self.enable[&dev.idx()] != dev.iter().map(|tr| tr.enable).collect::<BitVec>()
+        }) {
+            return Err(GainError::new(
+                "PrecomputedGains is calculated with different transducer enable flags".to_string(),
+            ));
+        }
+        Ok(self.gains)
+    }
+
+    fn len(&self) -> usize {
+        self.gains.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use autd3_driver::{
+        datagram::{GainSTMIterator, GainSTMIteratorGenerator},
+        firmware::fpga::{EmitIntensity, Phase},
+    };
+
+    use crate::{gain::Uniform, tests::create_geometry};
+
+    use super::*;
+
+    fn gains(n: usize) -> Vec<Uniform> {
+        (0..n)
+            .map(|i| Uniform {
+                intensity: EmitIntensity(i as _),
+                phase: Phase(i as _),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn precomputed_gains() -> anyhow::Result<()> {
+        let geometry = create_geometry(2);
+
+        let history = Mutex::new(Vec::new());
+        let g = PrecomputedGains::new(
+            gains(10),
+            &geometry,
+            |completed, total| history.lock().unwrap().push((completed, total)),
+            &CancellationToken::new(),
+        )?;
+        assert_eq!(10, g.len());
+
+        let mut history = history.into_inner().unwrap();
+        history.sort();
+        assert_eq!((1..=10).map(|i| (i, 10)).collect::<Vec<_>>(), history);
+
+        let mut g = g.init(&geometry, None, false)?;
+        geometry.devices().for_each(|dev| {
+            let mut iterator = g.generate(dev);
+            (0..10).for_each(|i| {
+                let c = iterator.next().unwrap();
+                dev.iter().for_each(|tr| {
+                    assert_eq!(
+                        Drive {
+                            intensity: EmitIntensity(i as _),
+                            phase: Phase(i as _),
+                        },
+                        c.calc(tr)
+                    );
+                });
+            });
+            assert!(iterator.next().is_none());
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn precomputed_gains_cancelled() {
+        let geometry = create_geometry(1);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(
+            Some(GainError::new("Precomputation is cancelled".to_string())),
+            PrecomputedGains::new(gains(10), &geometry, |_, _| {}, &token).err()
+        );
+    }
+
+    #[test]
+    fn precomputed_gains_cancelled_in_progress() {
+        let geometry = create_geometry(1);
+
+        let token = CancellationToken::new();
+        assert_eq!(
+            Some(GainError::new("Precomputation is cancelled".to_string())),
+            PrecomputedGains::new(gains(10), &geometry, |_, _| token.cancel(), &token).err()
+        );
+    }
+
+    #[test]
+    fn precomputed_gains_different_geometry() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(2);
+        geometry[1].enable = false;
+
+        let g = PrecomputedGains::new(gains(2), &geometry, |_, _| {}, &CancellationToken::new())?;
+
+        geometry[1].enable = true;
+        assert_eq!(
+            Some(GainError::new(
+                "PrecomputedGains is calculated with different geometry".to_string()
+            )),
+            g.init(&geometry, None, false).err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn precomputed_gains_different_transducer_enable() -> anyhow::Result<()> {
+        let mut geometry = create_geometry(1);
+
+        let g = PrecomputedGains::new(gains(2), &geometry, |_, _| {}, &CancellationToken::new())?;
+        assert!(g.clone().init(&geometry, None, false).is_ok());
+
+        if let Some(tr) = geometry[0].iter_mut().next() {
+            tr.enable = false;
+        }
+        assert_eq!(
+            Some(GainError::new(
+                "PrecomputedGains is calculated with different transducer enable flags".to_string()
+            )),
+            g.init(&geometry, None, false).err()
+        );
+
+        Ok(())
+    }
+}
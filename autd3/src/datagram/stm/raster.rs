@@ -0,0 +1,212 @@
+use autd3_core::{defined::Freq, gain::EmitIntensity};
+use autd3_driver::{
+    datagram::{ControlPoint, ControlPoints, FociSTM, FociSTMGenerator},
+    error::AUTDDriverError,
+    geometry::{Point3, UnitQuaternion, Vector3},
+};
+
+use crate::modulation::{sampling_mode::Nearest, Sine};
+
+/// The scanning order of the lines of [`Raster`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineOrder {
+    /// All lines are scanned from left to right.
+    #[default]
+    Unidirectional,
+    /// The lines are scanned alternately from left to right and from right to left, so that the focus does not jump back to the left edge at the end of each line.
+    Bidirectional,
+}
+
+/// Utility for rendering a 2D intensity image as a tactile raster with [`FociSTM`].
+///
+/// The focus scans the pixels of [`image`] line by line from the top line, and the intensity of each [`ControlPoints`] is the value of the pixel.
+/// The image is placed on the local xy-plane with the pixel spacing of [`pitch`], centered at [`center`] and rotated by [`rotation`]. The first line of the image is at the largest y.
+/// The scan rate, i.e., the number of times the whole image is scanned per second, is the frequency of [`FociSTM`].
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// let image = vec![
+///     vec![EmitIntensity::MAX, EmitIntensity::MIN, EmitIntensity::MAX],
+///     vec![EmitIntensity::MIN, EmitIntensity::MAX, EmitIntensity::MIN],
+///     vec![EmitIntensity::MAX, EmitIntensity::MIN, EmitIntensity::MAX],
+/// ];
+/// let (m, stm) = Raster {
+///     image,
+///     pitch: 2.0 * mm,
+///     center: Point3::new(0., 0., 150.0 * mm),
+///     rotation: UnitQuaternion::identity(),
+///     line_order: LineOrder::Bidirectional,
+/// }
+/// .into_datagram(10.0 * Hz, 200.0 * Hz);
+/// ```
+///
+/// [`image`]: Raster::image
+/// [`pitch`]: Raster::pitch
+/// [`center`]: Raster::center
+/// [`rotation`]: Raster::rotation
+/// [`FociSTM`]: autd3_driver::datagram::FociSTM
+#[derive(Clone, Debug)]
+pub struct Raster {
+    /// The lines of the image. The lines may have different lengths, in which case they are left-aligned.
+    pub image: Vec<Vec<EmitIntensity>>,
+    /// The distance between adjacent pixels.
+    pub pitch: f32,
+    /// The center of the image.
+    pub center: Point3,
+    /// The rotation of the local xy-plane.
+    pub rotation: UnitQuaternion,
+    /// The scanning order of the lines.
+    pub line_order: LineOrder,
+}
+
+impl Raster {
+    fn sample(&self) -> Vec<ControlPoints<1>> {
+        let width = self.image.iter().map(Vec::len).max().unwrap_or(0);
+        let height = self.image.len();
+        let x0 = -(width.saturating_sub(1) as f32) * self.pitch / 2.;
+        let y0 = height.saturating_sub(1) as f32 * self.pitch / 2.;
+        self.image
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                let reverse = self.line_order == LineOrder::Bidirectional && row % 2 == 1;
+                (0..line.len()).map(move |i| {
+                    let col = if reverse { line.len() - 1 - i } else { i };
+                    let p = Vector3::new(
+                        x0 + col as f32 * self.pitch,
+                        y0 - row as f32 * self.pitch,
+                        0.,
+                    );
+                    ControlPoints {
+                        points: [ControlPoint::from(self.center + self.rotation * p)],
+                        intensity: line[col],
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Creates the combination of [`Sine`] modulation with the frequency of `modulation` and [`FociSTM`] which scans the whole image `scan_rate` times per second.
+    ///
+    /// [`FociSTM`]: autd3_driver::datagram::FociSTM
+    pub fn into_datagram(
+        self,
+        scan_rate: Freq<f32>,
+        modulation: Freq<f32>,
+    ) -> (Sine<Nearest>, FociSTM<1, Self, Freq<f32>>) {
+        (
+            Sine {
+                freq: modulation,
+                option: Default::default(),
+            }
+            .into_nearest(),
+            FociSTM {
+                foci: self,
+                config: scan_rate,
+            },
+        )
+    }
+}
+
+impl FociSTMGenerator<1> for Raster {
+    type T = <Vec<ControlPoints<1>> as FociSTMGenerator<1>>::T;
+
+    fn init(self) -> Result<Self::T, AUTDDriverError> {
+        self.sample().init()
+    }
+
+    fn len(&self) -> usize {
+        self.image.iter().map(Vec::len).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{
+        datagram::{FociSTMIterator, FociSTMIteratorGenerator},
+        defined::{mm, Hz, PI},
+        geometry::IntoDevice,
+    };
+
+    use crate::assert_near_vector3;
+
+    use super::*;
+
+    fn image(line_order: LineOrder) -> Raster {
+        Raster {
+            image: vec![
+                vec![EmitIntensity(0), EmitIntensity(1), EmitIntensity(2)],
+                vec![EmitIntensity(3), EmitIntensity(4)],
+            ],
+            pitch: 2.0 * mm,
+            center: Point3::origin(),
+            rotation: UnitQuaternion::identity(),
+            line_order,
+        }
+    }
+
+    #[rstest::rstest]
+    #[case(
+        vec![
+            (Point3::new(-2.0 * mm, 1.0 * mm, 0.), 0),
+            (Point3::new(0., 1.0 * mm, 0.), 1),
+            (Point3::new(2.0 * mm, 1.0 * mm, 0.), 2),
+            (Point3::new(-2.0 * mm, -mm, 0.), 3),
+            (Point3::new(0., -mm, 0.), 4),
+        ],
+        image(LineOrder::Unidirectional)
+    )]
+    #[case(
+        vec![
+            (Point3::new(-2.0 * mm, 1.0 * mm, 0.), 0),
+            (Point3::new(0., 1.0 * mm, 0.), 1),
+            (Point3::new(2.0 * mm, 1.0 * mm, 0.), 2),
+            (Point3::new(0., -mm, 0.), 4),
+            (Point3::new(-2.0 * mm, -mm, 0.), 3),
+        ],
+        image(LineOrder::Bidirectional)
+    )]
+    #[case(
+        vec![
+            (Point3::new(-2.0 * mm, 0., 1.0 * mm), 0),
+            (Point3::new(0., 0., 1.0 * mm), 1),
+            (Point3::new(2.0 * mm, 0., 1.0 * mm), 2),
+            (Point3::new(-2.0 * mm, 0., -mm), 3),
+            (Point3::new(0., 0., -mm), 4),
+        ],
+        Raster {
+            rotation: UnitQuaternion::from_axis_angle(&Vector3::x_axis(), PI / 2.),
+            ..image(LineOrder::Unidirectional)
+        }
+    )]
+    #[case(
+        vec![],
+        Raster { image: vec![], ..image(LineOrder::Unidirectional) }
+    )]
+    #[test]
+    fn raster(#[case] expect: Vec<(Point3, u8)>, #[case] target: Raster) -> anyhow::Result<()> {
+        assert_eq!(expect.len(), target.len());
+
+        let device = autd3_driver::autd3_device::AUTD3::default().into_device(0);
+        let mut g = target.init()?;
+        let mut iterator = g.generate(&device);
+        expect.iter().for_each(|(p, intensity)| {
+            let f = iterator.next();
+            assert_near_vector3!(p, f.points[0].point);
+            assert_eq!(EmitIntensity(*intensity), f.intensity);
+        });
+
+        Ok(())
+    }
+
+    #[test]
+    fn into_datagram() {
+        let (m, stm) = image(LineOrder::Unidirectional).into_datagram(10.0 * Hz, 150.0 * Hz);
+        assert_eq!(150.0 * Hz, m.freq.0);
+        assert_eq!(10.0 * Hz, stm.config);
+        assert_eq!(5, stm.foci.len());
+    }
+}
@@ -0,0 +1,238 @@
+use std::{collections::HashMap, f32::consts::PI};
+
+use autd3_core::{
+    derive::{Device, Geometry},
+    gain::{BitVec, EmitIntensity, GainError, Phase},
+};
+use autd3_driver::{
+    datagram::{
+        ControlPoint, ControlPoints, FociSTMGenerator, FociSTMIterator, FociSTMIteratorGenerator,
+        GainSTMGenerator, GainSTMIterator, GainSTMIteratorGenerator,
+    },
+    error::AUTDDriverError,
+    geometry::{Point3, UnitQuaternion, Vector3},
+};
+
+/// Utility for generating an Archimedean spiral trajectory STM.
+///
+/// The radius increases linearly from [`start_radius`] to [`end_radius`] while the focus rotates [`turns`] times.
+/// The spiral is defined on the local xy-plane and then rotated by [`rotation`] around [`center`].
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+///
+/// FociSTM {
+///     config: 1.0 * Hz,
+///     foci: Spiral {
+///         center: Point3::origin(),
+///         start_radius: 0.0 * mm,
+///         end_radius: 30.0 * mm,
+///         turns: 3.0,
+///         num_points: 200,
+///         rotation: UnitQuaternion::identity(),
+///         intensity: EmitIntensity::MAX,
+///     },
+/// };
+/// ```
+///
+/// [`start_radius`]: Spiral::start_radius
+/// [`end_radius`]: Spiral::end_radius
+/// [`turns`]: Spiral::turns
+/// [`rotation`]: Spiral::rotation
+/// [`center`]: Spiral::center
+#[derive(Clone, Debug)]
+pub struct Spiral {
+    /// The center of the spiral.
+    pub center: Point3,
+    /// The radius at the first point.
+    pub start_radius: f32,
+    /// The radius at the last point.
+    pub end_radius: f32,
+    /// The number of turns.
+    pub turns: f32,
+    /// The number of points on the spiral. Both end points are included.
+    pub num_points: usize,
+    /// The rotation of the spiral plane.
+    pub rotation: UnitQuaternion,
+    /// The intensity of the emitted ultrasound.
+    pub intensity: EmitIntensity,
+}
+
+pub struct SpiralSTMIterator {
+    center: Point3,
+    start_radius: f32,
+    end_radius: f32,
+    turns: f32,
+    num_points: usize,
+    rotation: UnitQuaternion,
+    wavenumber: f32,
+    intensity: EmitIntensity,
+    i: usize,
+}
+
+impl SpiralSTMIterator {
+    fn next(&mut self) -> Option<Point3> {
+        if self.i >= self.num_points {
+            return None;
+        }
+        let t = if self.num_points == 1 {
+            0.
+        } else {
+            self.i as f32 / (self.num_points - 1) as f32
+        };
+        let theta = 2.0 * PI * self.turns * t;
+        let r = self.start_radius + (self.end_radius - self.start_radius) * t;
+        self.i += 1;
+        Some(self.center + self.rotation * (r * Vector3::new(theta.cos(), theta.sin(), 0.)))
+    }
+}
+impl FociSTMIterator<1> for SpiralSTMIterator {
+    fn next(&mut self) -> ControlPoints<1> {
+        ControlPoints {
+            points: [ControlPoint::from(self.next().unwrap())],
+            intensity: self.intensity,
+        }
+    }
+}
+
+impl GainSTMIterator for SpiralSTMIterator {
+    type Calculator = crate::gain::focus::Impl;
+
+    fn next(&mut self) -> Option<Self::Calculator> {
+        Some(Self::Calculator {
+            pos: self.next()?,
+            intensity: self.intensity,
+            phase_offset: Phase::ZERO,
+            wavenumber: self.wavenumber,
+            compensation: None,
+        })
+    }
+}
+
+impl FociSTMIteratorGenerator<1> for Spiral {
+    type Iterator = SpiralSTMIterator;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        Self::Iterator {
+            center: self.center,
+            start_radius: self.start_radius,
+            end_radius: self.end_radius,
+            turns: self.turns,
+            num_points: self.num_points,
+            rotation: self.rotation,
+            wavenumber: device.wavenumber(),
+            intensity: self.intensity,
+            i: 0,
+        }
+    }
+}
+
+impl GainSTMIteratorGenerator for Spiral {
+    type Gain = crate::gain::focus::Generator;
+    type Iterator = SpiralSTMIterator;
+
+    fn generate(&mut self, device: &Device) -> Self::Iterator {
+        FociSTMIteratorGenerator::<1>::generate(self, device)
+    }
+}
+
+impl FociSTMGenerator<1> for Spiral {
+    type T = Self;
+
+    // GRCOV_EXCL_START
+    fn init(self) -> Result<Self::T, AUTDDriverError> {
+        Ok(self)
+    }
+    // GRCOV_EXCL_STOP
+
+    fn len(&self) -> usize {
+        self.num_points
+    }
+}
+
+impl GainSTMGenerator for Spiral {
+    type T = Self;
+
+    // GRCOV_EXCL_START
+    fn init(
+        self,
+        _: &Geometry,
+        _filter: Option<&HashMap<usize, BitVec>>,
+        _: bool,
+    ) -> Result<Self::T, GainError> {
+        Ok(self)
+    }
+    // GRCOV_EXCL_STOP
+
+    fn len(&self) -> usize {
+        self.num_points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::DerefMut;
+
+    use autd3_core::modulation::SamplingConfig;
+    use autd3_driver::{
+        datagram::{FociSTM, GainSTM, GainSTMOption},
+        defined::mm,
+        geometry::IntoDevice,
+    };
+
+    use crate::assert_near_vector3;
+
+    use super::*;
+
+    #[test]
+    fn spiral() {
+        let spiral = Spiral {
+            center: Point3::new(0., 0., 150.0 * mm),
+            start_radius: 10.0 * mm,
+            end_radius: 30.0 * mm,
+            turns: 1.0,
+            num_points: 5,
+            rotation: UnitQuaternion::identity(),
+            intensity: EmitIntensity::MAX,
+        };
+        assert_eq!(5, FociSTMGenerator::len(&spiral));
+        assert_eq!(5, GainSTMGenerator::len(&spiral));
+
+        let expect = [
+            Point3::new(10.0 * mm, 0., 150.0 * mm),
+            Point3::new(0., 15.0 * mm, 150.0 * mm),
+            Point3::new(-20.0 * mm, 0., 150.0 * mm),
+            Point3::new(0., -25.0 * mm, 150.0 * mm),
+            Point3::new(30.0 * mm, 0., 150.0 * mm),
+        ];
+
+        let device = autd3_driver::autd3_device::AUTD3::default().into_device(0);
+        {
+            let mut stm = FociSTM {
+                foci: spiral.clone(),
+                config: SamplingConfig::DIV_10,
+            };
+            let mut iterator = FociSTMIteratorGenerator::generate(stm.deref_mut(), &device);
+            expect.iter().for_each(|e| {
+                let f = FociSTMIterator::<1>::next(&mut iterator).points[0];
+                assert_near_vector3!(e, f.point);
+            });
+            assert!(iterator.next().is_none());
+        }
+        {
+            let mut stm = GainSTM {
+                gains: spiral.clone(),
+                config: SamplingConfig::DIV_10,
+                option: GainSTMOption::default(),
+            };
+            let mut iterator = GainSTMIteratorGenerator::generate(stm.deref_mut(), &device);
+            expect.iter().for_each(|e| {
+                let f = GainSTMIterator::next(&mut iterator).unwrap();
+                assert_near_vector3!(e, &f.pos);
+            });
+            assert!(iterator.next().is_none());
+        }
+    }
+}
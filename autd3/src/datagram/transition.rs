@@ -0,0 +1,425 @@
+use std::num::NonZeroU16;
+
+use autd3_core::{
+    datagram::{DatagramL, DatagramS, GPIOIn, LoopBehavior, Segment, TransitionMode},
+    ethercat::DcSysTime,
+};
+use autd3_driver::datagram::{WithLoopBehavior, WithSegment};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A transition mode which can be used for any [`DatagramS`], i.e., [`Immediate`] and [`Later`].
+pub trait SegmentTransition: sealed::Sealed {
+    #[doc(hidden)]
+    fn mode(self) -> Option<TransitionMode>;
+}
+
+/// A transition mode which can be used with the infinite loop of [`DatagramL`], i.e., [`Immediate`], [`Later`], and [`Ext`].
+pub trait InfiniteTransition: sealed::Sealed {
+    #[doc(hidden)]
+    fn mode(self) -> Option<TransitionMode>;
+}
+
+/// A transition mode which can be used with the finite loop of [`DatagramL`], i.e., [`Later`], [`SyncIdx`], [`SysTime`], and [`GPIO`].
+pub trait FiniteTransition: sealed::Sealed {
+    #[doc(hidden)]
+    fn mode(self) -> Option<TransitionMode>;
+}
+
+/// Switches to the segment immediately. See [`TransitionMode::Immediate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Immediate;
+
+/// Writes the data to the segment without switching to it. The segment is switched by [`SwapSegment`] later.
+///
+/// [`SwapSegment`]: autd3_driver::datagram::SwapSegment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Later;
+
+/// Switches to the next segment automatically when the data in the current segment is finished. See [`TransitionMode::Ext`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ext;
+
+/// Switches to the segment when the sampling index in the segment is 0. See [`TransitionMode::SyncIdx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncIdx;
+
+/// Switches to the segment at the system time. See [`TransitionMode::SysTime`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SysTime(pub DcSysTime);
+
+/// Switches to the segment when the GPIO pin is high. See [`TransitionMode::GPIO`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GPIO(pub GPIOIn);
+
+/// Creates [`SysTime`] transition at `time`.
+pub const fn at_systime(time: DcSysTime) -> SysTime {
+    SysTime(time)
+}
+
+/// Creates [`GPIO`] transition on `pin`.
+pub const fn on_gpio(pin: GPIOIn) -> GPIO {
+    GPIO(pin)
+}
+
+impl sealed::Sealed for Immediate {}
+impl sealed::Sealed for Later {}
+impl sealed::Sealed for Ext {}
+impl sealed::Sealed for SyncIdx {}
+impl sealed::Sealed for SysTime {}
+impl sealed::Sealed for GPIO {}
+
+impl SegmentTransition for Immediate {
+    fn mode(self) -> Option<TransitionMode> {
+        Some(TransitionMode::Immediate)
+    }
+}
+
+impl SegmentTransition for Later {
+    fn mode(self) -> Option<TransitionMode> {
+        None
+    }
+}
+
+impl InfiniteTransition for Immediate {
+    fn mode(self) -> Option<TransitionMode> {
+        Some(TransitionMode::Immediate)
+    }
+}
+
+impl InfiniteTransition for Later {
+    fn mode(self) -> Option<TransitionMode> {
+        None
+    }
+}
+
+impl InfiniteTransition for Ext {
+    fn mode(self) -> Option<TransitionMode> {
+        Some(TransitionMode::Ext)
+    }
+}
+
+impl FiniteTransition for Later {
+    fn mode(self) -> Option<TransitionMode> {
+        None
+    }
+}
+
+impl FiniteTransition for SyncIdx {
+    fn mode(self) -> Option<TransitionMode> {
+        Some(TransitionMode::SyncIdx)
+    }
+}
+
+impl FiniteTransition for SysTime {
+    fn mode(self) -> Option<TransitionMode> {
+        Some(TransitionMode::SysTime(self.0))
+    }
+}
+
+impl FiniteTransition for GPIO {
+    fn mode(self) -> Option<TransitionMode> {
+        Some(TransitionMode::GPIO(self.0))
+    }
+}
+
+/// A builder of the segment and the transition of [`DatagramS`] created by [`IntoTransition::segment`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segmented<D> {
+    inner: D,
+    segment: Segment,
+}
+
+impl<D: DatagramS> Segmented<D> {
+    /// Sets the transition to the segment. Only [`Immediate`] and [`Later`] are allowed, which are valid for any [`DatagramS`].
+    pub fn transition(self, transition: impl SegmentTransition) -> WithSegment<D> {
+        WithSegment {
+            inner: self.inner,
+            segment: self.segment,
+            transition_mode: transition.mode(),
+        }
+    }
+}
+
+impl<D: DatagramL> Segmented<D> {
+    /// Sets the loop behavior to infinite.
+    pub fn infinite(self) -> Looped<D, Infinite> {
+        Looped {
+            inner: self.inner,
+            segment: self.segment,
+            rep: Infinite,
+        }
+    }
+
+    /// Sets the loop behavior to finite with `rep` repetitions.
+    pub fn finite(self, rep: NonZeroU16) -> Looped<D, Finite> {
+        Looped {
+            inner: self.inner,
+            segment: self.segment,
+            rep: Finite(rep),
+        }
+    }
+
+    /// Sets the loop behavior to finite with one repetition.
+    pub fn once(self) -> Looped<D, Finite> {
+        self.finite(NonZeroU16::MIN)
+    }
+}
+
+/// The marker of the infinite loop of [`Looped`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Infinite;
+
+/// The marker of the finite loop of [`Looped`] with the number of repetitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Finite(NonZeroU16);
+
+/// A builder of the transition of [`DatagramL`] with the loop behavior created by [`Segmented::infinite`], [`Segmented::finite`], or [`Segmented::once`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Looped<D, R> {
+    inner: D,
+    segment: Segment,
+    rep: R,
+}
+
+impl<D: DatagramL> Looped<D, Infinite> {
+    /// Sets the transition to the segment. Only [`Immediate`], [`Later`], and [`Ext`] are allowed with the infinite loop.
+    pub fn transition(self, transition: impl InfiniteTransition) -> WithLoopBehavior<D> {
+        WithLoopBehavior {
+            inner: self.inner,
+            loop_behavior: LoopBehavior::Infinite,
+            segment: self.segment,
+            transition_mode: transition.mode(),
+        }
+    }
+}
+
+impl<D: DatagramL> Looped<D, Finite> {
+    /// Sets the transition to the segment. Only [`Later`], [`SyncIdx`], [`SysTime`], and [`GPIO`] are allowed with the finite loop.
+    pub fn transition(self, transition: impl FiniteTransition) -> WithLoopBehavior<D> {
+        WithLoopBehavior {
+            inner: self.inner,
+            loop_behavior: LoopBehavior::Finite(self.rep.0),
+            segment: self.segment,
+            transition_mode: transition.mode(),
+        }
+    }
+}
+
+/// A trait to start building the segment and the transition of [`DatagramS`].
+///
+/// The transition modes valid for each datagram and loop behavior are checked at compile time.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+/// use autd3::datagram::transition::{at_systime, IntoTransition, Immediate, Later};
+/// use autd3::driver::ethercat::DcSysTime;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open([AUTD3::default()], Nop::new())?;
+///
+/// // Write the gain to segment 1 without switching to it.
+/// autd.send(Null {}.segment(Segment::S1).transition(Later))?;
+///
+/// // Switch to segment 1 immediately and loop the modulation infinitely.
+/// autd.send(Static::default().segment(Segment::S1).infinite().transition(Immediate))?;
+///
+/// // Play the modulation in segment 0 once at the specified time.
+/// let time = DcSysTime::now() + std::time::Duration::from_millis(100);
+/// autd.send(Static::default().segment(Segment::S0).once().transition(at_systime(time)))?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The invalid combinations do not compile.
+///
+/// ```compile_fail
+/// use autd3::prelude::*;
+/// use autd3::datagram::transition::{IntoTransition, SyncIdx};
+///
+/// // `SyncIdx` is not allowed for the infinite loop.
+/// let _ = Static::default().segment(Segment::S1).infinite().transition(SyncIdx);
+/// ```
+///
+/// ```compile_fail
+/// use autd3::prelude::*;
+/// use autd3::datagram::transition::{IntoTransition, Immediate};
+///
+/// // `Gain` does not have the loop behavior.
+/// let _ = Null {}.segment(Segment::S1).once().transition(Immediate);
+/// ```
+pub trait IntoTransition: DatagramS + Sized {
+    /// Sets the segment to write the data.
+    fn segment(self, segment: Segment) -> Segmented<Self> {
+        Segmented {
+            inner: self,
+            segment,
+        }
+    }
+}
+
+impl<D: DatagramS> IntoTransition for D {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use autd3_driver::firmware::fpga::{Drive, EmitIntensity, Phase};
+
+    use super::*;
+
+    use crate::{
+        controller::tests::create_controller,
+        gain::{Null, Uniform},
+        modulation::Static,
+    };
+
+    #[test]
+    fn segment_transition() {
+        let d = Null {}.segment(Segment::S1).transition(Later);
+        assert_eq!((Segment::S1, None), (d.segment, d.transition_mode));
+
+        let d = Null {}.segment(Segment::S1).transition(Immediate);
+        assert_eq!(
+            (Segment::S1, Some(TransitionMode::Immediate)),
+            (d.segment, d.transition_mode)
+        );
+    }
+
+    #[test]
+    fn infinite_transition() {
+        [
+            (
+                None,
+                Static::default()
+                    .segment(Segment::S1)
+                    .infinite()
+                    .transition(Later),
+            ),
+            (
+                Some(TransitionMode::Immediate),
+                Static::default()
+                    .segment(Segment::S1)
+                    .infinite()
+                    .transition(Immediate),
+            ),
+            (
+                Some(TransitionMode::Ext),
+                Static::default()
+                    .segment(Segment::S1)
+                    .infinite()
+                    .transition(Ext),
+            ),
+        ]
+        .into_iter()
+        .for_each(|(expect, d)| {
+            assert_eq!(
+                (Segment::S1, LoopBehavior::Infinite, expect),
+                (d.segment, d.loop_behavior, d.transition_mode)
+            );
+        });
+    }
+
+    #[test]
+    fn finite_transition() {
+        let rep = NonZeroU16::new(3).unwrap();
+        [
+            (
+                LoopBehavior::Finite(rep),
+                None,
+                Static::default()
+                    .segment(Segment::S0)
+                    .finite(rep)
+                    .transition(Later),
+            ),
+            (
+                LoopBehavior::ONCE,
+                Some(TransitionMode::SyncIdx),
+                Static::default()
+                    .segment(Segment::S0)
+                    .once()
+                    .transition(SyncIdx),
+            ),
+            (
+                LoopBehavior::Finite(rep),
+                Some(TransitionMode::SysTime(DcSysTime::ZERO)),
+                Static::default()
+                    .segment(Segment::S0)
+                    .finite(rep)
+                    .transition(at_systime(DcSysTime::ZERO)),
+            ),
+            (
+                LoopBehavior::ONCE,
+                Some(TransitionMode::GPIO(GPIOIn::I1)),
+                Static::default()
+                    .segment(Segment::S0)
+                    .once()
+                    .transition(on_gpio(GPIOIn::I1)),
+            ),
+        ]
+        .into_iter()
+        .for_each(|(loop_behavior, expect, d)| {
+            assert_eq!(
+                (Segment::S0, loop_behavior, expect),
+                (d.segment, d.loop_behavior, d.transition_mode)
+            );
+        });
+    }
+
+    #[test]
+    fn send() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+
+        let g = Uniform {
+            intensity: EmitIntensity(0x80),
+            phase: Phase::ZERO,
+        };
+        autd.send(g.segment(Segment::S1).transition(Later))?;
+        assert_eq!(Segment::S0, autd.link()[0].fpga().current_stm_segment());
+        assert_eq!(
+            vec![
+                Drive {
+                    intensity: EmitIntensity(0x80),
+                    phase: Phase::ZERO
+                };
+                autd[0].num_transducers()
+            ],
+            autd.link()[0].fpga().drives_at(Segment::S1, 0)
+        );
+
+        autd.send(
+            Static { intensity: 0x40 }
+                .segment(Segment::S1)
+                .once()
+                .transition(Later),
+        )?;
+        assert_eq!(Segment::S0, autd.link()[0].fpga().req_modulation_segment());
+        assert_eq!(
+            vec![0x40; 2],
+            autd.link()[0].fpga().modulation_buffer(Segment::S1)
+        );
+
+        let time = DcSysTime::now() + Duration::from_secs(1);
+        autd.send(
+            Static { intensity: 0x80 }
+                .segment(Segment::S1)
+                .once()
+                .transition(at_systime(time)),
+        )?;
+        assert_eq!(Segment::S1, autd.link()[0].fpga().req_modulation_segment());
+        assert_eq!(
+            TransitionMode::SysTime(time),
+            autd.link()[0].fpga().modulation_transition_mode()
+        );
+        assert_eq!(
+            LoopBehavior::ONCE,
+            autd.link()[0].fpga().modulation_loop_behavior(Segment::S1)
+        );
+
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
-use autd3_core::link::LinkError;
+use autd3_core::{defined::Freq, link::LinkError};
 use autd3_driver::error::AUTDDriverError;
 use thiserror::Error;
 
+use crate::controller::CloseReport;
+
 /// A interface for error handling in autd3.
 #[derive(Error, Debug, PartialEq)]
 #[non_exhaustive]
@@ -12,9 +14,91 @@ pub enum AUTDError {
     /// Failed to read FPGA state.
     #[error("Read FPGA state failed")]
     ReadFPGAStateFailed,
+    /// The geometry has no devices.
+    #[error("Geometry must have at least one device")]
+    EmptyGeometry,
+    /// The device index is out of range.
+    #[error("Device index ({0}) is out of range")]
+    DeviceIndexOutOfRange(usize),
     /// Driver error.
     #[error("{0}")]
     Driver(#[from] AUTDDriverError),
+    /// The ultrasound frequency has already been fixed to another value.
+    #[cfg(feature = "dynamic_freq")]
+    #[error(
+        "Ultrasound frequency cannot be set to {0:?} because it has already been fixed to {1:?}"
+    )]
+    UltrasoundFreqAlreadyFixed(
+        autd3_core::defined::Freq<u32>,
+        autd3_core::defined::Freq<u32>,
+    ),
+
+    /// The firmware of the device does not support the GPIO output configuration.
+    #[error("GPIO output configuration is not supported by the firmware ({1}) of device {0}")]
+    UnsupportedFirmware(usize, String),
+    /// The GPIO output configuration is invalid for the device.
+    #[error("Invalid GPIO output ({1}) for device {0}")]
+    InvalidGPIOOutput(usize, String),
+
+    /// Phase calibration samples are insufficient.
+    #[error("At least 3 samples with distinct phases are required to calibrate transducer {1} of device {0}")]
+    InsufficientCalibrationSamples(usize, usize),
+    /// Failed to save or load the phase correction file.
+    #[error("Phase correction file error: {0}")]
+    PhaseCorrectionFile(String),
+    /// The pressure measured for amplitude calibration is invalid.
+    #[error("Measured pressure ({1}) of group {0} must be positive and finite")]
+    InvalidCalibrationPressure(String, f32),
+    /// Failed to save or load the geometry file.
+    #[error("Geometry file error: {0}")]
+    GeometryFile(String),
+
+    /// The completion steps of the silencer exceed the sampling period in strict mode.
+    #[error("Silencer completion steps (intensity: {0}, phase: {1}) must be at most (intensity: {2}, phase: {3}) in strict mode")]
+    SilencerStrictModeViolation(u16, u16, u16, u16),
+
+    /// The size of the pulse width encoder table does not match the firmware.
+    #[error("Pulse width encoder table must have {1} entries, but {0} entries are given")]
+    InvalidPulseWidthTableSize(usize, usize),
+    /// The duty ratio of the pulse width curve is out of range.
+    #[error("Duty ratio ({1}) for intensity {0} must be in [0, 1)")]
+    InvalidDutyRatio(u8, f32),
+
+    /// The number of foci per pattern exceeds the limit of the firmware.
+    #[error("Device {0} supports at most {2} foci per pattern, but {1} foci are required")]
+    FociNumNotSupported(usize, usize, usize),
+    /// The enabled devices are not enough to output all groups of foci.
+    #[error(
+        "{0} devices are required to output all groups of foci, but only {1} devices are enabled"
+    )]
+    InsufficientDevicesForFoci(usize, usize),
+
+    /// The parameter of the sensation is invalid.
+    #[error("Invalid sensation parameter: {0}")]
+    InvalidSensationParameter(String),
+
+    /// Some stages of closing the controller failed.
+    #[error("Failed to close the controller: {0}")]
+    CloseFailed(Box<CloseReport>),
+
+    /// The snapshot was taken with a different number of devices.
+    #[error("Snapshot was taken with {0} devices, but the controller has {1} devices")]
+    SnapshotDeviceMismatch(usize, usize),
+    /// The segment captured in the snapshot has been overwritten.
+    #[error("The {1} of device {0} has been overwritten since the snapshot was taken")]
+    SnapshotOverwritten(usize, String),
+    /// Tracking the configuration for the snapshot is not enabled.
+    #[error("Snapshot is not enabled. Call `enable_snapshot` just after opening the controller")]
+    SnapshotDisabled,
+    /// Tracking the configuration for the snapshot is enabled after sending data.
+    #[error("Snapshot must be enabled before sending any data")]
+    SnapshotEnabledAfterSend,
+
+    /// The maximum rate of [`RateLimiter`] is invalid.
+    ///
+    /// [`RateLimiter`]: crate::controller::RateLimiter
+    #[error("Maximum rate ({0:?}) must be positive and finite")]
+    InvalidMaxRate(Freq<f32>),
 
     /// Unknown group key.
     #[error("Unknown group key({0})")]
@@ -0,0 +1,390 @@
+use std::path::Path;
+
+use autd3_driver::{
+    autd3_device::AUTD3,
+    defined::mm,
+    geometry::{Geometry, Point3, UnitQuaternion, Vector3},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AUTDError;
+
+/// The format of [`GeometryConfig`] files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeometryFormat {
+    /// TOML format.
+    Toml,
+    /// JSON format.
+    Json,
+}
+
+impl GeometryFormat {
+    /// Returns the format from the extension of the path, i.e., `toml` or `json`.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, AUTDError> {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => Ok(Self::Toml),
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Ok(Self::Json),
+            _ => Err(AUTDError::GeometryFile(format!(
+                "Unsupported file extension: {}",
+                path.as_ref().display()
+            ))),
+        }
+    }
+}
+
+/// The rotation of a device in [`DeviceConfig`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationConfig {
+    /// A unit quaternion in the order of `[w, x, y, z]`. The quaternion is normalized when loaded.
+    Quaternion([f32; 4]),
+    /// Euler angles in degree.
+    Euler {
+        /// The rotation axes in the order of application, e.g., `"ZYZ"`.
+        order: String,
+        /// The rotation angles in degree.
+        angles: [f32; 3],
+        /// If `true`, the angles are intrinsic, i.e., each rotation is applied about the rotated axes, as [`EulerAngle`]. The default value is `true`.
+        ///
+        /// [`EulerAngle`]: autd3_driver::geometry::EulerAngle
+        #[serde(default = "default_intrinsic")]
+        intrinsic: bool,
+    },
+}
+
+const fn default_intrinsic() -> bool {
+    true
+}
+
+impl Default for RotationConfig {
+    fn default() -> Self {
+        Self::Quaternion([1., 0., 0., 0.])
+    }
+}
+
+impl RotationConfig {
+    fn to_quaternion(&self) -> Result<UnitQuaternion, AUTDError> {
+        match self {
+            RotationConfig::Quaternion([w, x, y, z]) => {
+                let q = autd3_driver::geometry::Quaternion::new(*w, *x, *y, *z);
+                if q.norm() == 0. {
+                    return Err(AUTDError::GeometryFile(
+                        "Quaternion must not be zero".to_string(),
+                    ));
+                }
+                Ok(UnitQuaternion::from_quaternion(q))
+            }
+            RotationConfig::Euler {
+                order,
+                angles,
+                intrinsic,
+            } => {
+                if order.len() != 3 {
+                    return Err(AUTDError::GeometryFile(format!(
+                        "Invalid Euler angle order: {}",
+                        order
+                    )));
+                }
+                let rotations = order
+                    .chars()
+                    .zip(angles.iter())
+                    .map(|(axis, &angle)| {
+                        let axis = match axis.to_ascii_lowercase() {
+                            'x' => Vector3::x_axis(),
+                            'y' => Vector3::y_axis(),
+                            'z' => Vector3::z_axis(),
+                            _ => {
+                                return Err(AUTDError::GeometryFile(format!(
+                                    "Invalid Euler angle order: {}",
+                                    order
+                                )))
+                            }
+                        };
+                        Ok(UnitQuaternion::from_axis_angle(&axis, angle.to_radians()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(if *intrinsic {
+                    rotations
+                        .iter()
+                        .fold(UnitQuaternion::identity(), |acc, r| acc * r)
+                } else {
+                    rotations
+                        .iter()
+                        .rev()
+                        .fold(UnitQuaternion::identity(), |acc, r| acc * r)
+                })
+            }
+        }
+    }
+}
+
+/// The arrangement of a device in [`GeometryConfig`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DeviceConfig {
+    /// The position of the device in millimeter regardless of the `use_meter` feature. See [`AUTD3::pos`].
+    pub pos: [f32; 3],
+    /// The rotation of the device. See [`AUTD3::rot`]. The default value is the identity.
+    #[serde(default)]
+    pub rot: RotationConfig,
+}
+
+/// The arrangement of [`AUTD3`] devices, which can be loaded from and saved to a TOML or JSON file.
+///
+/// The devices are listed in the order of the connection, e.g., in TOML,
+///
+/// ```toml
+/// [[devices]]
+/// pos = [0.0, 0.0, 0.0]
+///
+/// [[devices]]
+/// pos = [192.0, 0.0, 0.0]
+/// rot = { euler = { order = "ZYZ", angles = [90.0, 0.0, 0.0] } }
+///
+/// [[devices]]
+/// pos = [384.0, 0.0, 0.0]
+/// rot = { quaternion = [1.0, 0.0, 0.0, 0.0] }
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+/// use autd3::geometry::GeometryConfig;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// # let path = std::env::temp_dir().join("autd3_geometry_doctest.toml");
+/// # let autd = Controller::open([AUTD3::default()], Nop::new())?;
+/// # GeometryConfig::from_geometry(&autd).save(&path)?;
+/// let config = GeometryConfig::load(&path)?;
+/// let autd = Controller::open(config.devices()?, Nop::new())?;
+///
+/// GeometryConfig::from_geometry(&autd).save(&path)?;
+/// # std::fs::remove_file(&path).unwrap();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct GeometryConfig {
+    /// The devices.
+    pub devices: Vec<DeviceConfig>,
+}
+
+impl GeometryConfig {
+    /// Creates a [`GeometryConfig`] from the current arrangement of the devices.
+    ///
+    /// The position of the first transducer and the rotation of each device are saved, which correspond to [`AUTD3::pos`] and [`AUTD3::rot`]. The rotation is saved as a quaternion.
+    pub fn from_geometry(geometry: &Geometry) -> Self {
+        Self {
+            devices: geometry
+                .iter()
+                .map(|dev| {
+                    let pos = dev[0].position();
+                    let rot = dev.rotation();
+                    DeviceConfig {
+                        pos: [pos.x / mm, pos.y / mm, pos.z / mm],
+                        rot: RotationConfig::Quaternion([rot.w, rot.i, rot.j, rot.k]),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the [`AUTD3`] devices to open [`Controller`].
+    ///
+    /// Returns [`AUTDError::GeometryFile`] if any rotation is invalid.
+    ///
+    /// [`Controller`]: crate::controller::Controller
+    pub fn devices(&self) -> Result<Vec<AUTD3>, AUTDError> {
+        self.devices
+            .iter()
+            .map(|dev| {
+                Ok(AUTD3 {
+                    pos: Point3::from(dev.pos) * mm,
+                    rot: dev.rot.to_quaternion()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Parses the content in the format.
+    pub fn parse(content: &str, format: GeometryFormat) -> Result<Self, AUTDError> {
+        match format {
+            GeometryFormat::Toml => {
+                toml::from_str(content).map_err(|e| AUTDError::GeometryFile(e.to_string()))
+            }
+            GeometryFormat::Json => {
+                serde_json::from_str(content).map_err(|e| AUTDError::GeometryFile(e.to_string()))
+            }
+        }
+    }
+
+    /// Serializes into a string in the format.
+    pub fn to_string(&self, format: GeometryFormat) -> Result<String, AUTDError> {
+        match format {
+            GeometryFormat::Toml => {
+                toml::to_string(self).map_err(|e| AUTDError::GeometryFile(e.to_string()))
+            }
+            GeometryFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| AUTDError::GeometryFile(e.to_string())),
+        }
+    }
+
+    /// Loads from a file. The format is determined by the extension of the path, see [`GeometryFormat::from_path`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AUTDError> {
+        let format = GeometryFormat::from_path(&path)?;
+        let content =
+            std::fs::read_to_string(path).map_err(|e| AUTDError::GeometryFile(e.to_string()))?;
+        Self::parse(&content, format)
+    }
+
+    /// Saves to a file. The format is determined by the extension of the path, see [`GeometryFormat::from_path`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AUTDError> {
+        let content = self.to_string(GeometryFormat::from_path(&path)?)?;
+        std::fs::write(path, content).map_err(|e| AUTDError::GeometryFile(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{
+        defined::deg,
+        geometry::{EulerAngle, IntoDevice},
+    };
+
+    use super::*;
+
+    const TOML: &str = r#"
+[[devices]]
+pos = [0.0, 0.0, 0.0]
+
+[[devices]]
+pos = [192.0, 0.0, 0.0]
+rot = { euler = { order = "ZYZ", angles = [90.0, 30.0, 0.0] } }
+
+[[devices]]
+pos = [0.0, 0.0, 10.0]
+rot = { euler = { order = "XYZ", angles = [0.0, 0.0, 90.0], intrinsic = false } }
+"#;
+
+    #[test]
+    fn parse_toml() -> anyhow::Result<()> {
+        let devices = GeometryConfig::parse(TOML, GeometryFormat::Toml)?.devices()?;
+
+        assert_eq!(3, devices.len());
+        assert_eq!(Point3::origin(), devices[0].pos);
+        assert_eq!(UnitQuaternion::identity(), devices[0].rot);
+        assert_eq!(Point3::new(192. * mm, 0., 0.), devices[1].pos);
+        approx::assert_relative_eq!(
+            UnitQuaternion::from(EulerAngle::ZYZ(90. * deg, 30. * deg, 0. * deg)),
+            devices[1].rot,
+            epsilon = 1e-6
+        );
+        approx::assert_relative_eq!(
+            UnitQuaternion::from_axis_angle(&Vector3::z_axis(), 90f32.to_radians()),
+            devices[2].rot,
+            epsilon = 1e-6
+        );
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(GeometryFormat::Toml)]
+    #[case(GeometryFormat::Json)]
+    fn roundtrip(#[case] format: GeometryFormat) -> anyhow::Result<()> {
+        let geometry = Geometry::new(
+            GeometryConfig::parse(TOML, GeometryFormat::Toml)?
+                .devices()?
+                .into_iter()
+                .enumerate()
+                .map(|(i, d)| d.into_device(i as _))
+                .collect(),
+        );
+
+        let config = GeometryConfig::from_geometry(&geometry);
+        let loaded = GeometryConfig::parse(&config.to_string(format)?, format)?;
+        assert_eq!(config, loaded);
+        geometry
+            .iter()
+            .zip(loaded.devices()?)
+            .for_each(|(dev, autd3)| {
+                let dev2 = autd3.into_device(dev.idx() as _);
+                dev.iter().zip(dev2.iter()).for_each(|(a, b)| {
+                    approx::assert_abs_diff_eq!(a.position(), b.position(), epsilon = 1e-3);
+                });
+            });
+
+        Ok(())
+    }
+
+    #[test]
+    fn millimeter() -> anyhow::Result<()> {
+        let geometry = Geometry::new(vec![AUTD3 {
+            pos: Point3::new(10. * mm, 20. * mm, 30. * mm),
+            rot: UnitQuaternion::identity(),
+        }
+        .into_device(0)]);
+
+        let config = GeometryConfig::from_geometry(&geometry);
+        approx::assert_abs_diff_eq!(10., config.devices[0].pos[0], epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(20., config.devices[0].pos[1], epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(30., config.devices[0].pos[2], epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(
+            Point3::new(10. * mm, 20. * mm, 30. * mm),
+            config.devices()?[0].pos,
+            epsilon = 1e-3 * mm
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn save_load() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join("autd3_geometry_save_load.json");
+        let config = GeometryConfig::parse(TOML, GeometryFormat::Toml)?;
+        config.save(&path)?;
+        let loaded = GeometryConfig::load(&path);
+        std::fs::remove_file(&path)?;
+        assert_eq!(config, loaded?);
+
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case(
+        r#"[[devices]]
+pos = [0.0, 0.0]"#
+    )]
+    #[case(
+        r#"[[devices]]
+pos = [0.0, 0.0, 0.0]
+rot = { quaternion = [0.0, 0.0, 0.0, 0.0] }"#
+    )]
+    #[case(
+        r#"[[devices]]
+pos = [0.0, 0.0, 0.0]
+rot = { euler = { order = "ZW", angles = [0.0, 0.0, 0.0] } }"#
+    )]
+    #[case(
+        r#"[[devices]]
+pos = [0.0, 0.0, 0.0]
+rot = { euler = { order = "ZWZ", angles = [0.0, 0.0, 0.0] } }"#
+    )]
+    fn invalid(#[case] content: &str) {
+        assert!(matches!(
+            GeometryConfig::parse(content, GeometryFormat::Toml)
+                .and_then(|config| config.devices()),
+            Err(AUTDError::GeometryFile(_))
+        ));
+    }
+
+    #[test]
+    fn unsupported_extension() {
+        assert!(matches!(
+            GeometryConfig::load("geometry.yaml"),
+            Err(AUTDError::GeometryFile(_))
+        ));
+    }
+}
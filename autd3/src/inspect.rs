@@ -0,0 +1,541 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    num::NonZeroU16,
+    time::Duration,
+};
+
+use autd3_core::{
+    datagram::{LoopBehavior, Segment},
+    defined::ultrasound_freq,
+    gain::Drive,
+    geometry::Geometry,
+    modulation::{Modulation, ModulationError, SamplingConfig},
+};
+use autd3_driver::{
+    datagram::Datagram,
+    error::AUTDDriverError,
+    firmware::{
+        cpu::{check_firmware_err, TxMessage},
+        operation::{Operation, OperationGenerator, OperationHandler},
+    },
+};
+use autd3_firmware_emulator::{CPUEmulator, FPGAEmulator};
+use zerocopy::FromZeros;
+
+/// The option to render [`ModulationInspection`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOption {
+    /// The maximum duration to render. [`LoopBehavior::Infinite`] is rendered for this duration.
+    pub max_duration: Duration,
+    /// The sample rate of the WAV file.
+    pub sample_rate: u32,
+}
+
+impl Default for RenderOption {
+    fn default() -> Self {
+        Self {
+            max_duration: Duration::from_secs(1),
+            sample_rate: 48000,
+        }
+    }
+}
+
+/// The modulation data with its [`SamplingConfig`] and [`LoopBehavior`] to verify what will be emitted.
+///
+/// The envelope can be rendered into a WAV file with [`ModulationInspection::write_wav`] or an SVG plot with [`ModulationInspection::to_svg`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModulationInspection {
+    /// The modulation data.
+    pub buffer: Vec<u8>,
+    /// The sampling configuration of the modulation data.
+    pub sampling_config: SamplingConfig,
+    /// The loop behavior of the modulation data.
+    pub loop_behavior: LoopBehavior,
+}
+
+impl ModulationInspection {
+    /// Calculates the [`Modulation`] to be played with `loop_behavior`.
+    pub fn new<M: Modulation>(m: M, loop_behavior: LoopBehavior) -> Result<Self, ModulationError> {
+        let sampling_config = m.sampling_config()?;
+        Ok(Self {
+            buffer: m.calc()?,
+            sampling_config,
+            loop_behavior,
+        })
+    }
+
+    /// Reads the modulation written in the `segment` of the emulated FPGA, e.g., [`Audit`].
+    ///
+    /// [`Audit`]: crate::link::Audit
+    pub fn from_fpga(fpga: &FPGAEmulator, segment: Segment) -> Self {
+        Self {
+            buffer: fpga.modulation_buffer(segment),
+            sampling_config: SamplingConfig {
+                division: NonZeroU16::new(fpga.modulation_freq_division(segment))
+                    .unwrap_or(NonZeroU16::MIN),
+            },
+            loop_behavior: fpga.modulation_loop_behavior(segment),
+        }
+    }
+
+    /// The duration of one cycle of the modulation.
+    pub fn cycle_duration(&self) -> Duration {
+        self.samples_duration(self.buffer.len() as _)
+    }
+
+    /// The duration to be rendered, that is, the duration of the whole loop limited by [`RenderOption::max_duration`].
+    pub fn render_duration(&self, option: &RenderOption) -> Duration {
+        match self.loop_behavior {
+            LoopBehavior::Infinite => option.max_duration,
+            LoopBehavior::Finite(rep) => {
+                (self.cycle_duration() * rep.get() as u32).min(option.max_duration)
+            }
+        }
+    }
+
+    /// Writes the envelope as an 8-bit mono PCM WAV file with [`RenderOption::sample_rate`].
+    pub fn write_wav<W: Write>(&self, mut w: W, option: &RenderOption) -> io::Result<()> {
+        let n = (self.render_duration(option).as_nanos() * option.sample_rate as u128
+            / 1_000_000_000) as u32;
+
+        w.write_all(b"RIFF")?;
+        w.write_all(&(36 + n).to_le_bytes())?;
+        w.write_all(b"WAVEfmt ")?;
+        w.write_all(&16u32.to_le_bytes())?;
+        w.write_all(&1u16.to_le_bytes())?; // PCM
+        w.write_all(&1u16.to_le_bytes())?; // mono
+        w.write_all(&option.sample_rate.to_le_bytes())?;
+        w.write_all(&option.sample_rate.to_le_bytes())?; // byte rate
+        w.write_all(&1u16.to_le_bytes())?; // block align
+        w.write_all(&8u16.to_le_bytes())?; // bits per sample
+        w.write_all(b"data")?;
+        w.write_all(&n.to_le_bytes())?;
+
+        let data = (0..n as u128)
+            .map(|i| {
+                self.value_at(
+                    i * ultrasound_freq().hz() as u128
+                        / (option.sample_rate as u128
+                            * self.sampling_config.division.get() as u128),
+                )
+            })
+            .collect::<Vec<_>>();
+        w.write_all(&data)
+    }
+
+    /// Renders the envelope versus time as an SVG step plot annotated with the [`SamplingConfig`] and [`LoopBehavior`].
+    pub fn to_svg(&self, option: &RenderOption) -> String {
+        const WIDTH: f32 = 800.;
+        const HEIGHT: f32 = 300.;
+        const MARGIN: f32 = 40.;
+        const MAX_CYCLE_LINES: usize = 100;
+
+        let duration = self.render_duration(option);
+        let num_samples = self.samples_in(duration);
+        let x = |samples: u128| {
+            MARGIN
+                + (WIDTH - 2. * MARGIN)
+                    * if duration.is_zero() {
+                        0.
+                    } else {
+                        self.samples_duration(samples).as_secs_f32() / duration.as_secs_f32()
+                    }
+        };
+        let y = |v: u8| HEIGHT - MARGIN - (HEIGHT - 2. * MARGIN) * v as f32 / u8::MAX as f32;
+
+        let mut svg = String::new();
+        _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{HEIGHT}" viewBox="0 0 {WIDTH} {HEIGHT}">"#
+        );
+        _ = writeln!(
+            svg,
+            r#"<text x="{MARGIN}" y="{}" font-family="monospace" font-size="12">{:?} (division: {}), {:?}, {} samples/cycle</text>"#,
+            MARGIN / 2.,
+            self.sampling_config.freq(),
+            self.sampling_config.division.get(),
+            self.loop_behavior,
+            self.buffer.len(),
+        );
+        _ = writeln!(
+            svg,
+            r#"<path d="M{MARGIN},{MARGIN} V{0} H{1}" fill="none" stroke="black"/>"#,
+            HEIGHT - MARGIN,
+            WIDTH - MARGIN,
+        );
+        [0, u8::MAX].iter().for_each(|&v| {
+            _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" font-family="monospace" font-size="10" text-anchor="end">{v}</text>"#,
+                MARGIN - 4.,
+                y(v) + 4.,
+            );
+        });
+        _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-family="monospace" font-size="10" text-anchor="end">{duration:?}</text>"#,
+            WIDTH - MARGIN,
+            HEIGHT - MARGIN + 14.,
+        );
+
+        let len = self.buffer.len() as u128;
+        if len > 0 && num_samples / len <= MAX_CYCLE_LINES as u128 {
+            (1..num_samples.div_ceil(len)).for_each(|k| {
+                _ = writeln!(
+                    svg,
+                    r#"<line x1="{0}" y1="{MARGIN}" x2="{0}" y2="{1}" stroke="gray" stroke-dasharray="4"/>"#,
+                    x(k * len),
+                    HEIGHT - MARGIN,
+                );
+            });
+        }
+
+        let mut points = String::new();
+        (0..num_samples).for_each(|i| {
+            let v = y(self.value_at(i));
+            _ = write!(points, "{},{v} {},{v} ", x(i), x(i + 1));
+        });
+        _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="blue"/>"#,
+            points.trim_end()
+        );
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    fn value_at(&self, sample: u128) -> u8 {
+        if self.buffer.is_empty() {
+            return 0;
+        }
+        self.buffer[(sample % self.buffer.len() as u128) as usize]
+    }
+
+    fn samples_duration(&self, samples: u128) -> Duration {
+        Duration::from_nanos(
+            (samples * self.sampling_config.division.get() as u128 * 1_000_000_000
+                / ultrasound_freq().hz() as u128) as u64,
+        )
+    }
+
+    fn samples_in(&self, duration: Duration) -> u128 {
+        (duration.as_nanos() * ultrasound_freq().hz() as u128)
+            .div_ceil(self.sampling_config.division.get() as u128 * 1_000_000_000)
+    }
+}
+
+/// The inspection result of a device, which is what the device emits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceInspection {
+    /// The modulation of the current segment.
+    pub modulation: ModulationInspection,
+    /// The phase and intensity of each pattern of the current STM segment. A [`Gain`] has only one pattern.
+    ///
+    /// [`Gain`]: autd3_core::gain::Gain
+    pub drives: Vec<Vec<Drive>>,
+}
+
+impl DeviceInspection {
+    /// Reads the current segments of the emulated FPGA.
+    pub fn from_fpga(fpga: &FPGAEmulator) -> Self {
+        let segment = fpga.current_stm_segment();
+        Self {
+            modulation: ModulationInspection::from_fpga(fpga, fpga.current_mod_segment()),
+            drives: (0..fpga.stm_cycle(segment))
+                .map(|idx| fpga.drives_at(segment, idx))
+                .collect(),
+        }
+    }
+}
+
+/// Inspects what the devices emit after the [`Datagram`] is sent.
+///
+/// The [`Datagram`] is sent to the emulated devices just after opening, instead of the actual devices. Any [`Datagram`], including composite ones such as tuples and [`Group`], is inspected as a whole.
+/// The result is indexed by the device index and is `None` for the disabled devices.
+///
+/// # Examples
+///
+/// ```
+/// use autd3::prelude::*;
+/// use autd3::inspect::inspect;
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let autd = Controller::open([AUTD3::default()], Nop::new())?;
+/// let result = inspect(
+///     &autd,
+///     (
+///         Sine {
+///             freq: 150. * Hz,
+///             option: Default::default(),
+///         },
+///         Uniform {
+///             intensity: EmitIntensity::MAX,
+///             phase: Phase::ZERO,
+///         },
+///     ),
+/// )?;
+/// assert_eq!(1, result[0].as_ref().unwrap().drives.len());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Group`]: crate::gain::Group
+pub fn inspect<D: Datagram>(
+    geometry: &Geometry,
+    d: D,
+) -> Result<Vec<Option<DeviceInspection>>, AUTDDriverError>
+where
+    AUTDDriverError: From<D::Error>,
+    D::G: OperationGenerator,
+    AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+        + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+{
+    let mut cpus = geometry
+        .iter()
+        .map(|dev| CPUEmulator::new(dev.idx(), dev.num_transducers()))
+        .collect::<Vec<_>>();
+    let mut tx = vec![TxMessage::new_zeroed(); geometry.len()];
+
+    let mut operations =
+        OperationHandler::generate(d.operation_generator(geometry, false)?, geometry);
+    while !OperationHandler::is_done(&operations) {
+        OperationHandler::pack(&mut operations, geometry, &mut tx, false)?;
+        cpus.iter_mut()
+            .zip(geometry.iter())
+            .filter(|(_, dev)| dev.enable)
+            .try_for_each(|(cpu, _)| {
+                cpu.send(&tx);
+                cpu.update();
+                check_firmware_err(&cpu.rx())
+            })?;
+    }
+
+    Ok(geometry
+        .iter()
+        .zip(cpus.iter())
+        .map(|(dev, cpu)| dev.enable.then(|| DeviceInspection::from_fpga(cpu.fpga())))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use autd3_core::gain::{EmitIntensity, Phase};
+    use autd3_driver::datagram::{GainSTM, GainSTMOption, WithLoopBehavior};
+
+    use super::*;
+
+    use crate::{
+        controller::tests::create_controller,
+        gain::{Group, Uniform},
+        modulation::Custom,
+    };
+
+    fn inspection(loop_behavior: LoopBehavior) -> ModulationInspection {
+        ModulationInspection {
+            buffer: vec![0x00, 0xFF],
+            sampling_config: SamplingConfig {
+                division: NonZeroU16::new(10).unwrap(),
+            },
+            loop_behavior,
+        }
+    }
+
+    #[test]
+    fn new() -> anyhow::Result<()> {
+        let m = Custom {
+            buffer: vec![0x00, 0x80, 0xFF],
+            sampling_config: SamplingConfig::DIV_10,
+        };
+        assert_eq!(
+            ModulationInspection {
+                buffer: vec![0x00, 0x80, 0xFF],
+                sampling_config: SamplingConfig::DIV_10,
+                loop_behavior: LoopBehavior::ONCE,
+            },
+            ModulationInspection::new(m, LoopBehavior::ONCE)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_fpga() -> anyhow::Result<()> {
+        let mut autd = create_controller(1)?;
+        autd.send(WithLoopBehavior {
+            inner: Custom {
+                buffer: vec![0x00, 0x80, 0xFF],
+                sampling_config: SamplingConfig::DIV_10,
+            },
+            segment: Segment::S1,
+            transition_mode: None,
+            loop_behavior: LoopBehavior::Finite(NonZeroU16::new(3).unwrap()),
+        })?;
+
+        assert_eq!(
+            ModulationInspection {
+                buffer: vec![0x00, 0x80, 0xFF],
+                sampling_config: SamplingConfig::DIV_10,
+                loop_behavior: LoopBehavior::Finite(NonZeroU16::new(3).unwrap()),
+            },
+            ModulationInspection::from_fpga(autd.link()[0].fpga(), Segment::S1)
+        );
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[case(Duration::from_secs(1), LoopBehavior::Infinite)]
+    #[case(Duration::from_micros(500), LoopBehavior::ONCE)]
+    #[case(Duration::from_millis(1), LoopBehavior::Finite(NonZeroU16::new(2).unwrap()))]
+    #[test]
+    fn render_duration(#[case] expect: Duration, #[case] loop_behavior: LoopBehavior) {
+        assert_eq!(
+            expect,
+            inspection(loop_behavior).render_duration(&RenderOption::default())
+        );
+    }
+
+    #[test]
+    fn write_wav() -> anyhow::Result<()> {
+        let mut wav = Vec::new();
+        inspection(LoopBehavior::Finite(NonZeroU16::new(2).unwrap())).write_wav(
+            &mut wav,
+            &RenderOption {
+                sample_rate: 8000,
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(44 + 8, wav.len());
+        assert_eq!(b"RIFF", &wav[0..4]);
+        assert_eq!(44u32.to_le_bytes(), wav[4..8]);
+        assert_eq!(b"WAVEfmt ", &wav[8..16]);
+        assert_eq!(8000u32.to_le_bytes(), wav[24..28]);
+        assert_eq!(8u16.to_le_bytes(), wav[34..36]);
+        assert_eq!(b"data", &wav[36..40]);
+        assert_eq!(8u32.to_le_bytes(), wav[40..44]);
+        assert_eq!([0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0xFF, 0xFF], wav[44..]);
+        Ok(())
+    }
+
+    #[test]
+    fn to_svg() {
+        let svg = inspection(LoopBehavior::Finite(NonZeroU16::new(2).unwrap()))
+            .to_svg(&RenderOption::default());
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert!(svg.contains("4000 Hz (division: 10), Finite(2), 2 samples/cycle"));
+        assert_eq!(1, svg.matches("<line").count());
+        assert_eq!(
+            Some(4 * 2),
+            svg.lines()
+                .find_map(|l| l.strip_prefix(r#"<polyline points=""#))
+                .and_then(|l| l.split('"').next())
+                .map(|p| p.split(' ').count())
+        );
+    }
+
+    fn uniform(intensity: u8, phase: u8) -> Uniform {
+        Uniform {
+            intensity: EmitIntensity(intensity),
+            phase: Phase(phase),
+        }
+    }
+
+    fn drive(intensity: u8, phase: u8) -> Drive {
+        Drive {
+            phase: Phase(phase),
+            intensity: EmitIntensity(intensity),
+        }
+    }
+
+    #[test]
+    fn inspect_composite() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        let d = || {
+            (
+                Custom {
+                    buffer: vec![0x00, 0x80, 0xFF],
+                    sampling_config: SamplingConfig::DIV_10,
+                },
+                Group {
+                    key_map: |_dev| |tr| Some(tr.idx() < 100),
+                    gain_map: HashMap::from([
+                        (true, uniform(0x80, 0x40)),
+                        (false, uniform(0xFF, 0x00)),
+                    ]),
+                },
+            )
+        };
+
+        let result = autd.inspect(d())?;
+        autd.send(d())?;
+
+        assert_eq!(
+            autd.link()
+                .iter()
+                .map(|cpu| Some(DeviceInspection::from_fpga(cpu.fpga())))
+                .collect::<Vec<_>>(),
+            result
+        );
+        result.iter().try_for_each(|r| {
+            let r = r.as_ref().ok_or(anyhow::anyhow!("device is disabled"))?;
+            assert_eq!(vec![0x00, 0x80, 0xFF], r.modulation.buffer);
+            assert_eq!(1, r.drives.len());
+            assert_eq!(drive(0x80, 0x40), r.drives[0][0]);
+            assert_eq!(drive(0xFF, 0x00), r.drives[0][100]);
+            anyhow::Ok(())
+        })?;
+        Ok(())
+    }
+
+    #[test]
+    fn inspect_stm_with_disabled() -> anyhow::Result<()> {
+        let mut autd = create_controller(2)?;
+        autd[1].enable = false;
+
+        let result = inspect(
+            &autd,
+            GainSTM {
+                gains: vec![uniform(0x01, 0x02), uniform(0x03, 0x04)],
+                config: SamplingConfig::FREQ_MIN,
+                option: GainSTMOption::default(),
+            },
+        )?;
+
+        assert_eq!(2, result.len());
+        assert!(result[1].is_none());
+        let r = result[0]
+            .as_ref()
+            .ok_or(anyhow::anyhow!("device is disabled"))?;
+        assert_eq!(vec![0xFF, 0xFF], r.modulation.buffer);
+        assert_eq!(
+            vec![
+                vec![drive(0x01, 0x02); autd[0].num_transducers()],
+                vec![drive(0x03, 0x04); autd[0].num_transducers()],
+            ],
+            r.drives
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn inspect_err() -> anyhow::Result<()> {
+        let autd = create_controller(1)?;
+        assert_eq!(
+            Some(AUTDDriverError::InvalidSilencerSettings),
+            inspect(
+                &autd,
+                GainSTM {
+                    gains: vec![uniform(0x01, 0x02), uniform(0x03, 0x04)],
+                    config: SamplingConfig::DIV_10,
+                    option: GainSTMOption::default(),
+                },
+            )
+            .err()
+        );
+        Ok(())
+    }
+}
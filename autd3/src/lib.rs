@@ -8,6 +8,21 @@
 //! Airborne Ultrasound Tactile Display (AUTD) is a midair haptic device that can remotely produce tactile sensation on a human skin surface without wearing devices.
 //! Please see [our laboratory homepage](https://hapislab.org/en/airborne-ultrasound-tactile-display) for more details on AUTD.
 //! This crate is a client library to drive AUTD version 3 devices. This cross-platform library supports Windows, macOS, and Linux (including Single Board Computer such as Raspberry Pi).
+//!
+//! # Feature flags
+//!
+//! - `async` (default): Enables the asynchronous [`Controller`](crate::async::Controller).
+//! - `stm` (default): Enables [`FociSTM`], [`GainSTM`] and the utilities in [`datagram::stm`]. If only static [`Gain`]s and [`Modulation`]s are used, disabling this feature reduces the code size (the release rlibs of `autd3-driver` and `autd3` are about 33% and 19% smaller on x86_64 Linux, respectively).
+//! - `parallel` (default): Uses `rayon` to pack the operations of the devices and to calculate [`PrecomputedGains`](crate::datagram::stm::PrecomputedGains) in parallel. Without this feature, they are processed sequentially.
+//! - `dynamic_freq`: Enables to change the ultrasound frequency with `Controller::open_with_freq`.
+//! - `inspect`: Enables the [`inspect`] module to render the modulation into a WAV file or an SVG plot for debugging.
+//! - `telemetry`: Records a unique `id` in the `send` span of each [`Datagram`](autd3_core::datagram::Datagram), so that the events of the same send can be correlated by a subscriber.
+//! - `serde`: Implements `serde::Serialize` and `serde::Deserialize` for the core value types, e.g., [`Drive`](autd3_core::gain::Drive), [`SamplingConfig`](autd3_core::modulation::SamplingConfig), [`Geometry`](autd3_core::geometry::Geometry), and [`FirmwareVersion`](autd3_driver::firmware::version::FirmwareVersion), implements `serde::Serialize` for [`DiagnosticsReport`](crate::controller::DiagnosticsReport), and enables loading and saving the arrangement of devices from TOML/JSON files with [`GeometryConfig`](crate::geometry::GeometryConfig).
+//!
+//! [`FociSTM`]: autd3_driver::datagram::FociSTM
+//! [`GainSTM`]: autd3_driver::datagram::GainSTM
+//! [`Gain`]: autd3_core::gain::Gain
+//! [`Modulation`]: autd3_core::modulation::Modulation
 
 /// [`Controller`] module.
 pub mod controller;
@@ -27,6 +42,16 @@ pub mod link;
 /// Prelude module.
 pub mod prelude;
 
+/// Loading and saving the arrangement of devices.
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+#[cfg(feature = "serde")]
+pub mod geometry;
+
+/// Rendering the modulation for debugging.
+#[cfg_attr(docsrs, doc(cfg(feature = "inspect")))]
+#[cfg(feature = "inspect")]
+pub mod inspect;
+
 /// Asynchronous module.
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 #[cfg(feature = "async")]
@@ -1,12 +1,96 @@
+use std::time::{Duration, Instant};
+
 use autd3_core::{
     geometry::Geometry,
     link::{Link, LinkError},
 };
 
 use autd3_driver::firmware::cpu::{RxMessage, TxMessage};
-use autd3_firmware_emulator::CPUEmulator;
+use autd3_firmware_emulator::{CPUEmulator, TimingModel};
 
 use derive_more::{Deref, DerefMut};
+use zerocopy::FromZeros;
+
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub struct ReceiveContext {
+    /// The time elapsed since the link was opened.
+    pub elapsed: Duration,
+    /// The number of polls since the last new message was sent.
+    pub polls: usize,
+    /// The response returned at the previous poll.
+    pub previous: RxMessage,
+}
+
+type PollHandler = Box<dyn FnMut(&ReceiveContext, &mut CPUEmulator) + Send>;
+type ResponseHandler = Box<dyn FnMut(&ReceiveContext, &mut RxMessage) + Send>;
+
+#[doc(hidden)]
+#[derive(Default)]
+pub struct Scenario {
+    poll_handlers: Vec<(usize, PollHandler)>,
+    response_handlers: Vec<(usize, ResponseHandler)>,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls `f` with the emulator of the device at `idx` before updating it at each poll.
+    pub fn on_poll(
+        mut self,
+        idx: usize,
+        f: impl FnMut(&ReceiveContext, &mut CPUEmulator) + Send + 'static,
+    ) -> Self {
+        self.poll_handlers.push((idx, Box::new(f)));
+        self
+    }
+
+    /// Calls `f` with the response of the device at `idx` before returning it at each poll.
+    pub fn on_response(
+        mut self,
+        idx: usize,
+        f: impl FnMut(&ReceiveContext, &mut RxMessage) + Send + 'static,
+    ) -> Self {
+        self.response_handlers.push((idx, Box::new(f)));
+        self
+    }
+
+    /// The device at `idx` returns the previous response for the first `polls` polls after each new message.
+    pub fn stale_response(self, idx: usize, polls: usize) -> Self {
+        self.on_response(idx, move |ctx, rx| {
+            if ctx.polls < polls {
+                *rx = ctx.previous;
+            }
+        })
+    }
+
+    /// The device at `idx` returns the message ID of the message before the last one after the first `polls` polls after each new message, as if an old frame were duplicated.
+    pub fn duplicated_response(self, idx: usize, polls: usize) -> Self {
+        self.on_response(idx, move |ctx, rx| {
+            if ctx.polls >= polls {
+                *rx = RxMessage::new(rx.data(), rx.ack().wrapping_sub(1) & 0x7F);
+            }
+        })
+    }
+
+    /// The thermal sensor of the device at `idx` is asserted after `after` has elapsed since opening.
+    pub fn thermal_assert_after(self, idx: usize, after: Duration) -> Self {
+        self.on_poll(idx, move |ctx, cpu| {
+            if ctx.elapsed >= after {
+                cpu.fpga_mut().assert_thermal_sensor();
+            }
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct DeviceState {
+    msg_id: Option<u8>,
+    polls: usize,
+    previous: RxMessage,
+}
 
 #[derive(Default)]
 #[doc(hidden)]
@@ -14,6 +98,8 @@ pub struct AuditOption {
     pub initial_msg_id: Option<u8>,
     pub initial_phase_corr: Option<u8>,
     pub down: bool,
+    pub timing_model: Option<TimingModel>,
+    pub scenario: Scenario,
 }
 
 #[doc(hidden)]
@@ -26,6 +112,8 @@ pub struct Audit {
     cpus: Vec<CPUEmulator>,
     down: bool,
     broken: bool,
+    opened_at: Instant,
+    states: Vec<DeviceState>,
 }
 
 impl Audit {
@@ -36,6 +124,8 @@ impl Audit {
             cpus: Vec::new(),
             down: false,
             broken: false,
+            opened_at: Instant::now(),
+            states: Vec::new(),
         }
     }
 
@@ -54,16 +144,14 @@ impl Audit {
     pub fn repair(&mut self) {
         self.broken = false;
     }
-}
 
-impl Link for Audit {
-    fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
-        self.is_open = true;
-        self.cpus = geometry
+    fn create_cpus(&self, geometry: &Geometry) -> Vec<CPUEmulator> {
+        geometry
             .iter()
             .enumerate()
             .map(|(i, dev)| {
                 let mut cpu = CPUEmulator::new(i, dev.num_transducers());
+                cpu.set_timing_model(self.option.timing_model.clone());
                 if let Some(msg_id) = self.option.initial_msg_id {
                     cpu.set_last_msg_id(msg_id);
                 }
@@ -76,12 +164,48 @@ impl Link for Audit {
                 }
                 cpu
             })
-            .collect();
+            .collect()
+    }
+
+    fn create_states(geometry: &Geometry) -> Vec<DeviceState> {
+        vec![
+            DeviceState {
+                msg_id: None,
+                polls: 0,
+                previous: RxMessage::new_zeroed(),
+            };
+            geometry.len()
+        ]
+    }
+}
+
+impl Link for Audit {
+    fn preflight(&mut self, _: &Geometry) -> Result<(), LinkError> {
+        if self.broken {
+            return Err(LinkError::new("broken".to_owned()));
+        }
+        Ok(())
+    }
+
+    fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.is_open = true;
+        self.cpus = self.create_cpus(geometry);
+        self.states = Self::create_states(geometry);
+        self.opened_at = Instant::now();
         self.down = self.option.down;
         self.broken = false;
         Ok(())
     }
 
+    fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        if self.broken {
+            return Err(LinkError::new("broken".to_owned()));
+        }
+        self.cpus = self.create_cpus(geometry);
+        self.states = Self::create_states(geometry);
+        Ok(())
+    }
+
     fn close(&mut self) -> Result<(), LinkError> {
         self.is_open = false;
         Ok(())
@@ -97,8 +221,20 @@ impl Link for Audit {
         }
 
         self.cpus.iter_mut().for_each(|cpu| {
+            if cpu.timing_model().is_some() {
+                cpu.update();
+            }
             cpu.send(tx);
         });
+        self.states
+            .iter_mut()
+            .zip(tx.iter())
+            .for_each(|(state, tx)| {
+                if state.msg_id != Some(tx.header.msg_id) {
+                    state.msg_id = Some(tx.header.msg_id);
+                    state.polls = 0;
+                }
+            });
 
         Ok(true)
     }
@@ -112,10 +248,35 @@ impl Link for Audit {
             return Ok(false);
         }
 
-        self.cpus.iter_mut().for_each(|cpu| {
-            cpu.update();
-            rx[cpu.idx()] = cpu.rx();
-        });
+        let elapsed = self.opened_at.elapsed();
+        let Scenario {
+            poll_handlers,
+            response_handlers,
+        } = &mut self.option.scenario;
+        self.cpus
+            .iter_mut()
+            .zip(self.states.iter_mut())
+            .for_each(|(cpu, state)| {
+                let idx = cpu.idx();
+                let ctx = ReceiveContext {
+                    elapsed,
+                    polls: state.polls,
+                    previous: state.previous,
+                };
+                poll_handlers
+                    .iter_mut()
+                    .filter(|(i, _)| *i == idx)
+                    .for_each(|(_, f)| f(&ctx, cpu));
+                cpu.update();
+                let mut res = cpu.rx();
+                response_handlers
+                    .iter_mut()
+                    .filter(|(i, _)| *i == idx)
+                    .for_each(|(_, f)| f(&ctx, &mut res));
+                rx[idx] = res;
+                state.previous = res;
+                state.polls += 1;
+            });
 
         Ok(true)
     }
@@ -132,6 +293,10 @@ use autd3_core::link::AsyncLink;
 #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
 #[cfg_attr(feature = "async-trait", autd3_core::async_trait)]
 impl AsyncLink for Audit {
+    async fn preflight(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        <Self as Link>::preflight(self, geometry)
+    }
+
     async fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
         <Self as Link>::open(self, geometry)
     }
@@ -140,6 +305,10 @@ impl AsyncLink for Audit {
         <Self as Link>::close(self)
     }
 
+    async fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        <Self as Link>::reconfigure(self, geometry)
+    }
+
     async fn send(&mut self, tx: &[TxMessage]) -> Result<bool, LinkError> {
         <Self as Link>::send(self, tx)
     }
@@ -0,0 +1,285 @@
+use std::{
+    num::NonZeroU64,
+    time::{Duration, Instant},
+};
+
+use autd3_core::{
+    derive::*,
+    link::{Link, LinkError},
+};
+
+use autd3_driver::firmware::cpu::{RxMessage, TxMessage};
+use autd3_firmware_emulator::CPUEmulator;
+use zerocopy::FromZeros;
+
+/// The option of [`Bench`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchOption {
+    /// The latency from sending data to receiving its response. The default is 0.
+    pub latency: Duration,
+    /// The maximum jitter added to [`latency`]. The jitter is uniformly distributed in `[0, jitter]`. The default is 0.
+    ///
+    /// [`latency`]: BenchOption::latency
+    pub jitter: Duration,
+    /// The throughput of the link in bytes per second. The transfer time of the data is added to the latency. If `None`, the transfer time is 0. The default is `None`.
+    pub throughput: Option<NonZeroU64>,
+    /// The seed of the pseudo-random number generator for the jitter. The default is 0.
+    pub seed: u64,
+}
+
+impl Default for BenchOption {
+    fn default() -> Self {
+        Self {
+            latency: Duration::ZERO,
+            jitter: Duration::ZERO,
+            throughput: None,
+            seed: 0,
+        }
+    }
+}
+
+/// A [`Link`] with deterministic timing for benchmarks.
+///
+/// The data are processed by the emulators immediately, but the responses become available only after the delay of [`BenchOption`] elapses. Until then, [`Link::receive`] returns the previous responses.
+/// The sequence of the delays is determined by [`BenchOption::seed`], so that the benchmarks of the sender are reproducible without hardware.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use autd3::prelude::*;
+/// use autd3::link::{Bench, BenchOption};
+///
+/// # fn main() -> Result<(), AUTDError> {
+/// let mut autd = Controller::open(
+///     [AUTD3::default()],
+///     Bench::new(BenchOption {
+///         latency: Duration::from_micros(100),
+///         jitter: Duration::from_micros(20),
+///         ..Default::default()
+///     }),
+/// )?;
+/// autd.send(Static::default())?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Bench {
+    option: BenchOption,
+    is_open: bool,
+    cpus: Vec<CPUEmulator>,
+    rx: Vec<RxMessage>,
+    rng: u64,
+    ready_at: Option<Instant>,
+}
+
+impl Bench {
+    /// Creates a new [`Bench`].
+    pub const fn new(option: BenchOption) -> Self {
+        Self {
+            option,
+            is_open: false,
+            cpus: Vec::new(),
+            rx: Vec::new(),
+            rng: option.seed,
+            ready_at: None,
+        }
+    }
+
+    // SplitMix64
+    fn next_u64(&mut self) -> u64 {
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_delay(&mut self, bytes: usize) -> Duration {
+        let jitter = if self.option.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            let max = self.option.jitter.as_nanos() as u64;
+            Duration::from_nanos(self.next_u64() % (max + 1))
+        };
+        let transfer = self.option.throughput.map_or(Duration::ZERO, |t| {
+            Duration::from_nanos((bytes as u128 * 1_000_000_000 / t.get() as u128) as u64)
+        });
+        self.option.latency + jitter + transfer
+    }
+}
+
+impl Link for Bench {
+    fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.is_open = true;
+        self.rng = self.option.seed;
+        <Self as Link>::reconfigure(self, geometry)
+    }
+
+    fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.cpus = geometry
+            .iter()
+            .enumerate()
+            .map(|(i, dev)| CPUEmulator::new(i, dev.num_transducers()))
+            .collect();
+        self.rx = vec![RxMessage::new_zeroed(); self.cpus.len()];
+        self.ready_at = None;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), LinkError> {
+        self.is_open = false;
+        Ok(())
+    }
+
+    fn send(&mut self, tx: &[TxMessage]) -> Result<bool, LinkError> {
+        self.cpus.iter_mut().for_each(|cpu| {
+            cpu.send(tx);
+        });
+        let delay = self.next_delay(std::mem::size_of_val(tx));
+        self.ready_at = Some(Instant::now() + delay);
+
+        Ok(true)
+    }
+
+    fn receive(&mut self, rx: &mut [RxMessage]) -> Result<bool, LinkError> {
+        if self.ready_at.is_some_and(|t| t <= Instant::now()) {
+            self.ready_at = None;
+            self.cpus.iter_mut().for_each(|cpu| {
+                cpu.update();
+                self.rx[cpu.idx()] = cpu.rx();
+            });
+        }
+        rx.copy_from_slice(&self.rx);
+
+        Ok(true)
+    }
+
+    fn is_open(&self) -> bool {
+        self.is_open
+    }
+}
+
+#[cfg(feature = "async")]
+use autd3_core::link::AsyncLink;
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[cfg_attr(feature = "async-trait", autd3_core::async_trait)]
+impl AsyncLink for Bench {
+    async fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        <Self as Link>::open(self, geometry)
+    }
+
+    async fn close(&mut self) -> Result<(), LinkError> {
+        <Self as Link>::close(self)
+    }
+
+    async fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        <Self as Link>::reconfigure(self, geometry)
+    }
+
+    async fn send(&mut self, tx: &[TxMessage]) -> Result<bool, LinkError> {
+        <Self as Link>::send(self, tx)
+    }
+
+    async fn receive(&mut self, rx: &mut [RxMessage]) -> Result<bool, LinkError> {
+        <Self as Link>::receive(self, rx)
+    }
+
+    fn is_open(&self) -> bool {
+        <Self as Link>::is_open(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::{autd3_device::AUTD3, error::AUTDDriverError, geometry::IntoDevice};
+    use spin_sleep::SpinSleeper;
+
+    use crate::{
+        controller::{Controller, SenderOption},
+        modulation::Static,
+    };
+
+    use super::*;
+
+    fn option(seed: u64) -> BenchOption {
+        BenchOption {
+            latency: Duration::from_micros(100),
+            jitter: Duration::from_micros(50),
+            throughput: NonZeroU64::new(1_000_000),
+            seed,
+        }
+    }
+
+    #[rstest::rstest]
+    #[case(Duration::ZERO, BenchOption::default(), 1000)]
+    #[case(Duration::from_micros(100), BenchOption { latency: Duration::from_micros(100), ..Default::default() }, 1000)]
+    #[case(Duration::from_millis(1), BenchOption { throughput: NonZeroU64::new(1_000_000), ..Default::default() }, 1000)]
+    #[case(Duration::from_micros(1100), BenchOption { latency: Duration::from_micros(100), throughput: NonZeroU64::new(1_000_000), ..Default::default() }, 1000)]
+    #[test]
+    fn delay_without_jitter(
+        #[case] expect: Duration,
+        #[case] option: BenchOption,
+        #[case] bytes: usize,
+    ) {
+        let mut link = Bench::new(option);
+        assert_eq!(expect, link.next_delay(bytes));
+        assert_eq!(expect, link.next_delay(bytes));
+    }
+
+    #[test]
+    fn delay_deterministic() {
+        let delays = |seed| {
+            let mut link = Bench::new(option(seed));
+            (0..100).map(|_| link.next_delay(0)).collect::<Vec<_>>()
+        };
+
+        let a = delays(0);
+        assert_eq!(a, delays(0));
+        assert_ne!(a, delays(1));
+        assert!(a
+            .iter()
+            .all(|d| (Duration::from_micros(100)..=Duration::from_micros(150)).contains(d)));
+        assert!(a.iter().any(|&d| d != a[0]));
+    }
+
+    #[test]
+    fn open_resets_seed() -> anyhow::Result<()> {
+        let geometry = Geometry::new(vec![AUTD3::default().into_device(0)]);
+        let mut link = Bench::new(option(0));
+        let a = (0..10).map(|_| link.next_delay(0)).collect::<Vec<_>>();
+        <Bench as Link>::open(&mut link, &geometry)?;
+        let b = (0..10).map(|_| link.next_delay(0)).collect::<Vec<_>>();
+        assert_eq!(a, b);
+        Ok(())
+    }
+
+    #[test]
+    fn send_receive() -> anyhow::Result<()> {
+        let latency = Duration::from_millis(5);
+        let mut autd = Controller::open(
+            [AUTD3::default(); 2],
+            Bench::new(BenchOption {
+                latency,
+                ..Default::default()
+            }),
+        )?;
+
+        let start = Instant::now();
+        autd.send(Static::default())?;
+        assert!(start.elapsed() >= latency);
+
+        assert_eq!(
+            Err(AUTDDriverError::ConfirmResponseFailed),
+            autd.sender(SenderOption::<SpinSleeper> {
+                timeout: Some(Duration::from_millis(1)),
+                ..Default::default()
+            })
+            .send(Static::default())
+        );
+
+        autd.close()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,414 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use autd3_core::{
+    geometry::Geometry,
+    link::{Link, LinkError, RxMessage, TxMessage},
+};
+
+use derive_more::{Deref, DerefMut};
+
+/// The option of [`Instrumented`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstrumentedOption {
+    /// The number of the latest round trips of each device kept for the statistics.
+    pub window: usize,
+    /// The width of each bin of the histogram.
+    pub bin_width: Duration,
+    /// The number of bins of the histogram. The last bin also counts the round trips longer than the range.
+    pub num_bins: usize,
+}
+
+impl Default for InstrumentedOption {
+    fn default() -> Self {
+        Self {
+            window: 1000,
+            bin_width: Duration::from_micros(100),
+            num_bins: 100,
+        }
+    }
+}
+
+/// The round trip latency statistics of a device.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LatencyStatistics {
+    /// The number of round trips in the window.
+    pub count: usize,
+    /// The minimum latency.
+    pub min: Duration,
+    /// The maximum latency.
+    pub max: Duration,
+    /// The mean latency.
+    pub mean: Duration,
+    /// The jitter, i.e., the standard deviation of the latency.
+    pub jitter: Duration,
+    /// The histogram of the latency. See [`InstrumentedOption::bin_width`] and [`InstrumentedOption::num_bins`].
+    pub histogram: Vec<usize>,
+}
+
+/// The statistics measured by [`Instrumented`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LinkStatistics {
+    /// The round trip latency statistics of each device.
+    pub devices: Vec<LatencyStatistics>,
+    /// The number of frames sent since the link was opened.
+    pub frames_sent: u64,
+    /// The number of bytes sent since the link was opened.
+    pub bytes_sent: u64,
+    /// The time elapsed since the link was opened.
+    pub elapsed: Duration,
+}
+
+impl LinkStatistics {
+    /// The number of frames sent per second.
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.;
+        }
+        self.frames_sent as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+struct DeviceRecord {
+    pending: Option<(u8, Instant)>,
+    latencies: VecDeque<Duration>,
+}
+
+/// A [`Link`] wrapper to measure the round trip latency, jitter, and throughput of the inner link.
+///
+/// The round trip latency of each device is the time from sending a frame to receiving the response whose ack is the message id of the frame.
+/// The send and receive calls are also recorded as `tracing` spans, and each round trip is emitted as a `trace` event.
+#[derive(Deref, DerefMut)]
+pub struct Instrumented<L> {
+    #[deref]
+    #[deref_mut]
+    inner: L,
+    option: InstrumentedOption,
+    devices: Vec<DeviceRecord>,
+    frames_sent: u64,
+    bytes_sent: u64,
+    opened_at: Instant,
+}
+
+impl<L> Instrumented<L> {
+    /// Creates a new [`Instrumented`] wrapping `inner`.
+    pub fn new(inner: L, option: InstrumentedOption) -> Self {
+        Self {
+            inner,
+            option,
+            devices: Vec::new(),
+            frames_sent: 0,
+            bytes_sent: 0,
+            opened_at: Instant::now(),
+        }
+    }
+
+    /// Gets the inner link.
+    pub fn into_inner(self) -> L {
+        self.inner
+    }
+
+    /// Gets the [`LinkStatistics`] measured so far.
+    pub fn statistics(&self) -> LinkStatistics {
+        LinkStatistics {
+            devices: self
+                .devices
+                .iter()
+                .map(|dev| self.latency_statistics(&dev.latencies))
+                .collect(),
+            frames_sent: self.frames_sent,
+            bytes_sent: self.bytes_sent,
+            elapsed: self.opened_at.elapsed(),
+        }
+    }
+
+    /// Clears the statistics measured so far.
+    pub fn reset(&mut self) {
+        self.devices.iter_mut().for_each(|dev| {
+            dev.pending = None;
+            dev.latencies.clear();
+        });
+        self.frames_sent = 0;
+        self.bytes_sent = 0;
+        self.opened_at = Instant::now();
+    }
+
+    fn latency_statistics(&self, latencies: &VecDeque<Duration>) -> LatencyStatistics {
+        let mut histogram = vec![0; self.option.num_bins];
+        if latencies.is_empty() {
+            return LatencyStatistics {
+                histogram,
+                ..Default::default()
+            };
+        }
+        let bin_width = self.option.bin_width.as_nanos().max(1);
+        latencies.iter().for_each(|l| {
+            if let Some(last) = self.option.num_bins.checked_sub(1) {
+                histogram[((l.as_nanos() / bin_width) as usize).min(last)] += 1;
+            }
+        });
+        let count = latencies.len();
+        let mean = latencies.iter().map(|l| l.as_secs_f64()).sum::<f64>() / count as f64;
+        let variance = latencies
+            .iter()
+            .map(|l| (l.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / count as f64;
+        LatencyStatistics {
+            count,
+            min: latencies.iter().min().copied().unwrap_or_default(),
+            max: latencies.iter().max().copied().unwrap_or_default(),
+            mean: Duration::from_secs_f64(mean),
+            jitter: Duration::from_secs_f64(variance.sqrt()),
+            histogram,
+        }
+    }
+
+    fn on_open(&mut self, geometry: &Geometry) {
+        self.devices = (0..geometry.len())
+            .map(|_| DeviceRecord {
+                pending: None,
+                latencies: VecDeque::with_capacity(self.option.window),
+            })
+            .collect();
+        self.frames_sent = 0;
+        self.bytes_sent = 0;
+        self.opened_at = Instant::now();
+    }
+
+    fn on_reconfigure(&mut self, geometry: &Geometry) {
+        self.devices.resize_with(geometry.len(), || DeviceRecord {
+            pending: None,
+            latencies: VecDeque::with_capacity(self.option.window),
+        });
+    }
+
+    fn on_send(&mut self, tx: &[TxMessage]) {
+        let now = Instant::now();
+        self.frames_sent += 1;
+        self.bytes_sent += std::mem::size_of_val(tx) as u64;
+        self.devices.iter_mut().zip(tx).for_each(|(dev, tx)| {
+            // A resent frame with the same message id is measured from the first send.
+            if dev
+                .pending
+                .is_none_or(|(msg_id, _)| msg_id != tx.header.msg_id)
+            {
+                dev.pending = Some((tx.header.msg_id, now));
+            }
+        });
+    }
+
+    fn on_receive(&mut self, rx: &[RxMessage]) {
+        let now = Instant::now();
+        let window = self.option.window;
+        self.devices
+            .iter_mut()
+            .zip(rx)
+            .enumerate()
+            .for_each(|(idx, (dev, rx))| {
+                if let Some((msg_id, sent)) = dev.pending {
+                    if rx.ack() == msg_id {
+                        let latency = now - sent;
+                        tracing::trace!(device = idx, ?latency, "round trip");
+                        dev.pending = None;
+                        if window == 0 {
+                            return;
+                        }
+                        if dev.latencies.len() == window {
+                            dev.latencies.pop_front();
+                        }
+                        dev.latencies.push_back(latency);
+                    }
+                }
+            });
+    }
+}
+
+impl<L: Link> Link for Instrumented<L> {
+    fn preflight(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.inner.preflight(geometry)
+    }
+
+    fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.inner.open(geometry)?;
+        self.on_open(geometry);
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), LinkError> {
+        self.inner.close()
+    }
+
+    fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.inner.reconfigure(geometry)?;
+        self.on_reconfigure(geometry);
+        Ok(())
+    }
+
+    fn update(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.inner.update(geometry)
+    }
+
+    fn send(&mut self, tx: &[TxMessage]) -> Result<bool, LinkError> {
+        let _span = tracing::trace_span!("link_send").entered();
+        let res = self.inner.send(tx)?;
+        if res {
+            self.on_send(tx);
+        }
+        Ok(res)
+    }
+
+    fn receive(&mut self, rx: &mut [RxMessage]) -> Result<bool, LinkError> {
+        let _span = tracing::trace_span!("link_receive").entered();
+        let res = self.inner.receive(rx)?;
+        if res {
+            self.on_receive(rx);
+        }
+        Ok(res)
+    }
+
+    fn is_open(&self) -> bool {
+        self.inner.is_open()
+    }
+}
+
+#[cfg(feature = "async")]
+use autd3_core::link::AsyncLink;
+#[cfg(feature = "async")]
+use tracing::Instrument;
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[cfg_attr(feature = "async-trait", autd3_core::async_trait)]
+impl<L: AsyncLink> AsyncLink for Instrumented<L> {
+    async fn preflight(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.inner.preflight(geometry).await
+    }
+
+    async fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.inner.open(geometry).await?;
+        self.on_open(geometry);
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), LinkError> {
+        self.inner.close().await
+    }
+
+    async fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.inner.reconfigure(geometry).await?;
+        self.on_reconfigure(geometry);
+        Ok(())
+    }
+
+    async fn update(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        self.inner.update(geometry).await
+    }
+
+    async fn send(&mut self, tx: &[TxMessage]) -> Result<bool, LinkError> {
+        let res = self
+            .inner
+            .send(tx)
+            .instrument(tracing::trace_span!("link_send"))
+            .await?;
+        if res {
+            self.on_send(tx);
+        }
+        Ok(res)
+    }
+
+    async fn receive(&mut self, rx: &mut [RxMessage]) -> Result<bool, LinkError> {
+        let res = self
+            .inner
+            .receive(rx)
+            .instrument(tracing::trace_span!("link_receive"))
+            .await?;
+        if res {
+            self.on_receive(rx);
+        }
+        Ok(res)
+    }
+
+    fn is_open(&self) -> bool {
+        self.inner.is_open()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use autd3_driver::autd3_device::AUTD3;
+    use autd3_firmware_emulator::TimingModel;
+
+    use super::*;
+
+    use crate::{
+        controller::{Controller, SenderOption, SpinSleeper},
+        link::{Audit, AuditOption},
+        modulation::Static,
+    };
+
+    #[test]
+    fn statistics() -> anyhow::Result<()> {
+        let mut autd = Controller::open(
+            [AUTD3::default(), AUTD3::default()],
+            Instrumented::new(
+                Audit::new(AuditOption {
+                    timing_model: Some(TimingModel {
+                        default_delay: Duration::from_millis(1),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                InstrumentedOption {
+                    window: 3,
+                    bin_width: Duration::from_millis(1),
+                    num_bins: 4,
+                },
+            ),
+        )?;
+
+        autd.link_mut().reset();
+        let stats = autd.link().statistics();
+        assert_eq!(
+            vec![
+                LatencyStatistics {
+                    histogram: vec![0; 4],
+                    ..Default::default()
+                };
+                2
+            ],
+            stats.devices
+        );
+        assert_eq!(0, stats.frames_sent);
+        assert_eq!(0, stats.bytes_sent);
+
+        (0..5).try_for_each(|_| {
+            autd.sender(SenderOption::<SpinSleeper> {
+                timeout: Some(Duration::from_millis(100)),
+                ..Default::default()
+            })
+            .send(Static::default())
+        })?;
+
+        let stats = autd.link().statistics();
+        assert_eq!(2, stats.devices.len());
+        stats.devices.iter().for_each(|dev| {
+            assert_eq!(3, dev.count);
+            assert_eq!(3, dev.histogram.iter().sum::<usize>());
+            assert_eq!(0, dev.histogram[0]);
+            assert!(Duration::from_millis(1) <= dev.min);
+            assert!(dev.min <= dev.mean && dev.mean <= dev.max);
+            assert!(dev.jitter <= dev.max - dev.min);
+        });
+        assert!(stats.frames_sent >= 5);
+        assert_eq!(
+            stats.frames_sent * 2 * size_of::<TxMessage>() as u64,
+            stats.bytes_sent
+        );
+        assert!(stats.throughput() > 0.);
+
+        Ok(())
+    }
+}
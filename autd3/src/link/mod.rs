@@ -1,5 +1,9 @@
 mod audit;
+mod bench;
+mod instrumented;
 mod nop;
 
-pub use audit::{Audit, AuditOption};
+pub use audit::{Audit, AuditOption, ReceiveContext, Scenario};
+pub use bench::{Bench, BenchOption};
+pub use instrumented::{Instrumented, InstrumentedOption, LatencyStatistics, LinkStatistics};
 pub use nop::Nop;
@@ -25,6 +25,10 @@ impl Nop {
 impl Link for Nop {
     fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
         self.is_open = true;
+        <Self as Link>::reconfigure(self, geometry)
+    }
+
+    fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
         self.cpus = geometry
             .iter()
             .enumerate()
@@ -75,6 +79,10 @@ impl AsyncLink for Nop {
         <Self as Link>::close(self)
     }
 
+    async fn reconfigure(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        <Self as Link>::reconfigure(self, geometry)
+    }
+
     async fn send(&mut self, tx: &[TxMessage]) -> Result<bool, LinkError> {
         <Self as Link>::send(self, tx)
     }
@@ -1,11 +1,20 @@
 pub use crate::{
-    controller::{Controller, ParallelMode, SenderOption, SpinSleeper},
+    controller::{
+        AdaptiveTimeout, BackgroundSender, ConfigSnapshot, Controller, FPGAStateEvent,
+        FPGAStateMonitor, FPGAStateMonitorOption, GPIOPlan, HapticOptions, ModulationSplit,
+        MsgIdPolicy, ParallelMode, PowerBudget, PowerBudgetAction, RateLimiter, SenderOption,
+        Sequence, SpinSleeper, SyncOption, Watchdog, WatchdogOption,
+    },
     datagram::{
+        calibration::{AmplitudeCalibration, PhaseCalibration},
         gain::{
-            Bessel, BesselOption, Focus, FocusOption, Group, Null, Plane, PlaneOption, Uniform,
+            Bessel, BesselOption, Calibrated, Focus, FocusOption, Group, Null, Plane, PlaneOption,
+            Uniform,
         },
         modulation::{FourierOption, Sine, SineOption, Square, SquareOption, Static},
-        stm::{Circle, Line},
+        pulse_width::{PulseWidthCurve, PulseWidthTable},
+        silencer::SilencerRequirement,
+        static_pressure::{StaticPressure, StaticPressureOption},
     },
     error::AUTDError,
     link::Nop,
@@ -16,9 +25,8 @@ pub use autd3_core::modulation::Modulation;
 pub use autd3_driver::{
     autd3_device::AUTD3,
     datagram::{
-        Clear, ControlPoint, ControlPoints, DebugSettings, FixedUpdateRate, FociSTM, ForceFan,
-        GainSTM, GainSTMOption, PhaseCorrection, PulseWidthEncoder, ReadsFPGAState, Silencer,
-        SwapSegment,
+        Clear, DebugSettings, FixedUpdateRate, ForceFan, PhaseCorrection, PulseWidthEncoder,
+        ReadsFPGAState, Silencer, SwapSegment,
     },
     defined::{deg, kHz, mm, rad, ultrasound_freq, Hz, PI},
     error::AUTDDriverError,
@@ -26,12 +34,34 @@ pub use autd3_driver::{
     firmware::{
         cpu::GainSTMMode,
         fpga::{
-            DebugType, Drive, EmitIntensity, GPIOIn, GPIOOut, LoopBehavior, Phase, SamplingConfig,
-            Segment, SilencerTarget, TransitionMode,
+            DebugType, Drive, EmitIntensity, FreqRatio, GPIOIn, GPIOOut, LoopBehavior, Phase,
+            SamplingConfig, Segment, SilencerTarget, TransitionMode,
         },
     },
-    geometry::{EulerAngle, Geometry, Point3, Quaternion, UnitQuaternion, UnitVector3, Vector3},
+    geometry::{
+        EulerAngle, Geometry, Point2, Point3, Quaternion, UnitQuaternion, UnitVector3, Vector3,
+    },
+};
+
+autd3_driver::with_fixed_completion_time! {
+    pub use autd3_driver::datagram::FixedCompletionTime;
+}
+#[cfg(feature = "dynamic_freq")]
+pub use autd3_driver::datagram::UltrasoundFreq;
+
+#[cfg(feature = "stm")]
+pub use crate::datagram::stm::{
+    ArcPath, BoundedTrajectory, CancellationToken, Circle, FociGroups, IntensityProfile, Line,
+    LineOrder, Lissajous, MovingFocus, MovingPoint, Polyline, PrecomputedGains, Raster, Spiral,
+};
+
+#[cfg(feature = "stm")]
+pub use crate::datagram::patterns::{BrushStroke, ExpandingRing, PulseTrain, TextureNoise};
+
+#[cfg(feature = "stm")]
+pub use crate::datagram::sensations::{
+    CircleSensation, LineSensation, Sensation, SensationOption, TextureSensation,
 };
 
-#[cfg(not(feature = "dynamic_freq"))]
-pub use autd3_driver::datagram::FixedCompletionTime;
+#[cfg(feature = "stm")]
+pub use autd3_driver::datagram::{ControlPoint, ControlPoints, FociSTM, GainSTM, GainSTMOption};
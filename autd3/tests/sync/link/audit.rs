@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use autd3::{
     controller::SenderOption,
-    link::{Audit, AuditOption},
+    link::{Audit, AuditOption, Scenario},
     prelude::*,
 };
 use autd3_core::link::LinkError;
@@ -84,3 +84,91 @@ fn audit_test() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn audit_scenario_stale_response() -> anyhow::Result<()> {
+    let stale = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let mut autd = Controller::open(
+        [AUTD3::default(), AUTD3::default()],
+        Audit::new(AuditOption {
+            scenario: Scenario::new().stale_response(1, 3).on_response(1, {
+                let stale = stale.clone();
+                move |ctx, rx| {
+                    if rx.ack() == ctx.previous.ack() {
+                        stale.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    }
+                }
+            }),
+            ..Default::default()
+        }),
+    )?;
+
+    stale.store(0, std::sync::atomic::Ordering::Relaxed);
+    autd.send(Static::default())?;
+    assert_eq!(3, stale.load(std::sync::atomic::Ordering::Relaxed));
+
+    Ok(())
+}
+
+#[test]
+fn audit_scenario_stale_response_timeout() {
+    assert_eq!(
+        Some(AUTDError::Driver(AUTDDriverError::ConfirmResponseFailed)),
+        Controller::open(
+            [AUTD3::default(), AUTD3::default()],
+            Audit::new(AuditOption {
+                scenario: Scenario::new().stale_response(1, usize::MAX),
+                ..Default::default()
+            }),
+        )
+        .err()
+    );
+}
+
+#[test]
+fn audit_scenario_thermal_assert() -> anyhow::Result<()> {
+    let mut autd = Controller::open(
+        [AUTD3::default(), AUTD3::default()],
+        Audit::new(AuditOption {
+            scenario: Scenario::new().thermal_assert_after(1, Duration::from_millis(50)),
+            ..Default::default()
+        }),
+    )?;
+    autd.send(ReadsFPGAState::new(|_| true))?;
+
+    let states = autd.fpga_state()?;
+    assert!(states.iter().all(|s| !s.unwrap().is_thermal_assert()));
+
+    std::thread::sleep(Duration::from_millis(50));
+    let states = autd.fpga_state()?;
+    assert!(!states[0].unwrap().is_thermal_assert());
+    assert!(states[1].unwrap().is_thermal_assert());
+
+    Ok(())
+}
+
+#[rstest::rstest]
+#[case(false, MsgIdPolicy::Strict)]
+#[case(true, MsgIdPolicy::Window(1))]
+#[case(true, MsgIdPolicy::Tracking)]
+#[test]
+fn audit_scenario_msg_id_policy(#[case] expect: bool, #[case] msg_id_policy: MsgIdPolicy) {
+    assert_eq!(
+        expect,
+        Controller::open_with_option(
+            [AUTD3::default(), AUTD3::default()],
+            Audit::new(AuditOption {
+                scenario: Scenario::new()
+                    .duplicated_response(0, 1)
+                    .stale_response(1, 1),
+                ..Default::default()
+            }),
+            SenderOption::<SpinSleeper> {
+                timeout: Some(Duration::from_millis(10)),
+                msg_id_policy,
+                ..Default::default()
+            },
+        )
+        .is_ok()
+    );
+}
@@ -1,3 +1,5 @@
 #[cfg(feature = "async")]
 mod r#async;
 mod sync;
+#[cfg(not(feature = "dynamic_freq"))]
+mod test_vector;
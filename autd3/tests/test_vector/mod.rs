@@ -0,0 +1,190 @@
+//! Test vectors of the packed bytes for each [`Datagram`].
+//!
+//! The corpus in `corpus.json` is shared with the other implementations and the firmware to check compatibility.
+//! Each vector contains the [`TxMessage`]s, encoded in hexadecimal, sent to a single [`AUTD3`] device placed at the origin.
+//!
+//! To regenerate the corpus, run `cargo make update-test-vectors`.
+
+use std::num::NonZeroU16;
+
+use autd3::prelude::*;
+use autd3_core::{datagram::Datagram, geometry::IntoDevice};
+use autd3_driver::{
+    datagram::Synchronize,
+    firmware::{
+        cpu::TxMessage,
+        operation::{Operation, OperationGenerator, OperationHandler},
+    },
+};
+use serde::{Deserialize, Serialize};
+use zerocopy::{FromZeros, IntoBytes};
+
+const CORPUS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_vector/corpus.json");
+const UPDATE_ENV: &str = "AUTD3_UPDATE_TEST_VECTORS";
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Corpus {
+    description: String,
+    vectors: Vec<TestVector>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct TestVector {
+    name: String,
+    frames: Vec<String>,
+}
+
+fn pack<D: Datagram>(d: D, geometry: &Geometry) -> Result<Vec<String>, AUTDDriverError>
+where
+    AUTDDriverError: From<D::Error>,
+    D::G: OperationGenerator,
+    AUTDDriverError: From<<<D::G as OperationGenerator>::O1 as Operation>::Error>
+        + From<<<D::G as OperationGenerator>::O2 as Operation>::Error>,
+{
+    let mut operations =
+        OperationHandler::generate(d.operation_generator(geometry, false)?, geometry);
+    let mut tx = vec![TxMessage::new_zeroed(); geometry.len()];
+    let mut frames = Vec::new();
+    loop {
+        OperationHandler::pack(&mut operations, geometry, &mut tx, false)?;
+        frames.push(
+            tx[0]
+                .as_bytes()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+        );
+        if OperationHandler::is_done(&operations) {
+            return Ok(frames);
+        }
+    }
+}
+
+fn generate() -> anyhow::Result<Corpus> {
+    let geometry = Geometry::new(vec![AUTD3::default().into_device(0)]);
+
+    let mut vectors = Vec::new();
+    macro_rules! add {
+        ($name:expr, $d:expr) => {
+            vectors.push(TestVector {
+                name: $name.to_string(),
+                frames: pack($d, &geometry)?,
+            });
+        };
+    }
+
+    add!("clear", Clear {});
+    add!("synchronize", Synchronize {});
+    add!("force_fan", ForceFan::new(|_| true));
+    add!("reads_fpga_state", ReadsFPGAState::new(|_| true));
+    add!("silencer_fixed_completion_steps", Silencer::default());
+    add!(
+        "silencer_fixed_update_rate",
+        Silencer {
+            config: FixedUpdateRate {
+                intensity: NonZeroU16::new(256).unwrap(),
+                phase: NonZeroU16::new(256).unwrap(),
+            },
+            target: SilencerTarget::Intensity,
+        }
+    );
+    add!("silencer_disable", Silencer::disable());
+    add!(
+        "swap_segment_gain",
+        SwapSegment::Gain(Segment::S1, TransitionMode::Immediate)
+    );
+    add!("modulation_static", Static { intensity: 0x80 });
+    add!(
+        "modulation_sine",
+        Sine {
+            freq: 150 * Hz,
+            option: Default::default(),
+        }
+    );
+    add!("gain_null", Null {});
+    add!(
+        "gain_uniform",
+        Uniform {
+            intensity: EmitIntensity(0x80),
+            phase: Phase(0x40),
+        }
+    );
+    add!(
+        "gain_focus",
+        Focus {
+            pos: Point3::new(0., 0., 150. * mm),
+            option: Default::default(),
+        }
+    );
+    #[cfg(feature = "stm")]
+    add!(
+        "foci_stm_circle",
+        FociSTM {
+            foci: Circle {
+                center: Point3::new(0., 0., 150. * mm),
+                radius: 30. * mm,
+                num_points: 4,
+                n: Vector3::z_axis(),
+                intensity: EmitIntensity::MAX,
+            },
+            config: SamplingConfig::DIV_10,
+        }
+    );
+    #[cfg(feature = "stm")]
+    add!(
+        "gain_stm_line",
+        GainSTM {
+            gains: Line {
+                start: Point3::new(-10. * mm, 0., 150. * mm),
+                end: Point3::new(10. * mm, 0., 150. * mm),
+                num_points: 2,
+                intensity: EmitIntensity::MAX,
+            },
+            config: SamplingConfig::DIV_10,
+            option: GainSTMOption::default(),
+        }
+    );
+    add!(
+        "phase_correction",
+        PhaseCorrection::new(|_| |tr| Phase(tr.idx() as u8))
+    );
+    add!("pulse_width_encoder", PulseWidthEncoder::default());
+
+    Ok(Corpus {
+        description: "Packed TxMessages (header and payload, hex-encoded) for a single AUTD3 device at the origin, generated by autd3/tests/test_vector".to_string(),
+        vectors,
+    })
+}
+
+#[test]
+fn test_vector() -> anyhow::Result<()> {
+    let corpus = generate()?;
+
+    if std::env::var(UPDATE_ENV).is_ok() {
+        std::fs::write(CORPUS_PATH, serde_json::to_string_pretty(&corpus)? + "\n")?;
+        return Ok(());
+    }
+
+    let expect: Corpus = serde_json::from_str(&std::fs::read_to_string(CORPUS_PATH)?)?;
+    // The STM vectors are not generated without the `stm` feature.
+    #[cfg(not(feature = "stm"))]
+    let expect = Corpus {
+        vectors: expect
+            .vectors
+            .into_iter()
+            .filter(|e| !e.name.contains("_stm_"))
+            .collect(),
+        ..expect
+    };
+    assert_eq!(expect.vectors.len(), corpus.vectors.len());
+    expect
+        .vectors
+        .iter()
+        .zip(corpus.vectors.iter())
+        .for_each(|(e, v)| {
+            assert_eq!(e.name, v.name);
+            assert_eq!(e.frames, v.frames, "test vector `{}` mismatch", e.name);
+        });
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use anyhow::Result;
+
+use autd3::prelude::*;
+use serde_json::{Map, Value};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Subscriber,
+};
+use tracing_subscriber::{
+    filter::Targets, layer::Context, prelude::*, registry::LookupSpan, Layer, Registry,
+};
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{value:?}").into());
+    }
+}
+
+// Prints one JSON line per `send` span when it is closed, e.g.,
+// {"datagram":"autd3::datagram::gain::focus::Focus","devices":2,"duration_us":52,"frames":1,"id":0,"parallel":false,"success":true,"timeout_us":20000}
+// The lines can be shipped to Loki or another log store to monitor the send latency in Grafana.
+struct JsonTelemetryLayer;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for JsonTelemetryLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if span.name() == "send" {
+            let mut fields = Map::new();
+            attrs.record(&mut JsonVisitor(&mut fields));
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<Map<String, Value>>() {
+                values.record(&mut JsonVisitor(fields));
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(fields) = ctx
+            .span(&id)
+            .and_then(|span| span.extensions_mut().remove::<Map<String, Value>>())
+        {
+            println!("{}", Value::Object(fields));
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    Registry::default()
+        .with(Targets::new().with_target("autd3", tracing::Level::INFO))
+        .with(JsonTelemetryLayer)
+        .init();
+
+    let mut autd = Controller::open([AUTD3::default(); 2], Nop::new())?;
+
+    let center = autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm);
+    autd.send(Silencer::default())?;
+    autd.send((
+        Sine {
+            freq: 150. * Hz,
+            option: Default::default(),
+        },
+        Focus {
+            pos: center,
+            option: Default::default(),
+        },
+    ))?;
+
+    autd.close()?;
+
+    Ok(())
+}
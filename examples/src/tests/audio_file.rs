@@ -3,7 +3,7 @@ use autd3::{core::link::Link, prelude::*};
 pub fn audio_file(autd: &mut Controller<impl Link>) -> anyhow::Result<bool> {
     autd.send(Silencer::default())?;
 
-    let center = autd.center() + Vector3::new(0., 0., 150.0 * mm);
+    let center = autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm);
 
     let g = Focus {
         pos: center,
@@ -3,7 +3,7 @@ use autd3::{core::link::Link, prelude::*};
 pub fn bessel(autd: &mut Controller<impl Link>) -> anyhow::Result<bool> {
     autd.send(Silencer::default())?;
 
-    let center = autd.center();
+    let center = autd.center().unwrap();
     let dir = Vector3::z_axis();
 
     let g = Bessel {
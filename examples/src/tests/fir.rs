@@ -3,7 +3,7 @@ use autd3::{core::link::Link, modulation::Fir, prelude::*};
 pub fn fir(autd: &mut Controller<impl Link>) -> anyhow::Result<bool> {
     autd.send(Silencer::disable())?;
 
-    let center = autd.center() + Vector3::new(0., 0., 150.0 * mm);
+    let center = autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm);
 
     // fs = 20kHz, fc = 200Hz, n_tap = 199
     let coef = vec![
@@ -5,7 +5,7 @@ use autd3::{core::link::Link, prelude::*};
 pub fn group_by_device(autd: &mut Controller<impl Link>) -> anyhow::Result<bool> {
     use autd3::datagram::IntoBoxedDatagram;
 
-    let center = autd.center() + Vector3::new(0., 0., 150.0 * mm);
+    let center = autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm);
 
     autd.group_send(
         |dev| match dev.idx() {
@@ -32,7 +32,7 @@ pub fn group_by_device(autd: &mut Controller<impl Link>) -> anyhow::Result<bool>
 pub fn group_by_transducer(autd: &mut Controller<impl Link>) -> anyhow::Result<bool> {
     use autd3::gain::IntoBoxedGain;
 
-    let cx = autd.center().x;
+    let cx = autd.center().unwrap().x;
     let pos = autd[0].center() + Vector3::new(0., 0., 150.0 * mm);
 
     let g = Group {
@@ -10,7 +10,7 @@ use std::io::{self, Write};
 pub fn holo(autd: &mut Controller<impl Link>) -> anyhow::Result<bool> {
     autd.send(Silencer::default())?;
 
-    let center = autd.center() + Vector3::new(0., 0., 150.0 * mm);
+    let center = autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm);
     let p = Vector3::new(30. * mm, 0., 0.);
     let backend = std::sync::Arc::new(NalgebraBackend::default());
     let target_amp = 2.5e3 * autd.num_devices() as f32 * Pa;
@@ -5,7 +5,7 @@ pub fn foci_stm(autd: &mut Controller<impl Link>) -> anyhow::Result<bool> {
 
     let stm = FociSTM {
         foci: Circle {
-            center: autd.center() + Vector3::new(0., 0., 150.0 * mm),
+            center: autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm),
             radius: 30.0 * mm,
             num_points: 50,
             n: Vector3::z_axis(),
@@ -26,7 +26,7 @@ pub fn gain_stm(autd: &mut Controller<impl Link>) -> anyhow::Result<bool> {
 
     let stm = GainSTM {
         gains: Circle {
-            center: autd.center() + Vector3::new(0., 0., 150.0 * mm),
+            center: autd.center().unwrap() + Vector3::new(0., 0., 150.0 * mm),
             radius: 30.0 * mm,
             num_points: 50,
             n: Vector3::z_axis(),
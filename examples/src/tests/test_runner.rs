@@ -38,7 +38,7 @@ pub fn run<L: Link>(mut autd: Controller<L>) -> anyhow::Result<()> {
         }),
     ];
     if autd.num_devices() >= 2 {
-        examples.push(("Group (by Device) test", |autd| (group_by_device(autd))));
+        examples.push(("Group (by Device) test", |autd| group_by_device(autd)));
     }
 
     loop {